@@ -9,6 +9,8 @@ pub mod parse_address_lookup_table;
 pub mod parse_bpf_loader;
 #[allow(deprecated)]
 pub mod parse_config;
+pub mod parse_idl;
+pub mod parse_loader_v4;
 pub mod parse_nonce;
 pub mod parse_stake;
 pub mod parse_sysvar;
@@ -1,15 +1,21 @@
 use {
     crate::{
         parse_address_lookup_table::parse_address_lookup_table,
-        parse_bpf_loader::parse_bpf_upgradeable_loader, parse_config::parse_config,
-        parse_nonce::parse_nonce, parse_stake::parse_stake, parse_sysvar::parse_sysvar,
-        parse_token::parse_token_v2, parse_vote::parse_vote,
+        parse_bpf_loader::parse_bpf_upgradeable_loader,
+        parse_config::parse_config,
+        parse_idl::{idl_registry, IdlError, IdlRegistry},
+        parse_loader_v4::parse_loader_v4,
+        parse_nonce::parse_nonce,
+        parse_stake::parse_stake,
+        parse_sysvar::parse_sysvar,
+        parse_token::parse_token_v2,
+        parse_vote::parse_vote,
     },
     inflector::Inflector,
     serde_json::Value,
     solana_sdk::{
-        address_lookup_table, clock::UnixTimestamp, instruction::InstructionError, pubkey::Pubkey,
-        stake, system_program, sysvar, vote,
+        address_lookup_table, clock::UnixTimestamp, instruction::InstructionError, loader_v4,
+        pubkey::Pubkey, stake, system_program, sysvar, vote,
     },
     spl_token_2022::extension::interest_bearing_mint::InterestBearingConfig,
     std::collections::HashMap,
@@ -20,6 +26,7 @@ lazy_static! {
     static ref ADDRESS_LOOKUP_PROGRAM_ID: Pubkey = address_lookup_table::program::id();
     static ref BPF_UPGRADEABLE_LOADER_PROGRAM_ID: Pubkey = solana_sdk::bpf_loader_upgradeable::id();
     static ref CONFIG_PROGRAM_ID: Pubkey = solana_config_program::id();
+    static ref LOADER_V4_PROGRAM_ID: Pubkey = loader_v4::id();
     static ref STAKE_PROGRAM_ID: Pubkey = stake::program::id();
     static ref SYSTEM_PROGRAM_ID: Pubkey = system_program::id();
     static ref SYSVAR_PROGRAM_ID: Pubkey = sysvar::id();
@@ -35,6 +42,7 @@ lazy_static! {
             ParsableAccount::BpfUpgradeableLoader,
         );
         m.insert(*CONFIG_PROGRAM_ID, ParsableAccount::Config);
+        m.insert(*LOADER_V4_PROGRAM_ID, ParsableAccount::LoaderV4);
         m.insert(*SYSTEM_PROGRAM_ID, ParsableAccount::Nonce);
         m.insert(spl_token::id(), ParsableAccount::SplToken);
         m.insert(spl_token_2022::id(), ParsableAccount::SplToken2022);
@@ -61,6 +69,9 @@ pub enum ParseAccountError {
 
     #[error("Serde json error")]
     SerdeJsonError(#[from] serde_json::error::Error),
+
+    #[error("IDL error: {0}")]
+    IdlError(#[from] IdlError),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -77,6 +88,7 @@ pub enum ParsableAccount {
     AddressLookupTable,
     BpfUpgradeableLoader,
     Config,
+    LoaderV4,
     Nonce,
     SplToken,
     SplToken2022,
@@ -137,9 +149,28 @@ pub fn parse_account_data_v2(
     data: &[u8],
     additional_data: Option<AccountAdditionalDataV2>,
 ) -> Result<ParsedAccount, ParseAccountError> {
-    let program_name = PARSABLE_PROGRAM_IDS
-        .get(program_id)
-        .ok_or(ParseAccountError::ProgramNotParsable)?;
+    parse_account_data_with_idl_registry(pubkey, program_id, data, additional_data, idl_registry())
+}
+
+/// Parses the account with the built-in parser of its program, or else with the IDL registered
+/// for the program in `idl_registry`
+fn parse_account_data_with_idl_registry(
+    pubkey: &Pubkey,
+    program_id: &Pubkey,
+    data: &[u8],
+    additional_data: Option<AccountAdditionalDataV2>,
+    idl_registry: &IdlRegistry,
+) -> Result<ParsedAccount, ParseAccountError> {
+    let Some(program_name) = PARSABLE_PROGRAM_IDS.get(program_id) else {
+        let idl = idl_registry
+            .get(program_id)
+            .ok_or(ParseAccountError::ProgramNotParsable)?;
+        return Ok(ParsedAccount {
+            program: idl.name().to_kebab_case(),
+            parsed: idl.parse_account(data)?,
+            space: data.len() as u64,
+        });
+    };
     let additional_data = additional_data.unwrap_or_default();
     let parsed_json = match program_name {
         ParsableAccount::AddressLookupTable => {
@@ -149,6 +180,7 @@ pub fn parse_account_data_v2(
             serde_json::to_value(parse_bpf_upgradeable_loader(data)?)?
         }
         ParsableAccount::Config => serde_json::to_value(parse_config(data, pubkey)?)?,
+        ParsableAccount::LoaderV4 => serde_json::to_value(parse_loader_v4(data)?)?,
         ParsableAccount::Nonce => serde_json::to_value(parse_nonce(data)?)?,
         ParsableAccount::SplToken | ParsableAccount::SplToken2022 => serde_json::to_value(
             parse_token_v2(data, additional_data.spl_token_additional_data.as_ref())?,
@@ -168,6 +200,8 @@ pub fn parse_account_data_v2(
 mod test {
     use {
         super::*,
+        crate::parse_idl::Idl,
+        assert_matches::assert_matches,
         solana_sdk::{
             nonce::{
                 state::{Data, Versions},
@@ -213,4 +247,59 @@ mod test {
         assert_eq!(parsed.program, "nonce".to_string());
         assert_eq!(parsed.space, State::size() as u64);
     }
+
+    #[test]
+    fn test_parse_account_data_with_idl() {
+        let account_pubkey = solana_sdk::pubkey::new_rand();
+        let program_id = solana_sdk::pubkey::new_rand();
+        let idl = serde_json::json!({
+            "address": program_id.to_string(),
+            "metadata": {"name": "my_program"},
+            "accounts": [{"name": "Config", "discriminator": [1, 1, 1, 1, 1, 1, 1, 1]}],
+            "types": [{
+                "name": "Config",
+                "type": {"kind": "struct", "fields": [{"name": "fee_bps", "type": "u16"}]},
+            }],
+        });
+        let data = [1, 1, 1, 1, 1, 1, 1, 1, 25, 0];
+        let idl_registry = IdlRegistry::default();
+        let parse = |program_id: &Pubkey, data: &[u8]| {
+            parse_account_data_with_idl_registry(
+                &account_pubkey,
+                program_id,
+                data,
+                None,
+                &idl_registry,
+            )
+        };
+        assert!(parse(&program_id, &data).is_err());
+
+        idl_registry.register(
+            program_id,
+            Idl::from_json(idl.to_string().as_bytes()).unwrap(),
+        );
+        let parsed = parse(&program_id, &data).unwrap();
+        assert_eq!(parsed.program, "my-program".to_string());
+        assert_eq!(
+            parsed.parsed,
+            serde_json::json!({"type": "config", "info": {"feeBps": 25}})
+        );
+        assert_eq!(parsed.space, 10);
+        assert_matches!(
+            parse(&program_id, &[0; 10]),
+            Err(ParseAccountError::IdlError(_))
+        );
+
+        // An IDL doesn't override the built-in parser of a program
+        let nonce_data = Versions::new(State::Initialized(Data::default()));
+        let nonce_data = bincode::serialize(&nonce_data).unwrap();
+        idl_registry.register(
+            system_program::id(),
+            Idl::from_json(idl.to_string().as_bytes()).unwrap(),
+        );
+        assert_eq!(
+            parse(&system_program::id(), &nonce_data).unwrap().program,
+            "nonce".to_string()
+        );
+    }
 }
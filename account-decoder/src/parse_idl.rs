@@ -0,0 +1,860 @@
+//! Decodes the instructions and accounts of programs described by an Anchor-style IDL
+//!
+//! IDLs are registered at runtime, from JSON files or from the IDL account Anchor programs
+//! keep on-chain. `parse_account_data_v2` and the instruction parser of
+//! `solana-transaction-status` consult the registered IDLs for the programs without a built-in
+//! parser, so an IDL can't change how a built-in program is decoded.
+//!
+//! Both the current IDL spec, with explicit discriminators and a top level `address`, and the
+//! legacy format, whose discriminators are derived from the instruction and account names, are
+//! supported.
+
+use {
+    flate2::read::ZlibDecoder,
+    inflector::Inflector,
+    serde_json::{Map, Number, Value},
+    solana_sdk::{hash::hashv, pubkey::Pubkey},
+    std::{
+        collections::HashMap,
+        fs,
+        io::Read,
+        path::Path,
+        str::FromStr,
+        sync::{Arc, RwLock},
+    },
+    thiserror::Error,
+};
+
+/// Length of the discriminator prefixing the data of Anchor instructions and accounts
+pub const DISCRIMINATOR_LEN: usize = 8;
+/// Seed of the on-chain IDL account, derived from the program's signer address
+const IDL_ACCOUNT_SEED: &str = "anchor:idl";
+/// Limits the recursion of self-referencing types
+const MAX_TYPE_DEPTH: usize = 32;
+/// Limits the size of the JSON decompressed from an on-chain IDL account
+const MAX_IDL_JSON_LEN: usize = 10 * 1024 * 1024;
+
+lazy_static! {
+    static ref IDL_REGISTRY: IdlRegistry = IdlRegistry::default();
+}
+
+#[derive(Error, Debug)]
+pub enum IdlError {
+    #[error("Invalid IDL: {0}")]
+    InvalidIdl(String),
+
+    #[error("Unknown discriminator")]
+    UnknownDiscriminator,
+
+    #[error("Unsupported IDL type: {0}")]
+    UnsupportedType(String),
+
+    #[error("Undefined IDL type: {0}")]
+    UndefinedType(String),
+
+    #[error("Invalid {0}")]
+    InvalidValue(&'static str),
+
+    #[error("Data too short")]
+    DataTooShort,
+
+    #[error("Types nested too deeply")]
+    MaxDepthExceeded,
+
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug, Deserialize)]
+struct IdlJson {
+    address: Option<String>,
+    /// Legacy name of the program
+    name: Option<String>,
+    #[serde(default)]
+    metadata: IdlMetadata,
+    #[serde(default)]
+    instructions: Vec<IdlInstructionJson>,
+    #[serde(default)]
+    accounts: Vec<IdlAccountJson>,
+    #[serde(default)]
+    types: Vec<IdlTypeDef>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct IdlMetadata {
+    name: Option<String>,
+    /// Legacy address of the program
+    address: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdlInstructionJson {
+    name: String,
+    discriminator: Option<Vec<u8>>,
+    #[serde(default)]
+    accounts: Vec<IdlAccountItem>,
+    #[serde(default)]
+    args: Vec<IdlField>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum IdlAccountItem {
+    /// Nested accounts, flattened when parsing
+    Composite { accounts: Vec<IdlAccountItem> },
+    Single {
+        name: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct IdlAccountJson {
+    name: String,
+    discriminator: Option<Vec<u8>>,
+    /// Legacy inline definition of the account type
+    #[serde(rename = "type")]
+    ty: Option<IdlTypeDefTy>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct IdlField {
+    name: String,
+    #[serde(rename = "type")]
+    ty: IdlType,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdlTypeDef {
+    name: String,
+    #[serde(rename = "type")]
+    ty: IdlTypeDefTy,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum IdlTypeDefTy {
+    Struct {
+        #[serde(default)]
+        fields: Option<IdlFields>,
+    },
+    Enum {
+        variants: Vec<IdlEnumVariant>,
+    },
+    Type {
+        alias: IdlType,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+enum IdlFields {
+    Named(Vec<IdlField>),
+    Tuple(Vec<IdlType>),
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct IdlEnumVariant {
+    name: String,
+    #[serde(default)]
+    fields: Option<IdlFields>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+enum IdlType {
+    Primitive(String),
+    Composite(IdlCompositeType),
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum IdlCompositeType {
+    Option(Box<IdlType>),
+    #[serde(rename = "coption")]
+    COption(Box<IdlType>),
+    Vec(Box<IdlType>),
+    /// The length is a generic parameter unless it is a number
+    Array(Box<IdlType>, Value),
+    Defined(IdlDefined),
+    Generic(String),
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+enum IdlDefined {
+    Name(String),
+    Struct { name: String },
+}
+
+impl IdlDefined {
+    fn name(&self) -> &str {
+        match self {
+            Self::Name(name) | Self::Struct { name } => name,
+        }
+    }
+}
+
+/// An instruction of an IDL
+#[derive(Debug)]
+pub struct IdlInstruction {
+    /// camelCase name of the instruction
+    pub name: String,
+    /// camelCase names of the accounts of the instruction, nested accounts flattened
+    pub accounts: Vec<String>,
+    discriminator: Vec<u8>,
+    args: Vec<IdlField>,
+}
+
+#[derive(Debug)]
+struct IdlAccount {
+    name: String,
+    discriminator: Vec<u8>,
+    ty: IdlTypeDefTy,
+}
+
+/// A program interface parsed from an Anchor-style IDL
+#[derive(Debug)]
+pub struct Idl {
+    name: String,
+    address: Option<Pubkey>,
+    instructions: Vec<IdlInstruction>,
+    accounts: Vec<IdlAccount>,
+    types: HashMap<String, IdlTypeDefTy>,
+}
+
+/// Returns the discriminator legacy IDLs derive from `namespace` and `name`
+fn legacy_discriminator(namespace: &str, name: &str) -> Vec<u8> {
+    hashv(&[format!("{namespace}:{name}").as_bytes()]).to_bytes()[..DISCRIMINATOR_LEN].to_vec()
+}
+
+/// An empty discriminator would match any data
+fn check_discriminator(
+    discriminator: Option<Vec<u8>>,
+    name: &str,
+) -> Result<Option<Vec<u8>>, IdlError> {
+    if discriminator.as_ref().is_some_and(Vec::is_empty) {
+        return Err(IdlError::InvalidIdl(format!(
+            "empty discriminator of {name}"
+        )));
+    }
+    Ok(discriminator)
+}
+
+fn flatten_account_names(items: &[IdlAccountItem], names: &mut Vec<String>) {
+    for item in items {
+        match item {
+            IdlAccountItem::Composite { accounts } => flatten_account_names(accounts, names),
+            IdlAccountItem::Single { name } => names.push(name.to_camel_case()),
+        }
+    }
+}
+
+impl Idl {
+    /// Parses an IDL from its JSON representation
+    pub fn from_json(json: &[u8]) -> Result<Self, IdlError> {
+        let idl: IdlJson =
+            serde_json::from_slice(json).map_err(|err| IdlError::InvalidIdl(err.to_string()))?;
+
+        let name = idl
+            .metadata
+            .name
+            .or(idl.name)
+            .ok_or_else(|| IdlError::InvalidIdl("missing program name".to_string()))?;
+        let address = idl
+            .address
+            .or(idl.metadata.address)
+            .map(|address| {
+                Pubkey::from_str(&address).map_err(|err| {
+                    IdlError::InvalidIdl(format!("invalid address {address}: {err}"))
+                })
+            })
+            .transpose()?;
+
+        let instructions = idl
+            .instructions
+            .into_iter()
+            .map(|instruction| {
+                let mut accounts = vec![];
+                flatten_account_names(&instruction.accounts, &mut accounts);
+                Ok(IdlInstruction {
+                    discriminator: check_discriminator(
+                        instruction.discriminator,
+                        &instruction.name,
+                    )?
+                    .unwrap_or_else(|| {
+                        legacy_discriminator("global", &instruction.name.to_snake_case())
+                    }),
+                    name: instruction.name.to_camel_case(),
+                    accounts,
+                    args: instruction.args,
+                })
+            })
+            .collect::<Result<_, IdlError>>()?;
+
+        let types: HashMap<_, _> = idl
+            .types
+            .into_iter()
+            .map(|type_def| (type_def.name, type_def.ty))
+            .collect();
+        let accounts = idl
+            .accounts
+            .into_iter()
+            .map(|account| {
+                let ty = account
+                    .ty
+                    .or_else(|| types.get(&account.name).cloned())
+                    .ok_or_else(|| IdlError::UndefinedType(account.name.clone()))?;
+                Ok(IdlAccount {
+                    discriminator: check_discriminator(account.discriminator, &account.name)?
+                        .unwrap_or_else(|| legacy_discriminator("account", &account.name)),
+                    name: account.name,
+                    ty,
+                })
+            })
+            .collect::<Result<_, IdlError>>()?;
+
+        Ok(Self {
+            name,
+            address,
+            instructions,
+            accounts,
+            types,
+        })
+    }
+
+    /// Parses the IDL stored in an on-chain Anchor IDL account
+    pub fn from_onchain_account(data: &[u8]) -> Result<Self, IdlError> {
+        // discriminator, authority, then the length of the zlib compressed JSON
+        const HEADER_LEN: usize = DISCRIMINATOR_LEN + 32 + 4;
+        let header = data.get(..HEADER_LEN).ok_or(IdlError::DataTooShort)?;
+        let len = u32::from_le_bytes(header[HEADER_LEN - 4..].try_into().unwrap()) as usize;
+        let compressed = data
+            .get(HEADER_LEN..HEADER_LEN.saturating_add(len))
+            .ok_or(IdlError::DataTooShort)?;
+        let mut json = vec![];
+        ZlibDecoder::new(compressed)
+            .take(MAX_IDL_JSON_LEN as u64 + 1)
+            .read_to_end(&mut json)?;
+        if json.len() > MAX_IDL_JSON_LEN {
+            return Err(IdlError::InvalidIdl(format!(
+                "decompressed IDL exceeds {MAX_IDL_JSON_LEN} bytes"
+            )));
+        }
+        Self::from_json(&json)
+    }
+
+    /// Name of the program
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Address of the program, if recorded in the IDL
+    pub fn address(&self) -> Option<&Pubkey> {
+        self.address.as_ref()
+    }
+
+    /// Decodes an account of the program into its type and fields
+    pub fn parse_account(&self, data: &[u8]) -> Result<Value, IdlError> {
+        let account = self
+            .accounts
+            .iter()
+            .find(|account| data.starts_with(&account.discriminator))
+            .ok_or(IdlError::UnknownDiscriminator)?;
+        let mut decoder = Decoder {
+            types: &self.types,
+            data: &data[account.discriminator.len()..],
+        };
+        let info = decoder.decode_type_def(&account.ty, 0)?;
+        let mut parsed = Map::new();
+        parsed.insert(
+            "type".to_string(),
+            Value::String(account.name.to_camel_case()),
+        );
+        parsed.insert("info".to_string(), info);
+        Ok(Value::Object(parsed))
+    }
+
+    /// Decodes the data of an instruction of the program, returning the instruction and its
+    /// arguments keyed by their camelCase names
+    pub fn parse_instruction(
+        &self,
+        data: &[u8],
+    ) -> Result<(&IdlInstruction, Map<String, Value>), IdlError> {
+        let instruction = self
+            .instructions
+            .iter()
+            .find(|instruction| data.starts_with(&instruction.discriminator))
+            .ok_or(IdlError::UnknownDiscriminator)?;
+        let mut decoder = Decoder {
+            types: &self.types,
+            data: &data[instruction.discriminator.len()..],
+        };
+        let args = instruction
+            .args
+            .iter()
+            .map(|arg| Ok((arg.name.to_camel_case(), decoder.decode(&arg.ty, 0)?)))
+            .collect::<Result<_, IdlError>>()?;
+        Ok((instruction, args))
+    }
+}
+
+fn option_flag(flag: u32) -> Result<bool, IdlError> {
+    match flag {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(IdlError::InvalidValue("option")),
+    }
+}
+
+/// Borsh decoder of the values of IDL types
+struct Decoder<'a> {
+    types: &'a HashMap<String, IdlTypeDefTy>,
+    data: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], IdlError> {
+        if self.data.len() < len {
+            return Err(IdlError::DataTooShort);
+        }
+        let (taken, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(taken)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], IdlError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn take_len(&mut self) -> Result<usize, IdlError> {
+        Ok(u32::from_le_bytes(self.take_array()?) as usize)
+    }
+
+    fn decode(&mut self, ty: &IdlType, depth: usize) -> Result<Value, IdlError> {
+        if depth > MAX_TYPE_DEPTH {
+            return Err(IdlError::MaxDepthExceeded);
+        }
+        match ty {
+            IdlType::Primitive(primitive) => self.decode_primitive(primitive),
+            IdlType::Composite(IdlCompositeType::Option(ty)) => {
+                let flag = self.take_array::<1>()?[0];
+                if option_flag(flag as u32)? {
+                    self.decode(ty, depth + 1)
+                } else {
+                    Ok(Value::Null)
+                }
+            }
+            IdlType::Composite(IdlCompositeType::COption(ty)) => {
+                // The value is stored even when absent, so the layout has a fixed size
+                let flag = u32::from_le_bytes(self.take_array()?);
+                let is_some = option_flag(flag)?;
+                let value = self.decode(ty, depth + 1)?;
+                Ok(if is_some { value } else { Value::Null })
+            }
+            IdlType::Composite(IdlCompositeType::Vec(ty)) => {
+                let len = self.take_len()?;
+                // Don't allocate for lengths the remaining data can't possibly hold
+                if len > self.data.len() {
+                    return Err(IdlError::DataTooShort);
+                }
+                (0..len)
+                    .map(|_| self.decode(ty, depth + 1))
+                    .collect::<Result<_, _>>()
+                    .map(Value::Array)
+            }
+            IdlType::Composite(IdlCompositeType::Array(ty, len)) => {
+                let len = len
+                    .as_u64()
+                    .ok_or_else(|| IdlError::UnsupportedType(format!("array length {len}")))?;
+                if len > self.data.len() as u64 {
+                    return Err(IdlError::DataTooShort);
+                }
+                (0..len)
+                    .map(|_| self.decode(ty, depth + 1))
+                    .collect::<Result<_, _>>()
+                    .map(Value::Array)
+            }
+            IdlType::Composite(IdlCompositeType::Defined(defined)) => {
+                let type_def = self
+                    .types
+                    .get(defined.name())
+                    .ok_or_else(|| IdlError::UndefinedType(defined.name().to_string()))?;
+                self.decode_type_def(type_def, depth + 1)
+            }
+            IdlType::Composite(IdlCompositeType::Generic(name)) => {
+                Err(IdlError::UnsupportedType(format!("generic {name}")))
+            }
+        }
+    }
+
+    fn decode_primitive(&mut self, primitive: &str) -> Result<Value, IdlError> {
+        // 64 and 128 bit integers are rendered as strings, as JSON numbers lose their precision
+        Ok(match primitive {
+            "bool" => match self.take_array::<1>()?[0] {
+                0 => Value::Bool(false),
+                1 => Value::Bool(true),
+                _ => return Err(IdlError::InvalidValue("bool")),
+            },
+            "u8" => Value::from(u8::from_le_bytes(self.take_array()?)),
+            "i8" => Value::from(i8::from_le_bytes(self.take_array()?)),
+            "u16" => Value::from(u16::from_le_bytes(self.take_array()?)),
+            "i16" => Value::from(i16::from_le_bytes(self.take_array()?)),
+            "u32" => Value::from(u32::from_le_bytes(self.take_array()?)),
+            "i32" => Value::from(i32::from_le_bytes(self.take_array()?)),
+            "u64" => Value::String(u64::from_le_bytes(self.take_array()?).to_string()),
+            "i64" => Value::String(i64::from_le_bytes(self.take_array()?).to_string()),
+            "u128" => Value::String(u128::from_le_bytes(self.take_array()?).to_string()),
+            "i128" => Value::String(i128::from_le_bytes(self.take_array()?).to_string()),
+            "f32" => Number::from_f64(f32::from_le_bytes(self.take_array()?) as f64)
+                .map_or(Value::Null, Value::Number),
+            "f64" => Number::from_f64(f64::from_le_bytes(self.take_array()?))
+                .map_or(Value::Null, Value::Number),
+            "string" => {
+                let len = self.take_len()?;
+                let string = std::str::from_utf8(self.take(len)?)
+                    .map_err(|_| IdlError::InvalidValue("string"))?;
+                Value::String(string.to_string())
+            }
+            "bytes" => {
+                let len = self.take_len()?;
+                Value::String(bs58::encode(self.take(len)?).into_string())
+            }
+            "pubkey" | "publicKey" => {
+                Value::String(Pubkey::from(self.take_array::<32>()?).to_string())
+            }
+            _ => return Err(IdlError::UnsupportedType(primitive.to_string())),
+        })
+    }
+
+    fn decode_fields(&mut self, fields: &IdlFields, depth: usize) -> Result<Value, IdlError> {
+        match fields {
+            IdlFields::Named(fields) => fields
+                .iter()
+                .map(|field| Ok((field.name.to_camel_case(), self.decode(&field.ty, depth)?)))
+                .collect::<Result<_, _>>()
+                .map(Value::Object),
+            IdlFields::Tuple(types) => types
+                .iter()
+                .map(|ty| self.decode(ty, depth))
+                .collect::<Result<_, _>>()
+                .map(Value::Array),
+        }
+    }
+
+    fn decode_type_def(&mut self, ty: &IdlTypeDefTy, depth: usize) -> Result<Value, IdlError> {
+        match ty {
+            IdlTypeDefTy::Struct { fields: None } => Ok(Value::Object(Map::new())),
+            IdlTypeDefTy::Struct {
+                fields: Some(fields),
+            } => self.decode_fields(fields, depth),
+            IdlTypeDefTy::Enum { variants } => {
+                let index = self.take_array::<1>()?[0];
+                let variant = variants
+                    .get(index as usize)
+                    .ok_or(IdlError::InvalidValue("enum variant"))?;
+                let name = variant.name.to_camel_case();
+                // Unit variants are rendered as their name, others as an object keyed by it
+                match &variant.fields {
+                    None => Ok(Value::String(name)),
+                    Some(fields) => {
+                        let mut value = Map::new();
+                        value.insert(name, self.decode_fields(fields, depth)?);
+                        Ok(Value::Object(value))
+                    }
+                }
+            }
+            IdlTypeDefTy::Type { alias } => self.decode(alias, depth),
+        }
+    }
+}
+
+/// IDLs keyed by the program whose instructions and accounts they parse
+#[derive(Debug, Default)]
+pub struct IdlRegistry {
+    idls: RwLock<HashMap<Pubkey, Arc<Idl>>>,
+}
+
+impl IdlRegistry {
+    /// Registers `idl` for `program_id`, replacing any IDL previously registered for the
+    /// program
+    pub fn register(&self, program_id: Pubkey, idl: Idl) {
+        self.idls.write().unwrap().insert(program_id, Arc::new(idl));
+    }
+
+    /// Returns the IDL registered for `program_id`, if any
+    pub fn get(&self, program_id: &Pubkey) -> Option<Arc<Idl>> {
+        self.idls.read().unwrap().get(program_id).cloned()
+    }
+
+    /// Registers each `.json` IDL file in `dir` under the program address recorded in it,
+    /// returning the registered program ids. Nothing is registered if any of the files is
+    /// invalid.
+    pub fn register_dir(&self, dir: &Path) -> Result<Vec<Pubkey>, IdlError> {
+        let mut idls = vec![];
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+                continue;
+            }
+            let idl = Idl::from_json(&fs::read(&path)?)
+                .map_err(|err| IdlError::InvalidIdl(format!("{}: {err}", path.display())))?;
+            let program_id = *idl.address().ok_or_else(|| {
+                IdlError::InvalidIdl(format!("{}: missing program address", path.display()))
+            })?;
+            idls.push((program_id, idl));
+        }
+
+        Ok(idls
+            .into_iter()
+            .map(|(program_id, idl)| {
+                self.register(program_id, idl);
+                program_id
+            })
+            .collect())
+    }
+}
+
+/// The registry consulted by `parse_account_data_v2` and the instruction parser of
+/// `solana-transaction-status`
+pub fn idl_registry() -> &'static IdlRegistry {
+    &IDL_REGISTRY
+}
+
+/// Registers `idl` to parse the instructions and accounts of `program_id`, replacing any IDL
+/// previously registered for the program. Programs with a built-in parser keep being decoded by
+/// it.
+pub fn register_idl(program_id: Pubkey, idl: Idl) {
+    IDL_REGISTRY.register(program_id, idl)
+}
+
+/// Returns the IDL registered for `program_id`, if any
+pub fn registered_idl(program_id: &Pubkey) -> Option<Arc<Idl>> {
+    IDL_REGISTRY.get(program_id)
+}
+
+/// Registers each `.json` IDL file in `dir`, see `IdlRegistry::register_dir`
+pub fn register_idl_dir(dir: &Path) -> Result<Vec<Pubkey>, IdlError> {
+    IDL_REGISTRY.register_dir(dir)
+}
+
+/// Returns the address of the on-chain IDL account of the Anchor program `program_id`
+pub fn onchain_idl_address(program_id: &Pubkey) -> Pubkey {
+    let (base, _bump) = Pubkey::find_program_address(&[], program_id);
+    Pubkey::create_with_seed(&base, IDL_ACCOUNT_SEED, program_id)
+        .expect("IDL account seed is shorter than the maximum seed length")
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, flate2::write::ZlibEncoder, serde_json::json, std::io::Write};
+
+    fn test_idl(address: &Pubkey) -> Value {
+        json!({
+            "address": address.to_string(),
+            "metadata": {"name": "counter_program", "version": "0.1.0", "spec": "0.1.0"},
+            "instructions": [{
+                "name": "set_count",
+                "discriminator": [1, 2, 3, 4, 5, 6, 7, 8],
+                "accounts": [
+                    {"name": "counter", "writable": true},
+                    {"name": "authority_info", "accounts": [{"name": "authority", "signer": true}]},
+                ],
+                "args": [
+                    {"name": "new_count", "type": "u64"},
+                    {"name": "label", "type": {"option": "string"}},
+                ],
+            }],
+            "accounts": [{"name": "Counter", "discriminator": [8, 7, 6, 5, 4, 3, 2, 1]}],
+            "types": [
+                {
+                    "name": "Counter",
+                    "type": {"kind": "struct", "fields": [
+                        {"name": "authority", "type": "pubkey"},
+                        {"name": "count", "type": "u64"},
+                        {"name": "mode", "type": {"defined": {"name": "Mode"}}},
+                        {"name": "history", "type": {"vec": "u16"}},
+                        {"name": "flags", "type": {"array": ["bool", 2]}},
+                    ]},
+                },
+                {
+                    "name": "Mode",
+                    "type": {"kind": "enum", "variants": [
+                        {"name": "Idle"},
+                        {"name": "Stepping", "fields": [{"name": "step_size", "type": "u8"}]},
+                    ]},
+                },
+            ],
+        })
+    }
+
+    #[test]
+    fn test_parse_account() {
+        let address = Pubkey::new_unique();
+        let idl = Idl::from_json(test_idl(&address).to_string().as_bytes()).unwrap();
+        assert_eq!(idl.name(), "counter_program");
+        assert_eq!(idl.address(), Some(&address));
+
+        let authority = Pubkey::new_unique();
+        let mut data = vec![8, 7, 6, 5, 4, 3, 2, 1];
+        data.extend_from_slice(authority.as_ref());
+        data.extend_from_slice(&42u64.to_le_bytes());
+        data.extend_from_slice(&[1, 3]);
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&[10, 0, 20, 0]);
+        data.extend_from_slice(&[1, 0]);
+        // unused space at the end of the account
+        data.extend_from_slice(&[0; 16]);
+
+        assert_eq!(
+            idl.parse_account(&data).unwrap(),
+            json!({
+                "type": "counter",
+                "info": {
+                    "authority": authority.to_string(),
+                    "count": "42",
+                    "mode": {"stepping": {"stepSize": 3}},
+                    "history": [10, 20],
+                    "flags": [true, false],
+                },
+            })
+        );
+
+        assert!(matches!(
+            idl.parse_account(&[0; 64]),
+            Err(IdlError::UnknownDiscriminator)
+        ));
+        assert!(matches!(
+            idl.parse_account(&data[..48]),
+            Err(IdlError::DataTooShort)
+        ));
+    }
+
+    #[test]
+    fn test_parse_instruction() {
+        let idl = Idl::from_json(test_idl(&Pubkey::new_unique()).to_string().as_bytes()).unwrap();
+
+        let mut data = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        data.extend_from_slice(&7u64.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(b"hi");
+        let (instruction, args) = idl.parse_instruction(&data).unwrap();
+        assert_eq!(instruction.name, "setCount");
+        assert_eq!(instruction.accounts, vec!["counter", "authority"]);
+        assert_eq!(Value::Object(args), json!({"newCount": "7", "label": "hi"}));
+
+        data.truncate(16);
+        data.push(0);
+        let (_, args) = idl.parse_instruction(&data).unwrap();
+        assert_eq!(Value::Object(args), json!({"newCount": "7", "label": null}));
+    }
+
+    #[test]
+    fn test_legacy_idl() {
+        let address = Pubkey::new_unique();
+        let idl = json!({
+            "version": "0.1.0",
+            "name": "legacy_program",
+            "instructions": [{
+                "name": "initialize",
+                "accounts": [{"name": "state", "isMut": true, "isSigner": false}],
+                "args": [{"name": "owner", "type": "publicKey"}],
+            }],
+            "accounts": [{
+                "name": "State",
+                "type": {"kind": "struct", "fields": [{"name": "owner", "type": "publicKey"}]},
+            }],
+            "metadata": {"address": address.to_string()},
+        });
+        let idl = Idl::from_json(idl.to_string().as_bytes()).unwrap();
+        assert_eq!(idl.name(), "legacy_program");
+        assert_eq!(idl.address(), Some(&address));
+
+        let owner = Pubkey::new_unique();
+        let mut data = legacy_discriminator("account", "State");
+        data.extend_from_slice(owner.as_ref());
+        assert_eq!(
+            idl.parse_account(&data).unwrap(),
+            json!({"type": "state", "info": {"owner": owner.to_string()}})
+        );
+
+        let mut data = legacy_discriminator("global", "initialize");
+        data.extend_from_slice(owner.as_ref());
+        let (instruction, args) = idl.parse_instruction(&data).unwrap();
+        assert_eq!(instruction.name, "initialize");
+        assert_eq!(Value::Object(args), json!({"owner": owner.to_string()}));
+    }
+
+    #[test]
+    fn test_from_onchain_account() {
+        let address = Pubkey::new_unique();
+        let mut encoder = ZlibEncoder::new(vec![], flate2::Compression::default());
+        encoder
+            .write_all(test_idl(&address).to_string().as_bytes())
+            .unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let mut data = vec![0; DISCRIMINATOR_LEN];
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+        data.extend_from_slice(&compressed);
+        data.extend_from_slice(&[0; 32]);
+        let idl = Idl::from_onchain_account(&data).unwrap();
+        assert_eq!(idl.address(), Some(&address));
+
+        assert!(Idl::from_onchain_account(&data[..DISCRIMINATOR_LEN + 32 + 4 + 1]).is_err());
+
+        // A zlib bomb is cut off after the maximum length
+        let mut encoder = ZlibEncoder::new(vec![], flate2::Compression::best());
+        encoder
+            .write_all(&vec![b' '; MAX_IDL_JSON_LEN + 1])
+            .unwrap();
+        let compressed = encoder.finish().unwrap();
+        let mut data = vec![0; DISCRIMINATOR_LEN];
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+        data.extend_from_slice(&compressed);
+        assert!(matches!(
+            Idl::from_onchain_account(&data),
+            Err(IdlError::InvalidIdl(_))
+        ));
+    }
+
+    #[test]
+    fn test_empty_discriminator() {
+        let mut idl = test_idl(&Pubkey::new_unique());
+        idl["instructions"][0]["discriminator"] = json!([]);
+        assert!(matches!(
+            Idl::from_json(idl.to_string().as_bytes()),
+            Err(IdlError::InvalidIdl(_))
+        ));
+
+        let mut idl = test_idl(&Pubkey::new_unique());
+        idl["accounts"][0]["discriminator"] = json!([]);
+        assert!(matches!(
+            Idl::from_json(idl.to_string().as_bytes()),
+            Err(IdlError::InvalidIdl(_))
+        ));
+    }
+
+    #[test]
+    fn test_register_idl_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let address = Pubkey::new_unique();
+        fs::write(
+            dir.path().join("counter.json"),
+            test_idl(&address).to_string(),
+        )
+        .unwrap();
+        fs::write(dir.path().join("README.md"), "not an IDL").unwrap();
+        let registry = IdlRegistry::default();
+        assert_eq!(registry.register_dir(dir.path()).unwrap(), vec![address]);
+        assert_eq!(registry.get(&address).unwrap().name(), "counter_program");
+        assert!(registered_idl(&address).is_none());
+
+        fs::write(dir.path().join("bad.json"), "{}").unwrap();
+        let registry = IdlRegistry::default();
+        assert!(registry.register_dir(dir.path()).is_err());
+        assert!(registry.get(&address).is_none());
+    }
+}
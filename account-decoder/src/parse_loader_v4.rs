@@ -0,0 +1,95 @@
+use {
+    crate::{
+        parse_account_data::{ParsableAccount, ParseAccountError},
+        UiAccountData, UiAccountEncoding,
+    },
+    base64::{prelude::BASE64_STANDARD, Engine},
+    solana_sdk::{loader_v4::LoaderV4State, pubkey::Pubkey},
+};
+
+pub fn parse_loader_v4(data: &[u8]) -> Result<LoaderV4AccountType, ParseAccountError> {
+    // The loader reinterprets the account data as a `LoaderV4State` in place, so the header is
+    // read field by field: the slot, the authority and the status as a u64 discriminant
+    let not_parsable = || ParseAccountError::AccountNotParsable(ParsableAccount::LoaderV4);
+    let header = data
+        .get(..LoaderV4State::program_data_offset())
+        .ok_or_else(not_parsable)?;
+    let slot = u64::from_le_bytes(header[0..8].try_into().unwrap());
+    let authority = Pubkey::try_from(&header[8..40]).unwrap();
+    let status = match u64::from_le_bytes(header[40..48].try_into().unwrap()) {
+        0 => UiLoaderV4Status::Retracted,
+        1 => UiLoaderV4Status::Deployed,
+        2 => UiLoaderV4Status::Finalized,
+        _ => return Err(not_parsable()),
+    };
+    Ok(LoaderV4AccountType::Program(UiLoaderV4Program {
+        slot,
+        authority: authority.to_string(),
+        status,
+        data: UiAccountData::Binary(
+            BASE64_STANDARD.encode(&data[LoaderV4State::program_data_offset()..]),
+            UiAccountEncoding::Base64,
+        ),
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "type", content = "info")]
+pub enum LoaderV4AccountType {
+    Program(UiLoaderV4Program),
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiLoaderV4Program {
+    pub slot: u64,
+    pub authority: String,
+    pub status: UiLoaderV4Status,
+    pub data: UiAccountData,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum UiLoaderV4Status {
+    Retracted,
+    Deployed,
+    Finalized,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_loader_v4_accounts() {
+        let authority = Pubkey::new_unique();
+        let program = vec![7u8; 64]; // Arbitrary program data
+
+        let mut account_data = 42u64.to_le_bytes().to_vec();
+        account_data.extend_from_slice(authority.as_ref());
+        account_data.extend_from_slice(&1u64.to_le_bytes());
+        assert_eq!(account_data.len(), LoaderV4State::program_data_offset());
+        account_data.extend_from_slice(&program);
+        assert_eq!(
+            parse_loader_v4(&account_data).unwrap(),
+            LoaderV4AccountType::Program(UiLoaderV4Program {
+                slot: 42,
+                authority: authority.to_string(),
+                status: UiLoaderV4Status::Deployed,
+                data: UiAccountData::Binary(
+                    BASE64_STANDARD.encode(&program),
+                    UiAccountEncoding::Base64
+                ),
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(parse_loader_v4(&account_data).unwrap()).unwrap()["info"]
+                ["status"],
+            "deployed"
+        );
+
+        account_data[40] = 3;
+        assert!(parse_loader_v4(&account_data).is_err());
+        assert!(parse_loader_v4(&account_data[..40]).is_err());
+    }
+}
@@ -499,6 +499,8 @@ pub const ACCOUNTS_DB_CONFIG_FOR_TESTING: AccountsDbConfig = AccountsDbConfig {
     skip_initial_hash_calc: false,
     exhaustively_verify_refcounts: false,
     create_ancient_storage: CreateAncientStorage::Pack,
+    accounts_file_provider: AccountsFileProvider::AppendVec,
+    ancient_accounts_file_provider: None,
     test_partitioned_epoch_rewards: TestPartitionedEpochRewards::CompareResults,
     test_skip_rewrites_but_include_in_bank_hash: false,
     storage_access: StorageAccess::Mmap,
@@ -514,6 +516,8 @@ pub const ACCOUNTS_DB_CONFIG_FOR_BENCHMARKS: AccountsDbConfig = AccountsDbConfig
     skip_initial_hash_calc: false,
     exhaustively_verify_refcounts: false,
     create_ancient_storage: CreateAncientStorage::Pack,
+    accounts_file_provider: AccountsFileProvider::AppendVec,
+    ancient_accounts_file_provider: None,
     test_partitioned_epoch_rewards: TestPartitionedEpochRewards::None,
     test_skip_rewrites_but_include_in_bank_hash: false,
     storage_access: StorageAccess::Mmap,
@@ -608,6 +612,11 @@ pub struct AccountsDbConfig {
     pub exhaustively_verify_refcounts: bool,
     /// how to create ancient storages
     pub create_ancient_storage: CreateAncientStorage,
+    /// storage format of newly created storages
+    pub accounts_file_provider: AccountsFileProvider,
+    /// storage format of ancient storages created by packing
+    /// if None, ancient storages use the same format as all other storages
+    pub ancient_accounts_file_provider: Option<AccountsFileProvider>,
     pub test_partitioned_epoch_rewards: TestPartitionedEpochRewards,
     pub storage_access: StorageAccess,
 }
@@ -1099,7 +1108,7 @@ impl AccountStorageEntry {
         file_size: u64,
         provider: AccountsFileProvider,
     ) -> Self {
-        let tail = AccountsFile::file_name(slot, id);
+        let tail = provider.format().file_name(slot, id);
         let path = Path::new(path).join(tail);
         let accounts = provider.new_writable(path, file_size);
 
@@ -1501,6 +1510,10 @@ pub struct AccountsDb {
     /// storage format to use for new storages
     accounts_file_provider: AccountsFileProvider,
 
+    /// storage format to use for ancient storages created by packing
+    /// falls back to `accounts_file_provider` if None
+    pub(crate) ancient_accounts_file_provider: Option<AccountsFileProvider>,
+
     /// method to use for accessing storages
     storage_access: StorageAccess,
 
@@ -2492,6 +2505,7 @@ impl AccountsDb {
             log_dead_slots: AtomicBool::new(true),
             exhaustively_verify_refcounts: false,
             accounts_file_provider: AccountsFileProvider::default(),
+            ancient_accounts_file_provider: None,
             storage_access: StorageAccess::default(),
             partitioned_epoch_rewards_config: PartitionedEpochRewardsConfig::default(),
             epoch_accounts_hash_manager: EpochAccountsHashManager::new_invalid(),
@@ -2573,6 +2587,15 @@ impl AccountsDb {
             .map(|config| config.create_ancient_storage)
             .unwrap_or_default();
 
+        let accounts_file_provider = accounts_db_config
+            .as_ref()
+            .map(|config| config.accounts_file_provider)
+            .unwrap_or_default();
+
+        let ancient_accounts_file_provider = accounts_db_config
+            .as_ref()
+            .and_then(|config| config.ancient_accounts_file_provider);
+
         let test_partitioned_epoch_rewards = accounts_db_config
             .as_ref()
             .map(|config| config.test_partitioned_epoch_rewards)
@@ -2609,6 +2632,8 @@ impl AccountsDb {
             shrink_ratio,
             accounts_update_notifier,
             create_ancient_storage,
+            accounts_file_provider,
+            ancient_accounts_file_provider,
             read_only_accounts_cache: ReadOnlyAccountsCache::new(
                 read_cache_size.0,
                 read_cache_size.1,
@@ -2667,14 +2692,14 @@ impl AccountsDb {
         next_id
     }
 
-    fn new_storage_entry(&self, slot: Slot, path: &Path, size: u64) -> AccountStorageEntry {
-        AccountStorageEntry::new(
-            path,
-            slot,
-            self.next_id(),
-            size,
-            self.accounts_file_provider,
-        )
+    fn new_storage_entry(
+        &self,
+        slot: Slot,
+        path: &Path,
+        size: u64,
+        provider: AccountsFileProvider,
+    ) -> AccountStorageEntry {
+        AccountStorageEntry::new(path, slot, self.next_id(), size, provider)
     }
 
     pub fn expected_cluster_type(&self) -> ClusterType {
@@ -4425,6 +4450,22 @@ impl AccountsDb {
         self.storage.shrinking_in_progress(slot, shrunken_store)
     }
 
+    /// return a store that can contain 'size' bytes, in the format used for
+    /// ancient storages created by packing
+    pub(crate) fn get_store_for_ancient(&self, slot: Slot, size: u64) -> ShrinkInProgress<'_> {
+        let provider = self
+            .ancient_accounts_file_provider
+            .unwrap_or(self.accounts_file_provider);
+        let ancient_store = self.create_store_with_provider(
+            slot,
+            size,
+            "ancient",
+            self.shrink_paths.as_slice(),
+            provider,
+        );
+        self.storage.shrinking_in_progress(slot, ancient_store)
+    }
+
     // Reads all accounts in given slot's AppendVecs and filter only to alive,
     // then create a minimum AppendVec filled with the alive.
     fn shrink_slot_forced(&self, slot: Slot) {
@@ -5909,12 +5950,24 @@ impl AccountsDb {
         size: u64,
         from: &str,
         paths: &[PathBuf],
+    ) -> Arc<AccountStorageEntry> {
+        self.create_store_with_provider(slot, size, from, paths, self.accounts_file_provider)
+    }
+
+    fn create_store_with_provider(
+        &self,
+        slot: Slot,
+        size: u64,
+        from: &str,
+        paths: &[PathBuf],
+        provider: AccountsFileProvider,
     ) -> Arc<AccountStorageEntry> {
         self.stats
             .create_store_count
             .fetch_add(1, Ordering::Relaxed);
         let path_index = thread_rng().gen_range(0..paths.len());
-        let store = Arc::new(self.new_storage_entry(slot, Path::new(&paths[path_index]), size));
+        let store =
+            Arc::new(self.new_storage_entry(slot, Path::new(&paths[path_index]), size, provider));
 
         debug!(
             "creating store: {} slot: {} len: {} size: {} from: {} path: {}",
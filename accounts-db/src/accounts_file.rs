@@ -6,11 +6,12 @@ use {
         append_vec::{AppendVec, AppendVecError, IndexInfo},
         storable_accounts::StorableAccounts,
         tiered_storage::{
-            error::TieredStorageError, hot::HOT_FORMAT, index::IndexOffset, TieredStorage,
+            cold::COLD_FORMAT, error::TieredStorageError, index::IndexOffset, TieredStorage,
         },
     },
     solana_sdk::{account::AccountSharedData, clock::Slot, pubkey::Pubkey},
     std::{
+        collections::{HashMap, HashSet},
         mem,
         path::{Path, PathBuf},
     },
@@ -59,6 +60,38 @@ pub enum StorageAccess {
 
 pub type Result<T> = std::result::Result<T, AccountsFileError>;
 
+/// The extension of the file names of tiered storages.
+///
+/// Append vecs have no extension. The format is recorded in the file name as
+/// it cannot be told from the file contents: the tail of an append vec is
+/// account data that anyone can write.
+pub const TIERED_STORAGE_FILE_EXTENSION: &str = "tiered";
+
+/// The on-disk format of an accounts file
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AccountsFileFormat {
+    AppendVec,
+    TieredStorage,
+}
+
+impl AccountsFileFormat {
+    /// Returns the format recorded in the name of an accounts file
+    pub fn from_file_name(file_name: &str) -> Self {
+        match file_name.rsplit_once('.') {
+            Some((_, TIERED_STORAGE_FILE_EXTENSION)) => Self::TieredStorage,
+            _ => Self::AppendVec,
+        }
+    }
+
+    /// Returns the name of the accounts file of `slot` and `id` in this format
+    pub fn file_name(&self, slot: Slot, id: AccountsFileId) -> String {
+        match self {
+            Self::AppendVec => AccountsFile::file_name(slot, id),
+            Self::TieredStorage => format!("{slot}.{id}.{TIERED_STORAGE_FILE_EXTENSION}"),
+        }
+    }
+}
+
 #[derive(Debug)]
 /// An enum for accessing an accounts file which can be implemented
 /// under different formats.
@@ -68,17 +101,47 @@ pub enum AccountsFile {
 }
 
 impl AccountsFile {
-    /// Create an AccountsFile instance from the specified path.
+    /// Create an AccountsFile instance of the specified format from the specified path.
     ///
     /// The second element of the returned tuple is the number of accounts in the
     /// accounts file.
+    ///
+    /// Tiered storages are always accessed by mmap, regardless of `storage_access`.
     pub fn new_from_file(
         path: impl Into<PathBuf>,
         current_len: usize,
         storage_access: StorageAccess,
+        format: AccountsFileFormat,
     ) -> Result<(Self, usize)> {
-        let (av, num_accounts) = AppendVec::new_from_file(path, current_len, storage_access)?;
-        Ok((Self::AppendVec(av), num_accounts))
+        match format {
+            AccountsFileFormat::AppendVec => {
+                let (av, num_accounts) =
+                    AppendVec::new_from_file(path, current_len, storage_access)?;
+                Ok((Self::AppendVec(av), num_accounts))
+            }
+            AccountsFileFormat::TieredStorage => {
+                let ts = TieredStorage::new_readonly(path)?;
+                if ts.len() != current_len {
+                    return Err(AccountsFileError::TieredStorageError(
+                        TieredStorageError::LenMismatch(
+                            ts.path().to_path_buf(),
+                            ts.len(),
+                            current_len,
+                        ),
+                    ));
+                }
+                let num_accounts = ts.reader().map_or(0, |reader| reader.num_accounts());
+                Ok((Self::TieredStorage(ts), num_accounts))
+            }
+        }
+    }
+
+    /// Returns the on-disk format of this accounts file
+    pub fn format(&self) -> AccountsFileFormat {
+        match self {
+            Self::AppendVec(_) => AccountsFileFormat::AppendVec,
+            Self::TieredStorage(_) => AccountsFileFormat::TieredStorage,
+        }
     }
 
     /// true if this storage can possibly be appended to (independent of capacity check)
@@ -140,6 +203,7 @@ impl AccountsFile {
         }
     }
 
+    /// Returns the name of the append vec of `slot` and `id`
     pub fn file_name(slot: Slot, id: AccountsFileId) -> String {
         format!("{slot}.{id}")
     }
@@ -259,6 +323,27 @@ impl AccountsFile {
         }
     }
 
+    /// Returns the accounts in this file whose pubkey is in `pubkeys`.
+    ///
+    /// Only the index entries are scanned, so the data of the other accounts is never read.
+    /// If a pubkey was stored more than once, the last version stored is returned.
+    pub fn get_accounts(&self, pubkeys: &HashSet<Pubkey>) -> HashMap<Pubkey, AccountSharedData> {
+        let mut offsets = HashMap::new();
+        self.scan_index(|index_info| {
+            let index_info = index_info.index_info;
+            if pubkeys.contains(&index_info.pubkey) {
+                offsets.insert(index_info.pubkey, index_info.offset);
+            }
+        });
+        offsets
+            .into_iter()
+            .filter_map(|(pubkey, offset)| {
+                self.get_account_shared_data(offset)
+                    .map(|account| (pubkey, account))
+            })
+            .collect()
+    }
+
     /// Copy each account metadata, account and hash to the internal buffer.
     /// If there is no room to write the first entry, None is returned.
     /// Otherwise, returns the starting offset of each account metadata.
@@ -277,7 +362,7 @@ impl AccountsFile {
             // assumes all offsets are multiple of 8 while TieredStorage uses
             // IndexOffset that is equivalent to AccountInfo::reduced_offset.
             Self::TieredStorage(ts) => ts
-                .write_accounts(accounts, skip, &HOT_FORMAT)
+                .write_accounts(accounts, skip, ts.format())
                 .map(|mut stored_accounts_info| {
                     stored_accounts_info.offsets.iter_mut().for_each(|offset| {
                         *offset = AccountInfo::reduced_offset_to_offset(*offset as u32);
@@ -307,15 +392,27 @@ pub enum AccountsFileProvider {
     #[default]
     AppendVec,
     HotStorage,
+    ColdStorage,
 }
 
 impl AccountsFileProvider {
+    /// Returns the on-disk format of the accounts files created by this provider
+    pub fn format(&self) -> AccountsFileFormat {
+        match self {
+            Self::AppendVec => AccountsFileFormat::AppendVec,
+            Self::HotStorage | Self::ColdStorage => AccountsFileFormat::TieredStorage,
+        }
+    }
+
     pub fn new_writable(&self, path: impl Into<PathBuf>, file_size: u64) -> AccountsFile {
         match self {
             Self::AppendVec => {
                 AccountsFile::AppendVec(AppendVec::new(path, true, file_size as usize))
             }
             Self::HotStorage => AccountsFile::TieredStorage(TieredStorage::new_writable(path)),
+            Self::ColdStorage => AccountsFile::TieredStorage(
+                TieredStorage::new_writable_with_format(path, COLD_FORMAT),
+            ),
         }
     }
 }
@@ -340,7 +437,11 @@ pub struct StoredAccountsInfo {
 
 #[cfg(test)]
 pub mod tests {
-    use crate::accounts_file::AccountsFile;
+    use {
+        super::*, assert_matches::assert_matches, solana_sdk::account::ReadableAccount,
+        std::mem::ManuallyDrop, tempfile::TempDir,
+    };
+
     impl AccountsFile {
         pub(crate) fn set_current_len_for_tests(&self, len: usize) {
             match self {
@@ -349,4 +450,145 @@ pub mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_new_from_file_cold_storage() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("test_new_from_file_cold_storage");
+        let accounts: Vec<_> = (1..10u64)
+            .map(|i| {
+                (
+                    Pubkey::new_unique(),
+                    AccountSharedData::new(i, i as usize, &Pubkey::new_unique()),
+                )
+            })
+            .collect();
+        let account_refs: Vec<_> = accounts
+            .iter()
+            .map(|(pubkey, account)| (pubkey, account))
+            .collect();
+
+        let (stored_accounts_info, len) = {
+            let accounts_file =
+                ManuallyDrop::new(AccountsFileProvider::ColdStorage.new_writable(&path, 0));
+            let stored_accounts_info = accounts_file
+                .append_accounts(&(Slot::MAX, &account_refs[..]), 0)
+                .unwrap();
+            (stored_accounts_info, accounts_file.len())
+        };
+
+        // reopening the file, as done when loading from a snapshot
+        let (accounts_file, num_accounts) = AccountsFile::new_from_file(
+            &path,
+            len,
+            StorageAccess::Mmap,
+            AccountsFileFormat::TieredStorage,
+        )
+        .unwrap();
+        assert_matches!(accounts_file, AccountsFile::TieredStorage(_));
+        assert_eq!(num_accounts, accounts.len());
+        assert_eq!(accounts_file.len(), len);
+
+        for ((pubkey, account), offset) in accounts.iter().zip(stored_accounts_info.offsets) {
+            accounts_file
+                .get_stored_account_meta_callback(offset, |stored_account_meta| {
+                    assert_eq!(stored_account_meta.pubkey(), pubkey);
+                    assert_eq!(stored_account_meta.to_account_shared_data(), *account);
+                })
+                .unwrap();
+            assert_eq!(
+                accounts_file.get_account_shared_data(offset).unwrap(),
+                *account
+            );
+        }
+    }
+
+    #[test]
+    fn test_new_from_file_tiered_storage_len_mismatch() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir
+            .path()
+            .join("test_new_from_file_tiered_storage_len_mismatch");
+        let pubkey = Pubkey::new_unique();
+        let account = AccountSharedData::new(1, 1, &Pubkey::new_unique());
+        let len = {
+            let accounts_file =
+                ManuallyDrop::new(AccountsFileProvider::HotStorage.new_writable(&path, 0));
+            accounts_file
+                .append_accounts(&(Slot::MAX, &[(&pubkey, &account)][..]), 0)
+                .unwrap();
+            accounts_file.len()
+        };
+
+        assert_matches!(
+            AccountsFile::new_from_file(
+                &path,
+                len + 1,
+                StorageAccess::Mmap,
+                AccountsFileFormat::TieredStorage,
+            ),
+            Err(AccountsFileError::TieredStorageError(
+                TieredStorageError::LenMismatch(..)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_accounts_file_format_file_name() {
+        for format in [
+            AccountsFileFormat::AppendVec,
+            AccountsFileFormat::TieredStorage,
+        ] {
+            let file_name = format.file_name(123, 45);
+            assert_eq!(AccountsFileFormat::from_file_name(&file_name), format);
+        }
+        assert_eq!(
+            AccountsFileFormat::AppendVec.file_name(123, 45),
+            AccountsFile::file_name(123, 45)
+        );
+        assert_eq!(
+            AccountsFileFormat::TieredStorage.file_name(123, 45),
+            "123.45.tiered"
+        );
+        assert_eq!(
+            AccountsFileProvider::ColdStorage.format(),
+            AccountsFileFormat::TieredStorage
+        );
+    }
+
+    #[test]
+    fn test_get_accounts() {
+        let temp_dir = TempDir::new().unwrap();
+        let pubkey_a = Pubkey::new_unique();
+        let pubkey_b = Pubkey::new_unique();
+        let account_a = AccountSharedData::new(1, 1, &Pubkey::new_unique());
+        let account_a_updated = AccountSharedData::new(2, 3, &Pubkey::new_unique());
+        let account_b = AccountSharedData::new(4, 5, &Pubkey::new_unique());
+
+        for provider in [
+            AccountsFileProvider::AppendVec,
+            AccountsFileProvider::HotStorage,
+        ] {
+            let path = temp_dir
+                .path()
+                .join(format!("test_get_accounts_{provider:?}"));
+            let accounts_file = provider.new_writable(&path, 1024 * 1024);
+            let accounts = [
+                (&pubkey_a, &account_a),
+                (&pubkey_b, &account_b),
+                (&pubkey_a, &account_a_updated),
+            ];
+            accounts_file
+                .append_accounts(&(Slot::MAX, &accounts[..]), 0)
+                .unwrap();
+
+            let found =
+                accounts_file.get_accounts(&HashSet::from([pubkey_a, Pubkey::new_unique()]));
+            assert_eq!(
+                found,
+                HashMap::from([(pubkey_a, account_a_updated.clone())])
+            );
+            assert!(accounts_file.get_accounts(&HashSet::new()).is_empty());
+        }
+    }
 }
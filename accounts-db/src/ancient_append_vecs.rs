@@ -357,6 +357,23 @@ impl AccountsDb {
         self.shrink_ancient_stats.report();
     }
 
+    /// combines ALL possible slots in `sorted_slots`
+    #[cfg(feature = "dev-context-only-utils")]
+    pub fn combine_ancient_slots_packed_for_tests(&self, sorted_slots: Vec<Slot>) {
+        // combine normal append vec(s) into packed ancient append vec
+        let tuning = PackedAncientStorageTuning {
+            max_ancient_slots: 0,
+            // re-combine/shrink 55% of the data savings this pass
+            percent_of_alive_shrunk_data: 55,
+            ideal_storage_size: NonZeroU64::new(get_ancient_append_vec_capacity()).unwrap(),
+            can_randomly_shrink: false,
+            max_resulting_storages: NonZeroU64::new(10).unwrap(),
+        };
+
+        let mut stats_sub = ShrinkStatsSub::default();
+        self.combine_ancient_slots_packed_internal(sorted_slots, tuning, &mut stats_sub);
+    }
+
     /// return false if `many_refs_newest` accounts cannot be moved into `target_slots_sorted`.
     /// The slot # would be violated.
     /// accounts in `many_refs_newest` must be moved a slot >= each account's current slot.
@@ -534,7 +551,7 @@ impl AccountsDb {
     ) {
         let target_slot = accounts_to_write.target_slot();
         let (shrink_in_progress, create_and_insert_store_elapsed_us) =
-            measure_us!(self.get_store_for_shrink(target_slot, bytes));
+            measure_us!(self.get_store_for_ancient(target_slot, bytes));
         let (store_accounts_timing, rewrite_elapsed_us) = measure_us!(
             self.store_accounts_frozen(accounts_to_write, shrink_in_progress.new_storage(),)
         );
@@ -1184,6 +1201,7 @@ pub mod tests {
                 },
                 ShrinkCollectRefs,
             },
+            accounts_file::AccountsFileProvider,
             accounts_hash::AccountHash,
             accounts_index::UpsertReclaim,
             append_vec::{
@@ -1191,7 +1209,9 @@ pub mod tests {
                 MAXIMUM_APPEND_VEC_FILE_SIZE,
             },
             storable_accounts::{tests::build_accounts_from_storage, StorableAccountsBySlot},
+            tiered_storage::cold::COLD_FORMAT,
         },
+        assert_matches::assert_matches,
         rand::seq::SliceRandom as _,
         solana_sdk::{
             account::{AccountSharedData, ReadableAccount, WritableAccount},
@@ -3544,22 +3564,6 @@ pub mod tests {
         }
     }
 
-    /// combines ALL possible slots in `sorted_slots`
-    fn combine_ancient_slots_packed_for_tests(db: &AccountsDb, sorted_slots: Vec<Slot>) {
-        // combine normal append vec(s) into packed ancient append vec
-        let tuning = PackedAncientStorageTuning {
-            max_ancient_slots: 0,
-            // re-combine/shrink 55% of the data savings this pass
-            percent_of_alive_shrunk_data: 55,
-            ideal_storage_size: NonZeroU64::new(get_ancient_append_vec_capacity()).unwrap(),
-            can_randomly_shrink: CAN_RANDOMLY_SHRINK_FALSE,
-            ..default_tuning()
-        };
-
-        let mut stats_sub = ShrinkStatsSub::default();
-        db.combine_ancient_slots_packed_internal(sorted_slots, tuning, &mut stats_sub);
-    }
-
     #[test]
     fn test_shrink_packed_ancient() {
         // NOTE: The recycler has been removed.  Creating this many extra storages is no longer
@@ -3581,7 +3585,7 @@ pub mod tests {
             .collect::<Vec<_>>();
 
         // fill up the recycler with storages
-        combine_ancient_slots_packed_for_tests(&db, (initial_slot..=max_slot_inclusive).collect());
+        db.combine_ancient_slots_packed_for_tests((initial_slot..=max_slot_inclusive).collect());
 
         let mut starting_slot = max_slot_inclusive + 1;
         for num_normal_slots in 1..4 {
@@ -3603,8 +3607,7 @@ pub mod tests {
                 &get_all_accounts(&db, ancient_slot..(max_slot_inclusive + 1)),
             );
 
-            combine_ancient_slots_packed_for_tests(
-                &db,
+            db.combine_ancient_slots_packed_for_tests(
                 (ancient_slot..=max_slot_inclusive).collect(),
             );
 
@@ -3637,7 +3640,7 @@ pub mod tests {
             );
             compare_all_accounts(&initial_accounts, &get_all_accounts(&db, range.clone()));
 
-            combine_ancient_slots_packed_for_tests(&db, range.clone().collect());
+            db.combine_ancient_slots_packed_for_tests(range.clone().collect());
 
             compare_all_accounts(&initial_accounts_all, &get_all_accounts(&db, range_all));
             compare_all_accounts(&initial_accounts, &get_all_accounts(&db, range));
@@ -3651,6 +3654,30 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_combine_packed_ancient_slots_cold_storage() {
+        let num_slots = 4;
+        let (mut db, slot1) = create_db_with_storages_and_index(true, num_slots, None);
+        db.ancient_accounts_file_provider = Some(AccountsFileProvider::ColdStorage);
+        let range = slot1..(slot1 + num_slots as Slot);
+        let initial_accounts = get_all_accounts(&db, range.clone());
+
+        db.combine_ancient_slots_packed_for_tests(range.clone().collect());
+
+        compare_all_accounts(&initial_accounts, &get_all_accounts(&db, range.clone()));
+        // every storage created by packing uses the cold format
+        let packed = range
+            .filter_map(|slot| db.storage.get_slot_storage_entry(slot))
+            .collect::<Vec<_>>();
+        assert!(!packed.is_empty());
+        packed.iter().for_each(|storage| {
+            assert_matches!(
+                &storage.accounts,
+                AccountsFile::TieredStorage(ts) if ts.format() == &COLD_FORMAT
+            );
+        });
+    }
+
     #[test]
     fn test_shrink_collect_alive_add() {
         let num_slots = 1;
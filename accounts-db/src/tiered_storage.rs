@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
 pub mod byte_block;
+pub mod cold;
 pub mod error;
 pub mod file;
 pub mod footer;
@@ -14,6 +15,7 @@ mod test_utils;
 
 use {
     crate::{accounts_file::StoredAccountsInfo, storable_accounts::StorableAccounts},
+    cold::{ColdStorageWriter, COLD_FORMAT},
     error::TieredStorageError,
     footer::{AccountBlockFormat, AccountMetaFormat},
     hot::{HotStorageWriter, HOT_FORMAT},
@@ -54,6 +56,8 @@ pub struct TieredStorage {
     already_written: AtomicBool,
     /// The path to the file that stores accounts.
     path: PathBuf,
+    /// The format of this TieredStorage.
+    format: TieredStorageFormat,
 }
 
 impl Drop for TieredStorage {
@@ -72,16 +76,26 @@ impl Drop for TieredStorage {
 }
 
 impl TieredStorage {
+    /// Creates a new writable instance of TieredStorage in the hot format
+    /// based on the specified path.
+    ///
+    /// Note that the actual file will not be created until write_accounts
+    /// is called.
+    pub fn new_writable(path: impl Into<PathBuf>) -> Self {
+        Self::new_writable_with_format(path, HOT_FORMAT)
+    }
+
     /// Creates a new writable instance of TieredStorage based on the
     /// specified path and TieredStorageFormat.
     ///
     /// Note that the actual file will not be created until write_accounts
     /// is called.
-    pub fn new_writable(path: impl Into<PathBuf>) -> Self {
+    pub fn new_writable_with_format(path: impl Into<PathBuf>, format: TieredStorageFormat) -> Self {
         Self {
             reader: OnceLock::<TieredStorageReader>::new(),
             already_written: false.into(),
             path: path.into(),
+            format,
         }
     }
 
@@ -89,10 +103,16 @@ impl TieredStorage {
     /// specified path.
     pub fn new_readonly(path: impl Into<PathBuf>) -> TieredStorageResult<Self> {
         let path = path.into();
+        let reader = TieredStorageReader::new_from_path(&path)?;
+        let format = match reader.footer().account_meta_format {
+            AccountMetaFormat::Hot => HOT_FORMAT,
+            AccountMetaFormat::Cold => COLD_FORMAT,
+        };
         Ok(Self {
-            reader: TieredStorageReader::new_from_path(&path).map(OnceLock::from)?,
+            reader: OnceLock::from(reader),
             already_written: true.into(),
             path,
+            format,
         })
     }
 
@@ -101,6 +121,11 @@ impl TieredStorage {
         self.path.as_path()
     }
 
+    /// Returns the format of this TieredStorage.
+    pub fn format(&self) -> &TieredStorageFormat {
+        &self.format
+    }
+
     /// Writes the specified accounts into this TieredStorage.
     ///
     /// Note that this function can only be called once per a TieredStorage
@@ -117,26 +142,29 @@ impl TieredStorage {
             panic!("cannot write same tiered storage file more than once");
         }
 
-        if format == &HOT_FORMAT {
-            let stored_accounts_info = {
-                let mut writer = HotStorageWriter::new(&self.path)?;
-                let stored_accounts_info = writer.write_accounts(accounts, skip)?;
-                writer.flush()?;
-                stored_accounts_info
-            };
-
-            // panic here if self.reader.get() is not None as self.reader can only be
-            // None since a false-value `was_written` indicates the accounts file has
-            // not been written previously, implying is_read_only() was also false.
-            debug_assert!(!self.is_read_only());
-            self.reader
-                .set(TieredStorageReader::new_from_path(&self.path)?)
-                .unwrap();
-
-            Ok(stored_accounts_info)
+        let stored_accounts_info = if format == &HOT_FORMAT {
+            let mut writer = HotStorageWriter::new(&self.path)?;
+            let stored_accounts_info = writer.write_accounts(accounts, skip)?;
+            writer.flush()?;
+            stored_accounts_info
+        } else if format == &COLD_FORMAT {
+            let mut writer = ColdStorageWriter::new(&self.path)?;
+            let stored_accounts_info = writer.write_accounts(accounts, skip)?;
+            writer.flush()?;
+            stored_accounts_info
         } else {
-            Err(TieredStorageError::UnknownFormat(self.path.to_path_buf()))
-        }
+            return Err(TieredStorageError::UnknownFormat(self.path.to_path_buf()));
+        };
+
+        // panic here if self.reader.get() is not None as self.reader can only be
+        // None since a false-value `was_written` indicates the accounts file has
+        // not been written previously, implying is_read_only() was also false.
+        debug_assert!(!self.is_read_only());
+        self.reader
+            .set(TieredStorageReader::new_from_path(&self.path)?)
+            .unwrap();
+
+        Ok(stored_accounts_info)
     }
 
     /// Returns the underlying reader of the TieredStorage.  None will be
@@ -170,6 +198,7 @@ impl TieredStorage {
 mod tests {
     use {
         super::*,
+        cold::COLD_FORMAT,
         file::TieredStorageMagicNumber,
         footer::TieredStorageFooter,
         hot::HOT_FORMAT,
@@ -337,7 +366,6 @@ mod tests {
     }
 
     /// The helper function for all write_accounts tests.
-    /// Currently only supports hot accounts.
     fn do_test_write_accounts(
         path_suffix: &str,
         account_data_sizes: &[u64],
@@ -358,7 +386,8 @@ mod tests {
 
         let temp_dir = tempdir().unwrap();
         let tiered_storage_path = temp_dir.path().join(path_suffix);
-        let tiered_storage = TieredStorage::new_writable(tiered_storage_path);
+        let tiered_storage =
+            TieredStorage::new_writable_with_format(tiered_storage_path, format.clone());
         _ = tiered_storage.write_accounts(&storable_accounts, 0, &format);
 
         let reader = tiered_storage.reader().unwrap();
@@ -434,4 +463,68 @@ mod tests {
             HOT_FORMAT.clone(),
         );
     }
+
+    #[test]
+    fn test_write_accounts_small_accounts_cold() {
+        do_test_write_accounts(
+            "test_write_accounts_small_accounts_cold",
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+            COLD_FORMAT.clone(),
+        );
+    }
+
+    #[test]
+    fn test_write_accounts_one_max_len_cold() {
+        do_test_write_accounts(
+            "test_write_accounts_one_max_len_cold",
+            &[MAX_PERMITTED_DATA_LENGTH],
+            COLD_FORMAT.clone(),
+        );
+    }
+
+    #[test]
+    fn test_write_accounts_mixed_size_cold() {
+        do_test_write_accounts(
+            "test_write_accounts_mixed_size_cold",
+            &[
+                1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 1000, 2000, 3000, 4000, 9, 8, 7, 6, 5, 4, 3, 2, 1,
+            ],
+            COLD_FORMAT.clone(),
+        );
+    }
+
+    #[test]
+    fn test_new_readonly_cold() {
+        let temp_dir = tempdir().unwrap();
+        let tiered_storage_path = temp_dir.path().join("test_new_readonly_cold");
+        let accounts: Vec<_> = (1..10).map(create_test_account).collect();
+        let account_refs: Vec<_> = accounts
+            .iter()
+            .map(|account| (&account.0.pubkey, &account.1))
+            .collect();
+
+        {
+            let tiered_storage = ManuallyDrop::new(TieredStorage::new_writable_with_format(
+                &tiered_storage_path,
+                COLD_FORMAT,
+            ));
+            assert_eq!(tiered_storage.format(), &COLD_FORMAT);
+            tiered_storage
+                .write_accounts(&(Slot::MAX, &account_refs[..]), 0, &COLD_FORMAT)
+                .unwrap();
+        }
+
+        let tiered_storage_readonly = TieredStorage::new_readonly(&tiered_storage_path).unwrap();
+        let footer = tiered_storage_readonly.footer().unwrap();
+        assert_eq!(tiered_storage_readonly.format(), &COLD_FORMAT);
+        assert_eq!(footer.account_meta_format, COLD_FORMAT.account_meta_format);
+        assert_eq!(
+            footer.account_block_format,
+            COLD_FORMAT.account_block_format
+        );
+        assert_eq!(
+            tiered_storage_readonly.reader().unwrap().num_accounts(),
+            accounts.len()
+        );
+    }
 }
@@ -0,0 +1,1097 @@
+//! The account meta and related structs for cold accounts.
+//!
+//! Unlike hot accounts, cold accounts are grouped into account blocks, and
+//! each account block is compressed independently.  This trades read latency
+//! for a smaller storage footprint, which fits ancient storages whose
+//! accounts are rarely accessed.
+
+use {
+    crate::{
+        account_info::AccountInfo,
+        account_storage::meta::StoredAccountMeta,
+        accounts_file::{MatchAccountOwnerError, StoredAccountsInfo},
+        append_vec::{IndexInfo, IndexInfoInner},
+        tiered_storage::{
+            byte_block::{ByteBlockReader, ByteBlockWriter},
+            file::{TieredReadableFile, TieredWritableFile},
+            footer::{AccountBlockFormat, AccountMetaFormat, TieredStorageFooter},
+            hot::HotAccount,
+            index::{AccountIndexWriterEntry, AccountOffset, IndexBlockFormat, IndexOffset},
+            meta::{
+                AccountAddressRange, AccountMetaFlags, AccountMetaOptionalFields, TieredAccountMeta,
+            },
+            mmap_utils::{get_pod, get_slice},
+            owners::{OwnerOffset, OwnersBlockFormat, OwnersTable},
+            StorableAccounts, TieredStorageError, TieredStorageFormat, TieredStorageResult,
+        },
+    },
+    bytemuck_derive::{Pod, Zeroable},
+    memmap2::{Mmap, MmapOptions},
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount, WritableAccount},
+        pubkey::Pubkey,
+        rent_collector::RENT_EXEMPT_RENT_EPOCH,
+        stake_history::Epoch,
+    },
+    std::{io::Write, path::Path},
+};
+
+pub const COLD_FORMAT: TieredStorageFormat = TieredStorageFormat {
+    meta_entry_size: std::mem::size_of::<ColdAccountMeta>(),
+    account_meta_format: AccountMetaFormat::Cold,
+    owners_block_format: OwnersBlockFormat::AddressesOnly,
+    index_block_format: IndexBlockFormat::AddressesThenOffsets,
+    account_block_format: AccountBlockFormat::Lz4,
+};
+
+/// The default size of a cold account block before compression.
+///
+/// Accounts are packed into the same account block until the next account
+/// no longer fits.  An account that alone exceeds this size is stored in its
+/// own account block (i.e. a blob account).
+pub const COLD_ACCOUNT_BLOCK_SIZE: u64 = 16 * 1024;
+
+/// An helper function that creates a new default footer for cold
+/// accounts storage.
+fn new_cold_footer() -> TieredStorageFooter {
+    TieredStorageFooter {
+        account_meta_format: COLD_FORMAT.account_meta_format,
+        account_meta_entry_size: COLD_FORMAT.meta_entry_size as u32,
+        account_block_format: COLD_FORMAT.account_block_format,
+        account_block_size: COLD_ACCOUNT_BLOCK_SIZE,
+        index_block_format: COLD_FORMAT.index_block_format,
+        owners_block_format: COLD_FORMAT.owners_block_format,
+        ..TieredStorageFooter::default()
+    }
+}
+
+/// The alignment for the blocks inside a cold accounts file.  A cold accounts
+/// file consists of encoded account blocks, index block, owners block, and
+/// footer.  Aligning each block allows its header to be read under mmap and
+/// allows ColdAccountOffset to address more bytes.
+pub(crate) const COLD_BLOCK_ALIGNMENT: usize = 8;
+
+/// The maximum supported offset to an account block in a cold accounts file.
+const MAX_COLD_BLOCK_OFFSET: usize = u32::MAX as usize * COLD_BLOCK_ALIGNMENT;
+
+/// The buffer that is used for padding.
+const PADDING_BUFFER: [u8; COLD_BLOCK_ALIGNMENT] = [0u8; COLD_BLOCK_ALIGNMENT];
+
+// returns the required number of padding
+fn padding_bytes(len: usize) -> usize {
+    (COLD_BLOCK_ALIGNMENT - (len % COLD_BLOCK_ALIGNMENT)) % COLD_BLOCK_ALIGNMENT
+}
+
+/// The header that precedes each encoded account block.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Pod, Zeroable)]
+struct ColdBlockHeader {
+    /// The size of the encoded account block that follows this header.
+    encoded_len: u32,
+    /// The size of the account block after decoding.
+    decoded_len: u32,
+}
+
+// Ensure there are no implicit padding bytes
+const _: () = assert!(std::mem::size_of::<ColdBlockHeader>() == 4 + 4);
+
+/// The offset to access a cold account.
+///
+/// As multiple cold accounts share one account block, the offset consists of
+/// the offset to its account block and the offset to its account meta inside
+/// the decoded account block.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Pod, Zeroable)]
+pub struct ColdAccountOffset {
+    /// The offset to the account block in units of COLD_BLOCK_ALIGNMENT.
+    block_offset: u32,
+    /// The offset to the account meta inside the decoded account block.
+    intra_block_offset: u32,
+}
+
+// Ensure there are no implicit padding bytes
+const _: () = assert!(std::mem::size_of::<ColdAccountOffset>() == 4 + 4);
+
+impl AccountOffset for ColdAccountOffset {}
+
+impl ColdAccountOffset {
+    /// Creates a new ColdAccountOffset instance
+    pub fn new(block_offset: usize, intra_block_offset: usize) -> TieredStorageResult<Self> {
+        if block_offset > MAX_COLD_BLOCK_OFFSET {
+            return Err(TieredStorageError::OffsetOutOfBounds(
+                block_offset,
+                MAX_COLD_BLOCK_OFFSET,
+            ));
+        }
+
+        // Account blocks are aligned based on COLD_BLOCK_ALIGNMENT.
+        if block_offset % COLD_BLOCK_ALIGNMENT != 0 {
+            return Err(TieredStorageError::OffsetAlignmentError(
+                block_offset,
+                COLD_BLOCK_ALIGNMENT,
+            ));
+        }
+
+        let intra_block_offset = u32::try_from(intra_block_offset).map_err(|_| {
+            TieredStorageError::OffsetOutOfBounds(intra_block_offset, u32::MAX as usize)
+        })?;
+
+        Ok(Self {
+            block_offset: (block_offset / COLD_BLOCK_ALIGNMENT) as u32,
+            intra_block_offset,
+        })
+    }
+
+    /// Returns the offset to the account block.
+    fn block_offset(&self) -> usize {
+        self.block_offset as usize * COLD_BLOCK_ALIGNMENT
+    }
+
+    /// Returns the offset to the account meta inside the decoded account block.
+    fn intra_block_offset(&self) -> usize {
+        self.intra_block_offset as usize
+    }
+}
+
+/// The storage and in-memory representation of the metadata entry for a
+/// cold account.
+///
+/// As cold account metas live inside compressed account blocks, they are
+/// copied out of the decoded account block instead of being referenced
+/// directly, and hence they do not require any alignment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C)]
+pub struct ColdAccountMeta {
+    /// The balance of this account.
+    lamports: u64,
+    /// The size of the account data.
+    account_data_size: u64,
+    /// The index to the owner of this account inside its AccountsFile.
+    owner_offset: u32,
+    /// Stores boolean flags and existence of each optional field.
+    flags: AccountMetaFlags,
+}
+
+// Ensure there are no implicit padding bytes
+const _: () = assert!(std::mem::size_of::<ColdAccountMeta>() == 8 + 8 + 4 + 4);
+
+impl TieredAccountMeta for ColdAccountMeta {
+    /// Construct a ColdAccountMeta instance.
+    fn new() -> Self {
+        ColdAccountMeta {
+            lamports: 0,
+            account_data_size: 0,
+            owner_offset: 0,
+            flags: AccountMetaFlags::new(),
+        }
+    }
+
+    /// A builder function that initializes lamports.
+    fn with_lamports(mut self, lamports: u64) -> Self {
+        self.lamports = lamports;
+        self
+    }
+
+    /// Cold accounts are packed without any padding inside their account
+    /// block, so the only supported padding is zero.
+    fn with_account_data_padding(self, padding: u8) -> Self {
+        if padding != 0 {
+            panic!("cold accounts do not support account data padding");
+        }
+        self
+    }
+
+    /// A builder function that initializes the owner's index.
+    fn with_owner_offset(mut self, owner_offset: OwnerOffset) -> Self {
+        self.owner_offset = owner_offset.0;
+        self
+    }
+
+    /// A builder function that initializes the account data size.
+    fn with_account_data_size(mut self, account_data_size: u64) -> Self {
+        self.account_data_size = account_data_size;
+        self
+    }
+
+    /// A builder function that initializes the AccountMetaFlags of the current
+    /// meta.
+    fn with_flags(mut self, flags: &AccountMetaFlags) -> Self {
+        self.flags = *flags;
+        self
+    }
+
+    /// Returns the balance of the lamports associated with the account.
+    fn lamports(&self) -> u64 {
+        self.lamports
+    }
+
+    /// Always returns 0 as cold account data is never padded.
+    fn account_data_padding(&self) -> u8 {
+        0
+    }
+
+    /// Returns the index to the accounts' owner in the current AccountsFile.
+    fn owner_offset(&self) -> OwnerOffset {
+        OwnerOffset(self.owner_offset)
+    }
+
+    /// Returns the AccountMetaFlags of the current meta.
+    fn flags(&self) -> &AccountMetaFlags {
+        &self.flags
+    }
+
+    /// Always returns true as multiple cold accounts share the same
+    /// account block.
+    fn supports_shared_account_block() -> bool {
+        true
+    }
+
+    /// Returns the epoch that this account will next owe rent by parsing
+    /// the specified account block.  None will be returned if this account
+    /// does not persist this optional field.
+    fn rent_epoch(&self, account_block: &[u8]) -> Option<Epoch> {
+        self.flags()
+            .has_rent_epoch()
+            .then(|| {
+                let offset = self.optional_fields_offset(account_block)
+                    + AccountMetaOptionalFields::rent_epoch_offset(self.flags());
+                account_block
+                    .get(offset..offset.saturating_add(std::mem::size_of::<Epoch>()))
+                    .map(bytemuck::pod_read_unaligned::<Epoch>)
+            })
+            .flatten()
+    }
+
+    /// Returns the epoch that this account will next owe rent by parsing
+    /// the specified account block.  RENT_EXEMPT_RENT_EPOCH will be returned
+    /// if the account is rent-exempt.
+    ///
+    /// For a zero-lamport account, Epoch::default() will be returned to
+    /// default states of an AccountSharedData.
+    fn final_rent_epoch(&self, account_block: &[u8]) -> Epoch {
+        self.rent_epoch(account_block)
+            .unwrap_or(if self.lamports() != 0 {
+                RENT_EXEMPT_RENT_EPOCH
+            } else {
+                // While there is no valid-values for any fields of a zero
+                // lamport account, here we return Epoch::default() to
+                // match the default states of AccountSharedData.  Otherwise,
+                // a hash mismatch will occur.
+                Epoch::default()
+            })
+    }
+
+    /// Returns the offset of the optional fields based on the specified account
+    /// block.
+    fn optional_fields_offset(&self, _account_block: &[u8]) -> usize {
+        self.account_data_size as usize
+    }
+
+    /// Returns the length of the data associated to this account based on the
+    /// specified account block.
+    fn account_data_size(&self, _account_block: &[u8]) -> usize {
+        self.account_data_size as usize
+    }
+
+    /// Returns the data associated to this account based on the specified
+    /// account block.
+    fn account_data<'a>(&self, account_block: &'a [u8]) -> &'a [u8] {
+        &account_block[..self.account_data_size(account_block)]
+    }
+}
+
+/// The struct that offers read APIs for accessing a cold account.
+pub type ColdAccount<'accounts_file> = HotAccount<'accounts_file, ColdAccountMeta>;
+
+/// Keeps the most recently decoded account block so that accounts sharing
+/// the same account block are decoded only once when visited in order.
+#[derive(Debug, Default)]
+struct DecodedAccountBlock {
+    /// The offset of the decoded account block inside the accounts file.
+    block_offset: Option<usize>,
+    /// The decoded bytes of the account block.
+    bytes: Vec<u8>,
+}
+
+impl DecodedAccountBlock {
+    /// Returns the decoded account block at the specified offset, decoding
+    /// it only if it is not the currently cached account block.
+    fn get_or_decode(
+        &mut self,
+        reader: &ColdStorageReader,
+        block_offset: usize,
+    ) -> TieredStorageResult<&[u8]> {
+        if self.block_offset != Some(block_offset) {
+            self.bytes = reader.decode_account_block(block_offset)?;
+            self.block_offset = Some(block_offset);
+        }
+        Ok(&self.bytes)
+    }
+}
+
+/// The reader to a cold accounts file.
+#[derive(Debug)]
+pub struct ColdStorageReader {
+    mmap: Mmap,
+    footer: TieredStorageFooter,
+}
+
+impl ColdStorageReader {
+    pub fn new(file: TieredReadableFile) -> TieredStorageResult<Self> {
+        let mmap = unsafe { MmapOptions::new().map(&file.0)? };
+        // Here we are copying the footer, as accessing any data in a
+        // TieredStorage instance requires accessing its Footer.
+        let footer = *TieredStorageFooter::new_from_mmap(&mmap)?;
+
+        Ok(Self { mmap, footer })
+    }
+
+    /// Returns the size of the underlying storage.
+    pub fn len(&self) -> usize {
+        self.mmap.len()
+    }
+
+    /// Returns whether the underlying storage is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.len() as u64
+    }
+
+    /// Returns the footer of the underlying tiered-storage accounts file.
+    pub fn footer(&self) -> &TieredStorageFooter {
+        &self.footer
+    }
+
+    /// Returns the number of accounts inside the underlying tiered-storage
+    /// accounts file.
+    pub fn num_accounts(&self) -> usize {
+        self.footer.account_entry_count as usize
+    }
+
+    /// Returns the offset to the account given the specified index.
+    fn get_account_offset(
+        &self,
+        index_offset: IndexOffset,
+    ) -> TieredStorageResult<ColdAccountOffset> {
+        self.footer
+            .index_block_format
+            .get_account_offset::<ColdAccountOffset>(&self.mmap, &self.footer, index_offset)
+    }
+
+    /// Returns the address of the account associated with the specified index.
+    fn get_account_address(&self, index: IndexOffset) -> TieredStorageResult<&Pubkey> {
+        self.footer
+            .index_block_format
+            .get_account_address(&self.mmap, &self.footer, index)
+    }
+
+    /// Returns the address of the account owner given the specified
+    /// owner_offset.
+    fn get_owner_address(&self, owner_offset: OwnerOffset) -> TieredStorageResult<&Pubkey> {
+        self.footer
+            .owners_block_format
+            .get_owner_address(&self.mmap, &self.footer, owner_offset)
+    }
+
+    /// Decodes the account block located at the specified offset.
+    fn decode_account_block(&self, block_offset: usize) -> TieredStorageResult<Vec<u8>> {
+        assert!(
+            block_offset.saturating_add(std::mem::size_of::<ColdBlockHeader>())
+                <= self.footer.index_block_offset as usize,
+            "reading account block ({}) would exceed accounts blocks offset boundary ({}).",
+            block_offset,
+            self.footer.index_block_offset,
+        );
+        let (header, _) = get_pod::<ColdBlockHeader>(&self.mmap, block_offset)?;
+        let (encoded_block, _) = get_slice(
+            &self.mmap,
+            block_offset + std::mem::size_of::<ColdBlockHeader>(),
+            header.encoded_len as usize,
+        )?;
+
+        let decoded_block =
+            ByteBlockReader::decode(self.footer.account_block_format, encoded_block)?;
+        if decoded_block.len() != header.decoded_len as usize {
+            return Err(TieredStorageError::AccountBlockSizeMismatch(
+                block_offset,
+                decoded_block.len(),
+                header.decoded_len as usize,
+            ));
+        }
+
+        Ok(decoded_block)
+    }
+
+    /// Returns the meta of the account located at the specified offset
+    /// inside the decoded account block, along with the account's own
+    /// part of the account block (i.e. account data and optional fields).
+    fn get_account_from_block(
+        decoded_block: &[u8],
+        intra_block_offset: usize,
+    ) -> TieredStorageResult<(ColdAccountMeta, &[u8])> {
+        let meta_end = intra_block_offset.saturating_add(std::mem::size_of::<ColdAccountMeta>());
+        let meta_bytes = decoded_block.get(intra_block_offset..meta_end).ok_or(
+            TieredStorageError::OffsetOutOfBounds(meta_end, decoded_block.len()),
+        )?;
+        let meta = bytemuck::pod_read_unaligned::<ColdAccountMeta>(meta_bytes);
+
+        let account_block_end = meta_end
+            .saturating_add(meta.account_data_size as usize)
+            .saturating_add(AccountMetaOptionalFields::size_from_flags(meta.flags()));
+        let account_block = decoded_block.get(meta_end..account_block_end).ok_or(
+            TieredStorageError::OffsetOutOfBounds(account_block_end, decoded_block.len()),
+        )?;
+
+        Ok((meta, account_block))
+    }
+
+    /// Calls `callback` with the account located at the specified index
+    /// offset.  `decoded_block` is reused across calls so that accounts
+    /// sharing the same account block are decoded only once.
+    fn with_account<Ret>(
+        &self,
+        index_offset: IndexOffset,
+        decoded_block: &mut DecodedAccountBlock,
+        callback: impl for<'local> FnOnce(ColdAccount<'local>) -> Ret,
+    ) -> TieredStorageResult<Ret> {
+        let account_offset = self.get_account_offset(index_offset)?;
+        let block = decoded_block.get_or_decode(self, account_offset.block_offset())?;
+        let (meta, account_block) =
+            Self::get_account_from_block(block, account_offset.intra_block_offset())?;
+        let address = self.get_account_address(index_offset)?;
+        let owner = self.get_owner_address(meta.owner_offset())?;
+
+        Ok(callback(HotAccount {
+            meta: &meta,
+            address,
+            owner,
+            index: index_offset,
+            account_block,
+        }))
+    }
+
+    /// Returns Ok(index_of_matching_owner) if the account owner at
+    /// `index_offset` is one of the pubkeys in `owners`.
+    ///
+    /// Returns Err(MatchAccountOwnerError::NoMatch) if the account has 0
+    /// lamports or the owner is not one of the pubkeys in `owners`.
+    ///
+    /// Returns Err(MatchAccountOwnerError::UnableToLoad) if there is any internal
+    /// error that causes the data unable to load, including `index_offset`
+    /// causes a data overrun.
+    pub fn account_matches_owners(
+        &self,
+        index_offset: IndexOffset,
+        owners: &[Pubkey],
+    ) -> Result<usize, MatchAccountOwnerError> {
+        let account_offset = self
+            .get_account_offset(index_offset)
+            .map_err(|_| MatchAccountOwnerError::UnableToLoad)?;
+        let decoded_block = self
+            .decode_account_block(account_offset.block_offset())
+            .map_err(|_| MatchAccountOwnerError::UnableToLoad)?;
+        let (account_meta, _) =
+            Self::get_account_from_block(&decoded_block, account_offset.intra_block_offset())
+                .map_err(|_| MatchAccountOwnerError::UnableToLoad)?;
+
+        if account_meta.lamports() == 0 {
+            Err(MatchAccountOwnerError::NoMatch)
+        } else {
+            let account_owner = self
+                .get_owner_address(account_meta.owner_offset())
+                .map_err(|_| MatchAccountOwnerError::UnableToLoad)?;
+
+            owners
+                .iter()
+                .position(|candidate| account_owner == candidate)
+                .ok_or(MatchAccountOwnerError::NoMatch)
+        }
+    }
+
+    /// calls `callback` with the account located at the specified index offset.
+    pub fn get_stored_account_meta_callback<Ret>(
+        &self,
+        index_offset: IndexOffset,
+        mut callback: impl for<'local> FnMut(StoredAccountMeta<'local>) -> Ret,
+    ) -> TieredStorageResult<Option<Ret>> {
+        if index_offset.0 >= self.footer.account_entry_count {
+            return Ok(None);
+        }
+
+        self.with_account(
+            index_offset,
+            &mut DecodedAccountBlock::default(),
+            |account| callback(StoredAccountMeta::Cold(account)),
+        )
+        .map(Some)
+    }
+
+    /// Returns the account located at the specified index offset.
+    pub fn get_account_shared_data(
+        &self,
+        index_offset: IndexOffset,
+    ) -> TieredStorageResult<Option<AccountSharedData>> {
+        if index_offset.0 >= self.footer.account_entry_count {
+            return Ok(None);
+        }
+
+        self.with_account(
+            index_offset,
+            &mut DecodedAccountBlock::default(),
+            |account| {
+                AccountSharedData::create(
+                    account.lamports(),
+                    account.data().to_vec(),
+                    *account.owner(),
+                    account.executable(),
+                    account.rent_epoch(),
+                )
+            },
+        )
+        .map(Some)
+    }
+
+    /// iterate over all pubkeys
+    pub fn scan_pubkeys(&self, mut callback: impl FnMut(&Pubkey)) -> TieredStorageResult<()> {
+        for i in 0..self.footer.account_entry_count {
+            let address = self.get_account_address(IndexOffset(i))?;
+            callback(address);
+        }
+        Ok(())
+    }
+
+    /// for each offset in `sorted_offsets`, return the account size
+    pub(crate) fn get_account_sizes(
+        &self,
+        sorted_offsets: &[usize],
+    ) -> TieredStorageResult<Vec<usize>> {
+        let mut decoded_block = DecodedAccountBlock::default();
+        sorted_offsets
+            .iter()
+            .map(|&offset| {
+                let index_offset = IndexOffset(AccountInfo::get_reduced_offset(offset));
+                self.with_account(index_offset, &mut decoded_block, |account| {
+                    account.stored_size()
+                })
+            })
+            .collect()
+    }
+
+    /// Iterate over all accounts and call `callback` with each account.
+    pub(crate) fn scan_accounts(
+        &self,
+        mut callback: impl for<'local> FnMut(StoredAccountMeta<'local>),
+    ) -> TieredStorageResult<()> {
+        let mut decoded_block = DecodedAccountBlock::default();
+        for i in 0..self.footer.account_entry_count {
+            self.with_account(IndexOffset(i), &mut decoded_block, |account| {
+                callback(StoredAccountMeta::Cold(account))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// iterate over all entries to put in index
+    pub(crate) fn scan_index(
+        &self,
+        mut callback: impl FnMut(IndexInfo),
+    ) -> TieredStorageResult<()> {
+        let mut decoded_block = DecodedAccountBlock::default();
+        for i in 0..self.footer.account_entry_count {
+            let index_info =
+                self.with_account(IndexOffset(i), &mut decoded_block, |account| IndexInfo {
+                    index_info: {
+                        IndexInfoInner {
+                            pubkey: *account.address(),
+                            lamports: account.lamports(),
+                            offset: AccountInfo::reduced_offset_to_offset(i),
+                            data_len: account.data().len() as u64,
+                            executable: account.executable(),
+                            rent_epoch: account.rent_epoch(),
+                        }
+                    },
+                    stored_size_aligned: account.stored_size(),
+                })?;
+            callback(index_info);
+        }
+        Ok(())
+    }
+
+    /// Returns a slice suitable for use when archiving cold storages
+    pub fn data_for_archive(&self) -> &[u8] {
+        self.mmap.as_ref()
+    }
+}
+
+/// The writer that creates a cold accounts file.
+#[derive(Debug)]
+pub struct ColdStorageWriter {
+    storage: TieredWritableFile,
+}
+
+impl ColdStorageWriter {
+    /// Create a new ColdStorageWriter with the specified path.
+    pub fn new(file_path: impl AsRef<Path>) -> TieredStorageResult<Self> {
+        Ok(Self {
+            storage: TieredWritableFile::new(file_path)?,
+        })
+    }
+
+    /// Encodes and persists the specified account block, including its
+    /// header and the padding that aligns the next block, and returns the
+    /// number of bytes written.
+    fn write_account_block(&mut self, block_writer: ByteBlockWriter) -> TieredStorageResult<usize> {
+        let decoded_len = block_writer.raw_len();
+        let encoded_block = block_writer.finish()?;
+        let header = ColdBlockHeader {
+            encoded_len: u32::try_from(encoded_block.len()).map_err(|_| {
+                TieredStorageError::OffsetOutOfBounds(encoded_block.len(), u32::MAX as usize)
+            })?,
+            decoded_len: u32::try_from(decoded_len).map_err(|_| {
+                TieredStorageError::OffsetOutOfBounds(decoded_len, u32::MAX as usize)
+            })?,
+        };
+
+        let mut bytes_written = 0;
+        bytes_written += self.storage.write_pod(&header)?;
+        bytes_written += self.storage.write_bytes(&encoded_block)?;
+        bytes_written += self
+            .storage
+            .write_bytes(&PADDING_BUFFER[..padding_bytes(bytes_written)])?;
+
+        Ok(bytes_written)
+    }
+
+    /// Persists `accounts` into the underlying cold accounts file associated
+    /// with this ColdStorageWriter.  The first `skip` number of accounts are
+    /// *not* persisted.
+    pub fn write_accounts<'a>(
+        &mut self,
+        accounts: &impl StorableAccounts<'a>,
+        skip: usize,
+    ) -> TieredStorageResult<StoredAccountsInfo> {
+        let mut footer = new_cold_footer();
+        let mut index = vec![];
+        let mut owners_table = OwnersTable::default();
+        let mut cursor = 0;
+        let mut address_range = AccountAddressRange::default();
+        let mut block_writer = ByteBlockWriter::new(footer.account_block_format);
+
+        let len = accounts.len();
+        let total_input_accounts = len.saturating_sub(skip);
+        let mut offsets = Vec::with_capacity(total_input_accounts);
+
+        // writing accounts blocks
+        for i in skip..len {
+            accounts.account_default_if_zero_lamport::<TieredStorageResult<()>>(i, |account| {
+                let optional_fields = AccountMetaOptionalFields {
+                    // only persist rent_epoch for those rent-paying accounts
+                    rent_epoch: (account.rent_epoch() != RENT_EXEMPT_RENT_EPOCH)
+                        .then_some(account.rent_epoch()),
+                };
+                let entry_size = std::mem::size_of::<ColdAccountMeta>()
+                    + account.data().len()
+                    + optional_fields.size();
+
+                // Start a new account block if the current one cannot hold
+                // this account.  An account that alone exceeds the account
+                // block size ends up in its own account block.
+                if block_writer.raw_len() > 0
+                    && block_writer.raw_len() + entry_size > footer.account_block_size as usize
+                {
+                    let full_block_writer = std::mem::replace(
+                        &mut block_writer,
+                        ByteBlockWriter::new(footer.account_block_format),
+                    );
+                    cursor += self.write_account_block(full_block_writer)?;
+                }
+
+                let index_entry = AccountIndexWriterEntry {
+                    address: *account.pubkey(),
+                    offset: ColdAccountOffset::new(cursor, block_writer.raw_len())?,
+                };
+                address_range.update(account.pubkey());
+
+                let mut flags = AccountMetaFlags::new_from(&optional_fields);
+                flags.set_executable(account.executable());
+                let meta = ColdAccountMeta::new()
+                    .with_lamports(account.lamports())
+                    .with_owner_offset(owners_table.insert(account.owner()))
+                    .with_account_data_size(account.data().len() as u64)
+                    .with_flags(&flags);
+
+                block_writer.write_pod(&meta)?;
+                block_writer.write(account.data())?;
+                block_writer.write_optional_fields(&optional_fields)?;
+
+                offsets.push(index.len());
+                index.push(index_entry);
+                Ok(())
+            })?;
+        }
+        if block_writer.raw_len() > 0 {
+            cursor += self.write_account_block(block_writer)?;
+        }
+        footer.account_entry_count = total_input_accounts as u32;
+
+        // writing index block
+        // expect the offset of each block aligned.
+        assert!(cursor % COLD_BLOCK_ALIGNMENT == 0);
+        footer.index_block_offset = cursor as u64;
+        cursor += footer
+            .index_block_format
+            .write_index_block(&mut self.storage, &index)?;
+
+        // writing owners block
+        // each index entry is a multiple of COLD_BLOCK_ALIGNMENT bytes.
+        assert!(cursor % COLD_BLOCK_ALIGNMENT == 0);
+        footer.owners_block_offset = cursor as u64;
+        footer.owner_count = owners_table.len() as u32;
+        cursor += footer
+            .owners_block_format
+            .write_owners_block(&mut self.storage, &owners_table)?;
+
+        // writing footer
+        footer.min_account_address = address_range.min;
+        footer.max_account_address = address_range.max;
+        cursor += footer.write_footer_block(&mut self.storage)?;
+
+        Ok(StoredAccountsInfo {
+            offsets,
+            size: cursor,
+        })
+    }
+
+    /// Flushes any buffered data to the file
+    pub fn flush(&mut self) -> TieredStorageResult<()> {
+        self.storage
+            .0
+            .flush()
+            .map_err(TieredStorageError::FlushColdWriter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::tiered_storage::{
+            file::TieredStorageMagicNumber,
+            test_utils::{create_test_account, verify_test_account},
+        },
+        assert_matches::assert_matches,
+        memoffset::offset_of,
+        solana_sdk::slot_history::Slot,
+        std::collections::HashSet,
+        tempfile::TempDir,
+    };
+
+    #[test]
+    fn test_cold_account_meta_layout() {
+        assert_eq!(offset_of!(ColdAccountMeta, lamports), 0x00);
+        assert_eq!(offset_of!(ColdAccountMeta, account_data_size), 0x08);
+        assert_eq!(offset_of!(ColdAccountMeta, owner_offset), 0x10);
+        assert_eq!(offset_of!(ColdAccountMeta, flags), 0x14);
+    }
+
+    #[test]
+    fn test_cold_account_meta() {
+        const TEST_LAMPORTS: u64 = 2314232137;
+        const TEST_OWNER_OFFSET: u32 = 0x1fef_1234;
+        const TEST_RENT_EPOCH: Epoch = 7;
+        const TEST_DATA: &[u8] = &[0x11, 0x22, 0x33, 0x44, 0x55];
+
+        let optional_fields = AccountMetaOptionalFields {
+            rent_epoch: Some(TEST_RENT_EPOCH),
+        };
+        let flags = AccountMetaFlags::new_from(&optional_fields);
+        let meta = ColdAccountMeta::new()
+            .with_lamports(TEST_LAMPORTS)
+            .with_owner_offset(OwnerOffset(TEST_OWNER_OFFSET))
+            .with_account_data_size(TEST_DATA.len() as u64)
+            .with_flags(&flags);
+
+        let mut account_block = TEST_DATA.to_vec();
+        account_block.extend_from_slice(bytemuck::bytes_of(&TEST_RENT_EPOCH));
+
+        assert_eq!(meta.lamports(), TEST_LAMPORTS);
+        assert_eq!(meta.owner_offset(), OwnerOffset(TEST_OWNER_OFFSET));
+        assert_eq!(*meta.flags(), flags);
+        assert_eq!(meta.account_data_padding(), 0);
+        assert_eq!(meta.account_data_size(&account_block), TEST_DATA.len());
+        assert_eq!(meta.account_data(&account_block), TEST_DATA);
+        assert_eq!(meta.rent_epoch(&account_block), Some(TEST_RENT_EPOCH));
+        assert_eq!(meta.final_rent_epoch(&account_block), TEST_RENT_EPOCH);
+        assert!(ColdAccountMeta::supports_shared_account_block());
+    }
+
+    #[test]
+    #[should_panic(expected = "cold accounts do not support account data padding")]
+    fn test_cold_meta_padding_not_supported() {
+        ColdAccountMeta::new().with_account_data_padding(1);
+    }
+
+    #[test]
+    fn test_cold_account_offset() {
+        let offset = ColdAccountOffset::new(8 * 1024, 77).unwrap();
+        assert_eq!(offset.block_offset(), 8 * 1024);
+        assert_eq!(offset.intra_block_offset(), 77);
+
+        let offset = ColdAccountOffset::new(MAX_COLD_BLOCK_OFFSET, u32::MAX as usize).unwrap();
+        assert_eq!(offset.block_offset(), MAX_COLD_BLOCK_OFFSET);
+        assert_eq!(offset.intra_block_offset(), u32::MAX as usize);
+
+        assert_matches!(
+            ColdAccountOffset::new(MAX_COLD_BLOCK_OFFSET + COLD_BLOCK_ALIGNMENT, 0),
+            Err(TieredStorageError::OffsetOutOfBounds(_, _))
+        );
+        assert_matches!(
+            ColdAccountOffset::new(COLD_BLOCK_ALIGNMENT - 1, 0),
+            Err(TieredStorageError::OffsetAlignmentError(_, _))
+        );
+        assert_matches!(
+            ColdAccountOffset::new(0, u32::MAX as usize + 1),
+            Err(TieredStorageError::OffsetOutOfBounds(_, _))
+        );
+    }
+
+    #[test]
+    fn test_cold_storage_writer_twice_on_same_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir
+            .path()
+            .join("test_cold_storage_writer_twice_on_same_path");
+
+        // Expect the first returns Ok
+        assert_matches!(ColdStorageWriter::new(&path), Ok(_));
+        // Expect the second call on the same path returns Err, as the
+        // ColdStorageWriter only writes once.
+        assert_matches!(ColdStorageWriter::new(&path), Err(_));
+    }
+
+    #[test]
+    fn test_write_and_read_cold_storage() {
+        // includes one account that exceeds COLD_ACCOUNT_BLOCK_SIZE, which
+        // must end up in its own account block.
+        let account_data_sizes = &[
+            1,
+            2,
+            3,
+            4,
+            5,
+            6,
+            7,
+            8,
+            9,
+            10,
+            1000,
+            2000,
+            3000,
+            4000,
+            COLD_ACCOUNT_BLOCK_SIZE * 2,
+            9,
+            8,
+            7,
+            6,
+            5,
+            4,
+            3,
+            2,
+            1,
+            0,
+        ];
+
+        let accounts: Vec<_> = account_data_sizes
+            .iter()
+            .map(|size| create_test_account(*size))
+            .collect();
+
+        let account_refs: Vec<_> = accounts
+            .iter()
+            .map(|account| (&account.0.pubkey, &account.1))
+            .collect();
+
+        // Slot information is not used here
+        let storable_accounts = (Slot::MAX, &account_refs[..]);
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("test_write_and_read_cold_storage");
+        let stored_accounts_info = {
+            let mut writer = ColdStorageWriter::new(&path).unwrap();
+            let stored_accounts_info = writer.write_accounts(&storable_accounts, 0).unwrap();
+            writer.flush().unwrap();
+            stored_accounts_info
+        };
+
+        let file = TieredReadableFile::new(&path).unwrap();
+        let cold_storage = ColdStorageReader::new(file).unwrap();
+        let num_accounts = account_data_sizes.len();
+        assert_eq!(cold_storage.num_accounts(), num_accounts);
+        assert_eq!(stored_accounts_info.size, cold_storage.len());
+
+        // accounts share account blocks, except for the oversized one.
+        let block_offsets: HashSet<_> = (0..num_accounts)
+            .map(|i| {
+                cold_storage
+                    .get_account_offset(IndexOffset(i as u32))
+                    .unwrap()
+                    .block_offset()
+            })
+            .collect();
+        assert!(block_offsets.len() > 1);
+        assert!(block_offsets.len() < num_accounts);
+
+        for offset in stored_accounts_info.offsets {
+            cold_storage
+                .get_stored_account_meta_callback(
+                    IndexOffset(offset as u32),
+                    |stored_account_meta| {
+                        storable_accounts.account_default_if_zero_lamport(offset, |account| {
+                            verify_test_account(
+                                &stored_account_meta,
+                                &account.to_account_shared_data(),
+                                account.pubkey(),
+                            );
+                        });
+                    },
+                )
+                .unwrap()
+                .unwrap();
+
+            let account_shared_data = cold_storage
+                .get_account_shared_data(IndexOffset(offset as u32))
+                .unwrap()
+                .unwrap();
+            storable_accounts.account_default_if_zero_lamport(offset, |account| {
+                assert_eq!(account_shared_data, account.to_account_shared_data());
+            });
+        }
+        // Make sure it returns None on num_accounts to allow termination on
+        // while loop in actual accounts-db read case.
+        assert_matches!(
+            cold_storage.get_stored_account_meta_callback(IndexOffset(num_accounts as u32), |_| {
+                panic!("unexpected");
+            }),
+            Ok(None)
+        );
+        assert_matches!(
+            cold_storage.get_account_shared_data(IndexOffset(num_accounts as u32)),
+            Ok(None)
+        );
+
+        // verify everything
+        let mut i = 0;
+        cold_storage
+            .scan_accounts(|stored_meta| {
+                storable_accounts.account_default_if_zero_lamport(i, |account| {
+                    verify_test_account(
+                        &stored_meta,
+                        &account.to_account_shared_data(),
+                        account.pubkey(),
+                    );
+                });
+                i += 1;
+            })
+            .unwrap();
+        assert_eq!(i, num_accounts);
+
+        let mut i = 0;
+        cold_storage
+            .scan_index(|index_info| {
+                storable_accounts.account_default_if_zero_lamport(i, |account| {
+                    assert_eq!(index_info.index_info.pubkey, *account.pubkey());
+                    assert_eq!(index_info.index_info.lamports, account.lamports());
+                    assert_eq!(index_info.index_info.data_len, account.data().len() as u64);
+                    assert_eq!(index_info.index_info.executable, account.executable());
+                });
+                i += 1;
+            })
+            .unwrap();
+        assert_eq!(i, num_accounts);
+
+        let footer = cold_storage.footer();
+        let expected_size = footer.owners_block_offset as usize
+            + std::mem::size_of::<Pubkey>() * footer.owner_count as usize
+            + std::mem::size_of::<TieredStorageFooter>()
+            + std::mem::size_of::<TieredStorageMagicNumber>();
+        assert!(!cold_storage.is_empty());
+        assert_eq!(expected_size, cold_storage.len());
+    }
+
+    #[test]
+    fn test_cold_storage_account_matches_owners() {
+        let accounts: Vec<_> = (0..10).map(create_test_account).collect();
+        let account_refs: Vec<_> = accounts
+            .iter()
+            .map(|account| (&account.0.pubkey, &account.1))
+            .collect();
+        let storable_accounts = (Slot::MAX, &account_refs[..]);
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir
+            .path()
+            .join("test_cold_storage_account_matches_owners");
+        {
+            let mut writer = ColdStorageWriter::new(&path).unwrap();
+            writer.write_accounts(&storable_accounts, 0).unwrap();
+            writer.flush().unwrap();
+        }
+
+        let file = TieredReadableFile::new(&path).unwrap();
+        let cold_storage = ColdStorageReader::new(file).unwrap();
+        let owners: Vec<_> = accounts.iter().map(|account| *account.1.owner()).collect();
+        let unknown_owners = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        for (i, (_, account)) in accounts.iter().enumerate() {
+            let index_offset = IndexOffset(i as u32);
+            if account.lamports() == 0 {
+                assert_eq!(
+                    cold_storage.account_matches_owners(index_offset, &owners),
+                    Err(MatchAccountOwnerError::NoMatch)
+                );
+            } else {
+                assert_eq!(
+                    cold_storage.account_matches_owners(index_offset, &owners),
+                    Ok(i)
+                );
+            }
+            assert_eq!(
+                cold_storage.account_matches_owners(index_offset, &unknown_owners),
+                Err(MatchAccountOwnerError::NoMatch)
+            );
+        }
+    }
+
+    #[test]
+    fn test_cold_storage_compresses_account_blocks() {
+        const NUM_ACCOUNTS: u64 = 100;
+        const DATA_LEN: u64 = 1000;
+        let accounts: Vec<_> = (0..NUM_ACCOUNTS)
+            .map(|_| create_test_account(DATA_LEN))
+            .collect();
+        let account_refs: Vec<_> = accounts
+            .iter()
+            .map(|account| (&account.0.pubkey, &account.1))
+            .collect();
+        let storable_accounts = (Slot::MAX, &account_refs[..]);
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir
+            .path()
+            .join("test_cold_storage_compresses_account_blocks");
+        {
+            let mut writer = ColdStorageWriter::new(&path).unwrap();
+            writer.write_accounts(&storable_accounts, 0).unwrap();
+            writer.flush().unwrap();
+        }
+
+        let file = TieredReadableFile::new(&path).unwrap();
+        let cold_storage = ColdStorageReader::new(file).unwrap();
+        // the test accounts have highly compressible data, so the account
+        // blocks must be smaller than the raw account data.
+        assert!(cold_storage.footer().index_block_offset < NUM_ACCOUNTS * DATA_LEN);
+    }
+}
@@ -34,4 +34,13 @@ pub enum TieredStorageError {
 
     #[error("failed to flush hot storage writer: {0}")]
     FlushHotWriter(#[source] std::io::Error),
+
+    #[error("failed to flush cold storage writer: {0}")]
+    FlushColdWriter(#[source] std::io::Error),
+
+    #[error("LenMismatch: file {0} is {1} bytes, expected {2} bytes")]
+    LenMismatch(PathBuf, usize, usize),
+
+    #[error("AccountBlockSizeMismatch: account block at offset {0} decoded into {1} bytes, expected {2}")]
+    AccountBlockSizeMismatch(usize, usize, usize),
 }
@@ -4,6 +4,7 @@ use {
         accounts_file::MatchAccountOwnerError,
         append_vec::IndexInfo,
         tiered_storage::{
+            cold::ColdStorageReader,
             file::TieredReadableFile,
             footer::{AccountMetaFormat, TieredStorageFooter},
             hot::HotStorageReader,
@@ -19,6 +20,7 @@ use {
 #[derive(Debug)]
 pub enum TieredStorageReader {
     Hot(HotStorageReader),
+    Cold(ColdStorageReader),
 }
 
 impl TieredStorageReader {
@@ -28,6 +30,7 @@ impl TieredStorageReader {
         let footer = TieredStorageFooter::new_from_footer_block(&file)?;
         match footer.account_meta_format {
             AccountMetaFormat::Hot => Ok(Self::Hot(HotStorageReader::new(file)?)),
+            AccountMetaFormat::Cold => Ok(Self::Cold(ColdStorageReader::new(file)?)),
         }
     }
 
@@ -35,6 +38,7 @@ impl TieredStorageReader {
     pub fn len(&self) -> usize {
         match self {
             Self::Hot(hot) => hot.len(),
+            Self::Cold(cold) => cold.len(),
         }
     }
 
@@ -42,12 +46,14 @@ impl TieredStorageReader {
     pub fn is_empty(&self) -> bool {
         match self {
             Self::Hot(hot) => hot.is_empty(),
+            Self::Cold(cold) => cold.is_empty(),
         }
     }
 
     pub fn capacity(&self) -> u64 {
         match self {
             Self::Hot(hot) => hot.capacity(),
+            Self::Cold(cold) => cold.capacity(),
         }
     }
 
@@ -55,6 +61,7 @@ impl TieredStorageReader {
     pub fn footer(&self) -> &TieredStorageFooter {
         match self {
             Self::Hot(hot) => hot.footer(),
+            Self::Cold(cold) => cold.footer(),
         }
     }
 
@@ -62,6 +69,7 @@ impl TieredStorageReader {
     pub fn num_accounts(&self) -> usize {
         match self {
             Self::Hot(hot) => hot.num_accounts(),
+            Self::Cold(cold) => cold.num_accounts(),
         }
     }
 
@@ -72,6 +80,7 @@ impl TieredStorageReader {
     ) -> TieredStorageResult<Option<AccountSharedData>> {
         match self {
             Self::Hot(hot) => hot.get_account_shared_data(index_offset),
+            Self::Cold(cold) => cold.get_account_shared_data(index_offset),
         }
     }
 
@@ -83,6 +92,7 @@ impl TieredStorageReader {
     ) -> TieredStorageResult<Option<Ret>> {
         match self {
             Self::Hot(hot) => hot.get_stored_account_meta_callback(index_offset, callback),
+            Self::Cold(cold) => cold.get_stored_account_meta_callback(index_offset, callback),
         }
     }
 
@@ -107,6 +117,7 @@ impl TieredStorageReader {
                     .map_err(|_| MatchAccountOwnerError::UnableToLoad)?;
                 hot.account_matches_owners(account_offset, owners)
             }
+            Self::Cold(cold) => cold.account_matches_owners(index_offset, owners),
         }
     }
 
@@ -114,6 +125,7 @@ impl TieredStorageReader {
     pub fn scan_pubkeys(&self, callback: impl FnMut(&Pubkey)) -> TieredStorageResult<()> {
         match self {
             Self::Hot(hot) => hot.scan_pubkeys(callback),
+            Self::Cold(cold) => cold.scan_pubkeys(callback),
         }
     }
 
@@ -121,6 +133,7 @@ impl TieredStorageReader {
     pub(crate) fn scan_index(&self, callback: impl FnMut(IndexInfo)) -> TieredStorageResult<()> {
         match self {
             Self::Hot(hot) => hot.scan_index(callback),
+            Self::Cold(cold) => cold.scan_index(callback),
         }
     }
 
@@ -131,6 +144,7 @@ impl TieredStorageReader {
     ) -> TieredStorageResult<()> {
         match self {
             Self::Hot(hot) => hot.scan_accounts(callback),
+            Self::Cold(cold) => cold.scan_accounts(callback),
         }
     }
 
@@ -141,6 +155,7 @@ impl TieredStorageReader {
     ) -> TieredStorageResult<Vec<usize>> {
         match self {
             Self::Hot(hot) => hot.get_account_sizes(sorted_offsets),
+            Self::Cold(cold) => cold.get_account_sizes(sorted_offsets),
         }
     }
 
@@ -148,6 +163,7 @@ impl TieredStorageReader {
     pub fn data_for_archive(&self) -> &[u8] {
         match self {
             Self::Hot(hot) => hot.data_for_archive(),
+            Self::Cold(cold) => cold.data_for_archive(),
         }
     }
 }
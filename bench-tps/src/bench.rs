@@ -6,6 +6,7 @@ use {
         },
         perf_utils::{sample_txs, SampleStats},
         send_batch::*,
+        workload::{create_address_lookup_table, TransactionKind, TransactionPlan, Workload},
     },
     chrono::Utc,
     log::*,
@@ -20,13 +21,13 @@ use {
         compute_budget::ComputeBudgetInstruction,
         hash::Hash,
         instruction::{AccountMeta, Instruction},
-        message::Message,
+        message::{v0, Message, VersionedMessage},
         native_token::Sol,
         pubkey::Pubkey,
         signature::{Keypair, Signer},
         system_instruction,
         timing::timestamp,
-        transaction::Transaction,
+        transaction::{Transaction, VersionedTransaction},
     },
     solana_tps_client::*,
     spl_instruction_padding::instruction::wrap_instruction,
@@ -54,7 +55,7 @@ const MAX_TX_QUEUE_AGE: u64 = (MAX_PROCESSING_AGE as f64 * DEFAULT_S_PER_SLOT) a
 // `TRANSFER_TRANSACTION_COMPUTE_UNIT * MAX_COMPUTE_UNIT_PRICE * COMPUTE_UNIT_PRICE_MULTIPLIER / 1_000_000`
 const MAX_RANDOM_COMPUTE_UNIT_PRICE: u64 = 50;
 const COMPUTE_UNIT_PRICE_MULTIPLIER: u64 = 1_000;
-const TRANSFER_TRANSACTION_COMPUTE_UNIT: u32 = 600; // 1 transfer is plus 3 compute_budget ixs
+pub(crate) const TRANSFER_TRANSACTION_COMPUTE_UNIT: u32 = 600; // 1 transfer is plus 3 compute_budget ixs
 const PADDED_TRANSFER_COMPUTE_UNIT: u32 = 3_000; // padding program execution requires consumes this amount
 
 /// calculate maximum possible prioritization fee, if `use-randomized-compute-unit-price` is
@@ -70,9 +71,13 @@ pub fn max_lamports_for_prioritization(compute_unit_price: &Option<ComputeUnitPr
         ComputeUnitPrice::Fixed(compute_unit_price) => *compute_unit_price as u128,
     };
 
+    prioritization_fee_lamports(compute_unit_price, TRANSFER_TRANSACTION_COMPUTE_UNIT)
+}
+
+/// prioritization fee of `compute_units` at `compute_unit_price`, rounded up to lamports
+pub(crate) fn prioritization_fee_lamports(compute_unit_price: u128, compute_units: u32) -> u64 {
     const MICRO_LAMPORTS_PER_LAMPORT: u64 = 1_000_000;
-    let micro_lamport_fee: u128 =
-        compute_unit_price.saturating_mul(TRANSFER_TRANSACTION_COMPUTE_UNIT as u128);
+    let micro_lamport_fee: u128 = compute_unit_price.saturating_mul(compute_units as u128);
     let fee = micro_lamport_fee
         .saturating_add(MICRO_LAMPORTS_PER_LAMPORT.saturating_sub(1) as u128)
         .saturating_div(MICRO_LAMPORTS_PER_LAMPORT as u128);
@@ -84,6 +89,8 @@ pub fn max_lamports_for_prioritization(compute_unit_price: &Option<ComputeUnitPr
 const TRANSFER_TRANSACTION_LOADED_ACCOUNTS_DATA_SIZE: u32 = 30 * 1024;
 // In case of padding program usage, we need to take into account program size
 const PADDING_PROGRAM_ACCOUNT_DATA_SIZE: u32 = 28 * 1024;
+// Same for the SPL Memo program invoked by the compute workload classes
+const MEMO_PROGRAM_ACCOUNT_DATA_SIZE: u32 = 76 * 1024;
 fn get_transaction_loaded_accounts_data_size(enable_padding: bool) -> u32 {
     if enable_padding {
         TRANSFER_TRANSACTION_LOADED_ACCOUNTS_DATA_SIZE + PADDING_PROGRAM_ACCOUNT_DATA_SIZE
@@ -94,9 +101,11 @@ fn get_transaction_loaded_accounts_data_size(enable_padding: bool) -> u32 {
 
 #[derive(Debug, PartialEq, Default, Eq, Clone)]
 pub(crate) struct TimestampedTransaction {
-    transaction: Transaction,
+    transaction: VersionedTransaction,
     timestamp: Option<u64>,
     compute_unit_price: Option<u64>,
+    /// Index of the class in the workload profile
+    transaction_class: Option<usize>,
 }
 
 pub(crate) type SharedTransactions = Arc<RwLock<VecDeque<Vec<TimestampedTransaction>>>>;
@@ -151,6 +160,7 @@ struct TransactionChunkGenerator<'a, 'b, T: ?Sized> {
     compute_unit_price: Option<ComputeUnitPrice>,
     instruction_padding_config: Option<InstructionPaddingConfig>,
     skip_tx_account_data_size: bool,
+    workload: Option<Workload>,
 }
 
 impl<'a, 'b, T> TransactionChunkGenerator<'a, 'b, T>
@@ -166,6 +176,7 @@ where
         instruction_padding_config: Option<InstructionPaddingConfig>,
         num_conflict_groups: Option<usize>,
         skip_tx_account_data_size: bool,
+        workload: Option<Workload>,
     ) -> Self {
         let account_chunks = if let Some(num_conflict_groups) = num_conflict_groups {
             KeypairChunks::new_with_conflict_groups(gen_keypairs, chunk_size, num_conflict_groups)
@@ -184,6 +195,7 @@ where
             compute_unit_price,
             instruction_padding_config,
             skip_tx_account_data_size,
+            workload,
         }
     }
 
@@ -212,6 +224,15 @@ where
                 self.skip_tx_account_data_size,
                 &self.instruction_padding_config,
             )
+        } else if let Some(workload) = &self.workload {
+            generate_workload_txs(
+                source_chunk,
+                dest_chunk,
+                self.reclaim_lamports_back_to_source_account,
+                blockhash.expect("workload transactions use a recent blockhash"),
+                workload,
+                self.skip_tx_account_data_size,
+            )
         } else {
             assert!(blockhash.is_some());
             generate_system_txs(
@@ -422,10 +443,31 @@ where
         num_conflict_groups,
         block_data_file,
         transaction_data_file,
+        workload_profile,
         ..
     } = config;
 
     assert!(gen_keypairs.len() >= 2 * tx_count);
+    let workload = workload_profile.map(|workload_profile| {
+        if let Err(err) = workload_profile.check_num_accounts(gen_keypairs.len()) {
+            panic!("{err}");
+        }
+        // The first accounts are the hot accounts, in order of popularity
+        let hot_accounts: Vec<_> = gen_keypairs
+            .iter()
+            .take(workload_profile.num_hot_accounts())
+            .map(|keypair| keypair.pubkey())
+            .collect();
+        let lookup_table = workload_profile.uses_address_lookup_table().then(|| {
+            create_address_lookup_table(client.as_ref(), &id, &hot_accounts)
+                .expect("Failed to create the address lookup table of the hot accounts")
+        });
+        Workload::new(workload_profile, hot_accounts, lookup_table)
+    });
+    let transaction_classes = workload
+        .as_ref()
+        .map(Workload::class_names)
+        .unwrap_or_default();
     let chunk_generator = TransactionChunkGenerator::new(
         client.clone(),
         &gen_keypairs,
@@ -435,6 +477,7 @@ where
         instruction_padding_config,
         num_conflict_groups,
         skip_tx_account_data_size,
+        workload,
     );
 
     let first_tx_count = loop {
@@ -484,6 +527,7 @@ where
         &client,
         block_data_file.as_deref(),
         transaction_data_file.as_deref(),
+        transaction_classes,
     );
 
     let sender_threads = create_sender_threads(
@@ -535,12 +579,13 @@ where
         }
     }
 
-    if let Some(log_transaction_service) = log_transaction_service {
+    let transaction_class_report = log_transaction_service.and_then(|log_transaction_service| {
         info!("Waiting for log_transaction_service thread...");
-        if let Err(err) = log_transaction_service.join() {
-            info!("  join() failed with: {:?}", err);
-        }
-    }
+        log_transaction_service
+            .join()
+            .map_err(|err| info!("  join() failed with: {:?}", err))
+            .ok()
+    });
 
     if let Some(nonce_keypairs) = nonce_keypairs {
         withdraw_durable_nonce_accounts(client.clone(), &gen_keypairs, &nonce_keypairs);
@@ -555,6 +600,9 @@ where
         &start.elapsed(),
         total_tx_sent_count.load(Ordering::Relaxed),
     );
+    if let Some(mut transaction_class_report) = transaction_class_report {
+        transaction_class_report.log();
+    }
 
     let r_maxes = maxes.read().unwrap();
     r_maxes.first().unwrap().1.txs
@@ -615,9 +663,11 @@ fn generate_system_txs(
                         instruction_padding_config,
                         compute_unit_price,
                         skip_tx_account_data_size,
-                    ),
+                    )
+                    .into(),
                     timestamp: Some(timestamp()),
                     compute_unit_price,
+                    transaction_class: None,
                 }
             })
             .collect()
@@ -633,14 +683,112 @@ fn generate_system_txs(
                     instruction_padding_config,
                     None,
                     skip_tx_account_data_size,
-                ),
+                )
+                .into(),
                 timestamp: Some(timestamp()),
                 compute_unit_price: None,
+                transaction_class: None,
             })
             .collect()
     }
 }
 
+fn generate_workload_txs(
+    source: &[&Keypair],
+    dest: &VecDeque<&Keypair>,
+    reclaim: bool,
+    blockhash: &Hash,
+    workload: &Workload,
+    skip_tx_account_data_size: bool,
+) -> Vec<TimestampedTransaction> {
+    let pairs: Vec<_> = if !reclaim {
+        source.iter().zip(dest.iter()).collect()
+    } else {
+        dest.iter().zip(source.iter()).collect()
+    };
+
+    let mut rng = rand::thread_rng();
+    let plans: Vec<_> = (0..pairs.len())
+        .map(|_| workload.sample(&mut rng))
+        .collect();
+
+    pairs
+        .par_iter()
+        .zip(plans)
+        .map(|((from, to), plan)| {
+            let to = plan.destination.unwrap_or_else(|| to.pubkey());
+            TimestampedTransaction {
+                transaction: workload_transaction(
+                    from,
+                    &to,
+                    *blockhash,
+                    workload,
+                    &plan,
+                    skip_tx_account_data_size,
+                ),
+                timestamp: Some(timestamp()),
+                compute_unit_price: plan.compute_unit_price,
+                transaction_class: Some(plan.class),
+            }
+        })
+        .collect()
+}
+
+fn workload_transaction(
+    from_keypair: &Keypair,
+    to: &Pubkey,
+    recent_blockhash: Hash,
+    workload: &Workload,
+    plan: &TransactionPlan,
+    skip_tx_account_data_size: bool,
+) -> VersionedTransaction {
+    let from_pubkey = from_keypair.pubkey();
+    let kind = &workload.class(plan.class).kind;
+    let memo_size = match kind {
+        TransactionKind::Compute { memo_size, .. } => Some(*memo_size),
+        TransactionKind::Transfer | TransactionKind::Contended { .. } => None,
+    };
+    let mut instructions = vec![];
+    if !skip_tx_account_data_size {
+        let mut loaded_accounts_data_size = get_transaction_loaded_accounts_data_size(false);
+        if memo_size.is_some() {
+            loaded_accounts_data_size += MEMO_PROGRAM_ACCOUNT_DATA_SIZE;
+        }
+        instructions.push(
+            ComputeBudgetInstruction::set_loaded_accounts_data_size_limit(
+                loaded_accounts_data_size,
+            ),
+        )
+    }
+    instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(
+        kind.compute_unit_limit(),
+    ));
+    if let Some(compute_unit_price) = plan.compute_unit_price {
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_price(
+            compute_unit_price,
+        ));
+    }
+    instructions.push(system_instruction::transfer(&from_pubkey, to, 1));
+    if let Some(memo_size) = memo_size {
+        instructions.push(spl_memo::build_memo(&vec![b'x'; memo_size], &[]));
+    }
+
+    if let Some(lookup_table) = workload.lookup_table(plan.class) {
+        let message = v0::Message::try_compile(
+            &from_pubkey,
+            &instructions,
+            std::slice::from_ref(lookup_table),
+            recent_blockhash,
+        )
+        .expect("Could not compile v0 message");
+        VersionedTransaction::try_new(VersionedMessage::V0(message), &[from_keypair])
+            .expect("Could not sign v0 transaction")
+    } else {
+        let message = Message::new(&instructions, Some(&from_pubkey));
+        Transaction::new(&[from_keypair], message, recent_blockhash).into()
+    }
+}
+
 fn transfer_with_compute_unit_price_and_padding(
     from_keypair: &Keypair,
     to: &Pubkey,
@@ -820,9 +968,11 @@ fn generate_nonced_system_txs<T: 'static + TpsClient + Send + Sync + ?Sized>(
                     blockhashes[i],
                     skip_tx_account_data_size,
                     instruction_padding_config,
-                ),
+                )
+                .into(),
                 timestamp: None,
                 compute_unit_price: None,
+                transaction_class: None,
             });
         }
     } else {
@@ -840,9 +990,11 @@ fn generate_nonced_system_txs<T: 'static + TpsClient + Send + Sync + ?Sized>(
                     blockhashes[i],
                     skip_tx_account_data_size,
                     instruction_padding_config,
-                ),
+                )
+                .into(),
                 timestamp: None,
                 compute_unit_price: None,
+                transaction_class: None,
             });
         }
     }
@@ -970,6 +1122,7 @@ fn do_tx_transfers<T: TpsClient + ?Sized>(
             let mut transactions = Vec::<_>::with_capacity(num_txs);
             let mut signatures = Vec::<_>::with_capacity(num_txs);
             let mut compute_unit_prices = Vec::<_>::with_capacity(num_txs);
+            let mut transaction_classes = Vec::<_>::with_capacity(num_txs);
             for tx in txs {
                 let now = timestamp();
                 // Transactions without durable nonce that are too old will be rejected by the cluster Don't bother
@@ -986,6 +1139,7 @@ fn do_tx_transfers<T: TpsClient + ?Sized>(
                 signatures.push(tx.transaction.signatures[0]);
                 transactions.push(tx.transaction);
                 compute_unit_prices.push(tx.compute_unit_price);
+                transaction_classes.push(tx.transaction_class);
             }
 
             if min_timestamp != u64::MAX {
@@ -1000,13 +1154,14 @@ fn do_tx_transfers<T: TpsClient + ?Sized>(
                     signatures,
                     sent_at: Utc::now(),
                     compute_unit_prices,
+                    transaction_classes,
                 }) {
                     error!("Receiver has been dropped with error `{error}`, stop sending transactions.");
                     break 'thread_loop;
                 }
             }
 
-            if let Err(error) = client.send_batch(transactions) {
+            if let Err(error) = client.send_versioned_batch(transactions) {
                 warn!("send_batch_sync in do_tx_transfers failed: {}", error);
             }
 
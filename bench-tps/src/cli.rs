@@ -1,4 +1,5 @@
 use {
+    crate::workload::{load_workload_profile, WorkloadProfile},
     clap::{crate_description, crate_name, value_t_or_exit, App, Arg, ArgMatches},
     solana_clap_utils::{
         hidden_unless_forced,
@@ -15,6 +16,7 @@ use {
     solana_tpu_client::tpu_client::{DEFAULT_TPU_CONNECTION_POOL_SIZE, DEFAULT_TPU_USE_QUIC},
     std::{
         net::{IpAddr, Ipv4Addr},
+        path::Path,
         time::Duration,
     },
 };
@@ -80,6 +82,7 @@ pub struct Config {
     pub commitment_config: CommitmentConfig,
     pub block_data_file: Option<String>,
     pub transaction_data_file: Option<String>,
+    pub workload_profile: Option<WorkloadProfile>,
 }
 
 impl Eq for Config {}
@@ -117,6 +120,7 @@ impl Default for Config {
             commitment_config: CommitmentConfig::confirmed(),
             block_data_file: None,
             transaction_data_file: None,
+            workload_profile: None,
         }
     }
 }
@@ -454,6 +458,25 @@ pub fn build_args<'a>(version: &'_ str) -> App<'a, '_> {
                     This option is useful for debug purposes."
                 ),
         )
+        .arg(
+            Arg::with_name("workload_profile")
+                .long("workload-profile")
+                .value_name("FILEPATH")
+                .takes_value(true)
+                .conflicts_with_all(&[
+                    "compute_unit_price",
+                    "use_randomized_compute_unit_price",
+                    "use_durable_nonce",
+                    "instruction_padding_data_size",
+                ])
+                .help(
+                    "YAML file describing the mix of transaction classes to send: transfers, \
+                    transactions requesting many compute units and transfers to hot accounts, \
+                    with their compute unit prices and whether they use an address lookup table. \
+                    The landing rate and confirmation latency of each class are reported, \
+                    latencies being measured to block times with a resolution of a second."
+                ),
+        )
 }
 
 /// Parses a clap `ArgMatches` structure into a `Config`
@@ -634,6 +657,22 @@ pub fn parse_args(matches: &ArgMatches) -> Result<Config, &'static str> {
         .value_of("transaction_data_file")
         .map(|s| s.to_string());
 
+    if let Some(workload_profile) = matches.value_of("workload_profile") {
+        let workload_profile =
+            load_workload_profile(Path::new(workload_profile)).map_err(|err| {
+                eprintln!("{err}");
+                "can't load workload-profile"
+            })?;
+        // The hot accounts are the first bench-tps accounts
+        workload_profile
+            .check_num_accounts(args.tx_count * args.keypair_multiplier)
+            .map_err(|err| {
+                eprintln!("{err}");
+                "workload-profile has more hot accounts than bench-tps accounts"
+            })?;
+        args.workload_profile = Some(workload_profile);
+    }
+
     Ok(args)
 }
 
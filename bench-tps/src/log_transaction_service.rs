@@ -1,5 +1,6 @@
 //! `LogTransactionService` requests confirmed blocks, analyses transactions submitted by bench-tps,
-//! and saves log files in csv format.
+//! saves log files in csv format and reports the landing rate and confirmation latency of the
+//! workload transaction classes.
 
 use {
     crate::rpc_with_retry_utils::{get_blocks_with_retry, get_slot_with_retry},
@@ -36,32 +37,115 @@ pub(crate) struct TransactionInfoBatch {
     pub signatures: Vec<Signature>,
     pub sent_at: DateTime<Utc>,
     pub compute_unit_prices: Vec<Option<u64>>,
+    /// Index of the workload class of each transaction
+    pub transaction_classes: Vec<Option<usize>>,
 }
 
 pub(crate) type SignatureBatchSender = Sender<TransactionInfoBatch>;
 
 pub(crate) struct LogTransactionService {
-    thread_handler: JoinHandle<()>,
+    thread_handler: JoinHandle<TransactionClassReport>,
 }
 
+/// Creates the service if there is a data file to write or there are workload `transaction_classes`
+/// to report on
 pub(crate) fn create_log_transactions_service_and_sender<Client>(
     client: &Arc<Client>,
     block_data_file: Option<&str>,
     transaction_data_file: Option<&str>,
+    transaction_classes: Vec<String>,
 ) -> (Option<LogTransactionService>, Option<SignatureBatchSender>)
 where
     Client: 'static + TpsClient + Send + Sync + ?Sized,
 {
-    if data_file_provided(block_data_file, transaction_data_file) {
+    if data_file_provided(block_data_file, transaction_data_file) || !transaction_classes.is_empty()
+    {
         let (sender, receiver) = unbounded();
-        let log_tx_service =
-            LogTransactionService::new(client, receiver, block_data_file, transaction_data_file);
+        let log_tx_service = LogTransactionService::new(
+            client,
+            receiver,
+            block_data_file,
+            transaction_data_file,
+            transaction_classes,
+        );
         (Some(log_tx_service), Some(sender))
     } else {
         (None, None)
     }
 }
 
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TransactionClassStats {
+    sent: usize,
+    landed: usize,
+    /// Landed with an error
+    failed: usize,
+    timed_out: usize,
+    /// Milliseconds from sending a transaction to the time of its block. Block times have a
+    /// resolution of a second, so these are accurate to a second and clamped at zero for
+    /// transactions landing in the second they were sent.
+    confirmation_latencies_ms: Vec<i64>,
+}
+
+/// Landing rate and confirmation latency of the transactions of each workload class
+#[derive(Debug, Default)]
+pub(crate) struct TransactionClassReport {
+    names: Vec<String>,
+    stats: Vec<TransactionClassStats>,
+}
+
+impl TransactionClassReport {
+    fn new(names: Vec<String>) -> Self {
+        let stats = names
+            .iter()
+            .map(|_| TransactionClassStats::default())
+            .collect();
+        Self { names, stats }
+    }
+
+    fn stats_mut(
+        &mut self,
+        transaction_class: Option<usize>,
+    ) -> Option<&mut TransactionClassStats> {
+        transaction_class.and_then(|index| self.stats.get_mut(index))
+    }
+
+    pub(crate) fn log(&mut self) {
+        if self.names.is_empty() {
+            return;
+        }
+        info!(" Transaction class    |     Sent |   Landed | Landing rate |   Failed | Timed out | p50 ms | p90 ms | Max ms");
+        info!("----------------------+----------+----------+--------------+----------+-----------+--------+--------+-------");
+        for (name, stats) in self.names.iter().zip(self.stats.iter_mut()) {
+            let landing_rate = if stats.sent > 0 {
+                stats.landed as f64 / stats.sent as f64
+            } else {
+                0.0
+            };
+            stats.confirmation_latencies_ms.sort_unstable();
+            let latencies = &stats.confirmation_latencies_ms;
+            let percentile = |percentile: usize| {
+                latencies
+                    .get(latencies.len().saturating_sub(1) * percentile / 100)
+                    .map(|latency| latency.to_string())
+                    .unwrap_or_default()
+            };
+            info!(
+                " {:20} | {:8} | {:8} | {:12.4} | {:8} | {:9} | {:>6} | {:>6} | {:>6}",
+                name,
+                stats.sent,
+                stats.landed,
+                landing_rate,
+                stats.failed,
+                stats.timed_out,
+                percentile(50),
+                percentile(90),
+                percentile(100),
+            );
+        }
+    }
+}
+
 // How many blocks to process during one iteration.
 // The time to process blocks is dominated by get_block calls.
 // Each call takes slightly less time than slot.
@@ -76,6 +160,7 @@ const REMOVE_TIMEOUT_TX_EVERY_MS: i64 = MAX_PROCESSING_AGE as i64 * DEFAULT_MS_P
 struct TransactionSendInfo {
     pub sent_at: DateTime<Utc>,
     pub compute_unit_price: Option<u64>,
+    pub transaction_class: Option<usize>,
 }
 type MapSignatureToTxInfo = HashMap<Signature, TransactionSendInfo>;
 
@@ -87,28 +172,38 @@ impl LogTransactionService {
         signature_receiver: SignatureBatchReceiver,
         block_data_file: Option<&str>,
         transaction_data_file: Option<&str>,
+        transaction_classes: Vec<String>,
     ) -> Self
     where
         Client: 'static + TpsClient + Send + Sync + ?Sized,
     {
-        if !data_file_provided(block_data_file, transaction_data_file) {
-            panic!("Expect block-data-file or transaction-data-file is specified, must have been verified by callee.");
+        if !data_file_provided(block_data_file, transaction_data_file)
+            && transaction_classes.is_empty()
+        {
+            panic!("Expect block-data-file, transaction-data-file or transaction classes are specified, must have been verified by callee.");
         }
 
         let client = client.clone();
         let tx_log_writer = TransactionLogWriter::new(transaction_data_file);
         let block_log_writer = BlockLogWriter::new(block_data_file);
+        let class_report = TransactionClassReport::new(transaction_classes);
 
         let thread_handler = Builder::new()
             .name("LogTransactionService".to_string())
             .spawn(move || {
-                Self::run(client, signature_receiver, tx_log_writer, block_log_writer);
+                Self::run(
+                    client,
+                    signature_receiver,
+                    tx_log_writer,
+                    block_log_writer,
+                    class_report,
+                )
             })
             .expect("LogTransactionService should have started successfully.");
         Self { thread_handler }
     }
 
-    pub fn join(self) -> thread::Result<()> {
+    pub fn join(self) -> thread::Result<TransactionClassReport> {
         self.thread_handler.join()
     }
 
@@ -117,7 +212,9 @@ impl LogTransactionService {
         signature_receiver: SignatureBatchReceiver,
         mut tx_log_writer: TransactionLogWriter,
         mut block_log_writer: BlockLogWriter,
-    ) where
+        mut class_report: TransactionClassReport,
+    ) -> TransactionClassReport
+    where
         Client: 'static + TpsClient + Send + Sync + ?Sized,
     {
         // used to request blocks data and only confirmed makes sense in this context.
@@ -138,12 +235,19 @@ impl LogTransactionService {
                         Ok(TransactionInfoBatch {
                             signatures,
                             sent_at,
-                            compute_unit_prices
+                            compute_unit_prices,
+                            transaction_classes,
                         }) => {
-                            signatures.iter().zip(compute_unit_prices).for_each( |(sign, compute_unit_price)| {signature_to_tx_info.insert(*sign, TransactionSendInfo {
-                                sent_at,
-                                compute_unit_price
-                            });});
+                            signatures.iter().zip(compute_unit_prices).zip(transaction_classes).for_each( |((sign, compute_unit_price), transaction_class)| {
+                                if let Some(stats) = class_report.stats_mut(transaction_class) {
+                                    stats.sent = stats.sent.saturating_add(1);
+                                }
+                                signature_to_tx_info.insert(*sign, TransactionSendInfo {
+                                    sent_at,
+                                    compute_unit_price,
+                                    transaction_class,
+                                });
+                            });
                         }
                         Err(_) => {
                             sender_stopped = true;
@@ -173,9 +277,10 @@ impl LogTransactionService {
                         &mut signature_to_tx_info,
                         &mut tx_log_writer,
                         &mut block_log_writer,
+                        &mut class_report,
                         commitment,
                     );
-                    Self::clean_transaction_map(&mut tx_log_writer, &mut signature_to_tx_info, last_block_time);
+                    Self::clean_transaction_map(&mut tx_log_writer, &mut signature_to_tx_info, &mut class_report, last_block_time);
 
                     start_slot = start_slot.saturating_add(NUM_SLOTS_PER_ITERATION);
                     tx_log_writer.flush();
@@ -187,6 +292,7 @@ impl LogTransactionService {
                 },
             }
         }
+        class_report
     }
 
     /// Download and process the blocks.
@@ -197,6 +303,7 @@ impl LogTransactionService {
         signature_to_tx_info: &mut MapSignatureToTxInfo,
         tx_log_writer: &mut TransactionLogWriter,
         block_log_writer: &mut BlockLogWriter,
+        class_report: &mut TransactionClassReport,
         commitment: CommitmentConfig,
     ) -> DateTime<Utc>
     where
@@ -225,6 +332,7 @@ impl LogTransactionService {
                 *slot,
                 tx_log_writer,
                 block_log_writer,
+                class_report,
             );
             // if last_time is some, it means that the there is at least one valid block
             if block_time.is_some() {
@@ -243,6 +351,7 @@ impl LogTransactionService {
         slot: u64,
         tx_log_writer: &mut TransactionLogWriter,
         block_log_writer: &mut BlockLogWriter,
+        class_report: &mut TransactionClassReport,
     ) -> Option<DateTime<Utc>> {
         let rewards = block
             .rewards
@@ -280,11 +389,28 @@ impl LogTransactionService {
             if let Some(TransactionSendInfo {
                 sent_at,
                 compute_unit_price,
+                transaction_class,
             }) = signature_to_tx_info.remove(signature)
             {
                 num_bench_tps_transactions = num_bench_tps_transactions.saturating_add(1);
                 bench_tps_cu_consumed = bench_tps_cu_consumed.saturating_add(cu_consumed);
 
+                if let Some(stats) = class_report.stats_mut(transaction_class) {
+                    stats.landed = stats.landed.saturating_add(1);
+                    if meta.as_ref().is_some_and(|meta| meta.status.is_err()) {
+                        stats.failed = stats.failed.saturating_add(1);
+                    }
+                    // Block times are truncated to the second, so they may precede the send time
+                    if let Some(block_time) = block.block_time {
+                        stats.confirmation_latencies_ms.push(
+                            block_time
+                                .saturating_mul(1000)
+                                .saturating_sub(sent_at.timestamp_millis())
+                                .max(0),
+                        );
+                    }
+                }
+
                 tx_log_writer.write(
                     Some(block.blockhash.clone()),
                     Some(slot_leader.clone()),
@@ -295,6 +421,7 @@ impl LogTransactionService {
                     meta.as_ref(),
                     false,
                     compute_unit_price,
+                    transaction_class.and_then(|index| class_report.names.get(index)),
                 );
             }
         }
@@ -321,12 +448,16 @@ impl LogTransactionService {
     fn clean_transaction_map(
         tx_log_writer: &mut TransactionLogWriter,
         signature_to_tx_info: &mut MapSignatureToTxInfo,
+        class_report: &mut TransactionClassReport,
         last_block_time: DateTime<Utc>,
     ) {
         signature_to_tx_info.retain(|signature, tx_info| {
             let duration_since_sent = last_block_time.signed_duration_since(tx_info.sent_at);
             let is_timeout_tx = duration_since_sent.num_milliseconds() > REMOVE_TIMEOUT_TX_EVERY_MS;
             if is_timeout_tx {
+                if let Some(stats) = class_report.stats_mut(tx_info.transaction_class) {
+                    stats.timed_out = stats.timed_out.saturating_add(1);
+                }
                 tx_log_writer.write(
                     None,
                     None,
@@ -337,6 +468,9 @@ impl LogTransactionService {
                     None,
                     true,
                     tx_info.compute_unit_price,
+                    tx_info
+                        .transaction_class
+                        .and_then(|index| class_report.names.get(index)),
                 );
             }
             !is_timeout_tx
@@ -430,6 +564,7 @@ struct TransactionData {
     pub error: Option<String>,
     pub timed_out: bool,
     pub compute_unit_price: u64,
+    pub transaction_class: Option<String>,
 }
 
 struct TransactionLogWriter {
@@ -461,6 +596,7 @@ impl TransactionLogWriter {
         meta: Option<&UiTransactionStatusMeta>,
         timed_out: bool,
         compute_unit_price: Option<u64>,
+        transaction_class: Option<&String>,
     ) {
         let Some(transaction_log_writer) = &mut self.log_writer else {
             return;
@@ -482,6 +618,7 @@ impl TransactionLogWriter {
                 .and_then(|m| m.err.as_ref().map(|x| x.to_string())),
             timed_out,
             compute_unit_price: compute_unit_price.unwrap_or(0),
+            transaction_class: transaction_class.cloned(),
         };
         let _ = transaction_log_writer.serialize(tx_data);
     }
@@ -196,6 +196,7 @@ fn main() {
         compute_unit_price,
         use_durable_nonce,
         instruction_padding_config,
+        workload_profile,
         bind_address,
         client_node_id,
         commitment_config,
@@ -209,7 +210,13 @@ fn main() {
         let num_accounts = keypairs.len() as u64;
         let max_fee = FeeRateGovernor::new(*target_lamports_per_signature, 0)
             .max_lamports_per_signature
-            .saturating_add(max_lamports_for_prioritization(compute_unit_price));
+            .saturating_add(max_lamports_for_prioritization(compute_unit_price))
+            .saturating_add(
+                workload_profile
+                    .as_ref()
+                    .map(|workload_profile| workload_profile.max_lamports_for_prioritization())
+                    .unwrap_or_default(),
+            );
         let num_lamports_per_account = (num_accounts - 1 + NUM_SIGNATURES_FOR_TXS * max_fee)
             / num_accounts
             + num_lamports_per_account;
@@ -0,0 +1,602 @@
+//! Workload profiles, the mix of transaction classes generated by bench-tps
+//!
+//! `--workload-profile` reads a YAML file like:
+//!
+//! ```yaml
+//! classes:
+//!   - name: transfer
+//!     weight: 60
+//!     kind: transfer
+//!   - name: compute
+//!     weight: 20
+//!     kind: compute
+//!     compute_units: 200000
+//!     memo_size: 512
+//!     compute_unit_price:
+//!       uniform:
+//!         min: 0
+//!         max: 10000
+//!   - name: contended
+//!     weight: 20
+//!     kind: contended
+//!     hot_accounts: 8
+//!     zipf_exponent: 1.2
+//!     address_lookup_table: true
+//!     compute_unit_price:
+//!       weighted:
+//!         - price: 0
+//!           weight: 9
+//!         - price: 100000
+//!           weight: 1
+//! ```
+//!
+//! Every transaction is of a class picked according to the class weights. Compute unit prices are
+//! in micro-lamports; a class without `compute_unit_price` pays no priority fee.
+
+use {
+    crate::{
+        bench::{prioritization_fee_lamports, TRANSFER_TRANSACTION_COMPUTE_UNIT},
+        send_batch::get_latest_blockhash,
+    },
+    log::*,
+    rand::{
+        distributions::{Distribution, Uniform, WeightedIndex},
+        Rng,
+    },
+    serde::Deserialize,
+    solana_sdk::{
+        address_lookup_table::{
+            instruction::{create_lookup_table, extend_lookup_table},
+            state::LOOKUP_TABLE_MAX_ADDRESSES,
+            AddressLookupTableAccount,
+        },
+        commitment_config::CommitmentConfig,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        transaction::Transaction,
+    },
+    solana_tps_client::{TpsClient, TpsClientError, TpsClientResult},
+    std::{
+        collections::HashSet,
+        fs::File,
+        path::Path,
+        thread::sleep,
+        time::{Duration, Instant},
+    },
+};
+
+const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+const DEFAULT_ZIPF_EXPONENT: f64 = 1.0;
+const DEFAULT_MEMO_SIZE: usize = 512;
+// Largest memo leaving room in a packet for the transfer and compute budget instructions
+const MAX_MEMO_SIZE: usize = 800;
+// Addresses added to the lookup table per transaction, which must fit in a packet
+const LOOKUP_TABLE_EXTEND_CHUNK_SIZE: usize = 20;
+const LOOKUP_TABLE_TRANSACTION_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkloadProfile {
+    pub classes: Vec<TransactionClass>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct TransactionClass {
+    pub name: String,
+    pub weight: u32,
+    #[serde(flatten)]
+    pub kind: TransactionKind,
+    #[serde(default)]
+    pub compute_unit_price: Option<PriceDistribution>,
+    /// Send v0 transactions, loading the hot accounts from an address lookup table
+    #[serde(default)]
+    pub address_lookup_table: bool,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum TransactionKind {
+    /// Transfer between the bench-tps accounts, like the default workload
+    Transfer,
+    /// Transfer with an SPL Memo of `memo_size` bytes, requesting `compute_units`. The memo
+    /// program consumes compute units validating and logging the memo, so these compete for
+    /// block space and execution time like CPU-heavy transactions. `compute_units` must cover
+    /// the memo, otherwise the transactions land as failed.
+    Compute {
+        compute_units: u32,
+        #[serde(default = "default_memo_size")]
+        memo_size: usize,
+    },
+    /// Transfer to one of the first `hot_accounts` bench-tps accounts, picked with a Zipf
+    /// distribution so that transactions contend for the most popular ones
+    Contended {
+        hot_accounts: usize,
+        #[serde(default = "default_zipf_exponent")]
+        zipf_exponent: f64,
+    },
+}
+
+fn default_zipf_exponent() -> f64 {
+    DEFAULT_ZIPF_EXPONENT
+}
+
+fn default_memo_size() -> usize {
+    DEFAULT_MEMO_SIZE
+}
+
+impl TransactionKind {
+    pub fn compute_unit_limit(&self) -> u32 {
+        match self {
+            Self::Compute { compute_units, .. } => *compute_units,
+            Self::Transfer | Self::Contended { .. } => TRANSFER_TRANSACTION_COMPUTE_UNIT,
+        }
+    }
+}
+
+/// Distribution of the compute unit price of the transactions of a class
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceDistribution {
+    Fixed(u64),
+    Uniform { min: u64, max: u64 },
+    Weighted(Vec<WeightedPrice>),
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WeightedPrice {
+    pub price: u64,
+    pub weight: u32,
+}
+
+impl PriceDistribution {
+    fn max(&self) -> u64 {
+        match self {
+            Self::Fixed(price) => *price,
+            Self::Uniform { max, .. } => *max,
+            Self::Weighted(prices) => prices.iter().map(|x| x.price).max().unwrap_or_default(),
+        }
+    }
+}
+
+impl WorkloadProfile {
+    /// Number of bench-tps accounts the contended classes transfer to
+    pub fn num_hot_accounts(&self) -> usize {
+        self.classes
+            .iter()
+            .filter_map(|class| match class.kind {
+                TransactionKind::Contended { hot_accounts, .. } => Some(hot_accounts),
+                _ => None,
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Checks that the bench-tps accounts include the hot accounts
+    pub fn check_num_accounts(&self, num_accounts: usize) -> Result<(), String> {
+        let num_hot_accounts = self.num_hot_accounts();
+        if num_hot_accounts > num_accounts {
+            return Err(format!(
+                "the workload profile has {num_hot_accounts} hot accounts, more than the \
+                 {num_accounts} bench-tps accounts"
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn uses_address_lookup_table(&self) -> bool {
+        self.classes.iter().any(|class| class.address_lookup_table)
+    }
+
+    /// Highest prioritization fee of a transaction of the profile, in lamports
+    pub fn max_lamports_for_prioritization(&self) -> u64 {
+        self.classes
+            .iter()
+            .map(|class| {
+                let max_price = class
+                    .compute_unit_price
+                    .as_ref()
+                    .map(PriceDistribution::max)
+                    .unwrap_or_default();
+                prioritization_fee_lamports(max_price as u128, class.kind.compute_unit_limit())
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self
+            .classes
+            .iter()
+            .map(|class| class.weight as u64)
+            .sum::<u64>()
+            == 0
+        {
+            return Err("the total weight of the classes must be positive".to_string());
+        }
+        let mut names = HashSet::new();
+        for class in &self.classes {
+            let name = &class.name;
+            if !names.insert(name) {
+                return Err(format!("duplicate class {name}"));
+            }
+            match class.kind {
+                TransactionKind::Transfer => {}
+                TransactionKind::Compute {
+                    compute_units,
+                    memo_size,
+                } => {
+                    if !(1..=MAX_COMPUTE_UNIT_LIMIT).contains(&compute_units) {
+                        return Err(format!(
+                            "compute_units of {name} must be between 1 and {MAX_COMPUTE_UNIT_LIMIT}"
+                        ));
+                    }
+                    if !(1..=MAX_MEMO_SIZE).contains(&memo_size) {
+                        return Err(format!(
+                            "memo_size of {name} must be between 1 and {MAX_MEMO_SIZE}"
+                        ));
+                    }
+                }
+                TransactionKind::Contended {
+                    hot_accounts,
+                    zipf_exponent,
+                } => {
+                    if hot_accounts == 0 {
+                        return Err(format!("hot_accounts of {name} must be positive"));
+                    }
+                    if !zipf_exponent.is_finite() || zipf_exponent < 0.0 {
+                        return Err(format!("zipf_exponent of {name} must not be negative"));
+                    }
+                }
+            }
+            match &class.compute_unit_price {
+                Some(PriceDistribution::Uniform { min, max }) if min > max => {
+                    return Err(format!("compute_unit_price of {name} has min above max"));
+                }
+                Some(PriceDistribution::Weighted(prices))
+                    if prices.iter().map(|x| x.weight as u64).sum::<u64>() == 0 =>
+                {
+                    return Err(format!(
+                        "compute_unit_price of {name} must have a positive total weight"
+                    ));
+                }
+                _ => {}
+            }
+        }
+        if self.uses_address_lookup_table() && self.num_hot_accounts() > LOOKUP_TABLE_MAX_ADDRESSES
+        {
+            return Err(format!(
+                "an address lookup table holds at most {LOOKUP_TABLE_MAX_ADDRESSES} hot accounts"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Loads the `--workload-profile` file at `path`
+pub fn load_workload_profile(path: &Path) -> Result<WorkloadProfile, String> {
+    let file =
+        File::open(path).map_err(|err| format!("Unable to open {}: {err}", path.display()))?;
+    let profile: WorkloadProfile = serde_yaml::from_reader(file)
+        .map_err(|err| format!("Unable to parse {}: {err}", path.display()))?;
+    profile
+        .validate()
+        .map_err(|err| format!("Invalid workload profile {}: {err}", path.display()))?;
+    Ok(profile)
+}
+
+enum PriceSampler {
+    Fixed(u64),
+    Uniform(Uniform<u64>),
+    Weighted(Vec<u64>, WeightedIndex<u32>),
+}
+
+impl PriceSampler {
+    fn new(distribution: &PriceDistribution) -> Self {
+        match distribution {
+            PriceDistribution::Fixed(price) => Self::Fixed(*price),
+            PriceDistribution::Uniform { min, max } => {
+                Self::Uniform(Uniform::new_inclusive(*min, *max))
+            }
+            PriceDistribution::Weighted(prices) => Self::Weighted(
+                prices.iter().map(|x| x.price).collect(),
+                WeightedIndex::new(prices.iter().map(|x| x.weight))
+                    .expect("validated price weights"),
+            ),
+        }
+    }
+
+    fn sample<R: Rng>(&self, rng: &mut R) -> u64 {
+        match self {
+            Self::Fixed(price) => *price,
+            Self::Uniform(uniform) => uniform.sample(rng),
+            Self::Weighted(prices, index) => prices[index.sample(rng)],
+        }
+    }
+}
+
+/// Transaction to generate, sampled from a `Workload`
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct TransactionPlan {
+    /// Index of the class in the workload profile
+    pub class: usize,
+    pub compute_unit_price: Option<u64>,
+    /// Hot account replacing the destination of the transfer
+    pub destination: Option<Pubkey>,
+}
+
+/// Samples the transactions of a workload profile
+pub(crate) struct Workload {
+    profile: WorkloadProfile,
+    class_index: WeightedIndex<u32>,
+    prices: Vec<Option<PriceSampler>>,
+    /// Zipf distribution over the hot accounts, for the contended classes
+    hot_account_indexes: Vec<Option<WeightedIndex<f64>>>,
+    hot_accounts: Vec<Pubkey>,
+    lookup_table: Option<AddressLookupTableAccount>,
+}
+
+impl Workload {
+    /// `hot_accounts` holds at least `profile.num_hot_accounts()` accounts, in order of
+    /// popularity
+    pub(crate) fn new(
+        profile: WorkloadProfile,
+        hot_accounts: Vec<Pubkey>,
+        lookup_table: Option<AddressLookupTableAccount>,
+    ) -> Self {
+        assert!(hot_accounts.len() >= profile.num_hot_accounts());
+        let class_index = WeightedIndex::new(profile.classes.iter().map(|class| class.weight))
+            .expect("validated class weights");
+        let prices = profile
+            .classes
+            .iter()
+            .map(|class| class.compute_unit_price.as_ref().map(PriceSampler::new))
+            .collect();
+        let hot_account_indexes = profile
+            .classes
+            .iter()
+            .map(|class| match class.kind {
+                TransactionKind::Contended {
+                    hot_accounts,
+                    zipf_exponent,
+                } => Some(
+                    WeightedIndex::new(
+                        (1..=hot_accounts).map(|rank| 1.0 / (rank as f64).powf(zipf_exponent)),
+                    )
+                    .expect("validated hot accounts"),
+                ),
+                _ => None,
+            })
+            .collect();
+        Self {
+            profile,
+            class_index,
+            prices,
+            hot_account_indexes,
+            hot_accounts,
+            lookup_table,
+        }
+    }
+
+    pub(crate) fn class(&self, index: usize) -> &TransactionClass {
+        &self.profile.classes[index]
+    }
+
+    pub(crate) fn class_names(&self) -> Vec<String> {
+        self.profile
+            .classes
+            .iter()
+            .map(|class| class.name.clone())
+            .collect()
+    }
+
+    /// Lookup table of the hot accounts, if the class sends v0 transactions
+    pub(crate) fn lookup_table(&self, class: usize) -> Option<&AddressLookupTableAccount> {
+        self.class(class)
+            .address_lookup_table
+            .then_some(self.lookup_table.as_ref())
+            .flatten()
+    }
+
+    pub(crate) fn sample<R: Rng>(&self, rng: &mut R) -> TransactionPlan {
+        let class = self.class_index.sample(rng);
+        TransactionPlan {
+            class,
+            compute_unit_price: self.prices[class].as_ref().map(|price| price.sample(rng)),
+            destination: self.hot_account_indexes[class]
+                .as_ref()
+                .map(|index| self.hot_accounts[index.sample(rng)]),
+        }
+    }
+}
+
+fn send_and_confirm<T: TpsClient + ?Sized>(
+    client: &T,
+    transaction: Transaction,
+) -> TpsClientResult<()> {
+    let signature = client.send_transaction(transaction)?;
+    let start = Instant::now();
+    while start.elapsed() < LOOKUP_TABLE_TRANSACTION_TIMEOUT {
+        match client.get_signature_status(&signature)? {
+            Some(Ok(())) => return Ok(()),
+            Some(Err(err)) => {
+                return Err(TpsClientError::Custom(format!(
+                    "Lookup table transaction {signature} failed: {err}"
+                )))
+            }
+            None => sleep(Duration::from_millis(100)),
+        }
+    }
+    Err(TpsClientError::Custom(format!(
+        "Lookup table transaction {signature} was not confirmed"
+    )))
+}
+
+/// Creates an address lookup table holding `addresses`, and waits until it can be used
+pub(crate) fn create_address_lookup_table<T: TpsClient + ?Sized>(
+    client: &T,
+    payer: &Keypair,
+    addresses: &[Pubkey],
+) -> TpsClientResult<AddressLookupTableAccount> {
+    let recent_slot = client.get_slot_with_commitment(CommitmentConfig::finalized())?;
+    let (create_instruction, lookup_table_address) =
+        create_lookup_table(payer.pubkey(), payer.pubkey(), recent_slot);
+    info!("Creating address lookup table {lookup_table_address}...");
+    send_and_confirm(
+        client,
+        Transaction::new_signed_with_payer(
+            &[create_instruction],
+            Some(&payer.pubkey()),
+            &[payer],
+            get_latest_blockhash(client),
+        ),
+    )?;
+    for chunk in addresses.chunks(LOOKUP_TABLE_EXTEND_CHUNK_SIZE) {
+        send_and_confirm(
+            client,
+            Transaction::new_signed_with_payer(
+                &[extend_lookup_table(
+                    lookup_table_address,
+                    payer.pubkey(),
+                    Some(payer.pubkey()),
+                    chunk.to_vec(),
+                )],
+                Some(&payer.pubkey()),
+                &[payer],
+                get_latest_blockhash(client),
+            ),
+        )?;
+    }
+
+    // Addresses can be looked up starting from the slot after they were added
+    let extended_slot = client.get_slot_with_commitment(CommitmentConfig::processed())?;
+    while client.get_slot_with_commitment(CommitmentConfig::processed())? <= extended_slot {
+        sleep(Duration::from_millis(100));
+    }
+    Ok(AddressLookupTableAccount {
+        key: lookup_table_address,
+        addresses: addresses.to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, rand::thread_rng};
+
+    fn parse(profile: &str) -> Result<WorkloadProfile, String> {
+        let profile: WorkloadProfile =
+            serde_yaml::from_str(profile).map_err(|err| err.to_string())?;
+        profile.validate()?;
+        Ok(profile)
+    }
+
+    #[test]
+    fn test_parse_workload_profile() {
+        let profile = parse(
+            "classes:
+  - name: transfer
+    weight: 6
+    kind: transfer
+  - name: compute
+    weight: 2
+    kind: compute
+    compute_units: 200000
+    compute_unit_price:
+      uniform:
+        min: 0
+        max: 10
+  - name: contended
+    weight: 2
+    kind: contended
+    hot_accounts: 8
+    address_lookup_table: true
+    compute_unit_price:
+      weighted:
+        - price: 0
+          weight: 9
+        - price: 1000000
+          weight: 1
+",
+        )
+        .unwrap();
+        assert_eq!(profile.classes.len(), 3);
+        assert_eq!(
+            profile.classes[1].kind,
+            TransactionKind::Compute {
+                compute_units: 200_000,
+                memo_size: DEFAULT_MEMO_SIZE,
+            }
+        );
+        assert_eq!(
+            profile.classes[2].kind,
+            TransactionKind::Contended {
+                hot_accounts: 8,
+                zipf_exponent: DEFAULT_ZIPF_EXPONENT,
+            }
+        );
+        assert_eq!(profile.num_hot_accounts(), 8);
+        assert!(profile.check_num_accounts(8).is_ok());
+        assert!(profile.check_num_accounts(7).is_err());
+        assert!(profile.uses_address_lookup_table());
+        // 1_000_000 micro-lamports for each of the compute units of a transfer
+        assert_eq!(
+            profile.max_lamports_for_prioritization(),
+            TRANSFER_TRANSACTION_COMPUTE_UNIT as u64
+        );
+
+        assert!(parse("classes:\n  - {name: a, weight: 0, kind: transfer}\n").is_err());
+        assert!(parse(
+            "classes:\n  - {name: a, weight: 1, kind: transfer}\n  - {name: a, weight: 1, \
+             kind: transfer}\n"
+        )
+        .is_err());
+        assert!(
+            parse("classes:\n  - {name: a, weight: 1, kind: compute, compute_units: 0}\n").is_err()
+        );
+        assert!(parse(
+            "classes:\n  - {name: a, weight: 1, kind: compute, compute_units: 1, memo_size: 801}\n"
+        )
+        .is_err());
+        assert!(
+            parse("classes:\n  - {name: a, weight: 1, kind: contended, hot_accounts: 0}\n")
+                .is_err()
+        );
+        assert!(parse(
+            "classes:\n  - {name: a, weight: 1, kind: transfer, compute_unit_price: {uniform: \
+             {min: 2, max: 1}}}\n"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_workload_sample() {
+        let profile = parse(
+            "classes:
+  - name: contended
+    weight: 1
+    kind: contended
+    hot_accounts: 4
+    zipf_exponent: 2.0
+    compute_unit_price:
+      fixed: 7
+",
+        )
+        .unwrap();
+        let hot_accounts: Vec<_> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let workload = Workload::new(profile, hot_accounts.clone(), None);
+        assert_eq!(workload.class_names(), vec!["contended".to_string()]);
+        assert!(workload.lookup_table(0).is_none());
+
+        let mut rng = thread_rng();
+        let mut counts = [0; 4];
+        for _ in 0..1_000 {
+            let plan = workload.sample(&mut rng);
+            assert_eq!(plan.class, 0);
+            assert_eq!(plan.compute_unit_price, Some(7));
+            let destination = plan.destination.unwrap();
+            counts[hot_accounts.iter().position(|x| *x == destination).unwrap()] += 1;
+        }
+        // The most popular account gets 1 / (1 + 1/4 + 1/9 + 1/16) ~ 70% of the transfers
+        assert!(counts[0] > counts[1] && counts[1] > counts[3]);
+    }
+}
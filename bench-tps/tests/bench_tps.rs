@@ -6,6 +6,7 @@ use {
         bench::{do_bench_tps, generate_and_fund_keypairs},
         cli::{Config, InstructionPaddingConfig},
         send_batch::generate_durable_nonce_accounts,
+        workload::{PriceDistribution, TransactionClass, TransactionKind, WorkloadProfile},
     },
     solana_connection_cache::connection_cache::NewConnectionConfig,
     solana_core::validator::ValidatorConfig,
@@ -220,3 +221,44 @@ fn test_bench_tps_tpu_client_with_padding() {
         ..Config::default()
     });
 }
+
+#[test]
+#[serial]
+fn test_bench_tps_tpu_client_workload() {
+    test_bench_tps_test_validator(Config {
+        tx_count: 100,
+        duration: Duration::from_secs(10),
+        workload_profile: Some(WorkloadProfile {
+            classes: vec![
+                TransactionClass {
+                    name: "transfer".to_string(),
+                    weight: 2,
+                    kind: TransactionKind::Transfer,
+                    compute_unit_price: None,
+                    address_lookup_table: false,
+                },
+                TransactionClass {
+                    name: "compute".to_string(),
+                    weight: 1,
+                    kind: TransactionKind::Compute {
+                        compute_units: 200_000,
+                        memo_size: 256,
+                    },
+                    compute_unit_price: Some(PriceDistribution::Uniform { min: 0, max: 1 }),
+                    address_lookup_table: false,
+                },
+                TransactionClass {
+                    name: "contended".to_string(),
+                    weight: 1,
+                    kind: TransactionKind::Contended {
+                        hot_accounts: 4,
+                        zipf_exponent: 1.0,
+                    },
+                    compute_unit_price: Some(PriceDistribution::Fixed(1)),
+                    address_lookup_table: true,
+                },
+            ],
+        }),
+        ..Config::default()
+    });
+}
@@ -19,6 +19,10 @@ use {
     clap::ArgMatches,
     rpassword::prompt_password,
     solana_remote_wallet::{
+        external_signer::{
+            ExternalSigner, ExternalSignerError, ExternalSignerLocator, SIGNER_SOURCE_EXTERNAL_TCP,
+            SIGNER_SOURCE_EXTERNAL_UNIX,
+        },
         locator::{Locator as RemoteWalletLocator, LocatorError as RemoteWalletLocatorError},
         remote_keypair::generate_remote_keypair,
         remote_wallet::{maybe_wallet_manager, RemoteWalletError, RemoteWalletManager},
@@ -400,6 +404,7 @@ const SIGNER_SOURCE_FILEPATH: &str = "file";
 const SIGNER_SOURCE_USB: &str = "usb";
 const SIGNER_SOURCE_STDIN: &str = "stdin";
 const SIGNER_SOURCE_PUBKEY: &str = "pubkey";
+const SIGNER_SOURCE_EXTERNAL: &str = "signer";
 
 pub(crate) enum SignerSourceKind {
     Prompt,
@@ -407,6 +412,7 @@ pub(crate) enum SignerSourceKind {
     Usb(RemoteWalletLocator),
     Stdin,
     Pubkey(Pubkey),
+    External(ExternalSignerLocator),
 }
 
 impl AsRef<str> for SignerSourceKind {
@@ -417,6 +423,7 @@ impl AsRef<str> for SignerSourceKind {
             Self::Usb(_) => SIGNER_SOURCE_USB,
             Self::Stdin => SIGNER_SOURCE_STDIN,
             Self::Pubkey(_) => SIGNER_SOURCE_PUBKEY,
+            Self::External(_) => SIGNER_SOURCE_EXTERNAL,
         }
     }
 }
@@ -437,6 +444,8 @@ pub(crate) enum SignerSourceError {
     #[error(transparent)]
     DerivationPathError(#[from] DerivationPathError),
     #[error(transparent)]
+    ExternalSignerError(#[from] ExternalSignerError),
+    #[error(transparent)]
     IoError(#[from] std::io::Error),
 }
 
@@ -483,6 +492,11 @@ pub(crate) fn parse_signer_source<S: AsRef<str>>(
                         legacy: false,
                     }),
                     SIGNER_SOURCE_STDIN => Ok(SignerSource::new(SignerSourceKind::Stdin)),
+                    SIGNER_SOURCE_EXTERNAL_UNIX | SIGNER_SOURCE_EXTERNAL_TCP => {
+                        Ok(SignerSource::new(SignerSourceKind::External(
+                            ExternalSignerLocator::new_from_uri(&uri)?,
+                        )))
+                    }
                     _ => {
                         #[cfg(target_family = "windows")]
                         // On Windows, an absolute path's drive letter will be parsed as the URI
@@ -618,6 +632,19 @@ pub struct SignerFromPathConfig {
 ///   - `usb://ledger/9rPVSygg3brqghvdZ6wsL2i5YNQTGhXGdJzF65YxaCQd`
 ///   - `usb://ledger/9rPVSygg3brqghvdZ6wsL2i5YNQTGhXGdJzF65YxaCQd?key=0/0`
 ///
+/// - `signer+unix:` and `signer+tcp:` &mdash; Sign with a key held by an
+///   external signing daemon, reached over a unix socket named by the URI
+///   path, or over TCP at the URI host and port. The `key=` query parameter
+///   selects one of the daemon's keys, and the `pubkey=` query parameter pins
+///   the base-58 pubkey the daemon is expected to report. See
+///   [`solana_remote_wallet::external_signer`] for the protocol spoken with
+///   the daemon.
+///
+///   Examples:
+///
+///   - `signer+unix:///run/signer.sock`
+///   - `signer+tcp://127.0.0.1:7700?key=withdraw-authority`
+///
 /// Next the `path` argument may be one of the following strings:
 ///
 /// - `-` &mdash; Read the keypair from stdin. This is the same as the `stdin:`
@@ -817,6 +844,10 @@ pub fn signer_from_path_with_config(
                 .into())
             }
         }
+        SignerSourceKind::External(locator) => Ok(Box::new(ExternalSigner::new(
+            locator,
+            matches.is_present("confirm_key"),
+        )?)),
     }
 }
 
@@ -930,6 +961,10 @@ pub fn resolve_signer_from_path(
                 Err(RemoteWalletError::NoDeviceFound.into())
             }
         }
+        SignerSourceKind::External(locator) => {
+            let signer = ExternalSigner::new(locator, matches.is_present("confirm_key"))?;
+            Ok(Some(signer.locator.to_string()))
+        }
         _ => Ok(Some(path.to_string())),
     }
 }
@@ -1123,7 +1158,10 @@ mod tests {
         crate::offline::OfflineArgs,
         assert_matches::assert_matches,
         clap::{value_t_or_exit, App, Arg},
-        solana_remote_wallet::{locator::Manufacturer, remote_wallet::initialize_wallet_manager},
+        solana_remote_wallet::{
+            external_signer::ExternalSignerAddress, locator::Manufacturer,
+            remote_wallet::initialize_wallet_manager,
+        },
         solana_sdk::{signer::keypair::write_keypair_file, system_instruction},
         tempfile::{NamedTempFile, TempDir},
     };
@@ -1253,6 +1291,30 @@ mod tests {
                 derivation_path: d,
                 legacy: false,
             } if u == expected_locator && d == expected_derivation_path);
+        let external = "signer+tcp://127.0.0.1:7700?key=withdraw".to_string();
+        let expected_locator = ExternalSignerLocator {
+            address: ExternalSignerAddress::Tcp("127.0.0.1:7700".to_string()),
+            key_id: Some("withdraw".to_string()),
+            pubkey: None,
+        };
+        assert_matches!(parse_signer_source(external).unwrap(), SignerSource {
+                kind: SignerSourceKind::External(l),
+                derivation_path: None,
+                legacy: false,
+            } if l == expected_locator);
+        let external = "signer+unix:///run/signer.sock".to_string();
+        assert_matches!(parse_signer_source(external).unwrap(), SignerSource {
+                kind: SignerSourceKind::External(ExternalSignerLocator {
+                    address: ExternalSignerAddress::Unix(p),
+                    ..
+                }),
+                derivation_path: None,
+                legacy: false,
+            } if p == std::path::Path::new("/run/signer.sock"));
+        assert_matches!(
+            parse_signer_source("signer+tcp://127.0.0.1"),
+            Err(SignerSourceError::ExternalSignerError(_))
+        );
         // Catchall into SignerSource::Filepath fails
         let junk = "sometextthatisnotapubkeyorfile".to_string();
         assert!(Pubkey::from_str(&junk).is_err());
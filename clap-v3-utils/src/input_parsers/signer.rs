@@ -6,6 +6,10 @@ use {
     },
     clap::{builder::ValueParser, ArgMatches},
     solana_remote_wallet::{
+        external_signer::{
+            ExternalSignerError, ExternalSignerLocator, SIGNER_SOURCE_EXTERNAL_TCP,
+            SIGNER_SOURCE_EXTERNAL_UNIX,
+        },
         locator::{Locator as RemoteWalletLocator, LocatorError as RemoteWalletLocatorError},
         remote_wallet::RemoteWalletManager,
     },
@@ -23,6 +27,7 @@ const SIGNER_SOURCE_FILEPATH: &str = "file";
 const SIGNER_SOURCE_USB: &str = "usb";
 const SIGNER_SOURCE_STDIN: &str = "stdin";
 const SIGNER_SOURCE_PUBKEY: &str = "pubkey";
+const SIGNER_SOURCE_EXTERNAL: &str = "signer";
 
 #[derive(Debug, Error)]
 pub enum SignerSourceError {
@@ -33,6 +38,8 @@ pub enum SignerSourceError {
     #[error(transparent)]
     DerivationPathError(#[from] DerivationPathError),
     #[error(transparent)]
+    ExternalSignerError(#[from] ExternalSignerError),
+    #[error(transparent)]
     IoError(#[from] std::io::Error),
     #[error("unsupported source")]
     UnsupportedSource,
@@ -45,6 +52,7 @@ pub enum SignerSourceKind {
     Usb(RemoteWalletLocator),
     Stdin,
     Pubkey(Pubkey),
+    External(ExternalSignerLocator),
 }
 
 impl AsRef<str> for SignerSourceKind {
@@ -55,6 +63,7 @@ impl AsRef<str> for SignerSourceKind {
             Self::Usb(_) => SIGNER_SOURCE_USB,
             Self::Stdin => SIGNER_SOURCE_STDIN,
             Self::Pubkey(_) => SIGNER_SOURCE_PUBKEY,
+            Self::External(_) => SIGNER_SOURCE_EXTERNAL,
         }
     }
 }
@@ -237,6 +246,11 @@ impl SignerSource {
                             legacy: false,
                         }),
                         SIGNER_SOURCE_STDIN => Ok(SignerSource::new(SignerSourceKind::Stdin)),
+                        SIGNER_SOURCE_EXTERNAL_UNIX | SIGNER_SOURCE_EXTERNAL_TCP => {
+                            Ok(SignerSource::new(SignerSourceKind::External(
+                                ExternalSignerLocator::new_from_uri(&uri)?,
+                            )))
+                        }
                         _ => {
                             #[cfg(target_family = "windows")]
                             // On Windows, an absolute path's drive letter will be parsed as the URI
@@ -274,6 +288,7 @@ pub struct SignerSourceParserBuilder {
     allow_usb: bool,
     allow_stdin: bool,
     allow_pubkey: bool,
+    allow_external: bool,
     allow_legacy: bool,
 }
 
@@ -284,6 +299,7 @@ impl SignerSourceParserBuilder {
         self.allow_usb = true;
         self.allow_stdin = true;
         self.allow_pubkey = true;
+        self.allow_external = true;
         self.allow_legacy = true;
         self
     }
@@ -313,6 +329,11 @@ impl SignerSourceParserBuilder {
         self
     }
 
+    pub fn allow_external(mut self) -> Self {
+        self.allow_external = true;
+        self
+    }
+
     pub fn allow_legacy(mut self) -> Self {
         self.allow_legacy = true;
         self
@@ -331,6 +352,7 @@ impl SignerSourceParserBuilder {
                     SignerSourceKind::Usb(_) if self.allow_usb => Ok(signer_source),
                     SignerSourceKind::Stdin if self.allow_stdin => Ok(signer_source),
                     SignerSourceKind::Pubkey(_) if self.allow_pubkey => Ok(signer_source),
+                    SignerSourceKind::External(_) if self.allow_external => Ok(signer_source),
                     _ => Err(SignerSourceError::UnsupportedSource),
                 }
             },
@@ -534,7 +556,7 @@ mod tests {
         crate::input_parsers::{keypair_of, pubkey_of, pubkeys_of},
         assert_matches::assert_matches,
         clap::{Arg, ArgAction, Command},
-        solana_remote_wallet::locator::Manufacturer,
+        solana_remote_wallet::{external_signer::ExternalSignerAddress, locator::Manufacturer},
         solana_sdk::signature::write_keypair_file,
         std::fs,
         tempfile::NamedTempFile,
@@ -625,6 +647,21 @@ mod tests {
                 derivation_path: d,
                 legacy: false,
             } if u == expected_locator && d == expected_derivation_path);
+        let external = "signer+tcp://127.0.0.1:7700?key=withdraw".to_string();
+        let expected_locator = ExternalSignerLocator {
+            address: ExternalSignerAddress::Tcp("127.0.0.1:7700".to_string()),
+            key_id: Some("withdraw".to_string()),
+            pubkey: None,
+        };
+        assert_matches!(SignerSource::parse(external).unwrap(), SignerSource {
+                kind: SignerSourceKind::External(l),
+                derivation_path: None,
+                legacy: false,
+            } if l == expected_locator);
+        assert_matches!(
+            SignerSource::parse("signer+tcp://127.0.0.1"),
+            Err(SignerSourceError::ExternalSignerError(_))
+        );
         // Catchall into SignerSource::Filepath fails
         let junk = "sometextthatisnotapubkeyorfile".to_string();
         assert!(Pubkey::from_str(&junk).is_err());
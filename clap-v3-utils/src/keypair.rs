@@ -19,6 +19,7 @@ use {
     clap::ArgMatches,
     rpassword::prompt_password,
     solana_remote_wallet::{
+        external_signer::ExternalSigner,
         remote_keypair::generate_remote_keypair,
         remote_wallet::{maybe_wallet_manager, RemoteWalletError, RemoteWalletManager},
     },
@@ -476,6 +477,19 @@ pub struct SignerFromPathConfig {
 ///   - `usb://ledger/9rPVSygg3brqghvdZ6wsL2i5YNQTGhXGdJzF65YxaCQd`
 ///   - `usb://ledger/9rPVSygg3brqghvdZ6wsL2i5YNQTGhXGdJzF65YxaCQd?key=0/0`
 ///
+/// - `signer+unix:` and `signer+tcp:` &mdash; Sign with a key held by an
+///   external signing daemon, reached over a unix socket named by the URI
+///   path, or over TCP at the URI host and port. The `key=` query parameter
+///   selects one of the daemon's keys, and the `pubkey=` query parameter pins
+///   the base-58 pubkey the daemon is expected to report. See
+///   [`solana_remote_wallet::external_signer`] for the protocol spoken with
+///   the daemon.
+///
+///   Examples:
+///
+///   - `signer+unix:///run/signer.sock`
+///   - `signer+tcp://127.0.0.1:7700?key=withdraw-authority`
+///
 /// Next the `path` argument may be one of the following strings:
 ///
 /// - `-` &mdash; Read the keypair from stdin. This is the same as the `stdin:`
@@ -697,6 +711,10 @@ pub fn signer_from_source_with_config(
                 .into())
             }
         }
+        SignerSourceKind::External(locator) => {
+            let confirm_key = matches.try_contains_id("confirm_key").unwrap_or(false);
+            Ok(Box::new(ExternalSigner::new(locator.clone(), confirm_key)?))
+        }
     }
 }
 
@@ -831,6 +849,11 @@ pub fn resolve_signer_from_source(
             }
         }
         SignerSourceKind::Pubkey(pubkey) => Ok(Some(pubkey.to_string())),
+        SignerSourceKind::External(locator) => {
+            let confirm_key = matches.try_contains_id("confirm_key").unwrap_or(false);
+            let signer = ExternalSigner::new(locator.clone(), confirm_key)?;
+            Ok(Some(signer.locator.to_string()))
+        }
     }
 }
 
@@ -239,6 +239,7 @@ mod tests {
             endpoints: mut response_recv_endpoints,
             thread: response_recv_thread,
             key_updater: _,
+            connection_stats_table: _,
         } = solana_streamer::quic::spawn_server(
             "solQuicTest",
             "quic_streamer_test",
@@ -6,6 +6,7 @@ use {
     solana_gossip::cluster_info::ClusterInfo,
     solana_runtime::bank_forks::BankForks,
     solana_sdk::{pubkey::Pubkey, quic::NotifyKeyUpdate},
+    solana_streamer::nonblocking::connection_stats::ConnectionStatsTable,
     std::{
         collections::HashSet,
         net::UdpSocket,
@@ -23,4 +24,6 @@ pub struct AdminRpcRequestMetadataPostInit {
     pub repair_socket: Arc<UdpSocket>,
     pub outstanding_repair_requests: Arc<RwLock<OutstandingRequests<ShredRepairType>>>,
     pub cluster_slots: Arc<ClusterSlots>,
+    pub tpu_quic_connection_stats: Arc<ConnectionStatsTable>,
+    pub tpu_forwards_quic_connection_stats: Arc<ConnectionStatsTable>,
 }
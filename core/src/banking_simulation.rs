@@ -0,0 +1,565 @@
+//! The `banking_simulation` module replays banking trace events recorded by
+//! [`BankingTracer`] against a real [`BankingStage`], so that block production
+//! can be reproduced offline with the same packet arrival timing as observed
+//! by the traced validator.
+use {
+    crate::{
+        banking_stage::BankingStage,
+        banking_trace::{
+            BankingPacketBatch, BankingTracer, ChannelLabel, TimedTracedEvent, TracedEvent,
+            TracedSender, BASENAME,
+        },
+        validator::BlockProductionMethod,
+    },
+    crossbeam_channel::unbounded,
+    solana_client::connection_cache::ConnectionCache,
+    solana_entry::entry::Entry,
+    solana_gossip::{cluster_info::ClusterInfo, contact_info::ContactInfo},
+    solana_ledger::{
+        blockstore::{Blockstore, BlockstoreError},
+        leader_schedule_cache::LeaderScheduleCache,
+        shred::{self, ProcessShredsStats, ReedSolomonCache, Shredder},
+    },
+    solana_poh::{
+        poh_recorder::PohRecorder,
+        poh_service::{PohService, DEFAULT_HASHES_PER_BATCH, DEFAULT_PINNED_CPU_CORE},
+    },
+    solana_runtime::{
+        bank::Bank, bank_forks::BankForks, prioritization_fee_cache::PrioritizationFeeCache,
+    },
+    solana_sdk::{
+        clock::{Slot, NUM_CONSECUTIVE_LEADER_SLOTS},
+        genesis_config::GenesisConfig,
+        hash::Hash,
+        shred_version::compute_shred_version,
+        signature::{Keypair, Signer},
+        timing::timestamp,
+    },
+    solana_streamer::socket::SocketAddrSpace,
+    std::{
+        collections::{BTreeMap, HashMap},
+        fs::{read_dir, File},
+        io::{self, BufReader},
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, RwLock,
+        },
+        thread::{self, sleep, JoinHandle},
+        time::{Duration, Instant, SystemTime},
+    },
+    thiserror::Error,
+};
+
+/// How long before the first simulated slot packets are fed into the banking
+/// stage. The traced validator kept buffering packets before its leader slot
+/// started, so this reproduces the buffered state at the start of the slot.
+const WARMUP_DURATION: Duration = Duration::from_millis(400);
+const BANK_COMPLETION_POLL_INTERVAL: Duration = Duration::from_millis(10);
+const SENDER_MAX_SLEEP: Duration = Duration::from_millis(100);
+pub const DEFAULT_SIMULATED_SLOT_COUNT: u64 = NUM_CONSECUTIVE_LEADER_SLOTS;
+
+#[derive(Error, Debug)]
+pub enum SimulateError {
+    #[error("IO Error: {0}")]
+    IoError(#[from] io::Error),
+
+    #[error("Deserialization Error: {0}")]
+    DeserializeError(#[from] bincode::Error),
+
+    #[error("No banking trace events were found in {0:?}")]
+    NoEvents(PathBuf),
+
+    #[error("Banking trace has no leader slot hash event preceding slot {0}")]
+    NoParentHashEvent(Slot),
+
+    #[error("Parent bank (slot: {0}) isn't found in bank forks")]
+    ParentBankNotFound(Slot),
+
+    #[error("Shred Error: {0}")]
+    ShredError(#[from] shred::Error),
+
+    #[error("Blockstore Error: {0}")]
+    BlockstoreError(#[from] BlockstoreError),
+}
+
+/// The hash event recorded by the traced validator right before it started
+/// one of its leader slots on top of the given parent slot.
+#[derive(Debug, Clone, Copy)]
+struct ParentHashEvent {
+    time: SystemTime,
+    blockhash: Hash,
+    bank_hash: Hash,
+}
+
+/// All of the events loaded from a banking trace directory, ordered by the
+/// time they were recorded.
+pub struct BankingTraceEvents {
+    packet_batches: Vec<(SystemTime, ChannelLabel, BankingPacketBatch)>,
+    hash_events: BTreeMap<Slot, ParentHashEvent>,
+}
+
+impl BankingTraceEvents {
+    /// Loads every rotated event file (`events`, `events.1`, ...) found in
+    /// `trace_dir`.
+    pub fn load(trace_dir: &Path) -> Result<Self, SimulateError> {
+        let mut event_file_paths = read_dir(trace_dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .map(|name| name.starts_with(BASENAME))
+                        .unwrap_or(false)
+            })
+            .collect::<Vec<_>>();
+        event_file_paths.sort();
+
+        let mut events = Self {
+            packet_batches: vec![],
+            hash_events: BTreeMap::new(),
+        };
+        for event_file_path in &event_file_paths {
+            info!("loading banking trace events from {event_file_path:?}");
+            events.read_event_file(event_file_path)?;
+        }
+        if events.packet_batches.is_empty() && events.hash_events.is_empty() {
+            return Err(SimulateError::NoEvents(trace_dir.to_path_buf()));
+        }
+        // rotated files are read in arbitrary order; sort_by_key() is stable so
+        // batches sharing the same timestamp keep their recorded order.
+        events
+            .packet_batches
+            .sort_by_key(|(event_time, _label, _batch)| *event_time);
+        info!(
+            "loaded {} packet batches and {} hash events",
+            events.packet_batches.len(),
+            events.hash_events.len()
+        );
+
+        Ok(events)
+    }
+
+    fn read_event_file(&mut self, event_file_path: &Path) -> Result<(), SimulateError> {
+        let mut reader = BufReader::new(File::open(event_file_path)?);
+        loop {
+            let TimedTracedEvent(event_time, event) =
+                match bincode::deserialize_from::<_, TimedTracedEvent>(&mut reader) {
+                    Ok(event) => event,
+                    Err(err) => match *err {
+                        // reached the end of file; possibly with a partially-written
+                        // event due to an unclean shutdown of the traced validator
+                        bincode::ErrorKind::Io(ref io_err)
+                            if io_err.kind() == io::ErrorKind::UnexpectedEof =>
+                        {
+                            break;
+                        }
+                        _ => return Err(err.into()),
+                    },
+                };
+            match event {
+                TracedEvent::PacketBatch(label, batch) => {
+                    self.packet_batches.push((event_time, label, batch));
+                }
+                TracedEvent::BlockAndBankHash(slot, blockhash, bank_hash) => {
+                    self.hash_events.insert(
+                        slot,
+                        ParentHashEvent {
+                            time: event_time,
+                            blockhash,
+                            bank_hash,
+                        },
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the parent slot which the traced validator built `slot` upon,
+    /// as indicated by the latest hash event recorded before `slot`.
+    pub fn parent_slot_of(&self, slot: Slot) -> Option<Slot> {
+        self.hash_events
+            .range(..slot)
+            .next_back()
+            .map(|(parent_slot, _event)| *parent_slot)
+    }
+
+    /// Returns the parent slots of the leader slots started by the traced
+    /// validator.
+    pub fn hash_event_slots(&self) -> impl Iterator<Item = Slot> + '_ {
+        self.hash_events.keys().copied()
+    }
+}
+
+/// A block produced by [`BankingSimulator`], along with its frozen bank.
+pub struct SimulatedBlock {
+    pub bank: Arc<Bank>,
+    pub entries: Vec<Entry>,
+}
+
+pub struct BankingSimulator {
+    banking_trace_events: BankingTraceEvents,
+    first_simulated_slot: Slot,
+    simulated_slot_count: u64,
+}
+
+impl BankingSimulator {
+    pub fn new(
+        banking_trace_events: BankingTraceEvents,
+        first_simulated_slot: Slot,
+        simulated_slot_count: u64,
+    ) -> Self {
+        assert!(simulated_slot_count > 0);
+        Self {
+            banking_trace_events,
+            first_simulated_slot,
+            simulated_slot_count,
+        }
+    }
+
+    pub fn parent_slot(&self) -> Result<Slot, SimulateError> {
+        self.banking_trace_events
+            .parent_slot_of(self.first_simulated_slot)
+            .ok_or(SimulateError::NoParentHashEvent(self.first_simulated_slot))
+    }
+
+    /// Runs a real banking stage as the leader of the simulated slots, feeding
+    /// it the traced packets with their original timing. The produced blocks
+    /// are shredded and inserted into `blockstore`, which should be separate
+    /// from the one the banks were loaded from.
+    pub fn start(
+        self,
+        genesis_config: &GenesisConfig,
+        bank_forks: Arc<RwLock<BankForks>>,
+        blockstore: Arc<Blockstore>,
+        block_production_method: BlockProductionMethod,
+    ) -> Result<Vec<SimulatedBlock>, SimulateError> {
+        let parent_slot = self.parent_slot()?;
+        let parent_hash_event = self.banking_trace_events.hash_events[&parent_slot];
+        let parent_bank = bank_forks
+            .read()
+            .unwrap()
+            .get(parent_slot)
+            .ok_or(SimulateError::ParentBankNotFound(parent_slot))?;
+        if parent_bank.last_blockhash() != parent_hash_event.blockhash
+            || parent_bank.hash() != parent_hash_event.bank_hash
+        {
+            warn!(
+                "parent bank (slot: {parent_slot}) differs from the traced one: blockhash {} vs \
+                 {}, bank hash {} vs {}; simulation may be inaccurate",
+                parent_bank.last_blockhash(),
+                parent_hash_event.blockhash,
+                parent_bank.hash(),
+                parent_hash_event.bank_hash,
+            );
+        }
+        let first_simulated_slot = self.first_simulated_slot;
+        let last_simulated_slot = first_simulated_slot + self.simulated_slot_count - 1;
+        info!(
+            "simulating block production for slots {first_simulated_slot}..={last_simulated_slot} \
+             (parent: {parent_slot})"
+        );
+
+        let exit = Arc::new(AtomicBool::default());
+        let leader_schedule_cache = Arc::new(LeaderScheduleCache::new_from_bank(&parent_bank));
+        let poh_config = &genesis_config.poh_config;
+        let (poh_recorder, entry_receiver, record_receiver) = PohRecorder::new(
+            parent_bank.tick_height(),
+            parent_bank.last_blockhash(),
+            parent_bank.clone(),
+            Some((first_simulated_slot, first_simulated_slot)),
+            parent_bank.ticks_per_slot(),
+            blockstore.clone(),
+            &leader_schedule_cache,
+            poh_config,
+            exit.clone(),
+        );
+        let poh_recorder = Arc::new(RwLock::new(poh_recorder));
+        let poh_service = PohService::new(
+            poh_recorder.clone(),
+            poh_config,
+            exit.clone(),
+            parent_bank.ticks_per_slot(),
+            DEFAULT_PINNED_CPU_CORE,
+            DEFAULT_HASHES_PER_BATCH,
+            record_receiver,
+        );
+
+        // packets are fed into the banking stage directly, so tracing of them is
+        // never needed.
+        let banking_tracer = BankingTracer::new_disabled();
+        let (non_vote_sender, non_vote_receiver) = banking_tracer.create_channel_non_vote();
+        let (tpu_vote_sender, tpu_vote_receiver) = banking_tracer.create_channel_tpu_vote();
+        let (gossip_vote_sender, gossip_vote_receiver) =
+            banking_tracer.create_channel_gossip_vote();
+        let keypair = Arc::new(Keypair::new());
+        let cluster_info = Arc::new(ClusterInfo::new(
+            ContactInfo::new_localhost(&keypair.pubkey(), timestamp()),
+            keypair,
+            SocketAddrSpace::Unspecified,
+        ));
+        let (replay_vote_sender, _replay_vote_receiver) = unbounded();
+        let banking_stage = BankingStage::new(
+            block_production_method,
+            &cluster_info,
+            &poh_recorder,
+            non_vote_receiver,
+            tpu_vote_receiver,
+            gossip_vote_receiver,
+            None,
+            replay_vote_sender,
+            None,
+            Arc::new(ConnectionCache::new("connection_cache_banking_simulation")),
+            bank_forks.clone(),
+            &Arc::new(PrioritizationFeeCache::new(0u64)),
+            false,
+        );
+
+        let sender_thread = self.spawn_sender_thread(
+            parent_hash_event.time,
+            non_vote_sender,
+            tpu_vote_sender,
+            gossip_vote_sender,
+            exit.clone(),
+        )?;
+        sleep(WARMUP_DURATION);
+
+        let mut bank = parent_bank;
+        let mut simulated_banks = Vec::with_capacity(self.simulated_slot_count as usize);
+        for slot in first_simulated_slot..=last_simulated_slot {
+            let collector_id = leader_schedule_cache
+                .slot_leader_at(slot, Some(&bank))
+                .unwrap_or_else(|| *bank.collector_id());
+            let new_bank = Bank::new_from_parent(bank.clone(), &collector_id, slot);
+            let new_bank = bank_forks.write().unwrap().insert(new_bank);
+            info!(
+                "new fork:{} parent:{} (simulated leader)",
+                slot,
+                bank.slot()
+            );
+            poh_recorder
+                .write()
+                .unwrap()
+                .set_bank(new_bank.clone_with_scheduler(), false);
+            bank = new_bank.clone_without_scheduler();
+
+            while !bank.is_complete() {
+                sleep(BANK_COMPLETION_POLL_INTERVAL);
+            }
+            // freezing blocks until the banking stage releases its freeze lock
+            // after committing the last recorded transactions.
+            bank.freeze();
+            info!(
+                "simulated slot {} frozen: bank hash {}, {} executed transactions",
+                slot,
+                bank.hash(),
+                bank.executed_transaction_count()
+            );
+            let next_leader_slot = (slot < last_simulated_slot).then_some((slot + 1, slot + 1));
+            poh_recorder
+                .write()
+                .unwrap()
+                .reset(bank.clone(), next_leader_slot);
+            simulated_banks.push(bank.clone());
+        }
+
+        exit.store(true, Ordering::Relaxed);
+        sender_thread.join().unwrap();
+        banking_stage.join().unwrap();
+        poh_service.join().unwrap();
+        drop(poh_recorder);
+
+        let mut entries_by_slot: HashMap<Slot, Vec<Entry>> = HashMap::new();
+        for (working_bank, (entry, _tick_height)) in entry_receiver.try_iter() {
+            entries_by_slot
+                .entry(working_bank.slot())
+                .or_default()
+                .push(entry);
+        }
+
+        let shred_keypair = Keypair::new();
+        let reed_solomon_cache = ReedSolomonCache::default();
+        simulated_banks
+            .into_iter()
+            .map(|bank| {
+                let entries = entries_by_slot.remove(&bank.slot()).unwrap_or_default();
+                Self::insert_simulated_block(
+                    &blockstore,
+                    genesis_config,
+                    &bank,
+                    &entries,
+                    &shred_keypair,
+                    &reed_solomon_cache,
+                )?;
+                Ok(SimulatedBlock { bank, entries })
+            })
+            .collect()
+    }
+
+    fn spawn_sender_thread(
+        &self,
+        leader_slot_start_time: SystemTime,
+        non_vote_sender: TracedSender,
+        tpu_vote_sender: TracedSender,
+        gossip_vote_sender: TracedSender,
+        exit: Arc<AtomicBool>,
+    ) -> Result<JoinHandle<()>, SimulateError> {
+        let warmup_start_time = leader_slot_start_time
+            .checked_sub(WARMUP_DURATION)
+            .unwrap_or(leader_slot_start_time);
+        let packet_batches = self
+            .banking_trace_events
+            .packet_batches
+            .iter()
+            .filter(|(event_time, _label, _batch)| *event_time >= warmup_start_time)
+            .cloned()
+            .collect::<Vec<_>>();
+        info!(
+            "sending {} traced packet batches to the banking stage",
+            packet_batches.len()
+        );
+
+        let thread = thread::Builder::new()
+            .name("solSimSender".into())
+            .spawn(move || {
+                let simulation_start = Instant::now();
+                for (event_time, label, batch) in packet_batches {
+                    let send_at = event_time
+                        .duration_since(warmup_start_time)
+                        .unwrap_or_default();
+                    loop {
+                        if exit.load(Ordering::Relaxed) {
+                            return;
+                        }
+                        let elapsed = simulation_start.elapsed();
+                        if elapsed >= send_at {
+                            break;
+                        }
+                        sleep((send_at - elapsed).min(SENDER_MAX_SLEEP));
+                    }
+
+                    let sender = match label {
+                        ChannelLabel::NonVote => &non_vote_sender,
+                        ChannelLabel::TpuVote => &tpu_vote_sender,
+                        ChannelLabel::GossipVote => &gossip_vote_sender,
+                        ChannelLabel::Dummy => continue,
+                    };
+                    if sender.send(batch).is_err() {
+                        // the banking stage has already been terminated
+                        return;
+                    }
+                }
+            })?;
+
+        Ok(thread)
+    }
+
+    fn insert_simulated_block(
+        blockstore: &Blockstore,
+        genesis_config: &GenesisConfig,
+        bank: &Bank,
+        entries: &[Entry],
+        keypair: &Keypair,
+        reed_solomon_cache: &ReedSolomonCache,
+    ) -> Result<(), SimulateError> {
+        let shred_version = compute_shred_version(&genesis_config.hash(), Some(&bank.hard_forks()));
+        let reference_tick = u8::try_from(bank.ticks_per_slot()).unwrap_or(u8::MAX);
+        let (data_shreds, coding_shreds) = Shredder::new(
+            bank.slot(),
+            bank.parent_slot(),
+            reference_tick,
+            shred_version,
+        )?
+        .entries_to_shreds(
+            keypair,
+            entries,
+            true, // is_last_in_slot
+            None, // chained_merkle_root
+            0,    // next_shred_index
+            0,    // next_code_index
+            true, // merkle_variant
+            reed_solomon_cache,
+            &mut ProcessShredsStats::default(),
+        );
+        // the shreds aren't signed by the actual leader, so insert them as trusted
+        blockstore.insert_shreds(
+            data_shreds.into_iter().chain(coding_shreds).collect(),
+            None,
+            true,
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::banking_trace::{
+            for_test, receiving_loop_with_minimized_sender_overhead, DirByteLimit, TraceError,
+        },
+        tempfile::TempDir,
+    };
+
+    #[test]
+    fn test_load_banking_trace_events() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("banking-trace");
+        let exit = Arc::<AtomicBool>::default();
+        let (tracer, tracer_thread) =
+            BankingTracer::new(Some((&path, exit.clone(), DirByteLimit::MAX))).unwrap();
+        let (non_vote_sender, non_vote_receiver) = tracer.create_channel_non_vote();
+
+        let dummy_main_thread = thread::spawn(move || {
+            receiving_loop_with_minimized_sender_overhead::<_, TraceError, 0>(
+                exit,
+                non_vote_receiver,
+                |_packet_batch| Ok(()),
+            )
+        });
+
+        tracer.hash_event(3, &Hash::new_unique(), &Hash::new_unique());
+        non_vote_sender
+            .send(for_test::sample_packet_batch())
+            .unwrap();
+        non_vote_sender
+            .send(for_test::sample_packet_batch())
+            .unwrap();
+        tracer.hash_event(7, &Hash::new_unique(), &Hash::new_unique());
+
+        for_test::terminate_tracer(
+            tracer,
+            tracer_thread,
+            dummy_main_thread,
+            non_vote_sender,
+            None,
+        );
+
+        let events = BankingTraceEvents::load(&path).unwrap();
+        assert_eq!(events.packet_batches.len(), 2);
+        assert!(events
+            .packet_batches
+            .iter()
+            .all(|(_time, label, _batch)| matches!(label, ChannelLabel::NonVote)));
+        assert_eq!(events.hash_event_slots().collect::<Vec<_>>(), vec![3, 7]);
+        assert_eq!(events.parent_slot_of(3), None);
+        assert_eq!(events.parent_slot_of(4), Some(3));
+        assert_eq!(events.parent_slot_of(7), Some(3));
+        assert_eq!(events.parent_slot_of(8), Some(7));
+
+        for_test::drop_and_clean_temp_dir_unless_suppressed(temp_dir);
+    }
+
+    #[test]
+    fn test_load_banking_trace_events_empty_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        assert_matches!(
+            BankingTraceEvents::load(temp_dir.path()),
+            Err(SimulateError::NoEvents(_))
+        );
+    }
+}
@@ -37,7 +37,10 @@ use {
     },
     solana_sdk::{clock::Slot, pubkey::Pubkey, quic::NotifyKeyUpdate, signature::Keypair},
     solana_streamer::{
-        nonblocking::quic::{DEFAULT_MAX_STREAMS_PER_MS, DEFAULT_WAIT_FOR_CHUNK_TIMEOUT},
+        nonblocking::{
+            connection_stats::ConnectionStatsTable,
+            quic::{DEFAULT_MAX_STREAMS_PER_MS, DEFAULT_WAIT_FOR_CHUNK_TIMEOUT},
+        },
         quic::{
             spawn_server_multi, SpawnServerResult, MAX_STAKED_CONNECTIONS, MAX_UNSTAKED_CONNECTIONS,
         },
@@ -75,6 +78,8 @@ pub struct Tpu {
     broadcast_stage: BroadcastStage,
     tpu_quic_t: thread::JoinHandle<()>,
     tpu_forwards_quic_t: thread::JoinHandle<()>,
+    tpu_quic_connection_stats: Arc<ConnectionStatsTable>,
+    tpu_forwards_quic_connection_stats: Arc<ConnectionStatsTable>,
     tpu_entry_notifier: Option<TpuEntryNotifier>,
     staked_nodes_updater_service: StakedNodesUpdaterService,
     tracer_thread_hdl: TracerThread,
@@ -159,6 +164,7 @@ impl Tpu {
             endpoints: _,
             thread: tpu_quic_t,
             key_updater,
+            connection_stats_table: tpu_quic_connection_stats,
         } = spawn_server_multi(
             "solQuicTpu",
             "quic_streamer_tpu",
@@ -181,6 +187,7 @@ impl Tpu {
             endpoints: _,
             thread: tpu_forwards_quic_t,
             key_updater: forwards_key_updater,
+            connection_stats_table: tpu_forwards_quic_connection_stats,
         } = spawn_server_multi(
             "solQuicTpuFwd",
             "quic_streamer_tpu_forwards",
@@ -285,6 +292,8 @@ impl Tpu {
                 broadcast_stage,
                 tpu_quic_t,
                 tpu_forwards_quic_t,
+                tpu_quic_connection_stats,
+                tpu_forwards_quic_connection_stats,
                 tpu_entry_notifier,
                 staked_nodes_updater_service,
                 tracer_thread_hdl,
@@ -293,6 +302,16 @@ impl Tpu {
         )
     }
 
+    /// Per-connection statistics of the TPU QUIC server.
+    pub fn quic_connection_stats(&self) -> &Arc<ConnectionStatsTable> {
+        &self.tpu_quic_connection_stats
+    }
+
+    /// Per-connection statistics of the TPU forwards QUIC server.
+    pub fn forwards_quic_connection_stats(&self) -> &Arc<ConnectionStatsTable> {
+        &self.tpu_forwards_quic_connection_stats
+    }
+
     pub fn join(self) -> thread::Result<()> {
         let results = vec![
             self.fetch_stage.join(),
@@ -233,6 +233,9 @@ pub struct ValidatorConfig {
     pub repair_validators: Option<HashSet<Pubkey>>, // None = repair from all
     pub repair_whitelist: Arc<RwLock<HashSet<Pubkey>>>, // Empty = repair with all
     pub gossip_validators: Option<HashSet<Pubkey>>, // None = gossip with all
+    /// Advertise a gossip QUIC address and exchange gossip over QUIC with the
+    /// nodes which also advertise one.
+    pub enable_gossip_quic: bool,
     pub accounts_hash_interval_slots: u64,
     pub max_genesis_archive_unpacked_size: u64,
     pub wal_recovery_mode: Option<BlockstoreRecoveryMode>,
@@ -274,6 +277,7 @@ pub struct ValidatorConfig {
     pub generator_config: Option<GeneratorConfig>,
     pub use_snapshot_archives_at_startup: UseSnapshotArchivesAtStartup,
     pub wen_restart_proto_path: Option<PathBuf>,
+    pub wen_restart_dry_run: bool,
     pub unified_scheduler_handler_threads: Option<usize>,
     pub ip_echo_server_threads: NonZeroUsize,
     pub replay_forks_threads: NonZeroUsize,
@@ -307,6 +311,7 @@ impl Default for ValidatorConfig {
             repair_validators: None,
             repair_whitelist: Arc::new(RwLock::new(HashSet::default())),
             gossip_validators: None,
+            enable_gossip_quic: false,
             accounts_hash_interval_slots: u64::MAX,
             max_genesis_archive_unpacked_size: MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
             wal_recovery_mode: None,
@@ -346,6 +351,7 @@ impl Default for ValidatorConfig {
             generator_config: None,
             use_snapshot_archives_at_startup: UseSnapshotArchivesAtStartup::default(),
             wen_restart_proto_path: None,
+            wen_restart_dry_run: false,
             unified_scheduler_handler_threads: None,
             ip_echo_server_threads: NonZeroUsize::new(1).expect("1 is non-zero"),
             replay_forks_threads: NonZeroUsize::new(1).expect("1 is non-zero"),
@@ -763,6 +769,22 @@ impl Validator {
             }
         }
 
+        let gossip_quic_socket = if config.enable_gossip_quic {
+            let gossip_quic_socket = node.sockets.gossip_quic.ok_or_else(|| {
+                ValidatorError::Other(String::from(
+                    "gossip over QUIC is enabled but the node has no gossip QUIC socket",
+                ))
+            })?;
+            let gossip_quic_addr = SocketAddr::new(
+                node.info.gossip()?.ip(),
+                gossip_quic_socket.local_addr()?.port(),
+            );
+            node.info.set_gossip_quic(gossip_quic_addr)?;
+            Some(gossip_quic_socket)
+        } else {
+            None
+        };
+
         let mut cluster_info = ClusterInfo::new(
             node.info.clone(),
             identity_keypair.clone(),
@@ -1141,10 +1163,11 @@ impl Validator {
         let stats_reporter_service =
             StatsReporterService::new(stats_reporter_receiver, exit.clone());
 
-        let gossip_service = GossipService::new(
+        let gossip_service = GossipService::new_with_quic(
             &cluster_info,
             Some(bank_forks.clone()),
             node.sockets.gossip,
+            gossip_quic_socket,
             config.gossip_validators.clone(),
             should_check_duplicate_instance,
             Some(stats_reporter_sender.clone()),
@@ -1392,7 +1415,11 @@ impl Validator {
                 accounts_background_request_sender: accounts_background_request_sender.clone(),
                 genesis_config_hash: genesis_config.hash(),
                 exit: exit.clone(),
+                dry_run: config.wen_restart_dry_run,
             })?;
+            if config.wen_restart_dry_run {
+                return Err(ValidatorError::WenRestartDryRunFinished.into());
+            }
             return Err(ValidatorError::WenRestartFinished.into());
         }
 
@@ -1463,6 +1490,8 @@ impl Validator {
             repair_socket: Arc::new(node.sockets.repair),
             outstanding_repair_requests,
             cluster_slots,
+            tpu_quic_connection_stats: tpu.quic_connection_stats().clone(),
+            tpu_forwards_quic_connection_stats: tpu.forwards_quic_connection_stats().clone(),
         });
 
         Ok(Self {
@@ -2364,6 +2393,9 @@ pub enum ValidatorError {
 
     #[error("Wen Restart finished, please continue with --wait-for-supermajority")]
     WenRestartFinished,
+
+    #[error("Wen Restart dry run finished, no snapshot was generated")]
+    WenRestartDryRunFinished,
 }
 
 // Return if the validator waited on other nodes to start. In this case
@@ -3,6 +3,10 @@ use {
     console::Emoji,
     indicatif::{ProgressBar, ProgressStyle},
     log::*,
+    reqwest::{
+        blocking::{Client, Response},
+        header, StatusCode,
+    },
     solana_runtime::{
         snapshot_hash::SnapshotHash,
         snapshot_package::SnapshotKind,
@@ -10,15 +14,27 @@ use {
     },
     solana_sdk::{clock::Slot, genesis_config::DEFAULT_GENESIS_ARCHIVE},
     std::{
-        fs::{self, File},
-        io::{self, Read},
+        collections::{BTreeSet, VecDeque},
+        fs::{self, File, OpenOptions},
+        io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write},
         net::SocketAddr,
         num::NonZeroUsize,
         path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, AtomicU64, Ordering},
+            Condvar, Mutex,
+        },
+        thread,
         time::{Duration, Instant},
     },
 };
 
+/// Number of byte ranges of a snapshot archive which are downloaded at once
+pub const DEFAULT_PARALLEL_DOWNLOADS: usize = 4;
+
+/// Size of the byte ranges `download_file_parallel` splits a download into
+const DOWNLOAD_RANGE_SIZE: u64 = 32 * 1024 * 1024;
+
 static TRUCK: Emoji = Emoji("🚚 ", "");
 static SPARKLE: Emoji = Emoji("✨ ", "");
 
@@ -60,14 +76,339 @@ pub struct DownloadProgressRecord {
 type DownloadProgressCallback<'a> = Box<dyn FnMut(&DownloadProgressRecord) -> bool + 'a>;
 type DownloadProgressCallbackOption<'a> = Option<DownloadProgressCallback<'a>>;
 
+/// Checks a download as it is written, reading its bytes in order.  Returns an error if the
+/// download is rejected.
+type DownloadVerifier<'a> = &'a (dyn Fn(&mut dyn BufRead) -> Result<(), String> + Sync);
+
+/// Tracks the progress of a download, and reports it to the progress bar (or the log) and to the
+/// caller's callback
+struct DownloadProgress<'e, 'f> {
+    progress_bar: ProgressBar,
+    last_print: Instant,
+    // bytes which were already downloaded by an earlier, interrupted, attempt
+    resumed_bytes: usize,
+    current_bytes: usize,
+    last_print_bytes: usize,
+    download_size: f32,
+    use_progress_bar: bool,
+    start_time: Instant,
+    callback: &'f mut DownloadProgressCallbackOption<'e>,
+    notification_count: u64,
+}
+
+impl<'e, 'f> DownloadProgress<'e, 'f> {
+    fn new(
+        progress_bar: ProgressBar,
+        url: &str,
+        download_size: u64,
+        resumed_bytes: u64,
+        use_progress_bar: bool,
+        callback: &'f mut DownloadProgressCallbackOption<'e>,
+    ) -> Self {
+        if use_progress_bar {
+            progress_bar.set_length(download_size);
+            progress_bar.set_position(resumed_bytes);
+            progress_bar.set_style(
+                ProgressStyle::default_bar()
+                    .template(
+                        "{spinner:.green}{msg_wide}[{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})",
+                    )
+                    .expect("ProgresStyle::template direct input to be correct")
+                    .progress_chars("=> "),
+            );
+            progress_bar.set_message(format!("{TRUCK}Downloading~ {url}"));
+        } else if resumed_bytes > 0 {
+            info!(
+                "Resuming download of {} bytes from {} at byte {}",
+                download_size, url, resumed_bytes
+            );
+        } else {
+            info!("Downloading {} bytes from {}", download_size, url);
+        }
+
+        Self {
+            progress_bar,
+            last_print: Instant::now(),
+            resumed_bytes: resumed_bytes as usize,
+            current_bytes: resumed_bytes as usize,
+            last_print_bytes: resumed_bytes as usize,
+            download_size: (download_size as f32).max(1f32),
+            use_progress_bar,
+            start_time: Instant::now(),
+            callback,
+            notification_count: 0,
+        }
+    }
+
+    /// Records `n` more downloaded bytes.  Returns `false` if the caller aborted the download.
+    fn update(&mut self, n: usize) -> bool {
+        self.current_bytes += n;
+        let total_bytes_f32 = self.current_bytes as f32;
+        let diff_bytes_f32 = (self.current_bytes - self.last_print_bytes) as f32;
+        let last_throughput = diff_bytes_f32 / self.last_print.elapsed().as_secs_f32();
+        let estimated_remaining_time = if last_throughput > 0_f32 {
+            (self.download_size - self.current_bytes as f32) / last_throughput
+        } else {
+            f32::MAX
+        };
+
+        let mut progress_record = DownloadProgressRecord {
+            elapsed_time: self.start_time.elapsed(),
+            last_elapsed_time: self.last_print.elapsed(),
+            last_throughput,
+            total_throughput: (self.current_bytes - self.resumed_bytes) as f32
+                / self.start_time.elapsed().as_secs_f32(),
+            total_bytes: self.download_size as usize,
+            current_bytes: self.current_bytes,
+            percentage_done: 100f32 * (total_bytes_f32 / self.download_size),
+            estimated_remaining_time,
+            notification_count: self.notification_count,
+        };
+        let mut to_update_progress = false;
+        if progress_record.last_elapsed_time.as_secs() > 5 {
+            self.last_print = Instant::now();
+            self.last_print_bytes = self.current_bytes;
+            to_update_progress = true;
+            self.notification_count += 1;
+            progress_record.notification_count = self.notification_count
+        }
+
+        if self.use_progress_bar {
+            self.progress_bar.inc(n as u64);
+        } else if to_update_progress {
+            info!(
+                "downloaded {} bytes {:.1}% {:.1} bytes/s",
+                self.current_bytes,
+                progress_record.percentage_done,
+                progress_record.last_throughput,
+            );
+        }
+
+        if let Some(callback) = self.callback {
+            if to_update_progress && !callback(&progress_record) {
+                info!("Download is aborted by the caller");
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Reads a download, recording its progress
+struct DownloadProgressReader<'p, 'e, 'f, R> {
+    progress: &'p mut DownloadProgress<'e, 'f>,
+    response: R,
+    // set when the download is rejected by its verifier
+    aborted: &'p AtomicBool,
+}
+
+impl<'p, 'e, 'f, R: Read> Read for DownloadProgressReader<'p, 'e, 'f, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.aborted.load(Ordering::Relaxed) {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Download is rejected by its verifier",
+            ));
+        }
+        let n = self.response.read(buf)?;
+        if !self.progress.update(n) {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Download is aborted by the caller",
+            ));
+        }
+        Ok(n)
+    }
+}
+
+/// How much of the start of a partial file has been completely written, and so can be read by a
+/// `DownloadVerifier`
+#[derive(Default)]
+struct DownloadedPrefix {
+    // the length of the prefix, and whether the download stopped writing to the partial file
+    state: Mutex<(u64, bool)>,
+    changed: Condvar,
+}
+
+impl DownloadedPrefix {
+    fn new(len: u64) -> Self {
+        Self {
+            state: Mutex::new((len, false)),
+            changed: Condvar::default(),
+        }
+    }
+
+    fn extend_to(&self, len: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.0 = state.0.max(len);
+        self.changed.notify_all();
+    }
+
+    fn finish(&self) {
+        self.state.lock().unwrap().1 = true;
+        self.changed.notify_all();
+    }
+
+    fn is_finished(&self) -> bool {
+        self.state.lock().unwrap().1
+    }
+
+    /// Blocks until the prefix is longer than `offset`, or the download stopped, and returns the
+    /// length of the prefix
+    fn wait_past(&self, offset: u64) -> u64 {
+        let state = self
+            .changed
+            .wait_while(self.state.lock().unwrap(), |(len, finished)| {
+                *len <= offset && !*finished
+            })
+            .unwrap();
+        state.0
+    }
+}
+
+/// Reads a partial file while it is being downloaded, waiting for each byte to be written.  It
+/// ends where the download stopped writing.
+struct DownloadedPrefixReader<'a> {
+    file: File,
+    prefix: &'a DownloadedPrefix,
+    offset: u64,
+}
+
+impl Read for DownloadedPrefixReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let len = self.prefix.wait_past(self.offset);
+        if len <= self.offset {
+            return Ok(0);
+        }
+        let available = usize::try_from(len - self.offset).unwrap_or(usize::MAX);
+        let buf_len = buf.len().min(available);
+        let n = self.file.read(&mut buf[..buf_len])?;
+        self.offset += n as u64;
+        Ok(n)
+    }
+}
+
+/// Writes a download to its partial file, recording how much of it has been written
+struct DownloadedPrefixWriter<'a> {
+    file: File,
+    prefix: &'a DownloadedPrefix,
+    len: u64,
+}
+
+impl Write for DownloadedPrefixWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.file.write(buf)?;
+        self.len += n as u64;
+        self.prefix.extend_to(self.len);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Runs `download`, which writes `partial_file` and records its progress in `prefix`, while
+/// `verifier` reads the partial file as it's written.  That way the download is checked without
+/// reading it again once it completes.  `stop_download` is called if the verifier rejects the
+/// download before it completes.
+///
+/// A download which fails verification is removed, so the next attempt starts over.
+fn download_verified(
+    partial_file: &Path,
+    prefix: &DownloadedPrefix,
+    verifier: Option<DownloadVerifier>,
+    stop_download: impl Fn() + Sync,
+    download: impl FnOnce() -> Result<(), String>,
+) -> Result<(), String> {
+    let Some(verifier) = verifier else {
+        return download();
+    };
+    let file = File::open(partial_file)
+        .map_err(|err| format!("Unable to open {partial_file:?}: {err:?}"))?;
+    let (download_result, verification_result) = thread::scope(|scope| {
+        let verification = scope.spawn(|| {
+            let mut reader = BufReader::new(DownloadedPrefixReader {
+                file,
+                prefix,
+                offset: 0,
+            });
+            let result = verifier(&mut reader);
+            // Once the download stopped, the verifier may have failed only because the download
+            // did
+            let rejected = result.is_err() && !prefix.is_finished();
+            if rejected {
+                stop_download();
+            }
+            (result, rejected)
+        });
+        let download_result = download();
+        prefix.finish();
+        (download_result, verification.join().unwrap())
+    });
+    match verification_result {
+        (Err(err), rejected) if rejected || download_result.is_ok() => {
+            let _ignored = fs::remove_file(partial_file);
+            let _ignored = fs::remove_file(completed_ranges_file_path(partial_file));
+            Err(format!(
+                "Download {partial_file:?} failed verification: {err}"
+            ))
+        }
+        _ => download_result,
+    }
+}
+
+/// The file a download is written to until it completes.  It is kept when the download fails, so
+/// the next attempt can resume from it.
+fn partial_file_path(destination_file: &Path) -> PathBuf {
+    let mut file_name = destination_file
+        .file_name()
+        .expect("file_name")
+        .to_os_string();
+    file_name.push(".partial");
+    destination_file.with_file_name(file_name)
+}
+
+/// The file recording which byte ranges of a parallel download have been completely written to its
+/// partial file, one range index per line
+fn completed_ranges_file_path(partial_file: &Path) -> PathBuf {
+    let mut file_name = partial_file.file_name().expect("file_name").to_os_string();
+    file_name.push(".ranges");
+    partial_file.with_file_name(file_name)
+}
+
 /// This callback allows the caller to get notified of the download progress modelled by DownloadProgressRecord
 /// Return "true" to continue the download
 /// Return "false" to abort the download
-pub fn download_file<'a, 'b>(
+///
+/// The download is written to a `.partial` file next to `destination_file`, which is left in place
+/// if the download fails.  The next download of the same file resumes from it, if the server
+/// supports range requests.
+pub fn download_file(
     url: &str,
     destination_file: &Path,
     use_progress_bar: bool,
-    progress_notify_callback: &'a mut DownloadProgressCallbackOption<'b>,
+    progress_notify_callback: &mut DownloadProgressCallbackOption<'_>,
+) -> Result<(), String> {
+    download_file_verified(
+        url,
+        destination_file,
+        use_progress_bar,
+        progress_notify_callback,
+        None,
+    )
+}
+
+fn download_file_verified(
+    url: &str,
+    destination_file: &Path,
+    use_progress_bar: bool,
+    progress_notify_callback: &mut DownloadProgressCallbackOption<'_>,
+    verifier: Option<DownloadVerifier>,
 ) -> Result<(), String> {
     if destination_file.is_file() {
         return Err(format!("{destination_file:?} already exists"));
@@ -77,162 +418,517 @@ pub fn download_file<'a, 'b>(
     fs::create_dir_all(destination_file.parent().expect("parent"))
         .map_err(|err| err.to_string())?;
 
-    let mut temp_destination_file = destination_file.to_path_buf();
-    temp_destination_file.set_file_name(format!(
-        "tmp-{}",
-        destination_file
-            .file_name()
-            .expect("file_name")
-            .to_str()
-            .expect("to_str")
-    ));
+    let partial_file = partial_file_path(destination_file);
+    let completed_ranges_file = completed_ranges_file_path(&partial_file);
+    if completed_ranges_file.exists() {
+        // The partial file of a parallel download has its full length from the start, so it can't
+        // be resumed as a single stream
+        let _ignored = fs::remove_file(&partial_file);
+        let _ignored = fs::remove_file(&completed_ranges_file);
+    }
+    let mut resume_from = fs::metadata(&partial_file)
+        .map(|metadata| metadata.len())
+        .unwrap_or(0);
 
     let progress_bar = new_spinner_progress_bar();
     if use_progress_bar {
         progress_bar.set_message(format!("{TRUCK}Downloading {url}..."));
     }
 
-    let response = reqwest::blocking::Client::new()
-        .get(url)
-        .send()
-        .and_then(|response| response.error_for_status())
-        .map_err(|err| {
+    let client = Client::new();
+    let (response, download_size) = loop {
+        let mut request = client.get(url);
+        if resume_from > 0 {
+            request = request.header(header::RANGE, format!("bytes={resume_from}-"));
+        }
+        let response = request.send().map_err(|err| {
+            progress_bar.finish_and_clear();
+            err.to_string()
+        })?;
+        if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+            // The partial file isn't shorter than the file being downloaded, so it can't be resumed
+            progress_bar.finish_and_clear();
+            let _ignored = fs::remove_file(&partial_file);
+            return Err(format!(
+                "Unable to resume download of {url}: {partial_file:?} is too long"
+            ));
+        }
+        let response = response.error_for_status().map_err(|err| {
             progress_bar.finish_and_clear();
             err.to_string()
         })?;
 
-    let download_size = {
-        response
-            .headers()
-            .get(reqwest::header::CONTENT_LENGTH)
-            .and_then(|content_length| content_length.to_str().ok())
-            .and_then(|content_length| content_length.parse().ok())
-            .unwrap_or(0)
+        // Servers which don't support range requests send the whole file
+        if response.status() != StatusCode::PARTIAL_CONTENT {
+            resume_from = 0;
+            let download_size = content_length(&response);
+            break (response, download_size);
+        }
+        match content_range(&response) {
+            Some((first, last, download_size))
+                if first == resume_from && last + 1 == download_size =>
+            {
+                break (response, download_size);
+            }
+            content_range => {
+                // The response doesn't continue the partial file, so it is downloaded again from
+                // the start
+                if resume_from == 0 {
+                    progress_bar.finish_and_clear();
+                    return Err(format!(
+                        "Unexpected Content-Range {content_range:?} from {url}"
+                    ));
+                }
+                warn!(
+                    "Unexpected Content-Range {content_range:?} from {url} when resuming at \
+                     byte {resume_from}, restarting the download"
+                );
+                resume_from = 0;
+            }
+        }
     };
 
-    if use_progress_bar {
-        progress_bar.set_length(download_size);
-        progress_bar.set_style(
-            ProgressStyle::default_bar()
-                .template(
-                    "{spinner:.green}{msg_wide}[{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})",
-                )
-                .expect("ProgresStyle::template direct input to be correct")
-                .progress_chars("=> "),
-        );
-        progress_bar.set_message(format!("{TRUCK}Downloading~ {url}"));
-    } else {
-        info!("Downloading {} bytes from {}", download_size, url);
-    }
+    let mut progress = DownloadProgress::new(
+        progress_bar,
+        url,
+        download_size,
+        resume_from,
+        use_progress_bar,
+        progress_notify_callback,
+    );
 
-    struct DownloadProgress<'e, 'f, R> {
-        progress_bar: ProgressBar,
-        response: R,
-        last_print: Instant,
-        current_bytes: usize,
-        last_print_bytes: usize,
-        download_size: f32,
-        use_progress_bar: bool,
-        start_time: Instant,
-        callback: &'f mut DownloadProgressCallbackOption<'e>,
-        notification_count: u64,
-    }
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resume_from > 0)
+        .truncate(resume_from == 0)
+        .open(&partial_file)
+        .map_err(|err| format!("Unable to write {partial_file:?}: {err:?}"))?;
+    let prefix = DownloadedPrefix::new(resume_from);
+    let aborted = AtomicBool::default();
+    download_verified(
+        &partial_file,
+        &prefix,
+        verifier,
+        || aborted.store(true, Ordering::Relaxed),
+        || {
+            let mut source = DownloadProgressReader {
+                progress: &mut progress,
+                response,
+                aborted: &aborted,
+            };
+            let mut destination = DownloadedPrefixWriter {
+                file,
+                prefix: &prefix,
+                len: resume_from,
+            };
+            io::copy(&mut source, &mut destination)
+                .map(|_| ())
+                .map_err(|err| format!("Unable to write {partial_file:?}: {err:?}"))
+        },
+    )
+    .map_err(|err| {
+        progress.progress_bar.finish_and_clear();
+        err
+    })?;
 
-    impl<'e, 'f, R: Read> Read for DownloadProgress<'e, 'f, R> {
-        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-            let n = self.response.read(buf)?;
+    progress.progress_bar.finish_and_clear();
+    info!(
+        "  {}{}",
+        SPARKLE,
+        format!(
+            "Downloaded {} ({} bytes) in {:?}",
+            url,
+            download_size,
+            Instant::now().duration_since(download_start),
+        )
+    );
 
-            self.current_bytes += n;
-            let total_bytes_f32 = self.current_bytes as f32;
-            let diff_bytes_f32 = (self.current_bytes - self.last_print_bytes) as f32;
-            let last_throughput = diff_bytes_f32 / self.last_print.elapsed().as_secs_f32();
-            let estimated_remaining_time = if last_throughput > 0_f32 {
-                (self.download_size - self.current_bytes as f32) / last_throughput
-            } else {
-                f32::MAX
-            };
+    std::fs::rename(partial_file, destination_file)
+        .map_err(|err| format!("Unable to rename: {err:?}"))?;
 
-            let mut progress_record = DownloadProgressRecord {
-                elapsed_time: self.start_time.elapsed(),
-                last_elapsed_time: self.last_print.elapsed(),
-                last_throughput,
-                total_throughput: self.current_bytes as f32
-                    / self.start_time.elapsed().as_secs_f32(),
-                total_bytes: self.download_size as usize,
-                current_bytes: self.current_bytes,
-                percentage_done: 100f32 * (total_bytes_f32 / self.download_size),
-                estimated_remaining_time,
-                notification_count: self.notification_count,
-            };
-            let mut to_update_progress = false;
-            if progress_record.last_elapsed_time.as_secs() > 5 {
-                self.last_print = Instant::now();
-                self.last_print_bytes = self.current_bytes;
-                to_update_progress = true;
-                self.notification_count += 1;
-                progress_record.notification_count = self.notification_count
-            }
+    Ok(())
+}
 
-            if self.use_progress_bar {
-                self.progress_bar.inc(n as u64);
-            } else if to_update_progress {
-                info!(
-                    "downloaded {} bytes {:.1}% {:.1} bytes/s",
-                    self.current_bytes,
-                    progress_record.percentage_done,
-                    progress_record.last_throughput,
-                );
-            }
+/// Downloads the file served at every one of `urls` into `destination_file`, fetching up to
+/// `parallel_downloads` byte ranges of it at once, spread across the urls.
+///
+/// Every url must serve the same file.  A range which fails to download from one url is retried
+/// from the others.  The ranges which were completely written to the `.partial` file are recorded
+/// next to it, so a download which fails part way through resumes where it left off.  If the first
+/// url doesn't support range requests, the file is downloaded from it with `download_file`
+/// instead.
+pub fn download_file_parallel(
+    urls: &[String],
+    destination_file: &Path,
+    parallel_downloads: usize,
+    use_progress_bar: bool,
+    progress_notify_callback: &mut DownloadProgressCallbackOption<'_>,
+) -> Result<(), String> {
+    download_file_in_ranges(
+        urls,
+        destination_file,
+        parallel_downloads,
+        DOWNLOAD_RANGE_SIZE,
+        use_progress_bar,
+        progress_notify_callback,
+        None,
+    )
+}
 
-            if let Some(callback) = self.callback {
-                if to_update_progress && !callback(&progress_record) {
-                    info!("Download is aborted by the caller");
-                    return Err(io::Error::new(
-                        io::ErrorKind::Other,
-                        "Download is aborted by the caller",
-                    ));
-                }
-            }
+fn download_file_in_ranges(
+    urls: &[String],
+    destination_file: &Path,
+    parallel_downloads: usize,
+    range_size: u64,
+    use_progress_bar: bool,
+    progress_notify_callback: &mut DownloadProgressCallbackOption<'_>,
+    verifier: Option<DownloadVerifier>,
+) -> Result<(), String> {
+    let Some(first_url) = urls.first() else {
+        return Err(format!("No url to download {destination_file:?} from"));
+    };
+    if destination_file.is_file() {
+        return Err(format!("{destination_file:?} already exists"));
+    }
+    let download_start = Instant::now();
 
-            Ok(n)
-        }
+    let client = Client::new();
+    let Some(download_size) = get_ranged_download_size(&client, first_url)? else {
+        info!("{first_url} doesn't support range requests, downloading it as a single stream");
+        return download_file_verified(
+            first_url,
+            destination_file,
+            use_progress_bar,
+            progress_notify_callback,
+            verifier,
+        );
+    };
+
+    fs::create_dir_all(destination_file.parent().expect("parent"))
+        .map_err(|err| err.to_string())?;
+
+    let partial_file = partial_file_path(destination_file);
+    let completed_ranges_file = completed_ranges_file_path(&partial_file);
+    let completed_ranges =
+        load_completed_ranges(&partial_file, &completed_ranges_file, download_size);
+    if completed_ranges.is_empty() {
+        let _ignored = fs::remove_file(&completed_ranges_file);
     }
+    let num_ranges = download_size.div_ceil(range_size);
+    let pending_ranges: VecDeque<_> = (0..num_ranges)
+        .filter(|range_index| !completed_ranges.contains(range_index))
+        .collect();
+    let resumed_bytes = completed_ranges
+        .iter()
+        .filter(|range_index| **range_index < num_ranges)
+        .map(|range_index| range_bounds(*range_index, range_size, download_size))
+        .map(|(first, last)| last - first + 1)
+        .sum();
+
+    OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(false)
+        .open(&partial_file)
+        .and_then(|file| file.set_len(download_size))
+        .map_err(|err| format!("Unable to create {partial_file:?}: {err:?}"))?;
+    let completed_ranges_writer = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&completed_ranges_file)
+        .map_err(|err| format!("Unable to create {completed_ranges_file:?}: {err:?}"))?;
 
-    let mut source = DownloadProgress::<'b, 'a> {
+    let progress_bar = new_spinner_progress_bar();
+    let mut progress = DownloadProgress::new(
         progress_bar,
-        response,
-        last_print: Instant::now(),
-        current_bytes: 0,
-        last_print_bytes: 0,
-        download_size: (download_size as f32).max(1f32),
+        first_url,
+        download_size,
+        resumed_bytes,
         use_progress_bar,
-        start_time: Instant::now(),
-        callback: progress_notify_callback,
-        notification_count: 0,
+        progress_notify_callback,
+    );
+
+    let num_workers = parallel_downloads.clamp(1, pending_ranges.len().max(1));
+    let prefix = DownloadedPrefix::new(downloaded_prefix_len(
+        &completed_ranges,
+        range_size,
+        download_size,
+    ));
+    let ranged_download = RangedDownload {
+        client,
+        urls,
+        partial_file: &partial_file,
+        range_size,
+        download_size,
+        pending_ranges: Mutex::new(pending_ranges),
+        completed_ranges: Mutex::new(completed_ranges),
+        completed_ranges_writer: Mutex::new(completed_ranges_writer),
+        prefix: &prefix,
+        downloaded_bytes: AtomicU64::default(),
+        stop: AtomicBool::default(),
     };
+    let mut aborted = false;
+    let result = download_verified(
+        &partial_file,
+        &prefix,
+        verifier,
+        || ranged_download.stop.store(true, Ordering::Relaxed),
+        || {
+            thread::scope(|scope| {
+                let ranged_download = &ranged_download;
+                let workers: Vec<_> = (0..num_workers)
+                    .map(|worker| scope.spawn(move || ranged_download.download_ranges(worker)))
+                    .collect();
 
-    File::create(&temp_destination_file)
-        .and_then(|mut file| std::io::copy(&mut source, &mut file))
-        .map_err(|err| format!("Unable to write {temp_destination_file:?}: {err:?}"))?;
+                let mut reported_bytes = 0;
+                while !workers.iter().all(|worker| worker.is_finished()) {
+                    thread::sleep(Duration::from_millis(100));
+                    // The bytes of a failed range are taken back out before it's retried, so
+                    // they're only reported once
+                    let downloaded_bytes = ranged_download.downloaded_bytes.load(Ordering::Relaxed);
+                    let n = downloaded_bytes.saturating_sub(reported_bytes);
+                    reported_bytes = reported_bytes.max(downloaded_bytes);
+                    if !aborted && !progress.update(n as usize) {
+                        aborted = true;
+                        ranged_download.stop.store(true, Ordering::Relaxed);
+                    }
+                }
+                workers
+                    .into_iter()
+                    .try_for_each(|worker| worker.join().unwrap())
+            })
+        },
+    );
+    progress.progress_bar.finish_and_clear();
+    if aborted {
+        return Err("Download is aborted by the caller".to_string());
+    }
+    result?;
 
-    source.progress_bar.finish_and_clear();
     info!(
         "  {}{}",
         SPARKLE,
         format!(
-            "Downloaded {} ({} bytes) in {:?}",
-            url,
+            "Downloaded {} ({} bytes) from {} source{} in {:?}",
+            first_url,
             download_size,
+            urls.len(),
+            if urls.len() > 1 { "s" } else { "" },
             Instant::now().duration_since(download_start),
         )
     );
 
-    std::fs::rename(temp_destination_file, destination_file)
+    std::fs::rename(&partial_file, destination_file)
         .map_err(|err| format!("Unable to rename: {err:?}"))?;
+    let _ignored = fs::remove_file(completed_ranges_file);
 
     Ok(())
 }
 
+fn content_length(response: &Response) -> u64 {
+    response
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|content_length| content_length.to_str().ok())
+        .and_then(|content_length| content_length.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Returns the first and last byte offsets of a partial response, and the size of the whole
+/// file, from its `Content-Range: bytes <first>-<last>/<size>` header
+fn content_range(response: &Response) -> Option<(u64, u64, u64)> {
+    let content_range = response
+        .headers()
+        .get(header::CONTENT_RANGE)?
+        .to_str()
+        .ok()?;
+    let (range, size) = content_range.strip_prefix("bytes ")?.split_once('/')?;
+    let (first, last) = range.split_once('-')?;
+    let (first, last, size) = (first.parse().ok()?, last.parse().ok()?, size.parse().ok()?);
+    (first <= last && last < size).then_some((first, last, size))
+}
+
+/// Returns the size of the file at `url`, or `None` if the server doesn't support range requests
+fn get_ranged_download_size(client: &Client, url: &str) -> Result<Option<u64>, String> {
+    let response = client
+        .get(url)
+        .header(header::RANGE, "bytes=0-0")
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| err.to_string())?;
+    if response.status() != StatusCode::PARTIAL_CONTENT {
+        return Ok(None);
+    }
+    Ok(content_range(&response).map(|(_first, _last, size)| size))
+}
+
+/// Returns the indexes of the byte ranges which an earlier attempt at the download completely
+/// wrote to `partial_file`
+fn load_completed_ranges(
+    partial_file: &Path,
+    completed_ranges_file: &Path,
+    download_size: u64,
+) -> BTreeSet<u64> {
+    // A partial file of another length is from a download of a different file
+    let partial_file_len = fs::metadata(partial_file).map(|metadata| metadata.len());
+    if partial_file_len.ok() != Some(download_size) {
+        return BTreeSet::new();
+    }
+    let Ok(completed_ranges) = fs::read_to_string(completed_ranges_file) else {
+        return BTreeSet::new();
+    };
+    // The last line may have been cut short by the interruption
+    let Some((completed_ranges, _)) = completed_ranges.rsplit_once('\n') else {
+        return BTreeSet::new();
+    };
+    completed_ranges
+        .lines()
+        .filter_map(|range_index| range_index.parse().ok())
+        .collect()
+}
+
+/// The length of the start of the partial file which is covered by `completed_ranges`
+fn downloaded_prefix_len(
+    completed_ranges: &BTreeSet<u64>,
+    range_size: u64,
+    download_size: u64,
+) -> u64 {
+    let num_ranges = (0..)
+        .take_while(|range_index| completed_ranges.contains(range_index))
+        .count() as u64;
+    (num_ranges * range_size).min(download_size)
+}
+
+/// The first and last byte offsets of the range at `range_index`
+fn range_bounds(range_index: u64, range_size: u64, download_size: u64) -> (u64, u64) {
+    let first = range_index * range_size;
+    let last = (first + range_size).min(download_size) - 1;
+    (first, last)
+}
+
+/// The state shared by the workers of `download_file_parallel`
+struct RangedDownload<'a> {
+    client: Client,
+    urls: &'a [String],
+    partial_file: &'a Path,
+    range_size: u64,
+    download_size: u64,
+    pending_ranges: Mutex<VecDeque<u64>>,
+    completed_ranges: Mutex<BTreeSet<u64>>,
+    completed_ranges_writer: Mutex<File>,
+    prefix: &'a DownloadedPrefix,
+    downloaded_bytes: AtomicU64,
+    stop: AtomicBool,
+}
+
+impl RangedDownload<'_> {
+    /// Downloads pending ranges until there are none left.  Each worker starts with its own url,
+    /// and falls back to the others when a range fails.
+    fn download_ranges(&self, worker: usize) -> Result<(), String> {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .open(self.partial_file)
+            .map_err(|err| format!("Unable to open {:?}: {err:?}", self.partial_file))?;
+
+        while !self.stop.load(Ordering::Relaxed) {
+            let Some(range_index) = self.pending_ranges.lock().unwrap().pop_front() else {
+                return Ok(());
+            };
+            let (first, last) = range_bounds(range_index, self.range_size, self.download_size);
+
+            let mut result = Err(String::default());
+            for attempt in 0..self.urls.len() {
+                let url = &self.urls[(worker + attempt) % self.urls.len()];
+                result = self.download_range(url, &mut file, first, last);
+                if self.stop.load(Ordering::Relaxed) {
+                    // Another worker failed, or the caller aborted the download
+                    return Ok(());
+                }
+                match &result {
+                    Ok(()) => break,
+                    Err(err) => warn!("Unable to download bytes {first}-{last} of {url}: {err}"),
+                }
+            }
+            if let Err(err) = result {
+                self.stop.store(true, Ordering::Relaxed);
+                return Err(err);
+            }
+
+            // Only recorded once the whole range is written, so a resumed download can't skip
+            // any bytes
+            writeln!(
+                self.completed_ranges_writer.lock().unwrap(),
+                "{range_index}"
+            )
+            .map_err(|err| format!("Unable to record downloaded range: {err:?}"))?;
+            let mut completed_ranges = self.completed_ranges.lock().unwrap();
+            completed_ranges.insert(range_index);
+            self.prefix.extend_to(downloaded_prefix_len(
+                &completed_ranges,
+                self.range_size,
+                self.download_size,
+            ));
+        }
+        Ok(())
+    }
+
+    fn download_range(
+        &self,
+        url: &str,
+        file: &mut File,
+        first: u64,
+        last: u64,
+    ) -> Result<(), String> {
+        let mut response = self
+            .client
+            .get(url)
+            .header(header::RANGE, format!("bytes={first}-{last}"))
+            .send()
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| err.to_string())?;
+        if response.status() != StatusCode::PARTIAL_CONTENT {
+            return Err("range requests are not supported".to_string());
+        }
+        if content_range(&response) != Some((first, last, self.download_size)) {
+            return Err(format!(
+                "unexpected Content-Range {:?}",
+                response.headers().get(header::CONTENT_RANGE)
+            ));
+        }
+        file.seek(SeekFrom::Start(first))
+            .map_err(|err| err.to_string())?;
+
+        let mut offset = first;
+        let mut buf = vec![0; 64 * 1024];
+        let result = loop {
+            if self.stop.load(Ordering::Relaxed) {
+                break Err("download was stopped".to_string());
+            }
+            if offset > last {
+                break Ok(());
+            }
+            let n = match response.read(&mut buf) {
+                Ok(0) => break Err(format!("response ended at byte {offset}")),
+                Ok(n) => n.min((last + 1 - offset) as usize),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => break Err(err.to_string()),
+            };
+            if let Err(err) = file.write_all(&buf[..n]) {
+                break Err(err.to_string());
+            }
+            offset += n as u64;
+            self.downloaded_bytes.fetch_add(n as u64, Ordering::Relaxed);
+        };
+        if result.is_err() {
+            // The range is downloaded again from scratch
+            self.downloaded_bytes
+                .fetch_sub(offset - first, Ordering::Relaxed);
+        }
+        result
+    }
+}
+
 pub fn download_genesis_if_missing(
     rpc_addr: &SocketAddr,
     genesis_package: &Path,
@@ -256,10 +952,14 @@ pub fn download_genesis_if_missing(
     }
 }
 
-/// Download a snapshot archive from `rpc_addr`.  Use `snapshot_kind` to specify downloading either
+/// Download a snapshot archive from `rpc_addrs`.  Use `snapshot_kind` to specify downloading either
 /// a full snapshot or an incremental snapshot.
+///
+/// Byte ranges of the archive are downloaded in parallel from all of `rpc_addrs`, which must all
+/// serve the snapshot archive for `desired_snapshot_hash`.  The archive is checked against the
+/// snapshot hash in its file name as it is downloaded, and removed if it doesn't match.
 pub fn download_snapshot_archive(
-    rpc_addr: &SocketAddr,
+    rpc_addrs: &[SocketAddr],
     full_snapshot_archives_dir: &Path,
     incremental_snapshot_archives_dir: &Path,
     desired_snapshot_hash: (Slot, SnapshotHash),
@@ -312,22 +1012,407 @@ pub fn download_snapshot_archive(
             return Ok(());
         }
 
-        match download_file(
-            &format!(
-                "http://{}/{}",
-                rpc_addr,
-                destination_path.file_name().unwrap().to_str().unwrap()
-            ),
+        let urls: Vec<_> = rpc_addrs
+            .iter()
+            .map(|rpc_addr| {
+                format!(
+                    "http://{}/{}",
+                    rpc_addr,
+                    destination_path.file_name().unwrap().to_str().unwrap()
+                )
+            })
+            .collect();
+        let verifier = |archive_stream: &mut dyn BufRead| {
+            snapshot_utils::verify_snapshot_archive_stream(
+                archive_stream,
+                archive_format,
+                desired_snapshot_hash,
+            )
+            .map_err(|err| err.to_string())
+        };
+        match download_file_in_ranges(
+            &urls,
             &destination_path,
+            DEFAULT_PARALLEL_DOWNLOADS,
+            DOWNLOAD_RANGE_SIZE,
             use_progress_bar,
             progress_notify_callback,
+            Some(&verifier),
         ) {
             Ok(()) => return Ok(()),
             Err(err) => info!("{}", err),
         }
     }
     Err(format!(
-        "Failed to download a snapshot archive for slot {} from {}",
-        desired_snapshot_hash.0, rpc_addr
+        "Failed to download a snapshot archive for slot {} from {:?}",
+        desired_snapshot_hash.0, rpc_addrs
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        std::{
+            io::{BufRead, BufReader},
+            net::{TcpListener, TcpStream},
+            sync::Arc,
+        },
+    };
+
+    /// A stand-in for a validator's RPC service, serving `content` at any path
+    struct TestServer {
+        addr: SocketAddr,
+        /// The `Range` header of each request served
+        requested_ranges: Arc<Mutex<Vec<Option<String>>>>,
+    }
+
+    impl TestServer {
+        /// `truncated_responses` is the number of responses which are cut short halfway through,
+        /// as if the connection dropped
+        fn start(content: &[u8], supports_ranges: bool, truncated_responses: usize) -> Self {
+            Self::start_with(content, supports_ranges, truncated_responses, false)
+        }
+
+        /// `ignores_range_start` makes the server answer every range request with the whole
+        /// file, as a partial response starting at byte 0
+        fn start_with(
+            content: &[u8],
+            supports_ranges: bool,
+            truncated_responses: usize,
+            ignores_range_start: bool,
+        ) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let addr = listener.local_addr().unwrap();
+            let content = Arc::new(content.to_vec());
+            let requested_ranges = Arc::<Mutex<Vec<_>>>::default();
+            let truncated_responses = Arc::new(AtomicU64::new(truncated_responses as u64));
+            {
+                let requested_ranges = requested_ranges.clone();
+                thread::spawn(move || {
+                    for stream in listener.incoming() {
+                        let stream = stream.unwrap();
+                        let content = content.clone();
+                        let requested_ranges = requested_ranges.clone();
+                        let truncated_responses = truncated_responses.clone();
+                        thread::spawn(move || {
+                            Self::serve(
+                                stream,
+                                &content,
+                                supports_ranges,
+                                ignores_range_start,
+                                &requested_ranges,
+                                &truncated_responses,
+                            )
+                        });
+                    }
+                });
+            }
+            Self {
+                addr,
+                requested_ranges,
+            }
+        }
+
+        fn serve(
+            mut stream: TcpStream,
+            content: &[u8],
+            supports_ranges: bool,
+            ignores_range_start: bool,
+            requested_ranges: &Mutex<Vec<Option<String>>>,
+            truncated_responses: &AtomicU64,
+        ) {
+            let mut range = None;
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("range") {
+                        range = Some(value.trim().to_string());
+                    }
+                }
+            }
+            requested_ranges.lock().unwrap().push(range.clone());
+
+            let byte_range = range.filter(|_| supports_ranges).and_then(|range| {
+                let (first, last) = range.strip_prefix("bytes=")?.split_once('-')?;
+                let first: usize = if ignores_range_start {
+                    0
+                } else {
+                    first.parse().ok()?
+                };
+                let last = last.parse().unwrap_or(content.len() - 1);
+                Some((first, last.min(content.len() - 1)))
+            });
+            let (header, body) = match byte_range {
+                Some((first, last)) => (
+                    format!(
+                        "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes {first}-{last}/{}\r\n",
+                        content.len()
+                    ),
+                    &content[first..=last],
+                ),
+                None => ("HTTP/1.1 200 OK\r\n".to_string(), content),
+            };
+            let truncate = truncated_responses
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+                .is_ok();
+            let _ = write!(
+                stream,
+                "{header}Content-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len(),
+            );
+            let body = if truncate {
+                &body[..body.len() / 2]
+            } else {
+                body
+            };
+            let _ = stream.write_all(body);
+        }
+
+        fn url(&self) -> String {
+            format!("http://{}/file", self.addr)
+        }
+
+        fn requested_ranges(&self) -> Vec<Option<String>> {
+            self.requested_ranges.lock().unwrap().clone()
+        }
+    }
+
+    fn test_content(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_download_file_resumes_partial_download() {
+        let content = test_content(10_000);
+        let server = TestServer::start(&content, true, 1);
+        let download_dir = tempfile::TempDir::new().unwrap();
+        let destination_file = download_dir.path().join("file");
+        let partial_file = partial_file_path(&destination_file);
+
+        // The connection drops halfway through, leaving the first half of the file downloaded
+        assert!(download_file(&server.url(), &destination_file, false, &mut None).is_err());
+        assert!(!destination_file.exists());
+        let downloaded = fs::read(&partial_file).unwrap();
+        assert_eq!(downloaded, content[..downloaded.len()]);
+
+        download_file(&server.url(), &destination_file, false, &mut None).unwrap();
+        assert_eq!(fs::read(&destination_file).unwrap(), content);
+        assert!(!partial_file.exists());
+        assert_eq!(
+            server.requested_ranges(),
+            vec![None, Some(format!("bytes={}-", downloaded.len()))],
+        );
+    }
+
+    #[test]
+    fn test_download_file_restarts_without_range_support() {
+        let content = test_content(10_000);
+        let server = TestServer::start(&content, false, 0);
+        let download_dir = tempfile::TempDir::new().unwrap();
+        let destination_file = download_dir.path().join("file");
+        fs::write(partial_file_path(&destination_file), [1, 2, 3]).unwrap();
+
+        download_file(&server.url(), &destination_file, false, &mut None).unwrap();
+        assert_eq!(fs::read(&destination_file).unwrap(), content);
+    }
+
+    #[test]
+    fn test_download_file_restarts_on_unexpected_content_range() {
+        let content = test_content(10_000);
+        let server = TestServer::start_with(&content, true, 0, true);
+        let download_dir = tempfile::TempDir::new().unwrap();
+        let destination_file = download_dir.path().join("file");
+        fs::write(partial_file_path(&destination_file), [1, 2, 3]).unwrap();
+
+        // The response to the resumed request starts at byte 0, so it's requested again in full
+        // instead of being appended to the partial file
+        download_file(&server.url(), &destination_file, false, &mut None).unwrap();
+        assert_eq!(fs::read(&destination_file).unwrap(), content);
+        assert_eq!(
+            server.requested_ranges(),
+            vec![Some("bytes=3-".to_string()), None],
+        );
+    }
+
+    #[test]
+    fn test_download_file_verifies_while_downloading() {
+        let content = test_content(10_000);
+        let verify_content = |stream: &mut dyn BufRead| {
+            let mut downloaded = vec![];
+            stream.read_to_end(&mut downloaded).unwrap();
+            (downloaded == content)
+                .then_some(())
+                .ok_or_else(|| "unexpected content".to_string())
+        };
+        let reject_content =
+            |_stream: &mut dyn BufRead| -> Result<(), String> { Err("rejected".to_string()) };
+
+        for supports_ranges in [true, false] {
+            let server = TestServer::start(&content, supports_ranges, 0);
+            let download_dir = tempfile::TempDir::new().unwrap();
+            let destination_file = download_dir.path().join("file");
+            let partial_file = partial_file_path(&destination_file);
+
+            // An earlier attempt completed the first two ranges
+            if supports_ranges {
+                let mut partial_content = content[..2_000].to_vec();
+                partial_content.resize(content.len(), 0);
+                fs::write(&partial_file, partial_content).unwrap();
+                fs::write(completed_ranges_file_path(&partial_file), "1\n0\n").unwrap();
+            }
+            download_file_in_ranges(
+                &[server.url()],
+                &destination_file,
+                4,
+                1_000,
+                false,
+                &mut None,
+                Some(&verify_content),
+            )
+            .unwrap();
+            assert_eq!(fs::read(&destination_file).unwrap(), content);
+
+            // A rejected download is removed, so the next attempt starts over
+            fs::remove_file(&destination_file).unwrap();
+            assert!(download_file_in_ranges(
+                &[server.url()],
+                &destination_file,
+                4,
+                1_000,
+                false,
+                &mut None,
+                Some(&reject_content),
+            )
+            .is_err());
+            assert!(!destination_file.exists());
+            assert!(!partial_file.exists());
+            assert!(!completed_ranges_file_path(&partial_file).exists());
+        }
+    }
+
+    #[test]
+    fn test_download_file_in_ranges_from_several_sources() {
+        let content = test_content(10_000);
+        let servers = [
+            TestServer::start(&content, true, 0),
+            TestServer::start(&content, true, 0),
+        ];
+        let urls: Vec<_> = servers.iter().map(TestServer::url).collect();
+        let download_dir = tempfile::TempDir::new().unwrap();
+        let destination_file = download_dir.path().join("file");
+
+        download_file_in_ranges(&urls, &destination_file, 4, 1_000, false, &mut None, None)
+            .unwrap();
+        assert_eq!(fs::read(&destination_file).unwrap(), content);
+        let partial_file = partial_file_path(&destination_file);
+        assert!(!partial_file.exists());
+        assert!(!completed_ranges_file_path(&partial_file).exists());
+
+        // Besides the request for the file's size, each range is requested once
+        let mut requested_ranges: Vec<_> = servers
+            .iter()
+            .flat_map(TestServer::requested_ranges)
+            .flatten()
+            .collect();
+        requested_ranges.sort();
+        let mut expected_ranges: Vec<_> = (0..10)
+            .map(|i| format!("bytes={}-{}", i * 1_000, i * 1_000 + 999))
+            .chain(["bytes=0-0".to_string()])
+            .collect();
+        expected_ranges.sort();
+        assert_eq!(requested_ranges, expected_ranges);
+        assert!(!servers[1].requested_ranges().is_empty());
+    }
+
+    #[test]
+    fn test_download_file_in_ranges_retries_other_sources() {
+        let content = test_content(10_000);
+        let servers = [
+            TestServer::start(&content, true, 0),
+            TestServer::start(&content, true, usize::MAX),
+        ];
+        let urls: Vec<_> = servers.iter().map(TestServer::url).collect();
+        let download_dir = tempfile::TempDir::new().unwrap();
+        let destination_file = download_dir.path().join("file");
+
+        download_file_in_ranges(&urls, &destination_file, 4, 1_000, false, &mut None, None)
+            .unwrap();
+        assert_eq!(fs::read(&destination_file).unwrap(), content);
+    }
+
+    #[test]
+    fn test_download_file_in_ranges_resumes_completed_ranges() {
+        let content = test_content(10_000);
+        let server = TestServer::start(&content, true, 0);
+        let download_dir = tempfile::TempDir::new().unwrap();
+        let destination_file = download_dir.path().join("file");
+        let partial_file = partial_file_path(&destination_file);
+
+        // An earlier attempt completed the first five ranges, and was interrupted while
+        // recording the seventh
+        let mut partial_content = content[..5_000].to_vec();
+        partial_content.resize(content.len(), 0);
+        fs::write(&partial_file, partial_content).unwrap();
+        fs::write(
+            completed_ranges_file_path(&partial_file),
+            "3\n0\n1\n4\n2\n6",
+        )
+        .unwrap();
+
+        download_file_in_ranges(
+            &[server.url()],
+            &destination_file,
+            2,
+            1_000,
+            false,
+            &mut None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(fs::read(&destination_file).unwrap(), content);
+
+        let mut requested_ranges: Vec<_> =
+            server.requested_ranges().into_iter().flatten().collect();
+        requested_ranges.sort();
+        assert_eq!(
+            requested_ranges,
+            vec![
+                "bytes=0-0",
+                "bytes=5000-5999",
+                "bytes=6000-6999",
+                "bytes=7000-7999",
+                "bytes=8000-8999",
+                "bytes=9000-9999",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_download_file_in_ranges_without_range_support() {
+        let content = test_content(10_000);
+        let server = TestServer::start(&content, false, 0);
+        let download_dir = tempfile::TempDir::new().unwrap();
+        let destination_file = download_dir.path().join("file");
+
+        download_file_in_ranges(
+            &[server.url()],
+            &destination_file,
+            4,
+            1_000,
+            false,
+            &mut None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(fs::read(&destination_file).unwrap(), content);
+        assert_eq!(
+            server.requested_ranges(),
+            vec![Some("bytes=0-0".to_string()), None],
+        );
+    }
+}
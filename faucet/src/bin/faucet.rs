@@ -1,20 +1,75 @@
 use {
     clap::{crate_description, crate_name, values_t, App, Arg},
     log::*,
+    serde_derive::Deserialize,
     solana_clap_utils::input_parsers::{lamports_of_sol, value_of},
     solana_faucet::{
-        faucet::{run_faucet, Faucet, FAUCET_PORT},
+        faucet::{run_faucet, Faucet, FaucetToken, TokenSource, FAUCET_PORT},
         socketaddr,
     },
-    solana_sdk::signature::read_keypair_file,
+    solana_sdk::{pubkey::Pubkey, signature::read_keypair_file},
     std::{
         collections::HashSet,
+        fs::File,
         net::{IpAddr, Ipv4Addr, SocketAddr},
+        process::exit,
+        str::FromStr,
         sync::{Arc, Mutex},
         thread,
     },
 };
 
+/// Entry of the `--token-config` file
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TokenConfig {
+    mint: String,
+    /// `spl-token` or `spl-token-2022`
+    #[serde(default = "default_token_program")]
+    program: String,
+    decimals: u8,
+    /// Token account of the faucet keypair to transfer the tokens from, instead of minting them
+    token_account: Option<String>,
+    /// In tokens
+    per_time_cap: f64,
+    per_request_cap: Option<f64>,
+}
+
+fn default_token_program() -> String {
+    "spl-token".to_string()
+}
+
+fn load_token_config(path: &str) -> Result<Vec<FaucetToken>, String> {
+    let file = File::open(path).map_err(|err| format!("Unable to open {path}: {err}"))?;
+    let tokens: Vec<TokenConfig> =
+        serde_yaml::from_reader(file).map_err(|err| format!("Unable to parse {path}: {err}"))?;
+    let parse_pubkey =
+        |pubkey: &str| Pubkey::from_str(pubkey).map_err(|err| format!("{pubkey}: {err}"));
+    tokens
+        .into_iter()
+        .map(|token| {
+            let token_program_id = match token.program.as_str() {
+                "spl-token" => spl_token::id(),
+                "spl-token-2022" => spl_token_2022::id(),
+                program => return Err(format!("Unknown token program: {program}")),
+            };
+            let source = match &token.token_account {
+                Some(token_account) => TokenSource::TokenAccount(parse_pubkey(token_account)?),
+                None => TokenSource::MintAuthority,
+            };
+            let to_amount = |ui_amount| spl_token::ui_amount_to_amount(ui_amount, token.decimals);
+            Ok(FaucetToken {
+                mint: parse_pubkey(&token.mint)?,
+                token_program_id,
+                decimals: token.decimals,
+                source,
+                per_time_cap: to_amount(token.per_time_cap),
+                per_request_cap: token.per_request_cap.map(to_amount),
+            })
+        })
+        .collect()
+}
+
 #[tokio::main]
 async fn main() {
     let default_keypair = solana_cli_config::Config::default().keypair_path;
@@ -67,6 +122,20 @@ async fn main() {
                     recipient address will be used to check request limits instead",
                 ),
         )
+        .arg(
+            Arg::with_name("token_config")
+                .long("token-config")
+                .value_name("PATH")
+                .takes_value(true)
+                .help(
+                    "YAML file of the SPL tokens to airdrop, a list of entries with the `mint`, \
+                    its `program` (spl-token or spl-token-2022) and `decimals`, an optional \
+                    `token_account` to transfer the tokens from instead of minting them, a \
+                    `per_time_cap` and an optional `per_request_cap` in tokens. The rent of the \
+                    associated token accounts created for the recipients counts against \
+                    --per-time-cap",
+                ),
+        )
         .get_matches();
 
     let faucet_keypair = read_keypair_file(matches.value_of("keypair").unwrap())
@@ -83,13 +152,27 @@ async fn main() {
 
     let faucet_addr = socketaddr!(Ipv4Addr::UNSPECIFIED, FAUCET_PORT);
 
-    let faucet = Arc::new(Mutex::new(Faucet::new_with_allowed_ips(
+    let tokens = matches
+        .value_of("token_config")
+        .map(load_token_config)
+        .transpose()
+        .unwrap_or_else(|err| {
+            eprintln!("Invalid --token-config: {err}");
+            exit(1);
+        })
+        .unwrap_or_default();
+
+    let mut faucet = Faucet::new_with_allowed_ips(
         faucet_keypair,
         time_slice,
         per_time_cap,
         per_request_cap,
         allowed_ips,
-    )));
+    );
+    for token in tokens {
+        faucet.add_token(token);
+    }
+    let faucet = Arc::new(Mutex::new(faucet));
 
     let faucet1 = faucet.clone();
     thread::spawn(move || loop {
@@ -3,6 +3,12 @@
 //! The Solana Faucet builds and sends airdrop transactions,
 //! checking requests against a single-request cap and a per-IP limit
 //! for a given time time_slice.
+//!
+//! The faucet can also airdrop SPL Token and Token-2022 tokens of the mints added with
+//! [`Faucet::add_token`], either minting them as the mint authority or transferring them from a
+//! token account it owns. The tokens are sent to the associated token account of the recipient,
+//! which is created if missing, and are capped per mint. The rent of that account counts against
+//! the SOL limit of the requester.
 
 use {
     bincode::{deserialize, serialize, serialized_size},
@@ -17,13 +23,21 @@ use {
         message::Message,
         native_token::lamports_to_sol,
         packet::PACKET_DATA_SIZE,
+        program_pack::Pack,
         pubkey::Pubkey,
+        rent::Rent,
         signature::{Keypair, Signer},
         system_instruction,
         transaction::Transaction,
     },
+    spl_associated_token_account::{
+        get_associated_token_address_with_program_id,
+        instruction::create_associated_token_account_idempotent,
+    },
+    spl_token_2022::extension::ExtensionType,
     std::{
         collections::{HashMap, HashSet},
+        fmt::Display,
         io::{Read, Write},
         net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream},
         sync::{Arc, Mutex},
@@ -73,6 +87,21 @@ pub enum FaucetError {
 
     #[error("limit reached; req: ◎{0}, to: {1}, current: ◎{2}, cap: ◎{3}")]
     PerTimeCapExceeded(f64, String, f64, f64),
+
+    #[error("request too large; req: {0} {1}, cap: {2} {1}")]
+    TokenPerRequestCapExceeded(f64, Pubkey, f64),
+
+    #[error("limit reached; req: {0} {1}, to: {2}, current: {3} {1}, cap: {4} {1}")]
+    TokenPerTimeCapExceeded(f64, Pubkey, String, f64, f64),
+
+    #[error("mint not supported by this faucet: {0}")]
+    UnsupportedMint(Pubkey),
+
+    #[error("token airdrop of 0 {0}")]
+    ZeroTokenAmount(Pubkey),
+
+    #[error("token instruction error: {0}")]
+    TokenInstruction(#[from] solana_sdk::program_error::ProgramError),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
@@ -82,6 +111,78 @@ pub enum FaucetRequest {
         to: Pubkey,
         blockhash: Hash,
     },
+    /// Airdrop of `amount` base units of `mint` to the associated token account of `to`
+    GetTokenAirdrop {
+        amount: u64,
+        mint: Pubkey,
+        to: Pubkey,
+        blockhash: Hash,
+    },
+}
+
+impl FaucetRequest {
+    /// Serialized size of the requests of the variant with index `variant`, which is serialized
+    /// first
+    fn serialized_size(variant: u32) -> Option<usize> {
+        let request = match variant {
+            0 => FaucetRequest::GetAirdrop {
+                lamports: u64::default(),
+                to: Pubkey::default(),
+                blockhash: Hash::default(),
+            },
+            1 => FaucetRequest::GetTokenAirdrop {
+                amount: u64::default(),
+                mint: Pubkey::default(),
+                to: Pubkey::default(),
+                blockhash: Hash::default(),
+            },
+            _ => return None,
+        };
+        serialized_size(&request).ok().map(|size| size as usize)
+    }
+}
+
+/// How the faucet funds the airdrops of a token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenSource {
+    /// The faucet keypair is the mint authority and mints the airdropped tokens
+    MintAuthority,
+    /// The airdropped tokens are transferred from this token account of the faucet keypair
+    TokenAccount(Pubkey),
+}
+
+/// A token the faucet airdrops. The caps are in base units of the token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FaucetToken {
+    pub mint: Pubkey,
+    /// SPL Token or Token-2022
+    pub token_program_id: Pubkey,
+    pub decimals: u8,
+    pub source: TokenSource,
+    /// Unlike SOL airdrops, token airdrops are always limited per time slice
+    pub per_time_cap: u64,
+    pub per_request_cap: Option<u64>,
+}
+
+impl FaucetToken {
+    fn ui_amount(&self, amount: u64) -> f64 {
+        spl_token_2022::amount_to_ui_amount(amount, self.decimals)
+    }
+
+    /// Rent of the associated token account of a recipient, which the faucet pays if it creates
+    /// the account. Token-2022 mints requiring more account extensions than `ImmutableOwner` make
+    /// the actual rent higher.
+    fn token_account_rent(&self) -> u64 {
+        let account_len = if self.token_program_id == spl_token_2022::id() {
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(&[
+                ExtensionType::ImmutableOwner,
+            ])
+            .unwrap_or(spl_token_2022::state::Account::LEN)
+        } else {
+            spl_token_2022::state::Account::LEN
+        };
+        Rent::default().minimum_balance(account_len)
+    }
 }
 
 pub enum FaucetTransaction {
@@ -97,6 +198,10 @@ pub struct Faucet {
     per_time_cap: Option<u64>,
     per_request_cap: Option<u64>,
     allowed_ips: HashSet<IpAddr>,
+    tokens: HashMap<Pubkey, FaucetToken>,
+    /// Tokens airdropped in the current time slice, by mint and requester
+    token_ip_cache: HashMap<(Pubkey, IpAddr), u64>,
+    token_address_cache: HashMap<(Pubkey, Pubkey), u64>,
 }
 
 impl Faucet {
@@ -141,9 +246,28 @@ impl Faucet {
             per_time_cap,
             per_request_cap,
             allowed_ips,
+            tokens: HashMap::new(),
+            token_ip_cache: HashMap::new(),
+            token_address_cache: HashMap::new(),
         }
     }
 
+    /// Airdrops `token` too, replacing the previous configuration of its mint
+    pub fn add_token(&mut self, token: FaucetToken) {
+        if let Some(per_request_cap) = token.per_request_cap {
+            if token.per_time_cap < per_request_cap {
+                warn!(
+                    "per_time_cap {} < per_request_cap {} of mint {}; \
+                    maximum single requests will fail",
+                    token.ui_amount(token.per_time_cap),
+                    token.ui_amount(per_request_cap),
+                    token.mint,
+                );
+            }
+        }
+        self.tokens.insert(token.mint, token);
+    }
+
     pub fn check_time_request_limit<T: LimitByTime + std::fmt::Display>(
         &mut self,
         request_amount: u64,
@@ -167,6 +291,21 @@ impl Faucet {
     pub fn clear_caches(&mut self) {
         self.ip_cache.clear();
         self.address_cache.clear();
+        self.token_ip_cache.clear();
+        self.token_address_cache.clear();
+    }
+
+    fn memo_transaction(&self, memo: String, blockhash: Hash) -> FaucetTransaction {
+        let memo_instruction = Instruction {
+            program_id: Pubkey::from(spl_memo::id().to_bytes()),
+            accounts: vec![],
+            data: memo.as_bytes().to_vec(),
+        };
+        let message = Message::new(&[memo_instruction], Some(&self.faucet_keypair.pubkey()));
+        FaucetTransaction::Memo((
+            Transaction::new(&[&self.faucet_keypair], message, blockhash),
+            memo,
+        ))
     }
 
     /// Checks per-request and per-time-ip limits; if both pass, this method returns a signed
@@ -201,16 +340,7 @@ impl Faucet {
                                 lamports_to_sol(cap),
                             )
                         );
-                        let memo_instruction = Instruction {
-                            program_id: Pubkey::from(spl_memo::id().to_bytes()),
-                            accounts: vec![],
-                            data: memo.as_bytes().to_vec(),
-                        };
-                        let message = Message::new(&[memo_instruction], Some(&mint_pubkey));
-                        return Ok(FaucetTransaction::Memo((
-                            Transaction::new(&[&self.faucet_keypair], message, blockhash),
-                            memo,
-                        )));
+                        return Ok(self.memo_transaction(memo, blockhash));
                     }
                 }
                 if !ip.is_loopback() && !self.allowed_ips.contains(&ip) {
@@ -227,9 +357,106 @@ impl Faucet {
                     blockhash,
                 )))
             }
+            FaucetRequest::GetTokenAirdrop {
+                amount,
+                mint,
+                to,
+                blockhash,
+            } => self.build_token_airdrop_transaction(amount, mint, to, blockhash, ip),
         }
     }
 
+    /// Checks the per-request and per-time limits of `mint`, like `build_airdrop_transaction`,
+    /// and the per-time SOL limit against the rent of the associated token account of `to`. The
+    /// returned transaction creates that account if missing, then mints or transfers the tokens
+    /// to it.
+    fn build_token_airdrop_transaction(
+        &mut self,
+        amount: u64,
+        mint: Pubkey,
+        to: Pubkey,
+        blockhash: Hash,
+        ip: IpAddr,
+    ) -> Result<FaucetTransaction, FaucetError> {
+        let token = self
+            .tokens
+            .get(&mint)
+            .cloned()
+            .ok_or(FaucetError::UnsupportedMint(mint))?;
+        if amount == 0 {
+            return Err(FaucetError::ZeroTokenAmount(mint));
+        }
+        info!(
+            "Requesting airdrop of {} {} to {:?}",
+            token.ui_amount(amount),
+            mint,
+            to
+        );
+
+        if let Some(cap) = token.per_request_cap {
+            if amount > cap {
+                let memo = format!(
+                    "{}",
+                    FaucetError::TokenPerRequestCapExceeded(
+                        token.ui_amount(amount),
+                        mint,
+                        token.ui_amount(cap),
+                    )
+                );
+                return Ok(self.memo_transaction(memo, blockhash));
+            }
+        }
+        // The faucet can't tell whether the associated token account exists, so the rent of a
+        // new one is always counted
+        let rent = token.token_account_rent();
+        if !ip.is_loopback() && !self.allowed_ips.contains(&ip) {
+            check_token_time_request_limit(&mut self.token_ip_cache, &token, amount, ip)?;
+            self.check_time_request_limit(rent, ip)?;
+        }
+        check_token_time_request_limit(&mut self.token_address_cache, &token, amount, to)?;
+        self.check_time_request_limit(rent, to)?;
+
+        let faucet_pubkey = self.faucet_keypair.pubkey();
+        let recipient_token_account =
+            get_associated_token_address_with_program_id(&to, &mint, &token.token_program_id);
+        let create_instruction = create_associated_token_account_idempotent(
+            &faucet_pubkey,
+            &to,
+            &mint,
+            &token.token_program_id,
+        );
+        let token_instruction = match token.source {
+            TokenSource::MintAuthority => spl_token_2022::instruction::mint_to_checked(
+                &token.token_program_id,
+                &mint,
+                &recipient_token_account,
+                &faucet_pubkey,
+                &[],
+                amount,
+                token.decimals,
+            )?,
+            TokenSource::TokenAccount(source) => spl_token_2022::instruction::transfer_checked(
+                &token.token_program_id,
+                &source,
+                &mint,
+                &recipient_token_account,
+                &faucet_pubkey,
+                &[],
+                amount,
+                token.decimals,
+            )?,
+        };
+        let message = Message::new(
+            &[create_instruction, token_instruction],
+            Some(&faucet_pubkey),
+        );
+        Ok(FaucetTransaction::Airdrop(Transaction::new(
+            &[&self.faucet_keypair],
+            message,
+            blockhash,
+        )))
+    }
+
     /// Deserializes a received airdrop request, and returns a serialized transaction
     pub fn process_faucet_request(
         &mut self,
@@ -268,6 +495,37 @@ impl Faucet {
     }
 }
 
+/// Adds `request_amount` to the tokens of the mint of `token` airdropped to `requester` in the
+/// current time slice, failing if the total exceeds the per-time cap of the token
+fn check_token_time_request_limit<T: Copy + Eq + std::hash::Hash + Display>(
+    cache: &mut HashMap<(Pubkey, T), u64>,
+    token: &FaucetToken,
+    request_amount: u64,
+    requester: T,
+) -> Result<(), FaucetError> {
+    let new_total = cache
+        .entry((token.mint, requester))
+        .and_modify(|total| *total = total.saturating_add(request_amount))
+        .or_insert(request_amount);
+    datapoint_info!(
+        "faucet-token-airdrop",
+        ("mint", token.mint.to_string(), String),
+        ("request_amount", request_amount, i64),
+        ("requester", requester.to_string(), String),
+        ("new_total", *new_total, i64)
+    );
+    if *new_total > token.per_time_cap {
+        return Err(FaucetError::TokenPerTimeCapExceeded(
+            token.ui_amount(request_amount),
+            token.mint,
+            requester.to_string(),
+            token.ui_amount(*new_total),
+            token.ui_amount(token.per_time_cap),
+        ));
+    }
+    Ok(())
+}
+
 impl Drop for Faucet {
     fn drop(&mut self) {
         solana_metrics::flush();
@@ -285,14 +543,48 @@ pub fn request_airdrop_transaction(
         faucet_addr, id, lamports, blockhash
     );
 
+    request_transaction(
+        faucet_addr,
+        &FaucetRequest::GetAirdrop {
+            lamports,
+            blockhash,
+            to: *id,
+        },
+    )
+}
+
+/// Requests a transaction airdropping `amount` base units of `mint` to the associated token
+/// account of `id`
+pub fn request_token_airdrop_transaction(
+    faucet_addr: &SocketAddr,
+    id: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+    blockhash: Hash,
+) -> Result<Transaction, FaucetError> {
+    info!(
+        "request_token_airdrop_transaction: faucet_addr={} id={} mint={} amount={} blockhash={}",
+        faucet_addr, id, mint, amount, blockhash
+    );
+
+    request_transaction(
+        faucet_addr,
+        &FaucetRequest::GetTokenAirdrop {
+            amount,
+            mint: *mint,
+            to: *id,
+            blockhash,
+        },
+    )
+}
+
+fn request_transaction(
+    faucet_addr: &SocketAddr,
+    req: &FaucetRequest,
+) -> Result<Transaction, FaucetError> {
     let mut stream = TcpStream::connect_timeout(faucet_addr, Duration::new(3, 0))?;
     stream.set_read_timeout(Some(Duration::new(10, 0)))?;
-    let req = FaucetRequest::GetAirdrop {
-        lamports,
-        blockhash,
-        to: *id,
-    };
-    let req = serialize(&req).expect("serialize faucet request");
+    let req = serialize(req).expect("serialize faucet request");
     stream.write_all(&req)?;
 
     // Read length of transaction
@@ -406,16 +698,19 @@ async fn process(
     mut stream: TokioTcpStream,
     faucet: Arc<Mutex<Faucet>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let mut request = vec![
-        0u8;
-        serialized_size(&FaucetRequest::GetAirdrop {
-            lamports: u64::default(),
-            to: Pubkey::default(),
-            blockhash: Hash::default(),
-        })
-        .unwrap() as usize
-    ];
-    while stream.read_exact(&mut request).await.is_ok() {
+    // The requests are read in two steps, their variant index and then the rest of the
+    // request, as their size depends on the variant
+    let mut variant = [0u8; 4];
+    while stream.read_exact(&mut variant).await.is_ok() {
+        let Some(request_size) = FaucetRequest::serialized_size(LittleEndian::read_u32(&variant))
+        else {
+            info!("Unknown request variant: {:?}", variant);
+            stream.write_all(&ERROR_RESPONSE).await?;
+            break;
+        };
+        let mut request = variant.to_vec();
+        request.resize(request_size, 0);
+        stream.read_exact(&mut request[variant.len()..]).await?;
         trace!("{:?}", request);
 
         let response = {
@@ -647,6 +942,212 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_faucet_build_token_airdrop_transaction() {
+        let to = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let blockhash = Hash::new_unique();
+        let request = FaucetRequest::GetTokenAirdrop {
+            amount: 2_000_000,
+            mint,
+            to,
+            blockhash,
+        };
+        let ip = socketaddr!([203, 0, 113, 1], 1234).ip();
+
+        let keypair = Keypair::new();
+        let faucet_pubkey = keypair.pubkey();
+        let mut faucet = Faucet::new(keypair, None, None, None);
+        assert!(matches!(
+            faucet.build_airdrop_transaction(request, ip),
+            Err(FaucetError::UnsupportedMint(unsupported_mint)) if unsupported_mint == mint
+        ));
+
+        let token = FaucetToken {
+            mint,
+            token_program_id: spl_token_2022::id(),
+            decimals: 6,
+            source: TokenSource::MintAuthority,
+            per_time_cap: 3_000_000,
+            per_request_cap: Some(2_000_000),
+        };
+        faucet.add_token(token.clone());
+        let recipient_token_account =
+            get_associated_token_address_with_program_id(&to, &mint, &spl_token_2022::id());
+        let expected_instructions = vec![
+            create_associated_token_account_idempotent(
+                &faucet_pubkey,
+                &to,
+                &mint,
+                &spl_token_2022::id(),
+            ),
+            spl_token_2022::instruction::mint_to_checked(
+                &spl_token_2022::id(),
+                &mint,
+                &recipient_token_account,
+                &faucet_pubkey,
+                &[],
+                2_000_000,
+                6,
+            )
+            .unwrap(),
+        ];
+        let FaucetTransaction::Airdrop(tx) = faucet.build_airdrop_transaction(request, ip).unwrap()
+        else {
+            panic!("airdrop should succeed");
+        };
+        assert_eq!(
+            tx.message,
+            Message::new_with_blockhash(&expected_instructions, Some(&faucet_pubkey), &blockhash)
+        );
+        tx.verify().unwrap();
+
+        // Test per-time request cap, which is accounted per mint
+        assert!(matches!(
+            faucet.build_airdrop_transaction(request, ip),
+            Err(FaucetError::TokenPerTimeCapExceeded(..))
+        ));
+        let other_mint = Pubkey::new_unique();
+        let source = Pubkey::new_unique();
+        faucet.add_token(FaucetToken {
+            mint: other_mint,
+            token_program_id: spl_token::id(),
+            source: TokenSource::TokenAccount(source),
+            ..token
+        });
+        let other_request = FaucetRequest::GetTokenAirdrop {
+            amount: 2_000_000,
+            mint: other_mint,
+            to,
+            blockhash,
+        };
+        let FaucetTransaction::Airdrop(tx) =
+            faucet.build_airdrop_transaction(other_request, ip).unwrap()
+        else {
+            panic!("airdrop of another mint should succeed");
+        };
+        let recipient_token_account =
+            get_associated_token_address_with_program_id(&to, &other_mint, &spl_token::id());
+        let transfer_instruction = spl_token_2022::instruction::transfer_checked(
+            &spl_token::id(),
+            &source,
+            &other_mint,
+            &recipient_token_account,
+            &faucet_pubkey,
+            &[],
+            2_000_000,
+            6,
+        )
+        .unwrap();
+        assert_eq!(
+            tx.message.instructions[1],
+            tx.message.compile_instruction(&transfer_instruction)
+        );
+        faucet.clear_caches();
+        assert!(faucet.build_airdrop_transaction(request, ip).is_ok());
+
+        // Test per-request cap
+        let request = FaucetRequest::GetTokenAirdrop {
+            amount: 2_000_001,
+            mint,
+            to,
+            blockhash,
+        };
+        let FaucetTransaction::Memo((_tx, memo)) =
+            faucet.build_airdrop_transaction(request, ip).unwrap()
+        else {
+            panic!("airdrop attempt should result in memo tx");
+        };
+        assert_eq!(
+            memo,
+            format!("request too large; req: 2.000001 {mint}, cap: 2 {mint}")
+        );
+
+        // Test zero amount
+        let request = FaucetRequest::GetTokenAirdrop {
+            amount: 0,
+            mint,
+            to,
+            blockhash,
+        };
+        assert!(matches!(
+            faucet.build_airdrop_transaction(request, ip),
+            Err(FaucetError::ZeroTokenAmount(zero_mint)) if zero_mint == mint
+        ));
+    }
+
+    #[test]
+    fn test_faucet_token_airdrop_rent_cap() {
+        let mint = Pubkey::new_unique();
+        let token = FaucetToken {
+            mint,
+            token_program_id: spl_token_2022::id(),
+            decimals: 6,
+            source: TokenSource::MintAuthority,
+            per_time_cap: 1_000,
+            per_request_cap: None,
+        };
+        // Base account, account type and ImmutableOwner extension
+        let rent = Rent::default().minimum_balance(170);
+        assert_eq!(token.token_account_rent(), rent);
+        assert_eq!(
+            FaucetToken {
+                token_program_id: spl_token::id(),
+                ..token.clone()
+            }
+            .token_account_rent(),
+            Rent::default().minimum_balance(165)
+        );
+
+        // The rent of the associated token accounts counts against the SOL per-time cap
+        let mut faucet = Faucet::new(Keypair::new(), None, Some(rent * 3 / 2), None);
+        faucet.add_token(token);
+        let ip = socketaddr!([203, 0, 113, 1], 1234).ip();
+        let request = |to| FaucetRequest::GetTokenAirdrop {
+            amount: 1,
+            mint,
+            to,
+            blockhash: Hash::new_unique(),
+        };
+        let to = Pubkey::new_unique();
+        assert!(faucet.build_airdrop_transaction(request(to), ip).is_ok());
+        assert!(matches!(
+            faucet.build_airdrop_transaction(request(to), ip),
+            Err(FaucetError::PerTimeCapExceeded(..))
+        ));
+        // Including the requests from the same IP address
+        assert!(matches!(
+            faucet.build_airdrop_transaction(request(Pubkey::new_unique()), ip),
+            Err(FaucetError::PerTimeCapExceeded(..))
+        ));
+    }
+
+    #[test]
+    fn test_faucet_request_serialized_size() {
+        let request = FaucetRequest::GetTokenAirdrop {
+            amount: 1,
+            mint: Pubkey::new_unique(),
+            to: Pubkey::new_unique(),
+            blockhash: Hash::new_unique(),
+        };
+        let bytes = serialize(&request).unwrap();
+        assert_eq!(
+            FaucetRequest::serialized_size(LittleEndian::read_u32(&bytes)),
+            Some(bytes.len())
+        );
+        let request = FaucetRequest::GetAirdrop {
+            lamports: 1,
+            to: Pubkey::new_unique(),
+            blockhash: Hash::new_unique(),
+        };
+        let bytes = serialize(&request).unwrap();
+        assert_eq!(
+            FaucetRequest::serialized_size(LittleEndian::read_u32(&bytes)),
+            Some(bytes.len())
+        );
+        assert_eq!(FaucetRequest::serialized_size(2), None);
+    }
+
     #[test]
     fn test_process_faucet_request() {
         let to = solana_sdk::pubkey::new_rand();
@@ -1,12 +1,27 @@
 use {
-    solana_faucet::faucet::{request_airdrop_transaction, run_local_faucet},
+    crossbeam_channel::unbounded,
+    solana_faucet::faucet::{
+        request_airdrop_transaction, request_token_airdrop_transaction, run_faucet,
+        run_local_faucet, Faucet, FaucetToken, TokenSource,
+    },
     solana_sdk::{
         hash::Hash,
         message::Message,
+        pubkey::Pubkey,
         signature::{Keypair, Signer},
         system_instruction,
         transaction::Transaction,
     },
+    spl_associated_token_account::{
+        get_associated_token_address_with_program_id,
+        instruction::create_associated_token_account_idempotent,
+    },
+    std::{
+        net::SocketAddr,
+        sync::{Arc, Mutex},
+        thread,
+    },
+    tokio::runtime::Runtime,
 };
 
 #[test]
@@ -24,3 +39,58 @@ fn test_local_faucet() {
     let result = request_airdrop_transaction(&faucet_addr, &to, lamports, blockhash);
     assert_eq!(expected_tx, result.unwrap());
 }
+
+#[test]
+fn test_local_faucet_token_airdrop() {
+    let keypair = Keypair::new();
+    let faucet_pubkey = keypair.pubkey();
+    let to = solana_sdk::pubkey::new_rand();
+    let mint = Pubkey::new_unique();
+    let amount = 50;
+    let blockhash = Hash::new(to.as_ref());
+    let recipient_token_account =
+        get_associated_token_address_with_program_id(&to, &mint, &spl_token::id());
+    let instructions = [
+        create_associated_token_account_idempotent(&faucet_pubkey, &to, &mint, &spl_token::id()),
+        spl_token_2022::instruction::mint_to_checked(
+            &spl_token::id(),
+            &mint,
+            &recipient_token_account,
+            &faucet_pubkey,
+            &[],
+            amount,
+            9,
+        )
+        .unwrap(),
+    ];
+    let message = Message::new(&instructions, Some(&faucet_pubkey));
+    let expected_tx = Transaction::new(&[&keypair], message, blockhash);
+
+    let mut faucet = Faucet::new(keypair, None, None, None);
+    faucet.add_token(FaucetToken {
+        mint,
+        token_program_id: spl_token::id(),
+        decimals: 9,
+        source: TokenSource::MintAuthority,
+        per_time_cap: amount,
+        per_request_cap: None,
+    });
+    let faucet = Arc::new(Mutex::new(faucet));
+    let (sender, receiver) = unbounded();
+    thread::spawn(move || {
+        let faucet_addr: SocketAddr = "127.0.0.1:0".parse().unwrap();
+        Runtime::new()
+            .unwrap()
+            .block_on(run_faucet(faucet, faucet_addr, Some(sender)));
+    });
+    let faucet_addr = receiver.recv().unwrap().unwrap();
+
+    let result = request_token_airdrop_transaction(&faucet_addr, &to, &mint, amount, blockhash);
+    assert_eq!(expected_tx, result.unwrap());
+
+    let other_mint = Pubkey::new_unique();
+    assert!(
+        request_token_airdrop_transaction(&faucet_addr, &to, &other_mint, amount, blockhash)
+            .is_err()
+    );
+}
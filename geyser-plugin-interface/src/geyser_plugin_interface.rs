@@ -274,6 +274,45 @@ pub enum ReplicaBlockInfoVersions<'a> {
     V0_0_4(&'a ReplicaBlockInfoV4<'a>),
 }
 
+/// Summary of an account written in a slot, without the account data
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[repr(C)]
+pub struct ReplicaSlotAccountDiff<'a> {
+    /// The Pubkey for the account
+    pub pubkey: &'a [u8],
+
+    /// The Pubkey of the owner program account
+    pub owner: &'a [u8],
+
+    /// The lamports for the account
+    pub lamports: u64,
+
+    /// The BLAKE3 hash of the data held in this account
+    pub data_hash: &'a [u8],
+
+    /// The write_version of the last update to this account in the slot.
+    /// See `ReplicaAccountInfo::write_version`.
+    pub write_version: u64,
+}
+
+/// The set of accounts written in a rooted slot
+#[derive(Debug, Clone)]
+#[repr(C)]
+pub struct ReplicaSlotAccountsDiffInfo<'a> {
+    pub slot: Slot,
+    pub parent: Option<Slot>,
+    /// One entry per account written in the slot, ordered by write_version
+    pub accounts: &'a [ReplicaSlotAccountDiff<'a>],
+}
+
+/// A wrapper to future-proof ReplicaSlotAccountsDiffInfo handling. To make a change to the
+/// structure of ReplicaSlotAccountsDiffInfo, add an new enum variant wrapping a newer version,
+/// which will force plugin implementations to handle the change.
+#[repr(u32)]
+pub enum ReplicaSlotAccountsDiffInfoVersions<'a> {
+    V0_0_1(&'a ReplicaSlotAccountsDiffInfo<'a>),
+}
+
 /// Errors returned by plugin calls
 #[derive(Error, Debug)]
 #[repr(u32)]
@@ -434,6 +473,13 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
         Ok(())
     }
 
+    /// Called once per rooted slot with the pubkeys written in the slot, along with their
+    /// owner, lamports and a hash of their data, but not the data itself.
+    #[allow(unused_variables)]
+    fn notify_slot_accounts_diff(&self, diff: ReplicaSlotAccountsDiffInfoVersions) -> Result<()> {
+        Ok(())
+    }
+
     /// Check if the plugin is interested in account data
     /// Default is true -- if the plugin is not interested in
     /// account data, please return false.
@@ -454,4 +500,11 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
     fn entry_notifications_enabled(&self) -> bool {
         false
     }
+
+    /// Check if the plugin is interested in slot account diffs
+    /// Default is false -- if the plugin is interested in
+    /// slot account diffs, return true.
+    fn slot_accounts_diff_notifications_enabled(&self) -> bool {
+        false
+    }
 }
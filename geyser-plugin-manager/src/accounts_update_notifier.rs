@@ -1,6 +1,9 @@
 /// Module responsible for notifying plugins of account updates
 use {
-    crate::geyser_plugin_manager::GeyserPluginManager,
+    crate::{
+        geyser_plugin_manager::GeyserPluginManager,
+        slot_accounts_diff_notifier::SlotAccountsDiffNotifier,
+    },
     agave_geyser_plugin_interface::geyser_plugin_interface::{
         ReplicaAccountInfoV3, ReplicaAccountInfoVersions,
     },
@@ -22,6 +25,9 @@ use {
 #[derive(Debug)]
 pub(crate) struct AccountsUpdateNotifierImpl {
     plugin_manager: Arc<RwLock<GeyserPluginManager>>,
+    /// false if the plugins only want the slot account diffs, not every account update
+    account_data_notifications_enabled: bool,
+    slot_accounts_diff_notifier: Option<Arc<SlotAccountsDiffNotifier>>,
 }
 
 impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
@@ -33,12 +39,23 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
         pubkey: &Pubkey,
         write_version: u64,
     ) {
+        if let Some(slot_accounts_diff_notifier) = &self.slot_accounts_diff_notifier {
+            slot_accounts_diff_notifier.record_account_update(slot, pubkey, account, write_version);
+        }
+        if !self.account_data_notifications_enabled {
+            return;
+        }
+
         let account_info =
             self.accountinfo_from_shared_account_data(account, txn, pubkey, write_version);
         self.notify_plugins_of_account_update(account_info, slot, false);
     }
 
     fn notify_account_restore_from_snapshot(&self, slot: Slot, account: &StoredAccountMeta) {
+        if !self.account_data_notifications_enabled {
+            return;
+        }
+
         let mut measure_all = Measure::start("geyser-plugin-notify-account-restore-all");
         let mut measure_copy = Measure::start("geyser-plugin-copy-stored-account-info");
 
@@ -97,8 +114,16 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
 }
 
 impl AccountsUpdateNotifierImpl {
-    pub fn new(plugin_manager: Arc<RwLock<GeyserPluginManager>>) -> Self {
-        AccountsUpdateNotifierImpl { plugin_manager }
+    pub fn new(
+        plugin_manager: Arc<RwLock<GeyserPluginManager>>,
+        account_data_notifications_enabled: bool,
+        slot_accounts_diff_notifier: Option<Arc<SlotAccountsDiffNotifier>>,
+    ) -> Self {
+        AccountsUpdateNotifierImpl {
+            plugin_manager,
+            account_data_notifications_enabled,
+            slot_accounts_diff_notifier,
+        }
     }
 
     fn accountinfo_from_shared_account_data<'a>(
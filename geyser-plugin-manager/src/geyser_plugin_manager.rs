@@ -105,6 +105,16 @@ impl GeyserPluginManager {
         false
     }
 
+    /// Check if there is any plugin interested in slot account diffs
+    pub fn slot_accounts_diff_notifications_enabled(&self) -> bool {
+        for plugin in &self.plugins {
+            if plugin.slot_accounts_diff_notifications_enabled() {
+                return true;
+            }
+        }
+        false
+    }
+
     /// Admin RPC request handler
     pub(crate) fn list_plugins(&self) -> JsonRpcResult<Vec<String>> {
         Ok(self.plugins.iter().map(|p| p.name().to_owned()).collect())
@@ -5,6 +5,7 @@ use {
         block_metadata_notifier_interface::BlockMetadataNotifierArc,
         entry_notifier::EntryNotifierImpl,
         geyser_plugin_manager::{GeyserPluginManager, GeyserPluginManagerRequest},
+        slot_accounts_diff_notifier::SlotAccountsDiffNotifier,
         slot_status_notifier::SlotStatusNotifierImpl,
         slot_status_observer::SlotStatusObserver,
         transaction_notifier::TransactionNotifierImpl,
@@ -81,12 +82,20 @@ impl GeyserPluginService {
             plugin_manager.account_data_notifications_enabled();
         let transaction_notifications_enabled = plugin_manager.transaction_notifications_enabled();
         let entry_notifications_enabled = plugin_manager.entry_notifications_enabled();
+        let slot_accounts_diff_notifications_enabled =
+            plugin_manager.slot_accounts_diff_notifications_enabled();
         let plugin_manager = Arc::new(RwLock::new(plugin_manager));
 
+        let slot_accounts_diff_notifier = slot_accounts_diff_notifications_enabled
+            .then(|| Arc::new(SlotAccountsDiffNotifier::new(plugin_manager.clone())));
+
         let accounts_update_notifier: Option<AccountsUpdateNotifier> =
-            if account_data_notifications_enabled {
-                let accounts_update_notifier =
-                    AccountsUpdateNotifierImpl::new(plugin_manager.clone());
+            if account_data_notifications_enabled || slot_accounts_diff_notifications_enabled {
+                let accounts_update_notifier = AccountsUpdateNotifierImpl::new(
+                    plugin_manager.clone(),
+                    account_data_notifications_enabled,
+                    slot_accounts_diff_notifier.clone(),
+                );
                 Some(Arc::new(accounts_update_notifier))
             } else {
                 None
@@ -113,8 +122,10 @@ impl GeyserPluginService {
         ) = if account_data_notifications_enabled
             || transaction_notifications_enabled
             || entry_notifications_enabled
+            || slot_accounts_diff_notifications_enabled
         {
-            let slot_status_notifier = SlotStatusNotifierImpl::new(plugin_manager.clone());
+            let slot_status_notifier =
+                SlotStatusNotifierImpl::new(plugin_manager.clone(), slot_accounts_diff_notifier);
             let slot_status_notifier = Arc::new(RwLock::new(slot_status_notifier));
             (
                 Some(SlotStatusObserver::new(
@@ -0,0 +1,241 @@
+/// Module responsible for summarizing the accounts written in a slot and
+/// notifying plugins of the summary once the slot is rooted
+use {
+    crate::geyser_plugin_manager::GeyserPluginManager,
+    agave_geyser_plugin_interface::geyser_plugin_interface::{
+        ReplicaSlotAccountDiff, ReplicaSlotAccountsDiffInfo, ReplicaSlotAccountsDiffInfoVersions,
+    },
+    log::*,
+    solana_measure::measure::Measure,
+    solana_metrics::*,
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        blake3::{hash, Hash},
+        clock::Slot,
+        pubkey::Pubkey,
+    },
+    std::{
+        collections::{hash_map::Entry, HashMap},
+        sync::{Arc, Mutex, RwLock},
+    },
+};
+
+/// The last write to an account in a slot
+#[derive(Debug)]
+struct SlotAccountWrite {
+    /// cloning shares the account data, so keeping the account doesn't copy it
+    account: AccountSharedData,
+    write_version: u64,
+}
+
+/// The last write to an account in a slot, without the account data
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SlotAccountDiff {
+    owner: Pubkey,
+    lamports: u64,
+    data_hash: Hash,
+    write_version: u64,
+}
+
+impl From<&SlotAccountWrite> for SlotAccountDiff {
+    fn from(write: &SlotAccountWrite) -> Self {
+        Self {
+            owner: *write.account.owner(),
+            lamports: write.account.lamports(),
+            data_hash: hash(write.account.data()),
+            write_version: write.write_version,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SlotAccountsDiffNotifier {
+    plugin_manager: Arc<RwLock<GeyserPluginManager>>,
+    /// accounts written in each slot which has not been rooted yet
+    pending_slots: Mutex<HashMap<Slot, HashMap<Pubkey, SlotAccountWrite>>>,
+}
+
+impl SlotAccountsDiffNotifier {
+    pub fn new(plugin_manager: Arc<RwLock<GeyserPluginManager>>) -> Self {
+        Self {
+            plugin_manager,
+            pending_slots: Mutex::default(),
+        }
+    }
+
+    /// Records an account written at `slot`.
+    /// A write with a higher write_version supersedes earlier writes of the same account.
+    ///
+    /// The account data is only hashed once the slot is rooted, so superseded writes and writes
+    /// in slots which are never rooted are never hashed.
+    pub fn record_account_update(
+        &self,
+        slot: Slot,
+        pubkey: &Pubkey,
+        account: &AccountSharedData,
+        write_version: u64,
+    ) {
+        let write = || SlotAccountWrite {
+            account: account.clone(),
+            write_version,
+        };
+
+        let mut pending_slots = self.pending_slots.lock().unwrap();
+        match pending_slots.entry(slot).or_default().entry(*pubkey) {
+            Entry::Occupied(mut entry) => {
+                if entry.get().write_version <= write_version {
+                    entry.insert(write());
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(write());
+            }
+        }
+    }
+
+    /// Notifies plugins of the accounts written in `slot`, which has just been rooted.
+    pub fn notify_slot_rooted(&self, slot: Slot, parent: Option<Slot>) {
+        let writes = self.take_rooted_slot(slot);
+
+        let plugin_manager = self.plugin_manager.read().unwrap();
+        // Plugins may have been unloaded since the service started
+        if !plugin_manager
+            .plugins
+            .iter()
+            .any(|plugin| plugin.slot_accounts_diff_notifications_enabled())
+        {
+            return;
+        }
+
+        let diffs: Vec<_> = writes
+            .iter()
+            .map(|(pubkey, write)| (pubkey, SlotAccountDiff::from(write)))
+            .collect();
+        let accounts: Vec<_> = diffs
+            .iter()
+            .map(|(pubkey, diff)| ReplicaSlotAccountDiff {
+                pubkey: pubkey.as_ref(),
+                owner: diff.owner.as_ref(),
+                lamports: diff.lamports,
+                data_hash: diff.data_hash.as_ref(),
+                write_version: diff.write_version,
+            })
+            .collect();
+        let diff_info = ReplicaSlotAccountsDiffInfo {
+            slot,
+            parent,
+            accounts: &accounts,
+        };
+
+        for plugin in plugin_manager.plugins.iter() {
+            if !plugin.slot_accounts_diff_notifications_enabled() {
+                continue;
+            }
+            let mut measure = Measure::start("geyser-plugin-notify-slot-accounts-diff");
+            match plugin
+                .notify_slot_accounts_diff(ReplicaSlotAccountsDiffInfoVersions::V0_0_1(&diff_info))
+            {
+                Err(err) => {
+                    error!(
+                        "Failed to notify slot accounts diff at slot {}, error: {} to plugin {}",
+                        slot,
+                        err,
+                        plugin.name()
+                    )
+                }
+                Ok(_) => {
+                    trace!(
+                        "Successfully notified slot accounts diff at slot {} to plugin {}",
+                        slot,
+                        plugin.name()
+                    );
+                }
+            }
+            measure.stop();
+            inc_new_counter_debug!(
+                "geyser-plugin-notify-slot-accounts-diff-us",
+                measure.as_us() as usize,
+                1000,
+                1000
+            );
+        }
+    }
+
+    /// Removes and returns the accounts written in the rooted `slot`, ordered by write_version.
+    ///
+    /// Roots are notified in increasing order, so any pending slot older than `slot` was on a
+    /// fork which can no longer be rooted and is discarded.
+    fn take_rooted_slot(&self, slot: Slot) -> Vec<(Pubkey, SlotAccountWrite)> {
+        let accounts = {
+            let mut pending_slots = self.pending_slots.lock().unwrap();
+            let accounts = pending_slots.remove(&slot).unwrap_or_default();
+            pending_slots.retain(|pending_slot, _| *pending_slot > slot);
+            accounts
+        };
+
+        let mut accounts: Vec<_> = accounts.into_iter().collect();
+        accounts.sort_unstable_by_key(|(_, write)| write.write_version);
+        accounts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_sdk::account::WritableAccount};
+
+    #[test]
+    fn test_take_rooted_slot() {
+        let notifier =
+            SlotAccountsDiffNotifier::new(Arc::new(RwLock::new(GeyserPluginManager::new())));
+        let owner = Pubkey::new_unique();
+        let pubkey1 = Pubkey::new_unique();
+        let pubkey2 = Pubkey::new_unique();
+        let mut account = AccountSharedData::new(1, 3, &owner);
+
+        // slot 1: pubkey1 is written twice, and the later write wins
+        notifier.record_account_update(1, &pubkey1, &account, 2);
+        notifier.record_account_update(1, &pubkey2, &account, 3);
+        account.set_lamports(10);
+        account.data_as_mut_slice().copy_from_slice(&[1, 2, 3]);
+        notifier.record_account_update(1, &pubkey1, &account, 4);
+        // slot 2 is on a fork which never gets rooted
+        notifier.record_account_update(2, &pubkey1, &account, 5);
+        notifier.record_account_update(3, &pubkey2, &account, 6);
+
+        let accounts: Vec<_> = notifier
+            .take_rooted_slot(1)
+            .iter()
+            .map(|(pubkey, write)| (*pubkey, SlotAccountDiff::from(write)))
+            .collect();
+        assert_eq!(
+            accounts,
+            vec![
+                (
+                    pubkey2,
+                    SlotAccountDiff {
+                        owner,
+                        lamports: 1,
+                        data_hash: hash(&[0, 0, 0]),
+                        write_version: 3,
+                    }
+                ),
+                (
+                    pubkey1,
+                    SlotAccountDiff {
+                        owner,
+                        lamports: 10,
+                        data_hash: hash(&[1, 2, 3]),
+                        write_version: 4,
+                    }
+                ),
+            ]
+        );
+        assert!(notifier.take_rooted_slot(1).is_empty());
+
+        // rooting slot 3 discards the dead fork at slot 2
+        let accounts = notifier.take_rooted_slot(3);
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].0, pubkey2);
+        assert!(notifier.pending_slots.lock().unwrap().is_empty());
+    }
+}
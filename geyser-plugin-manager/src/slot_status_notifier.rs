@@ -1,5 +1,8 @@
 use {
-    crate::geyser_plugin_manager::GeyserPluginManager,
+    crate::{
+        geyser_plugin_manager::GeyserPluginManager,
+        slot_accounts_diff_notifier::SlotAccountsDiffNotifier,
+    },
     agave_geyser_plugin_interface::geyser_plugin_interface::SlotStatus,
     log::*,
     solana_measure::measure::Measure,
@@ -23,6 +26,7 @@ pub type SlotStatusNotifier = Arc<RwLock<dyn SlotStatusNotifierInterface + Sync
 
 pub struct SlotStatusNotifierImpl {
     plugin_manager: Arc<RwLock<GeyserPluginManager>>,
+    slot_accounts_diff_notifier: Option<Arc<SlotAccountsDiffNotifier>>,
 }
 
 impl SlotStatusNotifierInterface for SlotStatusNotifierImpl {
@@ -36,12 +40,21 @@ impl SlotStatusNotifierInterface for SlotStatusNotifierImpl {
 
     fn notify_slot_rooted(&self, slot: Slot, parent: Option<Slot>) {
         self.notify_slot_status(slot, parent, SlotStatus::Rooted);
+        if let Some(slot_accounts_diff_notifier) = &self.slot_accounts_diff_notifier {
+            slot_accounts_diff_notifier.notify_slot_rooted(slot, parent);
+        }
     }
 }
 
 impl SlotStatusNotifierImpl {
-    pub fn new(plugin_manager: Arc<RwLock<GeyserPluginManager>>) -> Self {
-        Self { plugin_manager }
+    pub fn new(
+        plugin_manager: Arc<RwLock<GeyserPluginManager>>,
+        slot_accounts_diff_notifier: Option<Arc<SlotAccountsDiffNotifier>>,
+    ) -> Self {
+        Self {
+            plugin_manager,
+            slot_accounts_diff_notifier,
+        }
     }
 
     pub fn notify_slot_status(&self, slot: Slot, parent: Option<Slot>, slot_status: SlotStatus) {
@@ -34,12 +34,14 @@ use {
         gossip_error::GossipError,
         legacy_contact_info::LegacyContactInfo,
         ping_pong::{self, PingCache, Pong},
+        quic_endpoint::{GossipQuicRouter, MAX_QUIC_MESSAGE_SIZE},
         restart_crds_values::{
             RestartHeaviestFork, RestartLastVotedForkSlots, RestartLastVotedForkSlotsError,
         },
         weighted_shuffle::WeightedShuffle,
     },
-    bincode::{serialize, serialized_size},
+    bincode::{serialize, serialized_size, Options},
+    bytes::Bytes,
     crossbeam_channel::{Receiver, RecvTimeoutError, Sender},
     itertools::Itertools,
     rand::{seq::SliceRandom, thread_rng, CryptoRng, Rng},
@@ -91,7 +93,7 @@ use {
         path::{Path, PathBuf},
         result::Result,
         sync::{
-            atomic::{AtomicBool, Ordering},
+            atomic::{AtomicBool, AtomicU64, Ordering},
             Arc, Mutex, RwLock, RwLockReadGuard,
         },
         thread::{sleep, Builder, JoinHandle},
@@ -113,6 +115,10 @@ const MAX_GOSSIP_TRAFFIC: usize = 128_000_000 / PACKET_DATA_SIZE;
 /// is equal to PACKET_DATA_SIZE minus serialized size of an empty push
 /// message: Protocol::PushMessage(Pubkey::default(), Vec::default())
 const PUSH_MESSAGE_MAX_PAYLOAD_SIZE: usize = PACKET_DATA_SIZE - 44;
+/// Max size of serialized crds-values in a Protocol::PushMessage or a
+/// Protocol::PullResponse sent over QUIC. This is equal to
+/// MAX_QUIC_MESSAGE_SIZE minus serialized size of an empty push message.
+const QUIC_MESSAGE_MAX_PAYLOAD_SIZE: usize = MAX_QUIC_MESSAGE_SIZE - 44;
 pub(crate) const DUPLICATE_SHRED_MAX_PAYLOAD_SIZE: usize = PACKET_DATA_SIZE - 115;
 /// Maximum number of hashes in AccountsHashes a node publishes
 /// such that the serialized size of the push/pull message stays below
@@ -138,6 +144,8 @@ pub const DEFAULT_CONTACT_SAVE_INTERVAL_MILLIS: u64 = 60_000;
 const PULL_RESPONSE_MIN_SERIALIZED_SIZE: usize = 161;
 // Limit number of unique pubkeys in the crds table.
 pub(crate) const CRDS_UNIQUE_PUBKEY_CAPACITY: usize = 8192;
+/// Interval at which the gossip QUIC addresses of the nodes are refreshed.
+const GOSSIP_QUIC_ADDRS_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
 /// Minimum stake that a node should have so that its CRDS values are
 /// propagated through gossip (few types are exempted).
 const MIN_STAKE_FOR_GOSSIP: u64 = solana_sdk::native_token::LAMPORTS_PER_SOL;
@@ -174,6 +182,10 @@ pub struct ClusterInfo {
     my_contact_info: RwLock<ContactInfo>,
     ping_cache: Mutex<PingCache>,
     stats: GossipStats,
+    // Unlike the stats, not reset when the metrics are submitted.
+    quic_messages_received: AtomicU64,
+    // Set only if gossip over QUIC is enabled.
+    quic_router: RwLock<Option<Arc<GossipQuicRouter>>>,
     socket: UdpSocket,
     local_message_pending_push_queue: Mutex<Vec<CrdsValue>>,
     contact_debug_interval: u64, // milliseconds, 0 = disabled
@@ -393,6 +405,19 @@ impl Protocol {
             }
         }
     }
+
+    // Returns the node which originated the message, which for messages
+    // received over QUIC should match the identity bound to the connection.
+    fn sender(&self) -> Pubkey {
+        match self {
+            Protocol::PullRequest(_, caller) => caller.pubkey(),
+            Protocol::PullResponse(from, _) => *from,
+            Protocol::PushMessage(from, _) => *from,
+            Protocol::PruneMessage(from, _) => *from,
+            Protocol::PingMessage(ping) => ping.pubkey(),
+            Protocol::PongMessage(pong) => *pong.from(),
+        }
+    }
 }
 
 impl Sanitize for Protocol {
@@ -467,6 +492,8 @@ impl ClusterInfo {
                 GOSSIP_PING_CACHE_CAPACITY,
             )),
             stats: GossipStats::default(),
+            quic_messages_received: AtomicU64::default(),
+            quic_router: RwLock::default(),
             socket: UdpSocket::bind("0.0.0.0:0").unwrap(),
             local_message_pending_push_queue: Mutex::default(),
             contact_debug_interval: DEFAULT_CONTACT_DEBUG_INTERVAL_MILLIS,
@@ -668,6 +695,19 @@ impl ClusterInfo {
         self.keypair.read().unwrap().pubkey()
     }
 
+    /// Returns the total number of verified gossip messages received over QUIC.
+    pub fn quic_messages_received(&self) -> u64 {
+        self.quic_messages_received.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_quic_router(&self, quic_router: Arc<GossipQuicRouter>) {
+        *self.quic_router.write().unwrap() = Some(quic_router);
+    }
+
+    fn quic_router(&self) -> Option<Arc<GossipQuicRouter>> {
+        self.quic_router.read().unwrap().clone()
+    }
+
     pub fn keypair(&self) -> RwLockReadGuard<Arc<Keypair>> {
         self.keypair.read().unwrap()
     }
@@ -1661,10 +1701,22 @@ impl ClusterInfo {
                 })
                 .collect()
         };
-        let messages: Vec<_> = push_messages
+        // Push messages to the nodes which accept gossip over QUIC are not
+        // bounded by PACKET_DATA_SIZE, so they pack more values.
+        let (quic_push_messages, push_messages) = match self.quic_router() {
+            None => (Vec::default(), push_messages),
+            Some(quic_router) => quic_router.partition(push_messages),
+        };
+        let messages: Vec<_> = quic_push_messages
             .into_iter()
-            .flat_map(|(peer, msgs)| {
-                Self::split_gossip_messages(PUSH_MESSAGE_MAX_PAYLOAD_SIZE, msgs)
+            .map(|(peer, msgs)| (peer, msgs, QUIC_MESSAGE_MAX_PAYLOAD_SIZE))
+            .chain(
+                push_messages
+                    .into_iter()
+                    .map(|(peer, msgs)| (peer, msgs, PUSH_MESSAGE_MAX_PAYLOAD_SIZE)),
+            )
+            .flat_map(|(peer, msgs, max_payload_size)| {
+                Self::split_gossip_messages(max_payload_size, msgs)
                     .map(move |payload| (peer, Protocol::PushMessage(self_id, payload)))
             })
             .collect();
@@ -1723,6 +1775,7 @@ impl ClusterInfo {
             stakes,
             generate_pull_requests,
         );
+        let reqs = self.send_quic_messages(reqs);
         if !reqs.is_empty() {
             let packet_batch = PacketBatch::new_unpinned_with_recycler_data_and_dests(
                 recycler,
@@ -2166,11 +2219,45 @@ impl ClusterInfo {
         if responses.is_empty() {
             return packet_batch;
         }
+        // Pull responses to the nodes which accept gossip over QUIC are packed
+        // into messages which are not bounded by PACKET_DATA_SIZE.
+        let quic_router = self.quic_router();
+        let quic_addrs: HashMap<SocketAddr, SocketAddr> = quic_router
+            .as_ref()
+            .map(|quic_router| {
+                let (quic_addrs, _) = quic_router.partition(addrs.iter().map(|&addr| (addr, addr)));
+                quic_addrs
+                    .into_iter()
+                    .map(|(quic_addr, addr)| (addr, quic_addr))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut quic_responses = HashMap::<SocketAddr, Vec<CrdsValue>>::new();
         let mut rng = rand::thread_rng();
         let shuffle = WeightedShuffle::new("handle-pull-requests", &scores).shuffle(&mut rng);
         let mut total_bytes = 0;
         let mut sent = 0;
         for (addr, response) in shuffle.map(|i| &responses[i]) {
+            if let Some(&quic_addr) = quic_addrs.get(*addr) {
+                let size = match serialized_size(response) {
+                    Ok(size) => size as usize,
+                    Err(err) => {
+                        error!("failed to serialize pull-response: {:?}", err);
+                        continue;
+                    }
+                };
+                if !self.outbound_budget.take(size) {
+                    self.stats.gossip_pull_request_no_budget.add_relaxed(1);
+                    break;
+                }
+                total_bytes += size;
+                quic_responses
+                    .entry(quic_addr)
+                    .or_default()
+                    .push(response.clone());
+                sent += 1;
+                continue;
+            }
             let response = vec![response.clone()];
             let response = Protocol::PullResponse(self_id, response);
             match Packet::from_data(Some(addr), response) {
@@ -2187,6 +2274,14 @@ impl ClusterInfo {
                 }
             }
         }
+        if let Some(quic_router) = &quic_router {
+            for (addr, values) in quic_responses {
+                for values in Self::split_gossip_messages(QUIC_MESSAGE_MAX_PAYLOAD_SIZE, values) {
+                    let response = Protocol::PullResponse(self_id, values);
+                    self.send_quic_message(quic_router, addr, &response);
+                }
+            }
+        }
         time.stop();
         let dropped_responses = responses.len() - sent;
         self.stats
@@ -2408,7 +2503,7 @@ impl ClusterInfo {
         self.stats
             .push_message_pushes
             .add_relaxed(new_push_requests.len() as u64);
-        for (address, request) in new_push_requests {
+        for (address, request) in self.send_quic_messages(new_push_requests) {
             if ContactInfo::is_valid_address(&address, &self.socket_addr_space) {
                 match Packet::from_data(Some(&address), &request) {
                     Ok(packet) => packet_batch.push(packet),
@@ -2746,6 +2841,211 @@ impl ClusterInfo {
         Builder::new().name(thread_name).spawn(run_consume).unwrap()
     }
 
+    // Consumes messages received over QUIC. The sending node's identity is
+    // known from the connection, so messages which were not originated by
+    // that node are discarded before the signatures are verified.
+    fn run_quic_consume(
+        &self,
+        receiver: &Receiver<(/*identity:*/ Pubkey, /*from:*/ SocketAddr, Bytes)>,
+        sender: &Sender<Vec<(/*from:*/ SocketAddr, Protocol)>>,
+        quic_router: &GossipQuicRouter,
+        thread_pool: &ThreadPool,
+    ) -> Result<(), GossipError> {
+        const RECV_TIMEOUT: Duration = Duration::from_secs(1);
+        let mut messages = VecDeque::from([receiver.recv_timeout(RECV_TIMEOUT)?]);
+        for message in receiver.try_iter() {
+            messages.push_back(message);
+            if messages.len() > MAX_GOSSIP_TRAFFIC {
+                messages.pop_front();
+                self.stats.gossip_packets_dropped_count.add_relaxed(1);
+            }
+        }
+        self.stats
+            .quic_messages_received_count
+            .add_relaxed(messages.len() as u64);
+        let verify_message = |(identity, from, bytes): (Pubkey, SocketAddr, Bytes)| {
+            let protocol = bincode::options()
+                .with_limit(MAX_QUIC_MESSAGE_SIZE as u64)
+                .with_fixint_encoding()
+                .reject_trailing_bytes()
+                .deserialize::<Protocol>(&bytes)
+                .ok()
+                .filter(|protocol| protocol.sanitize().is_ok());
+            let Some(protocol) = protocol else {
+                self.stats.quic_messages_invalid_count.add_relaxed(1);
+                return None;
+            };
+            if protocol.sender() != identity {
+                self.stats
+                    .quic_messages_identity_mismatch_count
+                    .add_relaxed(1);
+                return None;
+            }
+            let protocol = protocol.par_verify(&self.stats)?;
+            Some((from, protocol))
+        };
+        let messages: Vec<_> = {
+            let _st = ScopedTimer::from(&self.stats.verify_gossip_packets_time);
+            thread_pool.install(|| {
+                messages
+                    .into_par_iter()
+                    .filter_map(verify_message)
+                    .collect()
+            })
+        };
+        // Responses to these nodes are sent back over QUIC too.
+        quic_router.add_peers(messages.iter().map(|(from, _)| *from));
+        self.stats
+            .packets_received_verified_count
+            .add_relaxed(messages.len() as u64);
+        self.quic_messages_received
+            .fetch_add(messages.len() as u64, Ordering::Relaxed);
+        Ok(sender.send(messages)?)
+    }
+
+    pub(crate) fn start_quic_consume_thread(
+        self: Arc<Self>,
+        receiver: Receiver<(/*identity:*/ Pubkey, /*from:*/ SocketAddr, Bytes)>,
+        sender: Sender<Vec<(/*from:*/ SocketAddr, Protocol)>>,
+        quic_router: Arc<GossipQuicRouter>,
+        exit: Arc<AtomicBool>,
+    ) -> JoinHandle<()> {
+        let thread_pool = ThreadPoolBuilder::new()
+            .num_threads(get_thread_count().min(8))
+            .thread_name(|i| format!("solGossipQCon{i:02}"))
+            .build()
+            .unwrap();
+        let run_consume = move || {
+            while !exit.load(Ordering::Relaxed) {
+                match self.run_quic_consume(&receiver, &sender, &quic_router, &thread_pool) {
+                    Err(GossipError::RecvTimeoutError(RecvTimeoutError::Disconnected)) => break,
+                    Err(GossipError::RecvTimeoutError(RecvTimeoutError::Timeout)) => (),
+                    Err(GossipError::SendError) => break,
+                    Err(err) => error!("gossip quic consume: {}", err),
+                    Ok(()) => (),
+                }
+            }
+        };
+        let thread_name = String::from("solGossipQCons");
+        Builder::new().name(thread_name).spawn(run_consume).unwrap()
+    }
+
+    // Maps both the UDP and the QUIC gossip addresses of the nodes which
+    // advertise a gossip QUIC address, to the QUIC address.
+    fn get_gossip_quic_addrs(&self) -> HashMap<SocketAddr, SocketAddr> {
+        let self_pubkey = self.id();
+        self.gossip
+            .crds
+            .read()
+            .unwrap()
+            .get_nodes_contact_info()
+            .filter(|node| node.pubkey() != &self_pubkey)
+            .filter_map(|node| Some((node.gossip().ok(), node.gossip_quic().ok()?)))
+            .flat_map(|(gossip, quic)| {
+                gossip
+                    .map(|gossip| (gossip, quic))
+                    .into_iter()
+                    .chain([(quic, quic)])
+            })
+            .collect()
+    }
+
+    fn send_quic_message(
+        &self,
+        quic_router: &GossipQuicRouter,
+        addr: SocketAddr,
+        message: &Protocol,
+    ) {
+        let bytes = match serialize(message) {
+            Ok(bytes) => Bytes::from(bytes),
+            Err(err) => {
+                error!("failed to serialize gossip message: {:?}", err);
+                return;
+            }
+        };
+        if quic_router.try_send(addr, bytes) {
+            self.stats.quic_messages_sent_count.add_relaxed(1);
+        } else {
+            self.stats.quic_messages_dropped_count.add_relaxed(1);
+        }
+    }
+
+    // Sends the messages destined to nodes which accept gossip over QUIC to
+    // the QUIC endpoint, and returns the remaining messages to be sent over
+    // UDP. Unlike packets, these messages may be larger than PACKET_DATA_SIZE.
+    fn send_quic_messages(
+        &self,
+        messages: Vec<(SocketAddr, Protocol)>,
+    ) -> Vec<(SocketAddr, Protocol)> {
+        let Some(quic_router) = self.quic_router() else {
+            return messages;
+        };
+        let (quic_messages, udp_messages) = quic_router.partition(messages);
+        for (addr, message) in quic_messages {
+            self.send_quic_message(&quic_router, addr, &message);
+        }
+        udp_messages
+    }
+
+    // Sends the packets destined to nodes which accept gossip over QUIC to
+    // the QUIC endpoint, and returns the remaining packets to be sent over
+    // UDP.
+    fn route_gossip_packets(
+        &self,
+        packets: PacketBatch,
+        quic_router: &GossipQuicRouter,
+    ) -> PacketBatch {
+        let (quic_packets, udp_packets) = quic_router.partition(
+            Vec::from(packets)
+                .into_iter()
+                .map(|packet| (packet.meta().socket_addr(), packet)),
+        );
+        for (addr, packet) in quic_packets {
+            let Some(data) = packet.data(..) else {
+                continue;
+            };
+            if quic_router.try_send(addr, Bytes::copy_from_slice(data)) {
+                self.stats.quic_messages_sent_count.add_relaxed(1);
+            } else {
+                self.stats.quic_messages_dropped_count.add_relaxed(1);
+            }
+        }
+        PacketBatch::new(udp_packets.into_iter().map(|(_, packet)| packet).collect())
+    }
+
+    pub(crate) fn start_quic_router_thread(
+        self: Arc<Self>,
+        receiver: PacketBatchReceiver,
+        udp_sender: PacketBatchSender,
+        quic_router: Arc<GossipQuicRouter>,
+        exit: Arc<AtomicBool>,
+    ) -> JoinHandle<()> {
+        const RECV_TIMEOUT: Duration = Duration::from_secs(1);
+        let run_router = move || {
+            let mut last_refresh: Option<Instant> = None;
+            while !exit.load(Ordering::Relaxed) {
+                if last_refresh
+                    .map(|last_refresh| last_refresh.elapsed() > GOSSIP_QUIC_ADDRS_REFRESH_INTERVAL)
+                    .unwrap_or(true)
+                {
+                    quic_router.set_addrs(self.get_gossip_quic_addrs());
+                    last_refresh = Some(Instant::now());
+                }
+                let packets = match receiver.recv_timeout(RECV_TIMEOUT) {
+                    Ok(packets) => packets,
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                };
+                let packets = self.route_gossip_packets(packets, &quic_router);
+                if !packets.is_empty() && udp_sender.send(packets).is_err() {
+                    break;
+                }
+            }
+        };
+        let thread_name = String::from("solGossipQRoute");
+        Builder::new().name(thread_name).spawn(run_router).unwrap()
+    }
+
     pub(crate) fn listen(
         self: Arc<Self>,
         bank_forks: Option<Arc<RwLock<BankForks>>>,
@@ -2859,6 +3159,9 @@ fn get_epoch_duration(bank_forks: Option<&RwLock<BankForks>>, stats: &GossipStat
 #[derive(Debug)]
 pub struct Sockets {
     pub gossip: UdpSocket,
+    /// Only bound if gossip over QUIC is enabled, since it takes one more
+    /// port from the port range.
+    pub gossip_quic: Option<UdpSocket>,
     pub ip_echo: Option<TcpListener>,
     pub tvu: Vec<UdpSocket>,
     pub tvu_quic: UdpSocket,
@@ -2885,6 +3188,8 @@ pub struct NodeConfig {
     pub num_tvu_sockets: NonZeroUsize,
     /// The number of QUIC tpu endpoints
     pub num_quic_endpoints: NonZeroUsize,
+    /// Bind a socket for gossip over QUIC
+    pub enable_gossip_quic: bool,
 }
 
 #[derive(Debug)]
@@ -2928,6 +3233,7 @@ impl Node {
         let (gossip_port, (gossip, ip_echo)) =
             bind_common_in_range(localhost_ip_addr, port_range).unwrap();
         let gossip_addr = SocketAddr::new(localhost_ip_addr, gossip_port);
+        let gossip_quic = Some(UdpSocket::bind(&localhost_bind_addr).unwrap());
         let tvu = UdpSocket::bind(&localhost_bind_addr).unwrap();
         let tvu_quic = UdpSocket::bind(&localhost_bind_addr).unwrap();
         let ((_tpu_forwards_port, tpu_forwards), (_tpu_forwards_quic_port, tpu_forwards_quic)) =
@@ -2992,6 +3298,7 @@ impl Node {
             info,
             sockets: Sockets {
                 gossip,
+                gossip_quic,
                 ip_echo: Some(ip_echo),
                 tvu: vec![tvu],
                 tvu_quic,
@@ -3118,6 +3425,7 @@ impl Node {
             info,
             sockets: Sockets {
                 gossip,
+                gossip_quic: None,
                 ip_echo: Some(ip_echo),
                 tvu: vec![tvu],
                 tvu_quic,
@@ -3145,10 +3453,12 @@ impl Node {
             public_tpu_forwards_addr,
             num_tvu_sockets,
             num_quic_endpoints,
+            enable_gossip_quic,
         } = config;
 
         let (gossip_port, (gossip, ip_echo)) =
             Self::get_gossip_port(&gossip_addr, port_range, bind_ip_addr);
+        let gossip_quic = enable_gossip_quic.then(|| Self::bind(bind_ip_addr, port_range).1);
 
         let (tvu_port, tvu_sockets) =
             multi_bind_in_range(bind_ip_addr, port_range, num_tvu_sockets.get())
@@ -3224,6 +3534,7 @@ impl Node {
             info,
             sockets: Sockets {
                 gossip,
+                gossip_quic,
                 tvu: tvu_sockets,
                 tvu_quic,
                 tpu: tpu_sockets,
@@ -3655,6 +3966,33 @@ mod tests {
         assert!(Packet::from_data(Some(&socket), prune_message).is_err());
     }
 
+    #[test]
+    fn test_protocol_sender() {
+        let mut rng = rand::thread_rng();
+        let keypair = Keypair::new();
+        let other = Keypair::new();
+        let ping = Ping::new_rand(&mut rng, &keypair).unwrap();
+        let pong = Pong::new(&ping, &other).unwrap();
+        assert_eq!(Protocol::PingMessage(ping).sender(), keypair.pubkey());
+        assert_eq!(Protocol::PongMessage(pong).sender(), other.pubkey());
+        assert_eq!(
+            Protocol::PushMessage(keypair.pubkey(), Vec::default()).sender(),
+            keypair.pubkey()
+        );
+        assert_eq!(
+            Protocol::PullResponse(other.pubkey(), Vec::default()).sender(),
+            other.pubkey()
+        );
+        let caller = CrdsValue::new_signed(
+            CrdsData::ContactInfo(ContactInfo::new_localhost(&other.pubkey(), timestamp())),
+            &other,
+        );
+        assert_eq!(
+            Protocol::PullRequest(CrdsFilter::default(), caller).sender(),
+            other.pubkey()
+        );
+    }
+
     #[test]
     fn test_push_message_max_payload_size() {
         let header = Protocol::PushMessage(Pubkey::default(), Vec::default());
@@ -3662,6 +4000,104 @@ mod tests {
             PUSH_MESSAGE_MAX_PAYLOAD_SIZE,
             PACKET_DATA_SIZE - serialized_size(&header).unwrap() as usize
         );
+        assert_eq!(
+            QUIC_MESSAGE_MAX_PAYLOAD_SIZE,
+            MAX_QUIC_MESSAGE_SIZE - serialized_size(&header).unwrap() as usize
+        );
+        let header = Protocol::PullResponse(Pubkey::default(), Vec::default());
+        assert_eq!(
+            QUIC_MESSAGE_MAX_PAYLOAD_SIZE,
+            MAX_QUIC_MESSAGE_SIZE - serialized_size(&header).unwrap() as usize
+        );
+    }
+
+    #[test]
+    fn test_quic_messages_carry_large_values() {
+        let thread_pool = ThreadPoolBuilder::new().build().unwrap();
+        let keypair = Arc::new(Keypair::new());
+        let peer_keypair = Keypair::new();
+        let contact_info = ContactInfo::new_localhost(&keypair.pubkey(), timestamp());
+        let mut peer = ContactInfo::new_localhost(&peer_keypair.pubkey(), timestamp());
+        let peer_quic_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, 8100));
+        peer.set_gossip_quic(peer_quic_addr).unwrap();
+        let cluster_info =
+            ClusterInfo::new(contact_info, keypair.clone(), SocketAddrSpace::Unspecified);
+        let (quic_sender, mut quic_receiver) = tokio::sync::mpsc::channel(1024);
+        let quic_router = Arc::new(GossipQuicRouter::new(quic_sender));
+        cluster_info.set_quic_router(quic_router.clone());
+        cluster_info.ping_cache.lock().unwrap().mock_pong(
+            *peer.pubkey(),
+            peer.gossip().unwrap(),
+            Instant::now(),
+        );
+        cluster_info.insert_info(peer.clone());
+        quic_router.set_addrs(cluster_info.get_gossip_quic_addrs());
+        let stakes: HashMap<Pubkey, u64> =
+            repeat_with(|| (Pubkey::new_unique(), 1)).take(8).collect();
+        cluster_info.gossip.refresh_push_active_set(
+            &cluster_info.keypair(),
+            cluster_info.my_shred_version(),
+            &stakes,
+            None, // gossip validators
+            &cluster_info.ping_cache,
+            &mut Vec::new(), // pings
+            &SocketAddrSpace::Unspecified,
+        );
+        // A CRDS value which does not fit in a UDP packet.
+        let value = CrdsValue::new_signed(
+            CrdsData::SnapshotHashes(SnapshotHashes {
+                from: keypair.pubkey(),
+                full: (0, Hash::new_unique()),
+                incremental: (1..=50).map(|slot| (slot, Hash::new_unique())).collect(),
+                wallclock: timestamp(),
+            }),
+            &keypair,
+        );
+        assert!(serialized_size(&value).unwrap() > PACKET_DATA_SIZE as u64);
+        cluster_info.push_message(value.clone());
+        // Returns the messages sent to the peer over QUIC, which include the
+        // large value.
+        let mut recv_quic_messages = || {
+            let messages: Vec<_> = std::iter::from_fn(|| quic_receiver.try_recv().ok())
+                .map(|(addr, bytes)| {
+                    assert_eq!(addr, peer_quic_addr);
+                    assert!(bytes.len() <= MAX_QUIC_MESSAGE_SIZE);
+                    bincode::deserialize::<Protocol>(&bytes).unwrap()
+                })
+                .collect();
+            assert!(messages.iter().any(|message| match message {
+                Protocol::PushMessage(_, values) | Protocol::PullResponse(_, values) =>
+                    values.contains(&value),
+                _ => false,
+            }));
+            messages
+        };
+
+        // Push messages to the peer are sent over QUIC.
+        let push_requests = cluster_info.new_push_requests(&stakes);
+        assert!(!push_requests.is_empty());
+        assert!(cluster_info.send_quic_messages(push_requests).is_empty());
+        for message in recv_quic_messages() {
+            assert_matches!(message, Protocol::PushMessage(from, _) if from == cluster_info.id());
+        }
+
+        // So are pull responses.
+        let caller = CrdsValue::new_signed(CrdsData::ContactInfo(peer.clone()), &peer_keypair);
+        let request = PullData {
+            from_addr: peer.gossip().unwrap(),
+            caller,
+            filter: CrdsFilter::default(),
+        };
+        let packet_batch = cluster_info.handle_pull_requests(
+            &thread_pool,
+            &PacketBatchRecycler::default(),
+            vec![request],
+            &stakes,
+        );
+        assert!(packet_batch.is_empty());
+        for message in recv_quic_messages() {
+            assert_matches!(message, Protocol::PullResponse(from, _) if from == cluster_info.id());
+        }
     }
 
     #[test]
@@ -3796,6 +4232,9 @@ mod tests {
 
     fn check_node_sockets(node: &Node, ip: IpAddr, range: (u16, u16)) {
         check_socket(&node.sockets.gossip, ip, range);
+        if let Some(gossip_quic) = &node.sockets.gossip_quic {
+            check_socket(gossip_quic, ip, range);
+        }
         check_socket(&node.sockets.repair, ip, range);
         check_socket(&node.sockets.tvu_quic, ip, range);
 
@@ -3814,11 +4253,13 @@ mod tests {
             public_tpu_forwards_addr: None,
             num_tvu_sockets: MINIMUM_NUM_TVU_SOCKETS,
             num_quic_endpoints: DEFAULT_NUM_QUIC_ENDPOINTS,
+            enable_gossip_quic: false,
         };
 
         let node = Node::new_with_external_ip(&solana_sdk::pubkey::new_rand(), config);
 
         check_node_sockets(&node, IpAddr::V4(ip), VALIDATOR_PORT_RANGE);
+        assert!(node.sockets.gossip_quic.is_none());
     }
 
     #[test]
@@ -3837,11 +4278,13 @@ mod tests {
             public_tpu_forwards_addr: None,
             num_tvu_sockets: MINIMUM_NUM_TVU_SOCKETS,
             num_quic_endpoints: DEFAULT_NUM_QUIC_ENDPOINTS,
+            enable_gossip_quic: true,
         };
 
         let node = Node::new_with_external_ip(&solana_sdk::pubkey::new_rand(), config);
 
         check_node_sockets(&node, ip, port_range);
+        assert!(node.sockets.gossip_quic.is_some());
 
         assert_eq!(node.sockets.gossip.local_addr().unwrap().port(), port);
     }
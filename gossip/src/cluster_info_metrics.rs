@@ -170,6 +170,11 @@ pub struct GossipStats {
     pub(crate) push_message_value_count: Counter,
     pub(crate) push_response_count: Counter,
     pub(crate) push_vote_read: Counter,
+    pub(crate) quic_messages_dropped_count: Counter,
+    pub(crate) quic_messages_identity_mismatch_count: Counter,
+    pub(crate) quic_messages_invalid_count: Counter,
+    pub(crate) quic_messages_received_count: Counter,
+    pub(crate) quic_messages_sent_count: Counter,
     pub(crate) repair_peers: Counter,
     pub(crate) require_stake_for_gossip_unknown_stakes: Counter,
     pub(crate) skip_pull_response_shred_version: Counter,
@@ -229,6 +234,31 @@ pub(crate) fn submit_gossip_stats(
             stats.gossip_packets_dropped_count.clear(),
             i64
         ),
+        (
+            "quic_messages_dropped_count",
+            stats.quic_messages_dropped_count.clear(),
+            i64
+        ),
+        (
+            "quic_messages_identity_mismatch_count",
+            stats.quic_messages_identity_mismatch_count.clear(),
+            i64
+        ),
+        (
+            "quic_messages_invalid_count",
+            stats.quic_messages_invalid_count.clear(),
+            i64
+        ),
+        (
+            "quic_messages_received_count",
+            stats.quic_messages_received_count.clear(),
+            i64
+        ),
+        (
+            "quic_messages_sent_count",
+            stats.quic_messages_sent_count.clear(),
+            i64
+        ),
         ("repair_peers", stats.repair_peers.clear(), i64),
         ("new_push_requests", stats.new_push_requests.clear(), i64),
         ("new_push_requests2", stats.new_push_requests2.clear(), i64),
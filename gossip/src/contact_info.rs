@@ -37,8 +37,9 @@ const SOCKET_TAG_TPU_QUIC: u8 = 8;
 const SOCKET_TAG_TPU_VOTE: u8 = 9;
 const SOCKET_TAG_TVU: u8 = 10;
 const SOCKET_TAG_TVU_QUIC: u8 = 11;
-const_assert_eq!(SOCKET_CACHE_SIZE, 12);
-const SOCKET_CACHE_SIZE: usize = SOCKET_TAG_TVU_QUIC as usize + 1usize;
+const SOCKET_TAG_GOSSIP_QUIC: u8 = 12;
+const_assert_eq!(SOCKET_CACHE_SIZE, 13);
+const SOCKET_CACHE_SIZE: usize = SOCKET_TAG_GOSSIP_QUIC as usize + 1usize;
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -228,6 +229,8 @@ impl ContactInfo {
     }
 
     get_socket!(gossip, SOCKET_TAG_GOSSIP);
+    // Only advertised by nodes which accept gossip traffic over QUIC.
+    get_socket!(gossip_quic, SOCKET_TAG_GOSSIP_QUIC);
     get_socket!(rpc, SOCKET_TAG_RPC);
     get_socket!(rpc_pubsub, SOCKET_TAG_RPC_PUBSUB);
     get_socket!(
@@ -245,6 +248,7 @@ impl ContactInfo {
     get_socket!(tvu, SOCKET_TAG_TVU, SOCKET_TAG_TVU_QUIC);
 
     set_socket!(set_gossip, SOCKET_TAG_GOSSIP);
+    set_socket!(set_gossip_quic, SOCKET_TAG_GOSSIP_QUIC);
     set_socket!(set_rpc, SOCKET_TAG_RPC);
     set_socket!(set_rpc_pubsub, SOCKET_TAG_RPC_PUBSUB);
     set_socket!(set_serve_repair, SOCKET_TAG_SERVE_REPAIR);
@@ -259,6 +263,7 @@ impl ContactInfo {
     set_socket!(set_tvu, SOCKET_TAG_TVU);
     set_socket!(set_tvu_quic, SOCKET_TAG_TVU_QUIC);
 
+    remove_socket!(remove_gossip_quic, SOCKET_TAG_GOSSIP_QUIC);
     remove_socket!(
         remove_serve_repair,
         SOCKET_TAG_SERVE_REPAIR,
@@ -693,6 +698,7 @@ mod tests {
         let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, 10));
         let ci = ContactInfo::new_gossip_entry_point(&addr);
         assert_eq!(ci.gossip().unwrap(), addr);
+        assert_matches!(ci.gossip_quic(), Err(Error::InvalidPort(0)));
         assert_matches!(ci.rpc(), Err(Error::InvalidPort(0)));
         assert_matches!(ci.rpc_pubsub(), Err(Error::InvalidPort(0)));
         assert_matches!(ci.serve_repair(Protocol::QUIC), Err(Error::InvalidPort(0)));
@@ -827,6 +833,10 @@ mod tests {
                 }
             }
             assert_eq!(node.gossip().ok().as_ref(), sockets.get(&SOCKET_TAG_GOSSIP));
+            assert_eq!(
+                node.gossip_quic().ok().as_ref(),
+                sockets.get(&SOCKET_TAG_GOSSIP_QUIC)
+            );
             assert_eq!(node.rpc().ok().as_ref(), sockets.get(&SOCKET_TAG_RPC));
             assert_eq!(
                 node.rpc_pubsub().ok().as_ref(),
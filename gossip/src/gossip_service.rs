@@ -1,8 +1,15 @@
 //! The `gossip_service` module implements the network control plane.
 
 use {
-    crate::{cluster_info::ClusterInfo, contact_info::ContactInfo},
+    crate::{
+        cluster_info::ClusterInfo,
+        contact_info::ContactInfo,
+        quic_endpoint::{
+            close_quic_endpoint, new_quic_endpoint, AsyncTryJoinHandle, GossipQuicRouter,
+        },
+    },
     crossbeam_channel::{unbounded, Sender},
+    quinn::Endpoint,
     rand::{thread_rng, Rng},
     solana_client::{connection_cache::ConnectionCache, tpu_client::TpuClientWrapper},
     solana_net_utils::DEFAULT_IP_ECHO_SERVER_THREADS,
@@ -28,10 +35,14 @@ use {
         thread::{self, sleep, JoinHandle},
         time::{Duration, Instant},
     },
+    tokio::runtime::Runtime as TokioRuntime,
 };
 
 pub struct GossipService {
     thread_hdls: Vec<JoinHandle<()>>,
+    quic_endpoint: Option<Endpoint>,
+    quic_endpoint_runtime: Option<TokioRuntime>,
+    quic_endpoint_join_handle: Option<AsyncTryJoinHandle>,
 }
 
 impl GossipService {
@@ -43,6 +54,33 @@ impl GossipService {
         should_check_duplicate_instance: bool,
         stats_reporter_sender: Option<Sender<Box<dyn FnOnce() + Send>>>,
         exit: Arc<AtomicBool>,
+    ) -> Self {
+        Self::new_with_quic(
+            cluster_info,
+            bank_forks,
+            gossip_socket,
+            None, // gossip_quic_socket
+            gossip_validators,
+            should_check_duplicate_instance,
+            stats_reporter_sender,
+            exit,
+        )
+    }
+
+    /// Same as `new`, but if `gossip_quic_socket` is provided gossip messages
+    /// are also exchanged over QUIC with the nodes which advertise a gossip
+    /// QUIC address in their contact-info. The socket address is expected to
+    /// be already advertised in the contact-info of this node.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_with_quic(
+        cluster_info: &Arc<ClusterInfo>,
+        bank_forks: Option<Arc<RwLock<BankForks>>>,
+        gossip_socket: UdpSocket,
+        gossip_quic_socket: Option<UdpSocket>,
+        gossip_validators: Option<HashSet<Pubkey>>,
+        should_check_duplicate_instance: bool,
+        stats_reporter_sender: Option<Sender<Box<dyn FnOnce() + Send>>>,
+        exit: Arc<AtomicBool>,
     ) -> Self {
         let (request_sender, request_receiver) = unbounded();
         let gossip_socket = Arc::new(gossip_socket);
@@ -67,10 +105,62 @@ impl GossipService {
         let (consume_sender, listen_receiver) = unbounded();
         let t_socket_consume = cluster_info.clone().start_socket_consume_thread(
             request_receiver,
-            consume_sender,
+            consume_sender.clone(),
             exit.clone(),
         );
         let (response_sender, response_receiver) = unbounded();
+        let mut thread_hdls = vec![t_receiver, t_socket_consume];
+        // If gossip over QUIC is enabled, outgoing packets are first routed
+        // through the QUIC endpoint, and only the remaining ones are sent
+        // through the UDP socket.
+        let (quic_endpoint, quic_endpoint_runtime, quic_endpoint_join_handle, response_receiver) =
+            match gossip_quic_socket {
+                None => (None, None, None, response_receiver),
+                Some(gossip_quic_socket) => {
+                    let current_runtime_handle = tokio::runtime::Handle::try_current();
+                    let quic_endpoint_runtime = current_runtime_handle.is_err().then(|| {
+                        tokio::runtime::Builder::new_multi_thread()
+                            .enable_all()
+                            .thread_name("solGossipQuic")
+                            .build()
+                            .unwrap()
+                    });
+                    let (quic_endpoint_sender, quic_endpoint_receiver) = unbounded();
+                    let (quic_endpoint, quic_sender, quic_endpoint_join_handle) =
+                        new_quic_endpoint(
+                            quic_endpoint_runtime
+                                .as_ref()
+                                .map(TokioRuntime::handle)
+                                .unwrap_or_else(|| current_runtime_handle.as_ref().unwrap()),
+                            &cluster_info.keypair(),
+                            gossip_quic_socket,
+                            quic_endpoint_sender,
+                            bank_forks.clone(),
+                        )
+                        .unwrap();
+                    let quic_router = Arc::new(GossipQuicRouter::new(quic_sender));
+                    cluster_info.set_quic_router(quic_router.clone());
+                    thread_hdls.push(cluster_info.clone().start_quic_consume_thread(
+                        quic_endpoint_receiver,
+                        consume_sender.clone(),
+                        quic_router.clone(),
+                        exit.clone(),
+                    ));
+                    let (udp_sender, udp_receiver) = unbounded();
+                    thread_hdls.push(cluster_info.clone().start_quic_router_thread(
+                        response_receiver,
+                        udp_sender,
+                        quic_router,
+                        exit.clone(),
+                    ));
+                    (
+                        Some(quic_endpoint),
+                        quic_endpoint_runtime,
+                        Some(quic_endpoint_join_handle),
+                        udp_receiver,
+                    )
+                }
+            };
         let t_listen = cluster_info.clone().listen(
             bank_forks.clone(),
             listen_receiver,
@@ -89,20 +179,27 @@ impl GossipService {
             socket_addr_space,
             stats_reporter_sender,
         );
-        let thread_hdls = vec![
-            t_receiver,
-            t_responder,
-            t_socket_consume,
-            t_listen,
-            t_gossip,
-        ];
-        Self { thread_hdls }
+        thread_hdls.extend([t_responder, t_listen, t_gossip]);
+        Self {
+            thread_hdls,
+            quic_endpoint,
+            quic_endpoint_runtime,
+            quic_endpoint_join_handle,
+        }
     }
 
     pub fn join(self) -> thread::Result<()> {
+        if let Some(quic_endpoint) = &self.quic_endpoint {
+            close_quic_endpoint(quic_endpoint);
+        }
         for thread_hdl in self.thread_hdls {
             thread_hdl.join()?;
         }
+        if let (Some(runtime), Some(join_handle)) =
+            (self.quic_endpoint_runtime, self.quic_endpoint_join_handle)
+        {
+            runtime.block_on(join_handle).unwrap();
+        }
         Ok(())
     }
 }
@@ -23,6 +23,7 @@ pub mod gossip_service;
 mod legacy_contact_info;
 pub mod ping_pong;
 mod push_active_set;
+pub mod quic_endpoint;
 mod received_cache;
 pub mod restart_crds_values;
 pub mod weighted_shuffle;
@@ -0,0 +1,248 @@
+//! QUIC endpoint for exchanging gossip messages with the peers which advertise
+//! a gossip QUIC address. Each message is sent over its own unidirectional
+//! stream, and the identity of the remote node is bound to the connection by
+//! its TLS certificate. Unlike UDP packets, messages are not bounded by
+//! PACKET_DATA_SIZE, so push messages and pull responses pack more CRDS
+//! values, and CRDS values too large for a UDP packet can be exchanged.
+
+pub use solana_streamer::quic_endpoint::{close_quic_endpoint, AsyncTryJoinHandle, Error};
+use {
+    bytes::Bytes,
+    crossbeam_channel::Sender,
+    lru::LruCache,
+    quinn::Endpoint,
+    solana_runtime::bank_forks::BankForks,
+    solana_sdk::{packet::PACKET_DATA_SIZE, pubkey::Pubkey, signature::Keypair},
+    solana_streamer::quic_endpoint::{QuicEndpointConfig, QuicEndpointTransport},
+    std::{
+        collections::HashMap,
+        net::{SocketAddr, UdpSocket},
+        num::NonZeroUsize,
+        sync::{Arc, Mutex, RwLock},
+        time::Duration,
+    },
+    tokio::sync::mpsc::Sender as AsyncSender,
+};
+
+/// Maximum size of a gossip message exchanged over QUIC.
+pub const MAX_QUIC_MESSAGE_SIZE: usize = 64 * PACKET_DATA_SIZE;
+/// Capacity of the cache of addresses which gossip messages were recently
+/// received from over QUIC, so that responses are sent back over QUIC too.
+const GOSSIP_QUIC_PEERS_CAPACITY: usize = 8192;
+
+const GOSSIP_QUIC_ENDPOINT_CONFIG: QuicEndpointConfig = QuicEndpointConfig {
+    protocol_id: "solana-gossip",
+    server_metrics_name: "gossip_quic_server",
+    client_metrics_name: "gossip_quic_client",
+    router_channel_buffer: 1 << 10,
+    connection_cache_capacity: 4096,
+    max_idle_timeout: Duration::from_secs(30),
+    transport: QuicEndpointTransport::UniStream {
+        max_concurrent_streams: 512,
+        max_message_size: MAX_QUIC_MESSAGE_SIZE,
+        read_timeout: Duration::from_secs(2),
+    },
+};
+
+/// Routes the outgoing gossip messages destined to the nodes which accept
+/// gossip over QUIC to the QUIC endpoint.
+pub(crate) struct GossipQuicRouter {
+    sender: AsyncSender<(SocketAddr, Bytes)>,
+    // Maps both the UDP and the QUIC gossip addresses of the nodes which
+    // advertise a gossip QUIC address, to the QUIC address.
+    addrs: RwLock<HashMap<SocketAddr, SocketAddr>>,
+    // Addresses which gossip messages were recently received from over QUIC.
+    peers: Mutex<LruCache<SocketAddr, ()>>,
+}
+
+impl GossipQuicRouter {
+    pub(crate) fn new(sender: AsyncSender<(SocketAddr, Bytes)>) -> Self {
+        Self {
+            sender,
+            addrs: RwLock::default(),
+            peers: Mutex::new(LruCache::new(
+                NonZeroUsize::new(GOSSIP_QUIC_PEERS_CAPACITY).unwrap(),
+            )),
+        }
+    }
+
+    pub(crate) fn set_addrs(&self, addrs: HashMap<SocketAddr, SocketAddr>) {
+        *self.addrs.write().unwrap() = addrs;
+    }
+
+    pub(crate) fn add_peers<I>(&self, addrs: I)
+    where
+        I: IntoIterator<Item = SocketAddr>,
+    {
+        let mut peers = self.peers.lock().unwrap();
+        for addr in addrs {
+            peers.put(addr, ());
+        }
+    }
+
+    /// Splits the messages into the ones destined to nodes which accept
+    /// gossip over QUIC, readdressed to their QUIC address, and the remaining
+    /// ones to be sent over UDP.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn partition<I, T>(
+        &self,
+        messages: I,
+    ) -> (
+        Vec<(/*QUIC addr:*/ SocketAddr, T)>,
+        Vec<(/*UDP addr:*/ SocketAddr, T)>,
+    )
+    where
+        I: IntoIterator<Item = (SocketAddr, T)>,
+    {
+        let addrs = self.addrs.read().unwrap();
+        let mut peers = self.peers.lock().unwrap();
+        let mut quic_messages = Vec::new();
+        let mut udp_messages = Vec::new();
+        for (addr, message) in messages {
+            match addrs
+                .get(&addr)
+                .copied()
+                .or_else(|| peers.get(&addr).map(|_| addr))
+            {
+                Some(addr) => quic_messages.push((addr, message)),
+                None => udp_messages.push((addr, message)),
+            }
+        }
+        (quic_messages, udp_messages)
+    }
+
+    /// Sends the message to the QUIC endpoint without blocking, and returns
+    /// false if the message was dropped.
+    pub(crate) fn try_send(&self, addr: SocketAddr, bytes: Bytes) -> bool {
+        self.sender.try_send((addr, bytes)).is_ok()
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn new_quic_endpoint(
+    runtime: &tokio::runtime::Handle,
+    keypair: &Keypair,
+    socket: UdpSocket,
+    sender: Sender<(Pubkey, SocketAddr, Bytes)>,
+    bank_forks: Option<Arc<RwLock<BankForks>>>,
+) -> Result<
+    (
+        Endpoint,
+        AsyncSender<(SocketAddr, Bytes)>,
+        AsyncTryJoinHandle,
+    ),
+    Error,
+> {
+    solana_streamer::quic_endpoint::new_quic_endpoint(
+        runtime,
+        GOSSIP_QUIC_ENDPOINT_CONFIG,
+        keypair,
+        socket,
+        sender,
+        // Without a bank, e.g. gossip spy nodes, all connections are unstaked.
+        Arc::new(move || {
+            bank_forks
+                .as_ref()
+                .map(|bank_forks| bank_forks.read().unwrap().root_bank().staked_nodes())
+                .unwrap_or_default()
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        itertools::{izip, multiunzip},
+        solana_sdk::signature::Signer,
+        std::{iter::repeat_with, net::Ipv4Addr},
+    };
+
+    #[test]
+    fn test_quic_endpoint() {
+        const NUM_ENDPOINTS: usize = 3;
+        const RECV_TIMEOUT: Duration = Duration::from_secs(60);
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(8)
+            .enable_all()
+            .build()
+            .unwrap();
+        let keypairs: Vec<Keypair> = repeat_with(Keypair::new).take(NUM_ENDPOINTS).collect();
+        let sockets: Vec<UdpSocket> = repeat_with(|| UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)))
+            .take(NUM_ENDPOINTS)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let addresses: Vec<SocketAddr> = sockets
+            .iter()
+            .map(UdpSocket::local_addr)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let (senders, receivers): (Vec<_>, Vec<_>) =
+            repeat_with(crossbeam_channel::unbounded::<(Pubkey, SocketAddr, Bytes)>)
+                .take(NUM_ENDPOINTS)
+                .unzip();
+        let (endpoints, senders, tasks): (Vec<_>, Vec<_>, Vec<_>) =
+            multiunzip(keypairs.iter().zip(sockets).zip(senders).map(
+                |((keypair, socket), sender)| {
+                    new_quic_endpoint(runtime.handle(), keypair, socket, sender, None).unwrap()
+                },
+            ));
+        // Send a unique message, larger than a gossip packet, from each
+        // endpoint to every other endpoint.
+        let new_message = |i: usize, j: usize| {
+            let mut bytes = format!("{i}=>{j}").into_bytes();
+            bytes.resize(MAX_QUIC_MESSAGE_SIZE, i as u8);
+            Bytes::from(bytes)
+        };
+        for (i, (keypair, &address, sender)) in izip!(&keypairs, &addresses, &senders).enumerate() {
+            for (j, &address) in addresses.iter().enumerate() {
+                if i != j {
+                    sender.blocking_send((address, new_message(i, j))).unwrap();
+                }
+            }
+            // Verify all messages are received, along with the sender's identity.
+            for (j, receiver) in receivers.iter().enumerate() {
+                if i != j {
+                    let entry = (keypair.pubkey(), address, new_message(i, j));
+                    assert_eq!(receiver.recv_timeout(RECV_TIMEOUT).unwrap(), entry);
+                }
+            }
+        }
+        drop(senders);
+        for endpoint in endpoints {
+            close_quic_endpoint(&endpoint);
+        }
+        for task in tasks {
+            runtime.block_on(task).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_gossip_quic_router_partition() {
+        let (sender, _receiver) = tokio::sync::mpsc::channel(1);
+        let router = GossipQuicRouter::new(sender);
+        let gossip_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, 8001));
+        let quic_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, 8002));
+        let peer_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, 8003));
+        let udp_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, 8004));
+        router.set_addrs(HashMap::from([
+            (gossip_addr, quic_addr),
+            (quic_addr, quic_addr),
+        ]));
+        router.add_peers([peer_addr]);
+        let (quic_messages, udp_messages) = router.partition([
+            (gossip_addr, 0),
+            (udp_addr, 1),
+            (peer_addr, 2),
+            (quic_addr, 3),
+        ]);
+        assert_eq!(
+            quic_messages,
+            vec![(quic_addr, 0), (peer_addr, 2), (quic_addr, 3)]
+        );
+        assert_eq!(udp_messages, vec![(udp_addr, 1)]);
+        // Messages are dropped rather than blocking once the channel is full.
+        assert!(router.try_send(quic_addr, Bytes::from_static(b"0")));
+        assert!(!router.try_send(quic_addr, Bytes::from_static(b"1")));
+    }
+}
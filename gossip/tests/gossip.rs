@@ -36,17 +36,32 @@ use {
 };
 
 fn test_node(exit: Arc<AtomicBool>) -> (Arc<ClusterInfo>, GossipService, UdpSocket) {
+    test_node_with_quic(exit, /*enable_quic:*/ false)
+}
+
+fn test_node_with_quic(
+    exit: Arc<AtomicBool>,
+    enable_quic: bool,
+) -> (Arc<ClusterInfo>, GossipService, UdpSocket) {
     let keypair = Arc::new(Keypair::new());
     let mut test_node = Node::new_localhost_with_pubkey(&keypair.pubkey());
+    let gossip_quic_socket = test_node.sockets.gossip_quic.take().filter(|_| enable_quic);
+    if let Some(gossip_quic_socket) = &gossip_quic_socket {
+        test_node
+            .info
+            .set_gossip_quic(gossip_quic_socket.local_addr().unwrap())
+            .unwrap();
+    }
     let cluster_info = Arc::new(ClusterInfo::new(
         test_node.info.clone(),
         keypair,
         SocketAddrSpace::Unspecified,
     ));
-    let gossip_service = GossipService::new(
+    let gossip_service = GossipService::new_with_quic(
         &cluster_info,
         None,
         test_node.sockets.gossip,
+        gossip_quic_socket,
         None,
         true, // should_check_duplicate_instance
         None,
@@ -225,6 +240,43 @@ fn gossip_rstar() {
     });
 }
 
+/// rstar a <- (b,c,d,e) where only every other node exchanges gossip over QUIC
+#[test]
+fn gossip_quic_mixed_cluster() {
+    solana_logger::setup();
+    const NUM_NODES: usize = 6;
+    let exit = Arc::new(AtomicBool::new(false));
+    let listen: Vec<_> = (0..NUM_NODES)
+        .map(|k| test_node_with_quic(exit.clone(), k % 2 == 0))
+        .collect();
+    let xd = listen[0].0.my_contact_info();
+    for (yv, _, _) in &listen[1..] {
+        yv.insert_info(xd.clone());
+    }
+    let mut done = false;
+    for _ in 0..30 {
+        done = listen.iter().enumerate().all(|(k, (node, _, _))| {
+            node.gossip_peers().len() == NUM_NODES - 1
+                && (k % 2 == 1 || node.quic_messages_received() > 0)
+        });
+        if done {
+            break;
+        }
+        sleep(Duration::from_secs(1));
+    }
+    // Only the QUIC enabled nodes advertise a gossip QUIC address, and
+    // receive gossip over QUIC.
+    for (k, (node, _, _)) in listen.iter().enumerate() {
+        assert_eq!(node.my_contact_info().gossip_quic().is_ok(), k % 2 == 0);
+        assert_eq!(node.quic_messages_received() > 0, k % 2 == 0);
+    }
+    exit.store(true, Ordering::Relaxed);
+    for (_, dr, _) in listen {
+        dr.join().unwrap();
+    }
+    assert!(done);
+}
+
 #[test]
 pub fn cluster_info_retransmit() {
     solana_logger::setup();
@@ -3,7 +3,7 @@ use {
     clap::{value_t, value_t_or_exit, values_t, values_t_or_exit, Arg, ArgMatches},
     solana_accounts_db::{
         accounts_db::{AccountsDb, AccountsDbConfig, CreateAncientStorage},
-        accounts_file::StorageAccess,
+        accounts_file::{AccountsFileProvider, StorageAccess},
         accounts_index::{AccountsIndexConfig, IndexLimitMb},
         partitioned_rewards::TestPartitionedEpochRewards,
         utils::create_and_canonicalize_directories,
@@ -116,6 +116,13 @@ pub fn accounts_db_args<'a, 'b>() -> Box<[Arg<'a, 'b>]> {
             .possible_values(&["pack", "append"])
             .help("Squash multiple account storage files together using this method")
             .hidden(hidden_unless_forced()),
+        Arg::with_name("accounts_db_ancient_storage_format")
+            .long("accounts-db-ancient-storage-format")
+            .value_name("FORMAT")
+            .takes_value(true)
+            .possible_values(&["append-vec", "hot", "cold"])
+            .help("Create ancient account storage files in this format when packing")
+            .hidden(hidden_unless_forced()),
         Arg::with_name("accounts_db_access_storages_method")
             .long("accounts-db-access-storages-method")
             .value_name("METHOD")
@@ -297,6 +304,17 @@ pub fn get_accounts_db_config(
             }
         })
         .unwrap_or_default();
+    let ancient_accounts_file_provider = arg_matches
+        .value_of("accounts_db_ancient_storage_format")
+        .map(|format| match format {
+            "append-vec" => AccountsFileProvider::AppendVec,
+            "hot" => AccountsFileProvider::HotStorage,
+            "cold" => AccountsFileProvider::ColdStorage,
+            _ => {
+                // clap will enforce one of the above values is given
+                unreachable!("invalid value given to accounts-db-ancient-storage-format")
+            }
+        });
     let storage_access = arg_matches
         .value_of("accounts_db_access_storages_method")
         .map(|method| match method {
@@ -321,6 +339,7 @@ pub fn get_accounts_db_config(
         test_skip_rewrites_but_include_in_bank_hash: arg_matches
             .is_present("accounts_db_test_skip_rewrites"),
         create_ancient_storage,
+        ancient_accounts_file_provider,
         storage_access,
         ..AccountsDbConfig::default()
     }
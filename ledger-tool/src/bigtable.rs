@@ -36,12 +36,12 @@ use {
         clock::Slot, hash::Hash, pubkey::Pubkey, shred_version::compute_shred_version,
         signature::Signature, signer::keypair::keypair_from_seed,
     },
-    solana_storage_bigtable::CredentialType,
+    solana_storage_bigtable::{CredentialType, LongTermLedgerStorageConfig},
     solana_transaction_status::{ConfirmedBlock, UiTransactionEncoding, VersionedConfirmedBlock},
     std::{
         cmp::min,
         collections::HashSet,
-        path::Path,
+        path::{Path, PathBuf},
         process::exit,
         result::Result,
         str::FromStr,
@@ -54,9 +54,10 @@ async fn upload(
     starting_slot: Option<Slot>,
     ending_slot: Option<Slot>,
     force_reupload: bool,
-    config: solana_storage_bigtable::LedgerStorageConfig,
+    config: LongTermLedgerStorageConfig,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(config)
+    let bigtable = config
+        .open()
         .await
         .map_err(|err| format!("Failed to connect to storage: {err:?}"))?;
 
@@ -100,10 +101,11 @@ async fn upload(
 
 async fn delete_slots(
     slots: Vec<Slot>,
-    config: solana_storage_bigtable::LedgerStorageConfig,
+    config: LongTermLedgerStorageConfig,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let dry_run = config.read_only;
-    let bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(config)
+    let dry_run = config.read_only();
+    let bigtable = config
+        .open()
         .await
         .map_err(|err| format!("Failed to connect to storage: {err:?}"))?;
 
@@ -111,9 +113,9 @@ async fn delete_slots(
 }
 
 async fn first_available_block(
-    config: solana_storage_bigtable::LedgerStorageConfig,
+    config: LongTermLedgerStorageConfig,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(config).await?;
+    let bigtable = config.open().await?;
     match bigtable.get_first_available_block().await? {
         Some(block) => println!("{block}"),
         None => println!("No blocks available"),
@@ -126,9 +128,10 @@ async fn block(
     slot: Slot,
     output_format: OutputFormat,
     show_entries: bool,
-    config: solana_storage_bigtable::LedgerStorageConfig,
+    config: LongTermLedgerStorageConfig,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(config)
+    let bigtable = config
+        .open()
         .await
         .map_err(|err| format!("Failed to connect to storage: {err:?}"))?;
 
@@ -158,15 +161,16 @@ async fn block(
 async fn entries(
     slot: Slot,
     output_format: OutputFormat,
-    config: solana_storage_bigtable::LedgerStorageConfig,
+    config: LongTermLedgerStorageConfig,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(config)
+    let bigtable = config
+        .open()
         .await
         .map_err(|err| format!("Failed to connect to storage: {err:?}"))?;
 
     let entries = bigtable.get_entries(slot).await?;
     let cli_entries = CliEntries {
-        entries: entries.map(Into::into).collect(),
+        entries: entries.into_iter().map(Into::into).collect(),
         slot,
     };
     println!("{}", output_format.formatted_string(&cli_entries));
@@ -254,9 +258,10 @@ async fn shreds(
     starting_slot: Slot,
     ending_slot: Slot,
     shred_config: ShredConfig,
-    config: solana_storage_bigtable::LedgerStorageConfig,
+    config: LongTermLedgerStorageConfig,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(config)
+    let bigtable = config
+        .open()
         .await
         .map_err(|err| format!("Failed to connect to storage: {err:?}"))?;
 
@@ -281,6 +286,7 @@ async fn shreds(
 
         let entries = match entry_summaries {
             Ok(entry_summaries) => entry_summaries
+                .into_iter()
                 .enumerate()
                 .map(|(i, entry_summary)| {
                     let num_hashes = entry_summary.num_hashes;
@@ -406,9 +412,10 @@ async fn shreds(
 async fn blocks(
     starting_slot: Slot,
     limit: usize,
-    config: solana_storage_bigtable::LedgerStorageConfig,
+    config: LongTermLedgerStorageConfig,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(config)
+    let bigtable = config
+        .open()
         .await
         .map_err(|err| format!("Failed to connect to storage: {err:?}"))?;
 
@@ -422,7 +429,7 @@ async fn blocks(
 async fn compare_blocks(
     starting_slot: Slot,
     limit: usize,
-    config: solana_storage_bigtable::LedgerStorageConfig,
+    config: LongTermLedgerStorageConfig,
     ref_config: solana_storage_bigtable::LedgerStorageConfig,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let reference_bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(ref_config)
@@ -442,7 +449,8 @@ async fn compare_blocks(
         return Ok(());
     }
 
-    let owned_bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(config)
+    let owned_bigtable = config
+        .open()
         .await
         .map_err(|err| format!("failed to connect to owned bigtable: {err:?}"))?;
     let owned_bigtable_slots = owned_bigtable
@@ -479,9 +487,10 @@ async fn confirm(
     signature: &Signature,
     verbose: bool,
     output_format: OutputFormat,
-    config: solana_storage_bigtable::LedgerStorageConfig,
+    config: LongTermLedgerStorageConfig,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(config)
+    let bigtable = config
+        .open()
         .await
         .map_err(|err| format!("Failed to connect to storage: {err:?}"))?;
 
@@ -531,9 +540,9 @@ pub async fn transaction_history(
     verbose: bool,
     show_transactions: bool,
     query_chunk_size: usize,
-    config: solana_storage_bigtable::LedgerStorageConfig,
+    config: LongTermLedgerStorageConfig,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(config).await?;
+    let bigtable = config.open().await?;
 
     let mut loaded_block: Option<(Slot, ConfirmedBlock)> = None;
     while limit > 0 {
@@ -914,6 +923,19 @@ impl BigTableSubCommand for App<'_, '_> {
                         .default_value(solana_storage_bigtable::DEFAULT_APP_PROFILE_ID)
                         .help("Bigtable application profile id to use in requests"),
                 )
+                .arg(
+                    Arg::with_name("local_storage_path")
+                        .global(true)
+                        .long("local-storage-path")
+                        .takes_value(true)
+                        .value_name("DIR")
+                        .help(
+                            "Use the ledger data in a local database at this path instead of \
+                             BigTable. The database is created if it does not exist. Not \
+                             supported by the copy subcommand, and only applies to the owned \
+                             side of compare-blocks",
+                        ),
+                )
                 .subcommand(
                     SubCommand::with_name("upload")
                         .about("Upload the ledger to BigTable")
@@ -1354,19 +1376,38 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
     let output_format = OutputFormat::from_matches(matches, "output_format", verbose);
 
     let (subcommand, sub_matches) = matches.subcommand();
-    let instance_name = get_global_subcommand_arg(
+    let instance_name: String = get_global_subcommand_arg(
         matches,
         sub_matches,
         "rpc_bigtable_instance_name",
         solana_storage_bigtable::DEFAULT_INSTANCE_NAME,
     );
-    let app_profile_id = get_global_subcommand_arg(
+    let app_profile_id: String = get_global_subcommand_arg(
         matches,
         sub_matches,
         "rpc_bigtable_app_profile_id",
         solana_storage_bigtable::DEFAULT_APP_PROFILE_ID,
     );
 
+    let local_storage_path = matches
+        .value_of("local_storage_path")
+        .or_else(|| sub_matches.and_then(|m| m.value_of("local_storage_path")))
+        .map(PathBuf::from);
+    let storage_config = |read_only: bool| match &local_storage_path {
+        Some(path) => LongTermLedgerStorageConfig::Local {
+            path: path.clone(),
+            read_only,
+        },
+        None => {
+            LongTermLedgerStorageConfig::BigTable(solana_storage_bigtable::LedgerStorageConfig {
+                read_only,
+                instance_name: instance_name.clone(),
+                app_profile_id: app_profile_id.clone(),
+                ..solana_storage_bigtable::LedgerStorageConfig::default()
+            })
+        }
+    };
+
     let future = match (subcommand, sub_matches) {
         ("upload", Some(arg_matches)) => {
             let starting_slot = value_t!(arg_matches, "starting_slot", Slot).ok();
@@ -1377,12 +1418,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 arg_matches,
                 AccessType::Secondary,
             );
-            let config = solana_storage_bigtable::LedgerStorageConfig {
-                read_only: false,
-                instance_name,
-                app_profile_id,
-                ..solana_storage_bigtable::LedgerStorageConfig::default()
-            };
+            let config = storage_config(false);
             runtime.block_on(upload(
                 blockstore,
                 starting_slot,
@@ -1393,42 +1429,22 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
         }
         ("delete-slots", Some(arg_matches)) => {
             let slots = values_t_or_exit!(arg_matches, "slots", Slot);
-            let config = solana_storage_bigtable::LedgerStorageConfig {
-                read_only: !arg_matches.is_present("force"),
-                instance_name,
-                app_profile_id,
-                ..solana_storage_bigtable::LedgerStorageConfig::default()
-            };
+            let config = storage_config(!arg_matches.is_present("force"));
             runtime.block_on(delete_slots(slots, config))
         }
         ("first-available-block", Some(_arg_matches)) => {
-            let config = solana_storage_bigtable::LedgerStorageConfig {
-                read_only: true,
-                instance_name,
-                app_profile_id,
-                ..solana_storage_bigtable::LedgerStorageConfig::default()
-            };
+            let config = storage_config(true);
             runtime.block_on(first_available_block(config))
         }
         ("block", Some(arg_matches)) => {
             let slot = value_t_or_exit!(arg_matches, "slot", Slot);
             let show_entries = arg_matches.is_present("show_entries");
-            let config = solana_storage_bigtable::LedgerStorageConfig {
-                read_only: true,
-                instance_name,
-                app_profile_id,
-                ..solana_storage_bigtable::LedgerStorageConfig::default()
-            };
+            let config = storage_config(true);
             runtime.block_on(block(slot, output_format, show_entries, config))
         }
         ("entries", Some(arg_matches)) => {
             let slot = value_t_or_exit!(arg_matches, "slot", Slot);
-            let config = solana_storage_bigtable::LedgerStorageConfig {
-                read_only: true,
-                instance_name,
-                app_profile_id,
-                ..solana_storage_bigtable::LedgerStorageConfig::default()
-            };
+            let config = storage_config(true);
             runtime.block_on(entries(slot, output_format, config))
         }
         ("shreds", Some(arg_matches)) => {
@@ -1467,12 +1483,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 )
             };
 
-            let config = solana_storage_bigtable::LedgerStorageConfig {
-                read_only: true,
-                instance_name,
-                app_profile_id,
-                ..solana_storage_bigtable::LedgerStorageConfig::default()
-            };
+            let config = storage_config(true);
 
             runtime.block_on(shreds(
                 blockstore,
@@ -1485,24 +1496,14 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
         ("blocks", Some(arg_matches)) => {
             let starting_slot = value_t_or_exit!(arg_matches, "starting_slot", Slot);
             let limit = value_t_or_exit!(arg_matches, "limit", usize);
-            let config = solana_storage_bigtable::LedgerStorageConfig {
-                read_only: true,
-                instance_name,
-                app_profile_id,
-                ..solana_storage_bigtable::LedgerStorageConfig::default()
-            };
+            let config = storage_config(true);
 
             runtime.block_on(blocks(starting_slot, limit, config))
         }
         ("compare-blocks", Some(arg_matches)) => {
             let starting_slot = value_t_or_exit!(arg_matches, "starting_slot", Slot);
             let limit = value_t_or_exit!(arg_matches, "limit", usize);
-            let config = solana_storage_bigtable::LedgerStorageConfig {
-                read_only: true,
-                instance_name,
-                app_profile_id,
-                ..solana_storage_bigtable::LedgerStorageConfig::default()
-            };
+            let config = storage_config(true);
 
             let credential_path = Some(value_t_or_exit!(
                 arg_matches,
@@ -1530,12 +1531,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 .unwrap()
                 .parse()
                 .expect("Invalid signature");
-            let config = solana_storage_bigtable::LedgerStorageConfig {
-                read_only: true,
-                instance_name,
-                app_profile_id,
-                ..solana_storage_bigtable::LedgerStorageConfig::default()
-            };
+            let config = storage_config(true);
 
             runtime.block_on(confirm(&signature, verbose, output_format, config))
         }
@@ -1550,12 +1546,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 .value_of("until")
                 .map(|signature| signature.parse().expect("Invalid signature"));
             let show_transactions = arg_matches.is_present("show_transactions");
-            let config = solana_storage_bigtable::LedgerStorageConfig {
-                read_only: true,
-                instance_name,
-                app_profile_id,
-                ..solana_storage_bigtable::LedgerStorageConfig::default()
-            };
+            let config = storage_config(true);
 
             runtime.block_on(transaction_history(
                 &address,
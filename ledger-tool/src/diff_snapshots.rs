@@ -0,0 +1,331 @@
+//! Compares the banks in two snapshot archives account-by-account
+
+use {
+    crate::{
+        error::{LedgerToolError, Result},
+        output::{AccountDiffKind, CliAccountDiff, CliBankFieldDiff, CliSnapshotAccount},
+    },
+    itertools::{EitherOrBoth, Itertools},
+    log::*,
+    solana_accounts_db::{
+        accounts::Accounts,
+        accounts_db::{AccountShrinkThreshold, AccountsDbConfig},
+        accounts_index::{AccountSecondaryIndexes, AccountsIndexConfig, ScanConfig},
+        utils::create_all_accounts_run_and_snapshot_dirs,
+    },
+    solana_runtime::{
+        bank::Bank,
+        runtime_config::RuntimeConfig,
+        snapshot_archive_info::{FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo},
+        snapshot_bank_utils,
+    },
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        genesis_config::GenesisConfig,
+        pubkey::Pubkey,
+    },
+    std::{
+        fs,
+        ops::RangeInclusive,
+        path::{Path, PathBuf},
+        sync::{atomic::AtomicBool, Arc},
+    },
+};
+
+/// The snapshot archives to load one of the banks to compare from
+pub struct SnapshotArchives {
+    pub full: FullSnapshotArchiveInfo,
+    pub incremental: Option<IncrementalSnapshotArchiveInfo>,
+}
+
+impl SnapshotArchives {
+    pub fn new(full: PathBuf, incremental: Option<PathBuf>) -> Result<Self> {
+        let full = FullSnapshotArchiveInfo::new_from_path(full).map_err(|err| {
+            LedgerToolError::BadArgument(format!("invalid full snapshot archive: {err}"))
+        })?;
+        let incremental = incremental
+            .map(IncrementalSnapshotArchiveInfo::new_from_path)
+            .transpose()
+            .map_err(|err| {
+                LedgerToolError::BadArgument(format!("invalid incremental snapshot archive: {err}"))
+            })?;
+        Ok(Self { full, incremental })
+    }
+}
+
+/// Loads the bank from `snapshot_archives`, unpacking the archives under `working_dir`.
+///
+/// Each bank must be given its own `working_dir`; its previous contents are removed.
+pub fn load_bank(
+    snapshot_archives: &SnapshotArchives,
+    genesis_config: &GenesisConfig,
+    accounts_db_config: &AccountsDbConfig,
+    working_dir: &Path,
+) -> Result<Bank> {
+    if working_dir.exists() {
+        fs::remove_dir_all(working_dir)?;
+    }
+    let (account_run_paths, _account_snapshot_paths) =
+        create_all_accounts_run_and_snapshot_dirs(&[working_dir.join("accounts")])?;
+    let bank_snapshots_dir = working_dir.join("snapshot");
+    fs::create_dir_all(&bank_snapshots_dir)?;
+
+    // The two banks are loaded side by side, so they can't share the disk index
+    let accounts_db_config = AccountsDbConfig {
+        index: Some(AccountsIndexConfig {
+            drives: Some(vec![working_dir.join("accounts_index")]),
+            ..accounts_db_config.index.clone().unwrap_or_default()
+        }),
+        ..accounts_db_config.clone()
+    };
+
+    let (bank, _) = snapshot_bank_utils::bank_from_snapshot_archives(
+        &account_run_paths,
+        &bank_snapshots_dir,
+        &snapshot_archives.full,
+        snapshot_archives.incremental.as_ref(),
+        genesis_config,
+        &RuntimeConfig::default(),
+        None,
+        None,
+        AccountSecondaryIndexes::default(),
+        None,
+        AccountShrinkThreshold::default(),
+        false,
+        true,
+        false,
+        false,
+        Some(accounts_db_config),
+        None,
+        Arc::new(AtomicBool::new(false)),
+    )
+    .map_err(|err| LedgerToolError::Generic(format!("failed to load snapshot: {err}")))?;
+    Ok(bank)
+}
+
+/// Returns the bank fields which differ between `bank_a` and `bank_b`
+pub fn diff_bank_fields(bank_a: &Bank, bank_b: &Bank) -> Vec<CliBankFieldDiff> {
+    bank_fields(bank_a)
+        .into_iter()
+        .zip(bank_fields(bank_b))
+        .filter(|((_, a), (_, b))| a != b)
+        .map(|((field, a), (_, b))| CliBankFieldDiff {
+            field: field.to_string(),
+            a,
+            b,
+        })
+        .collect()
+}
+
+fn bank_fields(bank: &Bank) -> Vec<(&'static str, String)> {
+    vec![
+        ("slot", bank.slot().to_string()),
+        ("parent_slot", bank.parent_slot().to_string()),
+        ("epoch", bank.epoch().to_string()),
+        ("block_height", bank.block_height().to_string()),
+        ("bank_hash", bank.hash().to_string()),
+        ("parent_hash", bank.parent_hash().to_string()),
+        ("capitalization", bank.capitalization().to_string()),
+        ("transaction_count", bank.transaction_count().to_string()),
+        ("signature_count", bank.signature_count().to_string()),
+        ("collector_id", bank.collector_id().to_string()),
+        (
+            "lamports_per_signature",
+            bank.get_lamports_per_signature().to_string(),
+        ),
+        ("ticks_per_slot", bank.ticks_per_slot().to_string()),
+        ("max_tick_height", bank.max_tick_height().to_string()),
+        (
+            "hard_forks",
+            format!("{:?}", bank.hard_forks().iter().collect::<Vec<_>>()),
+        ),
+    ]
+}
+
+/// Returns the accounts which were added, removed or modified going from `bank_a` to `bank_b`,
+/// in pubkey order
+///
+/// Both banks are scanned one accounts index bin at a time, so only the accounts of a single
+/// bin are held in memory. A range scan reads every entry of the bins the range overlaps, and
+/// loads them from the disk index if it is enabled, so ranges aligned with the bins read each
+/// entry once where finer ranges would read each bin once per range.
+pub fn diff_accounts(bank_a: &Bank, bank_b: &Bank) -> Vec<CliAccountDiff> {
+    // Both banks are loaded with the same accounts index config
+    let bins = bank_a.accounts().accounts_db.accounts_index.bins();
+    let mut diffs = vec![];
+    for bin in 0..bins {
+        let range = pubkey_range(bin, bins);
+        let accounts_a = load_accounts_in_range(bank_a, range.clone());
+        let accounts_b = load_accounts_in_range(bank_b, range);
+        diffs.extend(diff_sorted_accounts(accounts_a, accounts_b));
+
+        if (bin + 1) % (bins / 16).max(1) == 0 {
+            info!(
+                "Compared {} of {bins} accounts index bins, {} accounts differ so far",
+                bin + 1,
+                diffs.len()
+            );
+        }
+    }
+    diffs
+}
+
+/// Returns the range of the pubkeys in accounts index bin `bin` out of `bins`, which are split
+/// on the first 24 bits of the pubkey
+fn pubkey_range(bin: usize, bins: usize) -> RangeInclusive<Pubkey> {
+    debug_assert!(bins.is_power_of_two() && bins <= 1 << 24);
+    let shift = 24 - bins.trailing_zeros();
+    let first = (bin << shift) as u32;
+    let last = (((bin + 1) << shift) - 1) as u32;
+    let mut start = [0u8; 32];
+    let mut end = [u8::MAX; 32];
+    start[..3].copy_from_slice(&first.to_be_bytes()[1..]);
+    end[..3].copy_from_slice(&last.to_be_bytes()[1..]);
+    Pubkey::from(start)..=Pubkey::from(end)
+}
+
+/// Returns the loadable accounts in `range`, sorted by pubkey
+fn load_accounts_in_range(
+    bank: &Bank,
+    range: RangeInclusive<Pubkey>,
+) -> Vec<(Pubkey, AccountSharedData)> {
+    let mut accounts = vec![];
+    bank.accounts().accounts_db.range_scan_accounts(
+        "",
+        &bank.ancestors,
+        range,
+        &ScanConfig::new(false),
+        |item| {
+            if let Some((pubkey, account, _slot)) = item {
+                if Accounts::is_loadable(account.lamports()) {
+                    accounts.push((*pubkey, account));
+                }
+            }
+        },
+    );
+    accounts.sort_unstable_by_key(|(pubkey, _)| *pubkey);
+    accounts
+}
+
+fn diff_sorted_accounts(
+    accounts_a: Vec<(Pubkey, AccountSharedData)>,
+    accounts_b: Vec<(Pubkey, AccountSharedData)>,
+) -> impl Iterator<Item = CliAccountDiff> {
+    accounts_a
+        .into_iter()
+        .merge_join_by(accounts_b, |(pubkey_a, _), (pubkey_b, _)| {
+            pubkey_a.cmp(pubkey_b)
+        })
+        .filter_map(|accounts| {
+            let (pubkey, kind, a, b) = match accounts {
+                EitherOrBoth::Left((pubkey, a)) => {
+                    (pubkey, AccountDiffKind::Removed, Some(a), None)
+                }
+                EitherOrBoth::Right((pubkey, b)) => (pubkey, AccountDiffKind::Added, None, Some(b)),
+                EitherOrBoth::Both((pubkey, a), (_, b)) => {
+                    if a == b {
+                        return None;
+                    }
+                    (pubkey, AccountDiffKind::Modified, Some(a), Some(b))
+                }
+            };
+            Some(CliAccountDiff {
+                pubkey: pubkey.to_string(),
+                kind,
+                a: a.as_ref().map(CliSnapshotAccount::from),
+                b: b.as_ref().map(CliSnapshotAccount::from),
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, solana_accounts_db::pubkey_bins::PubkeyBinCalculator24, solana_sdk::hash::hash,
+    };
+
+    fn pubkey_with_prefix(prefix: [u8; 3], fill: u8) -> Pubkey {
+        let mut pubkey = [fill; 32];
+        pubkey[..3].copy_from_slice(&prefix);
+        Pubkey::from(pubkey)
+    }
+
+    #[test]
+    fn test_pubkey_range() {
+        // With 8192 bins, each bin covers 2048 values of the first 24 bits
+        let range = pubkey_range(0x0102, 8192);
+        assert!(range.contains(&pubkey_with_prefix([8, 16, 0], 0)));
+        assert!(range.contains(&pubkey_with_prefix([8, 23, 255], 255)));
+        assert!(!range.contains(&pubkey_with_prefix([8, 24, 0], 0)));
+        assert!(!range.contains(&pubkey_with_prefix([8, 15, 255], 255)));
+
+        for bins in [1, 2, 8192, 1 << 24] {
+            let bin_calculator = PubkeyBinCalculator24::new(bins);
+            assert_eq!(*pubkey_range(0, bins).start(), Pubkey::from([0; 32]));
+            assert_eq!(*pubkey_range(bins - 1, bins).end(), Pubkey::from([255; 32]));
+            for bin in [0, bins / 2, bins - 1] {
+                let range = pubkey_range(bin, bins);
+                assert_eq!(bin_calculator.bin_from_pubkey(range.start()), bin);
+                assert_eq!(bin_calculator.bin_from_pubkey(range.end()), bin);
+            }
+        }
+    }
+
+    #[test]
+    fn test_diff_sorted_accounts() {
+        let owner = Pubkey::new_unique();
+        let mut pubkeys: Vec<_> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        pubkeys.sort_unstable();
+        let account = AccountSharedData::new(1, 3, &owner);
+        let mut modified_account = account.clone();
+        modified_account.set_data_from_slice(&[1, 2, 3]);
+
+        let accounts_a = vec![
+            (pubkeys[0], account.clone()),
+            (pubkeys[1], account.clone()),
+            (pubkeys[2], account.clone()),
+        ];
+        let accounts_b = vec![
+            (pubkeys[1], account.clone()),
+            (pubkeys[2], modified_account),
+            (pubkeys[3], account),
+        ];
+        let diffs: Vec<_> = diff_sorted_accounts(accounts_a, accounts_b).collect();
+
+        let unmodified = CliSnapshotAccount {
+            lamports: 1,
+            owner: owner.to_string(),
+            data_len: 3,
+            data_hash: hash(&[0, 0, 0]).to_string(),
+            rent_epoch: 0,
+            executable: false,
+        };
+        let modified = CliSnapshotAccount {
+            data_hash: hash(&[1, 2, 3]).to_string(),
+            ..unmodified.clone()
+        };
+        assert_eq!(
+            diffs,
+            vec![
+                CliAccountDiff {
+                    pubkey: pubkeys[0].to_string(),
+                    kind: AccountDiffKind::Removed,
+                    a: Some(unmodified.clone()),
+                    b: None,
+                },
+                CliAccountDiff {
+                    pubkey: pubkeys[2].to_string(),
+                    kind: AccountDiffKind::Modified,
+                    a: Some(unmodified.clone()),
+                    b: Some(modified),
+                },
+                CliAccountDiff {
+                    pubkey: pubkeys[3].to_string(),
+                    kind: AccountDiffKind::Added,
+                    a: None,
+                    b: Some(unmodified),
+                },
+            ]
+        );
+    }
+}
@@ -4,11 +4,13 @@ use {
         args::*,
         bigtable::*,
         blockstore::*,
+        diff_snapshots::SnapshotArchives,
         ledger_path::*,
         ledger_utils::*,
         output::{
-            output_account, AccountsOutputConfig, AccountsOutputMode, AccountsOutputStreamer,
-            SlotBankHash,
+            output_account, output_snapshot_accounts, AccountsOutputConfig, AccountsOutputMode,
+            AccountsOutputStreamer, BlockProductionSummary, CliSnapshotDiff,
+            SimulatedBlockProduction, SimulatedSlotSummary, SlotBankHash,
         },
         program::*,
     },
@@ -31,10 +33,14 @@ use {
     },
     solana_cli_output::OutputFormat,
     solana_core::{
+        banking_simulation::{
+            BankingSimulator, BankingTraceEvents, SimulatedBlock, DEFAULT_SIMULATED_SLOT_COUNT,
+        },
         system_monitor_service::{SystemMonitorService, SystemMonitorStatsReportConfig},
-        validator::BlockVerificationMethod,
+        validator::{BlockProductionMethod, BlockVerificationMethod},
     },
     solana_cost_model::{cost_model::CostModel, cost_tracker::CostTracker},
+    solana_entry::entry::Entry,
     solana_ledger::{
         blockstore::{create_new_ledger, Blockstore},
         blockstore_options::{AccessType, LedgerColumnOptions},
@@ -101,11 +107,13 @@ use {
 mod args;
 mod bigtable;
 mod blockstore;
+mod diff_snapshots;
 mod error;
 mod ledger_path;
 mod ledger_utils;
 mod output;
 mod program;
+mod snapshot_accounts;
 
 fn parse_encoding_format(matches: &ArgMatches<'_>) -> UiAccountEncoding {
     match matches.value_of("encoding") {
@@ -513,6 +521,40 @@ fn compute_slot_cost(
     Ok(())
 }
 
+/// Summarizes the transactions of a block, as they would be charged by `bank`.
+/// Both simulated and confirmed blocks are summarized against the simulated
+/// bank, so that their differences stem only from the included transactions.
+fn summarize_block_production(bank: &Bank, entries: &[Entry]) -> BlockProductionSummary {
+    let mut summary = BlockProductionSummary::default();
+    for transaction in entries.iter().flat_map(|entry| entry.transactions.iter()) {
+        summary.transaction_count += 1;
+        let transaction = match SanitizedTransaction::try_create(
+            transaction.clone(),
+            MessageHash::Compute,
+            None,
+            bank,
+            bank.get_reserved_account_keys(),
+        ) {
+            Ok(transaction) => transaction,
+            Err(err) => {
+                warn!(
+                    "Slot: {}, Failed to sanitize transaction: {err:?}",
+                    bank.slot()
+                );
+                continue;
+            }
+        };
+        if transaction.is_simple_vote_transaction() {
+            summary.vote_transaction_count += 1;
+        }
+        summary.cost_units += CostModel::calculate_cost(&transaction, &bank.feature_set).sum();
+        summary.fees += bank
+            .get_fee_for_message(transaction.message())
+            .unwrap_or_default();
+    }
+    summary
+}
+
 /// Finds the accounts needed to replay slots `snapshot_slot` to `ending_slot`.
 /// Removes all other accounts from accounts_db, and updates the accounts hash
 /// and capitalization. This is used by the --minimize option in create-snapshot
@@ -859,6 +901,7 @@ fn main() {
         .max(rent.minimum_balance(StakeStateV2::size_of()))
         .to_string();
     let default_graph_vote_account_mode = GraphVoteAccountMode::default();
+    let default_simulated_slot_count = DEFAULT_SIMULATED_SLOT_COUNT.to_string();
 
     let mut measure_total_execution_time = Measure::start("ledger tool");
 
@@ -1450,6 +1493,45 @@ fn main() {
                         .help("Limit output to accounts owned by the provided program pubkey"),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("account")
+                .about(
+                    "Print the contents of accounts read directly out of a snapshot archive, \
+                     without loading the bank",
+                )
+                .arg(&accounts_data_encoding_arg)
+                .arg(
+                    Arg::with_name("pubkeys")
+                        .index(1)
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .validator(is_pubkey)
+                        .multiple(true)
+                        .required(true)
+                        .help("Accounts to print"),
+                )
+                .arg(
+                    Arg::with_name("snapshot")
+                        .long("snapshot")
+                        .value_name("ARCHIVE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Full snapshot archive to read the accounts from"),
+                )
+                .arg(
+                    Arg::with_name("incremental_snapshot")
+                        .long("incremental-snapshot")
+                        .value_name("ARCHIVE")
+                        .takes_value(true)
+                        .help("Incremental snapshot archive to read on top of the full snapshot"),
+                )
+                .arg(
+                    Arg::with_name("no_account_data")
+                        .long("no-account-data")
+                        .takes_value(false)
+                        .help("Do not print account data when printing account contents."),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("capitalization")
                 .about("Print capitalization (aka, total supply) while checksumming it")
@@ -1504,6 +1586,45 @@ fn main() {
                         .help("Output file in the csv format"),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("diff-snapshots")
+                .about(
+                    "Compare the banks in two snapshot archives, reporting the accounts and bank \
+                     fields which differ",
+                )
+                .arg(&load_genesis_config_arg)
+                .args(&accounts_db_config_args)
+                .arg(
+                    Arg::with_name("snapshot_archive_a")
+                        .index(1)
+                        .value_name("ARCHIVE_A")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Full snapshot archive of the first bank to compare"),
+                )
+                .arg(
+                    Arg::with_name("snapshot_archive_b")
+                        .index(2)
+                        .value_name("ARCHIVE_B")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Full snapshot archive of the second bank to compare"),
+                )
+                .arg(
+                    Arg::with_name("incremental_snapshot_archive_a")
+                        .long("incremental-snapshot-archive-a")
+                        .value_name("ARCHIVE")
+                        .takes_value(true)
+                        .help("Incremental snapshot archive to load on top of ARCHIVE_A"),
+                )
+                .arg(
+                    Arg::with_name("incremental_snapshot_archive_b")
+                        .long("incremental-snapshot-archive-b")
+                        .value_name("ARCHIVE")
+                        .takes_value(true)
+                        .help("Incremental snapshot archive to load on top of ARCHIVE_B"),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("compute-slot-cost")
                 .about(
@@ -1524,6 +1645,65 @@ fn main() {
                 )
                 .arg(&allow_dead_slots_arg),
         )
+        .subcommand(
+            SubCommand::with_name("simulate-block-production")
+                .about(
+                    "Simulate producing blocks as the leader with the banking trace events \
+                     recorded by a validator, and compare them against the confirmed blocks",
+                )
+                .arg(&load_genesis_config_arg)
+                .args(&accounts_db_config_args)
+                .args(&snapshot_config_args)
+                .arg(
+                    Arg::with_name("banking_trace_events")
+                        .long("banking-trace-events")
+                        .value_name("DIR")
+                        .takes_value(true)
+                        .help(
+                            "Use DIR as the location of the banking trace event files \
+                             [default: --ledger value/banking_trace]",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("first_simulated_slot")
+                        .long("first-simulated-slot")
+                        .value_name("SLOT")
+                        .validator(is_slot)
+                        .takes_value(true)
+                        .required(true)
+                        .help(
+                            "The first slot to be simulated. The ledger is replayed up to the \
+                             parent slot recorded for it in the banking trace",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("num_simulated_slots")
+                        .long("num-simulated-slots")
+                        .value_name("COUNT")
+                        .validator(|s| is_within_range(s, 1..))
+                        .takes_value(true)
+                        .default_value(&default_simulated_slot_count)
+                        .help("Number of consecutive slots to produce blocks for"),
+                )
+                .arg(
+                    Arg::with_name("simulated_ledger")
+                        .long("simulated-ledger")
+                        .value_name("DIR")
+                        .takes_value(true)
+                        .help(
+                            "Use DIR as the blockstore location to write the simulated blocks \
+                             into [default: --ledger value/banking_simulation]",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("block_production_method")
+                        .long("block-production-method")
+                        .value_name("METHOD")
+                        .takes_value(true)
+                        .possible_values(BlockProductionMethod::cli_names())
+                        .help(BlockProductionMethod::cli_message()),
+                ),
+        )
         .program_subcommand()
         .get_matches();
 
@@ -2410,6 +2590,56 @@ fn main() {
                     );
                     info!("{scan_time}");
                 }
+                ("account", Some(arg_matches)) => {
+                    let pubkeys = pubkeys_of(arg_matches, "pubkeys").unwrap();
+                    let output_format =
+                        OutputFormat::from_matches(arg_matches, "output_format", false);
+                    let snapshot_archives = SnapshotArchives::new(
+                        PathBuf::from(arg_matches.value_of("snapshot").unwrap()),
+                        arg_matches
+                            .value_of("incremental_snapshot")
+                            .map(PathBuf::from),
+                    )
+                    .unwrap_or_else(|err| {
+                        eprintln!("{err}");
+                        exit(1);
+                    });
+                    let working_dir = ledger_path.join(LEDGER_TOOL_DIRECTORY).join("account");
+
+                    let (accounts, load_time) = measure_time!(
+                        snapshot_accounts::load_accounts(
+                            &snapshot_archives,
+                            &pubkeys,
+                            &working_dir
+                        ),
+                        "load accounts"
+                    );
+                    info!("{load_time}");
+                    let accounts: Vec<_> = accounts
+                        .unwrap_or_else(|err| {
+                            eprintln!("Failed to load accounts: {err}");
+                            exit(1);
+                        })
+                        .into_iter()
+                        .filter_map(|(pubkey, account)| {
+                            if account.is_none() {
+                                eprintln!("Account {pubkey} not found in the snapshot");
+                            }
+                            account.map(|(account, slot)| (pubkey, account, slot))
+                        })
+                        .collect();
+
+                    output_snapshot_accounts(
+                        &accounts,
+                        &output_format,
+                        !arg_matches.is_present("no_account_data"),
+                        parse_encoding_format(arg_matches),
+                    )
+                    .unwrap_or_else(|err| {
+                        eprintln!("Failed to output accounts: {err}");
+                        exit(1);
+                    });
+                }
                 ("capitalization", Some(arg_matches)) => {
                     let process_options = parse_process_options(&ledger_path, arg_matches);
                     let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
@@ -2916,6 +3146,63 @@ fn main() {
                         println!("Capitalization: {}", Sol(bank.capitalization()));
                     }
                 }
+                ("diff-snapshots", Some(arg_matches)) => {
+                    let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                    let accounts_db_config = get_accounts_db_config(&ledger_path, arg_matches);
+                    let output_format =
+                        OutputFormat::from_matches(arg_matches, "output_format", false);
+                    let working_dir = ledger_path
+                        .join(LEDGER_TOOL_DIRECTORY)
+                        .join("diff_snapshots");
+
+                    let load_bank = |archive: &str, incremental_archive: &str, name: &str| {
+                        let (snapshot_archives, bank) = SnapshotArchives::new(
+                            PathBuf::from(arg_matches.value_of(archive).unwrap()),
+                            arg_matches.value_of(incremental_archive).map(PathBuf::from),
+                        )
+                        .and_then(|snapshot_archives| {
+                            let bank = diff_snapshots::load_bank(
+                                &snapshot_archives,
+                                &genesis_config,
+                                &accounts_db_config,
+                                &working_dir.join(name),
+                            )?;
+                            Ok((snapshot_archives, bank))
+                        })
+                        .unwrap_or_else(|err| {
+                            eprintln!("Failed to load snapshot {name}: {err}");
+                            exit(1);
+                        });
+                        info!("Loaded snapshot {name} at slot {}", bank.slot());
+                        (snapshot_archives, bank)
+                    };
+                    let (snapshot_archives_a, bank_a) =
+                        load_bank("snapshot_archive_a", "incremental_snapshot_archive_a", "a");
+                    let (snapshot_archives_b, bank_b) =
+                        load_bank("snapshot_archive_b", "incremental_snapshot_archive_b", "b");
+
+                    let bank_fields = diff_snapshots::diff_bank_fields(&bank_a, &bank_b);
+                    let (accounts, diff_time) = measure_time!(
+                        diff_snapshots::diff_accounts(&bank_a, &bank_b),
+                        "diff accounts"
+                    );
+                    info!("{diff_time}");
+
+                    let describe = |snapshot_archives: &SnapshotArchives| {
+                        let mut description = snapshot_archives.full.path().display().to_string();
+                        if let Some(incremental) = &snapshot_archives.incremental {
+                            description.push_str(&format!(" + {}", incremental.path().display()));
+                        }
+                        description
+                    };
+                    let snapshot_diff = CliSnapshotDiff::new(
+                        describe(&snapshot_archives_a),
+                        describe(&snapshot_archives_b),
+                        bank_fields,
+                        accounts,
+                    );
+                    println!("{}", output_format.formatted_string(&snapshot_diff));
+                }
                 ("compute-slot-cost", Some(arg_matches)) => {
                     let blockstore =
                         open_blockstore(&ledger_path, arg_matches, AccessType::Secondary);
@@ -2936,6 +3223,112 @@ fn main() {
                         }
                     }
                 }
+                ("simulate-block-production", Some(arg_matches)) => {
+                    let banking_trace_events_path = arg_matches
+                        .value_of("banking_trace_events")
+                        .map(PathBuf::from)
+                        .unwrap_or_else(|| ledger_path.join("banking_trace"));
+                    let first_simulated_slot =
+                        value_t_or_exit!(arg_matches, "first_simulated_slot", Slot);
+                    let num_simulated_slots =
+                        value_t_or_exit!(arg_matches, "num_simulated_slots", u64);
+                    let simulated_ledger_path = arg_matches
+                        .value_of("simulated_ledger")
+                        .map(PathBuf::from)
+                        .unwrap_or_else(|| ledger_path.join("banking_simulation"));
+                    let block_production_method = value_t!(
+                        arg_matches,
+                        "block_production_method",
+                        BlockProductionMethod
+                    )
+                    .unwrap_or_default();
+                    let output_format =
+                        OutputFormat::from_matches(arg_matches, "output_format", false);
+
+                    let banking_trace_events = BankingTraceEvents::load(&banking_trace_events_path)
+                        .unwrap_or_else(|err| {
+                            eprintln!(
+                                "Failed to load banking trace events from \
+                                     {banking_trace_events_path:?}: {err}"
+                            );
+                            exit(1);
+                        });
+                    let simulator = BankingSimulator::new(
+                        banking_trace_events,
+                        first_simulated_slot,
+                        num_simulated_slots,
+                    );
+                    let parent_slot = simulator.parent_slot().unwrap_or_else(|err| {
+                        eprintln!("{err}");
+                        exit(1);
+                    });
+
+                    let mut process_options = parse_process_options(&ledger_path, arg_matches);
+                    process_options.halt_at_slot = Some(parent_slot);
+                    let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                    let blockstore = Arc::new(open_blockstore(
+                        &ledger_path,
+                        arg_matches,
+                        get_access_type(&process_options),
+                    ));
+                    let LoadAndProcessLedgerOutput { bank_forks, .. } =
+                        load_and_process_ledger_or_exit(
+                            arg_matches,
+                            &genesis_config,
+                            blockstore.clone(),
+                            process_options,
+                            None,
+                        );
+
+                    let simulated_blockstore = Arc::new(
+                        Blockstore::open(&simulated_ledger_path).unwrap_or_else(|err| {
+                            eprintln!(
+                                "Failed to open simulated blockstore at \
+                                 {simulated_ledger_path:?}: {err:?}"
+                            );
+                            exit(1);
+                        }),
+                    );
+                    let simulated_blocks = simulator
+                        .start(
+                            &genesis_config,
+                            bank_forks,
+                            simulated_blockstore,
+                            block_production_method,
+                        )
+                        .unwrap_or_else(|err| {
+                            eprintln!("Failed to simulate block production: {err}");
+                            exit(1);
+                        });
+
+                    let slots = simulated_blocks
+                        .iter()
+                        .map(|SimulatedBlock { bank, entries }| {
+                            let actual = blockstore
+                                .get_slot_entries_with_shred_info(bank.slot(), 0, false)
+                                .ok()
+                                .filter(|(_entries, _num_shreds, is_full)| *is_full)
+                                .map(|(entries, _num_shreds, _is_full)| {
+                                    summarize_block_production(bank, &entries)
+                                });
+                            SimulatedSlotSummary {
+                                slot: bank.slot(),
+                                parent_slot: bank.parent_slot(),
+                                bank_hash: bank.hash().to_string(),
+                                simulated: summarize_block_production(bank, entries),
+                                actual,
+                            }
+                        })
+                        .collect();
+                    let simulated_block_production = SimulatedBlockProduction {
+                        simulated_ledger: simulated_ledger_path.display().to_string(),
+                        slots,
+                    };
+                    println!(
+                        "{}",
+                        output_format.formatted_string(&simulated_block_production)
+                    );
+                }
                 ("", _) => {
                     eprintln!("{}", matches.usage());
                     exit(1);
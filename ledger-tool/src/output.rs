@@ -20,8 +20,8 @@ use {
     solana_runtime::bank::{Bank, TotalAccountsStats},
     solana_sdk::{
         account::{AccountSharedData, ReadableAccount},
-        clock::{Slot, UnixTimestamp},
-        hash::Hash,
+        clock::{Epoch, Slot, UnixTimestamp},
+        hash::{hash, Hash},
         native_token::lamports_to_sol,
         pubkey::Pubkey,
     },
@@ -121,6 +121,264 @@ impl Display for SlotBankHash {
     }
 }
 
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockProductionSummary {
+    pub transaction_count: u64,
+    pub vote_transaction_count: u64,
+    pub cost_units: u64,
+    pub fees: u64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedSlotSummary {
+    pub slot: Slot,
+    pub parent_slot: Slot,
+    pub bank_hash: String,
+    pub simulated: BlockProductionSummary,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual: Option<BlockProductionSummary>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBlockProduction {
+    pub simulated_ledger: String,
+    pub slots: Vec<SimulatedSlotSummary>,
+}
+
+impl VerboseDisplay for SimulatedBlockProduction {}
+impl QuietDisplay for SimulatedBlockProduction {}
+
+fn writeln_simulated_diff(
+    f: &mut Formatter,
+    name: &str,
+    simulated: u64,
+    actual: u64,
+) -> fmt::Result {
+    writeln!(
+        f,
+        "  {name:<18} {simulated:>14} simulated {actual:>14} actual ({:+})",
+        i128::from(simulated) - i128::from(actual)
+    )
+}
+
+impl Display for SimulatedBlockProduction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "Simulated blocks written to {}", self.simulated_ledger)?;
+        for slot_summary in &self.slots {
+            writeln!(
+                f,
+                "Slot {} (parent: {}), simulated bank hash: {}",
+                slot_summary.slot, slot_summary.parent_slot, slot_summary.bank_hash
+            )?;
+            let simulated = &slot_summary.simulated;
+            match &slot_summary.actual {
+                Some(actual) => {
+                    writeln_simulated_diff(
+                        f,
+                        "Transactions:",
+                        simulated.transaction_count,
+                        actual.transaction_count,
+                    )?;
+                    writeln_simulated_diff(
+                        f,
+                        "Vote transactions:",
+                        simulated.vote_transaction_count,
+                        actual.vote_transaction_count,
+                    )?;
+                    writeln_simulated_diff(
+                        f,
+                        "Cost units:",
+                        simulated.cost_units,
+                        actual.cost_units,
+                    )?;
+                    writeln_simulated_diff(f, "Fees:", simulated.fees, actual.fees)?;
+                }
+                None => {
+                    writeln!(
+                        f,
+                        "  {} transactions ({} votes), {} cost units, {} lamports in fees; no \
+                         confirmed block found in the ledger",
+                        simulated.transaction_count,
+                        simulated.vote_transaction_count,
+                        simulated.cost_units,
+                        simulated.fees,
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CliSnapshotAccount {
+    pub lamports: u64,
+    pub owner: String,
+    pub data_len: usize,
+    pub data_hash: String,
+    pub rent_epoch: Epoch,
+    pub executable: bool,
+}
+
+impl From<&AccountSharedData> for CliSnapshotAccount {
+    fn from(account: &AccountSharedData) -> Self {
+        Self {
+            lamports: account.lamports(),
+            owner: account.owner().to_string(),
+            data_len: account.data().len(),
+            data_hash: hash(account.data()).to_string(),
+            rent_epoch: account.rent_epoch(),
+            executable: account.executable(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AccountDiffKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CliAccountDiff {
+    pub pubkey: String,
+    pub kind: AccountDiffKind,
+    /// The account in the first snapshot, None if it was added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub a: Option<CliSnapshotAccount>,
+    /// The account in the second snapshot, None if it was removed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub b: Option<CliSnapshotAccount>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CliBankFieldDiff {
+    pub field: String,
+    pub a: String,
+    pub b: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CliSnapshotDiff {
+    pub snapshot_a: String,
+    pub snapshot_b: String,
+    pub bank_fields: Vec<CliBankFieldDiff>,
+    pub num_added: usize,
+    pub num_removed: usize,
+    pub num_modified: usize,
+    pub accounts: Vec<CliAccountDiff>,
+}
+
+impl CliSnapshotDiff {
+    pub fn new(
+        snapshot_a: String,
+        snapshot_b: String,
+        bank_fields: Vec<CliBankFieldDiff>,
+        accounts: Vec<CliAccountDiff>,
+    ) -> Self {
+        let count = |kind| accounts.iter().filter(|diff| diff.kind == kind).count();
+        Self {
+            snapshot_a,
+            snapshot_b,
+            bank_fields,
+            num_added: count(AccountDiffKind::Added),
+            num_removed: count(AccountDiffKind::Removed),
+            num_modified: count(AccountDiffKind::Modified),
+            accounts,
+        }
+    }
+}
+
+impl VerboseDisplay for CliSnapshotDiff {}
+impl QuietDisplay for CliSnapshotDiff {}
+
+fn writeln_snapshot_account(f: &mut Formatter, account: &CliSnapshotAccount) -> fmt::Result {
+    writeln!(
+        f,
+        "    lamports: {}, owner: {}, data len: {}, data hash: {}, rent epoch: {}, executable: {}",
+        account.lamports,
+        account.owner,
+        account.data_len,
+        account.data_hash,
+        account.rent_epoch,
+        account.executable,
+    )
+}
+
+fn writeln_snapshot_account_changes(
+    f: &mut Formatter,
+    a: &CliSnapshotAccount,
+    b: &CliSnapshotAccount,
+) -> fmt::Result {
+    if a.lamports != b.lamports {
+        writeln!(f, "    lamports: {} -> {}", a.lamports, b.lamports)?;
+    }
+    if a.owner != b.owner {
+        writeln!(f, "    owner: {} -> {}", a.owner, b.owner)?;
+    }
+    if a.data_len != b.data_len {
+        writeln!(f, "    data len: {} -> {}", a.data_len, b.data_len)?;
+    }
+    if a.data_hash != b.data_hash {
+        writeln!(f, "    data hash: {} -> {}", a.data_hash, b.data_hash)?;
+    }
+    if a.rent_epoch != b.rent_epoch {
+        writeln!(f, "    rent epoch: {} -> {}", a.rent_epoch, b.rent_epoch)?;
+    }
+    if a.executable != b.executable {
+        writeln!(f, "    executable: {} -> {}", a.executable, b.executable)?;
+    }
+    Ok(())
+}
+
+impl Display for CliSnapshotDiff {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "Snapshot A: {}", self.snapshot_a)?;
+        writeln!(f, "Snapshot B: {}", self.snapshot_b)?;
+        if self.bank_fields.is_empty() {
+            writeln!(f, "Bank fields are identical")?;
+        } else {
+            writeln!(f, "Bank fields:")?;
+            for field in &self.bank_fields {
+                writeln!(f, "  {}: {} -> {}", field.field, field.a, field.b)?;
+            }
+        }
+
+        writeln!(
+            f,
+            "Accounts: {} added, {} removed, {} modified",
+            self.num_added, self.num_removed, self.num_modified
+        )?;
+        for diff in &self.accounts {
+            match (&diff.a, &diff.b) {
+                (None, Some(b)) => {
+                    writeln!(f, "  + {}", diff.pubkey)?;
+                    writeln_snapshot_account(f, b)?;
+                }
+                (Some(a), None) => {
+                    writeln!(f, "  - {}", diff.pubkey)?;
+                    writeln_snapshot_account(f, a)?;
+                }
+                (Some(a), Some(b)) => {
+                    writeln!(f, "  ~ {}", diff.pubkey)?;
+                    writeln_snapshot_account_changes(f, a, b)?;
+                }
+                (None, None) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
 fn writeln_entry(f: &mut dyn fmt::Write, i: usize, entry: &CliEntry, prefix: &str) -> fmt::Result {
     writeln!(
         f,
@@ -872,3 +1130,33 @@ pub fn output_account(
         };
     }
 }
+
+/// Prints the accounts read out of a snapshot by `ledger-tool account`
+pub fn output_snapshot_accounts(
+    accounts: &[(Pubkey, AccountSharedData, Slot)],
+    output_format: &OutputFormat,
+    print_account_data: bool,
+    encoding: UiAccountEncoding,
+) -> Result<()> {
+    match output_format {
+        OutputFormat::Json | OutputFormat::JsonCompact => {
+            let cli_account_new_config = CliAccountNewConfig {
+                data_encoding: encoding,
+                ..CliAccountNewConfig::default()
+            };
+            let cli_accounts: Vec<_> = accounts
+                .iter()
+                .map(|(pubkey, account, _slot)| {
+                    CliAccount::new_with_config(pubkey, account, &cli_account_new_config)
+                })
+                .collect();
+            println!("{}", serde_json::to_string(&cli_accounts)?);
+        }
+        _ => {
+            for (pubkey, account, slot) in accounts {
+                output_account(pubkey, account, Some(*slot), print_account_data, encoding);
+            }
+        }
+    }
+    Ok(())
+}
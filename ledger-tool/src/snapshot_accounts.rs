@@ -0,0 +1,105 @@
+//! Reads individual accounts straight out of the storages of snapshot archives
+
+use {
+    crate::{
+        diff_snapshots::SnapshotArchives,
+        error::{LedgerToolError, Result},
+    },
+    log::*,
+    rayon::prelude::*,
+    solana_accounts_db::{
+        accounts::Accounts, accounts_file::StorageAccess,
+        utils::create_all_accounts_run_and_snapshot_dirs,
+    },
+    solana_runtime::snapshot_utils,
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        clock::Slot,
+        pubkey::Pubkey,
+    },
+    std::{
+        collections::{HashMap, HashSet},
+        fs,
+        path::Path,
+    },
+};
+
+/// Returns the latest version of each of `pubkeys` in `snapshot_archives`, along with the slot
+/// it was last modified in, or None if the account doesn't exist.
+///
+/// The archives are unpacked under `working_dir`, whose previous contents are removed. Only the
+/// index of each storage is scanned, the accounts index of the whole bank is never built.
+pub fn load_accounts(
+    snapshot_archives: &SnapshotArchives,
+    pubkeys: &[Pubkey],
+    working_dir: &Path,
+) -> Result<Vec<(Pubkey, Option<(AccountSharedData, Slot)>)>> {
+    if working_dir.exists() {
+        fs::remove_dir_all(working_dir)?;
+    }
+    let (account_run_paths, _account_snapshot_paths) =
+        create_all_accounts_run_and_snapshot_dirs(&[working_dir.join("accounts")])?;
+    let bank_snapshots_dir = working_dir.join("snapshot");
+    fs::create_dir_all(&bank_snapshots_dir)?;
+
+    let (full, incremental, _next_accounts_file_id) =
+        snapshot_utils::verify_and_unarchive_snapshots(
+            &bank_snapshots_dir,
+            &snapshot_archives.full,
+            snapshot_archives.incremental.as_ref(),
+            &account_run_paths,
+            StorageAccess::Mmap,
+        )
+        .map_err(|err| LedgerToolError::Generic(format!("failed to unpack snapshot: {err}")))?;
+
+    let storages: Vec<_> = full
+        .storage
+        .iter()
+        .chain(
+            incremental
+                .iter()
+                .flat_map(|incremental| incremental.storage.iter()),
+        )
+        .map(|entry| (*entry.key(), entry.value().storage.clone()))
+        .collect();
+    info!(
+        "Searching {} storages for {} accounts",
+        storages.len(),
+        pubkeys.len()
+    );
+
+    let pubkey_set: HashSet<_> = pubkeys.iter().copied().collect();
+    let latest_accounts = storages
+        .par_iter()
+        .map(|(slot, storage)| {
+            storage
+                .accounts
+                .get_accounts(&pubkey_set)
+                .into_iter()
+                .map(|(pubkey, account)| (pubkey, (account, *slot)))
+                .collect::<HashMap<_, _>>()
+        })
+        .reduce(HashMap::new, |mut latest_accounts, accounts| {
+            for (pubkey, (account, slot)) in accounts {
+                if latest_accounts
+                    .get(&pubkey)
+                    .map_or(true, |(_, latest_slot)| slot > *latest_slot)
+                {
+                    latest_accounts.insert(pubkey, (account, slot));
+                }
+            }
+            latest_accounts
+        });
+
+    Ok(pubkeys
+        .iter()
+        .map(|pubkey| {
+            // A zero lamport account is how a deleted account is stored
+            let account = latest_accounts
+                .get(pubkey)
+                .filter(|(account, _)| Accounts::is_loadable(account.lamports()))
+                .cloned();
+            (*pubkey, account)
+        })
+        .collect())
+}
@@ -1,15 +1,30 @@
 use {
     assert_cmd::prelude::*,
+    serde_json::Value,
     solana_ledger::{
-        blockstore, blockstore::Blockstore, blockstore_options::ShredStorageType,
+        blockstore,
+        blockstore::Blockstore,
+        blockstore_options::ShredStorageType,
         create_new_tmp_ledger_auto_delete, create_new_tmp_ledger_fifo_auto_delete,
-        genesis_utils::create_genesis_config, get_tmp_ledger_path_auto_delete,
+        genesis_utils::{create_genesis_config, GenesisConfigInfo},
+        get_tmp_ledger_path_auto_delete,
+    },
+    solana_runtime::{
+        bank::Bank, snapshot_archive_info::SnapshotArchiveInfoGetter, snapshot_bank_utils,
+        snapshot_utils::ArchiveFormat,
+    },
+    solana_sdk::{
+        native_token::sol_to_lamports,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
     },
     std::{
         fs,
         path::Path,
         process::{Command, Output},
+        sync::Arc,
     },
+    tempfile::TempDir,
 };
 
 fn run_ledger_tool(args: &[&str]) -> Output {
@@ -112,3 +127,104 @@ fn copy_test() {
     ledger_tool_copy_test("fifo", "level");
     ledger_tool_copy_test("fifo", "fifo");
 }
+
+#[test]
+fn diff_snapshots() {
+    let GenesisConfigInfo {
+        genesis_config,
+        mint_keypair,
+        ..
+    } = create_genesis_config(sol_to_lamports(1_000.));
+    let (ledger_path, _blockhash) = create_new_tmp_ledger_auto_delete!(&genesis_config);
+    let bank_snapshots_dir = TempDir::new().unwrap();
+    let snapshot_archives_dir = TempDir::new().unwrap();
+    let snapshot_archive = |bank: &Bank| {
+        bank.fill_bank_with_ticks_for_tests();
+        let archive_info = snapshot_bank_utils::bank_to_full_snapshot_archive(
+            bank_snapshots_dir.path(),
+            bank,
+            None,
+            snapshot_archives_dir.path(),
+            snapshot_archives_dir.path(),
+            ArchiveFormat::Tar,
+        )
+        .unwrap();
+        archive_info.path().to_str().unwrap().to_string()
+    };
+
+    let removed = Keypair::new();
+    let modified = Pubkey::new_unique();
+    let added = Pubkey::new_unique();
+    let unchanged = Pubkey::new_unique();
+    let bank0 = Arc::new(Bank::new_for_tests(&genesis_config));
+    bank0
+        .transfer(sol_to_lamports(1.), &mint_keypair, &removed.pubkey())
+        .unwrap();
+    bank0
+        .transfer(sol_to_lamports(1.), &mint_keypair, &modified)
+        .unwrap();
+    bank0
+        .transfer(sol_to_lamports(1.), &mint_keypair, &unchanged)
+        .unwrap();
+    let archive_a = snapshot_archive(&bank0);
+
+    let bank1 = Bank::new_from_parent(bank0, &Pubkey::new_unique(), 1);
+    bank1
+        .transfer(sol_to_lamports(1.), &removed, &mint_keypair.pubkey())
+        .unwrap();
+    bank1
+        .transfer(sol_to_lamports(2.), &mint_keypair, &modified)
+        .unwrap();
+    bank1
+        .transfer(sol_to_lamports(3.), &mint_keypair, &added)
+        .unwrap();
+    let archive_b = snapshot_archive(&bank1);
+
+    let output = run_ledger_tool(&[
+        "-l",
+        ledger_path.path().to_str().unwrap(),
+        "diff-snapshots",
+        &archive_a,
+        &archive_b,
+        "--output",
+        "json",
+    ]);
+    assert!(output.status.success());
+    let diff: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let account_diff = |pubkey: &Pubkey| {
+        diff["accounts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|account| account["pubkey"] == pubkey.to_string())
+            .unwrap()
+            .clone()
+    };
+
+    let removed = account_diff(&removed.pubkey());
+    assert_eq!(removed["kind"], "removed");
+    assert_eq!(removed["a"]["lamports"], sol_to_lamports(1.));
+    assert!(removed.get("b").is_none());
+
+    let modified = account_diff(&modified);
+    assert_eq!(modified["kind"], "modified");
+    assert_eq!(modified["a"]["lamports"], sol_to_lamports(1.));
+    assert_eq!(modified["b"]["lamports"], sol_to_lamports(3.));
+
+    let added = account_diff(&added);
+    assert_eq!(added["kind"], "added");
+    assert!(added.get("a").is_none());
+    assert_eq!(added["b"]["lamports"], sol_to_lamports(3.));
+
+    assert_eq!(account_diff(&mint_keypair.pubkey())["kind"], "modified");
+    assert!(diff["accounts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|account| account["pubkey"] != unchanged.to_string()));
+    assert!(diff["bankFields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|field| field["field"] == "slot" && field["a"] == "0" && field["b"] == "1"));
+}
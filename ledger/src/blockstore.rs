@@ -4134,6 +4134,11 @@ impl Blockstore {
         self.db.is_primary_access()
     }
 
+    /// Returns the column options the blockstore was opened with
+    pub fn column_options(&self) -> &LedgerColumnOptions {
+        self.db.column_options()
+    }
+
     /// Scan for any ancestors of the supplied `start_root` that are not
     /// marked as roots themselves. Mark any found slots as roots since
     /// the ancestor of a root is also inherently a root. Returns the
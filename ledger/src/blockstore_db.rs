@@ -1537,6 +1537,10 @@ impl Database {
         self.backend.is_primary_access()
     }
 
+    pub fn column_options(&self) -> &LedgerColumnOptions {
+        &self.column_options
+    }
+
     pub fn set_oldest_slot(&self, oldest_slot: Slot) {
         self.backend.oldest_slot.set(oldest_slot);
     }
@@ -508,7 +508,7 @@ fn test_snapshot_download() {
 
     // Download the snapshot, then boot a validator from it.
     download_snapshot_archive(
-        &cluster.entry_point_info.rpc().unwrap(),
+        &[cluster.entry_point_info.rpc().unwrap()],
         &validator_snapshot_test_config
             .validator_config
             .snapshot_config
@@ -639,7 +639,7 @@ fn test_incremental_snapshot_download() {
 
     // Download the snapshots, then boot a validator from them.
     download_snapshot_archive(
-        &cluster.entry_point_info.rpc().unwrap(),
+        &[cluster.entry_point_info.rpc().unwrap()],
         &validator_snapshot_test_config
             .validator_config
             .snapshot_config
@@ -667,7 +667,7 @@ fn test_incremental_snapshot_download() {
     .unwrap();
 
     download_snapshot_archive(
-        &cluster.entry_point_info.rpc().unwrap(),
+        &[cluster.entry_point_info.rpc().unwrap()],
         &validator_snapshot_test_config
             .validator_config
             .snapshot_config
@@ -819,7 +819,7 @@ fn test_incremental_snapshot_download_with_crossing_full_snapshot_interval_at_st
     // Download the snapshots, then boot a validator from them.
     info!("Downloading full snapshot to validator...");
     download_snapshot_archive(
-        &cluster.entry_point_info.rpc().unwrap(),
+        &[cluster.entry_point_info.rpc().unwrap()],
         validator_snapshot_test_config
             .full_snapshot_archives_dir
             .path(),
@@ -853,7 +853,7 @@ fn test_incremental_snapshot_download_with_crossing_full_snapshot_interval_at_st
 
     info!("Downloading incremental snapshot to validator...");
     download_snapshot_archive(
-        &cluster.entry_point_info.rpc().unwrap(),
+        &[cluster.entry_point_info.rpc().unwrap()],
         validator_snapshot_test_config
             .full_snapshot_archives_dir
             .path(),
@@ -0,0 +1,439 @@
+//! The `prometheus` module serves the submitted datapoints and counters over
+//! HTTP in the Prometheus text exposition format
+//!
+//! Each numeric datapoint field is exported as a gauge named
+//! `solana_<datapoint>_<field>` holding the last submitted value, labelled by
+//! the datapoint tags. Each counter is exported as a monotonically increasing
+//! `solana_<counter>_count` counter.
+//!
+//! The number of metrics, and of label sets per metric, is bounded so that tags with
+//! unbounded values can't grow the registry without limit; samples beyond the bounds are
+//! dropped.
+
+use {
+    crate::{counter::CounterPoint, datapoint::DataPoint},
+    lazy_static::lazy_static,
+    log::*,
+    std::{
+        collections::{btree_map::Entry, BTreeMap},
+        fmt::Write as _,
+        io::{self, BufRead, BufReader, Read, Write},
+        net::{SocketAddr, TcpListener, TcpStream},
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+        thread::{self, JoinHandle},
+        time::{Duration, Instant},
+    },
+};
+
+const METRIC_NAME_PREFIX: &str = "solana_";
+const METRICS_PATH: &str = "/metrics";
+/// Time allowed for a client to send its whole request, and for each write of the response
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+/// Requests are only a request line and a few headers; anything past this is ignored
+const MAX_REQUEST_BYTES: u64 = 8 * 1024;
+/// Connections beyond this many being served at once are dropped
+const MAX_CONCURRENT_CONNECTIONS: usize = 4;
+const MAX_METRICS: usize = 10_000;
+const MAX_LABEL_SETS_PER_METRIC: usize = 1_000;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    static ref REGISTRY: Mutex<PrometheusRegistry> = Mutex::default();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MetricType {
+    Gauge,
+    Counter,
+}
+
+impl MetricType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gauge => "gauge",
+            Self::Counter => "counter",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Metric {
+    metric_type: MetricType,
+    /// samples keyed by their rendered label set
+    samples: BTreeMap<String, f64>,
+}
+
+/// The latest value of every metric, ready to be rendered for a scrape.
+#[derive(Debug, Default)]
+pub struct PrometheusRegistry {
+    metrics: BTreeMap<String, Metric>,
+}
+
+impl PrometheusRegistry {
+    pub fn record_point(&mut self, point: &DataPoint) {
+        let labels = render_labels(&point.tags);
+        for (field, value) in &point.fields {
+            // String fields have no numeric value to export
+            let Some(value) = parse_field_value(value) else {
+                continue;
+            };
+            if let Some(metric) = self.metric(metric_name(point.name, field), MetricType::Gauge) {
+                if metric.samples.len() < MAX_LABEL_SETS_PER_METRIC
+                    || metric.samples.contains_key(&labels)
+                {
+                    metric.samples.insert(labels.clone(), value);
+                }
+            }
+        }
+    }
+
+    pub fn record_counter(&mut self, counter: &CounterPoint) {
+        if let Some(metric) = self.metric(metric_name(counter.name, "count"), MetricType::Counter) {
+            *metric.samples.entry(String::new()).or_default() += counter.count as f64;
+        }
+    }
+
+    /// Renders every metric in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        for (name, metric) in &self.metrics {
+            let _ = writeln!(output, "# TYPE {name} {}", metric.metric_type.as_str());
+            for (labels, value) in &metric.samples {
+                let _ = writeln!(output, "{name}{labels} {}", render_value(*value));
+            }
+        }
+        output
+    }
+
+    /// Returns the metric called `name`, or None if it was already registered with a
+    /// different type or if the registry is full.
+    fn metric(&mut self, name: String, metric_type: MetricType) -> Option<&mut Metric> {
+        let num_metrics = self.metrics.len();
+        let metric = match self.metrics.entry(name) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                if num_metrics >= MAX_METRICS {
+                    return None;
+                }
+                entry.insert(Metric {
+                    metric_type,
+                    samples: BTreeMap::new(),
+                })
+            }
+        };
+        (metric.metric_type == metric_type).then_some(metric)
+    }
+}
+
+/// Returns true once the exporter has been started.
+pub(crate) fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub(crate) fn record_point(point: &DataPoint) {
+    REGISTRY.lock().unwrap().record_point(point);
+}
+
+pub(crate) fn record_counter(counter: &CounterPoint) {
+    REGISTRY.lock().unwrap().record_counter(counter);
+}
+
+/// Starts serving the submitted datapoints and counters at `http://<bind_address>/metrics`.
+///
+/// Only points submitted after the exporter starts are recorded.
+pub fn start_exporter(bind_address: SocketAddr) -> io::Result<JoinHandle<()>> {
+    let listener = TcpListener::bind(bind_address)?;
+    info!("Prometheus metrics exporter listening on {}", bind_address);
+    ENABLED.store(true, Ordering::Relaxed);
+    serve(listener)
+}
+
+/// Accepts connections on `listener`, serving each one on its own thread.
+fn serve(listener: TcpListener) -> io::Result<JoinHandle<()>> {
+    thread::Builder::new()
+        .name("solMetricsProm".into())
+        .spawn(move || {
+            let active_connections = Arc::new(AtomicUsize::default());
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        debug!("Prometheus metrics exporter connection failed: {}", err);
+                        continue;
+                    }
+                };
+                if active_connections.fetch_add(1, Ordering::Relaxed) >= MAX_CONCURRENT_CONNECTIONS
+                {
+                    active_connections.fetch_sub(1, Ordering::Relaxed);
+                    debug!("Prometheus metrics exporter is busy, dropping connection");
+                    continue;
+                }
+                let connection_active_connections = active_connections.clone();
+                let result =
+                    thread::Builder::new()
+                        .name("solMetricsPromC".into())
+                        .spawn(move || {
+                            if let Err(err) = handle_connection(stream) {
+                                debug!("Prometheus metrics exporter connection failed: {}", err);
+                            }
+                            connection_active_connections.fetch_sub(1, Ordering::Relaxed);
+                        });
+                if let Err(err) = result {
+                    active_connections.fetch_sub(1, Ordering::Relaxed);
+                    warn!(
+                        "Failed to spawn Prometheus metrics exporter thread: {}",
+                        err
+                    );
+                }
+            }
+        })
+}
+
+fn handle_connection(mut stream: TcpStream) -> io::Result<()> {
+    stream.set_write_timeout(Some(CONNECTION_TIMEOUT))?;
+
+    // The whole request must arrive before the deadline, however slowly it trickles in
+    let deadline = Instant::now() + CONNECTION_TIMEOUT;
+    let set_read_timeout = || {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "request timed out"));
+        }
+        stream.set_read_timeout(Some(remaining))
+    };
+    let mut reader = BufReader::new((&stream).take(MAX_REQUEST_BYTES));
+    let mut request_line = String::new();
+    set_read_timeout()?;
+    reader.read_line(&mut request_line)?;
+    // The headers are not needed, but must be read before responding
+    loop {
+        let mut header = String::new();
+        set_read_timeout()?;
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+    }
+    drop(reader);
+
+    let mut request = request_line.split_whitespace();
+    let method = request.next();
+    let path = request.next().and_then(|target| target.split('?').next());
+    let (status, body) = match (method, path) {
+        (Some("GET"), Some(METRICS_PATH)) => ("200 OK", REGISTRY.lock().unwrap().render()),
+        _ => ("404 Not Found", String::new()),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: \
+         {}\r\nConnection: close\r\n\r\n{body}",
+        body.len(),
+    )?;
+    stream.flush()
+}
+
+fn metric_name(name: &str, field: &str) -> String {
+    let mut metric_name =
+        String::with_capacity(METRIC_NAME_PREFIX.len() + name.len() + field.len() + 1);
+    metric_name.push_str(METRIC_NAME_PREFIX);
+    metric_name.extend(sanitize(name));
+    metric_name.push('_');
+    metric_name.extend(sanitize(field));
+    metric_name
+}
+
+fn sanitize(name: &str) -> impl Iterator<Item = char> + '_ {
+    name.chars().map(|c| {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            c
+        } else {
+            '_'
+        }
+    })
+}
+
+fn render_labels(tags: &[(&'static str, String)]) -> String {
+    if tags.is_empty() {
+        return String::new();
+    }
+
+    let mut tags: Vec<_> = tags.iter().collect();
+    tags.sort_unstable();
+    let mut labels = String::from("{");
+    for (i, (name, value)) in tags.into_iter().enumerate() {
+        if i > 0 {
+            labels.push(',');
+        }
+        labels.extend(sanitize(name));
+        labels.push_str("=\"");
+        for c in value.chars() {
+            match c {
+                '\\' => labels.push_str("\\\\"),
+                '"' => labels.push_str("\\\""),
+                '\n' => labels.push_str("\\n"),
+                c => labels.push(c),
+            }
+        }
+        labels.push('"');
+    }
+    labels.push('}');
+    labels
+}
+
+/// Parses a field value formatted by `DataPoint`, returning None for string fields.
+fn parse_field_value(value: &str) -> Option<f64> {
+    if let Some(value) = value.strip_suffix('i') {
+        return value.parse::<i64>().ok().map(|value| value as f64);
+    }
+    match value {
+        "true" => Some(1.0),
+        "false" => Some(0.0),
+        value => value.parse().ok(),
+    }
+}
+
+fn render_value(value: f64) -> String {
+    if value.is_infinite() {
+        if value.is_sign_positive() {
+            "+Inf"
+        } else {
+            "-Inf"
+        }
+        .to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::time::SystemTime};
+
+    #[test]
+    fn test_parse_field_value() {
+        assert_eq!(parse_field_value("42i"), Some(42.0));
+        assert_eq!(parse_field_value("-7i"), Some(-7.0));
+        assert_eq!(parse_field_value("1.5"), Some(1.5));
+        assert_eq!(parse_field_value("true"), Some(1.0));
+        assert_eq!(parse_field_value("false"), Some(0.0));
+        assert_eq!(parse_field_value("\"hi\""), None);
+    }
+
+    #[test]
+    fn test_registry_render() {
+        let mut registry = PrometheusRegistry::default();
+        registry.record_point(
+            DataPoint::new("replay-slot-stats")
+                .add_tag("fork", "main")
+                .add_tag("client", "agave \"v2\"")
+                .add_field_i64("total-us", 10)
+                .add_field_f64("ratio", 0.5)
+                .add_field_bool("is_leader", true)
+                .add_field_str("note", "ignored"),
+        );
+        // the last submitted value of a gauge wins
+        registry.record_point(
+            DataPoint::new("replay-slot-stats")
+                .add_tag("fork", "main")
+                .add_tag("client", "agave \"v2\"")
+                .add_field_i64("total-us", 20),
+        );
+        let counter = CounterPoint {
+            name: "bank-forks_set_root",
+            count: 3,
+            timestamp: SystemTime::now(),
+        };
+        registry.record_counter(&counter);
+        registry.record_counter(&counter);
+
+        assert_eq!(
+            registry.render(),
+            "# TYPE solana_bank_forks_set_root_count counter\n\
+             solana_bank_forks_set_root_count 6\n\
+             # TYPE solana_replay_slot_stats_is_leader gauge\n\
+             solana_replay_slot_stats_is_leader{client=\"agave \\\"v2\\\"\",fork=\"main\"} 1\n\
+             # TYPE solana_replay_slot_stats_ratio gauge\n\
+             solana_replay_slot_stats_ratio{client=\"agave \\\"v2\\\"\",fork=\"main\"} 0.5\n\
+             # TYPE solana_replay_slot_stats_total_us gauge\n\
+             solana_replay_slot_stats_total_us{client=\"agave \\\"v2\\\"\",fork=\"main\"} 20\n"
+        );
+    }
+
+    #[test]
+    fn test_registry_type_conflict() {
+        let mut registry = PrometheusRegistry::default();
+        registry.record_counter(&CounterPoint {
+            name: "votes",
+            count: 1,
+            timestamp: SystemTime::now(),
+        });
+        // a gauge with the same name as an existing counter is dropped
+        registry.record_point(DataPoint::new("votes").add_field_i64("count", 100));
+        assert_eq!(
+            registry.render(),
+            "# TYPE solana_votes_count counter\nsolana_votes_count 1\n"
+        );
+    }
+
+    #[test]
+    fn test_registry_label_set_limit() {
+        let mut registry = PrometheusRegistry::default();
+        for i in 0..MAX_LABEL_SETS_PER_METRIC + 10 {
+            registry.record_point(
+                DataPoint::new("peers")
+                    .add_tag("peer", &i.to_string())
+                    .add_field_i64("packets", 1),
+            );
+        }
+        // a label set which is already recorded is still updated
+        registry.record_point(
+            DataPoint::new("peers")
+                .add_tag("peer", "0")
+                .add_field_i64("packets", 2),
+        );
+
+        let metric = &registry.metrics["solana_peers_packets"];
+        assert_eq!(metric.samples.len(), MAX_LABEL_SETS_PER_METRIC);
+        assert_eq!(metric.samples["{peer=\"0\"}"], 2.0);
+    }
+
+    fn scrape(address: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_exporter_scrape() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        serve(listener).unwrap();
+        record_point(DataPoint::new("test-exporter-scrape").add_field_i64("value", 7));
+
+        let response = scrape(address, "/metrics");
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let mut head = head.lines();
+        assert_eq!(head.next(), Some("HTTP/1.1 200 OK"));
+        let headers: Vec<_> = head.collect();
+        assert!(headers.contains(&"Content-Type: text/plain; version=0.0.4"));
+        assert!(headers.contains(&format!("Content-Length: {}", body.len()).as_str()));
+        assert!(body.contains(
+            "# TYPE solana_test_exporter_scrape_value gauge\n\
+             solana_test_exporter_scrape_value 7\n"
+        ));
+        // every line is either a TYPE comment or a sample
+        assert!(body.lines().all(|line| {
+            line.starts_with("# TYPE solana_")
+                || (line.starts_with("solana_") && line.rsplit_once(' ').is_some())
+        }));
+
+        let response = scrape(address, "/other");
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
@@ -0,0 +1,90 @@
+//! Notification channels and routing rules loaded from a YAML file
+//!
+//! ```yaml
+//! channels:
+//!   oncall:
+//!     type: pagerduty
+//!     integration_key: ...
+//!   chat:
+//!     type: slack
+//!     webhook: https://hooks.slack.com/services/...
+//!   ops:
+//!     type: webhook
+//!     url: https://example.com/alerts
+//!     headers:
+//!       Authorization: Bearer ...
+//!     template: '{"text": "{{message}}", "severity": "{{severity}}", "type": "{{type}}"}'
+//! routes:
+//!   - types: [trigger, resolve]
+//!     channels: [oncall, ops]
+//!   - types: [info]
+//!     channels: [chat]
+//! ```
+//!
+//! Every notification goes to every channel when there are no routes. Otherwise a notification
+//! goes to the channels of the routes listing its type, or of the routes without `types`.
+
+use {
+    serde_derive::Deserialize,
+    std::collections::{BTreeMap, HashMap},
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct NotifierConfig {
+    pub(crate) channels: BTreeMap<String, ChannelConfig>,
+    #[serde(default)]
+    pub(crate) routes: Vec<RouteConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+pub(crate) enum ChannelConfig {
+    Discord {
+        webhook: String,
+    },
+    Slack {
+        webhook: String,
+    },
+    PagerDuty {
+        integration_key: String,
+    },
+    Telegram {
+        bot_token: String,
+        chat_id: String,
+    },
+    Twilio {
+        account: String,
+        token: String,
+        to: String,
+        from: String,
+    },
+    Log {
+        level: String,
+    },
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+        /// JSON body, defaults to `DEFAULT_WEBHOOK_TEMPLATE`
+        template: Option<String>,
+    },
+}
+
+/// Name of a `NotificationType` in the routing rules
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum NotificationKind {
+    Trigger,
+    Resolve,
+    Info,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RouteConfig {
+    /// All notification types if empty
+    #[serde(default)]
+    pub(crate) types: Vec<NotificationKind>,
+    pub(crate) channels: Vec<String>,
+}
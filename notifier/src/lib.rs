@@ -23,14 +23,23 @@
 /// ```bash
 /// export TWILIO_CONFIG='ACCOUNT=<account>,TOKEN=<securityToken>,TO=<receivingNumber>,FROM=<sendingNumber>'
 /// ```
+///
+/// Alternatively, `Notifier::from_config_file` loads the channels, including generic webhooks
+/// with a templated body, and the routing of the notifications to them from a file
 use log::*;
 use {
-    reqwest::{blocking::Client, StatusCode},
+    crate::config::{ChannelConfig, NotificationKind, NotifierConfig},
+    reqwest::{blocking::Client, header::CONTENT_TYPE, StatusCode},
     serde_json::json,
     solana_sdk::hash::Hash,
-    std::{env, str::FromStr, thread::sleep, time::Duration},
+    std::{collections::HashMap, env, fs, path::Path, str::FromStr, thread::sleep, time::Duration},
 };
 
+mod config;
+
+/// Webhook body used when the channel has no template
+const DEFAULT_WEBHOOK_TEMPLATE: &str = r#"{"message": "{{message}}", "severity": "{{severity}}", "type": "{{type}}", "incident": "{{incident}}"}"#;
+
 struct TelegramWebHook {
     bot_token: String,
     chat_id: String,
@@ -84,6 +93,13 @@ fn get_twilio_config() -> Result<Option<TwilioWebHook>, String> {
     Ok(Some(config))
 }
 
+struct WebHook {
+    url: String,
+    headers: Vec<(String, String)>,
+    /// JSON body with `{{message}}`, `{{severity}}`, `{{type}}` and `{{incident}}` placeholders
+    template: String,
+}
+
 enum NotificationChannel {
     Discord(String),
     Slack(String),
@@ -91,17 +107,114 @@ enum NotificationChannel {
     Telegram(TelegramWebHook),
     Twilio(TwilioWebHook),
     Log(Level),
+    WebHook(WebHook),
 }
 
 #[derive(Clone)]
+#[non_exhaustive]
 pub enum NotificationType {
-    Trigger { incident: Hash },
-    Resolve { incident: Hash },
+    Trigger {
+        incident: Hash,
+    },
+    Resolve {
+        incident: Hash,
+    },
+    /// Informational message, not part of an incident
+    Info,
+}
+
+impl NotificationType {
+    fn kind(&self) -> NotificationKind {
+        match self {
+            Self::Trigger { .. } => NotificationKind::Trigger,
+            Self::Resolve { .. } => NotificationKind::Resolve,
+            Self::Info => NotificationKind::Info,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Trigger { .. } => "trigger",
+            Self::Resolve { .. } => "resolve",
+            Self::Info => "info",
+        }
+    }
+
+    fn severity(&self) -> &'static str {
+        match self {
+            Self::Trigger { .. } => "critical",
+            Self::Resolve { .. } | Self::Info => "info",
+        }
+    }
+
+    fn incident(&self) -> Option<&Hash> {
+        match self {
+            Self::Trigger { incident } | Self::Resolve { incident } => Some(incident),
+            Self::Info => None,
+        }
+    }
+}
+
+/// Substitutes the placeholders of a webhook `template`, escaping the values for JSON strings.
+/// Unknown placeholders are left as they are.
+fn render_webhook_template(
+    template: &str,
+    msg: &str,
+    notification_type: &NotificationType,
+) -> String {
+    let value = |placeholder: &str| match placeholder {
+        "message" => Some(msg.to_string()),
+        "severity" => Some(notification_type.severity().to_string()),
+        "type" => Some(notification_type.name().to_string()),
+        "incident" => Some(
+            notification_type
+                .incident()
+                .map(|incident| incident.to_string())
+                .unwrap_or_default(),
+        ),
+        _ => None,
+    };
+    let escape = |value: String| {
+        let quoted = serde_json::Value::String(value).to_string();
+        quoted[1..quoted.len() - 1].to_string()
+    };
+
+    let mut body = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        body.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        match placeholder
+            .find("}}")
+            .and_then(|end| value(&placeholder[2..end]).map(|value| (end, value)))
+        {
+            Some((end, value)) => {
+                body.push_str(&escape(value));
+                rest = &placeholder[end + 2..];
+            }
+            None => {
+                body.push_str("{{");
+                rest = &placeholder[2..];
+            }
+        }
+    }
+    body.push_str(rest);
+    body
+}
+
+/// Sends the notifications of the listed types to the listed channels
+struct Route {
+    /// All notification types if empty
+    types: Vec<NotificationKind>,
+    /// Indexes into `Notifier::notifiers`
+    channels: Vec<usize>,
 }
 
 pub struct Notifier {
     client: Client,
     notifiers: Vec<NotificationChannel>,
+    /// Every notification goes to every channel if empty
+    routes: Vec<Route>,
 }
 
 impl Default for Notifier {
@@ -155,15 +268,124 @@ impl Notifier {
         Notifier {
             client: Client::new(),
             notifiers,
+            routes: vec![],
+        }
+    }
+
+    /// Loads the notification channels and routes from the YAML file at `path`, see the
+    /// `config` module for its format. The environment variables are ignored.
+    pub fn from_config_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let config = fs::read_to_string(path)
+            .map_err(|err| format!("Unable to read {}: {err}", path.display()))?;
+        let config = serde_yaml::from_str(&config)
+            .map_err(|err| format!("Unable to parse {}: {err}", path.display()))?;
+        Self::from_config(config)
+    }
+
+    fn from_config(config: NotifierConfig) -> Result<Self, String> {
+        let mut channel_indexes = HashMap::new();
+        let mut notifiers = vec![];
+        for (name, channel) in config.channels {
+            let channel = match channel {
+                ChannelConfig::Discord { webhook } => NotificationChannel::Discord(webhook),
+                ChannelConfig::Slack { webhook } => NotificationChannel::Slack(webhook),
+                ChannelConfig::PagerDuty { integration_key } => {
+                    NotificationChannel::PagerDuty(integration_key)
+                }
+                ChannelConfig::Telegram { bot_token, chat_id } => {
+                    NotificationChannel::Telegram(TelegramWebHook { bot_token, chat_id })
+                }
+                ChannelConfig::Twilio {
+                    account,
+                    token,
+                    to,
+                    from,
+                } => NotificationChannel::Twilio(TwilioWebHook {
+                    account,
+                    token,
+                    to,
+                    from,
+                }),
+                ChannelConfig::Log { level } => {
+                    NotificationChannel::Log(Level::from_str(&level).map_err(|err| {
+                        format!("Invalid log level of channel {name}: {level}: {err}")
+                    })?)
+                }
+                ChannelConfig::Webhook {
+                    url,
+                    headers,
+                    template,
+                } => {
+                    let template = template.unwrap_or_else(|| DEFAULT_WEBHOOK_TEMPLATE.to_string());
+                    let sample_body = render_webhook_template(
+                        &template,
+                        "message",
+                        &NotificationType::Trigger {
+                            incident: Hash::default(),
+                        },
+                    );
+                    serde_json::from_str::<serde_json::Value>(&sample_body).map_err(|err| {
+                        format!("Template of channel {name} is not a JSON template: {err}")
+                    })?;
+                    NotificationChannel::WebHook(WebHook {
+                        url,
+                        headers: headers.into_iter().collect(),
+                        template,
+                    })
+                }
+            };
+            channel_indexes.insert(name, notifiers.len());
+            notifiers.push(channel);
         }
+
+        let routes = config
+            .routes
+            .into_iter()
+            .map(|route| {
+                let channels = route
+                    .channels
+                    .iter()
+                    .map(|name| {
+                        channel_indexes
+                            .get(name)
+                            .copied()
+                            .ok_or_else(|| format!("Unknown channel in routes: {name}"))
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok(Route {
+                    types: route.types,
+                    channels,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+
+        info!("{} notifiers", notifiers.len());
+        Ok(Notifier {
+            client: Client::new(),
+            notifiers,
+            routes,
+        })
     }
 
     pub fn is_empty(&self) -> bool {
         self.notifiers.is_empty()
     }
 
+    fn is_routed(&self, channel: usize, notification_type: &NotificationType) -> bool {
+        let kind = notification_type.kind();
+        self.routes.is_empty()
+            || self.routes.iter().any(|route| {
+                (route.types.is_empty() || route.types.contains(&kind))
+                    && route.channels.contains(&channel)
+            })
+    }
+
     pub fn send(&self, msg: &str, notification_type: &NotificationType) {
-        for notifier in &self.notifiers {
+        for (index, notifier) in self.notifiers.iter().enumerate() {
+            if !self.is_routed(index, notification_type) {
+                continue;
+            }
             match notifier {
                 NotificationChannel::Discord(webhook) => {
                     for line in msg.split('\n') {
@@ -202,10 +424,13 @@ impl Notifier {
                     let event_action = match notification_type {
                         NotificationType::Trigger { incident: _ } => String::from("trigger"),
                         NotificationType::Resolve { incident: _ } => String::from("resolve"),
+                        // PagerDuty events are about incidents only
+                        NotificationType::Info => continue,
                     };
                     let dedup_key = match notification_type {
                         NotificationType::Trigger { ref incident } => incident.clone().to_string(),
                         NotificationType::Resolve { ref incident } => incident.clone().to_string(),
+                        NotificationType::Info => continue,
                     };
 
                     let data = json!({"payload":{"summary":msg,"source":"agave-watchtower","severity":"critical"},"routing_key":routing_key,"event_action":event_action,"dedup_key":dedup_key});
@@ -242,7 +467,159 @@ impl Notifier {
                 NotificationChannel::Log(level) => {
                     log!(*level, "{}", msg)
                 }
+                NotificationChannel::WebHook(WebHook {
+                    url,
+                    headers,
+                    template,
+                }) => {
+                    let body = render_webhook_template(template, msg, notification_type);
+                    let mut request = self
+                        .client
+                        .post(url)
+                        .header(CONTENT_TYPE, "application/json");
+                    for (name, value) in headers {
+                        request = request.header(name, value);
+                    }
+                    if let Err(err) = request
+                        .body(body)
+                        .send()
+                        .and_then(|response| response.error_for_status())
+                    {
+                        warn!("Failed to send webhook notification: {:?}", err);
+                    }
+                }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        std::{
+            io::{BufRead, BufReader, Read, Write},
+            net::TcpListener,
+            sync::mpsc::{channel, Receiver},
+            thread,
+        },
+    };
+
+    /// Answers `count` HTTP requests, returning their paths and bodies
+    fn start_listener(count: usize) -> (String, Receiver<(String, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = channel();
+        thread::spawn(move || {
+            for stream in listener.incoming().take(count) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&stream);
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut content_length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = header.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                    .unwrap();
+                let path = request_line.split_whitespace().nth(1).unwrap().to_string();
+                sender
+                    .send((path, String::from_utf8(body).unwrap()))
+                    .unwrap();
+            }
+        });
+        (url, receiver)
+    }
+
+    #[test]
+    fn test_render_webhook_template() {
+        let incident = Hash::new_unique();
+        assert_eq!(
+            render_webhook_template(
+                DEFAULT_WEBHOOK_TEMPLATE,
+                "\"quoted\" {{type}}\n",
+                &NotificationType::Trigger { incident },
+            ),
+            format!(
+                r#"{{"message": "\"quoted\" {{{{type}}}}\n", "severity": "critical", "type": "trigger", "incident": "{incident}"}}"#
+            ),
+        );
+        assert_eq!(
+            render_webhook_template(
+                "{{unknown}} {{severity}} {{type",
+                "",
+                &NotificationType::Info
+            ),
+            "{{unknown}} info {{type",
+        );
+    }
+
+    #[test]
+    fn test_webhook_routing() {
+        let (url, receiver) = start_listener(3);
+        let config = serde_yaml::from_str(&format!(
+            r#"
+channels:
+  oncall:
+    type: webhook
+    url: {url}/oncall
+    template: '{{"summary": "{{{{message}}}}", "action": "{{{{type}}}}"}}'
+  chat:
+    type: webhook
+    url: {url}/chat
+routes:
+  - types: [trigger, resolve]
+    channels: [oncall]
+  - types: [info]
+    channels: [chat]
+"#
+        ))
+        .unwrap();
+        let notifier = Notifier::from_config(config).unwrap();
+
+        let incident = Hash::new_unique();
+        notifier.send("down", &NotificationType::Trigger { incident });
+        notifier.send("all clear", &NotificationType::Resolve { incident });
+        notifier.send("epoch 7", &NotificationType::Info);
+        let requests = receiver.iter().take(3).collect::<Vec<_>>();
+        assert_eq!(
+            requests,
+            vec![
+                (
+                    "/oncall".to_string(),
+                    r#"{"summary": "down", "action": "trigger"}"#.to_string()
+                ),
+                (
+                    "/oncall".to_string(),
+                    r#"{"summary": "all clear", "action": "resolve"}"#.to_string()
+                ),
+                (
+                    "/chat".to_string(),
+                    r#"{"message": "epoch 7", "severity": "info", "type": "info", "incident": ""}"#
+                        .to_string()
+                ),
+            ]
+        );
+
+        let config = serde_yaml::from_str(
+            "channels:\n  chat:\n    type: webhook\n    url: http://localhost\n    template: '{{message}}'\n",
+        )
+        .unwrap();
+        assert!(Notifier::from_config(config).is_err());
+        let config =
+            serde_yaml::from_str("channels: {}\nroutes:\n  - channels: [missing]\n").unwrap();
+        assert!(Notifier::from_config(config).is_err());
+    }
+}
@@ -0,0 +1,260 @@
+//! Per-instruction execution tracing
+//!
+//! When an [`ExecutionTracer`] is attached to an `InvokeContext`, every top-level and
+//! cross-program invoked instruction is recorded in the order it was processed, along with the
+//! compute units it consumed and the changes it made to the lamports and data of its writable
+//! accounts.
+
+use {
+    solana_sdk::{
+        account::ReadableAccount,
+        instruction::InstructionError,
+        pubkey::Pubkey,
+        transaction_context::{IndexOfAccount, TransactionContext},
+    },
+    std::{cell::RefCell, ops::Range, rc::Rc},
+};
+
+/// Upper bound of the account data an `ExecutionTracer` holds at once, counting both the
+/// snapshots of the accounts of the instructions being processed and the changed bytes recorded
+pub const MAX_EXECUTION_TRACE_DATA_BYTES: usize = 1024 * 1024;
+
+/// The instructions processed during a transaction, in processing order
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExecutionTrace {
+    pub instructions: Vec<InstructionTrace>,
+    /// Set if changes to account data were left out to stay within the tracer's byte limit
+    pub truncated: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InstructionTrace {
+    pub program_id: Pubkey,
+    /// Top level instructions are at height
+    /// `solana_sdk::instruction::TRANSACTION_LEVEL_STACK_HEIGHT`
+    pub stack_height: usize,
+    /// Includes the compute units consumed by the instructions this one invoked
+    pub compute_units_consumed: u64,
+    /// The instruction's writable accounts whose lamports or data changed while it was processed
+    pub accounts: Vec<AccountTrace>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountTrace {
+    pub pubkey: Pubkey,
+    pub pre_lamports: u64,
+    pub post_lamports: u64,
+    pub pre_data_len: usize,
+    pub post_data_len: usize,
+    /// The byte ranges of the account's data which changed, in ascending order
+    pub data_changes: Vec<AccountDataChange>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountDataChange {
+    pub offset: usize,
+    /// The new contents of the changed bytes
+    pub data: Vec<u8>,
+}
+
+/// The state of an instruction being processed, which is needed to trace it once it finishes
+#[derive(Debug)]
+struct PendingInstruction {
+    trace_index: usize,
+    /// The writable accounts, with a snapshot of their data unless it would have exceeded the
+    /// byte limit
+    accounts: Vec<(IndexOfAccount, Option<Vec<u8>>)>,
+}
+
+#[derive(Debug)]
+pub struct ExecutionTracer {
+    trace: ExecutionTrace,
+    /// Instructions which started but haven't finished yet, innermost last
+    pending_instructions: Vec<PendingInstruction>,
+    max_data_bytes: usize,
+    data_bytes: usize,
+}
+
+impl ExecutionTracer {
+    pub fn new_ref(max_data_bytes: usize) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Self {
+            trace: ExecutionTrace::default(),
+            pending_instructions: Vec::new(),
+            max_data_bytes,
+            data_bytes: 0,
+        }))
+    }
+
+    /// Records the program of the current instruction and the pre-execution state of its
+    /// writable accounts.  If this succeeds, `finish_instruction` must be called once the
+    /// instruction is processed.
+    pub fn start_instruction(
+        &mut self,
+        transaction_context: &TransactionContext,
+    ) -> Result<(), InstructionError> {
+        let instruction_context = transaction_context.get_current_instruction_context()?;
+        let program_id = *instruction_context.get_last_program_key(transaction_context)?;
+        let mut account_traces = Vec::new();
+        let mut accounts = Vec::new();
+        let mut data_bytes = self.data_bytes;
+        for index_in_transaction in writable_account_indices(transaction_context)? {
+            let account = transaction_context
+                .get_account_at_index(index_in_transaction)?
+                .try_borrow()
+                .map_err(|_| InstructionError::AccountBorrowFailed)?;
+            let pre_data = account.data();
+            let snapshot = (data_bytes.saturating_add(pre_data.len()) <= self.max_data_bytes)
+                .then(|| pre_data.to_vec());
+            if snapshot.is_some() {
+                data_bytes = data_bytes.saturating_add(pre_data.len());
+            }
+            account_traces.push(AccountTrace {
+                pubkey: *transaction_context.get_key_of_account_at_index(index_in_transaction)?,
+                pre_lamports: account.lamports(),
+                pre_data_len: pre_data.len(),
+                ..AccountTrace::default()
+            });
+            accounts.push((index_in_transaction, snapshot));
+        }
+        self.data_bytes = data_bytes;
+
+        self.pending_instructions.push(PendingInstruction {
+            trace_index: self.trace.instructions.len(),
+            accounts,
+        });
+        self.trace.instructions.push(InstructionTrace {
+            program_id,
+            stack_height: transaction_context.get_instruction_context_stack_height(),
+            compute_units_consumed: 0,
+            accounts: account_traces,
+        });
+        Ok(())
+    }
+
+    /// Records the compute units consumed by the current instruction and the post-execution
+    /// state of its writable accounts, keeping only the accounts which changed
+    pub fn finish_instruction(
+        &mut self,
+        transaction_context: &TransactionContext,
+        compute_units_consumed: u64,
+    ) -> Result<(), InstructionError> {
+        let Some(PendingInstruction {
+            trace_index,
+            accounts,
+        }) = self.pending_instructions.pop()
+        else {
+            debug_assert!(false, "finish_instruction called without start_instruction");
+            return Ok(());
+        };
+        // Release the snapshots first, so they don't count against the changes recorded
+        for (_, snapshot) in &accounts {
+            self.data_bytes = self
+                .data_bytes
+                .saturating_sub(snapshot.as_ref().map_or(0, Vec::len));
+        }
+
+        let Some(instruction) = self.trace.instructions.get_mut(trace_index) else {
+            debug_assert!(false, "pending instruction {trace_index} isn't traced");
+            return Ok(());
+        };
+        instruction.compute_units_consumed = compute_units_consumed;
+        let mut changed = Vec::with_capacity(accounts.len());
+        for (account_trace, (index_in_transaction, snapshot)) in
+            instruction.accounts.iter_mut().zip(accounts)
+        {
+            let account = transaction_context
+                .get_account_at_index(index_in_transaction)?
+                .try_borrow()
+                .map_err(|_| InstructionError::AccountBorrowFailed)?;
+            account_trace.post_lamports = account.lamports();
+            account_trace.post_data_len = account.data().len();
+            let mut data_changed = account_trace.pre_data_len != account_trace.post_data_len;
+            match snapshot {
+                // Without a snapshot, whether the data changed is unknown
+                None => self.trace.truncated = true,
+                Some(pre_data) => {
+                    let changed_ranges = changed_ranges(&pre_data, account.data());
+                    let changed_bytes = changed_ranges.iter().map(Range::len).sum::<usize>();
+                    data_changed |= changed_bytes > 0;
+                    if self.data_bytes.saturating_add(changed_bytes) > self.max_data_bytes {
+                        self.trace.truncated = true;
+                    } else {
+                        self.data_bytes = self.data_bytes.saturating_add(changed_bytes);
+                        account_trace.data_changes = changed_ranges
+                            .into_iter()
+                            .filter_map(|range| {
+                                Some(AccountDataChange {
+                                    offset: range.start,
+                                    data: account.data().get(range)?.to_vec(),
+                                })
+                            })
+                            .collect();
+                    }
+                }
+            }
+            changed.push(account_trace.pre_lamports != account_trace.post_lamports || data_changed);
+        }
+        let mut changed = changed.into_iter();
+        instruction
+            .accounts
+            .retain(|_| changed.next().unwrap_or(false));
+        Ok(())
+    }
+
+    pub fn into_trace(self) -> ExecutionTrace {
+        self.trace
+    }
+}
+
+/// Returns the transaction wide indices of the current instruction's writable accounts, without
+/// duplicates
+fn writable_account_indices(
+    transaction_context: &TransactionContext,
+) -> Result<Vec<IndexOfAccount>, InstructionError> {
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    let mut indices = Vec::new();
+    for instruction_account_index in 0..instruction_context.get_number_of_instruction_accounts() {
+        if !instruction_context.is_instruction_account_writable(instruction_account_index)? {
+            continue;
+        }
+        let index_in_transaction = instruction_context
+            .get_index_of_instruction_account_in_transaction(instruction_account_index)?;
+        if !indices.contains(&index_in_transaction) {
+            indices.push(index_in_transaction);
+        }
+    }
+    Ok(indices)
+}
+
+/// Returns the ranges of `post_data` which differ from `pre_data`, including any bytes appended
+fn changed_ranges(pre_data: &[u8], post_data: &[u8]) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for (offset, post_byte) in post_data.iter().enumerate() {
+        if pre_data.get(offset) == Some(post_byte) {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some(range) if range.end == offset => range.end = offset.saturating_add(1),
+            _ => ranges.push(offset..offset.saturating_add(1)),
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_ranges() {
+        assert!(changed_ranges(&[1, 2, 3], &[1, 2, 3]).is_empty());
+        assert!(changed_ranges(&[1, 2, 3], &[1, 2]).is_empty());
+        assert!(changed_ranges(&[], &[]).is_empty());
+        assert_eq!(changed_ranges(&[1, 2, 3], &[1, 9, 3]), vec![1..2]);
+        assert_eq!(
+            changed_ranges(&[1, 2, 3, 4, 5], &[9, 9, 3, 9, 5, 6, 7]),
+            vec![0..2, 3..4, 5..7]
+        );
+        assert_eq!(changed_ranges(&[], &[0, 0]), vec![0..2]);
+    }
+}
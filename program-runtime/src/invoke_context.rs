@@ -1,12 +1,15 @@
 use {
     crate::{
+        execution_trace::ExecutionTracer,
         loaded_programs::{
             ProgramCacheEntry, ProgramCacheEntryType, ProgramCacheForTxBatch,
             ProgramRuntimeEnvironments,
         },
+        program_coverage::SharedProgramCoverage,
         stable_log,
         sysvar_cache::SysvarCache,
     },
+    log::warn,
     solana_compute_budget::compute_budget::ComputeBudget,
     solana_log_collector::{ic_msg, LogCollector},
     solana_measure::measure::Measure,
@@ -201,6 +204,8 @@ pub struct InvokeContext<'a> {
     /// the designated compute budget during program execution.
     compute_meter: RefCell<u64>,
     log_collector: Option<Rc<RefCell<LogCollector>>>,
+    execution_tracer: Option<Rc<RefCell<ExecutionTracer>>>,
+    program_coverage: Option<SharedProgramCoverage>,
     /// Latest measurement not yet accumulated in [ExecuteDetailsTimings::execute_us]
     pub execute_time: Option<Measure>,
     pub timings: ExecuteDetailsTimings,
@@ -222,6 +227,8 @@ impl<'a> InvokeContext<'a> {
             program_cache_for_tx_batch,
             environment_config,
             log_collector,
+            execution_tracer: None,
+            program_coverage: None,
             compute_budget,
             compute_meter: RefCell::new(compute_budget.compute_unit_limit),
             execute_time: None,
@@ -291,6 +298,20 @@ impl<'a> InvokeContext<'a> {
     /// Pop a stack frame from the invocation stack
     pub fn pop(&mut self) -> Result<(), InstructionError> {
         if let Some(Some(syscall_context)) = self.syscall_context.pop() {
+            if let Some(program_coverage) = &self.program_coverage {
+                if let Ok(program_id) = self
+                    .transaction_context
+                    .get_current_instruction_context()
+                    .and_then(|instruction_context| {
+                        instruction_context.get_last_program_key(self.transaction_context)
+                    })
+                {
+                    program_coverage
+                        .lock()
+                        .unwrap()
+                        .record(program_id, &syscall_context.trace_log);
+                }
+            }
             self.traces.push(syscall_context.trace_log);
         }
         self.transaction_context.pop()
@@ -459,10 +480,26 @@ impl<'a> InvokeContext<'a> {
             .get_next_instruction_context()?
             .configure(program_indices, instruction_accounts, instruction_data);
         self.push()?;
-        self.process_executable_chain(compute_units_consumed, timings)
-            // MUST pop if and only if `push` succeeded, independent of `result`.
-            // Thus, the `.and()` instead of an `.and_then()`.
-            .and(self.pop())
+        // Tracing failures are only logged, as the trace doesn't affect execution
+        let execution_tracer = self.execution_tracer.clone().filter(|execution_tracer| {
+            execution_tracer
+                .borrow_mut()
+                .start_instruction(self.transaction_context)
+                .map_err(|err| warn!("Failed to start tracing instruction: {err}"))
+                .is_ok()
+        });
+        let result = self.process_executable_chain(compute_units_consumed, timings);
+        if let Some(execution_tracer) = execution_tracer {
+            if let Err(err) = execution_tracer
+                .borrow_mut()
+                .finish_instruction(self.transaction_context, *compute_units_consumed)
+            {
+                warn!("Failed to finish tracing instruction: {err}");
+            }
+        }
+        // MUST pop if and only if `push` succeeded, independent of `result`.
+        // Thus, the `.and()` instead of an `.and_then()`.
+        result.and(self.pop())
     }
 
     /// Calls the instruction's program entrypoint method
@@ -570,6 +607,16 @@ impl<'a> InvokeContext<'a> {
         self.log_collector.clone()
     }
 
+    /// Records every instruction processed from now on into `execution_tracer`
+    pub fn set_execution_tracer(&mut self, execution_tracer: Option<Rc<RefCell<ExecutionTracer>>>) {
+        self.execution_tracer = execution_tracer;
+    }
+
+    /// Counts the instructions executed by SBF programs from now on into `program_coverage`
+    pub fn set_program_coverage(&mut self, program_coverage: Option<SharedProgramCoverage>) {
+        self.program_coverage = program_coverage;
+    }
+
     /// Consume compute units
     pub fn consume_checked(&self, amount: u64) -> Result<(), Box<dyn std::error::Error>> {
         let mut compute_meter = self.compute_meter.borrow_mut();
@@ -814,6 +861,10 @@ pub fn mock_process_instruction<F: FnMut(&mut InvokeContext), G: FnMut(&mut Invo
 mod tests {
     use {
         super::*,
+        crate::execution_trace::{
+            AccountDataChange, AccountTrace, ExecutionTrace, InstructionTrace,
+            MAX_EXECUTION_TRACE_DATA_BYTES,
+        },
         serde::{Deserialize, Serialize},
         solana_compute_budget::compute_budget_limits,
         solana_sdk::{account::WritableAccount, instruction::Instruction, rent::Rent},
@@ -1274,4 +1325,134 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_process_instruction_execution_trace() {
+        let program_key = Pubkey::new_unique();
+        let user_account = AccountSharedData::new(100, 2, &program_key);
+        // Only writable accounts are snapshotted, so this doesn't count against the byte limit
+        let readonly_account = AccountSharedData::new(10, 10, &program_key);
+        let mut program_account = AccountSharedData::new(500, 500, &native_loader::id());
+        program_account.set_executable(true);
+        let user_key = Pubkey::new_unique();
+        let transaction_accounts = vec![
+            (user_key, user_account),
+            (Pubkey::new_unique(), readonly_account),
+            (program_key, program_account),
+        ];
+        let instruction_accounts = [
+            InstructionAccount {
+                index_in_transaction: 0,
+                index_in_caller: 0,
+                index_in_callee: 0,
+                is_signer: false,
+                is_writable: true,
+            },
+            InstructionAccount {
+                index_in_transaction: 1,
+                index_in_caller: 1,
+                index_in_callee: 1,
+                is_signer: false,
+                is_writable: false,
+            },
+        ];
+        let instructions = [
+            MockInstruction::ConsumeComputeUnits {
+                compute_units_to_consume: 10,
+                desired_result: Ok(()),
+            },
+            MockInstruction::Resize { new_len: 3 },
+            MockInstruction::ModifyOwned,
+        ];
+
+        // With a limit of 2 bytes, the 3 bytes of the user account can't be snapshotted before
+        // the last instruction, on top of the byte recorded for the resize
+        for (max_data_bytes, last_data_changes, truncated) in [
+            (
+                MAX_EXECUTION_TRACE_DATA_BYTES,
+                vec![AccountDataChange {
+                    offset: 0,
+                    data: vec![1],
+                }],
+                false,
+            ),
+            (2, vec![], true),
+        ] {
+            with_mock_invoke_context!(
+                invoke_context,
+                transaction_context,
+                transaction_accounts.clone()
+            );
+            let mut program_cache_for_tx_batch = ProgramCacheForTxBatch::default();
+            program_cache_for_tx_batch.replenish(
+                program_key,
+                Arc::new(ProgramCacheEntry::new_builtin(0, 0, MockBuiltin::vm)),
+            );
+            invoke_context.program_cache_for_tx_batch = &mut program_cache_for_tx_batch;
+            let execution_tracer = ExecutionTracer::new_ref(max_data_bytes);
+            invoke_context.set_execution_tracer(Some(execution_tracer.clone()));
+
+            for instruction in &instructions {
+                invoke_context
+                    .process_instruction(
+                        &bincode::serialize(instruction).unwrap(),
+                        &instruction_accounts,
+                        &[2],
+                        &mut 0,
+                        &mut ExecuteTimings::default(),
+                    )
+                    .unwrap();
+            }
+            drop(invoke_context);
+
+            let execution_trace = Rc::try_unwrap(execution_tracer)
+                .unwrap()
+                .into_inner()
+                .into_trace();
+            assert_eq!(
+                execution_trace,
+                ExecutionTrace {
+                    instructions: vec![
+                        InstructionTrace {
+                            program_id: program_key,
+                            stack_height: 1,
+                            compute_units_consumed: 10u64
+                                .saturating_add(MOCK_BUILTIN_COMPUTE_UNIT_COST),
+                            accounts: vec![],
+                        },
+                        InstructionTrace {
+                            program_id: program_key,
+                            stack_height: 1,
+                            compute_units_consumed: MOCK_BUILTIN_COMPUTE_UNIT_COST,
+                            accounts: vec![AccountTrace {
+                                pubkey: user_key,
+                                pre_lamports: 100,
+                                post_lamports: 100,
+                                pre_data_len: 2,
+                                post_data_len: 3,
+                                data_changes: vec![AccountDataChange {
+                                    offset: 2,
+                                    data: vec![0],
+                                }],
+                            }],
+                        },
+                        InstructionTrace {
+                            program_id: program_key,
+                            stack_height: 1,
+                            compute_units_consumed: MOCK_BUILTIN_COMPUTE_UNIT_COST,
+                            accounts: vec![AccountTrace {
+                                pubkey: user_key,
+                                pre_lamports: 100,
+                                post_lamports: 100,
+                                pre_data_len: 3,
+                                post_data_len: 1,
+                                data_changes: last_data_changes,
+                            }],
+                        },
+                    ],
+                    truncated,
+                }
+            );
+        }
+    }
 }
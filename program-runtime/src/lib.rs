@@ -6,8 +6,10 @@
 extern crate solana_metrics;
 
 pub use solana_rbpf;
+pub mod execution_trace;
 pub mod invoke_context;
 pub mod loaded_programs;
 pub mod mem_pool;
+pub mod program_coverage;
 pub mod stable_log;
 pub mod sysvar_cache;
@@ -0,0 +1,108 @@
+//! Instruction coverage of SBF programs
+//!
+//! When a [`ProgramCoverage`] is attached to an `InvokeContext`, the instruction trace of every
+//! SBF program invocation is folded into per program execution counts. The program runtime
+//! environment has to be created with `debugging_features` for the VM to record the traces.
+
+use {
+    solana_sdk::pubkey::Pubkey,
+    std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    },
+};
+
+/// Position of the program counter in a trace log entry, after the registers r0 to r10
+const PC_REGISTER: usize = 11;
+
+pub type SharedProgramCoverage = Arc<Mutex<ProgramCoverage>>;
+
+/// Execution counts of the instructions of a program, by index in its text section
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct InstructionCounts {
+    /// How often each instruction was executed
+    pub executed: HashMap<u64, u64>,
+    /// How often execution continued from an instruction (first) to another one than the one
+    /// following it (second), for example because a branch was taken
+    pub jumps: HashMap<(u64, u64), u64>,
+}
+
+#[derive(Debug, Default)]
+pub struct ProgramCoverage {
+    programs: HashMap<Pubkey, InstructionCounts>,
+}
+
+impl ProgramCoverage {
+    pub fn new_shared() -> SharedProgramCoverage {
+        Arc::new(Mutex::new(Self::default()))
+    }
+
+    /// Adds the instructions executed in one invocation of `program_id`
+    pub fn record(&mut self, program_id: &Pubkey, trace_log: &[[u64; 12]]) {
+        if trace_log.is_empty() {
+            return;
+        }
+        let counts = self.programs.entry(*program_id).or_default();
+        let mut previous_pc = None;
+        for pc in trace_log.iter().filter_map(|state| state.get(PC_REGISTER)) {
+            let executed = counts.executed.entry(*pc).or_default();
+            *executed = executed.saturating_add(1);
+            if let Some(previous_pc) = previous_pc {
+                if previous_pc != pc.saturating_sub(1) {
+                    let jumps = counts.jumps.entry((previous_pc, *pc)).or_default();
+                    *jumps = jumps.saturating_add(1);
+                }
+            }
+            previous_pc = Some(*pc);
+        }
+    }
+
+    pub fn get(&self, program_id: &Pubkey) -> Option<&InstructionCounts> {
+        self.programs.get(program_id)
+    }
+
+    pub fn program_ids(&self) -> impl Iterator<Item = &Pubkey> {
+        self.programs.keys()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trace_log(pcs: &[u64]) -> Vec<[u64; 12]> {
+        pcs.iter()
+            .map(|pc| {
+                let mut state = [0; 12];
+                if let Some(register) = state.get_mut(PC_REGISTER) {
+                    *register = *pc;
+                }
+                state
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_record() {
+        let program_id = Pubkey::new_unique();
+        let mut program_coverage = ProgramCoverage::default();
+        program_coverage.record(&program_id, &[]);
+        assert!(program_coverage.get(&program_id).is_none());
+
+        // 0: jeq r1, 0, +2 (taken), 3: exit
+        program_coverage.record(&program_id, &trace_log(&[0, 3]));
+        // 0: jeq r1, 0, +2 (not taken), 1: mov, 2: ja -2 to 1, 1: mov, 2: ja +0, 3: exit
+        program_coverage.record(&program_id, &trace_log(&[0, 1, 2, 1, 2, 3]));
+
+        let counts = program_coverage.get(&program_id).unwrap();
+        assert_eq!(
+            counts.executed,
+            HashMap::from([(0, 2), (1, 2), (2, 2), (3, 2)])
+        );
+        assert_eq!(counts.jumps, HashMap::from([((0, 3), 1), ((2, 1), 1)]));
+        assert_eq!(
+            program_coverage.program_ids().collect::<Vec<_>>(),
+            vec![&program_id]
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! Line and branch coverage of the SBF programs run by a test, in the lcov tracefile format
+//!
+//! The executed instructions are mapped to source lines through the DWARF info of the program.
+//! `cargo build-sbf --debug` keeps it in a `<program_name>.debug` file next to the stripped
+//! shared object, which is looked up first. Instructions without line info are reported against
+//! the shared object itself, with the instruction index plus one as the line number.
+
+use {
+    addr2line::{
+        object::{self, Object, ObjectSection},
+        Context,
+    },
+    log::*,
+    solana_program_runtime::program_coverage::{
+        InstructionCounts, ProgramCoverage, SharedProgramCoverage,
+    },
+    solana_sdk::pubkey::Pubkey,
+    std::{
+        collections::BTreeMap,
+        fmt::Write as _,
+        fs, io,
+        path::{Path, PathBuf},
+        sync::atomic::{AtomicUsize, Ordering},
+    },
+};
+
+const INSN_SIZE: usize = 8;
+const BPF_CLASS_MASK: u8 = 0x07;
+const BPF_OP_MASK: u8 = 0xf0;
+const BPF_JMP: u8 = 0x05;
+const BPF_JMP32: u8 = 0x06;
+const BPF_JA: u8 = 0x00;
+const BPF_CALL: u8 = 0x80;
+const BPF_EXIT: u8 = 0x90;
+
+/// Distinguishes the reports of the tests run by one process
+static REPORT_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Writes the coverage of the SBF programs of a test to `output_dir` when dropped, one
+/// `<program_name>-<pid>-<n>.lcov` file per executed program
+pub(crate) struct CoverageReport {
+    output_dir: PathBuf,
+    programs: Vec<(Pubkey, &'static str, PathBuf)>,
+    program_coverage: SharedProgramCoverage,
+}
+
+impl CoverageReport {
+    pub(crate) fn new(output_dir: PathBuf, programs: Vec<(Pubkey, &'static str, PathBuf)>) -> Self {
+        Self {
+            output_dir,
+            programs,
+            program_coverage: ProgramCoverage::new_shared(),
+        }
+    }
+
+    pub(crate) fn program_coverage(&self) -> &SharedProgramCoverage {
+        &self.program_coverage
+    }
+
+    fn write(&self) -> io::Result<()> {
+        let program_coverage = self.program_coverage.lock().unwrap();
+        let report_id = REPORT_COUNT.fetch_add(1, Ordering::Relaxed);
+        fs::create_dir_all(&self.output_dir)?;
+        for (program_id, program_name, program_file) in &self.programs {
+            let Some(counts) = program_coverage.get(program_id) else {
+                continue;
+            };
+            let tracefile = program_tracefile(program_name, program_file, counts)?;
+            let path = self.output_dir.join(format!(
+                "{program_name}-{}-{report_id}.lcov",
+                std::process::id()
+            ));
+            fs::write(&path, tracefile)?;
+            info!(
+                "\"{}\" coverage written to {}",
+                program_name,
+                path.display()
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Drop for CoverageReport {
+    fn drop(&mut self) {
+        if let Err(err) = self.write() {
+            warn!(
+                "Failed to write coverage to {}: {}",
+                self.output_dir.display(),
+                err
+            );
+        }
+    }
+}
+
+/// Branch target of the conditional jump `insn` at `pc`
+fn conditional_jump_target(pc: u64, insn: &[u8]) -> Option<u64> {
+    let opcode = *insn.first()?;
+    let class = opcode & BPF_CLASS_MASK;
+    if class != BPF_JMP && class != BPF_JMP32 {
+        return None;
+    }
+    if matches!(opcode & BPF_OP_MASK, BPF_JA | BPF_CALL | BPF_EXIT) {
+        return None;
+    }
+    let offset = i16::from_le_bytes([*insn.get(2)?, *insn.get(3)?]);
+    pc.saturating_add(1).checked_add_signed(offset.into())
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct SourceFileCoverage {
+    /// Execution count of each line, the highest of its instructions
+    lines: BTreeMap<u32, u64>,
+    /// Line, pc, and the taken and not taken counts of the conditional jumps, if executed
+    branches: Vec<(u32, u64, Option<(u64, u64)>)>,
+}
+
+fn to_io_error(err: impl std::error::Error + Send + Sync + 'static) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+/// Maps the instruction `counts` of the program in `program_file` to its source files
+fn source_file_coverage(
+    program_file: &Path,
+    counts: &InstructionCounts,
+) -> io::Result<BTreeMap<String, SourceFileCoverage>> {
+    let elf = fs::read(program_file)?;
+    let elf = object::File::parse(elf.as_slice()).map_err(to_io_error)?;
+    let text = elf
+        .section_by_name(".text")
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no .text section"))?;
+    let text_address = text.address();
+    let text_bytes = text.data().map_err(to_io_error)?;
+
+    let debug_file = program_file.with_extension("debug");
+    let debug_data = debug_file
+        .exists()
+        .then(|| fs::read(&debug_file))
+        .transpose()?;
+    let context = match &debug_data {
+        Some(debug_data) => {
+            let debug_elf = object::File::parse(debug_data.as_slice()).map_err(to_io_error)?;
+            Context::new(&debug_elf)
+        }
+        None => Context::new(&elf),
+    }
+    .map_err(to_io_error)?;
+
+    let mut source_files = BTreeMap::<String, SourceFileCoverage>::new();
+    for (pc, insn) in (0u64..).zip(text_bytes.chunks_exact(INSN_SIZE)) {
+        let address = text_address.saturating_add(pc.saturating_mul(INSN_SIZE as u64));
+        let location = context.find_location(address).map_err(to_io_error)?;
+        let (file, line) = match location.and_then(|location| location.file.zip(location.line)) {
+            Some((file, line)) => (file.to_string(), line),
+            None => (
+                program_file.display().to_string(),
+                u32::try_from(pc.saturating_add(1)).unwrap_or(u32::MAX),
+            ),
+        };
+        let source_file = source_files.entry(file).or_default();
+
+        let executed = counts.executed.get(&pc).copied().unwrap_or_default();
+        let hits = source_file.lines.entry(line).or_default();
+        *hits = (*hits).max(executed);
+
+        if let Some(target) = conditional_jump_target(pc, insn) {
+            let taken_and_not_taken = (executed > 0).then(|| {
+                let taken = counts.jumps.get(&(pc, target)).copied().unwrap_or_default();
+                (taken, executed.saturating_sub(taken))
+            });
+            source_file.branches.push((line, pc, taken_and_not_taken));
+        }
+    }
+    Ok(source_files)
+}
+
+fn write_source_file_record(
+    tracefile: &mut String,
+    source_file: &str,
+    coverage: &SourceFileCoverage,
+) {
+    let _ = writeln!(tracefile, "SF:{source_file}");
+    for (line, pc, taken_and_not_taken) in &coverage.branches {
+        match taken_and_not_taken {
+            Some((taken, not_taken)) => {
+                let _ = writeln!(tracefile, "BRDA:{line},{pc},0,{taken}");
+                let _ = writeln!(tracefile, "BRDA:{line},{pc},1,{not_taken}");
+            }
+            None => {
+                let _ = writeln!(tracefile, "BRDA:{line},{pc},0,-");
+                let _ = writeln!(tracefile, "BRDA:{line},{pc},1,-");
+            }
+        }
+    }
+    let branches_hit = coverage
+        .branches
+        .iter()
+        .filter_map(|(_, _, taken_and_not_taken)| *taken_and_not_taken)
+        .map(|(taken, not_taken)| usize::from(taken > 0) + usize::from(not_taken > 0))
+        .sum::<usize>();
+    let _ = writeln!(tracefile, "BRF:{}", coverage.branches.len() * 2);
+    let _ = writeln!(tracefile, "BRH:{branches_hit}");
+    for (line, hits) in &coverage.lines {
+        let _ = writeln!(tracefile, "DA:{line},{hits}");
+    }
+    let _ = writeln!(tracefile, "LF:{}", coverage.lines.len());
+    let _ = writeln!(
+        tracefile,
+        "LH:{}",
+        coverage.lines.values().filter(|hits| **hits > 0).count()
+    );
+    let _ = writeln!(tracefile, "end_of_record");
+}
+
+/// Generates the lcov tracefile of `program_name`
+fn program_tracefile(
+    program_name: &str,
+    program_file: &Path,
+    counts: &InstructionCounts,
+) -> io::Result<String> {
+    let mut tracefile = format!("TN:{program_name}\n");
+    for (source_file, coverage) in source_file_coverage(program_file, counts)? {
+        write_source_file_record(&mut tracefile, &source_file, &coverage);
+    }
+    Ok(tracefile)
+}
@@ -4,6 +4,7 @@
 // Export tokio for test clients
 pub use tokio;
 use {
+    crate::coverage::CoverageReport,
     async_trait::async_trait,
     base64::{prelude::BASE64_STANDARD, Engine},
     chrono_humanize::{Accuracy, HumanTime, Tense},
@@ -78,6 +79,7 @@ pub use {
     solana_sdk::transaction_context::IndexOfAccount,
 };
 
+mod coverage;
 pub mod programs;
 
 /// Errors from the program test environment
@@ -472,6 +474,8 @@ pub struct ProgramTest {
     prefer_bpf: bool,
     deactivate_feature_set: HashSet<Pubkey>,
     transaction_account_lock_limit: Option<usize>,
+    coverage_dir: Option<PathBuf>,
+    sbf_programs: Vec<(Pubkey, &'static str, PathBuf)>,
 }
 
 impl Default for ProgramTest {
@@ -487,6 +491,10 @@ impl Default for ProgramTest {
     /// * the `tests/fixtures` sub-directory
     /// * the current working directory
     ///
+    /// If the `SBF_COVERAGE_DIR` environment variable is defined, coverage of the SBF programs
+    /// is written to that directory, see `ProgramTest::enable_coverage()`. `cargo test-sbf
+    /// --coverage-dir` will set it.
+    ///
     fn default() -> Self {
         solana_logger::setup_with_default(
             "solana_rbpf::vm=debug,\
@@ -505,6 +513,8 @@ impl Default for ProgramTest {
             prefer_bpf,
             deactivate_feature_set: HashSet::default(),
             transaction_account_lock_limit: None,
+            coverage_dir: std::env::var_os("SBF_COVERAGE_DIR").map(PathBuf::from),
+            sbf_programs: vec![],
         }
     }
 }
@@ -546,6 +556,16 @@ impl ProgramTest {
         self.transaction_account_lock_limit = Some(transaction_account_lock_limit);
     }
 
+    /// Record the instructions executed by the SBF programs added with `add_program()`, and
+    /// write their line and branch coverage as lcov tracefiles to `output_dir` once the test
+    /// environment is dropped.
+    ///
+    /// Source lines are resolved from the DWARF info in `<program_name>.debug`, as written by
+    /// `cargo build-sbf --debug`, or else in the shared object itself.
+    pub fn enable_coverage(&mut self, output_dir: impl Into<PathBuf>) {
+        self.coverage_dir = Some(output_dir.into());
+    }
+
     /// Add an account to the test environment's genesis config.
     pub fn add_genesis_account(&mut self, address: Pubkey, account: Account) {
         self.genesis_accounts
@@ -672,6 +692,8 @@ impl ProgramTest {
                     .unwrap_or_default()
             );
 
+            this.sbf_programs
+                .push((program_id, program_name, program_file));
             this.add_account(
                 program_id,
                 Account {
@@ -777,6 +799,7 @@ impl ProgramTest {
         Arc<RwLock<BlockCommitmentCache>>,
         Hash,
         GenesisConfigInfo,
+        Option<CoverageReport>,
     ) {
         {
             use std::sync::Once;
@@ -837,6 +860,10 @@ impl ProgramTest {
         debug!("Payer address: {}", mint_keypair.pubkey());
         debug!("Genesis config: {}", genesis_config);
 
+        let coverage_report = self.coverage_dir.clone().map(|coverage_dir| {
+            CoverageReport::new(coverage_dir, std::mem::take(&mut self.sbf_programs))
+        });
+
         let bank = Bank::new_with_paths(
             &genesis_config,
             Arc::new(RuntimeConfig {
@@ -845,6 +872,9 @@ impl ProgramTest {
                     ..ComputeBudget::default()
                 }),
                 transaction_account_lock_limit: self.transaction_account_lock_limit,
+                program_coverage: coverage_report
+                    .as_ref()
+                    .map(|coverage_report| coverage_report.program_coverage().clone()),
                 ..RuntimeConfig::default()
             }),
             Vec::default(),
@@ -904,11 +934,13 @@ impl ProgramTest {
                 voting_keypair,
                 validator_pubkey: bootstrap_validator_pubkey,
             },
+            coverage_report,
         )
     }
 
     pub async fn start(mut self) -> (BanksClient, Keypair, Hash) {
-        let (bank_forks, block_commitment_cache, last_blockhash, gci) = self.setup_bank();
+        let (bank_forks, block_commitment_cache, last_blockhash, gci, coverage_report) =
+            self.setup_bank();
         let target_tick_duration = gci.genesis_config.poh_config.target_tick_duration;
         let target_slot_duration = target_tick_duration * gci.genesis_config.ticks_per_slot as u32;
         let transport = start_local_server(
@@ -923,8 +955,9 @@ impl ProgramTest {
 
         // Run a simulated PohService to provide the client with new blockhashes.  New blockhashes
         // are required when sending multiple otherwise identical transactions in series from a
-        // test
+        // test. The coverage report is written once the runtime drops the task.
         tokio::spawn(async move {
+            let _coverage_report = coverage_report;
             loop {
                 tokio::time::sleep(target_slot_duration).await;
                 bank_forks
@@ -943,7 +976,8 @@ impl ProgramTest {
     /// Returns a `BanksClient` interface into the test environment as well as a payer `Keypair`
     /// with SOL for sending transactions
     pub async fn start_with_context(mut self) -> ProgramTestContext {
-        let (bank_forks, block_commitment_cache, last_blockhash, gci) = self.setup_bank();
+        let (bank_forks, block_commitment_cache, last_blockhash, gci, coverage_report) =
+            self.setup_bank();
         let target_tick_duration = gci.genesis_config.poh_config.target_tick_duration;
         let transport = start_local_server(
             bank_forks.clone(),
@@ -961,6 +995,7 @@ impl ProgramTest {
             banks_client,
             last_blockhash,
             gci,
+            coverage_report,
         )
     }
 }
@@ -1023,6 +1058,7 @@ pub struct ProgramTestContext {
     bank_forks: Arc<RwLock<BankForks>>,
     block_commitment_cache: Arc<RwLock<BlockCommitmentCache>>,
     _bank_task: DroppableTask<()>,
+    _coverage_report: Option<CoverageReport>,
 }
 
 impl ProgramTestContext {
@@ -1032,6 +1068,7 @@ impl ProgramTestContext {
         banks_client: BanksClient,
         last_blockhash: Hash,
         genesis_config_info: GenesisConfigInfo,
+        coverage_report: Option<CoverageReport>,
     ) -> Self {
         // Run a simulated PohService to provide the client with new blockhashes.  New blockhashes
         // are required when sending multiple otherwise identical transactions in series from a
@@ -1069,6 +1106,7 @@ impl ProgramTestContext {
             bank_forks,
             block_commitment_cache,
             _bank_task: bank_task,
+            _coverage_report: coverage_report,
         }
     }
 
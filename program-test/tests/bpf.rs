@@ -39,3 +39,44 @@ async fn test_add_bpf_program() {
         .await
         .unwrap();
 }
+
+#[tokio::test]
+async fn test_program_coverage() {
+    let program_id = Pubkey::new_unique();
+    let coverage_dir = tempfile::tempdir().unwrap();
+
+    let mut program_test = ProgramTest::default();
+    program_test.prefer_bpf(true);
+    program_test.add_program("noop_program", program_id, None);
+    program_test.enable_coverage(coverage_dir.path());
+
+    let context = program_test.start_with_context().await;
+    let instruction = Instruction::new_with_bytes(program_id, &[], Vec::new());
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+    drop(context);
+
+    let tracefiles = std::fs::read_dir(coverage_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    assert_eq!(tracefiles.len(), 1);
+    let tracefile = std::fs::read_to_string(&tracefiles[0]).unwrap();
+    assert!(tracefile.starts_with("TN:noop_program\nSF:"));
+    assert!(tracefile.ends_with("end_of_record\n"));
+    // The noop program returns right away, so only some of its instructions are executed
+    let lines_hit = tracefile
+        .lines()
+        .filter(|line| line.starts_with("DA:") && !line.ends_with(",0"))
+        .count();
+    assert!(lines_hit > 0);
+}
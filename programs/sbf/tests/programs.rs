@@ -103,6 +103,7 @@ fn process_transaction_and_record_inner(
                 enable_cpi_recording: true,
                 enable_log_recording: true,
                 enable_return_data_recording: false,
+                enable_execution_trace: false,
             },
             &mut ExecuteTimings::default(),
             None,
@@ -648,7 +649,7 @@ fn test_return_data_and_log_data_syscall() {
         let transaction = Transaction::new(&[&mint_keypair], message, blockhash);
         let sanitized_tx = SanitizedTransaction::from_transaction_for_tests(transaction);
 
-        let result = bank.simulate_transaction(&sanitized_tx, false);
+        let result = bank.simulate_transaction(&sanitized_tx, false, false);
 
         assert!(result.result.is_ok());
 
@@ -5218,6 +5219,7 @@ fn test_function_call_args() {
                 enable_cpi_recording: false,
                 enable_log_recording: false,
                 enable_return_data_recording: true,
+                enable_execution_trace: false,
             },
             &mut ExecuteTimings::default(),
             None,
@@ -76,6 +76,7 @@ mod tests {
             endpoints: _,
             thread: t,
             key_updater: _,
+            connection_stats_table: _,
         } = solana_streamer::quic::spawn_server(
             "solQuicTest",
             "quic_streamer_test",
@@ -163,6 +164,7 @@ mod tests {
             stats: _,
             thread: t,
             max_concurrent_connections: _,
+            connection_stats_table: _,
         } = solana_streamer::nonblocking::quic::spawn_server(
             "quic_streamer_test",
             s.try_clone().unwrap(),
@@ -226,6 +228,7 @@ mod tests {
             endpoints: request_recv_endpoints,
             thread: request_recv_thread,
             key_updater: _,
+            connection_stats_table: _,
         } = solana_streamer::quic::spawn_server(
             "solQuicTest",
             "quic_streamer_test",
@@ -256,6 +259,7 @@ mod tests {
             endpoints: mut response_recv_endpoints,
             thread: response_recv_thread,
             key_updater: _,
+            connection_stats_table: _,
         } = solana_streamer::quic::spawn_server(
             "solQuicTest",
             "quic_streamer_test",
@@ -0,0 +1,56 @@
+//! Serves the external signer protocol for keypair files, to try out `signer+`
+//! URIs without a signing daemon. The keys are held in memory in the clear.
+//!
+//! Usage: solana-test-signer-daemon <HOST:PORT | SOCKET_PATH> <KEYPAIR_FILE>...
+//!
+//! Each keypair is served under the file name without its extension as key id.
+//! The first one is also the default key.
+use {
+    solana_remote_wallet::external_signer::TestSignerDaemon,
+    solana_sdk::signature::{read_keypair_file, Signer},
+    std::{collections::HashMap, env, error, net::TcpListener, path::Path},
+};
+
+fn main() -> Result<(), Box<dyn error::Error>> {
+    let args: Vec<_> = env::args().skip(1).collect();
+    let Some((address, keypair_files)) = args.split_first().filter(|(_, files)| !files.is_empty())
+    else {
+        return Err(
+            "usage: solana-test-signer-daemon <HOST:PORT | SOCKET_PATH> <KEYPAIR_FILE>...".into(),
+        );
+    };
+
+    let mut keypairs = HashMap::new();
+    for (i, keypair_file) in keypair_files.iter().enumerate() {
+        let key_id = Path::new(keypair_file)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| format!("invalid keypair file name {keypair_file}"))?
+            .to_string();
+        let keypair = read_keypair_file(keypair_file)
+            .map_err(|err| format!("failed to read {keypair_file}: {err}"))?;
+        println!("{key_id}: {}", keypair.pubkey());
+        if i == 0 {
+            keypairs.insert("-".to_string(), keypair.insecure_clone());
+        }
+        keypairs.insert(key_id, keypair);
+    }
+    let daemon = TestSignerDaemon::new(keypairs);
+
+    let handle = if address.contains('/') {
+        #[cfg(unix)]
+        {
+            let listener = std::os::unix::net::UnixListener::bind(address)?;
+            println!("Listening on signer+unix://{address}");
+            daemon.spawn_unix(listener)
+        }
+        #[cfg(not(unix))]
+        return Err("unix sockets are not supported on this platform".into());
+    } else {
+        let listener = TcpListener::bind(address)?;
+        println!("Listening on signer+tcp://{}", listener.local_addr()?);
+        daemon.spawn_tcp(listener)
+    };
+    handle.join().map_err(|_| "test signer daemon panicked")?;
+    Ok(())
+}
@@ -0,0 +1,565 @@
+//! Signing with keys held by an external signing daemon, such as a service
+//! fronting an HSM.
+//!
+//! The daemon is located with a `signer+unix:` URI naming a unix socket, or a
+//! `signer+tcp:` URI naming a host and port:
+//!
+//! - `signer+unix:///run/signer.sock`
+//! - `signer+tcp://127.0.0.1:7700?key=withdraw-authority`
+//! - `signer+tcp://127.0.0.1:7700?key=withdraw-authority&pubkey=<base-58 pubkey>`
+//!
+//! The optional `key` query parameter selects one of the keys held by the
+//! daemon, which otherwise uses its default key. The optional `pubkey` query
+//! parameter pins the public key the daemon is expected to report.
+//!
+//! # Protocol
+//!
+//! Each request opens a new connection, writes a single request line and reads
+//! response lines until the request completes. Lines are UTF-8 and terminated
+//! by `\n`, fields are separated by a single space. The key id is `-` for the
+//! default key.
+//!
+//! Requests:
+//!
+//! - `PUBKEY <key id>` &mdash; Returns the base-58 public key of the key. A
+//!   trailing `confirm` field asks the daemon to have the key confirmed by its
+//!   operator before replying.
+//! - `SIGN <key id> <base-64 message>` &mdash; Returns the base-58 signature
+//!   of the message.
+//!
+//! Responses:
+//!
+//! - `PENDING <text>` &mdash; The request awaits confirmation, for example by
+//!   an operator approving it on the HSM. The text is shown to the user and
+//!   more response lines follow.
+//! - `OK <value>` &mdash; The request succeeded.
+//! - `DENIED <text>` &mdash; The request was rejected by the operator.
+//! - `ERR <text>` &mdash; The request failed.
+//!
+//! Connecting over TCP and writing a request time out after 10 seconds. Reading
+//! times out after 5 minutes without a response line, leaving the operator
+//! time to confirm pending requests.
+//!
+//! [`TestSignerDaemon`], also run by the `solana-test-signer-daemon` binary,
+//! serves the protocol for keypairs held in memory to try out `signer+` URIs.
+use {
+    base64::{prelude::BASE64_STANDARD, Engine},
+    log::*,
+    solana_sdk::{
+        pubkey::{ParsePubkeyError, Pubkey},
+        signature::{Keypair, Signature, Signer, SignerError},
+    },
+    std::{
+        collections::HashMap,
+        io::{self, BufRead, BufReader, Read, Write},
+        net::{TcpListener, TcpStream, ToSocketAddrs},
+        path::PathBuf,
+        str::FromStr,
+        sync::Arc,
+        thread::{self, JoinHandle},
+        time::Duration,
+    },
+    thiserror::Error,
+    uriparse::{URIReference, URIReferenceError},
+};
+
+pub const SIGNER_SOURCE_EXTERNAL_UNIX: &str = "signer+unix";
+pub const SIGNER_SOURCE_EXTERNAL_TCP: &str = "signer+tcp";
+
+const DEFAULT_KEY_ID: &str = "-";
+const QUERY_KEY: &str = "key";
+const QUERY_PUBKEY: &str = "pubkey";
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+// Allows for the operator confirming a pending request
+const READ_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Error)]
+pub enum ExternalSignerError {
+    #[error("unimplemented scheme")]
+    UnimplementedScheme,
+    #[error("missing signer address")]
+    MissingAddress,
+    #[error("invalid key id: {0}")]
+    InvalidKeyId(String),
+    #[error("invalid query string: {0}")]
+    InvalidQuery(String),
+    #[error(transparent)]
+    PubkeyError(#[from] ParsePubkeyError),
+    #[error(transparent)]
+    UriReferenceError(#[from] URIReferenceError),
+    #[error("connection error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("protocol error: {0}")]
+    Protocol(String),
+    #[error("external signer error: {0}")]
+    Signer(String),
+    #[error("request denied: {0}")]
+    Denied(String),
+    #[error("external signer holds {actual}, expected {expected}")]
+    PubkeyMismatch { expected: Pubkey, actual: Pubkey },
+    #[error("external signer returned an invalid signature")]
+    InvalidSignature,
+}
+
+impl From<ExternalSignerError> for SignerError {
+    fn from(err: ExternalSignerError) -> SignerError {
+        match err {
+            ExternalSignerError::Io(_) => SignerError::Connection(err.to_string()),
+            ExternalSignerError::Protocol(e) => SignerError::Protocol(e),
+            ExternalSignerError::Denied(e) => SignerError::UserCancel(e),
+            _ => SignerError::Custom(err.to_string()),
+        }
+    }
+}
+
+/// Address of an external signing daemon
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExternalSignerAddress {
+    Unix(PathBuf),
+    Tcp(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExternalSignerLocator {
+    pub address: ExternalSignerAddress,
+    pub key_id: Option<String>,
+    pub pubkey: Option<Pubkey>,
+}
+
+impl std::fmt::Display for ExternalSignerLocator {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.address {
+            ExternalSignerAddress::Unix(path) => {
+                write!(f, "{SIGNER_SOURCE_EXTERNAL_UNIX}://{}", path.display())?
+            }
+            ExternalSignerAddress::Tcp(address) => {
+                write!(f, "{SIGNER_SOURCE_EXTERNAL_TCP}://{address}")?
+            }
+        }
+        let query: Vec<_> = self
+            .key_id
+            .iter()
+            .map(|key_id| format!("{QUERY_KEY}={}", percent_encode(key_id)))
+            .chain(
+                self.pubkey
+                    .iter()
+                    .map(|pubkey| format!("{QUERY_PUBKEY}={pubkey}")),
+            )
+            .collect();
+        if !query.is_empty() {
+            write!(f, "?{}", query.join("&"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Percent-encodes everything but the unreserved characters of RFC 3986
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+                char::from(byte).to_string()
+            } else {
+                format!("%{byte:02X}")
+            }
+        })
+        .collect()
+}
+
+impl ExternalSignerLocator {
+    pub fn is_external_signer_scheme(scheme: &str) -> bool {
+        let scheme = scheme.to_ascii_lowercase();
+        scheme == SIGNER_SOURCE_EXTERNAL_UNIX || scheme == SIGNER_SOURCE_EXTERNAL_TCP
+    }
+
+    pub fn new_from_path<P: AsRef<str>>(path: P) -> Result<Self, ExternalSignerError> {
+        let uri = URIReference::try_from(path.as_ref())?;
+        Self::new_from_uri(&uri)
+    }
+
+    pub fn new_from_uri(uri: &URIReference<'_>) -> Result<Self, ExternalSignerError> {
+        let scheme = uri
+            .scheme()
+            .map(|scheme| scheme.as_str().to_ascii_lowercase())
+            .ok_or(ExternalSignerError::UnimplementedScheme)?;
+        let address = match scheme.as_str() {
+            SIGNER_SOURCE_EXTERNAL_UNIX => {
+                let path = uri.path().to_string();
+                // An empty path is normalized to the root
+                if path.is_empty()
+                    || path == "/"
+                    || uri.host().is_some_and(|host| !host.to_string().is_empty())
+                {
+                    return Err(ExternalSignerError::MissingAddress);
+                }
+                ExternalSignerAddress::Unix(PathBuf::from(path))
+            }
+            SIGNER_SOURCE_EXTERNAL_TCP => match (uri.host(), uri.port()) {
+                (Some(host), Some(port)) => ExternalSignerAddress::Tcp(format!("{host}:{port}")),
+                _ => return Err(ExternalSignerError::MissingAddress),
+            },
+            _ => return Err(ExternalSignerError::UnimplementedScheme),
+        };
+
+        let mut key_id = None;
+        let mut pubkey = None;
+        if let Some(query) = uri.query() {
+            for (name, value) in qstring::QString::from(query.as_str()).into_pairs() {
+                match name.as_str() {
+                    QUERY_KEY => {
+                        if value.is_empty() || value.contains(char::is_whitespace) {
+                            return Err(ExternalSignerError::InvalidKeyId(value));
+                        }
+                        key_id = Some(value);
+                    }
+                    QUERY_PUBKEY => pubkey = Some(Pubkey::from_str(&value)?),
+                    _ => return Err(ExternalSignerError::InvalidQuery(name)),
+                }
+            }
+        }
+        Ok(Self {
+            address,
+            key_id,
+            pubkey,
+        })
+    }
+
+    fn key_id(&self) -> &str {
+        self.key_id.as_deref().unwrap_or(DEFAULT_KEY_ID)
+    }
+
+    /// Sends a request to the daemon, returning the value of its `OK` response
+    fn request(&self, request: &str) -> Result<String, ExternalSignerError> {
+        match &self.address {
+            #[cfg(unix)]
+            ExternalSignerAddress::Unix(path) => {
+                let stream = std::os::unix::net::UnixStream::connect(path)?;
+                stream.set_read_timeout(Some(READ_TIMEOUT))?;
+                stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+                exchange(stream, request)
+            }
+            #[cfg(not(unix))]
+            ExternalSignerAddress::Unix(_) => Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "unix sockets are not supported on this platform",
+            )
+            .into()),
+            ExternalSignerAddress::Tcp(address) => {
+                let stream = connect_tcp(address)?;
+                stream.set_read_timeout(Some(READ_TIMEOUT))?;
+                stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+                exchange(stream, request)
+            }
+        }
+    }
+}
+
+/// Connects to the first reachable address `address` resolves to
+fn connect_tcp(address: &str) -> io::Result<TcpStream> {
+    let mut last_err = None;
+    for socket_addr in address.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&socket_addr, CONNECT_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last_err = Some(err),
+        }
+    }
+    Err(last_err.unwrap_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{address} resolves to no address"),
+        )
+    }))
+}
+
+fn exchange<S: Read + Write>(mut stream: S, request: &str) -> Result<String, ExternalSignerError> {
+    stream.write_all(request.as_bytes())?;
+    stream.write_all(b"\n")?;
+    stream.flush()?;
+
+    let mut lines = BufReader::new(stream).lines();
+    loop {
+        let line = lines.next().ok_or_else(|| {
+            ExternalSignerError::Protocol("connection closed before a response".to_string())
+        })??;
+        let (status, text) = line.split_once(' ').unwrap_or((line.as_str(), ""));
+        match status {
+            "PENDING" => println!("{text}"),
+            "OK" => return Ok(text.to_string()),
+            "DENIED" => return Err(ExternalSignerError::Denied(text.to_string())),
+            "ERR" => return Err(ExternalSignerError::Signer(text.to_string())),
+            _ => {
+                return Err(ExternalSignerError::Protocol(format!(
+                    "unexpected response: {line}"
+                )))
+            }
+        }
+    }
+}
+
+/// A [`Signer`] whose key is held by an external signing daemon
+#[derive(Debug)]
+pub struct ExternalSigner {
+    pub locator: ExternalSignerLocator,
+    pub pubkey: Pubkey,
+}
+
+impl ExternalSigner {
+    /// Connects to the daemon to look up the public key of the signer, which the daemon has
+    /// confirmed by its operator if `confirm_key` is set
+    pub fn new(
+        locator: ExternalSignerLocator,
+        confirm_key: bool,
+    ) -> Result<Self, ExternalSignerError> {
+        let mut request = format!("PUBKEY {}", locator.key_id());
+        if confirm_key {
+            request.push_str(" confirm");
+        }
+        let response = locator.request(&request)?;
+        let pubkey = Pubkey::from_str(&response)
+            .map_err(|_| ExternalSignerError::Protocol(format!("invalid pubkey: {response}")))?;
+        if let Some(expected) = locator.pubkey {
+            if expected != pubkey {
+                return Err(ExternalSignerError::PubkeyMismatch {
+                    expected,
+                    actual: pubkey,
+                });
+            }
+        }
+        Ok(Self { locator, pubkey })
+    }
+}
+
+impl Signer for ExternalSigner {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        Ok(self.pubkey)
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let response = self.locator.request(&format!(
+            "SIGN {} {}",
+            self.locator.key_id(),
+            BASE64_STANDARD.encode(message)
+        ))?;
+        let signature = Signature::from_str(&response)
+            .map_err(|_| ExternalSignerError::Protocol(format!("invalid signature: {response}")))?;
+        if !signature.verify(self.pubkey.as_ref(), message) {
+            return Err(ExternalSignerError::InvalidSignature.into());
+        }
+        Ok(signature)
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+}
+
+/// Serves the protocol for keypairs held in memory, standing in for a signing daemon to try
+/// out `signer+` URIs and to test integrations. It offers none of the protection of a real
+/// daemon. Requests for a key named `denied` are rejected as if by the operator.
+#[derive(Clone)]
+pub struct TestSignerDaemon {
+    keypairs: Arc<HashMap<String, Keypair>>,
+}
+
+impl TestSignerDaemon {
+    /// Serves `keypairs` keyed by their key id, `-` being the default key
+    pub fn new(keypairs: HashMap<String, Keypair>) -> Self {
+        Self {
+            keypairs: Arc::new(keypairs),
+        }
+    }
+
+    /// Serves the connections accepted by `listener` on a new thread
+    pub fn spawn_tcp(self, listener: TcpListener) -> JoinHandle<()> {
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if let Err(err) = stream.and_then(|stream| self.serve(stream)) {
+                    warn!("test signer daemon: {err}");
+                }
+            }
+        })
+    }
+
+    /// Serves the connections accepted by `listener` on a new thread
+    #[cfg(unix)]
+    pub fn spawn_unix(self, listener: std::os::unix::net::UnixListener) -> JoinHandle<()> {
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if let Err(err) = stream.and_then(|stream| self.serve(stream)) {
+                    warn!("test signer daemon: {err}");
+                }
+            }
+        })
+    }
+
+    /// Answers the request of a connection
+    pub fn serve<S: Read + Write>(&self, mut stream: S) -> io::Result<()> {
+        let mut line = String::new();
+        BufReader::new(&mut stream).read_line(&mut line)?;
+        let fields: Vec<_> = line.trim_end().split(' ').collect();
+        let keypair = fields.get(1).and_then(|key_id| self.keypairs.get(*key_id));
+        let response = match (fields.as_slice(), keypair) {
+            ([_, "denied", ..], _) => "DENIED not approved".to_string(),
+            (["PUBKEY", _] | ["PUBKEY", _, "confirm"], Some(keypair)) => {
+                format!("OK {}", keypair.pubkey())
+            }
+            (["SIGN", _, message], Some(keypair)) => match BASE64_STANDARD.decode(message) {
+                Ok(message) => format!(
+                    "PENDING Waiting for approval\nOK {}",
+                    keypair.sign_message(&message)
+                ),
+                Err(_) => "ERR invalid message".to_string(),
+            },
+            (["PUBKEY" | "SIGN", ..], None) => "ERR unknown key".to_string(),
+            _ => "ERR invalid request".to_string(),
+        };
+        writeln!(stream, "{response}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, assert_matches::assert_matches};
+
+    fn spawn_daemon(keypairs: HashMap<String, Keypair>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        TestSignerDaemon::new(keypairs).spawn_tcp(listener);
+        address
+    }
+
+    #[test]
+    fn test_external_signer_locator() {
+        let pubkey = Pubkey::new_unique();
+        assert_eq!(
+            ExternalSignerLocator::new_from_path("signer+unix:///run/signer.sock").unwrap(),
+            ExternalSignerLocator {
+                address: ExternalSignerAddress::Unix(PathBuf::from("/run/signer.sock")),
+                key_id: None,
+                pubkey: None,
+            }
+        );
+        let locator = ExternalSignerLocator::new_from_path(format!(
+            "signer+tcp://127.0.0.1:7700?key=withdraw&pubkey={pubkey}"
+        ))
+        .unwrap();
+        assert_eq!(
+            locator,
+            ExternalSignerLocator {
+                address: ExternalSignerAddress::Tcp("127.0.0.1:7700".to_string()),
+                key_id: Some("withdraw".to_string()),
+                pubkey: Some(pubkey),
+            }
+        );
+        assert_eq!(
+            ExternalSignerLocator::new_from_path(locator.to_string()).unwrap(),
+            locator
+        );
+        let locator = ExternalSignerLocator {
+            key_id: Some("a&b=c%d#e".to_string()),
+            ..locator
+        };
+        assert_eq!(
+            locator.to_string(),
+            format!("signer+tcp://127.0.0.1:7700?key=a%26b%3Dc%25d%23e&pubkey={pubkey}")
+        );
+        assert_eq!(
+            ExternalSignerLocator::new_from_path(locator.to_string()).unwrap(),
+            locator
+        );
+
+        assert_matches!(
+            ExternalSignerLocator::new_from_path("signer+tcp://127.0.0.1"),
+            Err(ExternalSignerError::MissingAddress)
+        );
+        assert_matches!(
+            ExternalSignerLocator::new_from_path("signer+unix://"),
+            Err(ExternalSignerError::MissingAddress)
+        );
+        assert_matches!(
+            ExternalSignerLocator::new_from_path("signer+tcp://127.0.0.1:7700?pubkey=bad"),
+            Err(ExternalSignerError::PubkeyError(_))
+        );
+        assert_matches!(
+            ExternalSignerLocator::new_from_path("signer+tcp://127.0.0.1:7700?other=1"),
+            Err(ExternalSignerError::InvalidQuery(_))
+        );
+        assert_matches!(
+            ExternalSignerLocator::new_from_path("usb://ledger"),
+            Err(ExternalSignerError::UnimplementedScheme)
+        );
+    }
+
+    #[test]
+    fn test_external_signer() {
+        let keypair = Keypair::new();
+        let pubkey = keypair.pubkey();
+        let other_keypair = Keypair::new();
+        let address = spawn_daemon(HashMap::from([
+            (DEFAULT_KEY_ID.to_string(), keypair),
+            ("other".to_string(), other_keypair.insecure_clone()),
+        ]));
+
+        let locator =
+            ExternalSignerLocator::new_from_path(format!("signer+tcp://{address}")).unwrap();
+        let signer = ExternalSigner::new(locator, true).unwrap();
+        assert_eq!(signer.pubkey(), pubkey);
+        let message = b"hello";
+        let signature = signer.try_sign_message(message).unwrap();
+        assert!(signature.verify(pubkey.as_ref(), message));
+
+        let locator =
+            ExternalSignerLocator::new_from_path(format!("signer+tcp://{address}?key=other"))
+                .unwrap();
+        let signer = ExternalSigner::new(locator, false).unwrap();
+        assert_eq!(signer.pubkey(), other_keypair.pubkey());
+
+        let locator = ExternalSignerLocator::new_from_path(format!(
+            "signer+tcp://{address}?key=other&pubkey={pubkey}"
+        ))
+        .unwrap();
+        assert_matches!(
+            ExternalSigner::new(locator, false),
+            Err(ExternalSignerError::PubkeyMismatch { .. })
+        );
+
+        let locator =
+            ExternalSignerLocator::new_from_path(format!("signer+tcp://{address}?key=missing"))
+                .unwrap();
+        assert_matches!(
+            ExternalSigner::new(locator, false),
+            Err(ExternalSignerError::Signer(_))
+        );
+
+        let locator =
+            ExternalSignerLocator::new_from_path(format!("signer+tcp://{address}?key=denied"))
+                .unwrap();
+        assert_matches!(
+            ExternalSigner::new(locator, false),
+            Err(ExternalSignerError::Denied(_))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_external_signer_unix() {
+        let keypair = Keypair::new();
+        let pubkey = keypair.pubkey();
+        let path = std::env::temp_dir().join(format!("test-signer-{pubkey}.sock"));
+        let listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+        TestSignerDaemon::new(HashMap::from([(DEFAULT_KEY_ID.to_string(), keypair)]))
+            .spawn_unix(listener);
+
+        let locator =
+            ExternalSignerLocator::new_from_path(format!("signer+unix://{}", path.display()))
+                .unwrap();
+        let signer = ExternalSigner::new(locator, false).unwrap();
+        assert_eq!(signer.pubkey(), pubkey);
+        let signature = signer.try_sign_message(b"hello").unwrap();
+        assert!(signature.verify(pubkey.as_ref(), b"hello"));
+        std::fs::remove_file(path).unwrap();
+    }
+}
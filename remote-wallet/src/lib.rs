@@ -1,5 +1,6 @@
 #![allow(clippy::arithmetic_side_effects)]
 #![allow(dead_code)]
+pub mod external_signer;
 pub mod ledger;
 pub mod ledger_error;
 pub mod locator;
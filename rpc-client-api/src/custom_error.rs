@@ -25,6 +25,7 @@ pub const JSON_RPC_SERVER_ERROR_BLOCK_STATUS_NOT_AVAILABLE_YET: i64 = -32014;
 pub const JSON_RPC_SERVER_ERROR_UNSUPPORTED_TRANSACTION_VERSION: i64 = -32015;
 pub const JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED: i64 = -32016;
 pub const JSON_RPC_SERVER_ERROR_EPOCH_REWARDS_PERIOD_ACTIVE: i64 = -32017;
+pub const JSON_RPC_SERVER_ERROR_HISTORICAL_ACCOUNTS_UNAVAILABLE: i64 = -32018;
 
 #[derive(Error, Debug)]
 pub enum RpcCustomError {
@@ -72,6 +73,8 @@ pub enum RpcCustomError {
         current_block_height: u64,
         rewards_complete_block_height: u64,
     },
+    #[error("HistoricalAccountsUnavailable")]
+    HistoricalAccountsUnavailable { slot: Slot, message: String },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -228,6 +231,11 @@ impl From<RpcCustomError> for Error {
                     rewards_complete_block_height,
                 })),
             },
+            RpcCustomError::HistoricalAccountsUnavailable { slot, message } => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_HISTORICAL_ACCOUNTS_UNAVAILABLE),
+                message: format!("Account state at slot {slot} is unavailable: {message}"),
+                data: None,
+            },
         }
     }
 }
@@ -11,6 +11,7 @@ pub enum RpcRequest {
     Custom { method: &'static str },
     DeregisterNode,
     GetAccountInfo,
+    GetAccountInfoAtSlot,
     GetBalance,
     GetBlock,
     GetBlockHeight,
@@ -39,6 +40,7 @@ pub enum RpcRequest {
     GetProgramAccounts,
     GetRecentPerformanceSamples,
     GetRecentPrioritizationFees,
+    GetPriorityFeeEstimate,
     GetHighestSnapshotSlot,
     GetSignaturesForAddress,
     GetSignatureStatuses,
@@ -76,6 +78,7 @@ impl fmt::Display for RpcRequest {
             RpcRequest::Custom { method } => method,
             RpcRequest::DeregisterNode => "deregisterNode",
             RpcRequest::GetAccountInfo => "getAccountInfo",
+            RpcRequest::GetAccountInfoAtSlot => "getAccountInfoAtSlot",
             RpcRequest::GetBalance => "getBalance",
             RpcRequest::GetBlock => "getBlock",
             RpcRequest::GetBlockHeight => "getBlockHeight",
@@ -104,6 +107,7 @@ impl fmt::Display for RpcRequest {
             RpcRequest::GetProgramAccounts => "getProgramAccounts",
             RpcRequest::GetRecentPerformanceSamples => "getRecentPerformanceSamples",
             RpcRequest::GetRecentPrioritizationFees => "getRecentPrioritizationFees",
+            RpcRequest::GetPriorityFeeEstimate => "getPriorityFeeEstimate",
             RpcRequest::GetHighestSnapshotSlot => "getHighestSnapshotSlot",
             RpcRequest::GetSignaturesForAddress => "getSignaturesForAddress",
             RpcRequest::GetSignatureStatuses => "getSignatureStatuses",
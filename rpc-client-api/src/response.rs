@@ -409,6 +409,47 @@ pub struct RpcSimulateTransactionResult {
     pub return_data: Option<UiTransactionReturnData>,
     pub inner_instructions: Option<Vec<UiInnerInstructions>>,
     pub replacement_blockhash: Option<RpcBlockhash>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<RpcExecutionTrace>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcExecutionTrace {
+    pub instructions: Vec<RpcInstructionTrace>,
+    /// Set if changes to account data were left out to bound the size of the trace
+    pub truncated: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcInstructionTrace {
+    pub program_id: String,
+    pub stack_height: usize,
+    /// Includes the compute units consumed by the instructions this one invoked
+    pub compute_units_consumed: u64,
+    /// The instruction's writable accounts whose lamports or data changed
+    pub accounts: Vec<RpcAccountTrace>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountTrace {
+    pub pubkey: String,
+    pub pre_lamports: u64,
+    pub post_lamports: u64,
+    pub pre_data_len: usize,
+    pub post_data_len: usize,
+    /// The byte ranges of the account's data which changed, in ascending order
+    pub data_changes: Vec<RpcAccountDataChange>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountDataChange {
+    pub offset: usize,
+    /// The new contents of the changed bytes, base64 encoded
+    pub data: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -532,6 +573,41 @@ pub struct RpcPrioritizationFee {
     pub prioritization_fee: u64,
 }
 
+/// Prioritization fees, in micro-lamports per compute unit, paid at each percentile
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPriorityFeeLevels {
+    pub p25: u64,
+    pub p50: u64,
+    pub p75: u64,
+    pub p90: u64,
+    pub max: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountPriorityFeeEstimate {
+    pub account: String,
+    /// Number of recent transactions which locked the account as writable
+    pub transaction_count: usize,
+    pub levels: RpcPriorityFeeLevels,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPriorityFeeEstimate {
+    /// Number of recent blocks the estimate is drawn from
+    pub slot_count: usize,
+    /// Number of recent transactions the estimate is drawn from
+    pub transaction_count: usize,
+    /// Fees paid by all recent transactions
+    pub block: RpcPriorityFeeLevels,
+    /// Fees paid by the recent transactions locking each requested account as writable
+    pub accounts: Vec<RpcAccountPriorityFeeEstimate>,
+    /// The highest of the block and account fees at each percentile
+    pub estimate: RpcPriorityFeeLevels,
+}
+
 #[cfg(test)]
 pub mod tests {
 
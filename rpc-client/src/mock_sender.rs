@@ -11,12 +11,13 @@ use {
         config::RpcBlockProductionConfig,
         request::RpcRequest,
         response::{
-            Response, RpcAccountBalance, RpcBlockProduction, RpcBlockProductionRange, RpcBlockhash,
-            RpcConfirmedTransactionStatusWithSignature, RpcContactInfo, RpcIdentity,
-            RpcInflationGovernor, RpcInflationRate, RpcInflationReward, RpcKeyedAccount,
-            RpcPerfSample, RpcPrioritizationFee, RpcResponseContext, RpcSimulateTransactionResult,
-            RpcSnapshotSlotInfo, RpcSupply, RpcVersionInfo, RpcVoteAccountInfo,
-            RpcVoteAccountStatus,
+            Response, RpcAccountBalance, RpcAccountPriorityFeeEstimate, RpcBlockProduction,
+            RpcBlockProductionRange, RpcBlockhash, RpcConfirmedTransactionStatusWithSignature,
+            RpcContactInfo, RpcIdentity, RpcInflationGovernor, RpcInflationRate,
+            RpcInflationReward, RpcKeyedAccount, RpcPerfSample, RpcPrioritizationFee,
+            RpcPriorityFeeEstimate, RpcPriorityFeeLevels, RpcResponseContext,
+            RpcSimulateTransactionResult, RpcSnapshotSlotInfo, RpcSupply, RpcVersionInfo,
+            RpcVoteAccountInfo, RpcVoteAccountStatus,
         },
     },
     solana_sdk::{
@@ -308,7 +309,8 @@ impl RpcSender for MockSender {
                     units_consumed: None,
                     return_data: None,
                     inner_instructions: None,
-                    replacement_blockhash: None
+                    replacement_blockhash: None,
+                    trace: None,
                 },
             })?,
             "getMinimumBalanceForRentExemption" => json![20],
@@ -392,6 +394,26 @@ impl RpcSender for MockSender {
                 slot: 123_456_789,
                 prioritization_fee: 10_000,
             }])?,
+            "getPriorityFeeEstimate" => {
+                let levels = RpcPriorityFeeLevels {
+                    p25: 1_000,
+                    p50: 5_000,
+                    p75: 10_000,
+                    p90: 50_000,
+                    max: 100_000,
+                };
+                serde_json::to_value(RpcPriorityFeeEstimate {
+                    slot_count: 150,
+                    transaction_count: 1_000,
+                    block: levels,
+                    accounts: vec![RpcAccountPriorityFeeEstimate {
+                        account: PUBKEY.to_string(),
+                        transaction_count: 10,
+                        levels,
+                    }],
+                    estimate: levels,
+                })?
+            }
             "getIdentity" => serde_json::to_value(RpcIdentity {
                 identity: PUBKEY.to_string(),
             })?,
@@ -2689,6 +2689,36 @@ impl RpcClient {
         self.invoke((self.rpc_client.as_ref()).get_recent_prioritization_fees(addresses))
     }
 
+    /// Returns percentiles of the prioritization fees paid by transactions in recent blocks.
+    /// Takes an optional vector of addresses; the response also includes the fees paid by the
+    /// recent transactions locking each of the provided accounts as writable.
+    ///
+    /// Currently, a node's prioritization-fee cache stores data from up to 150 blocks.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getPriorityFeeEstimate` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::client_error::Error;
+    /// # use solana_rpc_client::rpc_client::RpcClient;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// # let alice = Keypair::new();
+    /// let addresses = vec![alice.pubkey()];
+    /// let estimate = rpc_client.get_priority_fee_estimate(&addresses)?;
+    /// let fee = estimate.estimate.p75;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn get_priority_fee_estimate(
+        &self,
+        addresses: &[Pubkey],
+    ) -> ClientResult<RpcPriorityFeeEstimate> {
+        self.invoke((self.rpc_client.as_ref()).get_priority_fee_estimate(addresses))
+    }
+
     /// Returns the identity pubkey for the current node.
     ///
     /// # RPC Reference
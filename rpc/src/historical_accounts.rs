@@ -0,0 +1,516 @@
+//! The `historical_accounts` module loads banks at rooted slots which are no longer in
+//! `BankForks`, so that account state can be served for them.
+//!
+//! A bank at slot N is loaded by unpacking the highest full snapshot archive at or below N
+//! (and the highest incremental snapshot archive built on top of it, at or below N), then
+//! replaying the local blockstore from the snapshot slot up to N. The replay goes through a
+//! Secondary access to the blockstore, so it never writes to the ledger of the running
+//! validator (roots, bank hashes or dead slots). Loads run on their own
+//! threads, one at a time, and the number of loads waiting to run is bounded. Loaded banks are
+//! cached, up to a total size of their account storages.
+
+use {
+    solana_accounts_db::{
+        accounts_db::AccountShrinkThreshold,
+        accounts_index::AccountSecondaryIndexes,
+        hardened_unpack::{
+            open_genesis_config, OpenGenesisConfigError, MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
+        },
+    },
+    solana_ledger::{
+        blockstore::{Blockstore, BlockstoreError},
+        blockstore_options::{AccessType, BlockstoreOptions},
+        blockstore_processor::{self, BlockstoreProcessorError, ProcessOptions},
+        leader_schedule_cache::LeaderScheduleCache,
+    },
+    solana_runtime::{
+        accounts_background_service::AbsRequestSender,
+        bank::Bank,
+        bank_forks::BankForks,
+        runtime_config::RuntimeConfig,
+        snapshot_archive_info::{
+            FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo, SnapshotArchiveInfoGetter,
+        },
+        snapshot_bank_utils,
+        snapshot_utils::{self, SnapshotError},
+    },
+    solana_sdk::clock::Slot,
+    std::{
+        collections::VecDeque,
+        fs, io,
+        path::PathBuf,
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+        thread::Builder,
+    },
+    thiserror::Error,
+    tokio::sync::oneshot,
+};
+
+pub const DEFAULT_HISTORICAL_ACCOUNTS_CACHE_LIMIT_MB: u64 = 16 * 1024;
+pub const DEFAULT_HISTORICAL_ACCOUNTS_MAX_PENDING_LOADS: usize = 2;
+
+#[derive(Debug, Clone)]
+pub struct HistoricalAccountsConfig {
+    /// Directory holding the full and incremental snapshot archives to load from
+    pub snapshot_archives_dir: PathBuf,
+    /// Directory the snapshot archives are unpacked into
+    pub working_dir: PathBuf,
+    /// Maximum total size, in bytes, of the account storages of the historical banks kept loaded
+    pub cache_max_bytes: u64,
+    /// Maximum number of loads running or waiting to run; further requests are rejected
+    pub max_pending_loads: usize,
+}
+
+#[derive(Error, Debug)]
+pub enum HistoricalAccountsError {
+    #[error("slot {0} is not rooted in the local blockstore")]
+    SlotNotRooted(Slot),
+
+    #[error("no snapshot archive at or before slot {0}")]
+    NoSnapshotArchive(Slot),
+
+    #[error("slot {0} was skipped")]
+    SlotSkipped(Slot),
+
+    #[error("bank at slot {0} is not frozen")]
+    BankNotFrozen(Slot),
+
+    #[error("too many historical slots are being loaded, try again later")]
+    TooManyPendingLoads,
+
+    #[error("loading slot {0} was interrupted")]
+    LoadInterrupted(Slot),
+
+    #[error("failed to open genesis config: {0}")]
+    GenesisConfig(#[from] OpenGenesisConfigError),
+
+    #[error("failed to load snapshot archive: {0}")]
+    Snapshot(#[from] SnapshotError),
+
+    #[error("failed to open blockstore: {0}")]
+    Blockstore(#[from] BlockstoreError),
+
+    #[error("failed to replay blockstore: {0}")]
+    BlockstoreProcessor(#[from] BlockstoreProcessorError),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, HistoricalAccountsError>;
+
+pub struct HistoricalAccountsLoader {
+    config: HistoricalAccountsConfig,
+    blockstore: Arc<Blockstore>,
+    /// Loaded banks along with the size of their account storages, least recently used first
+    banks: Mutex<VecDeque<(Arc<Bank>, u64)>>,
+    /// Serializes loads, so concurrent requests for the same slot unpack the archives once
+    load_lock: Mutex<()>,
+    /// Number of loads running or waiting for `load_lock`
+    pending_loads: AtomicUsize,
+}
+
+impl HistoricalAccountsLoader {
+    pub fn new(config: HistoricalAccountsConfig, blockstore: Arc<Blockstore>) -> Self {
+        Self {
+            config,
+            blockstore,
+            banks: Mutex::default(),
+            load_lock: Mutex::default(),
+            pending_loads: AtomicUsize::default(),
+        }
+    }
+
+    /// Returns the bank at the rooted `slot`. If it is not cached, it is loaded from the
+    /// snapshot archives on a separate thread, so the caller is never blocked by the load.
+    pub async fn get_bank(self: &Arc<Self>, slot: Slot) -> Result<Arc<Bank>> {
+        if let Some(bank) = self.get_cached_bank(slot) {
+            return Ok(bank);
+        }
+        if !self.blockstore.is_root(slot) {
+            return Err(HistoricalAccountsError::SlotNotRooted(slot));
+        }
+
+        let pending_loads = self.pending_loads.fetch_add(1, Ordering::Relaxed);
+        if pending_loads >= self.config.max_pending_loads {
+            self.pending_loads.fetch_sub(1, Ordering::Relaxed);
+            return Err(HistoricalAccountsError::TooManyPendingLoads);
+        }
+        let (sender, receiver) = oneshot::channel();
+        let loader = Arc::clone(self);
+        let spawn_result = Builder::new()
+            .name("solRpcHistAcct".to_string())
+            .spawn(move || {
+                let result = loader.get_or_load_bank(slot);
+                loader.pending_loads.fetch_sub(1, Ordering::Relaxed);
+                // The request may have been dropped while the bank was loading; the bank
+                // stays cached for the next one
+                let _ = sender.send(result);
+            });
+        if let Err(err) = spawn_result {
+            self.pending_loads.fetch_sub(1, Ordering::Relaxed);
+            return Err(err.into());
+        }
+        receiver
+            .await
+            .map_err(|_| HistoricalAccountsError::LoadInterrupted(slot))?
+    }
+
+    fn get_or_load_bank(&self, slot: Slot) -> Result<Arc<Bank>> {
+        let _load_lock = self.load_lock.lock().unwrap();
+        // Another request may have loaded the bank while this one waited
+        if let Some(bank) = self.get_cached_bank(slot) {
+            return Ok(bank);
+        }
+        let bank = self.load_bank(slot)?;
+        self.cache_bank(bank.clone());
+        Ok(bank)
+    }
+
+    fn get_cached_bank(&self, slot: Slot) -> Option<Arc<Bank>> {
+        let mut banks = self.banks.lock().unwrap();
+        let index = banks.iter().position(|(bank, _)| bank.slot() == slot)?;
+        let entry = banks.remove(index)?;
+        let bank = entry.0.clone();
+        banks.push_back(entry);
+        Some(bank)
+    }
+
+    fn cache_bank(&self, bank: Arc<Bank>) {
+        let size = bank
+            .get_snapshot_storages(None)
+            .iter()
+            .map(|storage| storage.capacity())
+            .sum::<u64>();
+        let mut banks = self.banks.lock().unwrap();
+        banks.push_back((bank, size));
+        let mut cached_bytes = banks.iter().map(|(_, size)| size).sum::<u64>();
+        while cached_bytes > self.config.cache_max_bytes {
+            let Some((evicted, evicted_size)) = banks.pop_front() else {
+                break;
+            };
+            cached_bytes -= evicted_size;
+            // The bank's storages stay valid for any request still holding it, even once the
+            // files are unlinked
+            let load_dir = self.load_dir(evicted.slot());
+            if let Err(err) = fs::remove_dir_all(&load_dir) {
+                warn!(
+                    "Failed to remove historical accounts dir {}: {err}",
+                    load_dir.display()
+                );
+            }
+        }
+    }
+
+    fn load_dir(&self, slot: Slot) -> PathBuf {
+        self.config.working_dir.join(format!("slot-{slot}"))
+    }
+
+    fn load_bank(&self, slot: Slot) -> Result<Arc<Bank>> {
+        let (full_snapshot_archive_info, incremental_snapshot_archive_info) =
+            select_snapshot_archives(
+                snapshot_utils::get_full_snapshot_archives(&self.config.snapshot_archives_dir),
+                snapshot_utils::get_incremental_snapshot_archives(
+                    &self.config.snapshot_archives_dir,
+                ),
+                slot,
+            )
+            .ok_or(HistoricalAccountsError::NoSnapshotArchive(slot))?;
+        let genesis_config = open_genesis_config(
+            self.blockstore.ledger_path(),
+            MAX_GENESIS_ARCHIVE_UNPACKED_SIZE,
+        )?;
+
+        let load_dir = self.load_dir(slot);
+        if load_dir.exists() {
+            fs::remove_dir_all(&load_dir)?;
+        }
+        let account_paths = vec![load_dir.join("accounts")];
+        let bank_snapshots_dir = load_dir.join("snapshots");
+        fs::create_dir_all(&account_paths[0])?;
+        fs::create_dir_all(&bank_snapshots_dir)?;
+
+        info!(
+            "Loading historical bank at slot {slot} from snapshot archive {}",
+            full_snapshot_archive_info.path().display()
+        );
+        let (bank, _timings) = snapshot_bank_utils::bank_from_snapshot_archives(
+            &account_paths,
+            &bank_snapshots_dir,
+            &full_snapshot_archive_info,
+            incremental_snapshot_archive_info.as_ref(),
+            &genesis_config,
+            &RuntimeConfig::default(),
+            None,
+            None,
+            AccountSecondaryIndexes::default(),
+            None,
+            AccountShrinkThreshold::default(),
+            false,
+            true,
+            false,
+            false,
+            None,
+            None,
+            Arc::new(AtomicBool::new(false)),
+        )?;
+        if bank.slot() == slot {
+            // Banks loaded from snapshot archives are always frozen
+            return Ok(Arc::new(bank));
+        }
+
+        // A fresh Secondary access sees everything the validator wrote to the blockstore so far,
+        // and replaying through it leaves the validator's ledger untouched
+        let blockstore = Blockstore::open_with_options(
+            self.blockstore.ledger_path(),
+            BlockstoreOptions {
+                access_type: AccessType::Secondary,
+                column_options: self.blockstore.column_options().clone(),
+                ..BlockstoreOptions::default()
+            },
+        )?;
+        let bank_forks = BankForks::new_rw_arc(bank);
+        let leader_schedule_cache =
+            LeaderScheduleCache::new_from_bank(&bank_forks.read().unwrap().root_bank());
+        let process_options = ProcessOptions {
+            halt_at_slot: Some(slot),
+            accounts_db_skip_shrink: true,
+            ..ProcessOptions::default()
+        };
+        blockstore_processor::process_blockstore_from_root(
+            &blockstore,
+            &bank_forks,
+            &leader_schedule_cache,
+            &process_options,
+            None,
+            None,
+            None,
+            &AbsRequestSender::default(),
+        )?;
+        let bank = bank_forks
+            .read()
+            .unwrap()
+            .get(slot)
+            .ok_or(HistoricalAccountsError::SlotSkipped(slot))?;
+        if !bank.is_frozen() {
+            return Err(HistoricalAccountsError::BankNotFrozen(slot));
+        }
+        Ok(bank)
+    }
+}
+
+/// Selects the highest full snapshot archive at or below `slot`, along with the highest
+/// incremental snapshot archive at or below `slot` which was built on top of it.
+fn select_snapshot_archives(
+    full_snapshot_archives: Vec<FullSnapshotArchiveInfo>,
+    incremental_snapshot_archives: Vec<IncrementalSnapshotArchiveInfo>,
+    slot: Slot,
+) -> Option<(
+    FullSnapshotArchiveInfo,
+    Option<IncrementalSnapshotArchiveInfo>,
+)> {
+    let full_snapshot_archive_info = full_snapshot_archives
+        .into_iter()
+        .filter(|archive| archive.slot() <= slot)
+        .max()?;
+    let incremental_snapshot_archive_info = incremental_snapshot_archives
+        .into_iter()
+        .filter(|archive| {
+            archive.base_slot() == full_snapshot_archive_info.slot() && archive.slot() <= slot
+        })
+        .max();
+    Some((
+        full_snapshot_archive_info,
+        incremental_snapshot_archive_info,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        jsonrpc_core::futures::executor::block_on,
+        solana_ledger::{
+            blockstore_processor::fill_blockstore_slot_with_ticks,
+            create_new_tmp_ledger_auto_delete, get_tmp_ledger_path_auto_delete,
+        },
+        solana_runtime::{snapshot_hash::SnapshotHash, snapshot_utils::ArchiveFormat},
+        solana_sdk::{
+            genesis_config::create_genesis_config, hash::Hash, native_token::sol_to_lamports,
+            pubkey::Pubkey,
+        },
+    };
+
+    fn full_archive(slot: Slot) -> FullSnapshotArchiveInfo {
+        FullSnapshotArchiveInfo::new_from_path(snapshot_utils::build_full_snapshot_archive_path(
+            "/archives",
+            slot,
+            &SnapshotHash(Hash::default()),
+            ArchiveFormat::TarZstd,
+        ))
+        .unwrap()
+    }
+
+    fn incremental_archive(base_slot: Slot, slot: Slot) -> IncrementalSnapshotArchiveInfo {
+        IncrementalSnapshotArchiveInfo::new_from_path(
+            snapshot_utils::build_incremental_snapshot_archive_path(
+                "/archives",
+                base_slot,
+                slot,
+                &SnapshotHash(Hash::default()),
+                ArchiveFormat::TarZstd,
+            ),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_select_snapshot_archives() {
+        let full_snapshot_archives = vec![full_archive(100), full_archive(200), full_archive(300)];
+        let incremental_snapshot_archives = vec![
+            incremental_archive(100, 150),
+            incremental_archive(200, 250),
+            incremental_archive(200, 280),
+            incremental_archive(300, 350),
+        ];
+        let select = |slot| {
+            select_snapshot_archives(
+                full_snapshot_archives.clone(),
+                incremental_snapshot_archives.clone(),
+                slot,
+            )
+            .map(|(full, incremental)| (full.slot(), incremental.map(|archive| archive.slot())))
+        };
+
+        assert_eq!(select(99), None);
+        assert_eq!(select(100), Some((100, None)));
+        assert_eq!(select(199), Some((100, Some(150))));
+        assert_eq!(select(260), Some((200, Some(250))));
+        assert_eq!(select(280), Some((200, Some(280))));
+        assert_eq!(select(299), Some((200, Some(280))));
+        assert_eq!(select(1000), Some((300, Some(350))));
+    }
+
+    #[test]
+    fn test_get_bank() {
+        let (genesis_config, mint_keypair) = create_genesis_config(sol_to_lamports(1_000_000.));
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        genesis_config.write(ledger_path.path()).unwrap();
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+
+        let pubkey = Pubkey::new_unique();
+        let bank0 = Arc::new(Bank::new_for_tests(&genesis_config));
+        bank0
+            .transfer(sol_to_lamports(1.), &mint_keypair, &pubkey)
+            .unwrap();
+        bank0.fill_bank_with_ticks_for_tests();
+        let bank1 = Bank::new_from_parent(bank0, &Pubkey::new_unique(), 1);
+        bank1
+            .transfer(sol_to_lamports(2.), &mint_keypair, &pubkey)
+            .unwrap();
+        bank1.fill_bank_with_ticks_for_tests();
+        bank1.squash();
+        blockstore.set_roots([0, 1].iter()).unwrap();
+
+        let snapshot_archives_dir = ledger_path.path().join("snapshot-archives");
+        let bank_snapshots_dir = ledger_path.path().join("snapshots");
+        fs::create_dir_all(&bank_snapshots_dir).unwrap();
+        snapshot_bank_utils::bank_to_full_snapshot_archive(
+            &bank_snapshots_dir,
+            &bank1,
+            None,
+            &snapshot_archives_dir,
+            &snapshot_archives_dir,
+            ArchiveFormat::Tar,
+        )
+        .unwrap();
+
+        let loader = Arc::new(HistoricalAccountsLoader::new(
+            HistoricalAccountsConfig {
+                snapshot_archives_dir,
+                working_dir: ledger_path.path().join("historical-accounts"),
+                cache_max_bytes: u64::MAX,
+                max_pending_loads: 1,
+            },
+            blockstore,
+        ));
+
+        let bank = block_on(loader.get_bank(1)).unwrap();
+        assert_eq!(bank.slot(), 1);
+        assert_eq!(bank.get_balance(&pubkey), sol_to_lamports(3.));
+        // The loaded bank is served from the cache
+        assert!(Arc::ptr_eq(&bank, &block_on(loader.get_bank(1)).unwrap()));
+
+        // Slots which are not rooted are never loaded
+        assert!(matches!(
+            block_on(loader.get_bank(2)),
+            Err(HistoricalAccountsError::SlotNotRooted(2))
+        ));
+        // Neither are slots older than every snapshot archive
+        assert!(matches!(
+            block_on(loader.get_bank(0)),
+            Err(HistoricalAccountsError::NoSnapshotArchive(0))
+        ));
+    }
+
+    #[test]
+    fn test_get_bank_replays_without_writing_blockstore() {
+        let (genesis_config, _mint_keypair) = create_genesis_config(sol_to_lamports(1_000_000.));
+        let ticks_per_slot = genesis_config.ticks_per_slot;
+        let (ledger_path, blockhash) = create_new_tmp_ledger_auto_delete!(&genesis_config);
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+        let blockhash =
+            fill_blockstore_slot_with_ticks(&blockstore, ticks_per_slot, 1, 0, blockhash);
+        fill_blockstore_slot_with_ticks(&blockstore, ticks_per_slot, 2, 1, blockhash);
+        blockstore.set_roots([0, 1, 2].iter()).unwrap();
+
+        // Only slot 0 is covered by a snapshot archive, so loading slot 2 replays slots 1 and 2
+        let (bank_forks, _leader_schedule_cache) = blockstore_processor::test_process_blockstore(
+            &genesis_config,
+            &blockstore,
+            &ProcessOptions {
+                halt_at_slot: Some(0),
+                ..ProcessOptions::default()
+            },
+            Arc::default(),
+        );
+        let bank0 = bank_forks.read().unwrap().root_bank();
+        let snapshot_archives_dir = ledger_path.path().join("snapshot-archives");
+        let bank_snapshots_dir = ledger_path.path().join("snapshots");
+        fs::create_dir_all(&bank_snapshots_dir).unwrap();
+        snapshot_bank_utils::bank_to_full_snapshot_archive(
+            &bank_snapshots_dir,
+            &bank0,
+            None,
+            &snapshot_archives_dir,
+            &snapshot_archives_dir,
+            ArchiveFormat::Tar,
+        )
+        .unwrap();
+
+        let loader = Arc::new(HistoricalAccountsLoader::new(
+            HistoricalAccountsConfig {
+                snapshot_archives_dir,
+                working_dir: ledger_path.path().join("historical-accounts"),
+                cache_max_bytes: u64::MAX,
+                max_pending_loads: 1,
+            },
+            blockstore.clone(),
+        ));
+
+        let bank = block_on(loader.get_bank(2)).unwrap();
+        assert_eq!(bank.slot(), 2);
+        assert_eq!(bank.parent_slot(), 1);
+        assert!(bank.is_frozen());
+
+        // The replay did not record the bank hashes of the replayed slots, nor mark any slot dead
+        assert!(blockstore.get_bank_hash(0).is_some());
+        assert_eq!(blockstore.get_bank_hash(1), None);
+        assert_eq!(blockstore.get_bank_hash(2), None);
+        assert_eq!(blockstore.dead_slots_iterator(0).unwrap().count(), 0);
+        assert_eq!(blockstore.max_root(), 2);
+    }
+}
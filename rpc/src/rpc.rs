@@ -1,9 +1,15 @@
 //! The `rpc` module implements the Solana RPC interface.
 use {
     crate::{
-        filter::filter_allows, max_slots::MaxSlots,
+        filter::filter_allows,
+        historical_accounts::{
+            HistoricalAccountsConfig, HistoricalAccountsError, HistoricalAccountsLoader,
+        },
+        max_slots::MaxSlots,
         optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
-        parsed_token_accounts::*, rpc_cache::LargestAccountsCache, rpc_health::*,
+        parsed_token_accounts::*,
+        rpc_cache::LargestAccountsCache,
+        rpc_health::*,
     },
     base64::{prelude::BASE64_STANDARD, Engine},
     bincode::{config::Options, serialize},
@@ -55,7 +61,7 @@ use {
         commitment::{BlockCommitmentArray, BlockCommitmentCache, CommitmentSlots},
         installed_scheduler_pool::BankWithScheduler,
         non_circulating_supply::calculate_non_circulating_supply,
-        prioritization_fee_cache::PrioritizationFeeCache,
+        prioritization_fee_cache::{PrioritizationFeeCache, PrioritizationFeeDistribution},
         snapshot_config::SnapshotConfig,
         snapshot_utils,
     },
@@ -83,7 +89,7 @@ use {
         tpu_info::NullTpuInfo,
     },
     solana_stake_program,
-    solana_storage_bigtable::Error as StorageError,
+    solana_storage_bigtable::{Error as StorageError, LongTermLedgerStorage},
     solana_streamer::socket::SocketAddrSpace,
     solana_transaction_status::{
         map_inner_instructions, BlockEncodingOptions, ConfirmedBlock,
@@ -107,6 +113,7 @@ use {
         collections::{BinaryHeap, HashMap, HashSet},
         convert::TryFrom,
         net::SocketAddr,
+        path::PathBuf,
         str::FromStr,
         sync::{
             atomic::{AtomicBool, AtomicU64, Ordering},
@@ -156,6 +163,8 @@ pub struct JsonRpcConfig {
     pub max_request_body_size: Option<usize>,
     /// Disable the health check, used for tests and TestValidator
     pub disable_health_check: bool,
+    /// Serve `getAccountInfoAtSlot` from snapshot archives, disabled if None
+    pub historical_accounts_config: Option<HistoricalAccountsConfig>,
 }
 
 impl JsonRpcConfig {
@@ -175,6 +184,9 @@ pub struct RpcBigtableConfig {
     pub bigtable_app_profile_id: String,
     pub timeout: Option<Duration>,
     pub max_message_size: usize,
+    /// Serve and upload the long-term ledger data from a local database at this path instead
+    /// of Bigtable
+    pub local_ledger_storage_path: Option<PathBuf>,
 }
 
 impl Default for RpcBigtableConfig {
@@ -187,6 +199,7 @@ impl Default for RpcBigtableConfig {
             bigtable_app_profile_id,
             timeout: None,
             max_message_size: solana_storage_bigtable::DEFAULT_MAX_MESSAGE_SIZE,
+            local_ledger_storage_path: None,
         }
     }
 }
@@ -204,7 +217,7 @@ pub struct JsonRpcRequestProcessor {
     cluster_info: Arc<ClusterInfo>,
     genesis_hash: Hash,
     transaction_sender: Arc<Mutex<Sender<TransactionInfo>>>,
-    bigtable_ledger_storage: Option<solana_storage_bigtable::LedgerStorage>,
+    bigtable_ledger_storage: Option<Arc<dyn LongTermLedgerStorage>>,
     optimistically_confirmed_bank: Arc<RwLock<OptimisticallyConfirmedBank>>,
     largest_accounts_cache: Arc<RwLock<LargestAccountsCache>>,
     max_slots: Arc<MaxSlots>,
@@ -212,6 +225,7 @@ pub struct JsonRpcRequestProcessor {
     max_complete_transaction_status_slot: Arc<AtomicU64>,
     max_complete_rewards_slot: Arc<AtomicU64>,
     prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+    historical_accounts: Option<Arc<HistoricalAccountsLoader>>,
 }
 impl Metadata for JsonRpcRequestProcessor {}
 
@@ -310,7 +324,7 @@ impl JsonRpcRequestProcessor {
         health: Arc<RpcHealth>,
         cluster_info: Arc<ClusterInfo>,
         genesis_hash: Hash,
-        bigtable_ledger_storage: Option<solana_storage_bigtable::LedgerStorage>,
+        bigtable_ledger_storage: Option<Arc<dyn LongTermLedgerStorage>>,
         optimistically_confirmed_bank: Arc<RwLock<OptimisticallyConfirmedBank>>,
         largest_accounts_cache: Arc<RwLock<LargestAccountsCache>>,
         max_slots: Arc<MaxSlots>,
@@ -320,6 +334,16 @@ impl JsonRpcRequestProcessor {
         prioritization_fee_cache: Arc<PrioritizationFeeCache>,
     ) -> (Self, Receiver<TransactionInfo>) {
         let (sender, receiver) = unbounded();
+        let historical_accounts =
+            config
+                .historical_accounts_config
+                .clone()
+                .map(|historical_accounts_config| {
+                    Arc::new(HistoricalAccountsLoader::new(
+                        historical_accounts_config,
+                        blockstore.clone(),
+                    ))
+                });
         (
             Self {
                 config,
@@ -340,6 +364,7 @@ impl JsonRpcRequestProcessor {
                 max_complete_transaction_status_slot,
                 max_complete_rewards_slot,
                 prioritization_fee_cache,
+                historical_accounts,
             },
             receiver,
         )
@@ -414,6 +439,7 @@ impl JsonRpcRequestProcessor {
             max_complete_transaction_status_slot: Arc::new(AtomicU64::default()),
             max_complete_rewards_slot: Arc::new(AtomicU64::default()),
             prioritization_fee_cache: Arc::new(PrioritizationFeeCache::default()),
+            historical_accounts: None,
         }
     }
 
@@ -438,6 +464,48 @@ impl JsonRpcRequestProcessor {
         Ok(new_response(&bank, response))
     }
 
+    pub async fn get_account_info_at_slot(
+        &self,
+        pubkey: &Pubkey,
+        slot: Slot,
+        config: Option<RpcAccountInfoAtSlotConfig>,
+    ) -> Result<RpcResponse<Option<UiAccount>>> {
+        let RpcAccountInfoAtSlotConfig {
+            encoding,
+            data_slice,
+        } = config.unwrap_or_default();
+        let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
+
+        // The root bank doesn't need to be loaded from the snapshot archives
+        let root_bank = self.bank_forks.read().unwrap().root_bank();
+        let bank = if slot == root_bank.slot() {
+            root_bank
+        } else {
+            let historical_accounts = self.historical_accounts.clone().ok_or_else(|| {
+                Error::invalid_params("Historical account state is not enabled on this node")
+            })?;
+            let historical_accounts_unavailable =
+                |err: HistoricalAccountsError| RpcCustomError::HistoricalAccountsUnavailable {
+                    slot,
+                    message: err.to_string(),
+                };
+            // Only rooted, and so frozen, banks are served
+            if slot > root_bank.slot() {
+                return Err(historical_accounts_unavailable(
+                    HistoricalAccountsError::SlotNotRooted(slot),
+                )
+                .into());
+            }
+            historical_accounts
+                .get_bank(slot)
+                .await
+                .map_err(historical_accounts_unavailable)?
+        };
+
+        let response = get_encoded_account(&bank, pubkey, encoding, data_slice, None)?;
+        Ok(new_response(&bank, response))
+    }
+
     pub fn get_multiple_accounts(
         &self,
         pubkeys: Vec<Pubkey>,
@@ -2230,6 +2298,51 @@ impl JsonRpcRequestProcessor {
             })
             .collect())
     }
+
+    fn get_priority_fee_estimate(&self, pubkeys: Vec<Pubkey>) -> Result<RpcPriorityFeeEstimate> {
+        let (block_fees, account_fees) = self
+            .prioritization_fee_cache
+            .get_prioritization_fee_distributions(&pubkeys);
+        let block = priority_fee_levels(&block_fees);
+        let mut estimate = block;
+        let accounts = pubkeys
+            .iter()
+            .zip(account_fees)
+            .map(|(pubkey, fees)| {
+                let levels = priority_fee_levels(&fees);
+                estimate = RpcPriorityFeeLevels {
+                    p25: estimate.p25.max(levels.p25),
+                    p50: estimate.p50.max(levels.p50),
+                    p75: estimate.p75.max(levels.p75),
+                    p90: estimate.p90.max(levels.p90),
+                    max: estimate.max.max(levels.max),
+                };
+                RpcAccountPriorityFeeEstimate {
+                    account: pubkey.to_string(),
+                    transaction_count: fees.len(),
+                    levels,
+                }
+            })
+            .collect();
+        Ok(RpcPriorityFeeEstimate {
+            slot_count: self.prioritization_fee_cache.available_block_count(),
+            transaction_count: block_fees.len(),
+            block,
+            accounts,
+            estimate,
+        })
+    }
+}
+
+fn priority_fee_levels(fees: &PrioritizationFeeDistribution) -> RpcPriorityFeeLevels {
+    let percentile = |percentile| fees.percentile(percentile).unwrap_or_default();
+    RpcPriorityFeeLevels {
+        p25: percentile(25),
+        p50: percentile(50),
+        p75: percentile(75),
+        p90: percentile(90),
+        max: fees.max().unwrap_or_default(),
+    }
 }
 
 fn optimize_filters(filters: &mut [RpcFilterType]) {
@@ -3000,6 +3113,15 @@ pub mod rpc_accounts {
             config: Option<RpcAccountInfoConfig>,
         ) -> Result<RpcResponse<Option<UiAccount>>>;
 
+        #[rpc(meta, name = "getAccountInfoAtSlot")]
+        fn get_account_info_at_slot(
+            &self,
+            meta: Self::Metadata,
+            pubkey_str: String,
+            slot: Slot,
+            config: Option<RpcAccountInfoAtSlotConfig>,
+        ) -> BoxFuture<Result<RpcResponse<Option<UiAccount>>>>;
+
         #[rpc(meta, name = "getMultipleAccounts")]
         fn get_multiple_accounts(
             &self,
@@ -3051,6 +3173,24 @@ pub mod rpc_accounts {
             meta.get_account_info(&pubkey, config)
         }
 
+        fn get_account_info_at_slot(
+            &self,
+            meta: Self::Metadata,
+            pubkey_str: String,
+            slot: Slot,
+            config: Option<RpcAccountInfoAtSlotConfig>,
+        ) -> BoxFuture<Result<RpcResponse<Option<UiAccount>>>> {
+            debug!(
+                "get_account_info_at_slot rpc request received: {:?} {:?}",
+                pubkey_str, slot
+            );
+            let pubkey = match verify_pubkey(&pubkey_str) {
+                Ok(pubkey) => pubkey,
+                Err(err) => return Box::pin(future::err(err)),
+            };
+            Box::pin(async move { meta.get_account_info_at_slot(&pubkey, slot, config).await })
+        }
+
         fn get_multiple_accounts(
             &self,
             meta: Self::Metadata,
@@ -3438,6 +3578,13 @@ pub mod rpc_full {
             meta: Self::Metadata,
             pubkey_strs: Option<Vec<String>>,
         ) -> Result<Vec<RpcPrioritizationFee>>;
+
+        #[rpc(meta, name = "getPriorityFeeEstimate")]
+        fn get_priority_fee_estimate(
+            &self,
+            meta: Self::Metadata,
+            pubkey_strs: Option<Vec<String>>,
+        ) -> Result<RpcPriorityFeeEstimate>;
     }
 
     pub struct FullImpl;
@@ -3722,7 +3869,8 @@ pub mod rpc_full {
                     units_consumed,
                     return_data,
                     inner_instructions: _, // Always `None` due to `enable_cpi_recording = false`
-                } = preflight_bank.simulate_transaction(&transaction, false)
+                    execution_trace: _,    // Always `None` due to `enable_execution_trace = false`
+                } = preflight_bank.simulate_transaction(&transaction, false, false)
                 {
                     match err {
                         TransactionError::BlockhashNotFound => {
@@ -3742,6 +3890,7 @@ pub mod rpc_full {
                             return_data: return_data.map(|return_data| return_data.into()),
                             inner_instructions: None,
                             replacement_blockhash: None,
+                            trace: None,
                         },
                     }
                     .into());
@@ -3773,6 +3922,7 @@ pub mod rpc_full {
                 accounts: config_accounts,
                 min_context_slot,
                 inner_instructions: enable_cpi_recording,
+                enable_trace,
             } = config.unwrap_or_default();
             let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
             let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
@@ -3820,7 +3970,8 @@ pub mod rpc_full {
                 units_consumed,
                 return_data,
                 inner_instructions,
-            } = bank.simulate_transaction(&transaction, enable_cpi_recording);
+                execution_trace,
+            } = bank.simulate_transaction(&transaction, enable_cpi_recording, enable_trace);
 
             let account_keys = transaction.message().account_keys();
             let number_of_accounts = account_keys.len();
@@ -3877,6 +4028,38 @@ pub mod rpc_full {
                     .collect()
             });
 
+            let trace = execution_trace.map(|execution_trace| RpcExecutionTrace {
+                instructions: execution_trace
+                    .instructions
+                    .into_iter()
+                    .map(|instruction| RpcInstructionTrace {
+                        program_id: instruction.program_id.to_string(),
+                        stack_height: instruction.stack_height,
+                        compute_units_consumed: instruction.compute_units_consumed,
+                        accounts: instruction
+                            .accounts
+                            .into_iter()
+                            .map(|account| RpcAccountTrace {
+                                pubkey: account.pubkey.to_string(),
+                                pre_lamports: account.pre_lamports,
+                                post_lamports: account.post_lamports,
+                                pre_data_len: account.pre_data_len,
+                                post_data_len: account.post_data_len,
+                                data_changes: account
+                                    .data_changes
+                                    .into_iter()
+                                    .map(|change| RpcAccountDataChange {
+                                        offset: change.offset,
+                                        data: BASE64_STANDARD.encode(change.data),
+                                    })
+                                    .collect(),
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+                truncated: execution_trace.truncated,
+            });
+
             Ok(new_response(
                 bank,
                 RpcSimulateTransactionResult {
@@ -3887,6 +4070,7 @@ pub mod rpc_full {
                     return_data: return_data.map(|return_data| return_data.into()),
                     inner_instructions,
                     replacement_blockhash: blockhash,
+                    trace,
                 },
             ))
         }
@@ -4100,6 +4284,28 @@ pub mod rpc_full {
                 .collect::<Result<Vec<_>>>()?;
             meta.get_recent_prioritization_fees(pubkeys)
         }
+
+        fn get_priority_fee_estimate(
+            &self,
+            meta: Self::Metadata,
+            pubkey_strs: Option<Vec<String>>,
+        ) -> Result<RpcPriorityFeeEstimate> {
+            let pubkey_strs = pubkey_strs.unwrap_or_default();
+            debug!(
+                "get_priority_fee_estimate rpc request received: {:?} pubkeys",
+                pubkey_strs.len()
+            );
+            if pubkey_strs.len() > MAX_TX_ACCOUNT_LOCKS {
+                return Err(Error::invalid_params(format!(
+                    "Too many inputs provided; max {MAX_TX_ACCOUNT_LOCKS}"
+                )));
+            }
+            let pubkeys = pubkey_strs
+                .into_iter()
+                .map(|pubkey_str| verify_pubkey(&pubkey_str))
+                .collect::<Result<Vec<_>>>()?;
+            meta.get_priority_fee_estimate(pubkeys)
+        }
     }
 }
 
@@ -4327,6 +4533,7 @@ pub mod tests {
         solana_rpc_client_api::{
             custom_error::{
                 JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE,
+                JSON_RPC_SERVER_ERROR_HISTORICAL_ACCOUNTS_UNAVAILABLE,
                 JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE,
                 JSON_RPC_SERVER_ERROR_UNSUPPORTED_TRANSACTION_VERSION,
             },
@@ -5275,6 +5482,56 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_rpc_get_account_info_at_slot() {
+        let rpc = RpcHandler::start();
+
+        // Banks in BankForks are served directly
+        let request = create_test_request(
+            "getAccountInfoAtSlot",
+            Some(json!([rpc.mint_keypair.pubkey().to_string(), 0])),
+        );
+        let result: Value = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result["context"]["slot"], 0);
+        assert_eq!(result["value"]["lamports"], TEST_MINT_LAMPORTS);
+
+        let request = create_test_request(
+            "getAccountInfoAtSlot",
+            Some(json!([rpc.mint_keypair.pubkey().to_string(), 5])),
+        );
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        let expected = (
+            ErrorCode::InvalidParams.code(),
+            String::from("Historical account state is not enabled on this node"),
+        );
+        assert_eq!(response, expected);
+
+        // Slots which are not rooted in the blockstore are never loaded
+        let working_dir = get_tmp_ledger_path!();
+        let rpc = RpcHandler::start_with_config(JsonRpcConfig {
+            historical_accounts_config: Some(HistoricalAccountsConfig {
+                snapshot_archives_dir: working_dir.clone(),
+                working_dir,
+                cache_max_bytes: 0,
+                max_pending_loads: 1,
+            }),
+            ..JsonRpcConfig::default()
+        });
+        let request = create_test_request(
+            "getAccountInfoAtSlot",
+            Some(json!([rpc.mint_keypair.pubkey().to_string(), 5])),
+        );
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        let expected = (
+            JSON_RPC_SERVER_ERROR_HISTORICAL_ACCOUNTS_UNAVAILABLE,
+            String::from(
+                "Account state at slot 5 is unavailable: slot 5 is not rooted in the local \
+                 blockstore",
+            ),
+        );
+        assert_eq!(response, expected);
+    }
+
     #[test]
     fn test_encode_account_does_not_throw_when_slice_larger_than_account() {
         let data = vec![42; 5];
@@ -5704,6 +5961,36 @@ pub mod tests {
             .expect("actual response deserialization");
         assert_eq!(result, expected);
 
+        // Execution trace with enableTrace=true
+        let request = create_test_request(
+            "simulateTransaction",
+            Some(json!([tx_serialized_encoded, {"enableTrace": true}])),
+        );
+        let result: RpcResponse<RpcSimulateTransactionResult> =
+            parse_success_result(rpc.handle_request_sync(request));
+        let trace = result.value.trace.expect("trace");
+        assert!(!trace.truncated);
+        assert_eq!(trace.instructions.len(), 1);
+        let instruction = &trace.instructions[0];
+        assert_eq!(instruction.program_id, system_program::id().to_string());
+        assert_eq!(instruction.stack_height, 1);
+        assert_eq!(instruction.compute_units_consumed, 150);
+        assert_eq!(instruction.accounts.len(), 2);
+        let from = &instruction.accounts[0];
+        assert_eq!(from.pubkey, rpc.mint_keypair.pubkey().to_string());
+        assert_eq!(from.pre_lamports - from.post_lamports, rent_exempt_amount);
+        assert_eq!(
+            instruction.accounts[1],
+            RpcAccountTrace {
+                pubkey: bob_pubkey.to_string(),
+                pre_lamports: 0,
+                post_lamports: rent_exempt_amount,
+                pre_data_len: 0,
+                post_data_len: 0,
+                data_changes: vec![],
+            }
+        );
+
         // Too many input accounts...
         let req = format!(
             r#"{{"jsonrpc":"2.0",
@@ -8866,4 +9153,93 @@ pub mod tests {
             ],
         );
     }
+
+    #[test]
+    fn test_rpc_get_priority_fee_estimate() {
+        let rpc = RpcHandler::start();
+        let slot0 = rpc.working_bank().slot();
+        let bank0_id = rpc.working_bank().bank_id();
+        let account0 = Pubkey::new_unique();
+        let account1 = Pubkey::new_unique();
+        let account2 = Pubkey::new_unique();
+        let transfer = |to: &Pubkey, compute_unit_price: Option<u64>| {
+            let mut instructions = vec![system_instruction::transfer(&account0, to, 1)];
+            if let Some(compute_unit_price) = compute_unit_price {
+                instructions.push(ComputeBudgetInstruction::set_compute_unit_price(
+                    compute_unit_price,
+                ));
+            }
+            Transaction::new_unsigned(Message::new(&instructions, Some(&account0)))
+        };
+        rpc.update_prioritization_fee_cache(vec![
+            transfer(&account1, Some(42)),
+            transfer(&account2, None),
+            transfer(&account1, Some(7)),
+        ]);
+        let cache = rpc.get_prioritization_fee_cache();
+        cache.finalize_priority_fee(slot0, bank0_id);
+        while cache.available_block_count() < 1 {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+
+        let request = create_test_request(
+            "getPriorityFeeEstimate",
+            Some(json!([[account1.to_string(), account2.to_string()]])),
+        );
+        let response: RpcPriorityFeeEstimate =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(
+            response,
+            RpcPriorityFeeEstimate {
+                slot_count: 1,
+                transaction_count: 3,
+                block: RpcPriorityFeeLevels {
+                    p25: 0,
+                    p50: 7,
+                    p75: 42,
+                    p90: 42,
+                    max: 42,
+                },
+                accounts: vec![
+                    RpcAccountPriorityFeeEstimate {
+                        account: account1.to_string(),
+                        transaction_count: 2,
+                        levels: RpcPriorityFeeLevels {
+                            p25: 7,
+                            p50: 7,
+                            p75: 42,
+                            p90: 42,
+                            max: 42,
+                        },
+                    },
+                    RpcAccountPriorityFeeEstimate {
+                        account: account2.to_string(),
+                        transaction_count: 1,
+                        levels: RpcPriorityFeeLevels::default(),
+                    },
+                ],
+                estimate: RpcPriorityFeeLevels {
+                    p25: 7,
+                    p50: 7,
+                    p75: 42,
+                    p90: 42,
+                    max: 42,
+                },
+            }
+        );
+
+        let request = create_test_request(
+            "getPriorityFeeEstimate",
+            Some(json!([vec![
+                Pubkey::new_unique().to_string();
+                MAX_TX_ACCOUNT_LOCKS + 1
+            ]])),
+        );
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        let expected = (
+            ErrorCode::InvalidParams.code(),
+            format!("Too many inputs provided; max {MAX_TX_ACCOUNT_LOCKS}"),
+        );
+        assert_eq!(response, expected);
+    }
 }
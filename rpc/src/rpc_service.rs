@@ -37,8 +37,9 @@ use {
         native_token::lamports_to_sol,
     },
     solana_send_transaction_service::send_transaction_service::{self, SendTransactionService},
-    solana_storage_bigtable::CredentialType,
+    solana_storage_bigtable::{CredentialType, LedgerStorageConfig, LongTermLedgerStorageConfig},
     std::{
+        io::SeekFrom,
         net::SocketAddr,
         path::{Path, PathBuf},
         sync::{
@@ -47,6 +48,7 @@ use {
         },
         thread::{self, Builder, JoinHandle},
     },
+    tokio::io::{AsyncReadExt, AsyncSeekExt},
     tokio_util::codec::{BytesCodec, FramedRead},
 };
 
@@ -117,6 +119,41 @@ impl RpcRequestMiddleware {
             .unwrap()
     }
 
+    fn range_not_satisfiable(file_length: u64) -> hyper::Response<hyper::Body> {
+        hyper::Response::builder()
+            .status(hyper::StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(
+                hyper::header::CONTENT_RANGE,
+                format!("bytes */{file_length}"),
+            )
+            .body(hyper::Body::empty())
+            .unwrap()
+    }
+
+    /// Parses a `Range: bytes=<first>-[<last>]` request header into the first and last byte
+    /// offsets it asks for, clamped to the file.
+    ///
+    /// Returns `None` for ranges which aren't supported (multiple ranges, or suffix ranges), so the
+    /// whole file is sent instead, and `Some(Err(()))` for ranges past the end of the file.
+    fn parse_byte_range(range: &str, file_length: u64) -> Option<Result<(u64, u64), ()>> {
+        let (first, last) = range.strip_prefix("bytes=")?.split_once('-')?;
+        if last.contains(',') {
+            return None;
+        }
+        let first = first.trim().parse::<u64>().ok()?;
+        let last = match last.trim() {
+            "" => u64::MAX,
+            last => last.parse::<u64>().ok()?,
+        };
+        if first > last {
+            return None;
+        }
+        if first >= file_length {
+            return Some(Err(()));
+        }
+        Some(Ok((first, last.min(file_length - 1))))
+    }
+
     fn strip_leading_slash(path: &str) -> Option<&str> {
         path.strip_prefix('/')
     }
@@ -184,7 +221,7 @@ impl RpcRequestMiddleware {
         }
     }
 
-    fn process_file_get(&self, path: &str) -> RequestMiddlewareAction {
+    fn process_file_get(&self, path: &str, range: Option<&str>) -> RequestMiddlewareAction {
         let filename = {
             let stem = Self::strip_leading_slash(path).expect("path already verified");
             match path {
@@ -199,30 +236,56 @@ impl RpcRequestMiddleware {
             }
         };
 
-        let file_length = std::fs::metadata(&filename)
-            .map(|m| m.len())
-            .unwrap_or(0)
-            .to_string();
-        info!("get {} -> {:?} ({} bytes)", path, filename, file_length);
+        let file_length = std::fs::metadata(&filename).map(|m| m.len()).unwrap_or(0);
+        let byte_range = range.and_then(|range| Self::parse_byte_range(range, file_length));
+        info!(
+            "get {} -> {:?} ({} bytes, range {:?})",
+            path, filename, file_length, byte_range
+        );
         RequestMiddlewareAction::Respond {
             should_validate_hosts: true,
-            response: Box::pin(async {
+            response: Box::pin(async move {
                 match Self::open_no_follow(filename).await {
                     Err(err) => Ok(if err.kind() == std::io::ErrorKind::NotFound {
                         Self::not_found()
                     } else {
                         Self::internal_server_error()
                     }),
-                    Ok(file) => {
-                        let stream =
-                            FramedRead::new(file, BytesCodec::new()).map_ok(|b| b.freeze());
-                        let body = hyper::Body::wrap_stream(stream);
-
-                        Ok(hyper::Response::builder()
-                            .header(hyper::header::CONTENT_LENGTH, file_length)
-                            .body(body)
-                            .unwrap())
-                    }
+                    Ok(mut file) => match byte_range {
+                        None => {
+                            let stream =
+                                FramedRead::new(file, BytesCodec::new()).map_ok(|b| b.freeze());
+                            let body = hyper::Body::wrap_stream(stream);
+
+                            Ok(hyper::Response::builder()
+                                .header(hyper::header::CONTENT_LENGTH, file_length)
+                                .header(hyper::header::ACCEPT_RANGES, "bytes")
+                                .body(body)
+                                .unwrap())
+                        }
+                        Some(Err(())) => Ok(Self::range_not_satisfiable(file_length)),
+                        Some(Ok((first, last))) => {
+                            if file.seek(SeekFrom::Start(first)).await.is_err() {
+                                return Ok(Self::internal_server_error());
+                            }
+                            let range_length = last - first + 1;
+                            let stream =
+                                FramedRead::new(file.take(range_length), BytesCodec::new())
+                                    .map_ok(|b| b.freeze());
+                            let body = hyper::Body::wrap_stream(stream);
+
+                            Ok(hyper::Response::builder()
+                                .status(hyper::StatusCode::PARTIAL_CONTENT)
+                                .header(hyper::header::CONTENT_LENGTH, range_length)
+                                .header(
+                                    hyper::header::CONTENT_RANGE,
+                                    format!("bytes {first}-{last}/{file_length}"),
+                                )
+                                .header(hyper::header::ACCEPT_RANGES, "bytes")
+                                .body(body)
+                                .unwrap())
+                        }
+                    },
                 }
             }),
         }
@@ -294,7 +357,11 @@ impl RequestMiddleware for RpcRequestMiddleware {
                 .unwrap()
                 .into()
         } else if self.is_file_get_path(request.uri().path()) {
-            self.process_file_get(request.uri().path())
+            let range = request
+                .headers()
+                .get(hyper::header::RANGE)
+                .and_then(|range| range.to_str().ok());
+            self.process_file_get(request.uri().path(), range)
         } else if request.uri().path() == "/health" {
             hyper::Response::builder()
                 .status(hyper::StatusCode::OK)
@@ -403,22 +470,27 @@ impl JsonRpcService {
                 ref bigtable_app_profile_id,
                 timeout,
                 max_message_size,
+                ref local_ledger_storage_path,
             }) = config.rpc_bigtable_config
             {
-                let bigtable_config = solana_storage_bigtable::LedgerStorageConfig {
-                    read_only: !enable_bigtable_ledger_upload,
-                    timeout,
-                    credential_type: CredentialType::Filepath(None),
-                    instance_name: bigtable_instance_name.clone(),
-                    app_profile_id: bigtable_app_profile_id.clone(),
-                    max_message_size,
+                let storage_config = match local_ledger_storage_path {
+                    Some(path) => LongTermLedgerStorageConfig::Local {
+                        path: path.clone(),
+                        read_only: !enable_bigtable_ledger_upload,
+                    },
+                    None => LongTermLedgerStorageConfig::BigTable(LedgerStorageConfig {
+                        read_only: !enable_bigtable_ledger_upload,
+                        timeout,
+                        credential_type: CredentialType::Filepath(None),
+                        instance_name: bigtable_instance_name.clone(),
+                        app_profile_id: bigtable_app_profile_id.clone(),
+                        max_message_size,
+                    }),
                 };
                 runtime
-                    .block_on(solana_storage_bigtable::LedgerStorage::new_with_config(
-                        bigtable_config,
-                    ))
+                    .block_on(storage_config.open())
                     .map(|bigtable_ledger_storage| {
-                        info!("BigTable ledger storage initialized");
+                        info!("Long-term ledger storage initialized");
 
                         let bigtable_ledger_upload_service = if enable_bigtable_ledger_upload {
                             Some(Arc::new(BigTableUploadService::new_with_config(
@@ -441,7 +513,7 @@ impl JsonRpcService {
                         )
                     })
                     .unwrap_or_else(|err| {
-                        error!("Failed to initialize BigTable ledger storage: {:?}", err);
+                        error!("Failed to initialize long-term ledger storage: {:?}", err);
                         (None, None)
                     })
             } else {
@@ -824,6 +896,20 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_parse_byte_range() {
+        let parse = RpcRequestMiddleware::parse_byte_range;
+        assert_eq!(parse("bytes=0-", 10), Some(Ok((0, 9))));
+        assert_eq!(parse("bytes=2-5", 10), Some(Ok((2, 5))));
+        assert_eq!(parse("bytes=2-100", 10), Some(Ok((2, 9))));
+        assert_eq!(parse("bytes=9-9", 10), Some(Ok((9, 9))));
+        assert_eq!(parse("bytes=10-", 10), Some(Err(())));
+        assert_eq!(parse("bytes=5-2", 10), None);
+        assert_eq!(parse("bytes=-5", 10), None);
+        assert_eq!(parse("bytes=0-1,4-5", 10), None);
+        assert_eq!(parse("items=0-5", 10), None);
+    }
+
     #[test]
     fn test_process_file_get() {
         let runtime = Runtime::new().unwrap();
@@ -842,7 +928,7 @@ mod tests {
         );
 
         // File does not exist => request should fail.
-        let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH);
+        let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH, None);
         if let RequestMiddlewareAction::Respond { response, .. } = action {
             let response = runtime.block_on(response);
             let response = response.unwrap();
@@ -857,7 +943,7 @@ mod tests {
         }
 
         // Normal file exist => request should succeed.
-        let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH);
+        let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH, None);
         if let RequestMiddlewareAction::Respond { response, .. } = action {
             let response = runtime.block_on(response);
             let response = response.unwrap();
@@ -866,6 +952,34 @@ mod tests {
             panic!("Unexpected RequestMiddlewareAction variant");
         }
 
+        // Byte range of a normal file => request should return just that range.
+        let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH, Some("bytes=7-"));
+        if let RequestMiddlewareAction::Respond { response, .. } = action {
+            let response = runtime.block_on(response);
+            let response = response.unwrap();
+            assert_eq!(response.status(), 206);
+            assert_eq!(
+                response.headers()[hyper::header::CONTENT_RANGE],
+                "bytes 7-11/12"
+            );
+            let body = runtime
+                .block_on(hyper::body::to_bytes(response.into_body()))
+                .unwrap();
+            assert_eq!(&body[..], b"be ok");
+        } else {
+            panic!("Unexpected RequestMiddlewareAction variant");
+        }
+
+        // Byte range past the end of a normal file => request should fail.
+        let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH, Some("bytes=12-"));
+        if let RequestMiddlewareAction::Respond { response, .. } = action {
+            let response = runtime.block_on(response);
+            let response = response.unwrap();
+            assert_eq!(response.status(), 416);
+        } else {
+            panic!("Unexpected RequestMiddlewareAction variant");
+        }
+
         std::fs::remove_file(&genesis_path).unwrap();
         {
             let mut file = std::fs::File::create(ledger_path.path().join("wrong")).unwrap();
@@ -874,7 +988,7 @@ mod tests {
         symlink::symlink_file("wrong", &genesis_path).unwrap();
 
         // File is a symbolic link => request should fail.
-        let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH);
+        let action = rrm.process_file_get(DEFAULT_GENESIS_DOWNLOAD_PATH, None);
         if let RequestMiddlewareAction::Respond { response, .. } = action {
             let response = runtime.block_on(response);
             let response = response.unwrap();
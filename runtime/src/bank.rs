@@ -91,7 +91,8 @@ use {
     solana_loader_v4_program::create_program_runtime_environment_v2,
     solana_measure::{measure::Measure, measure_time, measure_us},
     solana_program_runtime::{
-        invoke_context::BuiltinFunctionWithContext, loaded_programs::ProgramCacheEntry,
+        execution_trace::ExecutionTrace, invoke_context::BuiltinFunctionWithContext,
+        loaded_programs::ProgramCacheEntry,
     },
     solana_runtime_transaction::instructions_processor::process_compute_budget_instructions,
     solana_sdk::{
@@ -335,6 +336,7 @@ pub struct TransactionSimulationResult {
     pub units_consumed: u64,
     pub return_data: Option<TransactionReturnData>,
     pub inner_instructions: Option<Vec<InnerInstructions>>,
+    pub execution_trace: Option<ExecutionTrace>,
 }
 pub struct TransactionBalancesSet {
     pub pre_balances: TransactionBalances,
@@ -1000,6 +1002,8 @@ impl Bank {
         bank.ancestors = Ancestors::from(vec![bank.slot()]);
         bank.compute_budget = runtime_config.compute_budget;
         bank.transaction_account_lock_limit = runtime_config.transaction_account_lock_limit;
+        bank.transaction_processor
+            .set_program_coverage(runtime_config.program_coverage.clone());
         bank.transaction_debug_keys = debug_keys;
         bank.cluster_type = Some(genesis_config.cluster_type);
 
@@ -1585,6 +1589,8 @@ impl Bank {
 
         bank.transaction_processor =
             TransactionBatchProcessor::new(bank.slot, bank.epoch, HashSet::default());
+        bank.transaction_processor
+            .set_program_coverage(runtime_config.program_coverage.clone());
 
         let thread_pool = ThreadPoolBuilder::new()
             .thread_name(|i| format!("solBnkNewFlds{i:02}"))
@@ -3304,10 +3310,15 @@ impl Bank {
         &self,
         transaction: &SanitizedTransaction,
         enable_cpi_recording: bool,
+        enable_execution_trace: bool,
     ) -> TransactionSimulationResult {
         assert!(self.is_frozen(), "simulation bank must be frozen");
 
-        self.simulate_transaction_unchecked(transaction, enable_cpi_recording)
+        self.simulate_transaction_unchecked(
+            transaction,
+            enable_cpi_recording,
+            enable_execution_trace,
+        )
     }
 
     /// Run transactions against a bank without committing the results; does not check if the bank
@@ -3316,6 +3327,7 @@ impl Bank {
         &self,
         transaction: &SanitizedTransaction,
         enable_cpi_recording: bool,
+        enable_execution_trace: bool,
     ) -> TransactionSimulationResult {
         let account_keys = transaction.message().account_keys();
         let number_of_accounts = account_keys.len();
@@ -3344,6 +3356,7 @@ impl Bank {
                     enable_cpi_recording,
                     enable_log_recording: true,
                     enable_return_data_recording: true,
+                    enable_execution_trace,
                 },
                 transaction_account_lock_limit: Some(self.get_transaction_account_lock_limit()),
             },
@@ -3365,7 +3378,7 @@ impl Bank {
             .pop()
             .unwrap_or(Err(TransactionError::InvalidProgramForExecution));
         let flattened_result = processing_result.flattened_result();
-        let (post_simulation_accounts, logs, return_data, inner_instructions) =
+        let (post_simulation_accounts, logs, return_data, inner_instructions, execution_trace) =
             match processing_result {
                 Ok(processed_tx) => {
                     let details = processed_tx.execution_details;
@@ -3380,9 +3393,10 @@ impl Bank {
                         details.log_messages,
                         details.return_data,
                         details.inner_instructions,
+                        details.execution_trace,
                     )
                 }
-                Err(_) => (vec![], None, None, None),
+                Err(_) => (vec![], None, None, None, None),
             };
         let logs = logs.unwrap_or_default();
 
@@ -3393,6 +3407,7 @@ impl Bank {
             units_consumed,
             return_data,
             inner_instructions,
+            execution_trace,
         }
     }
 
@@ -4640,6 +4655,7 @@ impl Bank {
                 enable_cpi_recording: false,
                 enable_log_recording: true,
                 enable_return_data_recording: true,
+                enable_execution_trace: false,
             },
             &mut ExecuteTimings::default(),
             Some(1000 * 1000),
@@ -4767,6 +4783,23 @@ impl Bank {
             .flush_accounts_cache(false, Some(self.slot()))
     }
 
+    /// Stores `accounts` unless this bank is frozen, issuing or burning lamports to keep the
+    /// capitalization consistent. Returns whether the accounts were stored.
+    ///
+    /// This is meant for development tools rewriting the state of a local cluster, like the
+    /// checkpoints of `solana-test-validator`: the bank diverges from any other node's.
+    pub fn store_accounts_unless_frozen(&self, accounts: &[(Pubkey, AccountSharedData)]) -> bool {
+        // Holding the freeze lock keeps the bank from being frozen meanwhile
+        let freeze_lock = self.freeze_lock();
+        if *freeze_lock != Hash::default() {
+            return false;
+        }
+        for (pubkey, account) in accounts {
+            self.store_account_and_update_capitalization(pubkey, account);
+        }
+        true
+    }
+
     /// Technically this issues (or even burns!) new lamports,
     /// so be extra careful for its usage
     fn store_account_and_update_capitalization(
@@ -4865,6 +4898,8 @@ impl Bank {
             }
         }
 
+        // The instruction trace is only recorded when collecting program coverage
+        let debugging_features = self.transaction_processor.program_coverage().is_some();
         let mut program_cache = self.transaction_processor.program_cache.write().unwrap();
         program_cache.latest_root_slot = self.slot();
         program_cache.latest_root_epoch = self.epoch();
@@ -4873,14 +4908,14 @@ impl Bank {
                 &self.feature_set,
                 &self.compute_budget().unwrap_or_default(),
                 false, /* deployment */
-                false, /* debugging_features */
+                debugging_features,
             )
             .unwrap(),
         );
         program_cache.environments.program_runtime_v2 =
             Arc::new(create_program_runtime_environment_v2(
                 &self.compute_budget().unwrap_or_default(),
-                false, /* debugging_features */
+                debugging_features,
             ));
     }
 
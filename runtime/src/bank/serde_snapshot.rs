@@ -59,7 +59,8 @@ mod tests {
         for storage_entry in storage_entries.into_iter() {
             // Copy file to new directory
             let storage_path = storage_entry.path();
-            let file_name = AccountsFile::file_name(storage_entry.slot(), storage_entry.id());
+            let format = storage_entry.accounts.format();
+            let file_name = format.file_name(storage_entry.slot(), storage_entry.id());
             let output_path = output_dir.as_ref().join(file_name);
             std::fs::copy(storage_path, &output_path)?;
 
@@ -68,6 +69,7 @@ mod tests {
                 output_path,
                 storage_entry.accounts.len(),
                 storage_access,
+                format,
             )?;
             let new_storage_entry = AccountStorageEntry::new_existing(
                 storage_entry.slot(),
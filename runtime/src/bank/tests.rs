@@ -250,6 +250,7 @@ fn new_processing_result(
             return_data: None,
             executed_units: 0,
             accounts_data_len_delta: 0,
+            execution_trace: None,
         },
         programs_modified_by_tx: HashMap::new(),
     })
@@ -9080,6 +9081,7 @@ fn test_tx_log_order() {
                 enable_cpi_recording: false,
                 enable_log_recording: true,
                 enable_return_data_recording: false,
+                enable_execution_trace: false,
             },
             &mut ExecuteTimings::default(),
             None,
@@ -9188,6 +9190,7 @@ fn test_tx_return_data() {
                     enable_cpi_recording: false,
                     enable_log_recording: false,
                     enable_return_data_recording: true,
+                    enable_execution_trace: false,
                 },
                 &mut ExecuteTimings::default(),
                 None,
@@ -12768,7 +12771,7 @@ fn test_failed_simulation_compute_units() {
 
     bank.freeze();
     let sanitized = SanitizedTransaction::from_transaction_for_tests(transaction);
-    let simulation = bank.simulate_transaction(&sanitized, false);
+    let simulation = bank.simulate_transaction(&sanitized, false, false);
     assert_eq!(expected_consumed_units, simulation.units_consumed);
 }
 
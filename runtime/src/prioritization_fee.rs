@@ -144,6 +144,14 @@ pub struct PrioritizationFee {
     // The minimum prioritization fee of each writable account in transactions in this block.
     min_writable_account_fees: HashMap<Pubkey, u64>,
 
+    // The prioritization fee of each transaction that landed in this block, sorted once the
+    // block is completed.
+    transaction_fees: Vec<u64>,
+
+    // The prioritization fees of the transactions writing to each account in this block, sorted
+    // once the block is completed.
+    writable_account_transaction_fees: HashMap<Pubkey, Vec<u64>>,
+
     // Default to `false`, set to `true` when a block is completed, therefore the minimum fees recorded
     // are finalized, and can be made available for use (e.g., RPC query)
     is_finalized: bool,
@@ -157,6 +165,8 @@ impl Default for PrioritizationFee {
         PrioritizationFee {
             min_transaction_fee: u64::MAX,
             min_writable_account_fees: HashMap::new(),
+            transaction_fees: Vec::new(),
+            writable_account_transaction_fees: HashMap::new(),
             is_finalized: false,
             metrics: PrioritizationFeeMetrics::default(),
         }
@@ -171,8 +181,13 @@ impl PrioritizationFee {
                 if transaction_fee < self.min_transaction_fee {
                     self.min_transaction_fee = transaction_fee;
                 }
+                self.transaction_fees.push(transaction_fee);
 
                 for write_account in writable_accounts {
+                    self.writable_account_transaction_fees
+                        .entry(write_account)
+                        .or_default()
+                        .push(transaction_fee);
                     self.min_writable_account_fees
                         .entry(write_account)
                         .and_modify(|write_lock_fee| {
@@ -207,6 +222,10 @@ impl PrioritizationFee {
             return Err(PrioritizationFeeError::BlockIsAlreadyFinalized);
         }
         self.prune_irrelevant_writable_accounts();
+        self.transaction_fees.sort_unstable();
+        self.writable_account_transaction_fees
+            .values_mut()
+            .for_each(|fees| fees.sort_unstable());
         self.is_finalized = true;
         Ok(())
     }
@@ -223,6 +242,22 @@ impl PrioritizationFee {
         self.min_writable_account_fees.iter()
     }
 
+    /// The prioritization fees of all transactions in this block, in ascending order once the
+    /// block is completed.
+    pub fn get_transaction_fees(&self) -> &[u64] {
+        &self.transaction_fees
+    }
+
+    /// The prioritization fees of the transactions writing to `key` in this block, in ascending
+    /// order once the block is completed. Unlike the minimum fees, these are kept for every
+    /// writable account, as percentiles cannot be derived from the block's fees.
+    pub fn get_writable_account_transaction_fees(&self, key: &Pubkey) -> &[u64] {
+        self.writable_account_transaction_fees
+            .get(key)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
     pub fn get_writable_accounts_count(&self) -> usize {
         self.min_writable_account_fees.len()
     }
@@ -367,6 +402,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_transaction_fee_distributions() {
+        let write_account_a = Pubkey::new_unique();
+        let write_account_b = Pubkey::new_unique();
+        let write_account_c = Pubkey::new_unique();
+
+        let mut prioritization_fee = PrioritizationFee::default();
+        prioritization_fee.update(5, vec![write_account_a, write_account_b]);
+        prioritization_fee.update(9, vec![write_account_b, write_account_c]);
+        prioritization_fee.update(2, vec![write_account_a]);
+        prioritization_fee.update(0, vec![write_account_c]);
+        assert!(prioritization_fee.mark_block_completed().is_ok());
+
+        // unlike the minimum fees, the distributions are kept for every account, including the
+        // ones whose minimum fee is the block minimum
+        assert!(prioritization_fee
+            .get_writable_account_fee(&write_account_c)
+            .is_none());
+        assert_eq!(
+            prioritization_fee.get_writable_account_transaction_fees(&write_account_c),
+            &[0, 9]
+        );
+        assert_eq!(prioritization_fee.get_transaction_fees(), &[0, 2, 5, 9]);
+        assert_eq!(
+            prioritization_fee.get_writable_account_transaction_fees(&write_account_a),
+            &[2, 5]
+        );
+        assert_eq!(
+            prioritization_fee.get_writable_account_transaction_fees(&write_account_b),
+            &[5, 9]
+        );
+        assert!(prioritization_fee
+            .get_writable_account_transaction_fees(&Pubkey::new_unique())
+            .is_empty());
+    }
+
     #[test]
     fn test_mark_block_completed() {
         let mut prioritization_fee = PrioritizationFee::default();
@@ -124,6 +124,41 @@ impl PrioritizationFeeCacheMetrics {
     }
 }
 
+/// Prioritization fees paid by a set of transactions, in ascending order
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PrioritizationFeeDistribution {
+    fees: Vec<u64>,
+}
+
+impl PrioritizationFeeDistribution {
+    fn new(mut fees: Vec<u64>) -> Self {
+        fees.sort_unstable();
+        Self { fees }
+    }
+
+    /// Returns the number of transactions in the distribution
+    pub fn len(&self) -> usize {
+        self.fees.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fees.is_empty()
+    }
+
+    /// Returns the fee at `percentile` (clamped to 100) using the nearest-rank method, or None if
+    /// the distribution is empty.
+    pub fn percentile(&self, percentile: u8) -> Option<u64> {
+        let rank = (usize::from(percentile.min(100)) * self.fees.len())
+            .div_ceil(100)
+            .max(1);
+        self.fees.get(rank - 1).copied()
+    }
+
+    pub fn max(&self) -> Option<u64> {
+        self.fees.last().copied()
+    }
+}
+
 #[derive(Debug)]
 enum CacheServiceUpdate {
     TransactionUpdate {
@@ -416,6 +451,39 @@ impl PrioritizationFeeCache {
             })
             .collect()
     }
+
+    /// Returns the distribution of the prioritization fees of all transactions in the cached
+    /// blocks, along with the distribution for the transactions writing to each of `account_keys`.
+    pub fn get_prioritization_fee_distributions(
+        &self,
+        account_keys: &[Pubkey],
+    ) -> (
+        PrioritizationFeeDistribution,
+        Vec<PrioritizationFeeDistribution>,
+    ) {
+        let cache = self.cache.read().unwrap();
+        let block_fees = cache
+            .values()
+            .flat_map(|slot_prioritization_fee| slot_prioritization_fee.get_transaction_fees())
+            .copied()
+            .collect();
+        let account_fees = account_keys
+            .iter()
+            .map(|account_key| {
+                PrioritizationFeeDistribution::new(
+                    cache
+                        .values()
+                        .flat_map(|slot_prioritization_fee| {
+                            slot_prioritization_fee
+                                .get_writable_account_transaction_fees(account_key)
+                        })
+                        .copied()
+                        .collect(),
+                )
+            })
+            .collect();
+        (PrioritizationFeeDistribution::new(block_fees), account_fees)
+    }
 }
 
 #[cfg(test)]
@@ -927,4 +995,72 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_prioritization_fee_distribution_percentile() {
+        let distribution = PrioritizationFeeDistribution::default();
+        assert!(distribution.is_empty());
+        assert_eq!(distribution.percentile(50), None);
+        assert_eq!(distribution.max(), None);
+
+        let distribution = PrioritizationFeeDistribution::new((1..=10).rev().collect());
+        assert_eq!(distribution.len(), 10);
+        assert_eq!(distribution.percentile(0), Some(1));
+        assert_eq!(distribution.percentile(25), Some(3));
+        assert_eq!(distribution.percentile(50), Some(5));
+        assert_eq!(distribution.percentile(75), Some(8));
+        assert_eq!(distribution.percentile(90), Some(9));
+        assert_eq!(distribution.percentile(100), Some(10));
+        assert_eq!(distribution.percentile(u8::MAX), Some(10));
+        assert_eq!(distribution.max(), Some(10));
+    }
+
+    #[test]
+    fn test_get_prioritization_fee_distributions() {
+        let write_account_a = Pubkey::new_unique();
+        let write_account_b = Pubkey::new_unique();
+
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank0 = Bank::new_for_benches(&genesis_config);
+        let bank_forks = BankForks::new_rw_arc(bank0);
+        let bank = bank_forks.read().unwrap().working_bank();
+        let collector = solana_sdk::pubkey::new_rand();
+        let bank1 = Arc::new(Bank::new_from_parent(bank.clone(), &collector, 1));
+        let bank2 = Arc::new(Bank::new_from_parent(bank, &collector, 2));
+
+        let prioritization_fee_cache = PrioritizationFeeCache::default();
+        let txs = vec![
+            build_sanitized_transaction_for_test(5, &write_account_a, &write_account_b),
+            build_sanitized_transaction_for_test(1, &Pubkey::new_unique(), &write_account_b),
+        ];
+        sync_update(&prioritization_fee_cache, bank1.clone(), txs.iter());
+        sync_finalize_priority_fee_for_test(&prioritization_fee_cache, 1, bank1.bank_id());
+
+        let txs = vec![
+            build_sanitized_transaction_for_test(3, &write_account_a, &Pubkey::new_unique()),
+            build_sanitized_transaction_for_test(8, &Pubkey::new_unique(), &Pubkey::new_unique()),
+        ];
+        sync_update(&prioritization_fee_cache, bank2.clone(), txs.iter());
+        sync_finalize_priority_fee_for_test(&prioritization_fee_cache, 2, bank2.bank_id());
+
+        // fees are gathered across all cached slots
+        let (block_fees, account_fees) = prioritization_fee_cache
+            .get_prioritization_fee_distributions(&[
+                write_account_a,
+                write_account_b,
+                Pubkey::new_unique(),
+            ]);
+        assert_eq!(
+            block_fees,
+            PrioritizationFeeDistribution::new(vec![1, 3, 5, 8])
+        );
+        assert_eq!(
+            account_fees,
+            vec![
+                PrioritizationFeeDistribution::new(vec![3, 5]),
+                PrioritizationFeeDistribution::new(vec![1, 5]),
+                PrioritizationFeeDistribution::default(),
+            ]
+        );
+    }
 }
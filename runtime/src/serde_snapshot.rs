@@ -9,6 +9,7 @@ use {
         epoch_stakes::{EpochStakes, VersionedEpochStakes},
         runtime_config::RuntimeConfig,
         serde_snapshot::storage::SerializableAccountStorageEntry,
+        snapshot_hash::SnapshotHash,
         snapshot_utils::{SnapshotError, StorageAndNextAccountsFileId},
         stakes::{serde_stakes_to_delegation_format, Stakes, StakesEnum},
     },
@@ -22,8 +23,8 @@ use {
             AccountShrinkThreshold, AccountStorageEntry, AccountsDb, AccountsDbConfig,
             AccountsFileId, AtomicAccountsFileId, BankHashStats, IndexGenerationInfo,
         },
-        accounts_file::{AccountsFile, StorageAccess},
-        accounts_hash::{AccountsDeltaHash, AccountsHash},
+        accounts_file::{AccountsFile, AccountsFileFormat, StorageAccess},
+        accounts_hash::{AccountsDeltaHash, AccountsHash, AccountsHashKind},
         accounts_index::AccountSecondaryIndexes,
         accounts_update_notifier_interface::AccountsUpdateNotifier,
         ancestors::AncestorsForSerialization,
@@ -509,6 +510,28 @@ pub(crate) fn fields_from_stream<R: Read>(
     deserialize_bank_fields(snapshot_stream)
 }
 
+/// Returns the slot and snapshot hash recorded in a serialized bank snapshot file, which are
+/// what the snapshot archive's file name is built from
+pub(crate) fn slot_and_snapshot_hash_from_stream<R: Read>(
+    snapshot_stream: &mut BufReader<R>,
+) -> Result<(Slot, SnapshotHash), Error> {
+    let (bank_fields, AccountsDbFields(_, _, _, bank_hash_info, _, _)) =
+        deserialize_bank_fields(snapshot_stream)?;
+    // Incremental snapshots record their accounts hash in the incremental snapshot persistence
+    // field, and a default hash in `BankHashInfo`
+    let accounts_hash = match bank_fields.incremental_snapshot_persistence {
+        Some(incremental_snapshot_persistence) => {
+            AccountsHashKind::Incremental(incremental_snapshot_persistence.incremental_hash.into())
+        }
+        None => AccountsHashKind::Full(bank_hash_info.accounts_hash.into()),
+    };
+    let epoch_accounts_hash = bank_fields.epoch_accounts_hash.map(EpochAccountsHash::new);
+    Ok((
+        bank_fields.slot,
+        SnapshotHash::new(&accounts_hash, epoch_accounts_hash.as_ref()),
+    ))
+}
+
 pub(crate) fn fields_from_streams(
     snapshot_streams: &mut SnapshotStreams<impl Read>,
 ) -> std::result::Result<
@@ -893,9 +916,10 @@ pub(crate) fn reconstruct_single_storage(
     current_len: usize,
     append_vec_id: AccountsFileId,
     storage_access: StorageAccess,
+    format: AccountsFileFormat,
 ) -> Result<Arc<AccountStorageEntry>, SnapshotError> {
     let (accounts_file, num_accounts) =
-        AccountsFile::new_from_file(append_vec_path, current_len, storage_access)?;
+        AccountsFile::new_from_file(append_vec_path, current_len, storage_access, format)?;
     Ok(Arc::new(AccountStorageEntry::new_existing(
         *slot,
         append_vec_id,
@@ -918,6 +942,13 @@ pub(crate) fn remap_append_vec_file(
     let append_vec_path_cstr = cstring_from_path(append_vec_path)?;
 
     let mut remapped_append_vec_path = append_vec_path.to_path_buf();
+    // the remapped file keeps the format recorded in the original file name
+    let format = AccountsFileFormat::from_file_name(
+        append_vec_path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .unwrap_or_default(),
+    );
 
     // Break out of the loop in the following situations:
     // 1. The new ID is the same as the original ID.  This means we do not need to
@@ -932,7 +963,7 @@ pub(crate) fn remap_append_vec_file(
             break (remapped_append_vec_id, remapped_append_vec_path);
         }
 
-        let remapped_file_name = AccountsFile::file_name(slot, remapped_append_vec_id);
+        let remapped_file_name = format.file_name(slot, remapped_append_vec_id);
         remapped_append_vec_path = append_vec_path.parent().unwrap().join(remapped_file_name);
 
         #[cfg(all(target_os = "linux", target_env = "gnu"))]
@@ -985,6 +1016,7 @@ pub(crate) fn remap_and_reconstruct_single_storage(
     next_append_vec_id: &AtomicAccountsFileId,
     num_collisions: &AtomicUsize,
     storage_access: StorageAccess,
+    format: AccountsFileFormat,
 ) -> Result<Arc<AccountStorageEntry>, SnapshotError> {
     let (remapped_append_vec_id, remapped_append_vec_path) = remap_append_vec_file(
         slot,
@@ -999,6 +1031,7 @@ pub(crate) fn remap_and_reconstruct_single_storage(
         current_len,
         remapped_append_vec_id,
         storage_access,
+        format,
     )?;
     Ok(storage)
 }
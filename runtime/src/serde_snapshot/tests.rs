@@ -140,7 +140,8 @@ mod serde_snapshot_tests {
         for storage_entry in storage_entries.into_iter() {
             // Copy file to new directory
             let storage_path = storage_entry.path();
-            let file_name = AccountsFile::file_name(storage_entry.slot(), storage_entry.id());
+            let format = storage_entry.accounts.format();
+            let file_name = format.file_name(storage_entry.slot(), storage_entry.id());
             let output_path = output_dir.as_ref().join(file_name);
             std::fs::copy(storage_path, &output_path)?;
 
@@ -149,6 +150,7 @@ mod serde_snapshot_tests {
                 output_path,
                 storage_entry.accounts.len(),
                 storage_access,
+                format,
             )?;
             let new_storage_entry = AccountStorageEntry::new_existing(
                 storage_entry.slot(),
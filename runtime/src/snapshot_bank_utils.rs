@@ -1065,13 +1065,17 @@ mod tests {
             },
             status_cache::Status,
         },
+        assert_matches::assert_matches,
         solana_accounts_db::{
             accounts_db::ACCOUNTS_DB_CONFIG_FOR_TESTING,
+            accounts_file::{AccountsFile, AccountsFileProvider},
             accounts_hash::{CalcAccountsHashConfig, HashStats},
             sorted_storages::SortedStorages,
+            tiered_storage::{cold::COLD_FORMAT, hot::HOT_FORMAT},
         },
         solana_sdk::{
             genesis_config::create_genesis_config,
+            hash::Hash,
             native_token::{sol_to_lamports, LAMPORTS_PER_SOL},
             signature::{Keypair, Signer},
             system_transaction,
@@ -1190,6 +1194,185 @@ mod tests {
         assert_eq!(original_bank, roundtrip_bank);
     }
 
+    /// Test roundtrip of bank to a full snapshot, then back again, where the bank's storages are a
+    /// mix of hot storages and cold storages created by ancient packing.
+    #[test]
+    fn test_roundtrip_bank_to_and_from_full_snapshot_hot_and_cold_storages() {
+        let collector = Pubkey::new_unique();
+        let (genesis_config, mint_keypair) = create_genesis_config(sol_to_lamports(1_000_000.));
+        let accounts_db_config = AccountsDbConfig {
+            accounts_file_provider: AccountsFileProvider::HotStorage,
+            ancient_accounts_file_provider: Some(AccountsFileProvider::ColdStorage),
+            ..ACCOUNTS_DB_CONFIG_FOR_TESTING
+        };
+        let bank0 = Bank::new_with_paths(
+            &genesis_config,
+            Arc::<RuntimeConfig>::default(),
+            Vec::new(),
+            None,
+            None,
+            AccountSecondaryIndexes::default(),
+            AccountShrinkThreshold::default(),
+            false,
+            Some(accounts_db_config.clone()),
+            None,
+            None,
+            Arc::default(),
+            None,
+        );
+        let (mut bank, bank_forks) = bank0.wrap_with_bank_forks_for_tests();
+        for slot in 1..=4 {
+            bank =
+                new_bank_from_parent_with_bank_forks(bank_forks.as_ref(), bank, &collector, slot);
+            bank.transfer(sol_to_lamports(1.), &mint_keypair, &Pubkey::new_unique())
+                .unwrap();
+            bank.fill_bank_with_ticks_for_tests();
+            bank.squash();
+            bank.force_flush_accounts_cache();
+        }
+
+        // pack the oldest slots into cold storages, leaving the newer slots in hot storages
+        let accounts_db = &bank.rc.accounts.accounts_db;
+        accounts_db.combine_ancient_slots_packed_for_tests((0..=2).collect());
+
+        let assert_hot_and_cold = |bank: &Bank| {
+            let storages = bank.get_snapshot_storages(None);
+            let count_format = |format| {
+                storages
+                    .iter()
+                    .filter(|storage| {
+                        matches!(
+                            &storage.accounts,
+                            AccountsFile::TieredStorage(ts) if ts.format() == format
+                        )
+                    })
+                    .count()
+            };
+            assert!(count_format(&HOT_FORMAT) > 0);
+            assert!(count_format(&COLD_FORMAT) > 0);
+            assert_eq!(
+                count_format(&HOT_FORMAT) + count_format(&COLD_FORMAT),
+                storages.len()
+            );
+        };
+        assert_hot_and_cold(&bank);
+
+        let (_tmp_dir, accounts_dir) = create_tmp_accounts_dir_for_tests();
+        let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
+        let full_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let incremental_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let snapshot_archive_format = ArchiveFormat::Tar;
+
+        let full_snapshot_archive_info = bank_to_full_snapshot_archive(
+            bank_snapshots_dir.path(),
+            &bank,
+            None,
+            full_snapshot_archives_dir.path(),
+            incremental_snapshot_archives_dir.path(),
+            snapshot_archive_format,
+        )
+        .unwrap();
+
+        let (roundtrip_bank, _) = bank_from_snapshot_archives(
+            &[accounts_dir],
+            bank_snapshots_dir.path(),
+            &full_snapshot_archive_info,
+            None,
+            &genesis_config,
+            &RuntimeConfig::default(),
+            None,
+            None,
+            AccountSecondaryIndexes::default(),
+            None,
+            AccountShrinkThreshold::default(),
+            false,
+            false,
+            false,
+            false,
+            Some(accounts_db_config),
+            None,
+            Arc::default(),
+        )
+        .unwrap();
+        roundtrip_bank.wait_for_initial_accounts_hash_verification_completed_for_tests();
+        assert_eq!(*bank, roundtrip_bank);
+        assert_hot_and_cold(&roundtrip_bank);
+    }
+
+    #[test]
+    fn test_verify_snapshot_archive_hash() {
+        let collector = Pubkey::new_unique();
+        let (genesis_config, _mint_keypair) = create_genesis_config(sol_to_lamports(1_000_000.));
+        let (bank0, bank_forks) = Bank::new_with_bank_forks_for_tests(&genesis_config);
+        while !bank0.is_complete() {
+            bank0.register_unique_tick();
+        }
+        let bank1 = new_bank_from_parent_with_bank_forks(bank_forks.as_ref(), bank0, &collector, 1);
+        while !bank1.is_complete() {
+            bank1.register_unique_tick();
+        }
+
+        let bank_snapshots_dir = tempfile::TempDir::new().unwrap();
+        let full_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let incremental_snapshot_archives_dir = tempfile::TempDir::new().unwrap();
+        let snapshot_archive_format = ArchiveFormat::TarZstd;
+
+        let full_snapshot_archive_info = bank_to_full_snapshot_archive(
+            bank_snapshots_dir.path(),
+            &bank1,
+            None,
+            full_snapshot_archives_dir.path(),
+            incremental_snapshot_archives_dir.path(),
+            snapshot_archive_format,
+        )
+        .unwrap();
+        snapshot_utils::verify_snapshot_archive_hash(&full_snapshot_archive_info).unwrap();
+
+        let bank2 = new_bank_from_parent_with_bank_forks(bank_forks.as_ref(), bank1, &collector, 2);
+        while !bank2.is_complete() {
+            bank2.register_unique_tick();
+        }
+        let incremental_snapshot_archive_info = bank_to_incremental_snapshot_archive(
+            bank_snapshots_dir.path(),
+            &bank2,
+            full_snapshot_archive_info.slot(),
+            None,
+            full_snapshot_archives_dir.path(),
+            incremental_snapshot_archives_dir.path(),
+            snapshot_archive_format,
+        )
+        .unwrap();
+        snapshot_utils::verify_snapshot_archive_hash(&incremental_snapshot_archive_info).unwrap();
+
+        // An archive whose file name doesn't match its contents
+        let wrong_hash = SnapshotHash(Hash::new_unique());
+        let mislabeled_archive_path = snapshot_utils::build_full_snapshot_archive_path(
+            full_snapshot_archives_dir.path(),
+            full_snapshot_archive_info.slot(),
+            &wrong_hash,
+            snapshot_archive_format,
+        );
+        fs::copy(full_snapshot_archive_info.path(), &mislabeled_archive_path).unwrap();
+        let mislabeled_archive_info =
+            FullSnapshotArchiveInfo::new_from_path(mislabeled_archive_path).unwrap();
+        assert_matches!(
+            snapshot_utils::verify_snapshot_archive_hash(&mislabeled_archive_info),
+            Err(SnapshotError::MismatchedSlotHash(archived, expected))
+                if archived == (1, *full_snapshot_archive_info.hash()) && expected == (1, wrong_hash)
+        );
+
+        // An archive which was only partially downloaded
+        let truncated_archives_dir = tempfile::TempDir::new().unwrap();
+        let truncated_archive_path = truncated_archives_dir
+            .path()
+            .join(full_snapshot_archive_info.path().file_name().unwrap());
+        let archive = fs::read(full_snapshot_archive_info.path()).unwrap();
+        fs::write(&truncated_archive_path, &archive[..archive.len() / 2]).unwrap();
+        let truncated_archive_info =
+            FullSnapshotArchiveInfo::new_from_path(truncated_archive_path).unwrap();
+        assert!(snapshot_utils::verify_snapshot_archive_hash(&truncated_archive_info).is_err());
+    }
+
     /// Test roundtrip of bank to a full snapshot, then back again.  This test is more involved
     /// than the simple version above; creating multiple banks over multiple slots and doing
     /// multiple transfers.  So this full snapshot should contain more data.
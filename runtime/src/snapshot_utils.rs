@@ -25,7 +25,7 @@ use {
     solana_accounts_db::{
         account_storage::{meta::StoredMetaWriteVersion, AccountStorageMap},
         accounts_db::{AccountStorageEntry, AtomicAccountsFileId, BankHashStats},
-        accounts_file::{AccountsFile, AccountsFileError, InternalsForArchive, StorageAccess},
+        accounts_file::{AccountsFileError, InternalsForArchive, StorageAccess},
         accounts_hash::{AccountsDeltaHash, AccountsHash},
         epoch_accounts_hash::EpochAccountsHash,
         hardened_unpack::{self, ParallelSelector, UnpackError},
@@ -41,7 +41,9 @@ use {
         cmp::Ordering,
         collections::{HashMap, HashSet},
         fmt, fs,
-        io::{BufReader, BufWriter, Error as IoError, Read, Result as IoResult, Seek, Write},
+        io::{
+            BufRead, BufReader, BufWriter, Error as IoError, Read, Result as IoResult, Seek, Write,
+        },
         mem,
         num::NonZeroUsize,
         ops::RangeInclusive,
@@ -342,6 +344,9 @@ pub enum SnapshotError {
     #[error("snapshot has mismatch: deserialized bank: {0:?}, snapshot archive info: {1:?}")]
     MismatchedSlotHash((Slot, SnapshotHash), (Slot, SnapshotHash)),
 
+    #[error("snapshot archive has no bank snapshot for slot {0}")]
+    MissingBankSnapshot(Slot),
+
     #[error("snapshot slot deltas are invalid: {0}")]
     VerifySlotDeltas(#[from] VerifySlotDeltasError),
 
@@ -1046,8 +1051,12 @@ fn archive_snapshot(
                 .map_err(E::ArchiveSnapshotsDir)?;
 
             for storage in snapshot_storages {
-                let path_in_archive = Path::new(ACCOUNTS_DIR)
-                    .join(AccountsFile::file_name(storage.slot(), storage.id()));
+                let path_in_archive = Path::new(ACCOUNTS_DIR).join(
+                    storage
+                        .accounts
+                        .format()
+                        .file_name(storage.slot(), storage.id()),
+                );
                 match storage.accounts.internals_for_archive() {
                     InternalsForArchive::Mmap(data) => {
                         let mut header = tar::Header::new_gnu();
@@ -1480,7 +1489,10 @@ pub fn hard_link_storages_to_snapshot(
         )?;
         // The appendvec could be recycled, so its filename may not be consistent to the slot and id.
         // Use the storage slot and id to compose a consistent file name for the hard-link file.
-        let hardlink_filename = AccountsFile::file_name(storage.slot(), storage.id());
+        let hardlink_filename = storage
+            .accounts
+            .format()
+            .file_name(storage.slot(), storage.id());
         let hard_link_path = snapshot_hardlink_dir.join(hardlink_filename);
         fs::hard_link(storage_path, &hard_link_path).map_err(|err| {
             HardLinkStoragesToSnapshotError::HardLinkStorage(
@@ -2275,6 +2287,74 @@ fn untar_snapshot_create_shared_buffer(
     }
 }
 
+fn create_snapshot_archive_decoder<'a>(
+    archive_stream: impl BufRead + 'a,
+    archive_format: ArchiveFormat,
+) -> Result<Box<dyn Read + 'a>> {
+    Ok(match archive_format {
+        ArchiveFormat::TarBzip2 => Box::new(BzDecoder::new(archive_stream)),
+        ArchiveFormat::TarGzip => Box::new(GzDecoder::new(archive_stream)),
+        ArchiveFormat::TarZstd => {
+            Box::new(zstd::stream::read::Decoder::with_buffer(archive_stream)?)
+        }
+        ArchiveFormat::TarLz4 => Box::new(lz4::Decoder::new(archive_stream)?),
+        ArchiveFormat::Tar => Box::new(archive_stream),
+    })
+}
+
+/// Check that the slot and snapshot hash recorded in the bank snapshot inside a snapshot archive
+/// match the ones in the archive's file name.
+///
+/// See `verify_snapshot_archive_stream`.
+pub fn verify_snapshot_archive_hash(
+    snapshot_archive_info: &impl SnapshotArchiveInfoGetter,
+) -> Result<()> {
+    verify_snapshot_archive_stream(
+        BufReader::new(fs::File::open(snapshot_archive_info.path())?),
+        snapshot_archive_info.archive_format(),
+        (snapshot_archive_info.slot(), *snapshot_archive_info.hash()),
+    )
+}
+
+/// Check that the slot and snapshot hash recorded in the bank snapshot inside the snapshot
+/// archive read from `archive_stream` match `expected_slot_hash`.
+///
+/// The archive is read in a single pass, so it can be checked while it is being downloaded.  The
+/// whole archive is decompressed, so a truncated or corrupted archive is caught too.  The accounts
+/// themselves are only checked against the snapshot hash once a bank is loaded from the archive.
+pub fn verify_snapshot_archive_stream(
+    archive_stream: impl BufRead,
+    archive_format: ArchiveFormat,
+    expected_slot_hash: (Slot, SnapshotHash),
+) -> Result<()> {
+    let slot = expected_slot_hash.0.to_string();
+    let bank_snapshot_path = Path::new("snapshots").join(&slot).join(&slot);
+
+    let mut archive = Archive::new(create_snapshot_archive_decoder(
+        archive_stream,
+        archive_format,
+    )?);
+    let mut archived_slot_hash = None;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.path()? == bank_snapshot_path {
+            archived_slot_hash = Some(serde_snapshot::slot_and_snapshot_hash_from_stream(
+                &mut BufReader::new(&mut entry),
+            )?);
+        }
+    }
+
+    let archived_slot_hash =
+        archived_slot_hash.ok_or(SnapshotError::MissingBankSnapshot(expected_slot_hash.0))?;
+    if archived_slot_hash != expected_slot_hash {
+        return Err(SnapshotError::MismatchedSlotHash(
+            archived_slot_hash,
+            expected_slot_hash,
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(feature = "dev-context-only-utils")]
 fn untar_snapshot_in(
     snapshot_tar: impl AsRef<Path>,
@@ -17,7 +17,7 @@ use {
     solana_accounts_db::{
         account_storage::{AccountStorageMap, AccountStorageReference},
         accounts_db::{AccountStorageEntry, AccountsFileId, AtomicAccountsFileId},
-        accounts_file::StorageAccess,
+        accounts_file::{AccountsFileFormat, StorageAccess, TIERED_STORAGE_FILE_EXTENSION},
     },
     solana_sdk::clock::Slot,
     std::{
@@ -312,6 +312,7 @@ impl SnapshotStorageRebuilder {
             .map(|path| {
                 let filename = path.file_name().unwrap().to_str().unwrap();
                 let (_, old_append_vec_id) = get_slot_and_append_vec_id(filename)?;
+                let format = AccountsFileFormat::from_file_name(filename);
                 let current_len = *self
                     .snapshot_storage_lengths
                     .get(&slot)
@@ -328,6 +329,7 @@ impl SnapshotStorageRebuilder {
                         &self.next_append_vec_id,
                         &self.num_collisions,
                         self.storage_access,
+                        format,
                     )?,
                     SnapshotFrom::Dir => reconstruct_single_storage(
                         &slot,
@@ -335,6 +337,7 @@ impl SnapshotStorageRebuilder {
                         current_len,
                         old_append_vec_id as AccountsFileId,
                         self.storage_access,
+                        format,
                     )?,
                 };
 
@@ -423,12 +426,16 @@ fn get_snapshot_file_kind(filename: &str) -> Option<SnapshotFileKind> {
 }
 
 /// Get the slot and append vec id from the filename
+///
+/// The filename of tiered storages has an extension recording their format.
 pub(crate) fn get_slot_and_append_vec_id(filename: &str) -> Result<(Slot, usize), SnapshotError> {
-    let mut parts = filename.splitn(2, '.');
+    let mut parts = filename.splitn(3, '.');
     let slot = parts.next().and_then(|s| Slot::from_str(s).ok());
     let id = parts.next().and_then(|s| usize::from_str(s).ok());
+    let extension = parts.next();
 
     slot.zip(id)
+        .filter(|_| extension.map_or(true, |ext| ext == TIERED_STORAGE_FILE_EXTENSION))
         .ok_or_else(|| SnapshotError::InvalidAppendVecPath(PathBuf::from(filename)))
 }
 
@@ -454,6 +461,11 @@ mod tests {
             Some(SnapshotFileKind::Storage),
             get_snapshot_file_kind("1000.999")
         );
+        assert_eq!(
+            Some(SnapshotFileKind::Storage),
+            get_snapshot_file_kind("1000.999.tiered")
+        );
+        assert_eq!(None, get_snapshot_file_kind("1000.999.txt"));
     }
 
     #[test]
@@ -465,5 +477,12 @@ mod tests {
                 .unwrap();
         assert_eq!(expected_slot, slot);
         assert_eq!(expected_id as usize, id);
+
+        let (slot, id) = get_slot_and_append_vec_id(
+            &AccountsFileFormat::TieredStorage.file_name(expected_slot, expected_id),
+        )
+        .unwrap();
+        assert_eq!(expected_slot, slot);
+        assert_eq!(expected_id as usize, id);
     }
 }
@@ -16,6 +16,7 @@ use {
 struct Config<'a> {
     sbf_sdk: Option<String>,
     sbf_out_dir: Option<String>,
+    coverage_dir: Option<String>,
     platform_tools_version: Option<String>,
     cargo: PathBuf,
     cargo_build_sbf: PathBuf,
@@ -38,6 +39,7 @@ impl Default for Config<'_> {
         Self {
             sbf_sdk: None,
             sbf_out_dir: None,
+            coverage_dir: None,
             platform_tools_version: None,
             cargo: PathBuf::from("cargo"),
             cargo_build_sbf: PathBuf::from("cargo-build-sbf"),
@@ -150,6 +152,11 @@ fn test_solana_package(
     build_sbf_args.push("--arch");
     build_sbf_args.push(config.arch);
 
+    // Coverage is mapped to the source lines through the debug info of the programs
+    if config.coverage_dir.is_some() {
+        build_sbf_args.push("--debug");
+    }
+
     if let Some(tools_version) = config.platform_tools_version.as_ref() {
         build_sbf_args.push("--tools-version");
         build_sbf_args.push(tools_version);
@@ -171,6 +178,9 @@ fn test_solana_package(
 
     // Pass --sbf-out-dir along to the solana-program-test crate
     env::set_var("SBF_OUT_DIR", sbf_out_dir);
+    if let Some(coverage_dir) = &config.coverage_dir {
+        env::set_var("SBF_COVERAGE_DIR", coverage_dir);
+    }
 
     cargo_args.insert(0, "test");
 
@@ -332,6 +342,16 @@ fn main() {
                 .takes_value(true)
                 .help("Place final SBF build artifacts in this directory"),
         )
+        .arg(
+            Arg::new("coverage_dir")
+                .long("coverage-dir")
+                .value_name("DIRECTORY")
+                .takes_value(true)
+                .help(
+                    "Write lcov line and branch coverage of the SBF programs run by \
+                     solana-program-test to this directory",
+                ),
+        )
         .arg(
             Arg::new("no_run")
                 .long("no-run")
@@ -403,6 +423,7 @@ fn main() {
     let mut config = Config {
         sbf_sdk: matches.value_of_t("sbf_sdk").ok(),
         sbf_out_dir: matches.value_of_t("sbf_out_dir").ok(),
+        coverage_dir: matches.value_of_t("coverage_dir").ok(),
         extra_cargo_test_args: matches
             .values_of_t("extra_cargo_test_args")
             .ok()
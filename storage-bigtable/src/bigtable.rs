@@ -3,9 +3,12 @@
 use {
     crate::{
         access_token::{AccessToken, Scope},
-        compression::{compress_best, decompress},
-        root_ca_certificate, CredentialType,
+        compression::decompress,
+        root_ca_certificate,
+        row_store::RowStore,
+        CredentialType,
     },
+    async_trait::async_trait,
     backoff::{future::retry, Error as BackoffError, ExponentialBackoff},
     log::*,
     std::{
@@ -283,22 +286,15 @@ impl BigTableConnection {
         cells: &[(RowKey, T)],
     ) -> Result<usize>
     where
-        T: serde::ser::Serialize,
+        T: serde::ser::Serialize + Sync,
     {
-        retry(ExponentialBackoff::default(), || async {
-            let mut client = self.client();
-            let result = client.put_bincode_cells(table, cells).await;
-            result.map_err(to_backoff_err)
-        })
-        .await
+        // `put_row_data` retries the write
+        self.put_bincode_cells(table, cells).await
     }
 
     pub async fn delete_rows_with_retry(&self, table: &str, row_keys: &[RowKey]) -> Result<()> {
-        retry(ExponentialBackoff::default(), || async {
-            let mut client = self.client();
-            Ok(client.delete_rows(table, row_keys).await?)
-        })
-        .await
+        // `delete_rows` retries the delete
+        self.delete_rows(table, row_keys).await
     }
 
     pub async fn get_bincode_cells_with_retry<T>(
@@ -310,8 +306,7 @@ impl BigTableConnection {
         T: serde::de::DeserializeOwned,
     {
         retry(ExponentialBackoff::default(), || async {
-            let mut client = self.client();
-            Ok(client.get_bincode_cells(table, row_keys).await?)
+            Ok(self.get_bincode_cells(table, row_keys).await?)
         })
         .await
     }
@@ -324,13 +319,69 @@ impl BigTableConnection {
     where
         T: prost::Message,
     {
+        // `put_row_data` retries the write
+        self.put_protobuf_cells(table, cells).await
+    }
+}
+
+#[async_trait]
+impl RowStore for BigTableConnection {
+    async fn get_row_keys(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<RowKey>> {
+        self.client()
+            .get_row_keys(table_name, start_at, end_at, rows_limit)
+            .await
+    }
+
+    async fn row_key_exists(&self, table_name: &str, row_key: RowKey) -> Result<bool> {
+        self.client().row_key_exists(table_name, row_key).await
+    }
+
+    async fn get_row_data(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        self.client()
+            .get_row_data(table_name, start_at, end_at, rows_limit)
+            .await
+    }
+
+    async fn get_multi_row_data(
+        &self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        self.client().get_multi_row_data(table_name, row_keys).await
+    }
+
+    async fn get_single_row_data(&self, table_name: &str, row_key: RowKey) -> Result<RowData> {
+        self.client().get_single_row_data(table_name, row_key).await
+    }
+
+    async fn put_row_data(&self, table_name: &str, row_data: &[(&RowKey, RowData)]) -> Result<()> {
         retry(ExponentialBackoff::default(), || async {
             let mut client = self.client();
-            let result = client.put_protobuf_cells(table, cells).await;
+            let result = client.put_row_data(table_name, "x", row_data).await;
             result.map_err(to_backoff_err)
         })
         .await
     }
+
+    async fn delete_rows(&self, table_name: &str, row_keys: &[RowKey]) -> Result<()> {
+        retry(ExponentialBackoff::default(), || async {
+            let mut client = self.client();
+            Ok(client.delete_rows(table_name, row_keys).await?)
+        })
+        .await
+    }
 }
 
 pub struct BigTable<F: FnMut(Request<()>) -> InterceptedRequestResult> {
@@ -657,7 +708,7 @@ impl<F: FnMut(Request<()>) -> InterceptedRequestResult> BigTable<F> {
     }
 
     /// Delete one or more `table` rows
-    async fn delete_rows(&mut self, table_name: &str, row_keys: &[RowKey]) -> Result<()> {
+    pub async fn delete_rows(&mut self, table_name: &str, row_keys: &[RowKey]) -> Result<()> {
         self.refresh_access_token();
 
         let mut entries = vec![];
@@ -698,7 +749,7 @@ impl<F: FnMut(Request<()>) -> InterceptedRequestResult> BigTable<F> {
     }
 
     /// Store data for one or more `table` rows in the `family_name` Column family
-    async fn put_row_data(
+    pub async fn put_row_data(
         &mut self,
         table_name: &str,
         family_name: &str,
@@ -751,124 +802,6 @@ impl<F: FnMut(Request<()>) -> InterceptedRequestResult> BigTable<F> {
         Ok(())
     }
 
-    pub async fn get_bincode_cell<T>(&mut self, table: &str, key: RowKey) -> Result<T>
-    where
-        T: serde::de::DeserializeOwned,
-    {
-        let row_data = self.get_single_row_data(table, key.clone()).await?;
-        deserialize_bincode_cell_data(&row_data, table, key.to_string())
-    }
-
-    pub async fn get_bincode_cells<T>(
-        &mut self,
-        table: &str,
-        keys: &[RowKey],
-    ) -> Result<Vec<(RowKey, Result<T>)>>
-    where
-        T: serde::de::DeserializeOwned,
-    {
-        Ok(self
-            .get_multi_row_data(table, keys)
-            .await?
-            .into_iter()
-            .map(|(key, row_data)| {
-                let key_str = key.to_string();
-                (
-                    key,
-                    deserialize_bincode_cell_data(&row_data, table, key_str),
-                )
-            })
-            .collect())
-    }
-
-    pub async fn get_protobuf_cell<P>(&mut self, table: &str, key: RowKey) -> Result<P>
-    where
-        P: prost::Message + Default,
-    {
-        let row_data = self.get_single_row_data(table, key.clone()).await?;
-        deserialize_protobuf_cell_data(&row_data, table, key.to_string())
-    }
-
-    pub async fn get_protobuf_or_bincode_cell<B, P>(
-        &mut self,
-        table: &str,
-        key: RowKey,
-    ) -> Result<CellData<B, P>>
-    where
-        B: serde::de::DeserializeOwned,
-        P: prost::Message + Default,
-    {
-        let row_data = self.get_single_row_data(table, key.clone()).await?;
-        deserialize_protobuf_or_bincode_cell_data(&row_data, table, key)
-    }
-
-    pub async fn get_protobuf_or_bincode_cells<'a, B, P>(
-        &mut self,
-        table: &'a str,
-        row_keys: impl IntoIterator<Item = RowKey>,
-    ) -> Result<impl Iterator<Item = (RowKey, CellData<B, P>)> + 'a>
-    where
-        B: serde::de::DeserializeOwned,
-        P: prost::Message + Default,
-    {
-        Ok(self
-            .get_multi_row_data(
-                table,
-                row_keys.into_iter().collect::<Vec<RowKey>>().as_slice(),
-            )
-            .await?
-            .into_iter()
-            .map(|(key, row_data)| {
-                let key_str = key.to_string();
-                (
-                    key,
-                    deserialize_protobuf_or_bincode_cell_data(&row_data, table, key_str).unwrap(),
-                )
-            }))
-    }
-
-    pub async fn put_bincode_cells<T>(
-        &mut self,
-        table: &str,
-        cells: &[(RowKey, T)],
-    ) -> Result<usize>
-    where
-        T: serde::ser::Serialize,
-    {
-        let mut bytes_written = 0;
-        let mut new_row_data = vec![];
-        for (row_key, data) in cells {
-            let data = compress_best(&bincode::serialize(&data).unwrap())?;
-            bytes_written += data.len();
-            new_row_data.push((row_key, vec![("bin".to_string(), data)]));
-        }
-
-        self.put_row_data(table, "x", &new_row_data).await?;
-        Ok(bytes_written)
-    }
-
-    pub async fn put_protobuf_cells<T>(
-        &mut self,
-        table: &str,
-        cells: &[(RowKey, T)],
-    ) -> Result<usize>
-    where
-        T: prost::Message,
-    {
-        let mut bytes_written = 0;
-        let mut new_row_data = vec![];
-        for (row_key, data) in cells {
-            let mut buf = Vec::with_capacity(data.encoded_len());
-            data.encode(&mut buf).unwrap();
-            let data = compress_best(&buf)?;
-            bytes_written += data.len();
-            new_row_data.push((row_key, vec![("proto".to_string(), data)]));
-        }
-
-        self.put_row_data(table, "x", &new_row_data).await?;
-        Ok(bytes_written)
-    }
-
     async fn read_rows(
         &mut self,
         table_name: &str,
@@ -1,7 +1,7 @@
 #![allow(clippy::arithmetic_side_effects)]
 
 use {
-    crate::bigtable::RowKey,
+    crate::{bigtable::RowKey, local_storage::LocalRowStore},
     log::*,
     serde::{Deserialize, Serialize},
     solana_metrics::datapoint_info,
@@ -26,6 +26,7 @@ use {
     std::{
         collections::{HashMap, HashSet},
         convert::TryInto,
+        path::Path,
         sync::{
             atomic::{AtomicUsize, Ordering},
             Arc,
@@ -45,7 +46,15 @@ extern crate serde_derive;
 mod access_token;
 mod bigtable;
 mod compression;
+mod local_storage;
+mod long_term_ledger_storage;
 mod root_ca_certificate;
+mod row_store;
+
+pub use crate::{
+    long_term_ledger_storage::{LongTermLedgerStorage, LongTermLedgerStorageConfig},
+    row_store::RowStore,
+};
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -442,12 +451,16 @@ impl LedgerStorageStats {
     }
 }
 
+/// Ledger data stored in Bigtable by default, or in any other [`RowStore`]
 #[derive(Clone)]
-pub struct LedgerStorage {
-    connection: bigtable::BigTableConnection,
+pub struct LedgerStorage<S = bigtable::BigTableConnection> {
+    connection: S,
     stats: Arc<LedgerStorageStats>,
 }
 
+/// Ledger data stored in a local RocksDB database, with the same row layout as Bigtable
+pub type LocalLedgerStorage = LedgerStorage<LocalRowStore>;
+
 impl LedgerStorage {
     pub async fn new(
         read_only: bool,
@@ -511,12 +524,23 @@ impl LedgerStorage {
         })
         .await
     }
+}
 
+impl LocalLedgerStorage {
+    pub fn open(path: &Path, read_only: bool) -> Result<Self> {
+        Ok(Self {
+            connection: LocalRowStore::open(path, read_only)?,
+            stats: Arc::new(LedgerStorageStats::default()),
+        })
+    }
+}
+
+impl<S: RowStore> LedgerStorage<S> {
     /// Return the available slot that contains a block
     pub async fn get_first_available_block(&self) -> Result<Option<Slot>> {
         trace!("LedgerStorage::get_first_available_block request received");
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
+        let bigtable = &self.connection;
         let blocks = bigtable.get_row_keys("blocks", None, None, 1).await?;
         if blocks.is_empty() {
             return Ok(None);
@@ -535,7 +559,7 @@ impl LedgerStorage {
             limit
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
+        let bigtable = &self.connection;
         let blocks = bigtable
             .get_row_keys(
                 "blocks",
@@ -557,11 +581,12 @@ impl LedgerStorage {
             slots
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
-        let row_keys = slots.iter().copied().map(slot_to_blocks_key);
+        let bigtable = &self.connection;
+        let row_keys = slots.iter().copied().map(slot_to_blocks_key).collect();
         let data = bigtable
             .get_protobuf_or_bincode_cells("blocks", row_keys)
             .await?
+            .into_iter()
             .filter_map(
                 |(row_key, block_cell_data): (
                     RowKey,
@@ -584,7 +609,7 @@ impl LedgerStorage {
             slot
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
+        let bigtable = &self.connection;
         let block_cell_data = bigtable
             .get_protobuf_or_bincode_cell::<StoredConfirmedBlock, generated::ConfirmedBlock>(
                 "blocks",
@@ -603,14 +628,14 @@ impl LedgerStorage {
         })
     }
 
-    /// Does the confirmed block exist in the Bigtable
+    /// Does the confirmed block exist in the ledger storage
     pub async fn confirmed_block_exists(&self, slot: Slot) -> Result<bool> {
         trace!(
             "LedgerStorage::confirmed_block_exists request received: {:?}",
             slot
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
+        let bigtable = &self.connection;
 
         let block_exists = bigtable
             .row_key_exists("blocks", slot_to_blocks_key(slot))
@@ -626,7 +651,7 @@ impl LedgerStorage {
             slot
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
+        let bigtable = &self.connection;
         let entry_cell_data = bigtable
             .get_protobuf_cell::<entries::Entries>("entries", slot_to_entries_key(slot))
             .await
@@ -644,7 +669,7 @@ impl LedgerStorage {
             signature
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
+        let bigtable = &self.connection;
         let transaction_info = bigtable
             .get_bincode_cell::<TransactionInfo>("tx", signature.to_string())
             .await
@@ -665,7 +690,7 @@ impl LedgerStorage {
             signatures
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
+        let bigtable = &self.connection;
 
         // Fetch transactions info
         let keys = signatures.iter().map(|s| s.to_string()).collect::<Vec<_>>();
@@ -727,7 +752,7 @@ impl LedgerStorage {
             signature
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
+        let bigtable = &self.connection;
 
         // Figure out which block the transaction is located in
         let TransactionInfo { slot, index, .. } = bigtable
@@ -787,7 +812,7 @@ impl LedgerStorage {
             address
         );
         self.stats.increment_num_queries();
-        let mut bigtable = self.connection.client();
+        let bigtable = &self.connection;
         let address_prefix = format!("{address}/");
 
         // Figure out where to start listing from based on `before_signature`
@@ -1007,7 +1032,7 @@ impl LedgerStorage {
         if !tx_cells.is_empty() {
             let conn = self.connection.clone();
             tasks.push(tokio::spawn(async move {
-                conn.put_bincode_cells_with_retry::<TransactionInfo>("tx", &tx_cells)
+                conn.put_bincode_cells::<TransactionInfo>("tx", &tx_cells)
                     .await
             }));
         }
@@ -1015,7 +1040,7 @@ impl LedgerStorage {
         if !tx_by_addr_cells.is_empty() {
             let conn = self.connection.clone();
             tasks.push(tokio::spawn(async move {
-                conn.put_protobuf_cells_with_retry::<tx_by_addr::TransactionByAddr>(
+                conn.put_protobuf_cells::<tx_by_addr::TransactionByAddr>(
                     "tx-by-addr",
                     &tx_by_addr_cells,
                 )
@@ -1026,7 +1051,7 @@ impl LedgerStorage {
         if num_entries > 0 {
             let conn = self.connection.clone();
             tasks.push(tokio::spawn(async move {
-                conn.put_protobuf_cells_with_retry::<entries::Entries>("entries", &[entry_cell])
+                conn.put_protobuf_cells::<entries::Entries>("entries", &[entry_cell])
                     .await
             }));
         }
@@ -1065,7 +1090,7 @@ impl LedgerStorage {
         let blocks_cells = [(slot_to_blocks_key(slot), confirmed_block.into())];
         bytes_written += self
             .connection
-            .put_protobuf_cells_with_retry::<generated::ConfirmedBlock>("blocks", &blocks_cells)
+            .put_protobuf_cells::<generated::ConfirmedBlock>("blocks", &blocks_cells)
             .await?;
         datapoint_info!(
             "storage-bigtable-upload-block",
@@ -1137,7 +1162,7 @@ impl LedgerStorage {
             let signatures = expected_tx_infos.keys().cloned().collect::<Vec<_>>();
             let fetched_tx_infos: HashMap<String, std::result::Result<UploadedTransaction, _>> =
                 self.connection
-                    .get_bincode_cells_with_retry::<TransactionInfo>("tx", &signatures)
+                    .get_bincode_cells::<TransactionInfo>("tx", &signatures)
                     .await?
                     .into_iter()
                     .map(|(signature, tx_info_res)| (signature, tx_info_res.map(Into::into)))
@@ -1175,7 +1200,6 @@ impl LedgerStorage {
 
         let entries_exist = self
             .connection
-            .client()
             .row_key_exists("entries", slot_to_entries_key(slot))
             .await
             .is_ok_and(|x| x);
@@ -1183,24 +1207,22 @@ impl LedgerStorage {
         if !dry_run {
             if !address_slot_rows.is_empty() {
                 self.connection
-                    .delete_rows_with_retry("tx-by-addr", &address_slot_rows)
+                    .delete_rows("tx-by-addr", &address_slot_rows)
                     .await?;
             }
 
             if !tx_deletion_rows.is_empty() {
-                self.connection
-                    .delete_rows_with_retry("tx", &tx_deletion_rows)
-                    .await?;
+                self.connection.delete_rows("tx", &tx_deletion_rows).await?;
             }
 
             if entries_exist {
                 self.connection
-                    .delete_rows_with_retry("entries", &[slot_to_entries_key(slot)])
+                    .delete_rows("entries", &[slot_to_entries_key(slot)])
                     .await?;
             }
 
             self.connection
-                .delete_rows_with_retry("blocks", &[slot_to_blocks_key(slot)])
+                .delete_rows("blocks", &[slot_to_blocks_key(slot)])
                 .await?;
         }
 
@@ -1219,11 +1241,100 @@ impl LedgerStorage {
 
 #[cfg(test)]
 mod test {
-    use super::*;
+    use {
+        super::*,
+        solana_sdk::{hash::Hash, signature::Keypair, system_transaction},
+    };
 
     #[test]
     fn test_slot_to_key() {
         assert_eq!(slot_to_key(0), "0000000000000000");
         assert_eq!(slot_to_key(!0), "ffffffffffffffff");
     }
+
+    #[tokio::test]
+    async fn test_local_ledger_storage() {
+        let ledger_path = tempfile::tempdir().unwrap();
+        let storage = LocalLedgerStorage::open(ledger_path.path(), false).unwrap();
+        assert_eq!(storage.get_first_available_block().await.unwrap(), None);
+
+        let address = Pubkey::new_unique();
+        let transaction = VersionedTransaction::from(system_transaction::transfer(
+            &Keypair::new(),
+            &address,
+            1,
+            Hash::default(),
+        ));
+        let signature = transaction.signatures[0];
+        let block = VersionedConfirmedBlock {
+            previous_blockhash: Hash::default().to_string(),
+            blockhash: Hash::new_unique().to_string(),
+            parent_slot: 41,
+            transactions: vec![VersionedTransactionWithStatusMeta {
+                transaction,
+                meta: TransactionStatusMeta::default(),
+            }],
+            rewards: vec![],
+            num_partitions: None,
+            block_time: Some(1_700_000_000),
+            block_height: Some(40),
+        };
+        let entry_hash = Hash::new_unique();
+        for slot in [42, 43] {
+            storage
+                .upload_confirmed_block_with_entries(
+                    slot,
+                    VersionedConfirmedBlockWithEntries {
+                        block: block.clone(),
+                        entries: vec![EntrySummary {
+                            num_hashes: 1,
+                            hash: entry_hash,
+                            num_transactions: 1,
+                            starting_transaction_index: 0,
+                        }],
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(storage.get_first_available_block().await.unwrap(), Some(42));
+        assert_eq!(
+            storage.get_confirmed_blocks(43, 10).await.unwrap(),
+            vec![43]
+        );
+        assert!(storage.confirmed_block_exists(42).await.unwrap());
+        let confirmed_block = storage.get_confirmed_block(42).await.unwrap();
+        assert_eq!(confirmed_block.blockhash, block.blockhash);
+        assert_eq!(confirmed_block.transactions.len(), 1);
+        let entries = storage.get_entries(42).await.unwrap().collect::<Vec<_>>();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].hash, entry_hash);
+        assert_eq!(entries[0].num_transactions, 1);
+        let transaction = storage
+            .get_confirmed_transaction(&signature)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(transaction.slot, 43);
+        let signatures = storage
+            .get_confirmed_signatures_for_address(&address, None, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            signatures
+                .iter()
+                .map(|(status, _)| status.slot)
+                .collect::<Vec<_>>(),
+            vec![43, 42]
+        );
+
+        storage.delete_confirmed_block(43, false).await.unwrap();
+        assert!(!storage.confirmed_block_exists(43).await.unwrap());
+        assert!(matches!(
+            storage.get_confirmed_block(43).await,
+            Err(Error::BlockNotFound(43))
+        ));
+        assert_eq!(storage.get_confirmed_blocks(0, 10).await.unwrap(), vec![42]);
+    }
 }
@@ -0,0 +1,223 @@
+// Primitives for reading/writing the ledger storage tables in a local RocksDB database
+
+use {
+    crate::{
+        bigtable::{Error, Result, RowData, RowKey},
+        row_store::RowStore,
+    },
+    async_trait::async_trait,
+    rocksdb::{ColumnFamily, Direction, IteratorMode, Options, WriteBatch, DB},
+    std::{io, path::Path, sync::Arc},
+};
+
+/// Each table is stored in the column family of the same name
+const TABLES: &[&str] = &["blocks", "entries", "tx", "tx-by-addr"];
+
+fn to_error(err: rocksdb::Error) -> Error {
+    Error::Io(io::Error::other(err))
+}
+
+/// Stores the ledger storage rows in a RocksDB database, keyed by row key, with the bincode
+/// serialized `RowData` of the row as the value.
+#[derive(Clone)]
+pub struct LocalRowStore {
+    db: Arc<DB>,
+}
+
+impl LocalRowStore {
+    pub fn open(path: &Path, read_only: bool) -> Result<Self> {
+        let db = if read_only {
+            DB::open_cf_for_read_only(&Options::default(), path, TABLES, false)
+        } else {
+            let mut options = Options::default();
+            options.create_if_missing(true);
+            options.create_missing_column_families(true);
+            DB::open_cf(&options, path, TABLES)
+        }
+        .map_err(to_error)?;
+        Ok(Self { db: Arc::new(db) })
+    }
+
+    fn table(&self, table_name: &str) -> Result<&ColumnFamily> {
+        self.db.cf_handle(table_name).ok_or_else(|| {
+            Error::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("unknown table: {table_name}"),
+            ))
+        })
+    }
+
+    /// Run a RocksDB operation on the blocking thread pool, so it doesn't stall the async runtime
+    async fn run_blocking<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Self) -> Result<T> + Send + 'static,
+    {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || f(&store))
+            .await
+            .map_err(|err| Error::Io(io::Error::other(err)))?
+    }
+
+    fn deserialize_row(row_key: &RowKey, table_name: &str, value: &[u8]) -> Result<RowData> {
+        bincode::deserialize(value)
+            .map_err(|_| Error::ObjectCorrupt(format!("{table_name}/{row_key}")))
+    }
+
+    /// Scan the `table` rows from `start_at` to `end_at` inclusive, up to `rows_limit` rows
+    fn scan(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<(RowKey, Box<[u8]>)>> {
+        let table = self.table(table_name)?;
+        let mode = match &start_at {
+            Some(start_at) => IteratorMode::From(start_at.as_bytes(), Direction::Forward),
+            None => IteratorMode::Start,
+        };
+        let mut rows = vec![];
+        for item in self.db.iterator_cf(table, mode) {
+            if rows.len() as i64 >= rows_limit {
+                break;
+            }
+            let (key, value) = item.map_err(to_error)?;
+            let row_key = String::from_utf8(key.into_vec())
+                .map_err(|_| Error::ObjectCorrupt(format!("{table_name}: invalid row key")))?;
+            if end_at.as_ref().is_some_and(|end_at| row_key > *end_at) {
+                break;
+            }
+            rows.push((row_key, value));
+        }
+        Ok(rows)
+    }
+}
+
+#[async_trait]
+impl RowStore for LocalRowStore {
+    async fn get_row_keys(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<RowKey>> {
+        let table_name = table_name.to_string();
+        self.run_blocking(move |store| {
+            Ok(store
+                .scan(&table_name, start_at, end_at, rows_limit)?
+                .into_iter()
+                .map(|(row_key, _)| row_key)
+                .collect())
+        })
+        .await
+    }
+
+    async fn row_key_exists(&self, table_name: &str, row_key: RowKey) -> Result<bool> {
+        let table_name = table_name.to_string();
+        self.run_blocking(move |store| {
+            let table = store.table(&table_name)?;
+            Ok(store
+                .db
+                .get_pinned_cf(table, row_key.as_bytes())
+                .map_err(to_error)?
+                .is_some())
+        })
+        .await
+    }
+
+    async fn get_row_data(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        let table_name = table_name.to_string();
+        self.run_blocking(move |store| {
+            store
+                .scan(&table_name, start_at, end_at, rows_limit)?
+                .into_iter()
+                .map(|(row_key, value)| {
+                    let row_data = Self::deserialize_row(&row_key, &table_name, &value)?;
+                    Ok((row_key, row_data))
+                })
+                .collect()
+        })
+        .await
+    }
+
+    async fn get_multi_row_data(
+        &self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        let table_name = table_name.to_string();
+        let row_keys = row_keys.to_vec();
+        self.run_blocking(move |store| {
+            let table = store.table(&table_name)?;
+            let mut rows = vec![];
+            for (row_key, value) in row_keys.iter().zip(store.db.batched_multi_get_cf(
+                table,
+                row_keys.iter().map(|key| key.as_bytes()),
+                false,
+            )) {
+                if let Some(value) = value.map_err(to_error)? {
+                    let row_data = Self::deserialize_row(row_key, &table_name, &value)?;
+                    rows.push((row_key.clone(), row_data));
+                }
+            }
+            Ok(rows)
+        })
+        .await
+    }
+
+    async fn get_single_row_data(&self, table_name: &str, row_key: RowKey) -> Result<RowData> {
+        let table_name = table_name.to_string();
+        self.run_blocking(move |store| {
+            let table = store.table(&table_name)?;
+            let value = store
+                .db
+                .get_pinned_cf(table, row_key.as_bytes())
+                .map_err(to_error)?
+                .ok_or(Error::RowNotFound)?;
+            Self::deserialize_row(&row_key, &table_name, &value)
+        })
+        .await
+    }
+
+    async fn put_row_data(&self, table_name: &str, row_data: &[(&RowKey, RowData)]) -> Result<()> {
+        let table_name = table_name.to_string();
+        let rows = row_data
+            .iter()
+            .map(|(row_key, row_data)| {
+                let value = bincode::serialize(row_data).map_err(|_| Error::RowWriteFailed)?;
+                Ok(((*row_key).clone(), value))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.run_blocking(move |store| {
+            let table = store.table(&table_name)?;
+            let mut batch = WriteBatch::default();
+            for (row_key, value) in rows {
+                batch.put_cf(table, row_key.as_bytes(), value);
+            }
+            store.db.write(batch).map_err(to_error)
+        })
+        .await
+    }
+
+    async fn delete_rows(&self, table_name: &str, row_keys: &[RowKey]) -> Result<()> {
+        let table_name = table_name.to_string();
+        let row_keys = row_keys.to_vec();
+        self.run_blocking(move |store| {
+            let table = store.table(&table_name)?;
+            let mut batch = WriteBatch::default();
+            for row_key in row_keys {
+                batch.delete_cf(table, row_key.as_bytes());
+            }
+            store.db.write(batch).map_err(to_error)
+        })
+        .await
+    }
+}
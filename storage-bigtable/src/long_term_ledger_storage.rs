@@ -0,0 +1,186 @@
+// Backend independent interface to the long-term ledger storage
+
+use {
+    crate::{LedgerStorage, LedgerStorageConfig, LocalLedgerStorage, Result, RowStore},
+    async_trait::async_trait,
+    solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature},
+    solana_transaction_status::{
+        ConfirmedBlock, ConfirmedTransactionStatusWithSignature,
+        ConfirmedTransactionWithStatusMeta, EntrySummary, TransactionStatus,
+        VersionedConfirmedBlock, VersionedConfirmedBlockWithEntries,
+    },
+    std::{path::PathBuf, sync::Arc},
+};
+
+/// Confirmed blocks and transactions kept beyond the local ledger, see [`LedgerStorage`] for
+/// the semantics of each method
+#[async_trait]
+pub trait LongTermLedgerStorage: Send + Sync {
+    async fn get_first_available_block(&self) -> Result<Option<Slot>>;
+
+    async fn get_confirmed_blocks(&self, start_slot: Slot, limit: usize) -> Result<Vec<Slot>>;
+
+    async fn get_confirmed_blocks_with_data(
+        &self,
+        slots: &[Slot],
+    ) -> Result<Vec<(Slot, ConfirmedBlock)>>;
+
+    async fn get_confirmed_block(&self, slot: Slot) -> Result<ConfirmedBlock>;
+
+    async fn confirmed_block_exists(&self, slot: Slot) -> Result<bool>;
+
+    async fn get_entries(&self, slot: Slot) -> Result<Vec<EntrySummary>>;
+
+    async fn get_signature_status(&self, signature: &Signature) -> Result<TransactionStatus>;
+
+    async fn get_confirmed_transactions(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Vec<ConfirmedTransactionWithStatusMeta>>;
+
+    async fn get_confirmed_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<ConfirmedTransactionWithStatusMeta>>;
+
+    async fn get_confirmed_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<
+        Vec<(
+            ConfirmedTransactionStatusWithSignature,
+            u32, /*slot index*/
+        )>,
+    >;
+
+    async fn upload_confirmed_block(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlock,
+    ) -> Result<()>;
+
+    async fn upload_confirmed_block_with_entries(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlockWithEntries,
+    ) -> Result<()>;
+
+    async fn delete_confirmed_block(&self, slot: Slot, dry_run: bool) -> Result<()>;
+}
+
+#[async_trait]
+impl<S: RowStore> LongTermLedgerStorage for LedgerStorage<S> {
+    async fn get_first_available_block(&self) -> Result<Option<Slot>> {
+        self.get_first_available_block().await
+    }
+
+    async fn get_confirmed_blocks(&self, start_slot: Slot, limit: usize) -> Result<Vec<Slot>> {
+        self.get_confirmed_blocks(start_slot, limit).await
+    }
+
+    async fn get_confirmed_blocks_with_data(
+        &self,
+        slots: &[Slot],
+    ) -> Result<Vec<(Slot, ConfirmedBlock)>> {
+        Ok(self.get_confirmed_blocks_with_data(slots).await?.collect())
+    }
+
+    async fn get_confirmed_block(&self, slot: Slot) -> Result<ConfirmedBlock> {
+        self.get_confirmed_block(slot).await
+    }
+
+    async fn confirmed_block_exists(&self, slot: Slot) -> Result<bool> {
+        self.confirmed_block_exists(slot).await
+    }
+
+    async fn get_entries(&self, slot: Slot) -> Result<Vec<EntrySummary>> {
+        Ok(self.get_entries(slot).await?.collect())
+    }
+
+    async fn get_signature_status(&self, signature: &Signature) -> Result<TransactionStatus> {
+        self.get_signature_status(signature).await
+    }
+
+    async fn get_confirmed_transactions(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Vec<ConfirmedTransactionWithStatusMeta>> {
+        self.get_confirmed_transactions(signatures).await
+    }
+
+    async fn get_confirmed_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<ConfirmedTransactionWithStatusMeta>> {
+        self.get_confirmed_transaction(signature).await
+    }
+
+    async fn get_confirmed_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<
+        Vec<(
+            ConfirmedTransactionStatusWithSignature,
+            u32, /*slot index*/
+        )>,
+    > {
+        self.get_confirmed_signatures_for_address(address, before_signature, until_signature, limit)
+            .await
+    }
+
+    async fn upload_confirmed_block(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlock,
+    ) -> Result<()> {
+        self.upload_confirmed_block(slot, confirmed_block).await
+    }
+
+    async fn upload_confirmed_block_with_entries(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlockWithEntries,
+    ) -> Result<()> {
+        self.upload_confirmed_block_with_entries(slot, confirmed_block)
+            .await
+    }
+
+    async fn delete_confirmed_block(&self, slot: Slot, dry_run: bool) -> Result<()> {
+        self.delete_confirmed_block(slot, dry_run).await
+    }
+}
+
+/// Selects the long-term ledger storage backend
+#[derive(Debug)]
+pub enum LongTermLedgerStorageConfig {
+    BigTable(LedgerStorageConfig),
+    /// A RocksDB database at `path`, holding the same rows as the Bigtable tables
+    Local {
+        path: PathBuf,
+        read_only: bool,
+    },
+}
+
+impl LongTermLedgerStorageConfig {
+    pub fn read_only(&self) -> bool {
+        match self {
+            Self::BigTable(config) => config.read_only,
+            Self::Local { read_only, .. } => *read_only,
+        }
+    }
+
+    pub async fn open(self) -> Result<Arc<dyn LongTermLedgerStorage>> {
+        Ok(match self {
+            Self::BigTable(config) => Arc::new(LedgerStorage::new_with_config(config).await?),
+            Self::Local { path, read_only } => {
+                Arc::new(LocalLedgerStorage::open(&path, read_only)?)
+            }
+        })
+    }
+}
@@ -0,0 +1,173 @@
+// Table access shared by the long-term ledger storage backends
+
+use {
+    crate::{
+        bigtable::{
+            deserialize_bincode_cell_data, deserialize_protobuf_cell_data,
+            deserialize_protobuf_or_bincode_cell_data, CellData, Result, RowData, RowKey,
+        },
+        compression::compress_best,
+    },
+    async_trait::async_trait,
+};
+
+/// Rows of the ledger storage tables: "blocks", "entries", "tx" and "tx-by-addr".
+///
+/// Row keys are listed in lexical order. Every cell holds a compressed bincode ("bin") or
+/// protobuf ("proto") serialized value, so the rows are identical across backends.
+#[async_trait]
+pub trait RowStore: Clone + Send + Sync + 'static {
+    /// Get `table` row keys in lexical order.
+    ///
+    /// If `start_at` is provided, the row key listing will start with key.
+    /// Otherwise the listing will start from the start of the table.
+    ///
+    /// If `end_at` is provided, the row key listing will end at the key. Otherwise it will
+    /// continue until the `rows_limit` is reached or the end of the table, whichever comes first.
+    /// If `rows_limit` is zero, this method will return an empty array.
+    async fn get_row_keys(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<RowKey>>;
+
+    /// Check whether a row key exists in a `table`
+    async fn row_key_exists(&self, table_name: &str, row_key: RowKey) -> Result<bool>;
+
+    /// Get the data of the `table` rows from `start_at` to `end_at`, with the same bounds as
+    /// [`RowStore::get_row_keys`]
+    async fn get_row_data(
+        &self,
+        table_name: &str,
+        start_at: Option<RowKey>,
+        end_at: Option<RowKey>,
+        rows_limit: i64,
+    ) -> Result<Vec<(RowKey, RowData)>>;
+
+    /// Get data from multiple rows of `table`, if those rows exist.
+    async fn get_multi_row_data(
+        &self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>>;
+
+    /// Get data from a single row of `table`, if that row exists. Returns an error if that
+    /// row does not exist.
+    async fn get_single_row_data(&self, table_name: &str, row_key: RowKey) -> Result<RowData>;
+
+    /// Store data for one or more `table` rows
+    async fn put_row_data(&self, table_name: &str, row_data: &[(&RowKey, RowData)]) -> Result<()>;
+
+    /// Delete one or more `table` rows
+    async fn delete_rows(&self, table_name: &str, row_keys: &[RowKey]) -> Result<()>;
+
+    async fn get_bincode_cell<T>(&self, table: &str, key: RowKey) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let row_data = self.get_single_row_data(table, key.clone()).await?;
+        deserialize_bincode_cell_data(&row_data, table, key.to_string())
+    }
+
+    async fn get_bincode_cells<T>(
+        &self,
+        table: &str,
+        keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, Result<T>)>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        Ok(self
+            .get_multi_row_data(table, keys)
+            .await?
+            .into_iter()
+            .map(|(key, row_data)| {
+                let key_str = key.to_string();
+                (
+                    key,
+                    deserialize_bincode_cell_data(&row_data, table, key_str),
+                )
+            })
+            .collect())
+    }
+
+    async fn get_protobuf_cell<P>(&self, table: &str, key: RowKey) -> Result<P>
+    where
+        P: prost::Message + Default,
+    {
+        let row_data = self.get_single_row_data(table, key.clone()).await?;
+        deserialize_protobuf_cell_data(&row_data, table, key.to_string())
+    }
+
+    async fn get_protobuf_or_bincode_cell<B, P>(
+        &self,
+        table: &str,
+        key: RowKey,
+    ) -> Result<CellData<B, P>>
+    where
+        B: serde::de::DeserializeOwned,
+        P: prost::Message + Default,
+    {
+        let row_data = self.get_single_row_data(table, key.clone()).await?;
+        deserialize_protobuf_or_bincode_cell_data(&row_data, table, key)
+    }
+
+    async fn get_protobuf_or_bincode_cells<B, P>(
+        &self,
+        table: &str,
+        row_keys: Vec<RowKey>,
+    ) -> Result<Vec<(RowKey, CellData<B, P>)>>
+    where
+        B: serde::de::DeserializeOwned,
+        P: prost::Message + Default,
+    {
+        Ok(self
+            .get_multi_row_data(table, &row_keys)
+            .await?
+            .into_iter()
+            .map(|(key, row_data)| {
+                let key_str = key.to_string();
+                (
+                    key,
+                    deserialize_protobuf_or_bincode_cell_data(&row_data, table, key_str).unwrap(),
+                )
+            })
+            .collect())
+    }
+
+    async fn put_bincode_cells<T>(&self, table: &str, cells: &[(RowKey, T)]) -> Result<usize>
+    where
+        T: serde::ser::Serialize + Sync,
+    {
+        let mut bytes_written = 0;
+        let mut new_row_data = vec![];
+        for (row_key, data) in cells {
+            let data = compress_best(&bincode::serialize(&data).unwrap())?;
+            bytes_written += data.len();
+            new_row_data.push((row_key, vec![("bin".to_string(), data)]));
+        }
+
+        self.put_row_data(table, &new_row_data).await?;
+        Ok(bytes_written)
+    }
+
+    async fn put_protobuf_cells<T>(&self, table: &str, cells: &[(RowKey, T)]) -> Result<usize>
+    where
+        T: prost::Message,
+    {
+        let mut bytes_written = 0;
+        let mut new_row_data = vec![];
+        for (row_key, data) in cells {
+            let mut buf = Vec::with_capacity(data.encoded_len());
+            data.encode(&mut buf).unwrap();
+            let data = compress_best(&buf)?;
+            bytes_written += data.len();
+            new_row_data.push((row_key, vec![("proto".to_string(), data)]));
+        }
+
+        self.put_row_data(table, &new_row_data).await?;
+        Ok(bytes_written)
+    }
+}
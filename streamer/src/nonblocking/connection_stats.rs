@@ -0,0 +1,170 @@
+//! Per-connection statistics of the QUIC server.
+//!
+//! `StreamerStats` only tracks aggregate counters, which makes it hard to tell
+//! why the streams of a particular peer are being throttled. This table tracks
+//! each live connection along with the peer's identity, stake, streams
+//! allotment and stream counters, and allows evicting a peer.
+//! Evicted peers are refused for `EVICTION_COOLDOWN` so that they cannot
+//! simply reconnect right away.
+
+use {
+    crate::nonblocking::{
+        quic::{
+            ConnectionPeerType, CONNECTION_CLOSE_CODE_EVICTED, CONNECTION_CLOSE_REASON_EVICTED,
+        },
+        stream_throttle::StakedStreamLoadEMA,
+    },
+    quinn::Connection,
+    solana_sdk::pubkey::Pubkey,
+    std::{
+        collections::HashMap,
+        net::SocketAddr,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, RwLock,
+        },
+        time::{Duration, Instant},
+    },
+};
+
+/// How long an evicted peer is refused before it may connect again.
+pub const EVICTION_COOLDOWN: Duration = Duration::from_secs(300);
+
+pub(crate) struct ConnectionStats {
+    connection: Connection,
+    remote_pubkey: Option<Pubkey>,
+    peer_type: ConnectionPeerType,
+    total_stake: u64,
+    stream_load_ema: Arc<StakedStreamLoadEMA>,
+    connected_at: Instant,
+    pub(crate) streams_opened: AtomicU64,
+    pub(crate) streams_throttled: AtomicU64,
+    pub(crate) streams_dropped: AtomicU64,
+}
+
+/// Point in time view of a live connection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConnectionStatsSnapshot {
+    pub remote_address: SocketAddr,
+    pub remote_pubkey: Option<Pubkey>,
+    /// Zero for unstaked peers, including the staked peers whose stake is too
+    /// low for them to be treated as staked.
+    pub stake: u64,
+    pub age: Duration,
+    pub streams_opened: u64,
+    pub streams_throttled: u64,
+    /// Streams which were not fully read, e.g. timed out or invalid chunks.
+    pub streams_dropped: u64,
+    /// Maximum number of streams the connection may currently open within a
+    /// stream throttling interval. This varies with the load of the server.
+    pub max_streams: u64,
+    pub rtt: Duration,
+}
+
+/// Live connections of a QUIC server, keyed by the connection's stable id.
+#[derive(Default)]
+pub struct ConnectionStatsTable {
+    connections: RwLock<HashMap<usize, Arc<ConnectionStats>>>,
+    // Identity of the evicted peers, along with the time of eviction.
+    evicted_peers: RwLock<HashMap<Pubkey, Instant>>,
+}
+
+impl ConnectionStats {
+    fn snapshot(&self) -> ConnectionStatsSnapshot {
+        ConnectionStatsSnapshot {
+            remote_address: self.connection.remote_address(),
+            remote_pubkey: self.remote_pubkey,
+            stake: match self.peer_type {
+                ConnectionPeerType::Unstaked => 0,
+                ConnectionPeerType::Staked(stake) => stake,
+            },
+            age: self.connected_at.elapsed(),
+            streams_opened: self.streams_opened.load(Ordering::Relaxed),
+            streams_throttled: self.streams_throttled.load(Ordering::Relaxed),
+            streams_dropped: self.streams_dropped.load(Ordering::Relaxed),
+            max_streams: self
+                .stream_load_ema
+                .available_load_capacity_in_throttling_duration(self.peer_type, self.total_stake),
+            rtt: self.connection.rtt(),
+        }
+    }
+}
+
+impl ConnectionStatsTable {
+    pub(crate) fn add_connection(
+        &self,
+        connection: Connection,
+        remote_pubkey: Option<Pubkey>,
+        peer_type: ConnectionPeerType,
+        total_stake: u64,
+        stream_load_ema: Arc<StakedStreamLoadEMA>,
+    ) -> Arc<ConnectionStats> {
+        let stable_id = connection.stable_id();
+        let stats = Arc::new(ConnectionStats {
+            connection,
+            remote_pubkey,
+            peer_type,
+            total_stake,
+            stream_load_ema,
+            connected_at: Instant::now(),
+            streams_opened: AtomicU64::default(),
+            streams_throttled: AtomicU64::default(),
+            streams_dropped: AtomicU64::default(),
+        });
+        self.connections
+            .write()
+            .unwrap()
+            .insert(stable_id, stats.clone());
+        stats
+    }
+
+    pub(crate) fn remove_connection(&self, stable_id: usize) {
+        self.connections.write().unwrap().remove(&stable_id);
+    }
+
+    /// Returns the statistics of all the live connections.
+    pub fn connections(&self) -> Vec<ConnectionStatsSnapshot> {
+        self.connections
+            .read()
+            .unwrap()
+            .values()
+            .map(|stats| stats.snapshot())
+            .collect()
+    }
+
+    /// Closes all the connections of the peer with the given identity, and
+    /// returns the number of connections closed. New connections of the peer
+    /// are refused for `EVICTION_COOLDOWN`.
+    pub fn evict_peer(&self, pubkey: &Pubkey) -> usize {
+        self.evicted_peers
+            .write()
+            .unwrap()
+            .insert(*pubkey, Instant::now());
+        let connections = self.connections.read().unwrap();
+        let mut num_evicted = 0;
+        for stats in connections
+            .values()
+            .filter(|stats| stats.remote_pubkey.as_ref() == Some(pubkey))
+        {
+            stats.connection.close(
+                CONNECTION_CLOSE_CODE_EVICTED.into(),
+                CONNECTION_CLOSE_REASON_EVICTED,
+            );
+            num_evicted += 1;
+        }
+        num_evicted
+    }
+
+    /// Returns true if the peer was evicted less than `EVICTION_COOLDOWN` ago.
+    pub(crate) fn is_evicted(&self, pubkey: &Pubkey) -> bool {
+        let Some(evicted_at) = self.evicted_peers.read().unwrap().get(pubkey).copied() else {
+            return false;
+        };
+        if evicted_at.elapsed() < EVICTION_COOLDOWN {
+            return true;
+        }
+        let mut evicted_peers = self.evicted_peers.write().unwrap();
+        evicted_peers.retain(|_, evicted_at| evicted_at.elapsed() < EVICTION_COOLDOWN);
+        evicted_peers.contains_key(pubkey)
+    }
+}
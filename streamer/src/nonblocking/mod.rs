@@ -1,4 +1,5 @@
 pub mod connection_rate_limiter;
+pub mod connection_stats;
 pub mod quic;
 pub mod recvmmsg;
 pub mod sendmmsg;
@@ -2,6 +2,7 @@ use {
     crate::{
         nonblocking::{
             connection_rate_limiter::{ConnectionRateLimiter, TotalConnectionRateLimiter},
+            connection_stats::{ConnectionStats, ConnectionStatsTable},
             stream_throttle::{
                 ConnectionStreamCounter, StakedStreamLoadEMA, STREAM_THROTTLING_INTERVAL,
                 STREAM_THROTTLING_INTERVAL_MS,
@@ -83,6 +84,9 @@ const CONNECTION_CLOSE_REASON_EXCEED_MAX_STREAM_COUNT: &[u8] = b"exceed_max_stre
 const CONNECTION_CLOSE_CODE_TOO_MANY: u32 = 4;
 const CONNECTION_CLOSE_REASON_TOO_MANY: &[u8] = b"too_many";
 
+pub(crate) const CONNECTION_CLOSE_CODE_EVICTED: u32 = 5;
+pub(crate) const CONNECTION_CLOSE_REASON_EVICTED: &[u8] = b"evicted";
+
 /// Limit to 250K PPS
 pub const DEFAULT_MAX_STREAMS_PER_MS: u64 = 250;
 
@@ -141,6 +145,7 @@ impl ConnectionPeerType {
 pub struct SpawnNonBlockingServerResult {
     pub endpoints: Vec<Endpoint>,
     pub stats: Arc<StreamerStats>,
+    pub connection_stats_table: Arc<ConnectionStatsTable>,
     pub thread: JoinHandle<()>,
     pub max_concurrent_connections: usize,
 }
@@ -213,6 +218,7 @@ pub fn spawn_server_multi(
         })
         .collect::<Result<Vec<_>, _>>()?;
     let stats = Arc::<StreamerStats>::default();
+    let connection_stats_table = Arc::<ConnectionStatsTable>::default();
     let handle = tokio::spawn(run_server(
         name,
         endpoints.clone(),
@@ -225,12 +231,14 @@ pub fn spawn_server_multi(
         max_streams_per_ms,
         max_connections_per_ipaddr_per_min,
         stats.clone(),
+        connection_stats_table.clone(),
         wait_for_chunk_timeout,
         coalesce,
     ));
     Ok(SpawnNonBlockingServerResult {
         endpoints,
         stats,
+        connection_stats_table,
         thread: handle,
         max_concurrent_connections,
     })
@@ -249,6 +257,7 @@ async fn run_server(
     max_streams_per_ms: u64,
     max_connections_per_ipaddr_per_min: u64,
     stats: Arc<StreamerStats>,
+    connection_stats_table: Arc<ConnectionStatsTable>,
     wait_for_chunk_timeout: Duration,
     coalesce: Duration,
 ) {
@@ -366,6 +375,7 @@ async fn run_server(
                 max_unstaked_connections,
                 max_streams_per_ms,
                 stats.clone(),
+                connection_stats_table.clone(),
                 wait_for_chunk_timeout,
                 stream_load_ema.clone(),
             ));
@@ -462,6 +472,7 @@ struct NewConnectionHandlerParams {
     total_stake: u64,
     max_connections_per_peer: usize,
     stats: Arc<StreamerStats>,
+    connection_stats_table: Arc<ConnectionStatsTable>,
     max_stake: u64,
     min_stake: u64,
 }
@@ -471,6 +482,7 @@ impl NewConnectionHandlerParams {
         packet_sender: AsyncSender<PacketAccumulator>,
         max_connections_per_peer: usize,
         stats: Arc<StreamerStats>,
+        connection_stats_table: Arc<ConnectionStatsTable>,
     ) -> NewConnectionHandlerParams {
         NewConnectionHandlerParams {
             packet_sender,
@@ -479,6 +491,7 @@ impl NewConnectionHandlerParams {
             total_stake: 0,
             max_connections_per_peer,
             stats,
+            connection_stats_table,
             max_stake: 0,
             min_stake: 0,
         }
@@ -527,6 +540,13 @@ fn handle_and_cache_new_connection(
                 connection.set_receive_window(receive_window);
             }
             connection.set_max_concurrent_uni_streams(max_uni_streams);
+            let connection_stats = params.connection_stats_table.add_connection(
+                connection.clone(),
+                params.remote_pubkey,
+                params.peer_type,
+                params.total_stake,
+                stream_load_ema.clone(),
+            );
 
             tokio::spawn(handle_connection(
                 connection,
@@ -538,6 +558,7 @@ fn handle_and_cache_new_connection(
                 wait_for_chunk_timeout,
                 stream_load_ema,
                 stream_counter,
+                connection_stats,
             ));
             Ok(())
         } else {
@@ -645,6 +666,7 @@ async fn setup_connection(
     max_unstaked_connections: usize,
     max_streams_per_ms: u64,
     stats: Arc<StreamerStats>,
+    connection_stats_table: Arc<ConnectionStatsTable>,
     wait_for_chunk_timeout: Duration,
     stream_load_ema: Arc<StakedStreamLoadEMA>,
 ) {
@@ -664,6 +686,7 @@ async fn setup_connection(
                         packet_sender.clone(),
                         max_connections_per_peer,
                         stats.clone(),
+                        connection_stats_table.clone(),
                     ),
                     |(pubkey, stake, total_stake, max_stake, min_stake)| {
                         // The heuristic is that the stake should be large engouh to have 1 stream pass throuh within one throttle
@@ -684,12 +707,26 @@ async fn setup_connection(
                             total_stake,
                             max_connections_per_peer,
                             stats: stats.clone(),
+                            connection_stats_table: connection_stats_table.clone(),
                             max_stake,
                             min_stake,
                         }
                     },
                 );
 
+                if let Some(pubkey) = params.remote_pubkey {
+                    if connection_stats_table.is_evicted(&pubkey) {
+                        new_connection.close(
+                            CONNECTION_CLOSE_CODE_EVICTED.into(),
+                            CONNECTION_CLOSE_REASON_EVICTED,
+                        );
+                        stats
+                            .connection_refused_evicted_peer
+                            .fetch_add(1, Ordering::Relaxed);
+                        return;
+                    }
+                }
+
                 match params.peer_type {
                     ConnectionPeerType::Staked(stake) => {
                         let mut connection_table_l = staked_connection_table.lock().await;
@@ -947,6 +984,7 @@ fn track_streamer_fetch_packet_performance(
         .fetch_add(measure.as_us(), Ordering::Relaxed);
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn handle_connection(
     connection: Connection,
     remote_addr: SocketAddr,
@@ -957,6 +995,7 @@ async fn handle_connection(
     wait_for_chunk_timeout: Duration,
     stream_load_ema: Arc<StakedStreamLoadEMA>,
     stream_counter: Arc<ConnectionStreamCounter>,
+    connection_stats: Arc<ConnectionStats>,
 ) {
     let stats = params.stats;
     debug!(
@@ -995,6 +1034,9 @@ async fn handle_connection(
                                     throttle_duration: {throttle_duration:?}",
                                     params.peer_type, params.total_stake);
                             stats.throttled_streams.fetch_add(1, Ordering::Relaxed);
+                            connection_stats
+                                .streams_throttled
+                                .fetch_add(1, Ordering::Relaxed);
                             match params.peer_type {
                                 ConnectionPeerType::Unstaked => {
                                     stats
@@ -1014,8 +1056,12 @@ async fn handle_connection(
                     stream_counter.stream_count.fetch_add(1, Ordering::Relaxed);
                     stats.total_streams.fetch_add(1, Ordering::Relaxed);
                     stats.total_new_streams.fetch_add(1, Ordering::Relaxed);
+                    connection_stats
+                        .streams_opened
+                        .fetch_add(1, Ordering::Relaxed);
                     let stream_exit = stream_exit.clone();
                     let stats = stats.clone();
+                    let connection_stats = connection_stats.clone();
                     let packet_sender = params.packet_sender.clone();
                     let last_update = last_update.clone();
                     let stream_load_ema = stream_load_ema.clone();
@@ -1036,6 +1082,7 @@ async fn handle_connection(
                             )
                             .await
                             {
+                                let end_of_stream = matches!(chunk, Ok(None));
                                 if handle_chunk(
                                     chunk,
                                     &mut maybe_batch,
@@ -1046,6 +1093,11 @@ async fn handle_connection(
                                 )
                                 .await
                                 {
+                                    if !end_of_stream {
+                                        connection_stats
+                                            .streams_dropped
+                                            .fetch_add(1, Ordering::Relaxed);
+                                    }
                                     last_update.store(timing::timestamp(), Ordering::Relaxed);
                                     break;
                                 }
@@ -1055,6 +1107,9 @@ async fn handle_connection(
                                 stats
                                     .total_stream_read_timeouts
                                     .fetch_add(1, Ordering::Relaxed);
+                                connection_stats
+                                    .streams_dropped
+                                    .fetch_add(1, Ordering::Relaxed);
                                 break;
                             }
                         }
@@ -1075,6 +1130,7 @@ async fn handle_connection(
         remote_addr.port(),
         stable_id,
     );
+    params.connection_stats_table.remove_connection(stable_id);
     if removed_connection_count > 0 {
         stats
             .connection_removed
@@ -1921,6 +1977,7 @@ pub mod test {
             stats: _,
             thread: t,
             max_concurrent_connections: _,
+            connection_stats_table: _,
         } = spawn_server(
             "quic_streamer_test",
             s,
@@ -1957,6 +2014,7 @@ pub mod test {
             stats,
             thread: t,
             max_concurrent_connections: _,
+            connection_stats_table: _,
         } = spawn_server(
             "quic_streamer_test",
             s,
@@ -1985,6 +2043,106 @@ pub mod test {
         assert_eq!(stats.total_new_connections.load(Ordering::Relaxed), 2);
     }
 
+    #[tokio::test]
+    async fn test_quic_server_connection_stats_table() {
+        solana_logger::setup();
+        let s = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let exit = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = unbounded();
+        let keypair = Keypair::new();
+        let server_address = s.local_addr().unwrap();
+        let client_keypair = Keypair::new();
+        let stakes = HashMap::from([(client_keypair.pubkey(), 100_000)]);
+        let staked_nodes = Arc::new(RwLock::new(StakedNodes::new(
+            Arc::new(stakes),
+            HashMap::<Pubkey, u64>::default(), // overrides
+        )));
+        let SpawnNonBlockingServerResult {
+            endpoints: _,
+            stats: _,
+            connection_stats_table,
+            thread: t,
+            max_concurrent_connections: _,
+        } = spawn_server(
+            "quic_streamer_test",
+            s,
+            &keypair,
+            sender,
+            exit.clone(),
+            1,
+            staked_nodes,
+            MAX_STAKED_CONNECTIONS,
+            MAX_UNSTAKED_CONNECTIONS,
+            DEFAULT_MAX_STREAMS_PER_MS,
+            DEFAULT_MAX_CONNECTIONS_PER_IPADDR_PER_MINUTE,
+            DEFAULT_WAIT_FOR_CHUNK_TIMEOUT,
+            DEFAULT_TPU_COALESCE,
+        )
+        .unwrap();
+
+        let conn = make_client_endpoint(&server_address, Some(&client_keypair)).await;
+        for _ in 0..5 {
+            let mut stream = conn.open_uni().await.unwrap();
+            stream.write_all(&[0u8]).await.unwrap();
+            stream.finish().await.unwrap();
+        }
+        let mut num_packets = 0;
+        while num_packets < 5 {
+            let packets = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+            num_packets += packets.len();
+        }
+
+        let connections = connection_stats_table.connections();
+        assert_eq!(connections.len(), 1);
+        let connection = &connections[0];
+        assert_eq!(connection.remote_pubkey, Some(client_keypair.pubkey()));
+        assert_eq!(connection.stake, 100_000);
+        assert_eq!(connection.streams_opened, 5);
+        assert_eq!(connection.streams_throttled, 0);
+        assert_eq!(connection.streams_dropped, 0);
+        // The server is lightly loaded, so the allotment matches the one of an
+        // idle server.
+        let stream_load_ema = StakedStreamLoadEMA::new(
+            Arc::default(),
+            MAX_UNSTAKED_CONNECTIONS,
+            DEFAULT_MAX_STREAMS_PER_MS,
+        );
+        assert_eq!(
+            connection.max_streams,
+            stream_load_ema.available_load_capacity_in_throttling_duration(
+                ConnectionPeerType::Staked(100_000),
+                100_000
+            )
+        );
+
+        assert_eq!(connection_stats_table.evict_peer(&Pubkey::new_unique()), 0);
+        assert_eq!(
+            connection_stats_table.evict_peer(&client_keypair.pubkey()),
+            1
+        );
+        assert_matches!(
+            conn.closed().await,
+            quinn::ConnectionError::ApplicationClosed(_)
+        );
+        let mut num_retries = 0;
+        while !connection_stats_table.connections().is_empty() {
+            num_retries += 1;
+            assert!(num_retries < 50);
+            sleep(Duration::from_millis(100)).await;
+        }
+
+        // The evicted peer is refused when reconnecting.
+        let conn = make_client_endpoint(&server_address, Some(&client_keypair)).await;
+        assert_matches!(
+            conn.closed().await,
+            quinn::ConnectionError::ApplicationClosed(close)
+                if close.error_code == CONNECTION_CLOSE_CODE_EVICTED.into()
+        );
+        assert!(connection_stats_table.connections().is_empty());
+        exit.store(true, Ordering::Relaxed);
+        t.await.unwrap();
+    }
+
     #[test]
     fn test_prune_table_with_ip() {
         use std::net::Ipv4Addr;
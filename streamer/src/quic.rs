@@ -1,6 +1,7 @@
 use {
     crate::{
-        nonblocking::quic::ALPN_TPU_PROTOCOL_ID, streamer::StakedNodes,
+        nonblocking::{connection_stats::ConnectionStatsTable, quic::ALPN_TPU_PROTOCOL_ID},
+        streamer::StakedNodes,
         tls_certificates::new_dummy_x509_certificate,
     },
     crossbeam_channel::Sender,
@@ -39,10 +40,33 @@ impl SkipClientVerification {
     }
 }
 
+pub struct SkipServerVerification;
+
+impl SkipServerVerification {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self)
+    }
+}
+
+impl rustls::client::ServerCertVerifier for SkipServerVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::ServerCertVerified::assertion())
+    }
+}
+
 pub struct SpawnServerResult {
     pub endpoints: Vec<Endpoint>,
     pub thread: thread::JoinHandle<()>,
     pub key_updater: Arc<EndpointKeyUpdater>,
+    pub connection_stats_table: Arc<ConnectionStatsTable>,
 }
 
 impl rustls::server::ClientCertVerifier for SkipClientVerification {
@@ -174,6 +198,7 @@ pub struct StreamerStats {
     pub(crate) connection_setup_timeout: AtomicUsize,
     pub(crate) connection_setup_error: AtomicUsize,
     pub(crate) connection_setup_error_closed: AtomicUsize,
+    pub(crate) connection_refused_evicted_peer: AtomicUsize,
     pub(crate) connection_setup_error_timed_out: AtomicUsize,
     pub(crate) connection_setup_error_transport: AtomicUsize,
     pub(crate) connection_setup_error_app_closed: AtomicUsize,
@@ -312,6 +337,12 @@ impl StreamerStats {
                     .swap(0, Ordering::Relaxed),
                 i64
             ),
+            (
+                "connection_refused_evicted_peer",
+                self.connection_refused_evicted_peer
+                    .swap(0, Ordering::Relaxed),
+                i64
+            ),
             (
                 "connection_setup_error_transport",
                 self.connection_setup_error_transport
@@ -637,6 +668,7 @@ pub fn spawn_server_multi(
         endpoints: result.endpoints,
         thread: handle,
         key_updater: Arc::new(updater),
+        connection_stats_table: result.connection_stats_table,
     })
 }
 
@@ -669,6 +701,7 @@ mod test {
             endpoints: _,
             thread: t,
             key_updater: _,
+            connection_stats_table: _,
         } = spawn_server(
             "solQuicTest",
             "quic_streamer_test",
@@ -730,6 +763,7 @@ mod test {
             endpoints: _,
             thread: t,
             key_updater: _,
+            connection_stats_table: _,
         } = spawn_server(
             "solQuicTest",
             "quic_streamer_test",
@@ -778,6 +812,7 @@ mod test {
             endpoints: _,
             thread: t,
             key_updater: _,
+            connection_stats_table: _,
         } = spawn_server(
             "solQuicTest",
             "quic_streamer_test",
@@ -0,0 +1,1156 @@
+//! QUIC endpoint exchanging messages with peers over connections bound to the
+//! remote node's identity by its TLS certificate. Connections are cached by
+//! the remote node's pubkey and once the cache grows too large, connections to
+//! the nodes with the least stake are pruned.
+//!
+//! Turbine and gossip run their own endpoints, which differ in the ALPN
+//! protocol id and in how messages are carried over a connection.
+
+use {
+    crate::{
+        nonblocking::quic::get_remote_pubkey as get_remote_pubkey_from_connection,
+        quic::{SkipClientVerification, SkipServerVerification},
+        tls_certificates::new_dummy_x509_certificate,
+    },
+    bytes::Bytes,
+    crossbeam_channel::Sender,
+    futures::future::TryJoin,
+    quinn::{
+        ClientConfig, ConnectError, Connecting, Connection, ConnectionError, Endpoint,
+        EndpointConfig, IdleTimeout, ReadToEndError, RecvStream, SendDatagramError, ServerConfig,
+        TokioRuntime, TransportConfig, VarInt, WriteError,
+    },
+    rustls::{Certificate, PrivateKey},
+    solana_sdk::{pubkey::Pubkey, signature::Keypair},
+    std::{
+        cmp::Reverse,
+        collections::{hash_map::Entry, HashMap},
+        io::Error as IoError,
+        net::{SocketAddr, UdpSocket},
+        sync::{
+            atomic::{AtomicBool, AtomicU64, Ordering},
+            Arc,
+        },
+        time::Duration,
+    },
+    thiserror::Error,
+    tokio::{
+        sync::{
+            mpsc::{error::TrySendError, Receiver as AsyncReceiver, Sender as AsyncSender},
+            Mutex, RwLock as AsyncRwLock,
+        },
+        task::JoinHandle,
+    },
+};
+
+const CLIENT_CHANNEL_BUFFER: usize = 1 << 14;
+
+// Transport config.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(4);
+const INITIAL_MAXIMUM_TRANSMISSION_UNIT: u16 = MINIMUM_MAXIMUM_TRANSMISSION_UNIT;
+const MINIMUM_MAXIMUM_TRANSMISSION_UNIT: u16 = 1280;
+
+const CONNECTION_CLOSE_ERROR_CODE_SHUTDOWN: VarInt = VarInt::from_u32(1);
+const CONNECTION_CLOSE_ERROR_CODE_DROPPED: VarInt = VarInt::from_u32(2);
+const CONNECTION_CLOSE_ERROR_CODE_INVALID_IDENTITY: VarInt = VarInt::from_u32(3);
+const CONNECTION_CLOSE_ERROR_CODE_REPLACED: VarInt = VarInt::from_u32(4);
+const CONNECTION_CLOSE_ERROR_CODE_PRUNED: VarInt = VarInt::from_u32(5);
+
+const CONNECTION_CLOSE_REASON_SHUTDOWN: &[u8] = b"SHUTDOWN";
+const CONNECTION_CLOSE_REASON_DROPPED: &[u8] = b"DROPPED";
+const CONNECTION_CLOSE_REASON_INVALID_IDENTITY: &[u8] = b"INVALID_IDENTITY";
+const CONNECTION_CLOSE_REASON_REPLACED: &[u8] = b"REPLACED";
+const CONNECTION_CLOSE_REASON_PRUNED: &[u8] = b"PRUNED";
+
+pub type AsyncTryJoinHandle = TryJoin<JoinHandle<()>, JoinHandle<()>>;
+
+/// Returns the stake of each node, used to prioritize connections when
+/// pruning the connection cache.
+pub type GetStakedNodes = Arc<dyn Fn() -> Arc<HashMap<Pubkey, u64>> + Send + Sync>;
+
+#[derive(Clone, Copy, Debug)]
+pub enum QuicEndpointTransport {
+    /// Each message is sent in a single unreliable datagram, and so has to
+    /// fit in the minimum MTU.
+    Datagram {
+        receive_buffer_size: usize,
+        send_buffer_size: usize,
+    },
+    /// Each message is sent over its own unidirectional stream.
+    UniStream {
+        max_concurrent_streams: u32,
+        max_message_size: usize,
+        read_timeout: Duration,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct QuicEndpointConfig {
+    /// ALPN protocol id, also used as the server name when connecting.
+    pub protocol_id: &'static str,
+    pub server_metrics_name: &'static str,
+    pub client_metrics_name: &'static str,
+    /// Number of outgoing messages buffered for each connection.
+    pub router_channel_buffer: usize,
+    /// Number of connections retained when the connection cache is pruned.
+    pub connection_cache_capacity: usize,
+    pub max_idle_timeout: Duration,
+    pub transport: QuicEndpointTransport,
+}
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Channel Send Error")]
+    ChannelSendError,
+    #[error(transparent)]
+    ConnectError(#[from] ConnectError),
+    #[error(transparent)]
+    ConnectionError(#[from] ConnectionError),
+    #[error("Invalid Identity: {0:?}")]
+    InvalidIdentity(SocketAddr),
+    #[error(transparent)]
+    IoError(#[from] IoError),
+    #[error(transparent)]
+    ReadToEndError(#[from] ReadToEndError),
+    #[error("read_to_end Timeout")]
+    ReadToEndTimeout,
+    #[error(transparent)]
+    SendDatagramError(#[from] SendDatagramError),
+    #[error(transparent)]
+    TlsError(#[from] rustls::Error),
+    #[error(transparent)]
+    WriteError(#[from] WriteError),
+}
+
+macro_rules! add_metric {
+    ($metric: expr) => {{
+        $metric.fetch_add(1, Ordering::Relaxed);
+    }};
+}
+
+#[allow(clippy::type_complexity)]
+pub fn new_quic_endpoint(
+    runtime: &tokio::runtime::Handle,
+    config: QuicEndpointConfig,
+    keypair: &Keypair,
+    socket: UdpSocket,
+    sender: Sender<(Pubkey, SocketAddr, Bytes)>,
+    get_staked_nodes: GetStakedNodes,
+) -> Result<
+    (
+        Endpoint,
+        AsyncSender<(SocketAddr, Bytes)>,
+        AsyncTryJoinHandle,
+    ),
+    Error,
+> {
+    let (cert, key) = new_dummy_x509_certificate(keypair);
+    let server_config = new_server_config(&config, cert.clone(), key.clone())?;
+    let client_config = new_client_config(&config, cert, key)?;
+    let mut endpoint = {
+        // Endpoint::new requires entering the runtime context,
+        // otherwise the code below will panic.
+        let _guard = runtime.enter();
+        Endpoint::new(
+            EndpointConfig::default(),
+            Some(server_config),
+            socket,
+            Arc::new(TokioRuntime),
+        )?
+    };
+    endpoint.set_default_client_config(client_config);
+    let prune_cache_pending = Arc::<AtomicBool>::default();
+    let cache = Arc::<Mutex<HashMap<Pubkey, Connection>>>::default();
+    let router = Arc::<AsyncRwLock<HashMap<SocketAddr, AsyncSender<Bytes>>>>::default();
+    let (client_sender, client_receiver) = tokio::sync::mpsc::channel(CLIENT_CHANNEL_BUFFER);
+    let server_task = runtime.spawn(run_server(
+        config,
+        endpoint.clone(),
+        sender.clone(),
+        get_staked_nodes.clone(),
+        prune_cache_pending.clone(),
+        router.clone(),
+        cache.clone(),
+    ));
+    let client_task = runtime.spawn(run_client(
+        config,
+        endpoint.clone(),
+        client_receiver,
+        sender,
+        get_staked_nodes,
+        prune_cache_pending,
+        router,
+        cache,
+    ));
+    let task = futures::future::try_join(server_task, client_task);
+    Ok((endpoint, client_sender, task))
+}
+
+pub fn close_quic_endpoint(endpoint: &Endpoint) {
+    endpoint.close(
+        CONNECTION_CLOSE_ERROR_CODE_SHUTDOWN,
+        CONNECTION_CLOSE_REASON_SHUTDOWN,
+    );
+}
+
+fn new_server_config(
+    config: &QuicEndpointConfig,
+    cert: Certificate,
+    key: PrivateKey,
+) -> Result<ServerConfig, rustls::Error> {
+    let mut crypto = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_client_cert_verifier(SkipClientVerification::new())
+        .with_single_cert(vec![cert], key)?;
+    crypto.alpn_protocols = vec![config.protocol_id.as_bytes().to_vec()];
+    let mut server_config = ServerConfig::with_crypto(Arc::new(crypto));
+    server_config
+        .transport_config(Arc::new(new_transport_config(config)))
+        .use_retry(true)
+        .migration(false);
+    Ok(server_config)
+}
+
+fn new_client_config(
+    config: &QuicEndpointConfig,
+    cert: Certificate,
+    key: PrivateKey,
+) -> Result<ClientConfig, rustls::Error> {
+    let mut crypto = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(SkipServerVerification::new())
+        .with_client_auth_cert(vec![cert], key)?;
+    crypto.enable_early_data = true;
+    crypto.alpn_protocols = vec![config.protocol_id.as_bytes().to_vec()];
+    let mut client_config = ClientConfig::new(Arc::new(crypto));
+    client_config.transport_config(Arc::new(new_transport_config(config)));
+    Ok(client_config)
+}
+
+fn new_transport_config(config: &QuicEndpointConfig) -> TransportConfig {
+    let max_idle_timeout = IdleTimeout::try_from(config.max_idle_timeout).unwrap();
+    let mut transport_config = TransportConfig::default();
+    transport_config
+        .keep_alive_interval(Some(KEEP_ALIVE_INTERVAL))
+        .max_concurrent_bidi_streams(VarInt::from(0u8))
+        .max_idle_timeout(Some(max_idle_timeout));
+    match config.transport {
+        QuicEndpointTransport::Datagram {
+            receive_buffer_size,
+            send_buffer_size,
+        } => {
+            transport_config
+                .datagram_receive_buffer_size(Some(receive_buffer_size))
+                .datagram_send_buffer_size(send_buffer_size)
+                .initial_mtu(INITIAL_MAXIMUM_TRANSMISSION_UNIT)
+                .max_concurrent_uni_streams(VarInt::from(0u8))
+                .min_mtu(MINIMUM_MAXIMUM_TRANSMISSION_UNIT)
+                .mtu_discovery_config(None);
+        }
+        QuicEndpointTransport::UniStream {
+            max_concurrent_streams,
+            ..
+        } => {
+            transport_config
+                .datagram_receive_buffer_size(None)
+                .max_concurrent_uni_streams(VarInt::from(max_concurrent_streams));
+        }
+    }
+    transport_config
+}
+
+async fn run_server(
+    config: QuicEndpointConfig,
+    endpoint: Endpoint,
+    sender: Sender<(Pubkey, SocketAddr, Bytes)>,
+    get_staked_nodes: GetStakedNodes,
+    prune_cache_pending: Arc<AtomicBool>,
+    router: Arc<AsyncRwLock<HashMap<SocketAddr, AsyncSender<Bytes>>>>,
+    cache: Arc<Mutex<HashMap<Pubkey, Connection>>>,
+) {
+    let stats = Arc::<QuicEndpointStats>::default();
+    let report_metrics_task = tokio::task::spawn(report_metrics_task(
+        config.server_metrics_name,
+        stats.clone(),
+    ));
+    while let Some(connecting) = endpoint.accept().await {
+        tokio::task::spawn(handle_connecting_task(
+            config,
+            endpoint.clone(),
+            connecting,
+            sender.clone(),
+            get_staked_nodes.clone(),
+            prune_cache_pending.clone(),
+            router.clone(),
+            cache.clone(),
+            stats.clone(),
+        ));
+    }
+    report_metrics_task.abort();
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_client(
+    config: QuicEndpointConfig,
+    endpoint: Endpoint,
+    mut receiver: AsyncReceiver<(SocketAddr, Bytes)>,
+    sender: Sender<(Pubkey, SocketAddr, Bytes)>,
+    get_staked_nodes: GetStakedNodes,
+    prune_cache_pending: Arc<AtomicBool>,
+    router: Arc<AsyncRwLock<HashMap<SocketAddr, AsyncSender<Bytes>>>>,
+    cache: Arc<Mutex<HashMap<Pubkey, Connection>>>,
+) {
+    let stats = Arc::<QuicEndpointStats>::default();
+    let report_metrics_task = tokio::task::spawn(report_metrics_task(
+        config.client_metrics_name,
+        stats.clone(),
+    ));
+    while let Some((remote_address, bytes)) = receiver.recv().await {
+        let Some(bytes) = try_route_bytes(&remote_address, bytes, &*router.read().await, &stats)
+        else {
+            continue;
+        };
+        let receiver = {
+            let mut router = router.write().await;
+            let Some(bytes) = try_route_bytes(&remote_address, bytes, &router, &stats) else {
+                continue;
+            };
+            let (sender, receiver) = tokio::sync::mpsc::channel(config.router_channel_buffer);
+            sender.try_send(bytes).unwrap();
+            router.insert(remote_address, sender);
+            receiver
+        };
+        tokio::task::spawn(make_connection_task(
+            config,
+            endpoint.clone(),
+            remote_address,
+            sender.clone(),
+            receiver,
+            get_staked_nodes.clone(),
+            prune_cache_pending.clone(),
+            router.clone(),
+            cache.clone(),
+            stats.clone(),
+        ));
+    }
+    close_quic_endpoint(&endpoint);
+    // Drop sender channels to unblock threads waiting on the receiving end.
+    router.write().await.clear();
+    report_metrics_task.abort();
+}
+
+fn try_route_bytes(
+    remote_address: &SocketAddr,
+    bytes: Bytes,
+    router: &HashMap<SocketAddr, AsyncSender<Bytes>>,
+    stats: &QuicEndpointStats,
+) -> Option<Bytes> {
+    match router.get(remote_address) {
+        None => Some(bytes),
+        Some(sender) => match sender.try_send(bytes) {
+            Ok(()) => None,
+            Err(TrySendError::Full(_)) => {
+                debug!("TrySendError::Full {remote_address}");
+                add_metric!(stats.router_try_send_error_full);
+                None
+            }
+            Err(TrySendError::Closed(bytes)) => Some(bytes),
+        },
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn handle_connecting_task(
+    config: QuicEndpointConfig,
+    endpoint: Endpoint,
+    connecting: Connecting,
+    sender: Sender<(Pubkey, SocketAddr, Bytes)>,
+    get_staked_nodes: GetStakedNodes,
+    prune_cache_pending: Arc<AtomicBool>,
+    router: Arc<AsyncRwLock<HashMap<SocketAddr, AsyncSender<Bytes>>>>,
+    cache: Arc<Mutex<HashMap<Pubkey, Connection>>>,
+    stats: Arc<QuicEndpointStats>,
+) {
+    if let Err(err) = handle_connecting(
+        config,
+        endpoint,
+        connecting,
+        sender,
+        get_staked_nodes,
+        prune_cache_pending,
+        router,
+        cache,
+        stats.clone(),
+    )
+    .await
+    {
+        debug!("handle_connecting: {err:?}");
+        record_error(&err, &stats);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn handle_connecting(
+    config: QuicEndpointConfig,
+    endpoint: Endpoint,
+    connecting: Connecting,
+    sender: Sender<(Pubkey, SocketAddr, Bytes)>,
+    get_staked_nodes: GetStakedNodes,
+    prune_cache_pending: Arc<AtomicBool>,
+    router: Arc<AsyncRwLock<HashMap<SocketAddr, AsyncSender<Bytes>>>>,
+    cache: Arc<Mutex<HashMap<Pubkey, Connection>>>,
+    stats: Arc<QuicEndpointStats>,
+) -> Result<(), Error> {
+    let connection = connecting.await?;
+    let remote_address = connection.remote_address();
+    let remote_pubkey = get_remote_pubkey(&connection)?;
+    let receiver = {
+        let (sender, receiver) = tokio::sync::mpsc::channel(config.router_channel_buffer);
+        router.write().await.insert(remote_address, sender);
+        receiver
+    };
+    handle_connection(
+        config,
+        endpoint,
+        remote_address,
+        remote_pubkey,
+        connection,
+        sender,
+        receiver,
+        get_staked_nodes,
+        prune_cache_pending,
+        router,
+        cache,
+        stats,
+    )
+    .await;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn handle_connection(
+    config: QuicEndpointConfig,
+    endpoint: Endpoint,
+    remote_address: SocketAddr,
+    remote_pubkey: Pubkey,
+    connection: Connection,
+    sender: Sender<(Pubkey, SocketAddr, Bytes)>,
+    receiver: AsyncReceiver<Bytes>,
+    get_staked_nodes: GetStakedNodes,
+    prune_cache_pending: Arc<AtomicBool>,
+    router: Arc<AsyncRwLock<HashMap<SocketAddr, AsyncSender<Bytes>>>>,
+    cache: Arc<Mutex<HashMap<Pubkey, Connection>>>,
+    stats: Arc<QuicEndpointStats>,
+) {
+    cache_connection(
+        config,
+        remote_pubkey,
+        connection.clone(),
+        get_staked_nodes,
+        prune_cache_pending,
+        router.clone(),
+        cache.clone(),
+    )
+    .await;
+    let (send_task, recv_task) = match config.transport {
+        QuicEndpointTransport::Datagram { .. } => (
+            tokio::task::spawn(send_datagram_task(connection.clone(), receiver)),
+            tokio::task::spawn(read_datagram_task(
+                endpoint,
+                remote_address,
+                remote_pubkey,
+                connection.clone(),
+                sender,
+                stats.clone(),
+            )),
+        ),
+        QuicEndpointTransport::UniStream {
+            max_message_size,
+            read_timeout,
+            ..
+        } => (
+            tokio::task::spawn(send_messages_task(
+                connection.clone(),
+                receiver,
+                stats.clone(),
+            )),
+            tokio::task::spawn(recv_messages_task(
+                endpoint,
+                remote_address,
+                remote_pubkey,
+                connection.clone(),
+                sender,
+                max_message_size,
+                read_timeout,
+                stats.clone(),
+            )),
+        ),
+    };
+    match futures::future::try_join(send_task, recv_task).await {
+        Err(err) => error!("handle_connection: {remote_pubkey}, {remote_address}, {err:?}"),
+        Ok(out) => {
+            if let (Err(ref err), _) = out {
+                debug!("send_task: {remote_pubkey}, {remote_address}, {err:?}");
+                record_error(err, &stats);
+            }
+            if let (_, Err(ref err)) = out {
+                debug!("recv_task: {remote_pubkey}, {remote_address}, {err:?}");
+                record_error(err, &stats);
+            }
+        }
+    }
+    drop_connection(remote_pubkey, &connection, &cache).await;
+    if let Entry::Occupied(entry) = router.write().await.entry(remote_address) {
+        if entry.get().is_closed() {
+            entry.remove();
+        }
+    }
+}
+
+async fn read_datagram_task(
+    endpoint: Endpoint,
+    remote_address: SocketAddr,
+    remote_pubkey: Pubkey,
+    connection: Connection,
+    sender: Sender<(Pubkey, SocketAddr, Bytes)>,
+    stats: Arc<QuicEndpointStats>,
+) -> Result<(), Error> {
+    // Assert that send won't block.
+    debug_assert_eq!(sender.capacity(), None);
+    loop {
+        match connection.read_datagram().await {
+            Ok(bytes) => {
+                if let Err(err) = sender.send((remote_pubkey, remote_address, bytes)) {
+                    close_quic_endpoint(&endpoint);
+                    return Err(Error::from(err));
+                }
+            }
+            Err(err) => {
+                if let Some(err) = connection.close_reason() {
+                    return Err(Error::from(err));
+                }
+                debug!("connection.read_datagram: {remote_pubkey}, {remote_address}, {err:?}");
+                record_error(&Error::from(err), &stats);
+            }
+        };
+    }
+}
+
+async fn send_datagram_task(
+    connection: Connection,
+    mut receiver: AsyncReceiver<Bytes>,
+) -> Result<(), Error> {
+    tokio::pin! {
+        let connection_closed = connection.closed();
+    }
+    loop {
+        tokio::select! {
+            biased;
+            bytes = receiver.recv() => {
+                match bytes {
+                    None => return Ok(()),
+                    Some(bytes) => connection.send_datagram(bytes)?,
+                }
+            }
+            err = &mut connection_closed => return Err(Error::from(err)),
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn recv_messages_task(
+    endpoint: Endpoint,
+    remote_address: SocketAddr,
+    remote_pubkey: Pubkey,
+    connection: Connection,
+    sender: Sender<(Pubkey, SocketAddr, Bytes)>,
+    max_message_size: usize,
+    read_timeout: Duration,
+    stats: Arc<QuicEndpointStats>,
+) -> Result<(), Error> {
+    loop {
+        let recv_stream = connection.accept_uni().await?;
+        tokio::task::spawn(read_message_task(
+            endpoint.clone(),
+            remote_address,
+            remote_pubkey,
+            recv_stream,
+            sender.clone(),
+            max_message_size,
+            read_timeout,
+            stats.clone(),
+        ));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn read_message_task(
+    endpoint: Endpoint,
+    remote_address: SocketAddr,
+    remote_pubkey: Pubkey,
+    recv_stream: RecvStream,
+    sender: Sender<(Pubkey, SocketAddr, Bytes)>,
+    max_message_size: usize,
+    read_timeout: Duration,
+    stats: Arc<QuicEndpointStats>,
+) {
+    if let Err(err) = read_message(
+        &endpoint,
+        remote_address,
+        remote_pubkey,
+        recv_stream,
+        &sender,
+        max_message_size,
+        read_timeout,
+    )
+    .await
+    {
+        debug!("read_message: {remote_pubkey}, {remote_address}, {err:?}");
+        record_error(&err, &stats);
+    }
+}
+
+async fn read_message(
+    endpoint: &Endpoint,
+    remote_address: SocketAddr,
+    remote_pubkey: Pubkey,
+    mut recv_stream: RecvStream,
+    sender: &Sender<(Pubkey, SocketAddr, Bytes)>,
+    max_message_size: usize,
+    read_timeout: Duration,
+) -> Result<(), Error> {
+    // Assert that send won't block.
+    debug_assert_eq!(sender.capacity(), None);
+    let bytes = tokio::time::timeout(read_timeout, recv_stream.read_to_end(max_message_size))
+        .await
+        .map_err(|_| Error::ReadToEndTimeout)??;
+    if let Err(err) = sender.send((remote_pubkey, remote_address, Bytes::from(bytes))) {
+        close_quic_endpoint(endpoint);
+        return Err(Error::from(err));
+    }
+    Ok(())
+}
+
+async fn send_messages_task(
+    connection: Connection,
+    mut receiver: AsyncReceiver<Bytes>,
+    stats: Arc<QuicEndpointStats>,
+) -> Result<(), Error> {
+    tokio::pin! {
+        let connection_closed = connection.closed();
+    }
+    loop {
+        tokio::select! {
+            biased;
+            bytes = receiver.recv() => {
+                match bytes {
+                    None => return Ok(()),
+                    Some(bytes) => {
+                        tokio::task::spawn(send_message_task(
+                            connection.clone(),
+                            bytes,
+                            stats.clone(),
+                        ));
+                    }
+                }
+            }
+            err = &mut connection_closed => return Err(Error::from(err)),
+        }
+    }
+}
+
+async fn send_message_task(connection: Connection, bytes: Bytes, stats: Arc<QuicEndpointStats>) {
+    if let Err(err) = send_message(&connection, &bytes).await {
+        debug!("send_message: {}, {err:?}", connection.remote_address());
+        record_error(&err, &stats);
+    }
+}
+
+async fn send_message(connection: &Connection, bytes: &[u8]) -> Result<(), Error> {
+    let mut send_stream = connection.open_uni().await?;
+    send_stream.write_all(bytes).await?;
+    send_stream.finish().await.map_err(Error::from)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn make_connection_task(
+    config: QuicEndpointConfig,
+    endpoint: Endpoint,
+    remote_address: SocketAddr,
+    sender: Sender<(Pubkey, SocketAddr, Bytes)>,
+    receiver: AsyncReceiver<Bytes>,
+    get_staked_nodes: GetStakedNodes,
+    prune_cache_pending: Arc<AtomicBool>,
+    router: Arc<AsyncRwLock<HashMap<SocketAddr, AsyncSender<Bytes>>>>,
+    cache: Arc<Mutex<HashMap<Pubkey, Connection>>>,
+    stats: Arc<QuicEndpointStats>,
+) {
+    if let Err(err) = make_connection(
+        config,
+        endpoint,
+        remote_address,
+        sender,
+        receiver,
+        get_staked_nodes,
+        prune_cache_pending,
+        router,
+        cache,
+        stats.clone(),
+    )
+    .await
+    {
+        debug!("make_connection: {remote_address}, {err:?}");
+        record_error(&err, &stats);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn make_connection(
+    config: QuicEndpointConfig,
+    endpoint: Endpoint,
+    remote_address: SocketAddr,
+    sender: Sender<(Pubkey, SocketAddr, Bytes)>,
+    receiver: AsyncReceiver<Bytes>,
+    get_staked_nodes: GetStakedNodes,
+    prune_cache_pending: Arc<AtomicBool>,
+    router: Arc<AsyncRwLock<HashMap<SocketAddr, AsyncSender<Bytes>>>>,
+    cache: Arc<Mutex<HashMap<Pubkey, Connection>>>,
+    stats: Arc<QuicEndpointStats>,
+) -> Result<(), Error> {
+    let connection = endpoint
+        .connect(remote_address, config.protocol_id)?
+        .await?;
+    handle_connection(
+        config,
+        endpoint,
+        connection.remote_address(),
+        get_remote_pubkey(&connection)?,
+        connection,
+        sender,
+        receiver,
+        get_staked_nodes,
+        prune_cache_pending,
+        router,
+        cache,
+        stats,
+    )
+    .await;
+    Ok(())
+}
+
+fn get_remote_pubkey(connection: &Connection) -> Result<Pubkey, Error> {
+    match get_remote_pubkey_from_connection(connection) {
+        Some(remote_pubkey) => Ok(remote_pubkey),
+        None => {
+            connection.close(
+                CONNECTION_CLOSE_ERROR_CODE_INVALID_IDENTITY,
+                CONNECTION_CLOSE_REASON_INVALID_IDENTITY,
+            );
+            Err(Error::InvalidIdentity(connection.remote_address()))
+        }
+    }
+}
+
+async fn cache_connection(
+    config: QuicEndpointConfig,
+    remote_pubkey: Pubkey,
+    connection: Connection,
+    get_staked_nodes: GetStakedNodes,
+    prune_cache_pending: Arc<AtomicBool>,
+    router: Arc<AsyncRwLock<HashMap<SocketAddr, AsyncSender<Bytes>>>>,
+    cache: Arc<Mutex<HashMap<Pubkey, Connection>>>,
+) {
+    let (old, should_prune_cache) = {
+        let mut cache = cache.lock().await;
+        (
+            cache.insert(remote_pubkey, connection),
+            cache.len() >= config.connection_cache_capacity.saturating_mul(2),
+        )
+    };
+    if let Some(old) = old {
+        old.close(
+            CONNECTION_CLOSE_ERROR_CODE_REPLACED,
+            CONNECTION_CLOSE_REASON_REPLACED,
+        );
+    }
+    if should_prune_cache && !prune_cache_pending.swap(true, Ordering::Relaxed) {
+        tokio::task::spawn(prune_connection_cache(
+            config,
+            get_staked_nodes,
+            prune_cache_pending,
+            router,
+            cache,
+        ));
+    }
+}
+
+async fn drop_connection(
+    remote_pubkey: Pubkey,
+    connection: &Connection,
+    cache: &Mutex<HashMap<Pubkey, Connection>>,
+) {
+    connection.close(
+        CONNECTION_CLOSE_ERROR_CODE_DROPPED,
+        CONNECTION_CLOSE_REASON_DROPPED,
+    );
+    if let Entry::Occupied(entry) = cache.lock().await.entry(remote_pubkey) {
+        if entry.get().stable_id() == connection.stable_id() {
+            entry.remove();
+        }
+    }
+}
+
+async fn prune_connection_cache(
+    config: QuicEndpointConfig,
+    get_staked_nodes: GetStakedNodes,
+    prune_cache_pending: Arc<AtomicBool>,
+    router: Arc<AsyncRwLock<HashMap<SocketAddr, AsyncSender<Bytes>>>>,
+    cache: Arc<Mutex<HashMap<Pubkey, Connection>>>,
+) {
+    debug_assert!(prune_cache_pending.load(Ordering::Relaxed));
+    let capacity = config.connection_cache_capacity;
+    let staked_nodes = get_staked_nodes();
+    {
+        let mut cache = cache.lock().await;
+        if cache.len() < capacity.saturating_mul(2) {
+            prune_cache_pending.store(false, Ordering::Relaxed);
+            return;
+        }
+        let mut connections: Vec<_> = cache
+            .drain()
+            .filter(|(_, connection)| connection.close_reason().is_none())
+            .map(|entry @ (pubkey, _)| {
+                let stake = staked_nodes.get(&pubkey).copied().unwrap_or_default();
+                (stake, entry)
+            })
+            .collect();
+        if connections.len() > capacity {
+            connections.select_nth_unstable_by_key(capacity, |&(stake, _)| Reverse(stake));
+            for (_, (_, connection)) in &connections[capacity..] {
+                connection.close(
+                    CONNECTION_CLOSE_ERROR_CODE_PRUNED,
+                    CONNECTION_CLOSE_REASON_PRUNED,
+                );
+            }
+        }
+        cache.extend(
+            connections
+                .into_iter()
+                .take(capacity)
+                .map(|(_, entry)| entry),
+        );
+        prune_cache_pending.store(false, Ordering::Relaxed);
+    }
+    router.write().await.retain(|_, sender| !sender.is_closed());
+}
+
+impl<T> From<crossbeam_channel::SendError<T>> for Error {
+    fn from(_: crossbeam_channel::SendError<T>) -> Self {
+        Error::ChannelSendError
+    }
+}
+
+#[derive(Default)]
+struct QuicEndpointStats {
+    connect_error_invalid_remote_address: AtomicU64,
+    connect_error_other: AtomicU64,
+    connect_error_too_many_connections: AtomicU64,
+    connection_error_application_closed: AtomicU64,
+    connection_error_connection_closed: AtomicU64,
+    connection_error_locally_closed: AtomicU64,
+    connection_error_reset: AtomicU64,
+    connection_error_timed_out: AtomicU64,
+    connection_error_transport_error: AtomicU64,
+    connection_error_version_mismatch: AtomicU64,
+    invalid_identity: AtomicU64,
+    read_to_end_error_other: AtomicU64,
+    read_to_end_error_too_long: AtomicU64,
+    read_to_end_timeout: AtomicU64,
+    router_try_send_error_full: AtomicU64,
+    send_datagram_error_connection_lost: AtomicU64,
+    send_datagram_error_too_large: AtomicU64,
+    send_datagram_error_unsupported_by_peer: AtomicU64,
+    write_error: AtomicU64,
+}
+
+async fn report_metrics_task(name: &'static str, stats: Arc<QuicEndpointStats>) {
+    loop {
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        report_metrics(name, &stats);
+    }
+}
+
+fn record_error(err: &Error, stats: &QuicEndpointStats) {
+    match err {
+        Error::ChannelSendError => (),
+        Error::ConnectError(ConnectError::EndpointStopping) => {
+            add_metric!(stats.connect_error_other)
+        }
+        Error::ConnectError(ConnectError::TooManyConnections) => {
+            add_metric!(stats.connect_error_too_many_connections)
+        }
+        Error::ConnectError(ConnectError::InvalidDnsName(_)) => {
+            add_metric!(stats.connect_error_other)
+        }
+        Error::ConnectError(ConnectError::InvalidRemoteAddress(_)) => {
+            add_metric!(stats.connect_error_invalid_remote_address)
+        }
+        Error::ConnectError(ConnectError::NoDefaultClientConfig) => {
+            add_metric!(stats.connect_error_other)
+        }
+        Error::ConnectError(ConnectError::UnsupportedVersion) => {
+            add_metric!(stats.connect_error_other)
+        }
+        Error::ConnectionError(ConnectionError::VersionMismatch) => {
+            add_metric!(stats.connection_error_version_mismatch)
+        }
+        Error::ConnectionError(ConnectionError::TransportError(_)) => {
+            add_metric!(stats.connection_error_transport_error)
+        }
+        Error::ConnectionError(ConnectionError::ConnectionClosed(_)) => {
+            add_metric!(stats.connection_error_connection_closed)
+        }
+        Error::ConnectionError(ConnectionError::ApplicationClosed(_)) => {
+            add_metric!(stats.connection_error_application_closed)
+        }
+        Error::ConnectionError(ConnectionError::Reset) => add_metric!(stats.connection_error_reset),
+        Error::ConnectionError(ConnectionError::TimedOut) => {
+            add_metric!(stats.connection_error_timed_out)
+        }
+        Error::ConnectionError(ConnectionError::LocallyClosed) => {
+            add_metric!(stats.connection_error_locally_closed)
+        }
+        Error::InvalidIdentity(_) => add_metric!(stats.invalid_identity),
+        Error::IoError(_) => (),
+        Error::ReadToEndError(ReadToEndError::TooLong) => {
+            add_metric!(stats.read_to_end_error_too_long)
+        }
+        Error::ReadToEndError(ReadToEndError::Read(_)) => {
+            add_metric!(stats.read_to_end_error_other)
+        }
+        Error::ReadToEndTimeout => add_metric!(stats.read_to_end_timeout),
+        Error::SendDatagramError(SendDatagramError::UnsupportedByPeer) => {
+            add_metric!(stats.send_datagram_error_unsupported_by_peer)
+        }
+        Error::SendDatagramError(SendDatagramError::Disabled) => (),
+        Error::SendDatagramError(SendDatagramError::TooLarge) => {
+            add_metric!(stats.send_datagram_error_too_large)
+        }
+        Error::SendDatagramError(SendDatagramError::ConnectionLost(_)) => {
+            add_metric!(stats.send_datagram_error_connection_lost)
+        }
+        Error::TlsError(_) => (),
+        Error::WriteError(_) => add_metric!(stats.write_error),
+    }
+}
+
+fn report_metrics(name: &'static str, stats: &QuicEndpointStats) {
+    macro_rules! reset_metric {
+        ($metric: expr) => {
+            $metric.swap(0, Ordering::Relaxed)
+        };
+    }
+    datapoint_info!(
+        name,
+        (
+            "connect_error_invalid_remote_address",
+            reset_metric!(stats.connect_error_invalid_remote_address),
+            i64
+        ),
+        (
+            "connect_error_other",
+            reset_metric!(stats.connect_error_other),
+            i64
+        ),
+        (
+            "connect_error_too_many_connections",
+            reset_metric!(stats.connect_error_too_many_connections),
+            i64
+        ),
+        (
+            "connection_error_application_closed",
+            reset_metric!(stats.connection_error_application_closed),
+            i64
+        ),
+        (
+            "connection_error_connection_closed",
+            reset_metric!(stats.connection_error_connection_closed),
+            i64
+        ),
+        (
+            "connection_error_locally_closed",
+            reset_metric!(stats.connection_error_locally_closed),
+            i64
+        ),
+        (
+            "connection_error_reset",
+            reset_metric!(stats.connection_error_reset),
+            i64
+        ),
+        (
+            "connection_error_timed_out",
+            reset_metric!(stats.connection_error_timed_out),
+            i64
+        ),
+        (
+            "connection_error_transport_error",
+            reset_metric!(stats.connection_error_transport_error),
+            i64
+        ),
+        (
+            "connection_error_version_mismatch",
+            reset_metric!(stats.connection_error_version_mismatch),
+            i64
+        ),
+        (
+            "invalid_identity",
+            reset_metric!(stats.invalid_identity),
+            i64
+        ),
+        (
+            "read_to_end_error_other",
+            reset_metric!(stats.read_to_end_error_other),
+            i64
+        ),
+        (
+            "read_to_end_error_too_long",
+            reset_metric!(stats.read_to_end_error_too_long),
+            i64
+        ),
+        (
+            "read_to_end_timeout",
+            reset_metric!(stats.read_to_end_timeout),
+            i64
+        ),
+        (
+            "router_try_send_error_full",
+            reset_metric!(stats.router_try_send_error_full),
+            i64
+        ),
+        (
+            "send_datagram_error_connection_lost",
+            reset_metric!(stats.send_datagram_error_connection_lost),
+            i64
+        ),
+        (
+            "send_datagram_error_too_large",
+            reset_metric!(stats.send_datagram_error_too_large),
+            i64
+        ),
+        (
+            "send_datagram_error_unsupported_by_peer",
+            reset_metric!(stats.send_datagram_error_unsupported_by_peer),
+            i64
+        ),
+        ("write_error", reset_metric!(stats.write_error), i64),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        itertools::{izip, multiunzip},
+        solana_sdk::signature::Signer,
+        std::{iter::repeat_with, net::Ipv4Addr},
+    };
+
+    const TEST_DATAGRAM_CONFIG: QuicEndpointConfig = QuicEndpointConfig {
+        protocol_id: "solana-test",
+        server_metrics_name: "test_quic_server",
+        client_metrics_name: "test_quic_client",
+        router_channel_buffer: 64,
+        connection_cache_capacity: 16,
+        max_idle_timeout: Duration::from_secs(10),
+        transport: QuicEndpointTransport::Datagram {
+            receive_buffer_size: 1 << 20,
+            send_buffer_size: 1 << 20,
+        },
+    };
+
+    const TEST_UNI_STREAM_CONFIG: QuicEndpointConfig = QuicEndpointConfig {
+        transport: QuicEndpointTransport::UniStream {
+            max_concurrent_streams: 64,
+            max_message_size: 16 * 1024,
+            read_timeout: Duration::from_secs(2),
+        },
+        ..TEST_DATAGRAM_CONFIG
+    };
+
+    fn run_quic_endpoints(config: QuicEndpointConfig, message_size: usize) {
+        const NUM_ENDPOINTS: usize = 3;
+        const RECV_TIMEOUT: Duration = Duration::from_secs(60);
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(8)
+            .enable_all()
+            .build()
+            .unwrap();
+        let keypairs: Vec<Keypair> = repeat_with(Keypair::new).take(NUM_ENDPOINTS).collect();
+        let sockets: Vec<UdpSocket> = repeat_with(|| UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)))
+            .take(NUM_ENDPOINTS)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let addresses: Vec<SocketAddr> = sockets
+            .iter()
+            .map(UdpSocket::local_addr)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let (senders, receivers): (Vec<_>, Vec<_>) =
+            repeat_with(crossbeam_channel::unbounded::<(Pubkey, SocketAddr, Bytes)>)
+                .take(NUM_ENDPOINTS)
+                .unzip();
+        let get_staked_nodes: GetStakedNodes = Arc::new(Arc::<HashMap<Pubkey, u64>>::default);
+        let (endpoints, senders, tasks): (Vec<_>, Vec<_>, Vec<_>) =
+            multiunzip(keypairs.iter().zip(sockets).zip(senders).map(
+                |((keypair, socket), sender)| {
+                    new_quic_endpoint(
+                        runtime.handle(),
+                        config,
+                        keypair,
+                        socket,
+                        sender,
+                        get_staked_nodes.clone(),
+                    )
+                    .unwrap()
+                },
+            ));
+        // Send a unique message from each endpoint to every other endpoint.
+        let new_message = |i: usize, j: usize| {
+            let mut bytes = format!("{i}=>{j}").into_bytes();
+            bytes.resize(message_size, i as u8);
+            Bytes::from(bytes)
+        };
+        for (i, (keypair, &address, sender)) in izip!(&keypairs, &addresses, &senders).enumerate() {
+            for (j, &address) in addresses.iter().enumerate() {
+                if i != j {
+                    sender.blocking_send((address, new_message(i, j))).unwrap();
+                }
+            }
+            // Verify all messages are received, along with the sender's identity.
+            for (j, receiver) in receivers.iter().enumerate() {
+                if i != j {
+                    let entry = (keypair.pubkey(), address, new_message(i, j));
+                    assert_eq!(receiver.recv_timeout(RECV_TIMEOUT).unwrap(), entry);
+                }
+            }
+        }
+        drop(senders);
+        for endpoint in endpoints {
+            close_quic_endpoint(&endpoint);
+        }
+        for task in tasks {
+            runtime.block_on(task).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_quic_endpoint_datagram() {
+        run_quic_endpoints(TEST_DATAGRAM_CONFIG, /*message_size:*/ 1000);
+    }
+
+    #[test]
+    fn test_quic_endpoint_uni_stream() {
+        // Messages sent over streams are not bounded by the MTU.
+        run_quic_endpoints(TEST_UNI_STREAM_CONFIG, /*message_size:*/ 8 * 1024);
+    }
+}
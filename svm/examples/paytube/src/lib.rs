@@ -185,6 +185,7 @@ impl PayTubeChannel {
                 enable_log_recording: true,
                 enable_return_data_recording: true,
                 enable_cpi_recording: false,
+                enable_execution_trace: false,
             },
             ..Default::default()
         };
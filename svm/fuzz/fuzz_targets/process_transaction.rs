@@ -0,0 +1,21 @@
+#![no_main]
+
+use {
+    libfuzzer_sys::fuzz_target,
+    solana_sdk::{account::AccountSharedData, native_token::LAMPORTS_PER_SOL, system_program},
+    solana_svm::harness::SvmHarness,
+};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(transaction) = SvmHarness::sanitize_transaction(data) else {
+        return;
+    };
+
+    // Fund the fee payer so the transaction gets past fee validation
+    let harness = SvmHarness::default();
+    harness.set_account(
+        *transaction.message().fee_payer(),
+        AccountSharedData::new(LAMPORTS_PER_SOL, 0, &system_program::id()),
+    );
+    harness.process_transactions(&[transaction]);
+});
@@ -0,0 +1,7 @@
+#![no_main]
+
+use {libfuzzer_sys::fuzz_target, solana_svm::harness::SvmHarness};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = SvmHarness::sanitize_transaction(data);
+});
@@ -0,0 +1,493 @@
+//! A deterministic, in-memory harness for running serialized transactions
+//! through the SVM without standing up a Bank.
+//!
+//! Transactions are parsed from their wire format with
+//! [`TransactionMeta`](agave_transaction_view::transaction_meta::TransactionMeta),
+//! executed against a fixed set of sysvars and builtins, and their results
+//! are committed back to the in-memory account set. Signatures are not
+//! verified, which makes the harness suitable as a fuzzing target; see
+//! `svm/fuzz` for the cargo-fuzz targets built on it.
+
+use {
+    crate::{
+        account_loader::{CheckedTransactionDetails, TransactionCheckResult},
+        account_saver::collect_accounts_to_store,
+        transaction_processing_callback::TransactionProcessingCallback,
+        transaction_processing_result::{
+            TransactionProcessingResult, TransactionProcessingResultExtensions,
+        },
+        transaction_processor::{
+            ExecutionRecordingConfig, LoadAndExecuteSanitizedTransactionsOutput,
+            TransactionBatchProcessor, TransactionProcessingConfig,
+            TransactionProcessingEnvironment,
+        },
+    },
+    agave_transaction_view::transaction_meta::{TransactionMeta, TransactionVersion},
+    solana_bpf_loader_program::syscalls::create_program_runtime_environment_v1,
+    solana_compute_budget::compute_budget::ComputeBudget,
+    solana_loader_v4_program::create_program_runtime_environment_v2,
+    solana_program_runtime::{
+        invoke_context::BuiltinFunctionWithContext,
+        loaded_programs::{
+            BlockRelation, ForkGraph, ProgramCacheEntry, ProgramRuntimeEnvironments,
+        },
+    },
+    solana_sdk::{
+        account::{create_account_shared_data_with_fields, AccountSharedData, ReadableAccount},
+        bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable,
+        clock::{Clock, Epoch, Slot},
+        compute_budget,
+        epoch_schedule::EpochSchedule,
+        feature_set::FeatureSet,
+        fee::FeeStructure,
+        hash::Hash,
+        instruction::CompiledInstruction,
+        loader_v4,
+        message::{v0, Message, MessageHeader, VersionedMessage},
+        native_loader,
+        nonce::state::DurableNonce,
+        pubkey::Pubkey,
+        rent_collector::RentCollector,
+        reserved_account_keys::ReservedAccountKeys,
+        slot_hashes::SlotHashes,
+        stake_history::StakeHistory,
+        system_program,
+        sysvar::Sysvar,
+        transaction::{
+            MessageHash, SanitizedTransaction, SimpleAddressLoader, TransactionError,
+            VersionedTransaction,
+        },
+    },
+    solana_type_overrides::sync::{Arc, RwLock},
+    std::{cmp::Ordering, collections::HashMap},
+    thiserror::Error,
+};
+
+/// Lamports charged per signature unless overridden with
+/// [`SvmHarness::set_lamports_per_signature`].
+pub const DEFAULT_LAMPORTS_PER_SIGNATURE: u64 = 5000;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum HarnessError {
+    #[error("failed to parse transaction")]
+    InvalidTransaction,
+
+    #[error("address table lookups are not supported")]
+    AddressTableLookupsNotSupported,
+
+    #[error("failed to sanitize transaction: {0}")]
+    SanitizeFailed(#[source] TransactionError),
+}
+
+/// Every slot is an ancestor of the slots after it, so programs deployed
+/// by earlier transactions are visible to later ones.
+#[derive(Debug, Default)]
+pub struct HarnessForkGraph;
+
+impl ForkGraph for HarnessForkGraph {
+    fn relationship(&self, a: Slot, b: Slot) -> BlockRelation {
+        match a.cmp(&b) {
+            Ordering::Less => BlockRelation::Ancestor,
+            Ordering::Equal => BlockRelation::Equal,
+            Ordering::Greater => BlockRelation::Descendant,
+        }
+    }
+
+    fn slot_epoch(&self, _slot: Slot) -> Option<Epoch> {
+        Some(0)
+    }
+}
+
+/// The in-memory account set the harness loads from and commits to.
+#[derive(Debug, Default)]
+pub struct HarnessAccounts {
+    accounts: RwLock<HashMap<Pubkey, AccountSharedData>>,
+}
+
+impl TransactionProcessingCallback for HarnessAccounts {
+    fn account_matches_owners(&self, account: &Pubkey, owners: &[Pubkey]) -> Option<usize> {
+        self.accounts
+            .read()
+            .unwrap()
+            .get(account)
+            .filter(|account| account.lamports() != 0)
+            .and_then(|account| owners.iter().position(|owner| account.owner() == owner))
+    }
+
+    fn get_account_shared_data(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.accounts.read().unwrap().get(pubkey).cloned()
+    }
+
+    fn add_builtin_account(&self, name: &str, program_id: &Pubkey) {
+        let account = native_loader::create_loadable_account_with_fields(name, (5000, 0));
+        self.set_account(*program_id, account);
+    }
+}
+
+impl HarnessAccounts {
+    pub fn set_account(&self, pubkey: Pubkey, account: AccountSharedData) {
+        self.accounts.write().unwrap().insert(pubkey, account);
+    }
+
+    fn set_sysvar<S: Sysvar>(&self, sysvar: &S) {
+        self.set_account(
+            S::id(),
+            create_account_shared_data_with_fields(sysvar, (1, 0)),
+        );
+    }
+}
+
+/// Runs serialized transactions through a [`TransactionBatchProcessor`].
+///
+/// The harness is fully deterministic: the clock, blockhash and sysvars are
+/// fixed at construction and only change through the accounts the processed
+/// transactions write.
+pub struct SvmHarness {
+    accounts: HarnessAccounts,
+    batch_processor: TransactionBatchProcessor<HarnessForkGraph>,
+    // The program cache only holds a weak reference to the fork graph
+    _fork_graph: Arc<RwLock<HarnessForkGraph>>,
+    feature_set: Arc<FeatureSet>,
+    fee_structure: FeeStructure,
+    rent_collector: RentCollector,
+    lamports_per_signature: u64,
+    blockhash: Hash,
+}
+
+impl Default for SvmHarness {
+    fn default() -> Self {
+        Self::new(0, FeatureSet::all_enabled())
+    }
+}
+
+impl SvmHarness {
+    pub fn new(slot: Slot, feature_set: FeatureSet) -> Self {
+        let epoch = 0;
+        let feature_set = Arc::new(feature_set);
+        let compute_budget = ComputeBudget::default();
+        let accounts = HarnessAccounts::default();
+        let batch_processor = TransactionBatchProcessor::new(slot, epoch, Default::default());
+        let fork_graph = Arc::new(RwLock::new(HarnessForkGraph));
+        {
+            let mut program_cache = batch_processor.program_cache.write().unwrap();
+            program_cache.environments = ProgramRuntimeEnvironments {
+                program_runtime_v1: Arc::new(
+                    create_program_runtime_environment_v1(
+                        &feature_set,
+                        &compute_budget,
+                        false, /* deployment */
+                        false, /* debugging_features */
+                    )
+                    .unwrap(),
+                ),
+                program_runtime_v2: Arc::new(create_program_runtime_environment_v2(
+                    &compute_budget,
+                    false, /* debugging_features */
+                )),
+            };
+            program_cache.set_fork_graph(Arc::downgrade(&fork_graph));
+        }
+
+        let rent_collector = RentCollector::default();
+        accounts.set_sysvar(&Clock {
+            slot,
+            epoch,
+            ..Clock::default()
+        });
+        accounts.set_sysvar(&EpochSchedule::default());
+        accounts.set_sysvar(&rent_collector.rent);
+        accounts.set_sysvar(&SlotHashes::default());
+        accounts.set_sysvar(&StakeHistory::default());
+        batch_processor.fill_missing_sysvar_cache_entries(&accounts);
+
+        let builtins: [(Pubkey, &str, BuiltinFunctionWithContext); 6] = [
+            (
+                system_program::id(),
+                "system_program",
+                solana_system_program::system_processor::Entrypoint::vm,
+            ),
+            (
+                bpf_loader_deprecated::id(),
+                "solana_bpf_loader_deprecated_program",
+                solana_bpf_loader_program::Entrypoint::vm,
+            ),
+            (
+                bpf_loader::id(),
+                "solana_bpf_loader_program",
+                solana_bpf_loader_program::Entrypoint::vm,
+            ),
+            (
+                bpf_loader_upgradeable::id(),
+                "solana_bpf_loader_upgradeable_program",
+                solana_bpf_loader_program::Entrypoint::vm,
+            ),
+            (
+                compute_budget::id(),
+                "compute_budget_program",
+                solana_compute_budget_program::Entrypoint::vm,
+            ),
+            (
+                loader_v4::id(),
+                "loader_v4",
+                solana_loader_v4_program::Entrypoint::vm,
+            ),
+        ];
+        for (program_id, name, entrypoint) in builtins {
+            batch_processor.add_builtin(
+                &accounts,
+                program_id,
+                name,
+                ProgramCacheEntry::new_builtin(slot, name.len(), entrypoint),
+            );
+        }
+
+        Self {
+            accounts,
+            batch_processor,
+            _fork_graph: fork_graph,
+            feature_set,
+            fee_structure: FeeStructure::default(),
+            rent_collector,
+            lamports_per_signature: DEFAULT_LAMPORTS_PER_SIGNATURE,
+            blockhash: Hash::default(),
+        }
+    }
+
+    pub fn set_lamports_per_signature(&mut self, lamports_per_signature: u64) {
+        self.lamports_per_signature = lamports_per_signature;
+    }
+
+    pub fn set_account(&self, pubkey: Pubkey, account: AccountSharedData) {
+        self.accounts.set_account(pubkey, account);
+    }
+
+    pub fn get_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.accounts.get_account_shared_data(pubkey)
+    }
+
+    /// Parses a serialized transaction into a [`SanitizedTransaction`].
+    ///
+    /// Address table lookups cannot be resolved against the in-memory
+    /// account set, so transactions using them are rejected.
+    pub fn sanitize_transaction(bytes: &[u8]) -> Result<SanitizedTransaction, HarnessError> {
+        let meta = TransactionMeta::try_new(bytes).map_err(|_| HarnessError::InvalidTransaction)?;
+        if meta.num_address_table_lookups() != 0 {
+            return Err(HarnessError::AddressTableLookupsNotSupported);
+        }
+
+        // SAFETY: `meta` was created from `bytes`.
+        let (signatures, account_keys, recent_blockhash, instructions) = unsafe {
+            (
+                meta.signatures(bytes).to_vec(),
+                meta.static_account_keys(bytes).to_vec(),
+                *meta.recent_blockhash(bytes),
+                meta.instructions_iter(bytes)
+                    .map(|instruction| CompiledInstruction {
+                        program_id_index: instruction.program_id_index,
+                        accounts: instruction.accounts.to_vec(),
+                        data: instruction.data.to_vec(),
+                    })
+                    .collect(),
+            )
+        };
+        let header = MessageHeader {
+            num_required_signatures: meta.num_required_signatures(),
+            num_readonly_signed_accounts: meta.num_readonly_signed_accounts(),
+            num_readonly_unsigned_accounts: meta.num_readonly_unsigned_accounts(),
+        };
+        let message = match meta.version() {
+            TransactionVersion::Legacy => VersionedMessage::Legacy(Message {
+                header,
+                account_keys,
+                recent_blockhash,
+                instructions,
+            }),
+            TransactionVersion::V0 => VersionedMessage::V0(v0::Message {
+                header,
+                account_keys,
+                recent_blockhash,
+                instructions,
+                address_table_lookups: vec![],
+            }),
+        };
+
+        SanitizedTransaction::try_create(
+            VersionedTransaction {
+                signatures,
+                message,
+            },
+            MessageHash::Compute,
+            None,
+            SimpleAddressLoader::Disabled,
+            &ReservedAccountKeys::empty_key_set(),
+        )
+        .map_err(HarnessError::SanitizeFailed)
+    }
+
+    /// Parses and processes a single serialized transaction, committing its
+    /// results to the in-memory account set.
+    pub fn process_transaction_bytes(
+        &self,
+        bytes: &[u8],
+    ) -> Result<TransactionProcessingResult, HarnessError> {
+        let transaction = Self::sanitize_transaction(bytes)?;
+        let mut output = self.process_transactions(&[transaction]);
+        Ok(output.processing_results.remove(0))
+    }
+
+    /// Processes a batch of transactions, committing their results to the
+    /// in-memory account set.
+    pub fn process_transactions(
+        &self,
+        transactions: &[SanitizedTransaction],
+    ) -> LoadAndExecuteSanitizedTransactionsOutput {
+        let check_results: Vec<TransactionCheckResult> = transactions
+            .iter()
+            .map(|_| {
+                Ok(CheckedTransactionDetails {
+                    nonce: None,
+                    lamports_per_signature: self.lamports_per_signature,
+                })
+            })
+            .collect();
+        let environment = TransactionProcessingEnvironment {
+            blockhash: self.blockhash,
+            feature_set: self.feature_set.clone(),
+            fee_structure: Some(&self.fee_structure),
+            lamports_per_signature: self.lamports_per_signature,
+            rent_collector: Some(&self.rent_collector),
+            ..TransactionProcessingEnvironment::default()
+        };
+        let config = TransactionProcessingConfig {
+            recording_config: ExecutionRecordingConfig::new_single_setting(true),
+            ..TransactionProcessingConfig::default()
+        };
+
+        let mut output = self
+            .batch_processor
+            .load_and_execute_sanitized_transactions(
+                &self.accounts,
+                transactions,
+                check_results,
+                &environment,
+                &config,
+            );
+        self.commit(transactions, &mut output.processing_results);
+        output
+    }
+
+    fn commit(
+        &self,
+        transactions: &[SanitizedTransaction],
+        processing_results: &mut [TransactionProcessingResult],
+    ) {
+        {
+            let mut program_cache = self.batch_processor.program_cache.write().unwrap();
+            for processed_tx in processing_results
+                .iter()
+                .filter_map(|result| result.processed_transaction())
+                .filter(|processed_tx| processed_tx.was_successful())
+            {
+                program_cache.merge(&processed_tx.programs_modified_by_tx);
+            }
+        }
+
+        let durable_nonce = DurableNonce::from_blockhash(&self.blockhash);
+        let (accounts_to_store, _) = collect_accounts_to_store(
+            transactions,
+            processing_results,
+            &durable_nonce,
+            self.lamports_per_signature,
+        );
+        let mut accounts = self.accounts.accounts.write().unwrap();
+        for (pubkey, account) in accounts_to_store {
+            accounts.insert(*pubkey, account.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            signature::{Keypair, Signer},
+            system_instruction, system_transaction,
+        },
+    };
+
+    #[test]
+    fn test_process_transaction_bytes_transfer() {
+        let harness = SvmHarness::default();
+        let payer = Keypair::new();
+        let recipient = Pubkey::new_unique();
+        harness.set_account(
+            payer.pubkey(),
+            AccountSharedData::new(10_000_000, 0, &system_program::id()),
+        );
+
+        let transaction =
+            system_transaction::transfer(&payer, &recipient, 1_000_000, Hash::default());
+        let bytes = bincode::serialize(&transaction).unwrap();
+        let result = harness.process_transaction_bytes(&bytes).unwrap();
+        assert!(result.was_processed_with_successful_result());
+
+        assert_eq!(
+            harness.get_account(&recipient).unwrap().lamports(),
+            1_000_000
+        );
+        assert_eq!(
+            harness.get_account(&payer.pubkey()).unwrap().lamports(),
+            10_000_000 - 1_000_000 - DEFAULT_LAMPORTS_PER_SIGNATURE
+        );
+    }
+
+    #[test]
+    fn test_sanitize_transaction_invalid() {
+        assert_eq!(
+            SvmHarness::sanitize_transaction(&[]).unwrap_err(),
+            HarnessError::InvalidTransaction
+        );
+
+        // instruction referencing an account index which does not exist
+        let payer = Keypair::new();
+        let mut transaction =
+            system_transaction::transfer(&payer, &Pubkey::new_unique(), 1, Hash::default());
+        transaction.message.instructions[0].program_id_index = u8::MAX;
+        let bytes = bincode::serialize(&transaction).unwrap();
+        assert_eq!(
+            SvmHarness::sanitize_transaction(&bytes).unwrap_err(),
+            HarnessError::SanitizeFailed(TransactionError::SanitizeFailure)
+        );
+    }
+
+    #[test]
+    fn test_process_transaction_bytes_fee_only() {
+        let harness = SvmHarness::default();
+        let payer = Keypair::new();
+        let recipient = Pubkey::new_unique();
+        harness.set_account(
+            payer.pubkey(),
+            AccountSharedData::new(10_000_000, 0, &system_program::id()),
+        );
+
+        // transferring more than the payer holds fails, but the fee is still charged
+        let instruction = system_instruction::transfer(&payer.pubkey(), &recipient, 100_000_000);
+        let transaction = solana_sdk::transaction::Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &[&payer],
+            Hash::default(),
+        );
+        let bytes = bincode::serialize(&transaction).unwrap();
+        let result = harness.process_transaction_bytes(&bytes).unwrap();
+        assert!(result.was_processed());
+        assert!(!result.was_processed_with_successful_result());
+
+        assert!(harness.get_account(&recipient).is_none());
+        assert_eq!(
+            harness.get_account(&payer.pubkey()).unwrap().lamports(),
+            10_000_000 - DEFAULT_LAMPORTS_PER_SIGNATURE
+        );
+    }
+}
@@ -5,6 +5,8 @@ pub mod account_loader;
 pub mod account_overrides;
 pub mod account_rent_state;
 pub mod account_saver;
+#[cfg(feature = "harness")]
+pub mod harness;
 pub mod message_processor;
 pub mod nonce_info;
 pub mod program_loader;
@@ -1,4 +1,7 @@
-use solana_compute_budget::compute_budget::ComputeBudget;
+use {
+    solana_compute_budget::compute_budget::ComputeBudget,
+    solana_program_runtime::program_coverage::SharedProgramCoverage,
+};
 
 #[cfg(all(RUSTC_WITH_SPECIALIZATION, feature = "frozen-abi"))]
 impl ::solana_frozen_abi::abi_example::AbiExample for RuntimeConfig {
@@ -14,4 +17,7 @@ pub struct RuntimeConfig {
     pub compute_budget: Option<ComputeBudget>,
     pub log_messages_bytes_limit: Option<usize>,
     pub transaction_account_lock_limit: Option<usize>,
+    /// Enables instruction tracing of SBF programs and counts the executed instructions into
+    /// this collector
+    pub program_coverage: Option<SharedProgramCoverage>,
 }
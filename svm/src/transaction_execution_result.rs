@@ -6,7 +6,7 @@
 pub use solana_sdk::inner_instruction::{InnerInstruction, InnerInstructionsList};
 use {
     crate::account_loader::LoadedTransaction,
-    solana_program_runtime::loaded_programs::ProgramCacheEntry,
+    solana_program_runtime::{execution_trace::ExecutionTrace, loaded_programs::ProgramCacheEntry},
     solana_sdk::{
         pubkey::Pubkey,
         transaction::{self, TransactionError},
@@ -61,6 +61,7 @@ pub struct TransactionExecutionDetails {
     /// The change in accounts data len for this transaction.
     /// NOTE: This value is valid IFF `status` is `Ok`.
     pub accounts_data_len_delta: i64,
+    pub execution_trace: Option<ExecutionTrace>,
 }
 
 impl TransactionExecutionDetails {
@@ -26,11 +26,13 @@ use {
     solana_log_collector::LogCollector,
     solana_measure::{measure::Measure, measure_us},
     solana_program_runtime::{
+        execution_trace::{ExecutionTracer, MAX_EXECUTION_TRACE_DATA_BYTES},
         invoke_context::{EnvironmentConfig, InvokeContext},
         loaded_programs::{
             ForkGraph, ProgramCache, ProgramCacheEntry, ProgramCacheForTxBatch,
             ProgramCacheMatchCriteria,
         },
+        program_coverage::SharedProgramCoverage,
         sysvar_cache::SysvarCache,
     },
     solana_runtime_transaction::instructions_processor::process_compute_budget_instructions,
@@ -81,14 +83,20 @@ pub struct ExecutionRecordingConfig {
     pub enable_cpi_recording: bool,
     pub enable_log_recording: bool,
     pub enable_return_data_recording: bool,
+    /// Record the compute units consumed and the accounts changed by every
+    /// instruction
+    pub enable_execution_trace: bool,
 }
 
 impl ExecutionRecordingConfig {
+    /// Sets every recording capability except execution tracing, which is
+    /// only meant for simulation
     pub fn new_single_setting(option: bool) -> Self {
         ExecutionRecordingConfig {
             enable_return_data_recording: option,
             enable_log_recording: option,
             enable_cpi_recording: option,
+            enable_execution_trace: false,
         }
     }
 }
@@ -156,6 +164,9 @@ pub struct TransactionBatchProcessor<FG: ForkGraph> {
 
     /// Builtin program ids
     pub builtin_program_ids: RwLock<HashSet<Pubkey>>,
+
+    /// Instruction coverage of the SBF programs, collected across all the processed transactions
+    program_coverage: Option<SharedProgramCoverage>,
 }
 
 impl<FG: ForkGraph> Debug for TransactionBatchProcessor<FG> {
@@ -180,6 +191,7 @@ impl<FG: ForkGraph> Default for TransactionBatchProcessor<FG> {
                 Epoch::default(),
             ))),
             builtin_program_ids: RwLock::new(HashSet::new()),
+            program_coverage: None,
         }
     }
 }
@@ -192,6 +204,7 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
             sysvar_cache: RwLock::<SysvarCache>::default(),
             program_cache: Arc::new(RwLock::new(ProgramCache::new(slot, epoch))),
             builtin_program_ids: RwLock::new(builtin_program_ids),
+            program_coverage: None,
         }
     }
 
@@ -202,9 +215,20 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
             sysvar_cache: RwLock::<SysvarCache>::default(),
             program_cache: self.program_cache.clone(),
             builtin_program_ids: RwLock::new(self.builtin_program_ids.read().unwrap().clone()),
+            program_coverage: self.program_coverage.clone(),
         }
     }
 
+    /// Counts the instructions executed by SBF programs into `program_coverage`. The program
+    /// runtime environments need to enable `debugging_features` for the counts to be recorded.
+    pub fn set_program_coverage(&mut self, program_coverage: Option<SharedProgramCoverage>) {
+        self.program_coverage = program_coverage;
+    }
+
+    pub fn program_coverage(&self) -> Option<&SharedProgramCoverage> {
+        self.program_coverage.as_ref()
+    }
+
     /// Returns the current environments depending on the given epoch
     /// Returns None if the call could result in a deadlock
     #[cfg(feature = "dev-context-only-utils")]
@@ -659,17 +683,16 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
             // so we can try to recompile loaded programs before the feature transition hits.
             drop(program_cache);
             let mut program_cache = self.program_cache.write().unwrap();
+            let debugging_features = self.program_coverage.is_some();
             let program_runtime_environment_v1 = create_program_runtime_environment_v1(
                 upcoming_feature_set,
                 compute_budget,
                 false, /* deployment */
-                false, /* debugging_features */
+                debugging_features,
             )
             .unwrap();
-            let program_runtime_environment_v2 = create_program_runtime_environment_v2(
-                compute_budget,
-                false, /* debugging_features */
-            );
+            let program_runtime_environment_v2 =
+                create_program_runtime_environment_v2(compute_budget, debugging_features);
             let mut upcoming_environments = program_cache.environments.clone();
             let changed_program_runtime_v1 =
                 *upcoming_environments.program_runtime_v1 != program_runtime_environment_v1;
@@ -772,6 +795,12 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
             log_collector.clone(),
             compute_budget,
         );
+        let execution_tracer = config
+            .recording_config
+            .enable_execution_trace
+            .then(|| ExecutionTracer::new_ref(MAX_EXECUTION_TRACE_DATA_BYTES));
+        invoke_context.set_execution_tracer(execution_tracer.clone());
+        invoke_context.set_program_coverage(self.program_coverage.clone());
 
         let mut process_message_time = Measure::start("process_message_time");
         let process_result = MessageProcessor::process_message(
@@ -824,6 +853,12 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
                     .ok()
             });
 
+        let execution_trace = execution_tracer.and_then(|execution_tracer| {
+            Rc::try_unwrap(execution_tracer)
+                .map(|execution_tracer| execution_tracer.into_inner().into_trace())
+                .ok()
+        });
+
         let inner_instructions = if config.recording_config.enable_cpi_recording {
             Some(Self::inner_instructions_list_from_instruction_trace(
                 &transaction_context,
@@ -874,6 +909,7 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
                 return_data,
                 executed_units,
                 accounts_data_len_delta,
+                execution_trace,
             },
             loaded_transaction,
             programs_modified_by_tx: program_cache_for_tx_batch.drain_modified_entries(),
@@ -237,6 +237,7 @@ fn svm_concurrent() {
                     enable_log_recording: true,
                     enable_return_data_recording: false,
                     enable_cpi_recording: false,
+                    enable_execution_trace: false,
                 },
                 ..Default::default()
             };
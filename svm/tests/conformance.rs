@@ -275,6 +275,7 @@ fn run_fixture(fixture: InstrFixture, filename: OsString, execute_as_instr: bool
         enable_log_recording: true,
         enable_return_data_recording: true,
         enable_cpi_recording: false,
+        enable_execution_trace: false,
     };
     let processor_config = TransactionProcessingConfig {
         account_overrides: None,
@@ -261,6 +261,7 @@ fn svm_integration() {
             enable_log_recording: true,
             enable_return_data_recording: true,
             enable_cpi_recording: false,
+            enable_execution_trace: false,
         },
         ..Default::default()
     };
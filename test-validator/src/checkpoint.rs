@@ -0,0 +1,183 @@
+//! Named checkpoints of the account states of a running test validator
+//!
+//! A checkpoint copies every account of the rooted bank, sysvars included. Rewinding to it
+//! overwrites the accounts of the working bank with the checkpointed ones and deletes the
+//! accounts created since, so the following transactions observe the checkpointed state. The
+//! chain itself keeps moving forward: slots, blockhashes and the status cache are not rewound.
+
+use {
+    log::*,
+    solana_runtime::{bank::Bank, bank_forks::BankForks},
+    solana_sdk::{
+        account::{AccountSharedData, ReadableAccount, PROGRAM_OWNERS},
+        clock::Slot,
+        pubkey::Pubkey,
+        sysvar,
+    },
+    std::{
+        collections::{HashMap, HashSet},
+        sync::RwLock,
+        thread::sleep,
+        time::{Duration, Instant},
+    },
+};
+
+/// Sysvars tracking the progress of the chain, which are left as they are by a rewind
+#[allow(deprecated)]
+const CHAIN_PROGRESS_SYSVARS: &[Pubkey] = &[
+    sysvar::clock::ID,
+    sysvar::epoch_rewards::ID,
+    sysvar::last_restart_slot::ID,
+    sysvar::recent_blockhashes::ID,
+    sysvar::slot_hashes::ID,
+    sysvar::slot_history::ID,
+];
+
+/// How long a rewind waits for the next working bank when the current one is already frozen
+const WORKING_BANK_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct Checkpoint {
+    slot: Slot,
+    accounts: HashMap<Pubkey, AccountSharedData>,
+}
+
+/// Vote accounts are left alone so that the validator keeps voting on its own forks
+fn is_rewindable(pubkey: &Pubkey, account: &AccountSharedData) -> bool {
+    !CHAIN_PROGRESS_SYSVARS.contains(pubkey)
+        && !solana_sdk::vote::program::check_id(account.owner())
+}
+
+fn rewindable_accounts(bank: &Bank) -> Result<HashMap<Pubkey, AccountSharedData>, String> {
+    Ok(bank
+        .get_all_accounts(false)
+        .map_err(|err| format!("Failed to scan the accounts of slot {}: {err}", bank.slot()))?
+        .into_iter()
+        .filter(|(pubkey, account, _slot)| is_rewindable(pubkey, account))
+        .map(|(pubkey, account, _slot)| (pubkey, account))
+        .collect())
+}
+
+/// Overwrites the accounts of `bank` with `checkpoint_accounts`, unless it's frozen
+///
+/// Returns whether the accounts were overwritten.
+fn rewind_bank(
+    bank: &Bank,
+    checkpoint_accounts: &HashMap<Pubkey, AccountSharedData>,
+) -> Result<bool, String> {
+    let current_accounts = rewindable_accounts(bank)?;
+
+    let mut changed_accounts = current_accounts
+        .iter()
+        .filter(|(pubkey, _account)| !checkpoint_accounts.contains_key(pubkey))
+        .map(|(pubkey, _account)| (*pubkey, AccountSharedData::default()))
+        .collect::<Vec<_>>();
+    changed_accounts.extend(
+        checkpoint_accounts
+            .iter()
+            .filter(|(pubkey, account)| current_accounts.get(pubkey) != Some(account))
+            .map(|(pubkey, account)| (*pubkey, account.clone())),
+    );
+    if !bank.store_accounts_unless_frozen(&changed_accounts) {
+        return Ok(false);
+    }
+
+    let is_changed_by_owner = |owners: &[Pubkey]| {
+        changed_accounts.iter().any(|(pubkey, account)| {
+            owners.contains(account.owner())
+                || current_accounts
+                    .get(pubkey)
+                    .is_some_and(|account| owners.contains(account.owner()))
+        })
+    };
+    let transaction_processor = bank.get_transaction_processor();
+    // Upgradeable programs are cached by program id rather than by their program data account,
+    // so all the programs are reloaded if any loader owned account changed
+    if is_changed_by_owner(PROGRAM_OWNERS) {
+        let program_ids = current_accounts
+            .iter()
+            .chain(checkpoint_accounts)
+            .filter(|(_pubkey, account)| PROGRAM_OWNERS.contains(account.owner()))
+            .map(|(pubkey, _account)| *pubkey)
+            .collect::<HashSet<_>>();
+        transaction_processor
+            .program_cache
+            .write()
+            .unwrap()
+            .remove_programs(program_ids.into_iter());
+    }
+    if is_changed_by_owner(&[sysvar::id()]) {
+        transaction_processor.reset_sysvar_cache();
+        transaction_processor.fill_missing_sysvar_cache_entries(bank);
+    }
+    Ok(true)
+}
+
+/// Named checkpoints of the account states of a test validator
+#[derive(Default)]
+pub struct Checkpoints {
+    checkpoints: RwLock<HashMap<String, Checkpoint>>,
+}
+
+impl Checkpoints {
+    /// Save the accounts of the rooted bank as checkpoint `name`, replacing any previous
+    /// checkpoint of that name. Only finalized transactions are part of the checkpoint.
+    ///
+    /// Returns the slot of the rooted bank.
+    pub fn save(&self, name: &str, bank_forks: &RwLock<BankForks>) -> Result<Slot, String> {
+        let root_bank = bank_forks.read().unwrap().root_bank();
+        let checkpoint = Checkpoint {
+            slot: root_bank.slot(),
+            accounts: rewindable_accounts(&root_bank)?,
+        };
+        let slot = checkpoint.slot;
+        self.checkpoints
+            .write()
+            .unwrap()
+            .insert(name.to_string(), checkpoint);
+        Ok(slot)
+    }
+
+    /// Reset the accounts of the working bank to checkpoint `name`
+    ///
+    /// Returns the slot of the working bank, the first slot with the checkpointed accounts.
+    pub fn rewind(&self, name: &str, bank_forks: &RwLock<BankForks>) -> Result<Slot, String> {
+        let checkpoints = self.checkpoints.read().unwrap();
+        let checkpoint = checkpoints
+            .get(name)
+            .ok_or_else(|| format!("Unknown checkpoint: {name}"))?;
+
+        let deadline = Instant::now() + WORKING_BANK_TIMEOUT;
+        loop {
+            let working_bank = bank_forks.read().unwrap().working_bank();
+            if rewind_bank(&working_bank, &checkpoint.accounts)? {
+                info!(
+                    "Rewound slot {} to checkpoint {} of slot {}",
+                    working_bank.slot(),
+                    name,
+                    checkpoint.slot
+                );
+                return Ok(working_bank.slot());
+            }
+            if Instant::now() > deadline {
+                return Err(format!(
+                    "Working bank of slot {} is frozen",
+                    working_bank.slot()
+                ));
+            }
+            sleep(Duration::from_millis(10));
+        }
+    }
+
+    /// Names of the checkpoints and the slots they were taken at
+    pub fn list(&self) -> Vec<(String, Slot)> {
+        let mut checkpoints = self
+            .checkpoints
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(name, checkpoint)| (name.clone(), checkpoint.slot))
+            .collect::<Vec<_>>();
+        checkpoints.sort();
+        checkpoints
+    }
+}
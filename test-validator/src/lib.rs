@@ -1,4 +1,5 @@
 #![allow(clippy::arithmetic_side_effects)]
+pub use checkpoint::Checkpoints;
 use {
     base64::{prelude::BASE64_STANDARD, Engine},
     crossbeam_channel::Receiver,
@@ -72,6 +73,8 @@ use {
     tokio::time::sleep,
 };
 
+mod checkpoint;
+
 #[derive(Clone)]
 pub struct AccountInfo<'a> {
     pub address: Option<Pubkey>,
@@ -136,7 +139,8 @@ pub struct TestValidatorGenesis {
     pub transaction_account_lock_limit: Option<usize>,
     pub tpu_enable_udp: bool,
     pub geyser_plugin_manager: Arc<RwLock<GeyserPluginManager>>,
-    admin_rpc_service_post_init: Arc<RwLock<Option<AdminRpcRequestMetadataPostInit>>>,
+    pub admin_rpc_service_post_init: Arc<RwLock<Option<AdminRpcRequestMetadataPostInit>>>,
+    pub checkpoints: Arc<Checkpoints>,
 }
 
 impl Default for TestValidatorGenesis {
@@ -170,6 +174,7 @@ impl Default for TestValidatorGenesis {
             geyser_plugin_manager: Arc::new(RwLock::new(GeyserPluginManager::new())),
             admin_rpc_service_post_init:
                 Arc::<RwLock<Option<AdminRpcRequestMetadataPostInit>>>::default(),
+            checkpoints: Arc::<Checkpoints>::default(),
         }
     }
 }
@@ -698,6 +703,7 @@ pub struct TestValidator {
     gossip: SocketAddr,
     validator: Option<Validator>,
     vote_account_address: Pubkey,
+    checkpoints: Arc<Checkpoints>,
 }
 
 impl TestValidator {
@@ -991,6 +997,7 @@ impl TestValidator {
                 }),
             log_messages_bytes_limit: config.log_messages_bytes_limit,
             transaction_account_lock_limit: config.transaction_account_lock_limit,
+            ..RuntimeConfig::default()
         };
 
         let mut validator_config = ValidatorConfig {
@@ -1070,6 +1077,7 @@ impl TestValidator {
             gossip,
             validator,
             vote_account_address,
+            checkpoints: config.checkpoints.clone(),
         };
         Ok(test_validator)
     }
@@ -1177,6 +1185,26 @@ impl TestValidator {
     pub fn repair_whitelist(&self) -> Arc<RwLock<HashSet<Pubkey>>> {
         Arc::new(RwLock::new(HashSet::default()))
     }
+
+    /// Save the accounts of the rooted bank, sysvars included, as checkpoint `name`
+    ///
+    /// Wait for the transactions to be finalized before taking a checkpoint of their effects.
+    /// Returns the slot of the rooted bank.
+    pub fn checkpoint(&self, name: &str) -> Result<Slot, String> {
+        self.checkpoints.save(name, &self.bank_forks())
+    }
+
+    /// Reset the accounts of the running validator to checkpoint `name`
+    ///
+    /// Returns the first slot with the checkpointed accounts.
+    pub fn rewind(&self, name: &str) -> Result<Slot, String> {
+        self.checkpoints.rewind(name, &self.bank_forks())
+    }
+
+    /// Names of the checkpoints and the slots they were taken at
+    pub fn checkpoints(&self) -> Vec<(String, Slot)> {
+        self.checkpoints.list()
+    }
 }
 
 impl Drop for TestValidator {
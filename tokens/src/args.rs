@@ -1,4 +1,7 @@
-use solana_sdk::{pubkey::Pubkey, signature::Signer};
+use {
+    solana_sdk::{clock::Epoch, pubkey::Pubkey, signature::Signer},
+    spl_token_2022::extension::transfer_fee::TransferFeeConfig,
+};
 
 pub struct SenderStakeArgs {
     pub stake_account_address: Pubkey,
@@ -31,6 +34,17 @@ pub struct SplTokenArgs {
     pub token_account_address: Pubkey,
     pub mint: Pubkey,
     pub decimals: u8,
+    /// SPL Token or Token-2022, the owner of the mint
+    pub token_program_id: Pubkey,
+    /// Transfer fee configuration, if the mint has the transfer fee extension
+    pub transfer_fee_config: Option<TransferFeeConfig>,
+    /// Epoch the transfer fees are computed for when building the transactions
+    pub epoch: Epoch,
+    pub non_transferable: bool,
+    /// New token accounts of the mint are frozen
+    pub default_account_frozen: bool,
+    /// Size of the associated token accounts created for recipients
+    pub token_account_len: usize,
 }
 
 pub struct BalancesArgs {
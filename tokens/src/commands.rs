@@ -37,7 +37,6 @@ use {
         transaction::Transaction,
     },
     solana_transaction_status::TransactionStatus,
-    spl_associated_token_account::get_associated_token_address,
     spl_token::solana_program::program_error::ProgramError,
     std::{
         cmp::{self},
@@ -104,6 +103,18 @@ impl From<Vec<FundingSource>> for FundingSources {
 
 type StakeExtras = Vec<(Keypair, Option<DateTime<Utc>>)>;
 
+/// Allocations a message was built for, leaving out the skipped ones
+#[derive(Default)]
+struct BuiltAllocations {
+    allocations: Vec<TypedAllocation>,
+    /// Tokens debited from the sender, including transfer fees
+    transfer_amount: u64,
+    /// Transfer fees withheld in the recipient token accounts
+    transfer_fees: u64,
+    /// SPL token transfer of each built allocation, empty when distributing SOL
+    spl_token_transfers: Vec<SplTokenTransfer>,
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("I/O error")]
@@ -205,10 +216,10 @@ fn distribution_instructions(
     new_stake_account_address: &Pubkey,
     args: &DistributeTokensArgs,
     lockup_date: Option<DateTime<Utc>>,
-    do_create_associated_token_account: bool,
+    spl_token_transfer: Option<&SplTokenTransfer>,
 ) -> Vec<Instruction> {
-    if args.spl_token_args.is_some() {
-        return build_spl_token_instructions(allocation, args, do_create_associated_token_account);
+    if let Some(spl_token_transfer) = spl_token_transfer {
+        return build_spl_token_instructions(allocation, args, spl_token_transfer);
     }
 
     match &args.stake_args {
@@ -332,16 +343,18 @@ fn build_messages(
     messages: &mut Vec<Message>,
     stake_extras: &mut StakeExtras,
     created_accounts: &mut u64,
-) -> Result<(), Error> {
+) -> Result<BuiltAllocations, Error> {
     let mut existing_associated_token_accounts = vec![];
     if let Some(spl_token_args) = &args.spl_token_args {
+        // The allocations skipped by the previous run are checked again
+        db::clear_skipped_allocations(db)?;
         let allocation_chunks = allocations.chunks(MAX_MULTIPLE_ACCOUNTS);
         for allocation_chunk in allocation_chunks {
             let associated_token_addresses = allocation_chunk
                 .iter()
                 .map(|x| {
                     let wallet_address = x.recipient;
-                    get_associated_token_address(&wallet_address, &spl_token_args.mint)
+                    get_associated_token_address(&wallet_address, spl_token_args)
                 })
                 .collect::<Vec<_>>();
             let mut maybe_accounts = client.get_multiple_accounts(&associated_token_addresses)?;
@@ -349,6 +362,7 @@ fn build_messages(
         }
     }
 
+    let mut built_allocations = BuiltAllocations::default();
     for (i, allocation) in allocations.iter().enumerate() {
         if exit.load(Ordering::SeqCst) {
             db.dump()?;
@@ -357,34 +371,67 @@ fn build_messages(
         let new_stake_account_keypair = Keypair::new();
         let lockup_date = allocation.lockup_date;
 
-        let do_create_associated_token_account = if let Some(spl_token_args) = &args.spl_token_args
-        {
-            let do_create_associated_token_account =
-                existing_associated_token_accounts[i].is_none();
-            if do_create_associated_token_account {
+        let spl_token_transfer = if let Some(spl_token_args) = &args.spl_token_args {
+            let spl_token_transfer = match check_spl_token_transfer(
+                allocation,
+                spl_token_args,
+                existing_associated_token_accounts[i].as_ref(),
+            ) {
+                Ok(spl_token_transfer) => spl_token_transfer,
+                Err(reason) => {
+                    println!(
+                        "{:<44}  {:>24}  {}",
+                        allocation.recipient,
+                        real_number_string(allocation.amount, spl_token_args.decimals),
+                        style(format!("Skipped: {reason}")).yellow(),
+                    );
+                    db::set_skipped_allocation(
+                        db,
+                        &allocation.recipient,
+                        allocation.amount,
+                        &reason,
+                    )?;
+                    continue;
+                }
+            };
+            if spl_token_transfer.create_associated_token_account {
                 *created_accounts += 1;
             }
-            println!(
-                "{:<44}  {:>24}",
-                allocation.recipient,
-                real_number_string(allocation.amount, spl_token_args.decimals)
-            );
-            do_create_associated_token_account
+            if spl_token_args.transfer_fee_config.is_some() {
+                println!(
+                    "{:<44}  {:>24}  {:>24}",
+                    allocation.recipient,
+                    real_number_string(allocation.amount, spl_token_args.decimals),
+                    real_number_string(spl_token_transfer.fee, spl_token_args.decimals),
+                );
+            } else {
+                println!(
+                    "{:<44}  {:>24}",
+                    allocation.recipient,
+                    real_number_string(allocation.amount, spl_token_args.decimals)
+                );
+            }
+            built_allocations.transfer_amount += spl_token_transfer.amount;
+            built_allocations.transfer_fees += spl_token_transfer.fee;
+            Some(spl_token_transfer)
         } else {
             println!(
                 "{:<44}  {:>24.9}",
                 allocation.recipient,
                 lamports_to_sol(allocation.amount)
             );
-            false
+            None
         };
         let instructions = distribution_instructions(
             allocation,
             &new_stake_account_keypair.pubkey(),
             args,
             lockup_date,
-            do_create_associated_token_account,
+            spl_token_transfer.as_ref(),
         );
+        built_allocations
+            .spl_token_transfers
+            .extend(spl_token_transfer);
         let fee_payer_pubkey = args.fee_payer.pubkey();
         let message = Message::new_with_blockhash(
             &instructions,
@@ -393,8 +440,9 @@ fn build_messages(
         );
         messages.push(message);
         stake_extras.push((new_stake_account_keypair, lockup_date));
+        built_allocations.allocations.push(allocation.clone());
     }
-    Ok(())
+    Ok(built_allocations)
 }
 
 fn send_messages(
@@ -405,14 +453,49 @@ fn send_messages(
     exit: Arc<AtomicBool>,
     messages: Vec<Message>,
     stake_extras: StakeExtras,
+    spl_token_transfers: &[SplTokenTransfer],
 ) -> Result<(), Error> {
-    for ((allocation, message), (new_stake_account_keypair, lockup_date)) in
-        allocations.iter().zip(messages).zip(stake_extras)
+    for (i, ((allocation, message), (new_stake_account_keypair, lockup_date))) in allocations
+        .iter()
+        .zip(messages)
+        .zip(stake_extras)
+        .enumerate()
     {
         if exit.load(Ordering::SeqCst) {
             db.dump()?;
             return Err(Error::ExitSignal);
         }
+        let message = match (&args.spl_token_args, spl_token_transfers.get(i)) {
+            (Some(spl_token_args), Some(spl_token_transfer))
+                if spl_token_args.transfer_fee_config.is_some() && !args.dry_run =>
+            {
+                // The fee must match the one of the epoch the transfer executes in, which may
+                // differ from the epoch the message was built for
+                let epoch = client.get_epoch_info()?.epoch;
+                let mut spl_token_transfer = spl_token_transfer.clone();
+                match update_spl_token_transfer_epoch(
+                    &mut spl_token_transfer,
+                    allocation,
+                    spl_token_args,
+                    epoch,
+                ) {
+                    Ok(true) => Message::new_with_blockhash(
+                        &build_spl_token_instructions(allocation, args, &spl_token_transfer),
+                        Some(&args.fee_payer.pubkey()),
+                        &Hash::default(),
+                    ),
+                    Ok(false) => message,
+                    Err(reason) => {
+                        eprintln!(
+                            "Error sending tokens to {}: {}",
+                            allocation.recipient, reason
+                        );
+                        continue;
+                    }
+                }
+            }
+            _ => message,
+        };
         let new_stake_account_address = new_stake_account_keypair.pubkey();
 
         let mut signers = vec![&*args.fee_payer, &*args.sender_keypair];
@@ -481,7 +564,7 @@ fn distribute_allocations(
     let mut stake_extras: StakeExtras = vec![];
     let mut created_accounts = 0;
 
-    build_messages(
+    let built_allocations = build_messages(
         client,
         db,
         allocations,
@@ -491,14 +574,51 @@ fn distribute_allocations(
         &mut stake_extras,
         &mut created_accounts,
     )?;
+    let allocations = &built_allocations.allocations;
 
-    if args.spl_token_args.is_some() {
-        check_spl_token_balances(&messages, allocations, client, args, created_accounts)?;
+    if let Some(spl_token_args) = &args.spl_token_args {
+        let skipped_allocations = db::read_skipped_allocations(db);
+        if !skipped_allocations.is_empty() {
+            let skipped_tokens = skipped_allocations.iter().map(|x| x.amount).sum();
+            println!(
+                "{} {} ({} recipients)",
+                style("Skipped:").bold(),
+                Token::spl_token(skipped_tokens, spl_token_args.decimals),
+                skipped_allocations.len(),
+            );
+        }
+        if spl_token_args.transfer_fee_config.is_some() {
+            println!(
+                "{} {}",
+                style("Withheld transfer fees:").bold(),
+                Token::spl_token(built_allocations.transfer_fees, spl_token_args.decimals),
+            );
+        }
+        if allocations.is_empty() {
+            db.dump()?;
+            return Ok(());
+        }
+        check_spl_token_balances(
+            &messages,
+            built_allocations.transfer_amount,
+            client,
+            args,
+            created_accounts,
+        )?;
     } else {
         check_payer_balances(&messages, allocations, client, args)?;
     }
 
-    send_messages(client, db, allocations, args, exit, messages, stake_extras)?;
+    send_messages(
+        client,
+        db,
+        allocations,
+        args,
+        exit,
+        messages,
+        stake_extras,
+        &built_allocations.spl_token_transfers,
+    )?;
 
     db.dump()?;
     Ok(())
@@ -670,10 +790,14 @@ pub fn process_allocations(
         distributed_tokens + undistributed_tokens,
     );
 
-    println!(
-        "{}",
-        style(format!("{:<44}  {:>24}", "Recipient", "Expected Balance",)).bold()
-    );
+    let header = match &args.spl_token_args {
+        Some(spl_token_args) if spl_token_args.transfer_fee_config.is_some() => format!(
+            "{:<44}  {:>24}  {:>24}",
+            "Recipient", "Expected Balance", "Transfer Fee"
+        ),
+        _ => format!("{:<44}  {:>24}", "Recipient", "Expected Balance"),
+    };
+    println!("{}", style(header).bold());
 
     distribute_allocations(client, &mut db, &allocations, args, exit.clone())?;
 
@@ -1816,7 +1940,7 @@ mod tests {
             &new_stake_account_address,
             &args,
             Some(lockup_date),
-            false,
+            None,
         );
         let lockup_instruction =
             bincode::deserialize(&instructions[SET_LOCKUP_INDEX].data).unwrap();
@@ -2452,6 +2576,7 @@ mod tests {
             Arc::new(AtomicBool::new(false)),
             vec![message.clone()],
             vec![(Keypair::new(), None)],
+            &[],
         )
         .unwrap();
         let read_db = db::open_db(&db_file, true).unwrap();
@@ -2462,7 +2587,17 @@ mod tests {
 
         // Empty messages/allocations will not dump data
         let exit = Arc::new(AtomicBool::new(true));
-        send_messages(&client, &mut db, &[], &args, exit.clone(), vec![], vec![]).unwrap();
+        send_messages(
+            &client,
+            &mut db,
+            &[],
+            &args,
+            exit.clone(),
+            vec![],
+            vec![],
+            &[],
+        )
+        .unwrap();
         let read_db = db::open_db(&db_file, true).unwrap();
         assert!(db::read_transaction_infos(&read_db).is_empty());
 
@@ -2475,6 +2610,7 @@ mod tests {
             exit,
             vec![message.clone()],
             vec![(Keypair::new(), None)],
+            &[],
         )
         .unwrap_err();
         let read_db = db::open_db(&db_file, true).unwrap();
@@ -18,6 +18,17 @@ pub struct TransactionInfo {
     pub lockup_date: Option<DateTime<Utc>>,
 }
 
+/// Allocation which was not distributed, because the recipient can't receive the tokens
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SkippedAllocation {
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub reason: String,
+}
+
+// Skipped allocations share the db with the transaction infos, which are keyed by signature
+const SKIPPED_ALLOCATION_KEY_PREFIX: &str = "skipped:";
+
 #[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
 struct SignedTransactionInfo {
     recipient: String,
@@ -99,10 +110,53 @@ pub fn write_transaction_log<P: AsRef<Path>>(db: &PickleDb, path: &P) -> Result<
 
 pub fn read_transaction_infos(db: &PickleDb) -> Vec<TransactionInfo> {
     db.iter()
+        .filter(|kv| !kv.get_key().starts_with(SKIPPED_ALLOCATION_KEY_PREFIX))
         .map(|kv| kv.get_value::<TransactionInfo>().unwrap())
         .collect()
 }
 
+pub fn read_skipped_allocations(db: &PickleDb) -> Vec<SkippedAllocation> {
+    db.iter()
+        .filter(|kv| kv.get_key().starts_with(SKIPPED_ALLOCATION_KEY_PREFIX))
+        .map(|kv| kv.get_value::<SkippedAllocation>().unwrap())
+        .collect()
+}
+
+pub fn set_skipped_allocation(
+    db: &mut PickleDb,
+    recipient: &Pubkey,
+    amount: u64,
+    reason: &str,
+) -> Result<(), Error> {
+    let key = format!("{SKIPPED_ALLOCATION_KEY_PREFIX}{recipient}");
+    // A recipient may be listed more than once in the input CSV
+    let amount = db
+        .get::<SkippedAllocation>(&key)
+        .map_or(amount, |skipped_allocation| {
+            skipped_allocation.amount.saturating_add(amount)
+        });
+    let skipped_allocation = SkippedAllocation {
+        recipient: *recipient,
+        amount,
+        reason: reason.to_string(),
+    };
+    db.set(&key, &skipped_allocation)?;
+    Ok(())
+}
+
+// Remove the skipped allocations of the previous run, which are checked again
+pub fn clear_skipped_allocations(db: &mut PickleDb) -> Result<(), Error> {
+    let keys: Vec<_> = db
+        .get_all()
+        .into_iter()
+        .filter(|key| key.starts_with(SKIPPED_ALLOCATION_KEY_PREFIX))
+        .collect();
+    for key in keys {
+        db.rem(&key)?;
+    }
+    Ok(())
+}
+
 pub fn set_transaction_info(
     db: &mut PickleDb,
     recipient: &Pubkey,
@@ -265,6 +319,33 @@ mod tests {
         assert_eq!(signed_infos, vec![signed_info]);
     }
 
+    #[test]
+    fn test_skipped_allocations() {
+        let mut db =
+            PickleDb::new_yaml(NamedTempFile::new().unwrap(), PickleDbDumpPolicy::NeverDump);
+        let signature = Signature::default();
+        let transaction_info = TransactionInfo::default();
+        db.set(&signature.to_string(), &transaction_info).unwrap();
+
+        let recipient = solana_sdk::pubkey::new_rand();
+        set_skipped_allocation(&mut db, &recipient, 1, "frozen").unwrap();
+        set_skipped_allocation(&mut db, &recipient, 2, "frozen").unwrap();
+        assert_eq!(
+            read_skipped_allocations(&db),
+            vec![SkippedAllocation {
+                recipient,
+                amount: 3,
+                reason: "frozen".to_string(),
+            }]
+        );
+        // Skipped allocations are not transactions
+        assert_eq!(read_transaction_infos(&db), vec![transaction_info.clone()]);
+
+        clear_skipped_allocations(&mut db).unwrap();
+        assert!(read_skipped_allocations(&db).is_empty());
+        assert_eq!(read_transaction_infos(&db), vec![transaction_info]);
+    }
+
     #[test]
     fn test_update_finalized_transaction_not_landed() {
         // Keep waiting for a transaction that hasn't landed yet.
@@ -6,76 +6,230 @@ use {
     console::style,
     solana_account_decoder::parse_token::{real_number_string, real_number_string_trimmed},
     solana_rpc_client::rpc_client::RpcClient,
-    solana_sdk::{instruction::Instruction, message::Message, native_token::lamports_to_sol},
+    solana_sdk::{
+        account::Account, clock::Epoch, instruction::Instruction, message::Message,
+        native_token::lamports_to_sol, pubkey::Pubkey,
+    },
     spl_associated_token_account::{
-        get_associated_token_address, instruction::create_associated_token_account,
+        get_associated_token_address_with_program_id, instruction::create_associated_token_account,
     },
-    spl_token::{
-        solana_program::program_pack::Pack,
-        state::{Account as SplTokenAccount, Mint},
+    spl_token::solana_program::program_error::ProgramError,
+    spl_token_2022::{
+        extension::{
+            default_account_state::DefaultAccountState,
+            memo_transfer::MemoTransfer,
+            non_transferable::NonTransferable,
+            transfer_fee::{instruction::transfer_checked_with_fee, TransferFeeConfig},
+            BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+        },
+        state::{Account as SplTokenAccount, AccountState, Mint},
     },
 };
 
+const MEMO: &[u8] = b"solana-tokens distribution";
+
 pub fn update_token_args(client: &RpcClient, args: &mut Option<SplTokenArgs>) -> Result<(), Error> {
     if let Some(spl_token_args) = args {
         let sender_account = client
             .get_account(&spl_token_args.token_account_address)
             .unwrap_or_default();
-        spl_token_args.mint = SplTokenAccount::unpack(&sender_account.data)?.mint;
+        spl_token_args.mint = StateWithExtensions::<SplTokenAccount>::unpack(&sender_account.data)?
+            .base
+            .mint;
         update_decimals(client, args)?;
     }
     Ok(())
 }
 
+/// Reads the decimals, the token program and the extensions of the mint
 pub fn update_decimals(client: &RpcClient, args: &mut Option<SplTokenArgs>) -> Result<(), Error> {
     if let Some(spl_token_args) = args {
         let mint_account = client.get_account(&spl_token_args.mint).unwrap_or_default();
-        let mint = Mint::unpack(&mint_account.data)?;
-        spl_token_args.decimals = mint.decimals;
+        if mint_account.owner != spl_token::id() && mint_account.owner != spl_token_2022::id() {
+            return Err(ProgramError::IncorrectProgramId.into());
+        }
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_account.data)?;
+        spl_token_args.decimals = mint.base.decimals;
+        spl_token_args.token_program_id = mint_account.owner;
+        spl_token_args.transfer_fee_config =
+            mint.get_extension::<TransferFeeConfig>().ok().copied();
+        if spl_token_args.transfer_fee_config.is_some() {
+            spl_token_args.epoch = client.get_epoch_info()?.epoch;
+        }
+        spl_token_args.non_transferable = mint.get_extension::<NonTransferable>().is_ok();
+        spl_token_args.default_account_frozen = mint
+            .get_extension::<DefaultAccountState>()
+            .is_ok_and(|default_account_state| {
+                default_account_state.state == AccountState::Frozen as u8
+            });
+
+        let mut account_extensions =
+            ExtensionType::get_required_init_account_extensions(&mint.get_extension_types()?);
+        if mint_account.owner == spl_token_2022::id() {
+            // Added to every associated token account by the associated token account program
+            account_extensions.push(ExtensionType::ImmutableOwner);
+        }
+        spl_token_args.token_account_len =
+            ExtensionType::try_calculate_account_len::<SplTokenAccount>(&account_extensions)?;
     }
     Ok(())
 }
 
+pub(crate) fn get_associated_token_address(
+    wallet_address: &Pubkey,
+    spl_token_args: &SplTokenArgs,
+) -> Pubkey {
+    get_associated_token_address_with_program_id(
+        wallet_address,
+        &spl_token_args.mint,
+        &spl_token_args.token_program_id,
+    )
+}
+
+/// How the tokens of an allocation are transferred to the associated token account of the
+/// recipient
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct SplTokenTransfer {
+    pub(crate) create_associated_token_account: bool,
+    /// The associated token account requires a memo on incoming transfers
+    pub(crate) require_memo: bool,
+    /// Amount debited from the sender, the allocation amount plus the transfer fee
+    pub(crate) amount: u64,
+    /// Transfer fee withheld in the associated token account
+    pub(crate) fee: u64,
+}
+
+/// Checks that the recipient can receive the tokens of the allocation, given its associated token
+/// account if it exists. Returns the reason otherwise, to skip the allocation.
+pub(crate) fn check_spl_token_transfer(
+    allocation: &TypedAllocation,
+    spl_token_args: &SplTokenArgs,
+    associated_token_account: Option<&Account>,
+) -> Result<SplTokenTransfer, String> {
+    if spl_token_args.non_transferable {
+        return Err("mint is non-transferable".to_string());
+    }
+    let (amount, fee) = gross_up_amount(allocation, spl_token_args, spl_token_args.epoch)?;
+
+    let Some(associated_token_account) = associated_token_account else {
+        if spl_token_args.default_account_frozen {
+            return Err("associated token account would be created frozen".to_string());
+        }
+        return Ok(SplTokenTransfer {
+            create_associated_token_account: true,
+            require_memo: false,
+            amount,
+            fee,
+        });
+    };
+    let token_account =
+        StateWithExtensions::<SplTokenAccount>::unpack(&associated_token_account.data)
+            .map_err(|_| "associated token account is not a token account".to_string())?;
+    if token_account.base.is_frozen() {
+        return Err("associated token account is frozen".to_string());
+    }
+    let require_memo = token_account
+        .get_extension::<MemoTransfer>()
+        .is_ok_and(|memo_transfer| bool::from(memo_transfer.require_incoming_transfer_memos));
+    Ok(SplTokenTransfer {
+        create_associated_token_account: false,
+        require_memo,
+        amount,
+        fee,
+    })
+}
+
+/// Grosses up the allocation amount with the transfer fee of the epoch, so that the recipient
+/// receives the allocation amount. Returns the amount debited from the sender and the fee.
+fn gross_up_amount(
+    allocation: &TypedAllocation,
+    spl_token_args: &SplTokenArgs,
+    epoch: Epoch,
+) -> Result<(u64, u64), String> {
+    match &spl_token_args.transfer_fee_config {
+        Some(transfer_fee_config) => {
+            let transfer_fee = transfer_fee_config.get_epoch_fee(epoch);
+            transfer_fee
+                .calculate_pre_fee_amount(allocation.amount)
+                .and_then(|amount| Some((amount, transfer_fee.calculate_fee(amount)?)))
+                .ok_or_else(|| "transfer fee overflows".to_string())
+        }
+        None => Ok((allocation.amount, 0)),
+    }
+}
+
+/// The transfer fee is checked against the fee of the epoch the transaction executes in, so a
+/// transfer built for an earlier epoch is recomputed with the fee of the epoch it is sent in.
+/// Returns whether the amount or the fee changed.
+pub(crate) fn update_spl_token_transfer_epoch(
+    spl_token_transfer: &mut SplTokenTransfer,
+    allocation: &TypedAllocation,
+    spl_token_args: &SplTokenArgs,
+    epoch: Epoch,
+) -> Result<bool, String> {
+    let (amount, fee) = gross_up_amount(allocation, spl_token_args, epoch)?;
+    let changed = amount != spl_token_transfer.amount || fee != spl_token_transfer.fee;
+    spl_token_transfer.amount = amount;
+    spl_token_transfer.fee = fee;
+    Ok(changed)
+}
+
 pub(crate) fn build_spl_token_instructions(
     allocation: &TypedAllocation,
     args: &DistributeTokensArgs,
-    do_create_associated_token_account: bool,
+    spl_token_transfer: &SplTokenTransfer,
 ) -> Vec<Instruction> {
     let spl_token_args = args
         .spl_token_args
         .as_ref()
         .expect("spl_token_args must be some");
     let wallet_address = allocation.recipient;
-    let associated_token_address =
-        get_associated_token_address(&wallet_address, &spl_token_args.mint);
+    let associated_token_address = get_associated_token_address(&wallet_address, spl_token_args);
     let mut instructions = vec![];
-    if do_create_associated_token_account {
+    if spl_token_transfer.create_associated_token_account {
         instructions.push(create_associated_token_account(
             &args.fee_payer.pubkey(),
             &wallet_address,
             &spl_token_args.mint,
-            &spl_token::id(),
+            &spl_token_args.token_program_id,
         ));
     }
-    instructions.push(
-        spl_token::instruction::transfer_checked(
-            &spl_token::id(),
+    if spl_token_transfer.require_memo {
+        // The memo must immediately precede the transfer
+        instructions.push(spl_memo::build_memo(MEMO, &[]));
+    }
+    let transfer_instruction = if spl_token_args.transfer_fee_config.is_some() {
+        transfer_checked_with_fee(
+            &spl_token_args.token_program_id,
             &spl_token_args.token_account_address,
             &spl_token_args.mint,
             &associated_token_address,
             &args.sender_keypair.pubkey(),
             &[],
-            allocation.amount,
+            spl_token_transfer.amount,
             spl_token_args.decimals,
+            spl_token_transfer.fee,
         )
-        .unwrap(),
-    );
+    } else {
+        spl_token_2022::instruction::transfer_checked(
+            &spl_token_args.token_program_id,
+            &spl_token_args.token_account_address,
+            &spl_token_args.mint,
+            &associated_token_address,
+            &args.sender_keypair.pubkey(),
+            &[],
+            spl_token_transfer.amount,
+            spl_token_args.decimals,
+        )
+    };
+    instructions.push(transfer_instruction.unwrap());
     instructions
 }
 
+/// `transfer_amount` is the amount debited from the sender, including transfer fees
 pub(crate) fn check_spl_token_balances(
     messages: &[Message],
-    allocations: &[TypedAllocation],
+    transfer_amount: u64,
     client: &RpcClient,
     args: &DistributeTokensArgs,
     created_accounts: u64,
@@ -84,11 +238,10 @@ pub(crate) fn check_spl_token_balances(
         .spl_token_args
         .as_ref()
         .expect("spl_token_args must be some");
-    let allocation_amount: u64 = allocations.iter().map(|x| x.amount).sum();
     let fees = get_fee_estimate_for_messages(messages, client)?;
 
     let token_account_rent_exempt_balance =
-        client.get_minimum_balance_for_rent_exemption(SplTokenAccount::LEN)?;
+        client.get_minimum_balance_for_rent_exemption(spl_token_args.token_account_len)?;
     let account_creation_amount = created_accounts * token_account_rent_exempt_balance;
     let fee_payer_balance = client.get_balance(&args.fee_payer.pubkey())?;
     if fee_payer_balance < fees + account_creation_amount {
@@ -100,11 +253,11 @@ pub(crate) fn check_spl_token_balances(
     let source_token_account = client
         .get_account(&spl_token_args.token_account_address)
         .unwrap_or_default();
-    let source_token = SplTokenAccount::unpack(&source_token_account.data)?;
-    if source_token.amount < allocation_amount {
+    let source_token = StateWithExtensions::<SplTokenAccount>::unpack(&source_token_account.data)?;
+    if source_token.base.amount < transfer_amount {
         return Err(Error::InsufficientFunds(
             vec![FundingSource::SplTokenAccount].into(),
-            real_number_string_trimmed(allocation_amount, spl_token_args.decimals),
+            real_number_string_trimmed(transfer_amount, spl_token_args.decimals),
         ));
     }
     Ok(())
@@ -117,16 +270,16 @@ pub(crate) fn print_token_balances(
 ) -> Result<(), Error> {
     let address = allocation.recipient;
     let expected = allocation.amount;
-    let associated_token_address = get_associated_token_address(&address, &spl_token_args.mint);
+    let associated_token_address = get_associated_token_address(&address, spl_token_args);
     let recipient_account = client
         .get_account(&associated_token_address)
         .unwrap_or_default();
     let (actual, difference) = if let Ok(recipient_token) =
-        SplTokenAccount::unpack(&recipient_account.data)
+        StateWithExtensions::<SplTokenAccount>::unpack(&recipient_account.data)
     {
-        let actual_ui_amount = real_number_string(recipient_token.amount, spl_token_args.decimals);
-        let delta_string =
-            real_number_string(recipient_token.amount - expected, spl_token_args.decimals);
+        let actual_amount = recipient_token.base.amount;
+        let actual_ui_amount = real_number_string(actual_amount, spl_token_args.decimals);
+        let delta_string = real_number_string(actual_amount - expected, spl_token_args.decimals);
         (
             style(format!("{actual_ui_amount:>24}")),
             format!("{delta_string:>24}"),
@@ -160,4 +313,140 @@ mod tests {
     // async fn test_check_spl_token_balances()
     //
     // https://github.com/solana-labs/solana/blob/5511d52c6284013a24ced10966d11d8f4585799e/tokens/src/spl_token.rs#L490-L685
+
+    use {
+        super::*,
+        spl_token_2022::extension::{
+            transfer_fee::TransferFee, BaseStateWithExtensionsMut, StateWithExtensionsMut,
+        },
+    };
+
+    fn token_account(state: AccountState, require_memo: bool) -> Account {
+        let account_len = ExtensionType::try_calculate_account_len::<SplTokenAccount>(&[
+            ExtensionType::MemoTransfer,
+        ])
+        .unwrap();
+        let mut data = vec![0; account_len];
+        let mut token_account =
+            StateWithExtensionsMut::<SplTokenAccount>::unpack_uninitialized(&mut data).unwrap();
+        token_account.base = SplTokenAccount {
+            state,
+            ..SplTokenAccount::default()
+        };
+        token_account.pack_base();
+        token_account.init_account_type().unwrap();
+        let memo_transfer = token_account.init_extension::<MemoTransfer>(true).unwrap();
+        memo_transfer.require_incoming_transfer_memos = require_memo.into();
+        Account {
+            data,
+            owner: spl_token_2022::id(),
+            ..Account::default()
+        }
+    }
+
+    #[test]
+    fn test_check_spl_token_transfer() {
+        let allocation = TypedAllocation {
+            recipient: Pubkey::new_unique(),
+            amount: 99,
+            lockup_date: None,
+        };
+        let mut spl_token_args = SplTokenArgs::default();
+
+        assert_eq!(
+            check_spl_token_transfer(&allocation, &spl_token_args, None),
+            Ok(SplTokenTransfer {
+                create_associated_token_account: true,
+                require_memo: false,
+                amount: 99,
+                fee: 0,
+            })
+        );
+        assert_eq!(
+            check_spl_token_transfer(
+                &allocation,
+                &spl_token_args,
+                Some(&token_account(AccountState::Initialized, true)),
+            ),
+            Ok(SplTokenTransfer {
+                create_associated_token_account: false,
+                require_memo: true,
+                amount: 99,
+                fee: 0,
+            })
+        );
+        assert!(check_spl_token_transfer(
+            &allocation,
+            &spl_token_args,
+            Some(&token_account(AccountState::Frozen, false)),
+        )
+        .is_err());
+
+        // 1% fee, the recipient receives the allocation amount
+        spl_token_args.transfer_fee_config = Some(TransferFeeConfig {
+            older_transfer_fee: TransferFee {
+                transfer_fee_basis_points: 100.into(),
+                maximum_fee: u64::MAX.into(),
+                ..TransferFee::default()
+            },
+            // 2% fee from epoch 5
+            newer_transfer_fee: TransferFee {
+                epoch: 5.into(),
+                transfer_fee_basis_points: 200.into(),
+                maximum_fee: u64::MAX.into(),
+            },
+            ..TransferFeeConfig::default()
+        });
+        spl_token_args.epoch = 4;
+        let mut spl_token_transfer =
+            check_spl_token_transfer(&allocation, &spl_token_args, None).unwrap();
+        assert_eq!(
+            spl_token_transfer,
+            SplTokenTransfer {
+                create_associated_token_account: true,
+                require_memo: false,
+                amount: 100,
+                fee: 1,
+            }
+        );
+
+        // Sent in the same epoch, or in another one with the same fee
+        for epoch in [4, 3] {
+            assert_eq!(
+                update_spl_token_transfer_epoch(
+                    &mut spl_token_transfer,
+                    &allocation,
+                    &spl_token_args,
+                    epoch,
+                ),
+                Ok(false)
+            );
+        }
+        // Sent once the newer fee applies
+        assert_eq!(
+            update_spl_token_transfer_epoch(
+                &mut spl_token_transfer,
+                &allocation,
+                &spl_token_args,
+                5
+            ),
+            Ok(true)
+        );
+        assert_eq!(
+            spl_token_transfer,
+            SplTokenTransfer {
+                create_associated_token_account: true,
+                require_memo: false,
+                amount: 102,
+                fee: 3,
+            }
+        );
+
+        spl_token_args.default_account_frozen = true;
+        assert!(check_spl_token_transfer(&allocation, &spl_token_args, None).is_err());
+
+        spl_token_args.default_account_frozen = false;
+        spl_token_args.non_transferable = true;
+        assert!(check_spl_token_transfer(&allocation, &spl_token_args, None).is_err());
+    }
 }
@@ -12,7 +12,7 @@ use {
         pubkey::Pubkey,
         signature::Signature,
         slot_history::Slot,
-        transaction::{Result, Transaction},
+        transaction::{Result, Transaction, VersionedTransaction},
     },
     solana_transaction_status::UiConfirmedBlock,
 };
@@ -24,6 +24,10 @@ impl TpsClient for BankClient {
     fn send_batch(&self, transactions: Vec<Transaction>) -> TpsClientResult<()> {
         AsyncClient::async_send_batch(self, transactions).map_err(|err| err.into())
     }
+    fn send_versioned_batch(&self, transactions: Vec<VersionedTransaction>) -> TpsClientResult<()> {
+        AsyncClient::async_send_versioned_transaction_batch(self, transactions)
+            .map_err(|err| err.into())
+    }
     fn get_latest_blockhash(&self) -> TpsClientResult<Hash> {
         SyncClient::get_latest_blockhash(self).map_err(|err| err.into())
     }
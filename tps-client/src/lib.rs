@@ -11,7 +11,7 @@ use {
         pubkey::Pubkey,
         signature::Signature,
         slot_history::Slot,
-        transaction::{Result, Transaction},
+        transaction::{Result, Transaction, VersionedTransaction},
         transport::TransportError,
     },
     solana_tpu_client::tpu_client::TpuSenderError,
@@ -48,6 +48,21 @@ pub trait TpsClient {
     /// Send a batch of signed transactions without confirmation.
     fn send_batch(&self, transactions: Vec<Transaction>) -> TpsClientResult<()>;
 
+    /// Send a batch of signed versioned transactions without confirmation.
+    ///
+    /// By default, only legacy transactions are supported and sent with `send_batch`.
+    fn send_versioned_batch(&self, transactions: Vec<VersionedTransaction>) -> TpsClientResult<()> {
+        let transactions = transactions
+            .into_iter()
+            .map(|transaction| {
+                transaction.into_legacy_transaction().ok_or_else(|| {
+                    TpsClientError::Custom("versioned transactions are not supported".to_string())
+                })
+            })
+            .collect::<TpsClientResult<Vec<_>>>()?;
+        self.send_batch(transactions)
+    }
+
     /// Get latest blockhash
     fn get_latest_blockhash(&self) -> TpsClientResult<Hash>;
 
@@ -11,7 +11,7 @@ use {
         pubkey::Pubkey,
         signature::Signature,
         slot_history::Slot,
-        transaction::{Result, Transaction},
+        transaction::{Result, Transaction, VersionedTransaction},
     },
     solana_transaction_status::UiConfirmedBlock,
 };
@@ -27,6 +27,13 @@ impl TpsClient for RpcClient {
         }
         Ok(())
     }
+
+    fn send_versioned_batch(&self, transactions: Vec<VersionedTransaction>) -> TpsClientResult<()> {
+        for transaction in transactions {
+            RpcClient::send_transaction(self, &transaction)?;
+        }
+        Ok(())
+    }
     fn get_latest_blockhash(&self) -> TpsClientResult<Hash> {
         RpcClient::get_latest_blockhash(self).map_err(|err| err.into())
     }
@@ -13,7 +13,7 @@ use {
         pubkey::Pubkey,
         signature::Signature,
         slot_history::Slot,
-        transaction::{Result, Transaction},
+        transaction::{Result, Transaction, VersionedTransaction},
     },
     solana_tpu_client::tpu_client::TpuClient,
     solana_transaction_status::UiConfirmedBlock,
@@ -34,6 +34,16 @@ where
         self.try_send_transaction_batch(&transactions)?;
         Ok(())
     }
+    fn send_versioned_batch(&self, transactions: Vec<VersionedTransaction>) -> TpsClientResult<()> {
+        let wire_transactions = transactions
+            .iter()
+            .map(|transaction| {
+                bincode::serialize(transaction).expect("serialize VersionedTransaction")
+            })
+            .collect();
+        self.try_send_wire_transaction_batch(wire_transactions)?;
+        Ok(())
+    }
     fn get_latest_blockhash(&self) -> TpsClientResult<Hash> {
         self.rpc_client()
             .get_latest_blockhash()
@@ -42,7 +42,12 @@ pub mod parse_accounts;
 pub mod parse_address_lookup_table;
 pub mod parse_associated_token;
 pub mod parse_bpf_loader;
+pub mod parse_compute_budget;
+pub mod parse_config;
+pub mod parse_idl;
 pub mod parse_instruction;
+pub mod parse_loader_v4;
+pub mod parse_precompiles;
 pub mod parse_stake;
 pub mod parse_system;
 pub mod parse_token;
@@ -0,0 +1,99 @@
+use {
+    crate::parse_instruction::{ParsableProgram, ParseInstructionError, ParsedInstructionEnum},
+    borsh::BorshDeserialize,
+    serde_json::json,
+    solana_sdk::{compute_budget::ComputeBudgetInstruction, instruction::CompiledInstruction},
+};
+
+pub fn parse_compute_budget(
+    instruction: &CompiledInstruction,
+) -> Result<ParsedInstructionEnum, ParseInstructionError> {
+    let compute_budget_instruction = ComputeBudgetInstruction::try_from_slice(&instruction.data)
+        .map_err(|_| {
+            ParseInstructionError::InstructionNotParsable(ParsableProgram::ComputeBudget)
+        })?;
+    match compute_budget_instruction {
+        ComputeBudgetInstruction::Unused => Err(ParseInstructionError::InstructionNotParsable(
+            ParsableProgram::ComputeBudget,
+        )),
+        ComputeBudgetInstruction::RequestHeapFrame(bytes) => Ok(ParsedInstructionEnum {
+            instruction_type: "requestHeapFrame".to_string(),
+            info: json!({
+                "bytes": bytes,
+            }),
+        }),
+        ComputeBudgetInstruction::SetComputeUnitLimit(units) => Ok(ParsedInstructionEnum {
+            instruction_type: "setComputeUnitLimit".to_string(),
+            info: json!({
+                "units": units,
+            }),
+        }),
+        ComputeBudgetInstruction::SetComputeUnitPrice(micro_lamports) => {
+            Ok(ParsedInstructionEnum {
+                instruction_type: "setComputeUnitPrice".to_string(),
+                info: json!({
+                    "microLamports": micro_lamports,
+                }),
+            })
+        }
+        ComputeBudgetInstruction::SetLoadedAccountsDataSizeLimit(bytes) => {
+            Ok(ParsedInstructionEnum {
+                instruction_type: "setLoadedAccountsDataSizeLimit".to_string(),
+                info: json!({
+                    "bytes": bytes,
+                }),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        solana_sdk::{message::Message, pubkey::Pubkey},
+    };
+
+    #[test]
+    fn test_parse_compute_budget_instructions() {
+        let fee_payer = Pubkey::new_unique();
+        let instructions = [
+            ComputeBudgetInstruction::request_heap_frame(64 * 1024),
+            ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+            ComputeBudgetInstruction::set_compute_unit_price(1_000),
+            ComputeBudgetInstruction::set_loaded_accounts_data_size_limit(32 * 1024),
+        ];
+        let message = Message::new(&instructions, Some(&fee_payer));
+        let parsed: Vec<_> = message
+            .instructions
+            .iter()
+            .map(|instruction| parse_compute_budget(instruction).unwrap())
+            .collect();
+        assert_eq!(
+            parsed,
+            vec![
+                ParsedInstructionEnum {
+                    instruction_type: "requestHeapFrame".to_string(),
+                    info: json!({"bytes": 64 * 1024}),
+                },
+                ParsedInstructionEnum {
+                    instruction_type: "setComputeUnitLimit".to_string(),
+                    info: json!({"units": 200_000}),
+                },
+                ParsedInstructionEnum {
+                    instruction_type: "setComputeUnitPrice".to_string(),
+                    info: json!({"microLamports": 1_000}),
+                },
+                ParsedInstructionEnum {
+                    instruction_type: "setLoadedAccountsDataSizeLimit".to_string(),
+                    info: json!({"bytes": 32 * 1024}),
+                },
+            ]
+        );
+
+        let bad_instruction = CompiledInstruction::new_from_raw_parts(0, vec![0], vec![]);
+        assert!(parse_compute_budget(&bad_instruction).is_err());
+        let bad_instruction = CompiledInstruction::new_from_raw_parts(0, vec![3, 1], vec![]);
+        assert!(parse_compute_budget(&bad_instruction).is_err());
+    }
+}
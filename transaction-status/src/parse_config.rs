@@ -0,0 +1,144 @@
+use {
+    crate::parse_instruction::{
+        check_num_accounts, ParsableProgram, ParseInstructionError, ParsedInstructionEnum,
+    },
+    bincode::deserialize,
+    serde_json::{json, Value},
+    solana_account_decoder::parse_config::{parse_config, ConfigAccountType, UiConfigKey},
+    solana_config_program::{get_config_data, ConfigKeys},
+    solana_sdk::{instruction::CompiledInstruction, message::AccountKeys},
+};
+
+/// Parses the single instruction of the config program, which stores its data into the config
+/// account. The data is decoded for the config account types known to the account decoder, and
+/// otherwise left base58 encoded.
+pub fn parse_config_instruction(
+    instruction: &CompiledInstruction,
+    account_keys: &AccountKeys,
+) -> Result<ParsedInstructionEnum, ParseInstructionError> {
+    let config_keys: ConfigKeys = deserialize(&instruction.data)
+        .map_err(|_| ParseInstructionError::InstructionNotParsable(ParsableProgram::Config))?;
+    let config_data = get_config_data(&instruction.data)
+        .map_err(|_| ParseInstructionError::InstructionNotParsable(ParsableProgram::Config))?;
+    match instruction.accounts.iter().max() {
+        Some(index) if (*index as usize) < account_keys.len() => {}
+        _ => {
+            // Runtime should prevent this from ever happening
+            return Err(ParseInstructionError::InstructionKeyMismatch(
+                ParsableProgram::Config,
+            ));
+        }
+    }
+    check_num_accounts(&instruction.accounts, 1, ParsableProgram::Config)?;
+    let config_account = account_keys[instruction.accounts[0] as usize];
+
+    // The instruction data is laid out like the data of the config account it is stored into
+    let config_data = match parse_config(&instruction.data, &config_account) {
+        Ok(ConfigAccountType::StakeConfig(stake_config)) => serde_json::to_value(stake_config)?,
+        Ok(ConfigAccountType::ValidatorInfo(validator_info)) => validator_info.config_data,
+        Err(_) => Value::String(bs58::encode(config_data).into_string()),
+    };
+    let keys: Vec<_> = config_keys
+        .keys
+        .iter()
+        .map(|(pubkey, signer)| UiConfigKey {
+            pubkey: pubkey.to_string(),
+            signer: *signer,
+        })
+        .collect();
+    Ok(ParsedInstructionEnum {
+        instruction_type: "store".to_string(),
+        info: json!({
+            "configAccount": config_account.to_string(),
+            "keys": keys,
+            "configData": config_data,
+        }),
+    })
+}
+
+#[cfg(test)]
+#[allow(deprecated)]
+mod test {
+    use {
+        super::*,
+        solana_account_decoder::validator_info::{self, ValidatorInfo},
+        solana_config_program::config_instruction,
+        solana_sdk::{
+            message::Message,
+            pubkey::Pubkey,
+            stake::config::{self as stake_config, Config as StakeConfig},
+        },
+    };
+
+    fn parse_message(message: &Message) -> Result<ParsedInstructionEnum, ParseInstructionError> {
+        parse_config_instruction(
+            &message.instructions[0],
+            &AccountKeys::new(&message.account_keys, None),
+        )
+    }
+
+    #[test]
+    fn test_parse_config_instruction() {
+        let fee_payer = Pubkey::new_unique();
+
+        let stake_config = StakeConfig {
+            warmup_cooldown_rate: 0.25,
+            slash_penalty: 50,
+        };
+        let instruction =
+            config_instruction::store(&stake_config::id(), false, vec![], &stake_config);
+        let message = Message::new(&[instruction], Some(&fee_payer));
+        assert_eq!(
+            parse_message(&message).unwrap(),
+            ParsedInstructionEnum {
+                instruction_type: "store".to_string(),
+                info: json!({
+                    "configAccount": stake_config::id().to_string(),
+                    "keys": [],
+                    "configData": {"warmupCooldownRate": 0.25, "slashPenalty": 50},
+                }),
+            }
+        );
+
+        let info_account = Pubkey::new_unique();
+        let validator = Pubkey::new_unique();
+        let validator_info = ValidatorInfo {
+            info: json!({"name": "Solana"}).to_string(),
+        };
+        let instruction = config_instruction::store(
+            &info_account,
+            true,
+            vec![(validator_info::id(), false), (validator, true)],
+            &validator_info,
+        );
+        let mut message = Message::new(&[instruction], Some(&fee_payer));
+        assert_eq!(
+            parse_message(&message).unwrap(),
+            ParsedInstructionEnum {
+                instruction_type: "store".to_string(),
+                info: json!({
+                    "configAccount": info_account.to_string(),
+                    "keys": [
+                        {"pubkey": validator_info::id().to_string(), "signer": false},
+                        {"pubkey": validator.to_string(), "signer": true},
+                    ],
+                    "configData": {"name": "Solana"},
+                }),
+            }
+        );
+
+        let other_account = Pubkey::new_unique();
+        let instruction = config_instruction::store(&other_account, true, vec![], &stake_config);
+        let data = get_config_data(&instruction.data).unwrap().to_vec();
+        let other_message = Message::new(&[instruction], Some(&fee_payer));
+        assert_eq!(
+            parse_message(&other_message).unwrap().info["configData"],
+            json!(bs58::encode(data).into_string())
+        );
+
+        message.instructions[0].accounts.clear();
+        assert!(parse_message(&message).is_err());
+        message.instructions[0].data = vec![0xff; 3];
+        assert!(parse_message(&message).is_err());
+    }
+}
@@ -0,0 +1,159 @@
+use {
+    crate::parse_instruction::{ParseInstructionError, ParsedInstructionEnum},
+    serde_json::Value,
+    solana_account_decoder::parse_idl::{Idl, IdlError},
+    solana_sdk::{instruction::CompiledInstruction, message::AccountKeys},
+};
+
+/// Keys of the parsed instruction info which are not IDL account names
+const ARGS_KEY: &str = "args";
+const REMAINING_ACCOUNTS_KEY: &str = "remainingAccounts";
+
+/// Parses an instruction of a program with a registered IDL. The accounts are keyed by their
+/// IDL names, the arguments are nested under `args` and accounts beyond those of the IDL are
+/// listed under `remainingAccounts`. Instructions with an account named like either key are not
+/// parsed, rather than have it overwritten.
+pub fn parse_idl_instruction(
+    idl: &Idl,
+    instruction: &CompiledInstruction,
+    account_keys: &AccountKeys,
+) -> Result<ParsedInstructionEnum, ParseInstructionError> {
+    let (idl_instruction, args) = idl.parse_instruction(&instruction.data)?;
+    if let Some(name) = idl_instruction
+        .accounts
+        .iter()
+        .find(|name| *name == ARGS_KEY || *name == REMAINING_ACCOUNTS_KEY)
+    {
+        return Err(IdlError::InvalidIdl(format!(
+            "account name {name} of instruction {} is reserved",
+            idl_instruction.name
+        ))
+        .into());
+    }
+    let mut account_pubkeys = instruction
+        .accounts
+        .iter()
+        .map(|index| {
+            account_keys
+                .get(*index as usize)
+                .map(|pubkey| Value::String(pubkey.to_string()))
+                // Runtime should prevent this from ever happening
+                .ok_or(IdlError::InvalidValue("account index"))
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter();
+
+    let mut info: serde_json::Map<_, _> = idl_instruction
+        .accounts
+        .iter()
+        .cloned()
+        .zip(account_pubkeys.by_ref())
+        .collect();
+    let remaining_accounts: Vec<_> = account_pubkeys.collect();
+    if !remaining_accounts.is_empty() {
+        info.insert(
+            REMAINING_ACCOUNTS_KEY.to_string(),
+            Value::Array(remaining_accounts),
+        );
+    }
+    info.insert(ARGS_KEY.to_string(), Value::Object(args));
+
+    Ok(ParsedInstructionEnum {
+        instruction_type: idl_instruction.name.clone(),
+        info: Value::Object(info),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, serde_json::json, solana_sdk::pubkey::Pubkey};
+
+    #[test]
+    fn test_parse_idl_instruction() {
+        let idl = json!({
+            "metadata": {"name": "vault"},
+            "instructions": [{
+                "name": "deposit",
+                "discriminator": [9, 9, 9, 9, 9, 9, 9, 9],
+                "accounts": [{"name": "vault"}, {"name": "depositor"}],
+                "args": [{"name": "amount", "type": "u64"}],
+            }],
+        });
+        let idl = Idl::from_json(idl.to_string().as_bytes()).unwrap();
+        let keys = vec![
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let account_keys = AccountKeys::new(&keys, None);
+        let mut data = vec![9; 8];
+        data.extend_from_slice(&500u64.to_le_bytes());
+
+        let instruction = CompiledInstruction::new_from_raw_parts(0, data.clone(), vec![1, 0]);
+        assert_eq!(
+            parse_idl_instruction(&idl, &instruction, &account_keys).unwrap(),
+            ParsedInstructionEnum {
+                instruction_type: "deposit".to_string(),
+                info: json!({
+                    "vault": keys[1].to_string(),
+                    "depositor": keys[0].to_string(),
+                    "args": {"amount": "500"},
+                }),
+            }
+        );
+
+        let instruction = CompiledInstruction::new_from_raw_parts(0, data.clone(), vec![1, 0, 2]);
+        assert_eq!(
+            parse_idl_instruction(&idl, &instruction, &account_keys)
+                .unwrap()
+                .info[REMAINING_ACCOUNTS_KEY],
+            json!([keys[2].to_string()])
+        );
+
+        let instruction = CompiledInstruction::new_from_raw_parts(0, data, vec![1, 3]);
+        assert!(parse_idl_instruction(&idl, &instruction, &account_keys).is_err());
+
+        let instruction = CompiledInstruction::new_from_raw_parts(0, vec![9; 8], vec![1, 0]);
+        assert!(parse_idl_instruction(&idl, &instruction, &account_keys).is_err());
+    }
+
+    #[test]
+    fn test_parse_idl_instruction_colliding_names() {
+        let idl = |account: &str| {
+            let idl = json!({
+                "metadata": {"name": "vault"},
+                "instructions": [{
+                    "name": "withdraw",
+                    "discriminator": [7, 7, 7, 7, 7, 7, 7, 7],
+                    "accounts": [{"name": account}],
+                    "args": [{"name": "vault", "type": "u8"}],
+                }],
+            });
+            Idl::from_json(idl.to_string().as_bytes()).unwrap()
+        };
+        let keys = vec![Pubkey::new_unique()];
+        let account_keys = AccountKeys::new(&keys, None);
+        let mut data = vec![7; 8];
+        data.push(3);
+        let instruction = CompiledInstruction::new_from_raw_parts(0, data, vec![0]);
+
+        // An argument named like an account doesn't overwrite its pubkey
+        assert_eq!(
+            parse_idl_instruction(&idl("vault"), &instruction, &account_keys)
+                .unwrap()
+                .info,
+            json!({
+                "vault": keys[0].to_string(),
+                "args": {"vault": 3},
+            })
+        );
+
+        // Neither are accounts named like the other keys of the info overwritten
+        for account in [ARGS_KEY, REMAINING_ACCOUNTS_KEY] {
+            assert!(matches!(
+                parse_idl_instruction(&idl(account), &instruction, &account_keys),
+                Err(ParseInstructionError::IdlError(IdlError::InvalidIdl(_)))
+            ));
+        }
+    }
+}
@@ -4,6 +4,11 @@ use {
         parse_address_lookup_table::parse_address_lookup_table,
         parse_associated_token::{parse_associated_token, spl_associated_token_id},
         parse_bpf_loader::{parse_bpf_loader, parse_bpf_upgradeable_loader},
+        parse_compute_budget::parse_compute_budget,
+        parse_config::parse_config_instruction,
+        parse_idl::parse_idl_instruction,
+        parse_loader_v4::parse_loader_v4,
+        parse_precompiles::{parse_ed25519, parse_secp256k1},
         parse_stake::parse_stake,
         parse_system::parse_system,
         parse_token::parse_token,
@@ -11,10 +16,14 @@ use {
     },
     inflector::Inflector,
     serde_json::Value,
-    solana_account_decoder::parse_token::spl_token_ids,
+    solana_account_decoder::{
+        parse_idl::{idl_registry, IdlError, IdlRegistry},
+        parse_token::spl_token_ids,
+    },
     solana_sdk::{
-        address_lookup_table, instruction::CompiledInstruction, message::AccountKeys,
-        pubkey::Pubkey, stake, system_program, vote,
+        address_lookup_table, compute_budget, ed25519_program, instruction::CompiledInstruction,
+        loader_v4, message::AccountKeys, pubkey::Pubkey, secp256k1_program, stake, system_program,
+        vote,
     },
     std::{
         collections::HashMap,
@@ -28,8 +37,13 @@ lazy_static! {
     static ref ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = spl_associated_token_id();
     static ref BPF_LOADER_PROGRAM_ID: Pubkey = solana_sdk::bpf_loader::id();
     static ref BPF_UPGRADEABLE_LOADER_PROGRAM_ID: Pubkey = solana_sdk::bpf_loader_upgradeable::id();
+    static ref COMPUTE_BUDGET_PROGRAM_ID: Pubkey = compute_budget::id();
+    static ref CONFIG_PROGRAM_ID: Pubkey = solana_config_program::id();
+    static ref ED25519_PROGRAM_ID: Pubkey = ed25519_program::id();
+    static ref LOADER_V4_PROGRAM_ID: Pubkey = loader_v4::id();
     static ref MEMO_V1_PROGRAM_ID: Pubkey = spl_memo_id_v1();
     static ref MEMO_V3_PROGRAM_ID: Pubkey = spl_memo_id_v3();
+    static ref SECP256K1_PROGRAM_ID: Pubkey = secp256k1_program::id();
     static ref STAKE_PROGRAM_ID: Pubkey = stake::program::id();
     static ref SYSTEM_PROGRAM_ID: Pubkey = system_program::id();
     static ref VOTE_PROGRAM_ID: Pubkey = vote::program::id();
@@ -53,6 +67,11 @@ lazy_static! {
             *BPF_UPGRADEABLE_LOADER_PROGRAM_ID,
             ParsableProgram::BpfUpgradeableLoader,
         );
+        m.insert(*LOADER_V4_PROGRAM_ID, ParsableProgram::LoaderV4);
+        m.insert(*COMPUTE_BUDGET_PROGRAM_ID, ParsableProgram::ComputeBudget);
+        m.insert(*CONFIG_PROGRAM_ID, ParsableProgram::Config);
+        m.insert(*ED25519_PROGRAM_ID, ParsableProgram::Ed25519);
+        m.insert(*SECP256K1_PROGRAM_ID, ParsableProgram::Secp256k1);
         m.insert(*STAKE_PROGRAM_ID, ParsableProgram::Stake);
         m.insert(*SYSTEM_PROGRAM_ID, ParsableProgram::System);
         m.insert(*VOTE_PROGRAM_ID, ParsableProgram::Vote);
@@ -73,6 +92,9 @@ pub enum ParseInstructionError {
 
     #[error("Internal error, please report")]
     SerdeJsonError(#[from] serde_json::error::Error),
+
+    #[error("IDL error: {0}")]
+    IdlError(#[from] IdlError),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -102,20 +124,63 @@ pub enum ParsableProgram {
     SplToken,
     BpfLoader,
     BpfUpgradeableLoader,
+    LoaderV4,
+    ComputeBudget,
+    Config,
+    Ed25519,
+    Secp256k1,
     Stake,
     System,
     Vote,
 }
 
+impl ParsableProgram {
+    /// Name of the program in parsed instructions
+    pub fn name(&self) -> String {
+        match self {
+            // Kebab casing would split the numbers off the precompile names
+            Self::Ed25519 => "ed25519".to_string(),
+            Self::Secp256k1 => "secp256k1".to_string(),
+            _ => format!("{self:?}").to_kebab_case(),
+        }
+    }
+}
+
 pub fn parse(
     program_id: &Pubkey,
     instruction: &CompiledInstruction,
     account_keys: &AccountKeys,
     stack_height: Option<u32>,
 ) -> Result<ParsedInstruction, ParseInstructionError> {
-    let program_name = PARSABLE_PROGRAM_IDS
-        .get(program_id)
-        .ok_or(ParseInstructionError::ProgramNotParsable)?;
+    parse_with_idl_registry(
+        program_id,
+        instruction,
+        account_keys,
+        stack_height,
+        idl_registry(),
+    )
+}
+
+/// Parses the instruction with the built-in parser of its program, or else with the IDL
+/// registered for the program in `idl_registry`
+fn parse_with_idl_registry(
+    program_id: &Pubkey,
+    instruction: &CompiledInstruction,
+    account_keys: &AccountKeys,
+    stack_height: Option<u32>,
+    idl_registry: &IdlRegistry,
+) -> Result<ParsedInstruction, ParseInstructionError> {
+    let Some(program_name) = PARSABLE_PROGRAM_IDS.get(program_id) else {
+        let idl = idl_registry
+            .get(program_id)
+            .ok_or(ParseInstructionError::ProgramNotParsable)?;
+        return Ok(ParsedInstruction {
+            program: idl.name().to_kebab_case(),
+            program_id: program_id.to_string(),
+            parsed: serde_json::to_value(parse_idl_instruction(&idl, instruction, account_keys)?)?,
+            stack_height,
+        });
+    };
     let parsed_json = match program_name {
         ParsableProgram::AddressLookupTable => {
             serde_json::to_value(parse_address_lookup_table(instruction, account_keys)?)?
@@ -131,12 +196,21 @@ pub fn parse(
         ParsableProgram::BpfUpgradeableLoader => {
             serde_json::to_value(parse_bpf_upgradeable_loader(instruction, account_keys)?)?
         }
+        ParsableProgram::LoaderV4 => {
+            serde_json::to_value(parse_loader_v4(instruction, account_keys)?)?
+        }
+        ParsableProgram::ComputeBudget => serde_json::to_value(parse_compute_budget(instruction)?)?,
+        ParsableProgram::Config => {
+            serde_json::to_value(parse_config_instruction(instruction, account_keys)?)?
+        }
+        ParsableProgram::Ed25519 => serde_json::to_value(parse_ed25519(instruction)?)?,
+        ParsableProgram::Secp256k1 => serde_json::to_value(parse_secp256k1(instruction)?)?,
         ParsableProgram::Stake => serde_json::to_value(parse_stake(instruction, account_keys)?)?,
         ParsableProgram::System => serde_json::to_value(parse_system(instruction, account_keys)?)?,
         ParsableProgram::Vote => serde_json::to_value(parse_vote(instruction, account_keys)?)?,
     };
     Ok(ParsedInstruction {
-        program: format!("{program_name:?}").to_kebab_case(),
+        program: program_name.name(),
         program_id: program_id.to_string(),
         parsed: parsed_json,
         stack_height,
@@ -169,7 +243,7 @@ pub(crate) fn check_num_accounts(
 
 #[cfg(test)]
 mod test {
-    use {super::*, serde_json::json};
+    use {super::*, serde_json::json, solana_account_decoder::parse_idl::Idl};
 
     #[test]
     fn test_parse() {
@@ -202,6 +276,91 @@ mod test {
         assert!(parse(&non_parsable_program_id, &memo_instruction, &no_keys, None).is_err());
     }
 
+    #[test]
+    fn test_parse_with_idl_registry() {
+        let idl = json!({
+            "metadata": {"name": "tagger"},
+            "instructions": [{
+                "name": "tag",
+                "discriminator": [240, 159, 166, 150],
+                "accounts": [],
+                "args": [],
+            }],
+        });
+        let program_id = Pubkey::new_unique();
+        let no_keys = AccountKeys::new(&[], None);
+        let instruction = CompiledInstruction {
+            program_id_index: 0,
+            accounts: vec![],
+            data: vec![240, 159, 166, 150],
+        };
+        let idl_registry = IdlRegistry::default();
+        assert!(
+            parse_with_idl_registry(&program_id, &instruction, &no_keys, None, &idl_registry)
+                .is_err()
+        );
+
+        for program_id in [program_id, MEMO_V3_PROGRAM_ID] {
+            idl_registry.register(
+                program_id,
+                Idl::from_json(idl.to_string().as_bytes()).unwrap(),
+            );
+        }
+        assert_eq!(
+            parse_with_idl_registry(&program_id, &instruction, &no_keys, None, &idl_registry)
+                .unwrap(),
+            ParsedInstruction {
+                program: "tagger".to_string(),
+                program_id: program_id.to_string(),
+                parsed: json!({"type": "tag", "info": {"args": {}}}),
+                stack_height: None,
+            }
+        );
+        // An IDL doesn't override the built-in parser of a program
+        assert_eq!(
+            parse_with_idl_registry(
+                &MEMO_V3_PROGRAM_ID,
+                &instruction,
+                &no_keys,
+                None,
+                &idl_registry
+            )
+            .unwrap()
+            .parsed,
+            json!("🦖")
+        );
+    }
+
+    #[test]
+    fn test_parsable_program_names() {
+        assert_eq!(ParsableProgram::ComputeBudget.name(), "compute-budget");
+        assert_eq!(ParsableProgram::LoaderV4.name(), "loader-v4");
+        assert_eq!(ParsableProgram::Ed25519.name(), "ed25519");
+        assert_eq!(ParsableProgram::Secp256k1.name(), "secp256k1");
+
+        let no_keys = AccountKeys::new(&[], None);
+        let compute_budget_instruction = CompiledInstruction {
+            program_id_index: 0,
+            accounts: vec![],
+            data: vec![2, 64, 13, 3, 0],
+        };
+        assert_eq!(
+            parse(
+                &COMPUTE_BUDGET_PROGRAM_ID,
+                &compute_budget_instruction,
+                &no_keys,
+                None
+            )
+            .unwrap(),
+            ParsedInstruction {
+                program: "compute-budget".to_string(),
+                program_id: COMPUTE_BUDGET_PROGRAM_ID.to_string(),
+                parsed: json!({"type": "setComputeUnitLimit", "info": {"units": 200_000}}),
+                stack_height: None,
+            }
+        );
+    }
+
     #[test]
     fn test_parse_memo() {
         let good_memo = "good memo".to_string();
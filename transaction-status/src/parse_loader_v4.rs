@@ -0,0 +1,191 @@
+use {
+    crate::parse_instruction::{
+        check_num_accounts, ParsableProgram, ParseInstructionError, ParsedInstructionEnum,
+    },
+    base64::{prelude::BASE64_STANDARD, Engine},
+    bincode::deserialize,
+    serde_json::json,
+    solana_sdk::{
+        instruction::CompiledInstruction, loader_v4_instruction::LoaderV4Instruction,
+        message::AccountKeys,
+    },
+};
+
+pub fn parse_loader_v4(
+    instruction: &CompiledInstruction,
+    account_keys: &AccountKeys,
+) -> Result<ParsedInstructionEnum, ParseInstructionError> {
+    let loader_v4_instruction: LoaderV4Instruction = deserialize(&instruction.data)
+        .map_err(|_| ParseInstructionError::InstructionNotParsable(ParsableProgram::LoaderV4))?;
+    match instruction.accounts.iter().max() {
+        Some(index) if (*index as usize) < account_keys.len() => {}
+        _ => {
+            // Runtime should prevent this from ever happening
+            return Err(ParseInstructionError::InstructionKeyMismatch(
+                ParsableProgram::LoaderV4,
+            ));
+        }
+    }
+    check_num_loader_v4_accounts(&instruction.accounts, 2)?;
+    let mut value = json!({
+        "account": account_keys[instruction.accounts[0] as usize].to_string(),
+        "authority": account_keys[instruction.accounts[1] as usize].to_string(),
+    });
+    let map = value.as_object_mut().unwrap();
+    let optional_account = instruction
+        .accounts
+        .get(2)
+        .map(|index| json!(account_keys[*index as usize].to_string()));
+    let instruction_type = match loader_v4_instruction {
+        LoaderV4Instruction::Write { offset, bytes } => {
+            map.insert("offset".to_string(), json!(offset));
+            map.insert("bytes".to_string(), json!(BASE64_STANDARD.encode(bytes)));
+            "write"
+        }
+        LoaderV4Instruction::Truncate { new_size } => {
+            map.insert("newSize".to_string(), json!(new_size));
+            if let Some(recipient) = optional_account {
+                map.insert("recipient".to_string(), recipient);
+            }
+            "truncate"
+        }
+        LoaderV4Instruction::Deploy => {
+            if let Some(source) = optional_account {
+                map.insert("source".to_string(), source);
+            }
+            "deploy"
+        }
+        LoaderV4Instruction::Retract => "retract",
+        LoaderV4Instruction::TransferAuthority => {
+            // Without a new authority the program is finalized
+            map.insert(
+                "newAuthority".to_string(),
+                optional_account.unwrap_or_default(),
+            );
+            "transferAuthority"
+        }
+    };
+    Ok(ParsedInstructionEnum {
+        instruction_type: instruction_type.to_string(),
+        info: value,
+    })
+}
+
+fn check_num_loader_v4_accounts(accounts: &[u8], num: usize) -> Result<(), ParseInstructionError> {
+    check_num_accounts(accounts, num, ParsableProgram::LoaderV4)
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        serde_json::Value,
+        solana_sdk::{loader_v4, message::Message, pubkey::Pubkey},
+    };
+
+    fn parse_message(message: &Message) -> Result<ParsedInstructionEnum, ParseInstructionError> {
+        parse_loader_v4(
+            message.instructions.last().unwrap(),
+            &AccountKeys::new(&message.account_keys, None),
+        )
+    }
+
+    #[test]
+    fn test_parse_loader_v4_instructions() {
+        let fee_payer = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let source = Pubkey::new_unique();
+        let new_authority = Pubkey::new_unique();
+
+        let bytes = vec![7; 32];
+        let instruction = loader_v4::write(&program, &authority, 64, bytes.clone());
+        let message = Message::new(&[instruction], Some(&fee_payer));
+        assert_eq!(
+            parse_message(&message).unwrap(),
+            ParsedInstructionEnum {
+                instruction_type: "write".to_string(),
+                info: json!({
+                    "account": program.to_string(),
+                    "authority": authority.to_string(),
+                    "offset": 64,
+                    "bytes": BASE64_STANDARD.encode(&bytes),
+                }),
+            }
+        );
+
+        let instructions =
+            loader_v4::create_buffer(&fee_payer, &program, 1_000, &authority, 128, &recipient);
+        let message = Message::new(&instructions, Some(&fee_payer));
+        assert_eq!(
+            parse_message(&message).unwrap(),
+            ParsedInstructionEnum {
+                instruction_type: "truncate".to_string(),
+                info: json!({
+                    "account": program.to_string(),
+                    "authority": authority.to_string(),
+                    "newSize": 128,
+                    "recipient": recipient.to_string(),
+                }),
+            }
+        );
+
+        let instruction = loader_v4::deploy(&program, &authority);
+        let message = Message::new(&[instruction], Some(&fee_payer));
+        assert_eq!(
+            parse_message(&message).unwrap(),
+            ParsedInstructionEnum {
+                instruction_type: "deploy".to_string(),
+                info: json!({
+                    "account": program.to_string(),
+                    "authority": authority.to_string(),
+                }),
+            }
+        );
+
+        let instruction = loader_v4::deploy_from_source(&program, &authority, &source);
+        let message = Message::new(&[instruction], Some(&fee_payer));
+        assert_eq!(
+            parse_message(&message).unwrap().info["source"],
+            json!(source.to_string())
+        );
+
+        let instruction = loader_v4::retract(&program, &authority);
+        let message = Message::new(&[instruction], Some(&fee_payer));
+        assert_eq!(
+            parse_message(&message).unwrap(),
+            ParsedInstructionEnum {
+                instruction_type: "retract".to_string(),
+                info: json!({
+                    "account": program.to_string(),
+                    "authority": authority.to_string(),
+                }),
+            }
+        );
+
+        let instruction = loader_v4::transfer_authority(&program, &authority, Some(&new_authority));
+        let message = Message::new(&[instruction], Some(&fee_payer));
+        assert_eq!(
+            parse_message(&message).unwrap(),
+            ParsedInstructionEnum {
+                instruction_type: "transferAuthority".to_string(),
+                info: json!({
+                    "account": program.to_string(),
+                    "authority": authority.to_string(),
+                    "newAuthority": new_authority.to_string(),
+                }),
+            }
+        );
+
+        let instruction = loader_v4::transfer_authority(&program, &authority, None);
+        let mut message = Message::new(&[instruction], Some(&fee_payer));
+        assert_eq!(
+            parse_message(&message).unwrap().info["newAuthority"],
+            Value::Null
+        );
+
+        message.instructions[0].accounts.pop();
+        assert!(parse_message(&message).is_err());
+    }
+}
@@ -0,0 +1,222 @@
+use {
+    crate::parse_instruction::{ParsableProgram, ParseInstructionError, ParsedInstructionEnum},
+    base64::{prelude::BASE64_STANDARD, Engine},
+    serde_json::json,
+    solana_sdk::{ed25519_instruction, instruction::CompiledInstruction, secp256k1_instruction},
+};
+
+/// Instruction index used by ed25519 signature offsets to refer to the instruction itself
+const ED25519_CURRENT_INSTRUCTION_INDEX: u16 = u16::MAX;
+
+/// Parses the signature offsets of an ed25519 precompile instruction. The public key, signature
+/// and message are included for the offsets pointing into the instruction itself.
+pub fn parse_ed25519(
+    instruction: &CompiledInstruction,
+) -> Result<ParsedInstructionEnum, ParseInstructionError> {
+    let data = &instruction.data;
+    let not_parsable = || ParseInstructionError::InstructionNotParsable(ParsableProgram::Ed25519);
+    let num_signatures = *data.first().ok_or_else(not_parsable)? as usize;
+    let mut signatures = Vec::with_capacity(num_signatures);
+    for i in 0..num_signatures {
+        let start = ed25519_instruction::SIGNATURE_OFFSETS_START
+            + i * ed25519_instruction::SIGNATURE_OFFSETS_SERIALIZED_SIZE;
+        let offsets = data
+            .get(start..start + ed25519_instruction::SIGNATURE_OFFSETS_SERIALIZED_SIZE)
+            .ok_or_else(not_parsable)?;
+        let field = |index: usize| u16::from_le_bytes([offsets[2 * index], offsets[2 * index + 1]]);
+        let signature_offset = field(0);
+        let signature_instruction_index = field(1);
+        let public_key_offset = field(2);
+        let public_key_instruction_index = field(3);
+        let message_data_offset = field(4);
+        let message_data_size = field(5);
+        let message_instruction_index = field(6);
+
+        let mut value = json!({
+            "signatureOffset": signature_offset,
+            "signatureInstructionIndex": signature_instruction_index,
+            "publicKeyOffset": public_key_offset,
+            "publicKeyInstructionIndex": public_key_instruction_index,
+            "messageDataOffset": message_data_offset,
+            "messageDataSize": message_data_size,
+            "messageInstructionIndex": message_instruction_index,
+        });
+        let map = value.as_object_mut().unwrap();
+        if public_key_instruction_index == ED25519_CURRENT_INSTRUCTION_INDEX {
+            let public_key = get_data_slice(
+                data,
+                public_key_offset,
+                ed25519_instruction::PUBKEY_SERIALIZED_SIZE,
+            )
+            .ok_or_else(not_parsable)?;
+            map.insert(
+                "publicKey".to_string(),
+                json!(bs58::encode(public_key).into_string()),
+            );
+        }
+        if signature_instruction_index == ED25519_CURRENT_INSTRUCTION_INDEX {
+            let signature = get_data_slice(
+                data,
+                signature_offset,
+                ed25519_instruction::SIGNATURE_SERIALIZED_SIZE,
+            )
+            .ok_or_else(not_parsable)?;
+            map.insert(
+                "signature".to_string(),
+                json!(bs58::encode(signature).into_string()),
+            );
+        }
+        if message_instruction_index == ED25519_CURRENT_INSTRUCTION_INDEX {
+            let message = get_data_slice(data, message_data_offset, message_data_size as usize)
+                .ok_or_else(not_parsable)?;
+            map.insert(
+                "message".to_string(),
+                json!(BASE64_STANDARD.encode(message)),
+            );
+        }
+        signatures.push(value);
+    }
+    Ok(ParsedInstructionEnum {
+        instruction_type: "verify".to_string(),
+        info: json!({
+            "signatures": signatures,
+        }),
+    })
+}
+
+/// Parses the signature offsets of a secp256k1 precompile instruction. The offsets refer to
+/// instructions by their index in the transaction, so the referenced data is not resolved.
+pub fn parse_secp256k1(
+    instruction: &CompiledInstruction,
+) -> Result<ParsedInstructionEnum, ParseInstructionError> {
+    let data = &instruction.data;
+    let not_parsable = || ParseInstructionError::InstructionNotParsable(ParsableProgram::Secp256k1);
+    let num_signatures = *data.first().ok_or_else(not_parsable)? as usize;
+    let mut signatures = Vec::with_capacity(num_signatures);
+    for i in 0..num_signatures {
+        let start = 1 + i * secp256k1_instruction::SIGNATURE_OFFSETS_SERIALIZED_SIZE;
+        let offsets: secp256k1_instruction::SecpSignatureOffsets = data
+            .get(start..start + secp256k1_instruction::SIGNATURE_OFFSETS_SERIALIZED_SIZE)
+            .and_then(|offsets| bincode::deserialize(offsets).ok())
+            .ok_or_else(not_parsable)?;
+        signatures.push(json!({
+            "signatureOffset": offsets.signature_offset,
+            "signatureInstructionIndex": offsets.signature_instruction_index,
+            "ethAddressOffset": offsets.eth_address_offset,
+            "ethAddressInstructionIndex": offsets.eth_address_instruction_index,
+            "messageDataOffset": offsets.message_data_offset,
+            "messageDataSize": offsets.message_data_size,
+            "messageInstructionIndex": offsets.message_instruction_index,
+        }));
+    }
+    Ok(ParsedInstructionEnum {
+        instruction_type: "verify".to_string(),
+        info: json!({
+            "signatures": signatures,
+        }),
+    })
+}
+
+fn get_data_slice(data: &[u8], offset: u16, size: usize) -> Option<&[u8]> {
+    let start = offset as usize;
+    data.get(start..start.checked_add(size)?)
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        solana_sdk::{message::Message, pubkey::Pubkey, secp256k1_program},
+    };
+
+    fn compile(instruction: solana_sdk::instruction::Instruction) -> CompiledInstruction {
+        let message = Message::new(&[instruction], Some(&Pubkey::new_unique()));
+        message.instructions[0].clone()
+    }
+
+    #[test]
+    fn test_parse_ed25519_instruction() {
+        let secret = ed25519_dalek::SecretKey::from_bytes(&[7; 32]).unwrap();
+        let public = ed25519_dalek::PublicKey::from(&secret);
+        let keypair = ed25519_dalek::Keypair { secret, public };
+        let message = b"hello";
+        let instruction = compile(ed25519_instruction::new_ed25519_instruction(
+            &keypair, message,
+        ));
+
+        let public_key_offset = ed25519_instruction::DATA_START;
+        let signature_offset = public_key_offset + ed25519_instruction::PUBKEY_SERIALIZED_SIZE;
+        let message_data_offset = signature_offset + ed25519_instruction::SIGNATURE_SERIALIZED_SIZE;
+        let signature = &instruction.data[signature_offset..message_data_offset];
+        assert_eq!(
+            parse_ed25519(&instruction).unwrap(),
+            ParsedInstructionEnum {
+                instruction_type: "verify".to_string(),
+                info: json!({
+                    "signatures": [{
+                        "signatureOffset": signature_offset,
+                        "signatureInstructionIndex": u16::MAX,
+                        "publicKeyOffset": public_key_offset,
+                        "publicKeyInstructionIndex": u16::MAX,
+                        "messageDataOffset": message_data_offset,
+                        "messageDataSize": message.len(),
+                        "messageInstructionIndex": u16::MAX,
+                        "publicKey": bs58::encode(public.to_bytes()).into_string(),
+                        "signature": bs58::encode(signature).into_string(),
+                        "message": BASE64_STANDARD.encode(message),
+                    }],
+                }),
+            }
+        );
+
+        let mut bad_instruction = instruction.clone();
+        bad_instruction.data.truncate(message_data_offset);
+        assert!(parse_ed25519(&bad_instruction).is_err());
+        bad_instruction
+            .data
+            .truncate(ed25519_instruction::SIGNATURE_OFFSETS_START);
+        assert!(parse_ed25519(&bad_instruction).is_err());
+        bad_instruction.data.clear();
+        assert!(parse_ed25519(&bad_instruction).is_err());
+    }
+
+    #[test]
+    fn test_parse_secp256k1_instruction() {
+        let secret_key = libsecp256k1::SecretKey::parse(&[7; 32]).unwrap();
+        let message = b"hello";
+        let instruction = secp256k1_instruction::new_secp256k1_instruction(&secret_key, message);
+        assert_eq!(instruction.program_id, secp256k1_program::id());
+        let instruction = compile(instruction);
+
+        let eth_address_offset = secp256k1_instruction::DATA_START;
+        let signature_offset =
+            eth_address_offset + secp256k1_instruction::HASHED_PUBKEY_SERIALIZED_SIZE;
+        let message_data_offset =
+            signature_offset + secp256k1_instruction::SIGNATURE_SERIALIZED_SIZE + 1;
+        assert_eq!(
+            parse_secp256k1(&instruction).unwrap(),
+            ParsedInstructionEnum {
+                instruction_type: "verify".to_string(),
+                info: json!({
+                    "signatures": [{
+                        "signatureOffset": signature_offset,
+                        "signatureInstructionIndex": 0,
+                        "ethAddressOffset": eth_address_offset,
+                        "ethAddressInstructionIndex": 0,
+                        "messageDataOffset": message_data_offset,
+                        "messageDataSize": message.len(),
+                        "messageInstructionIndex": 0,
+                    }],
+                }),
+            }
+        );
+
+        let mut bad_instruction = instruction;
+        bad_instruction
+            .data
+            .truncate(secp256k1_instruction::DATA_START - 1);
+        assert!(parse_secp256k1(&bad_instruction).is_err());
+        bad_instruction.data.clear();
+        assert!(parse_secp256k1(&bad_instruction).is_err());
+    }
+}
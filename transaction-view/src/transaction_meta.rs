@@ -1,9 +1,10 @@
+pub use crate::message_header_meta::TransactionVersion;
 use {
     crate::{
         address_table_lookup_meta::AddressTableLookupMeta,
         bytes::advance_offset_for_type,
         instructions_meta::{InstructionsIterator, InstructionsMeta},
-        message_header_meta::{MessageHeaderMeta, TransactionVersion},
+        message_header_meta::MessageHeaderMeta,
         result::{Result, TransactionParsingError},
         signature_meta::SignatureMeta,
         static_account_keys_meta::StaticAccountKeysMeta,
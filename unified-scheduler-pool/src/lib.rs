@@ -2313,7 +2313,7 @@ mod tests {
             ));
         // make sure this tx is really a good one to execute.
         assert_matches!(
-            bank.simulate_transaction_unchecked(good_tx_after_bad_tx, false)
+            bank.simulate_transaction_unchecked(good_tx_after_bad_tx, false, false)
                 .result,
             Ok(_)
         );
@@ -8,6 +8,7 @@ use {
     },
     log::*,
     serde::{de::Deserializer, Deserialize, Serialize},
+    solana_account_decoder::parse_idl::{onchain_idl_address, register_idl, Idl},
     solana_accounts_db::accounts_index::AccountIndex,
     solana_core::{
         admin_rpc_post_init::AdminRpcRequestMetadataPostInit,
@@ -20,10 +21,15 @@ use {
     solana_rpc::rpc::verify_pubkey,
     solana_rpc_client_api::{config::RpcAccountIndex, custom_error::RpcCustomError},
     solana_sdk::{
+        account::ReadableAccount,
+        clock::Slot,
         exit::Exit,
         pubkey::Pubkey,
         signature::{read_keypair_file, Keypair, Signer},
     },
+    solana_streamer::nonblocking::connection_stats::ConnectionStatsSnapshot,
+    solana_test_validator::Checkpoints,
+    solana_wen_restart::wen_restart_status::{read_wen_restart_status, WenRestartStatus},
     std::{
         collections::{HashMap, HashSet},
         error,
@@ -48,6 +54,10 @@ pub struct AdminRpcRequestMetadata {
     pub staked_nodes_overrides: Arc<RwLock<HashMap<Pubkey, u64>>>,
     pub post_init: Arc<RwLock<Option<AdminRpcRequestMetadataPostInit>>>,
     pub rpc_to_plugin_manager_sender: Option<Sender<GeyserPluginManagerRequest>>,
+    /// Account checkpoints, only available on solana-test-validator
+    pub checkpoints: Option<Arc<Checkpoints>>,
+    /// Wen Restart progress file, only set when running with --wen-restart
+    pub wen_restart_path: Option<PathBuf>,
 }
 
 impl Metadata for AdminRpcRequestMetadata {}
@@ -65,6 +75,20 @@ impl AdminRpcRequestMetadata {
             ))
         }
     }
+
+    fn with_checkpoints<F, R>(&self, func: F) -> Result<R>
+    where
+        F: FnOnce(&Checkpoints, &AdminRpcRequestMetadataPostInit) -> std::result::Result<R, String>,
+    {
+        let checkpoints = self.checkpoints.as_ref().ok_or_else(|| {
+            jsonrpc_core::error::Error::invalid_params(
+                "Checkpoints are only supported by solana-test-validator",
+            )
+        })?;
+        self.with_post_init(|post_init| {
+            func(checkpoints, post_init).map_err(jsonrpc_core::error::Error::invalid_params)
+        })
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -89,6 +113,43 @@ pub struct AdminRpcRepairWhitelist {
     pub whitelist: Vec<Pubkey>,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AdminRpcQuicConnection {
+    /// Either "tpu" or "tpu_forwards"
+    pub server: String,
+    pub remote_address: SocketAddr,
+    pub identity: Option<String>,
+    pub stake: u64,
+    pub age_ms: u64,
+    pub streams_opened: u64,
+    pub streams_throttled: u64,
+    pub streams_dropped: u64,
+    pub max_streams: u64,
+    pub rtt_us: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AdminRpcTpuQuicConnections {
+    pub connections: Vec<AdminRpcQuicConnection>,
+}
+
+impl AdminRpcQuicConnection {
+    fn new(server: &str, connection: ConnectionStatsSnapshot) -> Self {
+        Self {
+            server: server.to_string(),
+            remote_address: connection.remote_address,
+            identity: connection.remote_pubkey.as_ref().map(Pubkey::to_string),
+            stake: connection.stake,
+            age_ms: connection.age.as_millis() as u64,
+            streams_opened: connection.streams_opened,
+            streams_throttled: connection.streams_throttled,
+            streams_dropped: connection.streams_dropped,
+            max_streams: connection.max_streams,
+            rtt_us: connection.rtt.as_micros() as u64,
+        }
+    }
+}
+
 impl From<ContactInfo> for AdminRpcContactInfo {
     fn from(node: ContactInfo) -> Self {
         macro_rules! unwrap_socket {
@@ -140,6 +201,42 @@ impl Display for AdminRpcRepairWhitelist {
     }
 }
 
+impl Display for AdminRpcTpuQuicConnections {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<12} {:<22} {:<44} {:>20} {:>10} {:>8} {:>9} {:>8} {:>11} {:>8}",
+            "Server",
+            "Address",
+            "Identity",
+            "Stake",
+            "Age (s)",
+            "Streams",
+            "Throttled",
+            "Dropped",
+            "Max Streams",
+            "RTT (ms)",
+        )?;
+        for connection in &self.connections {
+            writeln!(
+                f,
+                "{:<12} {:<22} {:<44} {:>20} {:>10} {:>8} {:>9} {:>8} {:>11} {:>8}",
+                connection.server,
+                connection.remote_address.to_string(),
+                connection.identity.as_deref().unwrap_or("-"),
+                connection.stake,
+                connection.age_ms / 1000,
+                connection.streams_opened,
+                connection.streams_throttled,
+                connection.streams_dropped,
+                connection.max_streams,
+                connection.rtt_us / 1000,
+            )?;
+        }
+        writeln!(f, "{} connections", self.connections.len())
+    }
+}
+
 #[rpc]
 pub trait AdminRpc {
     type Metadata;
@@ -243,6 +340,27 @@ pub trait AdminRpc {
         meta: Self::Metadata,
         public_tpu_forwards_addr: SocketAddr,
     ) -> Result<()>;
+
+    #[rpc(meta, name = "tpuQuicConnections")]
+    fn tpu_quic_connections(&self, meta: Self::Metadata) -> Result<AdminRpcTpuQuicConnections>;
+
+    #[rpc(meta, name = "evictTpuQuicPeer")]
+    fn evict_tpu_quic_peer(&self, meta: Self::Metadata, pubkey_str: String) -> Result<usize>;
+
+    #[rpc(meta, name = "registerIdl")]
+    fn register_idl(&self, meta: Self::Metadata, program_id: String) -> Result<String>;
+
+    #[rpc(meta, name = "wenRestartStatus")]
+    fn wen_restart_status(&self, meta: Self::Metadata) -> Result<WenRestartStatus>;
+
+    #[rpc(meta, name = "checkpoint")]
+    fn checkpoint(&self, meta: Self::Metadata, name: String) -> Result<Slot>;
+
+    #[rpc(meta, name = "rewind")]
+    fn rewind(&self, meta: Self::Metadata, name: String) -> Result<Slot>;
+
+    #[rpc(meta, name = "listCheckpoints")]
+    fn list_checkpoints(&self, meta: Self::Metadata) -> Result<Vec<(String, Slot)>>;
 }
 
 pub struct AdminRpcImpl;
@@ -674,6 +792,102 @@ impl AdminRpc for AdminRpcImpl {
             Ok(())
         })
     }
+
+    fn tpu_quic_connections(&self, meta: Self::Metadata) -> Result<AdminRpcTpuQuicConnections> {
+        debug!("tpu_quic_connections rpc request received");
+
+        meta.with_post_init(|post_init| {
+            let connections = [
+                ("tpu", &post_init.tpu_quic_connection_stats),
+                (
+                    "tpu_forwards",
+                    &post_init.tpu_forwards_quic_connection_stats,
+                ),
+            ]
+            .into_iter()
+            .flat_map(|(server, connection_stats)| {
+                connection_stats
+                    .connections()
+                    .into_iter()
+                    .map(move |connection| AdminRpcQuicConnection::new(server, connection))
+            })
+            .collect();
+            Ok(AdminRpcTpuQuicConnections { connections })
+        })
+    }
+
+    fn evict_tpu_quic_peer(&self, meta: Self::Metadata, pubkey_str: String) -> Result<usize> {
+        debug!("evict_tpu_quic_peer rpc request received: {pubkey_str}");
+        let pubkey = verify_pubkey(&pubkey_str)?;
+
+        meta.with_post_init(|post_init| {
+            let num_evicted = post_init.tpu_quic_connection_stats.evict_peer(&pubkey)
+                + post_init
+                    .tpu_forwards_quic_connection_stats
+                    .evict_peer(&pubkey);
+            warn!("Evicted {num_evicted} TPU QUIC connections of {pubkey}");
+            Ok(num_evicted)
+        })
+    }
+
+    fn register_idl(&self, meta: Self::Metadata, program_id: String) -> Result<String> {
+        debug!("register_idl rpc request received: {program_id}");
+        let program_id = verify_pubkey(&program_id)?;
+
+        meta.with_post_init(|post_init| {
+            let idl_address = onchain_idl_address(&program_id);
+            let bank = post_init.bank_forks.read().unwrap().root_bank();
+            let account = bank.get_account(&idl_address).ok_or_else(|| {
+                jsonrpc_core::error::Error::invalid_params(format!(
+                    "IDL account {idl_address} of program {program_id} not found"
+                ))
+            })?;
+            let idl = Idl::from_onchain_account(account.data()).map_err(|err| {
+                jsonrpc_core::error::Error::invalid_params(format!(
+                    "Invalid IDL account {idl_address}: {err}"
+                ))
+            })?;
+            let name = idl.name().to_string();
+            register_idl(program_id, idl);
+            info!("Registered the on-chain IDL of {name} for program {program_id}");
+            Ok(name)
+        })
+    }
+
+    fn wen_restart_status(&self, meta: Self::Metadata) -> Result<WenRestartStatus> {
+        debug!("wen_restart_status rpc request received");
+        let wen_restart_path = meta.wen_restart_path.as_ref().ok_or_else(|| {
+            jsonrpc_core::error::Error::invalid_params("Validator is not running Wen Restart")
+        })?;
+        read_wen_restart_status(wen_restart_path).map_err(|err| {
+            jsonrpc_core::error::Error::invalid_params(format!(
+                "Failed to read Wen Restart progress {}: {err}",
+                wen_restart_path.display()
+            ))
+        })
+    }
+
+    fn checkpoint(&self, meta: Self::Metadata, name: String) -> Result<Slot> {
+        debug!("checkpoint rpc request received: {name}");
+
+        meta.with_checkpoints(|checkpoints, post_init| {
+            checkpoints.save(&name, &post_init.bank_forks)
+        })
+    }
+
+    fn rewind(&self, meta: Self::Metadata, name: String) -> Result<Slot> {
+        debug!("rewind rpc request received: {name}");
+
+        meta.with_checkpoints(|checkpoints, post_init| {
+            checkpoints.rewind(&name, &post_init.bank_forks)
+        })
+    }
+
+    fn list_checkpoints(&self, meta: Self::Metadata) -> Result<Vec<(String, Slot)>> {
+        debug!("list_checkpoints rpc request received");
+
+        meta.with_checkpoints(|checkpoints, _post_init| Ok(checkpoints.list()))
+    }
 }
 
 impl AdminRpcImpl {
@@ -875,7 +1089,9 @@ mod tests {
             pubkey::Pubkey,
             system_program,
         },
-        solana_streamer::socket::SocketAddrSpace,
+        solana_streamer::{
+            nonblocking::connection_stats::ConnectionStatsTable, socket::SocketAddrSpace,
+        },
         spl_token_2022::{
             solana_program::{program_option::COption, program_pack::Pack},
             state::{Account as TokenAccount, AccountState as TokenAccountState, Mint},
@@ -938,9 +1154,13 @@ mod tests {
                     cluster_slots: Arc::new(
                         solana_core::cluster_slots_service::cluster_slots::ClusterSlots::default(),
                     ),
+                    tpu_quic_connection_stats: Arc::<ConnectionStatsTable>::default(),
+                    tpu_forwards_quic_connection_stats: Arc::<ConnectionStatsTable>::default(),
                 }))),
                 staked_nodes_overrides: Arc::new(RwLock::new(HashMap::new())),
                 rpc_to_plugin_manager_sender: None,
+                checkpoints: Some(Arc::<Checkpoints>::default()),
+                wen_restart_path: None,
             };
             let mut io = MetaIoHandler::default();
             io.extend_with(AdminRpcImpl.to_delegate());
@@ -1275,4 +1495,102 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_checkpoint_and_rewind() {
+        let rpc = RpcHandler::start_with_config(TestConfig::default());
+        let bank = rpc.root_bank();
+        let RpcHandler { io, meta, .. } = rpc;
+
+        let request = |method: &str, params: &str| -> Value {
+            let req =
+                format!(r#"{{"jsonrpc":"2.0","id":1,"method":"{method}","params":{params}}}"#);
+            let res = io.handle_request_sync(&req, meta.clone());
+            serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization")
+        };
+        let wallet = |lamports| {
+            AccountSharedData::from(Account {
+                lamports,
+                owner: system_program::id(),
+                ..Account::default()
+            })
+        };
+
+        let wallet1_pubkey = Pubkey::new_unique();
+        let wallet2_pubkey = Pubkey::new_unique();
+        assert!(bank.store_accounts_unless_frozen(&[(wallet1_pubkey, wallet(1_000))]));
+        let capitalization = bank.capitalization();
+
+        let result = request("checkpoint", r#"["start"]"#);
+        assert_eq!(result["result"], bank.slot());
+
+        assert!(bank.store_accounts_unless_frozen(&[
+            (wallet1_pubkey, wallet(2_000)),
+            (wallet2_pubkey, wallet(3_000)),
+        ]));
+
+        let result = request("rewind", r#"["start"]"#);
+        assert_eq!(result["result"], bank.slot());
+        assert_eq!(bank.get_account(&wallet1_pubkey), Some(wallet(1_000)));
+        assert_eq!(bank.get_account(&wallet2_pubkey), None);
+        assert_eq!(bank.capitalization(), capitalization);
+
+        let result = request("rewind", r#"["unknown"]"#);
+        assert_eq!(result["error"]["message"], "Unknown checkpoint: unknown");
+
+        let result = request("listCheckpoints", "[]");
+        assert_eq!(
+            result["result"],
+            serde_json::json!([["start", bank.slot()]])
+        );
+    }
+
+    #[test]
+    fn test_wen_restart_status() {
+        let rpc = RpcHandler::start_with_config(TestConfig::default());
+        let RpcHandler { io, mut meta, .. } = rpc;
+        let request = r#"{"jsonrpc":"2.0","id":1,"method":"wenRestartStatus","params":[]}"#;
+
+        let res = io.handle_request_sync(request, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(
+            result["error"]["message"],
+            "Validator is not running Wen Restart"
+        );
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        meta.wen_restart_path = Some(temp_dir.path().join("wen_restart_progress.proto"));
+        let res = io.handle_request_sync(request, meta);
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert!(result["error"]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Failed to read Wen Restart progress"));
+    }
+
+    #[test]
+    fn test_tpu_quic_connections() {
+        let rpc = RpcHandler::start_with_config(TestConfig::default());
+        let RpcHandler { io, meta, .. } = rpc;
+        let request = |method: &str, params: &str| -> Value {
+            let req =
+                format!(r#"{{"jsonrpc":"2.0","id":1,"method":"{method}","params":{params}}}"#);
+            let res = io.handle_request_sync(&req, meta.clone());
+            serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization")
+        };
+
+        let result = request("tpuQuicConnections", "[]");
+        assert_eq!(result["result"], serde_json::json!({ "connections": [] }));
+
+        let pubkey = Pubkey::new_unique();
+        let result = request("evictTpuQuicPeer", &format!(r#"["{pubkey}"]"#));
+        assert_eq!(result["result"], 0);
+
+        let result = request("evictTpuQuicPeer", r#"["not a pubkey"]"#);
+        assert!(result["error"].is_object());
+    }
 }
@@ -37,7 +37,7 @@ use {
         net::{IpAddr, Ipv4Addr, SocketAddr},
         path::{Path, PathBuf},
         process::exit,
-        sync::{Arc, RwLock},
+        sync::Arc,
         time::{Duration, SystemTime, UNIX_EPOCH},
     },
 };
@@ -386,7 +386,6 @@ fn main() {
 
     let tower_storage = Arc::new(FileTowerStorage::new(ledger_path.clone()));
 
-    let admin_service_post_init = Arc::new(RwLock::new(None));
     // If geyser_plugin_config value is invalid, the validator will exit when the values are extracted below
     let (rpc_to_plugin_manager_sender, rpc_to_plugin_manager_receiver) =
         if matches.is_present("geyser_plugin_config") {
@@ -404,9 +403,11 @@ fn main() {
             validator_exit: genesis.validator_exit.clone(),
             authorized_voter_keypairs: genesis.authorized_voter_keypairs.clone(),
             staked_nodes_overrides: genesis.staked_nodes_overrides.clone(),
-            post_init: admin_service_post_init,
+            post_init: genesis.admin_rpc_service_post_init.clone(),
             tower_storage: tower_storage.clone(),
             rpc_to_plugin_manager_sender,
+            checkpoints: Some(genesis.checkpoints.clone()),
+            wen_restart_path: None,
         },
     );
     let dashboard = if output == Output::Dashboard {
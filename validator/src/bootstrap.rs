@@ -393,6 +393,7 @@ pub fn attempt_download_genesis_and_snapshot(
     maximum_snapshot_download_abort: u64,
     download_abort_count: &mut u64,
     snapshot_hash: Option<SnapshotHash>,
+    snapshot_mirrors: &[SocketAddr],
     identity_keypair: &Arc<Keypair>,
     vote_account: &Pubkey,
     authorized_voter_keypairs: Arc<RwLock<Vec<Arc<Keypair>>>>,
@@ -429,6 +430,7 @@ pub fn attempt_download_genesis_and_snapshot(
         download_abort_count,
         snapshot_hash,
         rpc_contact_info,
+        snapshot_mirrors,
     )?;
 
     if let Some(url) = bootstrap_config.check_vote_account.as_ref() {
@@ -648,6 +650,14 @@ pub fn rpc_bootstrap(
             &bootstrap_config,
         );
         let (rpc_contact_info, snapshot_hash, rpc_client) = vetted_rpc_nodes.pop().unwrap();
+        // The other vetted nodes with the same snapshot serve byte ranges of its archives too
+        let snapshot_mirrors: Vec<_> = vetted_rpc_nodes
+            .iter()
+            .filter(|(_, mirror_snapshot_hash, _)| {
+                snapshot_hash.is_some() && *mirror_snapshot_hash == snapshot_hash
+            })
+            .filter_map(|(mirror_contact_info, _, _)| mirror_contact_info.rpc().ok())
+            .collect();
         get_rpc_nodes_time += get_rpc_nodes_start.elapsed();
 
         let snapshot_download_start = Instant::now();
@@ -667,6 +677,7 @@ pub fn rpc_bootstrap(
             maximum_snapshot_download_abort,
             &mut download_abort_count,
             snapshot_hash,
+            &snapshot_mirrors,
             identity_keypair,
             vote_account,
             authorized_voter_keypairs.clone(),
@@ -1136,6 +1147,7 @@ fn download_snapshots(
     download_abort_count: &mut u64,
     snapshot_hash: Option<SnapshotHash>,
     rpc_contact_info: &ContactInfo,
+    snapshot_mirrors: &[SocketAddr],
 ) -> Result<(), String> {
     if snapshot_hash.is_none() {
         return Ok(());
@@ -1181,6 +1193,7 @@ fn download_snapshots(
             maximum_snapshot_download_abort,
             download_abort_count,
             rpc_contact_info,
+            snapshot_mirrors,
             full_snapshot_hash,
             SnapshotKind::FullSnapshot,
         )?;
@@ -1214,6 +1227,7 @@ fn download_snapshots(
                     maximum_snapshot_download_abort,
                     download_abort_count,
                     rpc_contact_info,
+                    snapshot_mirrors,
                     incremental_snapshot_hash,
                     SnapshotKind::IncrementalSnapshot(full_snapshot_hash.0),
                 )?;
@@ -1237,6 +1251,7 @@ fn download_snapshot(
     maximum_snapshot_download_abort: u64,
     download_abort_count: &mut u64,
     rpc_contact_info: &ContactInfo,
+    snapshot_mirrors: &[SocketAddr],
     desired_snapshot_hash: (Slot, Hash),
     snapshot_kind: SnapshotKind,
 ) -> Result<(), String> {
@@ -1247,16 +1262,20 @@ fn download_snapshot(
         .snapshot_config
         .maximum_incremental_snapshot_archives_to_retain;
 
+    let rpc_addr = rpc_contact_info.rpc().map_err(|err| format!("{err:?}"))?;
     *start_progress.write().unwrap() = ValidatorStartProgress::DownloadingSnapshot {
         slot: desired_snapshot_hash.0,
-        rpc_addr: rpc_contact_info.rpc().map_err(|err| format!("{err:?}"))?,
+        rpc_addr,
     };
     let desired_snapshot_hash = (
         desired_snapshot_hash.0,
         solana_runtime::snapshot_hash::SnapshotHash(desired_snapshot_hash.1),
     );
+    let rpc_addrs: Vec<_> = std::iter::once(rpc_addr)
+        .chain(snapshot_mirrors.iter().copied())
+        .collect();
     download_snapshot_archive(
-        &rpc_contact_info.rpc().map_err(|err| format!("{err:?}"))?,
+        &rpc_addrs,
         full_snapshot_archives_dir,
         incremental_snapshot_archives_dir,
         desired_snapshot_hash,
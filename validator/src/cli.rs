@@ -27,7 +27,14 @@ use {
     solana_ledger::use_snapshot_archives_at_startup,
     solana_net_utils::{MINIMUM_VALIDATOR_PORT_RANGE_WIDTH, VALIDATOR_PORT_RANGE},
     solana_rayon_threadlimit::get_thread_count,
-    solana_rpc::{rpc::MAX_REQUEST_BODY_SIZE, rpc_pubsub_service::PubSubConfig},
+    solana_rpc::{
+        historical_accounts::{
+            DEFAULT_HISTORICAL_ACCOUNTS_CACHE_LIMIT_MB,
+            DEFAULT_HISTORICAL_ACCOUNTS_MAX_PENDING_LOADS,
+        },
+        rpc::MAX_REQUEST_BODY_SIZE,
+        rpc_pubsub_service::PubSubConfig,
+    },
     solana_rpc_client_api::request::{DELINQUENT_VALIDATOR_SLOT_DISTANCE, MAX_MULTIPLE_ACCOUNTS},
     solana_runtime::{
         snapshot_bank_utils::{
@@ -274,6 +281,17 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                      JSON RPC method",
                 ),
         )
+        .arg(
+            Arg::with_name("rpc_idl_dir")
+                .long("rpc-idl-dir")
+                .value_name("DIR")
+                .takes_value(true)
+                .help(
+                    "Directory of Anchor-style IDL JSON files. The instructions and accounts of \
+                     their programs are decoded by the jsonParsed encoding, unless the program \
+                     has a built-in parser",
+                ),
+        )
         .arg(
             Arg::with_name("health_check_slot_distance")
                 .long("health-check-slot-distance")
@@ -861,6 +879,17 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                      push/pull from from validators outside this set. [default: all validators]",
                 ),
         )
+        .arg(
+            Arg::with_name("enable_gossip_quic")
+                .long("enable-gossip-quic")
+                .hidden(hidden_unless_forced())
+                .takes_value(false)
+                .help(
+                    "Advertise a gossip QUIC address and exchange gossip messages over QUIC \
+                     with the nodes which also advertise one. The gossip QUIC socket takes \
+                     one more port from --dynamic-port-range",
+                ),
+        )
         .arg(
             Arg::with_name("tpu_coalesce_ms")
                 .long("tpu-coalesce-ms")
@@ -950,6 +979,17 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                      present, otherwise use --bind-address]",
                 ),
         )
+        .arg(
+            Arg::with_name("metrics_bind_address")
+                .long("metrics-bind-address")
+                .value_name("HOST:PORT")
+                .takes_value(true)
+                .validator(solana_net_utils::is_host_port)
+                .help(
+                    "Serve the validator metrics in the Prometheus text format at \
+                     http://HOST:PORT/metrics",
+                ),
+        )
         .arg(
             Arg::with_name("rpc_threads")
                 .long("rpc-threads")
@@ -1005,6 +1045,16 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .default_value(&default_args.rpc_bigtable_max_message_size)
                 .help("Max encoding and decoding message size used in Bigtable Grpc client"),
         )
+        .arg(
+            Arg::with_name("rpc_local_ledger_storage_path")
+                .long("rpc-local-ledger-storage-path")
+                .value_name("DIR")
+                .takes_value(true)
+                .help(
+                    "Use a local database at this path instead of a BigTable instance for \
+                     --enable-rpc-bigtable-ledger-storage and --enable-bigtable-ledger-upload",
+                ),
+        )
         .arg(
             Arg::with_name("rpc_pubsub_worker_threads")
                 .long("rpc-pubsub-worker-threads")
@@ -1185,6 +1235,45 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .default_value(&default_args.rpc_max_request_body_size)
                 .help("The maximum request body size accepted by rpc service"),
         )
+        .arg(
+            Arg::with_name("rpc_historical_snapshot_archives_path")
+                .long("rpc-historical-snapshot-archives-path")
+                .value_name("DIR")
+                .takes_value(true)
+                .help(
+                    "Enable the 'getAccountInfoAtSlot' API, loading account state at \
+                     historical rooted slots from the full and incremental snapshot archives in \
+                     DIR and replaying the local ledger up to the requested slot. Disabled by \
+                     default",
+                ),
+        )
+        .arg(
+            Arg::with_name("rpc_historical_accounts_cache_limit_mb")
+                .long("rpc-historical-accounts-cache-limit-mb")
+                .value_name("MEGABYTES")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .requires("rpc_historical_snapshot_archives_path")
+                .default_value(&default_args.rpc_historical_accounts_cache_limit_mb)
+                .help(
+                    "Maximum total size of the account storages of the historical slots kept \
+                     loaded for 'getAccountInfoAtSlot'",
+                ),
+        )
+        .arg(
+            Arg::with_name("rpc_historical_accounts_max_pending_loads")
+                .long("rpc-historical-accounts-max-pending-loads")
+                .value_name("NUMBER")
+                .takes_value(true)
+                .validator(is_parsable::<usize>)
+                .requires("rpc_historical_snapshot_archives_path")
+                .default_value(&default_args.rpc_historical_accounts_max_pending_loads)
+                .help(
+                    "Maximum number of historical slots being loaded or waiting to be loaded for \
+                     'getAccountInfoAtSlot'. Slots are loaded one at a time, and requests \
+                     beyond this limit are rejected",
+                ),
+        )
         .arg(
             Arg::with_name("geyser_plugin_config")
                 .long("geyser-plugin-config")
@@ -1330,6 +1419,15 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .help("Squash multiple account storage files together using this method")
                 .hidden(hidden_unless_forced()),
         )
+        .arg(
+            Arg::with_name("accounts_db_ancient_storage_format")
+                .long("accounts-db-ancient-storage-format")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .possible_values(&["append-vec", "hot", "cold"])
+                .help("Create ancient account storage files in this format when packing")
+                .hidden(hidden_unless_forced()),
+        )
         .arg(
             Arg::with_name("accounts_db_access_storages_method")
                 .long("accounts-db-access-storages-method")
@@ -1600,6 +1698,21 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                     further debugging.",
                 ),
         )
+        .arg(
+            Arg::with_name("wen_restart_dry_run")
+                .long("wen-restart-dry-run")
+                .hidden(hidden_unless_forced())
+                .takes_value(false)
+                .requires("wen_restart")
+                .help(
+                    "Rehearse Wen Restart: run the last voted fork slots and heaviest fork \
+                    aggregation, then exit without generating a snapshot. The validator \
+                    still gossips its RestartLastVotedForkSlots and RestartHeaviestFork \
+                    messages, so every validator in the cluster should take part in the \
+                    rehearsal, and repairs missing blocks into the ledger. The tower is \
+                    left untouched, use a separate progress file for rehearsals.",
+                ),
+        )
         .args(&thread_args(&default_args.thread_args))
         .args(&get_deprecated_arguments())
         .after_help("The default subcommand is run")
@@ -1701,6 +1814,28 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                         .help("Output display mode"),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("wen-restart-status")
+                .about("Display the progress of Wen Restart")
+                .arg(
+                    Arg::with_name("path")
+                        .long("path")
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .help(
+                            "Decode the Wen Restart progress file directly instead of querying \
+                            the running validator",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .takes_value(true)
+                        .value_name("MODE")
+                        .possible_values(&["json", "json-compact"])
+                        .help("Output display mode"),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("repair-shred-from-peer")
                 .about("Request a repair from the specified validator")
@@ -1774,6 +1909,41 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                         ),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("tpu-quic-connections")
+                .about("Inspect and manage the validator's TPU QUIC connections")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .setting(AppSettings::InferSubcommands)
+                .subcommand(
+                    SubCommand::with_name("list")
+                        .about("Display the live TPU and TPU forwards QUIC connections")
+                        .arg(
+                            Arg::with_name("output")
+                                .long("output")
+                                .takes_value(true)
+                                .value_name("MODE")
+                                .possible_values(&["json", "json-compact"])
+                                .help("Output display mode"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("evict")
+                        .about("Close all the TPU QUIC connections of a peer")
+                        .setting(AppSettings::ArgRequiredElseHelp)
+                        .arg(
+                            Arg::with_name("pubkey")
+                                .long("pubkey")
+                                .validator(is_pubkey)
+                                .value_name("PUBKEY")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Identity of the peer to evict"),
+                        )
+                        .after_help(
+                            "Note: new connections of the evicted peer are refused for 5 minutes",
+                        ),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("init").about("Initialize the ledger directory then exit"),
         )
@@ -1919,6 +2089,22 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                      for a restart",
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("register-idl")
+                .about("Decode a program with its on-chain Anchor IDL in the jsonParsed encoding")
+                .arg(
+                    Arg::with_name("program_id")
+                        .index(1)
+                        .value_name("PROGRAM_ID")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_pubkey)
+                        .help("Program whose IDL account to register"),
+                )
+                .after_help(
+                    "Note: registered IDLs only apply to the currently running validator instance",
+                ),
+        )
         .subcommand(
             SubCommand::with_name("set-public-address")
                 .about("Specify addresses to advertise in gossip")
@@ -2202,6 +2388,8 @@ pub struct DefaultArgs {
     pub rpc_bigtable_app_profile_id: String,
     pub rpc_bigtable_max_message_size: String,
     pub rpc_max_request_body_size: String,
+    pub rpc_historical_accounts_cache_limit_mb: String,
+    pub rpc_historical_accounts_max_pending_loads: String,
     pub rpc_pubsub_worker_threads: String,
     pub rpc_pubsub_notification_threads: String,
 
@@ -2321,6 +2509,10 @@ impl DefaultArgs {
                 DEFAULT_MAX_CONNECTIONS_PER_IPADDR_PER_MINUTE.to_string(),
             num_quic_endpoints: DEFAULT_QUIC_ENDPOINTS.to_string(),
             rpc_max_request_body_size: MAX_REQUEST_BODY_SIZE.to_string(),
+            rpc_historical_accounts_cache_limit_mb: DEFAULT_HISTORICAL_ACCOUNTS_CACHE_LIMIT_MB
+                .to_string(),
+            rpc_historical_accounts_max_pending_loads:
+                DEFAULT_HISTORICAL_ACCOUNTS_MAX_PENDING_LOADS.to_string(),
             exit_min_idle_time: "10".to_string(),
             exit_max_delinquent_stake: "5".to_string(),
             wait_for_restart_window_min_idle_time: "10".to_string(),
@@ -16,9 +16,10 @@ use {
     crossbeam_channel::unbounded,
     log::*,
     rand::{seq::SliceRandom, thread_rng},
+    solana_account_decoder::parse_idl::register_idl_dir,
     solana_accounts_db::{
         accounts_db::{AccountShrinkThreshold, AccountsDb, AccountsDbConfig, CreateAncientStorage},
-        accounts_file::StorageAccess,
+        accounts_file::{AccountsFileProvider, StorageAccess},
         accounts_index::{
             AccountIndex, AccountSecondaryIndexes, AccountSecondaryIndexesIncludeExclude,
             AccountsIndexConfig, IndexLimitMb,
@@ -49,9 +50,11 @@ use {
         },
         use_snapshot_archives_at_startup::{self, UseSnapshotArchivesAtStartup},
     },
+    solana_net_utils::MINIMUM_VALIDATOR_PORT_RANGE_WIDTH,
     solana_perf::recycler::enable_recycler_warming,
     solana_poh::poh_service,
     solana_rpc::{
+        historical_accounts::HistoricalAccountsConfig,
         rpc::{JsonRpcConfig, RpcBigtableConfig},
         rpc_pubsub_service::PubSubConfig,
     },
@@ -73,6 +76,7 @@ use {
     solana_send_transaction_service::send_transaction_service,
     solana_streamer::socket::SocketAddrSpace,
     solana_tpu_client::tpu_client::DEFAULT_TPU_ENABLE_UDP,
+    solana_wen_restart::wen_restart_status::read_wen_restart_status,
     std::{
         collections::{HashSet, VecDeque},
         env,
@@ -645,6 +649,33 @@ pub fn main() {
             }
             return;
         }
+        ("wen-restart-status", Some(subcommand_matches)) => {
+            let output_mode = subcommand_matches.value_of("output");
+            let status = if let Some(path) = subcommand_matches.value_of("path") {
+                read_wen_restart_status(Path::new(path)).unwrap_or_else(|err| {
+                    eprintln!("Failed to read Wen Restart progress {path}: {err}");
+                    exit(1);
+                })
+            } else {
+                let admin_client = admin_rpc_service::connect(&ledger_path);
+                admin_rpc_service::runtime()
+                    .block_on(async move { admin_client.await?.wen_restart_status().await })
+                    .unwrap_or_else(|err| {
+                        eprintln!("Wen Restart status query failed: {err}");
+                        exit(1);
+                    })
+            };
+            if let Some(mode) = output_mode {
+                match mode {
+                    "json" => println!("{}", serde_json::to_string_pretty(&status).unwrap()),
+                    "json-compact" => print!("{}", serde_json::to_string(&status).unwrap()),
+                    _ => unreachable!(),
+                }
+            } else {
+                print!("{status}");
+            }
+            return;
+        }
         ("init", _) => Operation::Initialize,
         ("exit", Some(subcommand_matches)) => {
             let min_idle_time = value_t_or_exit!(subcommand_matches, "min_idle_time", usize);
@@ -868,6 +899,64 @@ pub fn main() {
                 _ => unreachable!(),
             }
         }
+        ("tpu-quic-connections", Some(tpu_quic_connections_subcommand_matches)) => {
+            match tpu_quic_connections_subcommand_matches.subcommand() {
+                ("list", Some(subcommand_matches)) => {
+                    let output_mode = subcommand_matches.value_of("output");
+                    let admin_client = admin_rpc_service::connect(&ledger_path);
+                    let connections = admin_rpc_service::runtime()
+                        .block_on(async move { admin_client.await?.tpu_quic_connections().await })
+                        .unwrap_or_else(|err| {
+                            eprintln!("TPU QUIC connections query failed: {err}");
+                            exit(1);
+                        });
+                    if let Some(mode) = output_mode {
+                        match mode {
+                            "json" => {
+                                println!("{}", serde_json::to_string_pretty(&connections).unwrap())
+                            }
+                            "json-compact" => {
+                                print!("{}", serde_json::to_string(&connections).unwrap())
+                            }
+                            _ => unreachable!(),
+                        }
+                    } else {
+                        print!("{connections}");
+                    }
+                    return;
+                }
+                ("evict", Some(subcommand_matches)) => {
+                    let pubkey = value_t_or_exit!(subcommand_matches, "pubkey", Pubkey);
+                    let admin_client = admin_rpc_service::connect(&ledger_path);
+                    let num_evicted = admin_rpc_service::runtime()
+                        .block_on(async move {
+                            admin_client
+                                .await?
+                                .evict_tpu_quic_peer(pubkey.to_string())
+                                .await
+                        })
+                        .unwrap_or_else(|err| {
+                            eprintln!("evictTpuQuicPeer request failed: {err}");
+                            exit(1);
+                        });
+                    println!("Evicted {num_evicted} connections of {pubkey}");
+                    return;
+                }
+                _ => unreachable!(),
+            }
+        }
+        ("register-idl", Some(subcommand_matches)) => {
+            let program_id = value_t_or_exit!(subcommand_matches, "program_id", String);
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            let name = admin_rpc_service::runtime()
+                .block_on(async move { admin_client.await?.register_idl(program_id).await })
+                .unwrap_or_else(|err| {
+                    eprintln!("registerIdl request failed: {err}");
+                    exit(1);
+                });
+            println!("Registered IDL of {name}");
+            return;
+        }
         ("set-public-address", Some(subcommand_matches)) => {
             let parse_arg_addr = |arg_name: &str, arg_long: &str| -> Option<SocketAddr> {
                 subcommand_matches.value_of(arg_name).map(|host_port| {
@@ -1262,6 +1351,17 @@ pub fn main() {
             }
         })
         .unwrap_or_default();
+    let ancient_accounts_file_provider = matches
+        .value_of("accounts_db_ancient_storage_format")
+        .map(|format| match format {
+            "append-vec" => AccountsFileProvider::AppendVec,
+            "hot" => AccountsFileProvider::HotStorage,
+            "cold" => AccountsFileProvider::ColdStorage,
+            _ => {
+                // clap will enforce one of the above values is given
+                unreachable!("invalid value given to accounts-db-ancient-storage-format")
+            }
+        });
     let storage_access = matches
         .value_of("accounts_db_access_storages_method")
         .map(|method| match method {
@@ -1286,6 +1386,7 @@ pub fn main() {
         ancient_append_vec_offset: value_t!(matches, "accounts_db_ancient_append_vecs", i64).ok(),
         exhaustively_verify_refcounts: matches.is_present("accounts_db_verify_refcounts"),
         create_ancient_storage,
+        ancient_accounts_file_provider,
         test_partitioned_epoch_rewards,
         test_skip_rewrites_but_include_in_bank_hash: matches
             .is_present("accounts_db_test_skip_rewrites"),
@@ -1295,6 +1396,14 @@ pub fn main() {
 
     let accounts_db_config = Some(accounts_db_config);
 
+    if let Some(idl_dir) = matches.value_of("rpc_idl_dir") {
+        let program_ids = register_idl_dir(Path::new(idl_dir)).unwrap_or_else(|err| {
+            eprintln!("Failed to load IDLs from {idl_dir}: {err}");
+            exit(1);
+        });
+        info!("Registered the IDLs of {} programs", program_ids.len());
+    }
+
     let on_start_geyser_plugin_config_files = if matches.is_present("geyser_plugin_config") {
         Some(
             values_t_or_exit!(matches, "geyser_plugin_config", String)
@@ -1322,8 +1431,17 @@ pub fn main() {
                 .ok()
                 .map(Duration::from_secs),
             max_message_size: value_t_or_exit!(matches, "rpc_bigtable_max_message_size", usize),
+            local_ledger_storage_path: value_t!(matches, "rpc_local_ledger_storage_path", PathBuf)
+                .ok(),
         })
     } else {
+        if matches.is_present("rpc_local_ledger_storage_path") {
+            eprintln!(
+                "--rpc-local-ledger-storage-path requires --enable-rpc-bigtable-ledger-storage or \
+                 --enable-bigtable-ledger-upload"
+            );
+            exit(1);
+        }
         None
     };
 
@@ -1373,6 +1491,23 @@ pub fn main() {
         };
 
     let full_api = matches.is_present("full_rpc_api");
+    let historical_accounts_config = matches
+        .value_of("rpc_historical_snapshot_archives_path")
+        .map(|snapshot_archives_dir| HistoricalAccountsConfig {
+            snapshot_archives_dir: PathBuf::from(snapshot_archives_dir),
+            working_dir: ledger_path.join("historical-accounts"),
+            cache_max_bytes: value_t_or_exit!(
+                matches,
+                "rpc_historical_accounts_cache_limit_mb",
+                u64
+            )
+            .saturating_mul(MB as u64),
+            max_pending_loads: value_t_or_exit!(
+                matches,
+                "rpc_historical_accounts_max_pending_loads",
+                usize
+            ),
+        });
 
     let cli::thread_args::NumThreadConfig {
         ip_echo_server_threads,
@@ -1422,6 +1557,7 @@ pub fn main() {
                 "rpc_max_request_body_size",
                 usize
             )),
+            historical_accounts_config,
         },
         on_start_geyser_plugin_config_files,
         rpc_addrs: value_t!(matches, "rpc_port", u16).ok().map(|rpc_port| {
@@ -1463,6 +1599,7 @@ pub fn main() {
         repair_validators,
         repair_whitelist,
         gossip_validators,
+        enable_gossip_quic: matches.is_present("enable_gossip_quic"),
         wal_recovery_mode,
         run_verification: !(matches.is_present("skip_poh_verify")
             || matches.is_present("skip_startup_ledger_verification")),
@@ -1524,6 +1661,11 @@ pub fn main() {
         replay_transactions_threads,
         delay_leader_block_for_pending_fork: matches
             .is_present("delay_leader_block_for_pending_fork"),
+        // `--wen-restart` alone is still parsed but ignored, only a dry run enters Wen Restart
+        wen_restart_proto_path: matches
+            .is_present("wen_restart_dry_run")
+            .then(|| value_t_or_exit!(matches, "wen_restart", PathBuf)),
+        wen_restart_dry_run: matches.is_present("wen_restart_dry_run"),
         ..ValidatorConfig::default()
     };
 
@@ -1538,6 +1680,17 @@ pub fn main() {
     let dynamic_port_range =
         solana_net_utils::parse_port_range(matches.value_of("dynamic_port_range").unwrap())
             .expect("invalid dynamic_port_range");
+    // The gossip QUIC socket takes one more port from the dynamic port range.
+    if matches.is_present("enable_gossip_quic")
+        && dynamic_port_range.1 - dynamic_port_range.0 < MINIMUM_VALIDATOR_PORT_RANGE_WIDTH + 1
+    {
+        eprintln!(
+            "Port range is too small for --enable-gossip-quic.  Try --dynamic-port-range {}-{}",
+            dynamic_port_range.0,
+            dynamic_port_range.0 + MINIMUM_VALIDATOR_PORT_RANGE_WIDTH + 1
+        );
+        exit(1);
+    }
 
     let account_paths: Vec<PathBuf> =
         if let Ok(account_paths) = values_t!(matches, "account_paths", String) {
@@ -1876,6 +2029,8 @@ pub fn main() {
             tower_storage: validator_config.tower_storage.clone(),
             staked_nodes_overrides,
             rpc_to_plugin_manager_sender,
+            checkpoints: None,
+            wen_restart_path: value_t!(matches, "wen_restart", PathBuf).ok(),
         },
     );
 
@@ -1956,6 +2111,7 @@ pub fn main() {
         public_tpu_forwards_addr,
         num_tvu_sockets: tvu_receive_threads,
         num_quic_endpoints,
+        enable_gossip_quic: validator_config.enable_gossip_quic,
     };
 
     let cluster_entrypoints = entrypoint_addrs
@@ -2003,6 +2159,14 @@ pub fn main() {
 
     solana_metrics::set_host_id(identity_keypair.pubkey().to_string());
     solana_metrics::set_panic_hook("validator", Some(String::from(solana_version)));
+    if let Some(metrics_bind_address) = matches.value_of("metrics_bind_address") {
+        let metrics_bind_address = solana_net_utils::parse_host_port(metrics_bind_address)
+            .expect("invalid metrics_bind_address");
+        if let Err(err) = solana_metrics::prometheus::start_exporter(metrics_bind_address) {
+            eprintln!("Failed to start the metrics exporter on {metrics_bind_address}: {err}");
+            exit(1);
+        }
+    }
     solana_entry::entry::init_poh();
     snapshot_utils::remove_tmp_snapshot_archives(&full_snapshot_archives_dir);
     snapshot_utils::remove_tmp_snapshot_archives(&incremental_snapshot_archives_dir);
@@ -2,6 +2,10 @@
 #![allow(clippy::arithmetic_side_effects)]
 
 use {
+    crate::{
+        status::{SharedWatchtowerStatus, ValidatorHealth},
+        validators_config::{check_unique_identities, load_validators_config, MonitoredValidator},
+    },
     clap::{crate_description, crate_name, value_t, value_t_or_exit, App, Arg},
     log::*,
     solana_clap_utils::{
@@ -13,32 +17,43 @@ use {
     solana_metrics::{datapoint_error, datapoint_info},
     solana_notifier::{NotificationType, Notifier},
     solana_rpc_client::rpc_client::RpcClient,
-    solana_rpc_client_api::{client_error, response::RpcVoteAccountStatus},
+    solana_rpc_client_api::{
+        client_error,
+        response::{RpcVoteAccountInfo, RpcVoteAccountStatus},
+    },
     solana_sdk::{
+        clock::{Epoch, Slot},
         hash::Hash,
         native_token::{sol_to_lamports, Sol},
         pubkey::Pubkey,
     },
     std::{
-        collections::HashMap,
+        collections::{HashMap, HashSet},
         error,
+        net::SocketAddr,
+        path::Path,
+        process::exit,
         thread::sleep,
-        time::{Duration, Instant},
+        time::{Duration, Instant, SystemTime, UNIX_EPOCH},
     },
 };
 
+mod status;
+mod validators_config;
+
 struct Config {
     address_labels: HashMap<String, String>,
     ignore_http_bad_gateway: bool,
     interval: Duration,
     json_rpc_url: String,
     rpc_timeout: Duration,
-    minimum_validator_identity_balance: u64,
     monitor_active_stake: bool,
     active_stake_alert_threshold: u8,
     unhealthy_threshold: usize,
-    validator_identity_pubkeys: Vec<Pubkey>,
+    validators: Vec<MonitoredValidator>,
     name_suffix: String,
+    notifier_config: Option<String>,
+    status_bind_address: Option<SocketAddr>,
 }
 
 fn get_config() -> Config {
@@ -65,7 +80,10 @@ fn get_config() -> Config {
         and a sending number owned by that account,
         define environment variable before running `agave-watchtower`:
 
-        export TWILIO_CONFIG='ACCOUNT=<account>,TOKEN=<securityToken>,TO=<receivingNumber>,FROM=<sendingNumber>'")
+        export TWILIO_CONFIG='ACCOUNT=<account>,TOKEN=<securityToken>,TO=<receivingNumber>,FROM=<sendingNumber>'
+
+        Alternatively, --notifier-config loads the notification channels, including generic
+        webhooks with a templated JSON body, and the routing of alerts to them from a file.")
         .arg({
             let arg = Arg::with_name("config_file")
                 .short("C")
@@ -121,6 +139,14 @@ fn get_config() -> Config {
                 .multiple(true)
                 .help("Validator identities to monitor for delinquency")
         )
+        .arg(
+            Arg::with_name("validators_config")
+                .long("validators-config")
+                .value_name("PATH")
+                .takes_value(true)
+                .help("YAML file of validators to monitor, each with its own identity balance, \
+                    skip rate, root lag, vote lag and delinquency thresholds")
+        )
         .arg(
             Arg::with_name("minimum_validator_identity_balance")
                 .long("minimum-validator-identity-balance")
@@ -168,6 +194,23 @@ fn get_config() -> Config {
                 .default_value("")
                 .help("Add this string into all notification messages after \"agave-watchtower\"")
         )
+        .arg(
+            Arg::with_name("notifier_config")
+                .long("notifier-config")
+                .value_name("PATH")
+                .takes_value(true)
+                .help("YAML file of the notification channels and of the routing of alerts to \
+                    them, instead of the notifier environment variables")
+        )
+        .arg(
+            Arg::with_name("status_bind_address")
+                .long("status-bind-address")
+                .value_name("HOST:PORT")
+                .takes_value(true)
+                .validator(solana_net_utils::is_host_port)
+                .help("Serve the current health of the cluster and of the monitored validators \
+                    as JSON at http://HOST:PORT/status")
+        )
         .get_matches();
 
     let config = if let Some(config_file) = matches.value_of("config_file") {
@@ -187,10 +230,27 @@ fn get_config() -> Config {
         value_t!(matches, "json_rpc_url", String).unwrap_or_else(|_| config.json_rpc_url.clone());
     let rpc_timeout = value_t_or_exit!(matches, "rpc_timeout", u64);
     let rpc_timeout = Duration::from_secs(rpc_timeout);
-    let validator_identity_pubkeys: Vec<_> = pubkeys_of(&matches, "validator_identities")
+    let mut validators: Vec<_> = pubkeys_of(&matches, "validator_identities")
         .unwrap_or_default()
         .into_iter()
+        .map(|identity| MonitoredValidator::new(identity, minimum_validator_identity_balance))
         .collect();
+    if let Some(validators_config) = matches.value_of("validators_config") {
+        match load_validators_config(
+            Path::new(validators_config),
+            minimum_validator_identity_balance,
+        ) {
+            Ok(configured_validators) => validators.extend(configured_validators),
+            Err(err) => {
+                eprintln!("{err}");
+                exit(1);
+            }
+        }
+    }
+    if let Err(err) = check_unique_identities(&validators) {
+        eprintln!("{err}");
+        exit(1);
+    }
 
     let monitor_active_stake = matches.is_present("monitor_active_stake");
     let active_stake_alert_threshold =
@@ -198,6 +258,10 @@ fn get_config() -> Config {
     let ignore_http_bad_gateway = matches.is_present("ignore_http_bad_gateway");
 
     let name_suffix = value_t_or_exit!(matches, "name_suffix", String);
+    let notifier_config = matches.value_of("notifier_config").map(str::to_string);
+    let status_bind_address = matches.value_of("status_bind_address").map(|address| {
+        solana_net_utils::parse_host_port(address).expect("invalid status_bind_address")
+    });
 
     let config = Config {
         address_labels: config.address_labels,
@@ -205,44 +269,303 @@ fn get_config() -> Config {
         interval,
         json_rpc_url,
         rpc_timeout,
-        minimum_validator_identity_balance,
         monitor_active_stake,
         active_stake_alert_threshold,
         unhealthy_threshold,
-        validator_identity_pubkeys,
+        validators,
         name_suffix,
+        notifier_config,
+        status_bind_address,
     };
 
     info!("RPC URL: {}", config.json_rpc_url);
     info!(
         "Monitored validators: {:?}",
-        config.validator_identity_pubkeys
+        config
+            .validators
+            .iter()
+            .map(|validator| validator.identity)
+            .collect::<Vec<_>>()
     );
     config
 }
 
+struct ClusterInfo {
+    transaction_count: u64,
+    recent_blockhash: Hash,
+    vote_accounts: RpcVoteAccountStatus,
+    validator_balances: HashMap<Pubkey, u64>,
+    /// Skip rates of the validators with a maximum skip rate
+    validator_skip_rates: HashMap<Pubkey, f64>,
+    highest_root_slot: Slot,
+    highest_last_vote: Slot,
+}
+
+/// Tracks which leader slots of the validators with a maximum skip rate produced a block.
+///
+/// The leader schedule is fetched once per epoch, and only the blocks of the slots since the
+/// previous update are fetched on every check.
+#[derive(Default)]
+struct SkipRateTracker {
+    epoch: Option<Epoch>,
+    /// Leader slots of each validator in the current epoch, in ascending order
+    leader_slots: HashMap<Pubkey, Vec<Slot>>,
+    /// Leader slots of the validators with a block
+    produced_slots: HashSet<Slot>,
+    /// First slot whose block hasn't been fetched yet
+    next_slot: Option<Slot>,
+}
+
+impl SkipRateTracker {
+    /// Returns the percentage of the last `skip_rate_leader_slots` leader slots of each validator
+    /// in the current epoch without a block, except for the validators which have not been
+    /// leader yet
+    fn update(
+        &mut self,
+        rpc_client: &RpcClient,
+        validators: &[MonitoredValidator],
+    ) -> client_error::Result<HashMap<Pubkey, f64>> {
+        let epoch_info = rpc_client.get_epoch_info()?;
+        if self.epoch != Some(epoch_info.epoch) {
+            let first_slot_in_epoch = epoch_info.absolute_slot - epoch_info.slot_index;
+            let leader_schedule = rpc_client
+                .get_leader_schedule(Some(epoch_info.absolute_slot))?
+                .unwrap_or_default();
+            let leader_slots = validators
+                .iter()
+                .map(|validator| {
+                    let mut leader_slots = leader_schedule
+                        .get(&validator.identity.to_string())
+                        .map(|slot_indexes| {
+                            slot_indexes
+                                .iter()
+                                .map(|slot_index| first_slot_in_epoch + *slot_index as u64)
+                                .collect::<Vec<_>>()
+                        })
+                        .unwrap_or_default();
+                    leader_slots.sort_unstable();
+                    (validator.identity, leader_slots)
+                })
+                .collect();
+            *self = Self {
+                epoch: Some(epoch_info.epoch),
+                leader_slots,
+                ..Self::default()
+            };
+        }
+
+        let recent_leader_slots = validators
+            .iter()
+            .filter_map(|validator| {
+                let leader_slots = self.leader_slots.get(&validator.identity)?;
+                let past_leader_slots = &leader_slots
+                    [..leader_slots.partition_point(|slot| *slot < epoch_info.absolute_slot)];
+                let recent_leader_slots = &past_leader_slots[past_leader_slots
+                    .len()
+                    .saturating_sub(validator.skip_rate_leader_slots)..];
+                (!recent_leader_slots.is_empty())
+                    .then_some((validator.identity, recent_leader_slots))
+            })
+            .collect::<Vec<_>>();
+
+        // The windows of recent leader slots only move forward, so the blocks before
+        // `next_slot` never need to be fetched again
+        let start_slot = self.next_slot.or_else(|| {
+            recent_leader_slots
+                .iter()
+                .filter_map(|(_identity, leader_slots)| leader_slots.first().copied())
+                .min()
+        });
+        if let Some(start_slot) = start_slot {
+            if start_slot < epoch_info.absolute_slot {
+                let blocks =
+                    rpc_client.get_blocks(start_slot, Some(epoch_info.absolute_slot - 1))?;
+                self.produced_slots
+                    .extend(blocks.into_iter().filter(|slot| {
+                        self.leader_slots
+                            .values()
+                            .any(|leader_slots| leader_slots.binary_search(slot).is_ok())
+                    }));
+                self.next_slot = Some(epoch_info.absolute_slot);
+            }
+        }
+
+        Ok(recent_leader_slots
+            .into_iter()
+            .map(|(identity, leader_slots)| {
+                let skipped_slots = leader_slots
+                    .iter()
+                    .filter(|slot| !self.produced_slots.contains(slot))
+                    .count();
+                let skip_rate = skipped_slots as f64 * 100. / leader_slots.len() as f64;
+                (identity, skip_rate)
+            })
+            .collect())
+    }
+}
+
 fn get_cluster_info(
     config: &Config,
     rpc_client: &RpcClient,
-) -> client_error::Result<(u64, Hash, RpcVoteAccountStatus, HashMap<Pubkey, u64>)> {
+    skip_rate_tracker: &mut SkipRateTracker,
+) -> client_error::Result<ClusterInfo> {
     let transaction_count = rpc_client.get_transaction_count()?;
     let recent_blockhash = rpc_client.get_latest_blockhash()?;
     let vote_accounts = rpc_client.get_vote_accounts()?;
 
     let mut validator_balances = HashMap::new();
-    for validator_identity in &config.validator_identity_pubkeys {
+    for validator in &config.validators {
         validator_balances.insert(
-            *validator_identity,
-            rpc_client.get_balance(validator_identity)?,
+            validator.identity,
+            rpc_client.get_balance(&validator.identity)?,
         );
     }
 
-    Ok((
+    let skip_rate_validators = config
+        .validators
+        .iter()
+        .filter(|validator| validator.max_skip_rate.is_some())
+        .cloned()
+        .collect::<Vec<_>>();
+    let validator_skip_rates = if skip_rate_validators.is_empty() {
+        HashMap::new()
+    } else {
+        skip_rate_tracker.update(rpc_client, &skip_rate_validators)?
+    };
+
+    let all_vote_accounts = || {
+        vote_accounts
+            .current
+            .iter()
+            .chain(&vote_accounts.delinquent)
+    };
+    let highest_root_slot = all_vote_accounts()
+        .map(|vote_account| vote_account.root_slot)
+        .max()
+        .unwrap_or_default();
+    let highest_last_vote = all_vote_accounts()
+        .map(|vote_account| vote_account.last_vote)
+        .max()
+        .unwrap_or_default();
+
+    Ok(ClusterInfo {
         transaction_count,
         recent_blockhash,
         vote_accounts,
         validator_balances,
-    ))
+        validator_skip_rates,
+        highest_root_slot,
+        highest_last_vote,
+    })
+}
+
+/// Checks `validator` against its thresholds. `delinquent_checks` counts the consecutive checks
+/// the validator has been delinquent in.
+///
+/// Returns the health of the validator along with its failures, by test name.
+fn check_validator(
+    validator: &MonitoredValidator,
+    cluster_info: &ClusterInfo,
+    address_labels: &HashMap<String, String>,
+    delinquent_checks: &mut usize,
+) -> (ValidatorHealth, Vec<(&'static str, String)>) {
+    let identity = validator.identity.to_string();
+    let formatted_identity = format_labeled_address(&identity, address_labels);
+    let is_validator_vote_account =
+        |vote_account: &&RpcVoteAccountInfo| match &validator.vote_account {
+            Some(vote_pubkey) => vote_account.vote_pubkey == vote_pubkey.to_string(),
+            None => vote_account.node_pubkey == identity,
+        };
+    let vote_accounts = &cluster_info.vote_accounts;
+    let current_vote_account = vote_accounts.current.iter().find(is_validator_vote_account);
+    let delinquent_vote_account = vote_accounts
+        .delinquent
+        .iter()
+        .find(is_validator_vote_account);
+
+    let mut failures = vec![];
+    if delinquent_vote_account.is_some() {
+        *delinquent_checks += 1;
+        if *delinquent_checks > validator.delinquency_tolerance {
+            failures.push(("delinquent", format!("{formatted_identity} delinquent")));
+        }
+    } else {
+        *delinquent_checks = 0;
+        if current_vote_account.is_none() {
+            failures.push(("delinquent", format!("{formatted_identity} missing")));
+        }
+    }
+
+    let vote_account = current_vote_account.or(delinquent_vote_account);
+    let root_lag = vote_account.map(|vote_account| {
+        cluster_info
+            .highest_root_slot
+            .saturating_sub(vote_account.root_slot)
+    });
+    if let Some((root_lag, max_root_lag)) = root_lag.zip(validator.max_root_lag) {
+        if root_lag > max_root_lag {
+            failures.push((
+                "root-lag",
+                format!("{formatted_identity} root is {root_lag} slots behind"),
+            ));
+        }
+    }
+    let vote_lag = vote_account.map(|vote_account| {
+        cluster_info
+            .highest_last_vote
+            .saturating_sub(vote_account.last_vote)
+    });
+    if let Some((vote_lag, max_vote_lag)) = vote_lag.zip(validator.max_vote_lag) {
+        if vote_lag > max_vote_lag {
+            failures.push((
+                "vote-lag",
+                format!("{formatted_identity} last vote is {vote_lag} slots behind"),
+            ));
+        }
+    }
+
+    let balance = cluster_info
+        .validator_balances
+        .get(&validator.identity)
+        .copied();
+    if let Some(balance) = balance {
+        if balance < validator.minimum_balance {
+            failures.push((
+                "balance",
+                format!("{} has {}", formatted_identity, Sol(balance)),
+            ));
+        }
+    }
+
+    let skip_rate = cluster_info
+        .validator_skip_rates
+        .get(&validator.identity)
+        .copied();
+    if let Some((skip_rate, max_skip_rate)) = skip_rate.zip(validator.max_skip_rate) {
+        if skip_rate > max_skip_rate {
+            failures.push((
+                "skip-rate",
+                format!("{formatted_identity} skip rate is {skip_rate:.2}%"),
+            ));
+        }
+    }
+
+    let health = ValidatorHealth {
+        label: address_labels.get(&identity).cloned(),
+        identity,
+        vote_account: vote_account.map(|vote_account| vote_account.vote_pubkey.clone()),
+        healthy: failures.is_empty(),
+        delinquent: delinquent_vote_account.is_some(),
+        balance,
+        skip_rate,
+        root_lag,
+        vote_lag,
+        failures: failures
+            .iter()
+            .map(|(_, message)| message.clone())
+            .collect(),
+    };
+    (health, failures)
 }
 
 fn main() -> Result<(), Box<dyn error::Error>> {
@@ -252,17 +575,53 @@ fn main() -> Result<(), Box<dyn error::Error>> {
     let config = get_config();
 
     let rpc_client = RpcClient::new_with_timeout(config.json_rpc_url.clone(), config.rpc_timeout);
-    let notifier = Notifier::default();
+    let notifier = match &config.notifier_config {
+        Some(notifier_config) => {
+            Notifier::from_config_file(notifier_config).unwrap_or_else(|err| {
+                eprintln!("{err}");
+                exit(1);
+            })
+        }
+        None => Notifier::default(),
+    };
+    notifier.send(
+        &format!(
+            "agave-watchtower{}: Monitoring {} with {} validators",
+            config.name_suffix,
+            config.json_rpc_url,
+            config.validators.len()
+        ),
+        &NotificationType::Info,
+    );
+
     let mut last_transaction_count = 0;
     let mut last_recent_blockhash = Hash::default();
     let mut last_notification_msg = "".into();
     let mut num_consecutive_failures = 0;
     let mut last_success = Instant::now();
     let mut incident = Hash::new_unique();
+    let mut delinquent_checks = vec![0; config.validators.len()];
+    let mut skip_rate_tracker = SkipRateTracker::default();
+
+    let status = SharedWatchtowerStatus::default();
+    if let Some(status_bind_address) = config.status_bind_address {
+        if let Err(err) = status::start_status_server(status_bind_address, status.clone()) {
+            eprintln!("Failed to start the status server on {status_bind_address}: {err}");
+            exit(1);
+        }
+    }
 
     loop {
-        let failure = match get_cluster_info(&config, &rpc_client) {
-            Ok((transaction_count, recent_blockhash, vote_accounts, validator_balances)) => {
+        let mut cluster_failures = vec![];
+        let mut validators_health = None;
+        let failure = match get_cluster_info(&config, &rpc_client, &mut skip_rate_tracker) {
+            Ok(cluster_info) => {
+                let ClusterInfo {
+                    transaction_count,
+                    recent_blockhash,
+                    ref vote_accounts,
+                    ..
+                } = cluster_info;
                 info!("Current transaction count: {}", transaction_count);
                 info!("Recent blockhash: {}", recent_blockhash);
                 info!("Current validator count: {}", vote_accounts.current.len());
@@ -323,35 +682,32 @@ fn main() -> Result<(), Box<dyn error::Error>> {
                     ));
                 }
 
+                cluster_failures = failures
+                    .iter()
+                    .map(|(test_name, message)| format!("{test_name}: {message}"))
+                    .collect();
+
                 let mut validator_errors = vec![];
-                for validator_identity in config.validator_identity_pubkeys.iter() {
-                    let formatted_validator_identity = format_labeled_address(
-                        &validator_identity.to_string(),
+                let mut health = vec![];
+                for (validator, delinquent_checks) in
+                    config.validators.iter().zip(delinquent_checks.iter_mut())
+                {
+                    let (validator_health, validator_failures) = check_validator(
+                        validator,
+                        &cluster_info,
                         &config.address_labels,
+                        delinquent_checks,
                     );
-                    if vote_accounts
-                        .delinquent
-                        .iter()
-                        .any(|vai| vai.node_pubkey == *validator_identity.to_string())
-                    {
-                        validator_errors.push(format!("{formatted_validator_identity} delinquent"));
-                    } else if !vote_accounts
-                        .current
-                        .iter()
-                        .any(|vai| vai.node_pubkey == *validator_identity.to_string())
-                    {
-                        validator_errors.push(format!("{formatted_validator_identity} missing"));
-                    }
-
-                    if let Some(balance) = validator_balances.get(validator_identity) {
-                        if *balance < config.minimum_validator_identity_balance {
-                            failures.push((
-                                "balance",
-                                format!("{} has {}", formatted_validator_identity, Sol(*balance)),
-                            ));
+                    for (test_name, message) in validator_failures {
+                        if test_name == "delinquent" {
+                            validator_errors.push(message);
+                        } else {
+                            failures.push((test_name, message));
                         }
                     }
+                    health.push(validator_health);
                 }
+                validators_health = Some(health);
 
                 if !validator_errors.is_empty() {
                     failures.push(("delinquent", validator_errors.join(",")));
@@ -363,6 +719,7 @@ fn main() -> Result<(), Box<dyn error::Error>> {
                 failures.into_iter().next() // Only report the first failure if any
             }
             Err(err) => {
+                cluster_failures.push(format!("rpc-error: {err}"));
                 let mut failure = Some(("rpc-error", err.to_string()));
 
                 if let client_error::ErrorKind::Reqwest(reqwest_err) = err.kind() {
@@ -379,6 +736,20 @@ fn main() -> Result<(), Box<dyn error::Error>> {
             }
         };
 
+        {
+            let mut status = status.write().unwrap();
+            status.last_check = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .ok()
+                .map(|duration| duration.as_secs());
+            status.healthy = failure.is_none();
+            status.cluster_failures = cluster_failures;
+            // The previous health of the validators is kept when the cluster could not be queried
+            if let Some(validators_health) = validators_health {
+                status.validators = validators_health;
+            }
+        }
+
         if let Some((failure_test_name, failure_error_message)) = &failure {
             let notification_msg = format!(
                 "agave-watchtower{}: Error: {}: {}",
@@ -427,3 +798,156 @@ fn main() -> Result<(), Box<dyn error::Error>> {
         sleep(config.interval);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vote_account_info(
+        identity: &Pubkey,
+        root_slot: Slot,
+        last_vote: Slot,
+    ) -> RpcVoteAccountInfo {
+        RpcVoteAccountInfo {
+            vote_pubkey: Pubkey::new_unique().to_string(),
+            node_pubkey: identity.to_string(),
+            activated_stake: 42,
+            commission: 0,
+            epoch_vote_account: true,
+            epoch_credits: vec![],
+            last_vote,
+            root_slot,
+        }
+    }
+
+    fn cluster_info(
+        current: Vec<RpcVoteAccountInfo>,
+        delinquent: Vec<RpcVoteAccountInfo>,
+        validator_balances: HashMap<Pubkey, u64>,
+        validator_skip_rates: HashMap<Pubkey, f64>,
+    ) -> ClusterInfo {
+        ClusterInfo {
+            transaction_count: 0,
+            recent_blockhash: Hash::default(),
+            highest_root_slot: 100,
+            highest_last_vote: 132,
+            vote_accounts: RpcVoteAccountStatus {
+                current,
+                delinquent,
+            },
+            validator_balances,
+            validator_skip_rates,
+        }
+    }
+
+    fn failed_tests(failures: &[(&'static str, String)]) -> Vec<&'static str> {
+        failures.iter().map(|(test_name, _)| *test_name).collect()
+    }
+
+    #[test]
+    fn test_check_validator() {
+        let identity = Pubkey::new_unique();
+        let validator = MonitoredValidator {
+            max_skip_rate: Some(25.0),
+            max_root_lag: Some(10),
+            max_vote_lag: Some(4),
+            delinquency_tolerance: 1,
+            ..MonitoredValidator::new(identity, 1_000)
+        };
+        let address_labels = HashMap::new();
+        let mut delinquent_checks = 0;
+
+        // Within every threshold
+        let healthy_cluster_info = cluster_info(
+            vec![vote_account_info(&identity, 95, 130)],
+            vec![],
+            HashMap::from([(identity, 1_000)]),
+            HashMap::from([(identity, 25.0)]),
+        );
+        let (health, failures) = check_validator(
+            &validator,
+            &healthy_cluster_info,
+            &address_labels,
+            &mut delinquent_checks,
+        );
+        assert!(failures.is_empty());
+        assert!(health.healthy);
+        assert!(!health.delinquent);
+        assert_eq!(health.root_lag, Some(5));
+        assert_eq!(health.vote_lag, Some(2));
+        assert_eq!(health.balance, Some(1_000));
+        assert_eq!(health.skip_rate, Some(25.0));
+
+        // Beyond every threshold
+        let (health, failures) = check_validator(
+            &validator,
+            &cluster_info(
+                vec![vote_account_info(&identity, 89, 127)],
+                vec![],
+                HashMap::from([(identity, 999)]),
+                HashMap::from([(identity, 25.5)]),
+            ),
+            &address_labels,
+            &mut delinquent_checks,
+        );
+        assert_eq!(
+            failed_tests(&failures),
+            vec!["root-lag", "vote-lag", "balance", "skip-rate"]
+        );
+        assert!(!health.healthy);
+        assert_eq!(health.failures.len(), 4);
+
+        // Delinquency is only a failure once it lasts longer than the tolerance
+        let delinquent_cluster_info = cluster_info(
+            vec![],
+            vec![vote_account_info(&identity, 95, 130)],
+            HashMap::from([(identity, 1_000)]),
+            HashMap::new(),
+        );
+        let (health, failures) = check_validator(
+            &validator,
+            &delinquent_cluster_info,
+            &address_labels,
+            &mut delinquent_checks,
+        );
+        assert!(failures.is_empty());
+        assert!(health.healthy);
+        assert!(health.delinquent);
+        let (health, failures) = check_validator(
+            &validator,
+            &delinquent_cluster_info,
+            &address_labels,
+            &mut delinquent_checks,
+        );
+        assert_eq!(failed_tests(&failures), vec!["delinquent"]);
+        assert!(!health.healthy);
+        assert_eq!(delinquent_checks, 2);
+
+        // Recovering resets the delinquency count
+        let (_health, failures) = check_validator(
+            &validator,
+            &healthy_cluster_info,
+            &address_labels,
+            &mut delinquent_checks,
+        );
+        assert!(failures.is_empty());
+        assert_eq!(delinquent_checks, 0);
+
+        // A validator without a vote account is missing, and its lag is unknown
+        let (health, failures) = check_validator(
+            &validator,
+            &cluster_info(
+                vec![vote_account_info(&Pubkey::new_unique(), 0, 0)],
+                vec![],
+                HashMap::from([(identity, 1_000)]),
+                HashMap::new(),
+            ),
+            &address_labels,
+            &mut delinquent_checks,
+        );
+        assert_eq!(failed_tests(&failures), vec!["delinquent"]);
+        assert_eq!(health.root_lag, None);
+        assert_eq!(health.vote_lag, None);
+        assert_eq!(health.vote_account, None);
+    }
+}
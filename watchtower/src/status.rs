@@ -0,0 +1,214 @@
+//! Current health of the cluster and of the monitored validators, served as JSON over HTTP
+
+use {
+    log::*,
+    serde_derive::Serialize,
+    std::{
+        io::{self, BufRead, BufReader, Read, Write},
+        net::{SocketAddr, TcpListener, TcpStream},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, RwLock,
+        },
+        thread::{self, JoinHandle},
+        time::{Duration, Instant},
+    },
+};
+
+const STATUS_PATH: &str = "/status";
+/// Time allowed for a client to send its whole request, and for each write of the response
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+/// Requests are only a request line and a few headers; anything past this is ignored
+const MAX_REQUEST_BYTES: u64 = 8 * 1024;
+/// Connections beyond this many being served at once are dropped
+const MAX_CONCURRENT_CONNECTIONS: usize = 4;
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorHealth {
+    pub identity: String,
+    /// Label of the identity in the address labels of the config file
+    pub label: Option<String>,
+    pub vote_account: Option<String>,
+    pub healthy: bool,
+    pub delinquent: bool,
+    /// Identity balance, in lamports
+    pub balance: Option<u64>,
+    /// Percentage of the recent leader slots without a block
+    pub skip_rate: Option<f64>,
+    pub root_lag: Option<u64>,
+    pub vote_lag: Option<u64>,
+    pub failures: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchtowerStatus {
+    /// Unix timestamp of the last check, in seconds
+    pub last_check: Option<u64>,
+    pub healthy: bool,
+    /// Cluster wide failures, like the transaction count not advancing or RPC errors
+    pub cluster_failures: Vec<String>,
+    pub validators: Vec<ValidatorHealth>,
+}
+
+pub type SharedWatchtowerStatus = Arc<RwLock<WatchtowerStatus>>;
+
+/// Starts serving `status` as JSON at `http://<bind_address>/status`
+pub fn start_status_server(
+    bind_address: SocketAddr,
+    status: SharedWatchtowerStatus,
+) -> io::Result<JoinHandle<()>> {
+    let listener = TcpListener::bind(bind_address)?;
+    info!("Status server listening on {}", bind_address);
+    serve(listener, status)
+}
+
+/// Accepts connections on `listener`, serving each one on its own thread.
+fn serve(listener: TcpListener, status: SharedWatchtowerStatus) -> io::Result<JoinHandle<()>> {
+    thread::Builder::new()
+        .name("watchtowerStatus".into())
+        .spawn(move || {
+            let active_connections = Arc::new(AtomicUsize::default());
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        debug!("Status server connection failed: {}", err);
+                        continue;
+                    }
+                };
+                if active_connections.fetch_add(1, Ordering::Relaxed) >= MAX_CONCURRENT_CONNECTIONS
+                {
+                    active_connections.fetch_sub(1, Ordering::Relaxed);
+                    debug!("Status server is busy, dropping connection");
+                    continue;
+                }
+                let connection_active_connections = active_connections.clone();
+                let status = status.clone();
+                let result = thread::Builder::new()
+                    .name("watchtowerStatusC".into())
+                    .spawn(move || {
+                        if let Err(err) = handle_connection(stream, &status) {
+                            debug!("Status server connection failed: {}", err);
+                        }
+                        connection_active_connections.fetch_sub(1, Ordering::Relaxed);
+                    });
+                if let Err(err) = result {
+                    active_connections.fetch_sub(1, Ordering::Relaxed);
+                    warn!("Failed to spawn status server thread: {}", err);
+                }
+            }
+        })
+}
+
+fn handle_connection(mut stream: TcpStream, status: &SharedWatchtowerStatus) -> io::Result<()> {
+    stream.set_write_timeout(Some(CONNECTION_TIMEOUT))?;
+
+    // The whole request must arrive before the deadline, however slowly it trickles in
+    let deadline = Instant::now() + CONNECTION_TIMEOUT;
+    let set_read_timeout = || {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "request timed out"));
+        }
+        stream.set_read_timeout(Some(remaining))
+    };
+    let mut reader = BufReader::new((&stream).take(MAX_REQUEST_BYTES));
+    let mut request_line = String::new();
+    set_read_timeout()?;
+    reader.read_line(&mut request_line)?;
+    // The headers are not needed, but must be read before responding
+    loop {
+        let mut header = String::new();
+        set_read_timeout()?;
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+    }
+    drop(reader);
+
+    let mut request = request_line.split_whitespace();
+    let method = request.next();
+    let path = request.next().and_then(|target| target.split('?').next());
+    let (status_line, body) = match (method, path) {
+        (Some("GET"), Some(STATUS_PATH)) => (
+            "200 OK",
+            serde_json::to_string(&*status.read().unwrap()).map_err(io::Error::other)?,
+        ),
+        _ => ("404 Not Found", String::new()),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status_line}\r\nContent-Type: application/json\r\nContent-Length: \
+         {}\r\nConnection: close\r\n\r\n{body}",
+        body.len(),
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_server() {
+        let status = SharedWatchtowerStatus::default();
+        status.write().unwrap().validators.push(ValidatorHealth {
+            identity: "validator".to_string(),
+            healthy: true,
+            ..ValidatorHealth::default()
+        });
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        serve(listener, status).unwrap();
+
+        let get = |path: &str| {
+            let mut stream = TcpStream::connect(address).unwrap();
+            write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let response = get("/status");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        let body = response.split("\r\n\r\n").nth(1).unwrap();
+        let body: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(body["healthy"], false);
+        assert_eq!(body["validators"][0]["identity"], "validator");
+        assert_eq!(body["validators"][0]["healthy"], true);
+
+        assert!(get("/metrics").starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+
+    #[test]
+    fn test_status_server_stalled_and_oversized_requests() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        serve(listener, SharedWatchtowerStatus::default()).unwrap();
+
+        // Clients which never finish their request don't hold up the others
+        let _stalled: Vec<_> = (0..MAX_CONCURRENT_CONNECTIONS - 1)
+            .map(|_| {
+                let mut stream = TcpStream::connect(address).unwrap();
+                write!(stream, "GET {STATUS_PATH} HTTP/1.1\r\n").unwrap();
+                stream
+            })
+            .collect();
+
+        // Requests are answered once the size limit is reached, even if the headers never end
+        let mut stream = TcpStream::connect(address).unwrap();
+        let request_line = format!("GET {STATUS_PATH} HTTP/1.1\r\n");
+        let padding = MAX_REQUEST_BYTES as usize - request_line.len() - "X-Padding: \r\n".len();
+        let request = format!("{request_line}X-Padding: {}\r\n", "a".repeat(padding));
+        assert_eq!(request.len() as u64, MAX_REQUEST_BYTES);
+        stream.write_all(request.as_bytes()).unwrap();
+        let start = Instant::now();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(start.elapsed() < CONNECTION_TIMEOUT);
+    }
+}
@@ -0,0 +1,228 @@
+//! Validators monitored by the watchtower and their health thresholds
+//!
+//! The `--validators-config` file lists the validators in YAML:
+//!
+//! ```yaml
+//! validators:
+//!   - identity: 7Np41oeYqPefeNQEHSv1UDhYrehxin3NStELsSKCT4K2
+//!     vote_account: 3ZT31jkAGhUaw8jsy4bTknwBMP8i4Eueh52By4zXcsVw
+//!     minimum_balance: 5.0
+//!     max_skip_rate: 25.0
+//!     skip_rate_leader_slots: 200
+//!     max_root_lag: 64
+//!     max_vote_lag: 16
+//!     delinquency_tolerance: 2
+//! ```
+//!
+//! Only `identity` is required. `minimum_balance` is in SOL and defaults to
+//! `--minimum-validator-identity-balance`; the skip rate and lag checks are disabled unless
+//! configured.
+
+use {
+    serde_derive::Deserialize,
+    solana_sdk::{clock::Slot, native_token::sol_to_lamports, pubkey::Pubkey},
+    std::{collections::HashSet, fs::File, path::Path, str::FromStr},
+};
+
+/// Number of the most recent leader slots of a validator its skip rate is computed over
+pub const DEFAULT_SKIP_RATE_LEADER_SLOTS: usize = 100;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonitoredValidator {
+    pub identity: Pubkey,
+    /// Vote account of the validator, otherwise any vote account of `identity`
+    pub vote_account: Option<Pubkey>,
+    /// Minimum identity balance, in lamports
+    pub minimum_balance: u64,
+    /// Maximum percentage of leader slots without a block, over the last
+    /// `skip_rate_leader_slots` leader slots of the current epoch
+    pub max_skip_rate: Option<f64>,
+    pub skip_rate_leader_slots: usize,
+    /// Maximum number of slots the root of the validator may be behind the highest root
+    pub max_root_lag: Option<Slot>,
+    /// Maximum number of slots the last vote of the validator may be behind the highest vote
+    pub max_vote_lag: Option<Slot>,
+    /// Number of consecutive checks the validator may be delinquent without being unhealthy
+    pub delinquency_tolerance: usize,
+}
+
+impl MonitoredValidator {
+    pub fn new(identity: Pubkey, minimum_balance: u64) -> Self {
+        Self {
+            identity,
+            vote_account: None,
+            minimum_balance,
+            max_skip_rate: None,
+            skip_rate_leader_slots: DEFAULT_SKIP_RATE_LEADER_SLOTS,
+            max_root_lag: None,
+            max_vote_lag: None,
+            delinquency_tolerance: 0,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ValidatorsConfig {
+    validators: Vec<ValidatorConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ValidatorConfig {
+    identity: String,
+    vote_account: Option<String>,
+    /// SOL
+    minimum_balance: Option<f64>,
+    max_skip_rate: Option<f64>,
+    skip_rate_leader_slots: Option<usize>,
+    max_root_lag: Option<Slot>,
+    max_vote_lag: Option<Slot>,
+    delinquency_tolerance: Option<usize>,
+}
+
+fn parse_pubkey(pubkey: &str) -> Result<Pubkey, String> {
+    Pubkey::from_str(pubkey).map_err(|err| format!("Invalid pubkey {pubkey}: {err}"))
+}
+
+fn parse_validators_config(
+    config: ValidatorsConfig,
+    default_minimum_balance: u64,
+) -> Result<Vec<MonitoredValidator>, String> {
+    config
+        .validators
+        .into_iter()
+        .map(|validator| {
+            let max_skip_rate = validator.max_skip_rate;
+            if max_skip_rate.is_some_and(|max_skip_rate| !(0.0..=100.0).contains(&max_skip_rate)) {
+                return Err(format!(
+                    "max_skip_rate of {} must be a percentage",
+                    validator.identity
+                ));
+            }
+            Ok(MonitoredValidator {
+                identity: parse_pubkey(&validator.identity)?,
+                vote_account: validator
+                    .vote_account
+                    .as_deref()
+                    .map(parse_pubkey)
+                    .transpose()?,
+                minimum_balance: validator
+                    .minimum_balance
+                    .map(sol_to_lamports)
+                    .unwrap_or(default_minimum_balance),
+                max_skip_rate,
+                skip_rate_leader_slots: validator
+                    .skip_rate_leader_slots
+                    .unwrap_or(DEFAULT_SKIP_RATE_LEADER_SLOTS),
+                max_root_lag: validator.max_root_lag,
+                max_vote_lag: validator.max_vote_lag,
+                delinquency_tolerance: validator.delinquency_tolerance.unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Checks that each validator is monitored once, whether it's listed by `--validator-identity`
+/// or in the `--validators-config` file
+pub fn check_unique_identities(validators: &[MonitoredValidator]) -> Result<(), String> {
+    let mut identities = HashSet::new();
+    for validator in validators {
+        if !identities.insert(validator.identity) {
+            return Err(format!(
+                "Validator {} is monitored more than once",
+                validator.identity
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Loads the validators of the `--validators-config` file at `path`
+pub fn load_validators_config(
+    path: &Path,
+    default_minimum_balance: u64,
+) -> Result<Vec<MonitoredValidator>, String> {
+    let file =
+        File::open(path).map_err(|err| format!("Unable to open {}: {err}", path.display()))?;
+    let config = serde_yaml::from_reader(file)
+        .map_err(|err| format!("Unable to parse {}: {err}", path.display()))?;
+    parse_validators_config(config, default_minimum_balance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_validators_config() {
+        let identity = Pubkey::new_unique();
+        let vote_account = Pubkey::new_unique();
+        let other_identity = Pubkey::new_unique();
+        let config = serde_yaml::from_str(&format!(
+            "validators:
+  - identity: {identity}
+    vote_account: {vote_account}
+    minimum_balance: 2.5
+    max_skip_rate: 25
+    skip_rate_leader_slots: 40
+    max_root_lag: 64
+    max_vote_lag: 16
+    delinquency_tolerance: 2
+  - identity: {other_identity}
+"
+        ))
+        .unwrap();
+        assert_eq!(
+            parse_validators_config(config, 10).unwrap(),
+            vec![
+                MonitoredValidator {
+                    identity,
+                    vote_account: Some(vote_account),
+                    minimum_balance: 2_500_000_000,
+                    max_skip_rate: Some(25.0),
+                    skip_rate_leader_slots: 40,
+                    max_root_lag: Some(64),
+                    max_vote_lag: Some(16),
+                    delinquency_tolerance: 2,
+                },
+                MonitoredValidator::new(other_identity, 10),
+            ]
+        );
+
+        let config = serde_yaml::from_str(&format!(
+            "validators:
+  - identity: {identity}
+    max_skip_rate: 101
+"
+        ))
+        .unwrap();
+        assert!(parse_validators_config(config, 10).is_err());
+
+        let config =
+            serde_yaml::from_str::<ValidatorsConfig>("validators:\n  - identity: bogus\n").unwrap();
+        assert!(parse_validators_config(config, 10).is_err());
+        assert!(serde_yaml::from_str::<ValidatorsConfig>(&format!(
+            "validators:\n  - identity: {identity}\n    unknown: 1\n"
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn test_check_unique_identities() {
+        let identity = Pubkey::new_unique();
+        let other_identity = Pubkey::new_unique();
+        assert!(check_unique_identities(&[]).is_ok());
+        assert!(check_unique_identities(&[
+            MonitoredValidator::new(identity, 10),
+            MonitoredValidator::new(other_identity, 10),
+        ])
+        .is_ok());
+        assert!(check_unique_identities(&[
+            MonitoredValidator::new(identity, 10),
+            MonitoredValidator::new(other_identity, 10),
+            MonitoredValidator::new(identity, 20),
+        ])
+        .is_err());
+    }
+}
@@ -45,7 +45,7 @@ use {
     solana_vote_program::vote_state::VoteTransaction,
     std::{
         collections::{HashMap, HashSet},
-        fs::{read, File},
+        fs::{metadata, read, rename, File},
         io::{Cursor, Write},
         path::{Path, PathBuf},
         str::FromStr,
@@ -78,6 +78,7 @@ pub enum WenRestartError {
     BlockNotFrozenAfterReplay(Slot, Option<String>),
     BlockNotLinkedToExpectedParent(Slot, Option<Slot>, Slot),
     ChildStakeLargerThanParent(Slot, u64, Slot, u64),
+    DryRunMismatch(bool),
     Exiting,
     FutureSnapshotExists(Slot, Slot, String),
     GenerateSnapshotWhenOneExists(Slot, String),
@@ -120,6 +121,12 @@ impl std::fmt::Display for WenRestartError {
                     slot, child_stake, parent, parent_stake
                 )
             }
+            WenRestartError::DryRunMismatch(recorded_dry_run) => {
+                write!(
+                    f,
+                    "Progress was recorded with dry run {recorded_dry_run}, remove the progress file or restart with the same dry run setting",
+                )
+            }
             WenRestartError::Exiting => write!(f, "Exiting"),
             WenRestartError::FutureSnapshotExists(slot, highest_slot, directory) => {
                 write!(
@@ -820,6 +827,8 @@ pub struct WenRestartConfig {
     pub accounts_background_request_sender: AbsRequestSender,
     pub genesis_config_hash: Hash,
     pub exit: Arc<AtomicBool>,
+    // Stop after the heaviest fork is agreed upon, without generating a snapshot.
+    pub dry_run: bool,
 }
 
 pub fn wait_for_wen_restart(config: WenRestartConfig) -> Result<()> {
@@ -828,6 +837,13 @@ pub fn wait_for_wen_restart(config: WenRestartConfig) -> Result<()> {
         config.last_vote.clone(),
         config.blockstore.clone(),
     )?;
+    if progress.dry_run != config.dry_run {
+        // Only a progress which has not sent anything to the cluster yet can switch modes.
+        if progress.my_last_voted_fork_slots.is_some() {
+            return Err(WenRestartError::DryRunMismatch(progress.dry_run).into());
+        }
+        progress.dry_run = config.dry_run;
+    }
     loop {
         state = match state {
             WenRestartProgressInternalState::Init {
@@ -907,6 +923,14 @@ pub fn wait_for_wen_restart(config: WenRestartConfig) -> Result<()> {
                     config.exit.clone(),
                     &mut progress,
                 )?;
+                if config.dry_run {
+                    write_wen_restart_records(&config.wen_restart_path, &progress)?;
+                    info!(
+                        "wen_restart dry run finished, heaviest fork slot: {}",
+                        new_root_slot
+                    );
+                    return Ok(());
+                }
                 WenRestartProgressInternalState::HeaviestFork { new_root_slot }
             }
             WenRestartProgressInternalState::GenerateSnapshot {
@@ -1140,7 +1164,7 @@ pub(crate) fn initialize(
     }
 }
 
-fn read_wen_restart_records(records_path: &PathBuf) -> Result<WenRestartProgress> {
+pub(crate) fn read_wen_restart_records(records_path: &PathBuf) -> Result<WenRestartProgress> {
     let buffer = read(records_path)?;
     let progress = WenRestartProgress::decode(&mut Cursor::new(buffer))?;
     info!("read record {:?}", progress);
@@ -1151,12 +1175,26 @@ pub(crate) fn write_wen_restart_records(
     records_path: &PathBuf,
     new_progress: &WenRestartProgress,
 ) -> Result<()> {
-    // overwrite anything if exists
-    let mut file = File::create(records_path)?;
     info!("writing new record {:?}", new_progress);
     let mut buf = Vec::with_capacity(new_progress.encoded_len());
     new_progress.encode(&mut buf)?;
+    // Write to a temporary file and rename it over the old records, so a crash
+    // or a concurrent wen-restart-status never sees a partially written file.
+    // The rename would replace a read-only file, so check its permissions first.
+    if records_path.exists() && metadata(records_path)?.permissions().readonly() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!("{} is read-only", records_path.display()),
+        )
+        .into());
+    }
+    let mut tmp_path = records_path.clone().into_os_string();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+    let mut file = File::create(&tmp_path)?;
     file.write_all(&buf)?;
+    file.sync_all()?;
+    rename(&tmp_path, records_path)?;
     Ok(())
 }
 
@@ -1438,6 +1476,7 @@ mod tests {
             accounts_background_request_sender: AbsRequestSender::default(),
             genesis_config_hash: test_state.genesis_config_hash,
             exit: exit.clone(),
+            dry_run: false,
         };
         let wen_restart_thread_handle = Builder::new()
             .name("solana-wen-restart".to_string())
@@ -1456,6 +1495,60 @@ mod tests {
 
     #[test]
     fn test_wen_restart_normal_flow() {
+        wen_restart_normal_flow(false);
+    }
+
+    #[test]
+    fn test_wen_restart_dry_run() {
+        wen_restart_normal_flow(true);
+    }
+
+    #[test]
+    fn test_wen_restart_dry_run_mismatch() {
+        solana_logger::setup();
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let test_state = wen_restart_test_init(&ledger_path);
+        let last_vote_slot = test_state.last_voted_fork_slots[0];
+        let last_vote_bankhash = Hash::new_unique();
+        assert!(write_wen_restart_records(
+            &test_state.wen_restart_proto_path,
+            &WenRestartProgress {
+                state: RestartState::Init.into(),
+                my_last_voted_fork_slots: Some(LastVotedForkSlotsRecord {
+                    last_voted_fork_slots: test_state.last_voted_fork_slots.clone(),
+                    last_vote_bankhash: last_vote_bankhash.to_string(),
+                    shred_version: SHRED_VERSION as u32,
+                    wallclock: 0,
+                }),
+                dry_run: true,
+                ..Default::default()
+            },
+        )
+        .is_ok());
+        let wen_restart_config = WenRestartConfig {
+            wen_restart_path: test_state.wen_restart_proto_path.clone(),
+            last_vote: VoteTransaction::from(Vote::new(vec![last_vote_slot], last_vote_bankhash)),
+            blockstore: test_state.blockstore.clone(),
+            cluster_info: test_state.cluster_info.clone(),
+            bank_forks: test_state.bank_forks.clone(),
+            wen_restart_repair_slots: Some(Arc::new(RwLock::new(Vec::new()))),
+            wait_for_supermajority_threshold_percent: 80,
+            snapshot_config: SnapshotConfig::default(),
+            accounts_background_request_sender: AbsRequestSender::default(),
+            genesis_config_hash: test_state.genesis_config_hash,
+            exit: Arc::new(AtomicBool::new(false)),
+            dry_run: false,
+        };
+        assert_eq!(
+            wait_for_wen_restart(wen_restart_config)
+                .unwrap_err()
+                .downcast::<WenRestartError>()
+                .unwrap(),
+            WenRestartError::DryRunMismatch(true),
+        );
+    }
+
+    fn wen_restart_normal_flow(dry_run: bool) {
         solana_logger::setup();
         let ledger_path = get_tmp_ledger_path_auto_delete!();
         let wen_restart_repair_slots = Some(Arc::new(RwLock::new(Vec::new())));
@@ -1506,6 +1599,7 @@ mod tests {
             accounts_background_request_sender: AbsRequestSender::default(),
             genesis_config_hash: test_state.genesis_config_hash,
             exit: exit.clone(),
+            dry_run,
         };
         let wen_restart_thread_handle = Builder::new()
             .name("solana-wen-restart".to_string())
@@ -1639,7 +1733,11 @@ mod tests {
         assert_eq!(
             progress,
             WenRestartProgress {
-                state: RestartState::Done.into(),
+                state: if dry_run {
+                    RestartState::HeaviestFork.into()
+                } else {
+                    RestartState::Done.into()
+                },
                 my_last_voted_fork_slots: Some(LastVotedForkSlotsRecord {
                     last_voted_fork_slots: test_state.last_voted_fork_slots,
                     last_vote_bankhash: last_vote_bankhash.to_string(),
@@ -1675,14 +1773,22 @@ mod tests {
                         total_active_stake_agreed_with_me: total_active_stake_during_heaviest_fork,
                     }),
                 }),
-                my_snapshot: Some(GenerateSnapshotRecord {
+                my_snapshot: (!dry_run).then(|| GenerateSnapshotRecord {
                     slot: expected_heaviest_fork_slot,
                     bankhash: progress.my_snapshot.as_ref().unwrap().bankhash.clone(),
                     shred_version: progress.my_snapshot.as_ref().unwrap().shred_version,
                     path: progress.my_snapshot.as_ref().unwrap().path.clone(),
                 }),
+                dry_run,
             }
         );
+        if dry_run {
+            // No snapshot should be generated in dry run mode.
+            assert!(std::fs::read_dir(incremental_snapshot_archives_dir.path())
+                .unwrap()
+                .next()
+                .is_none());
+        }
     }
 
     fn change_proto_file_readonly(wen_restart_proto_path: &PathBuf, readonly: bool) {
@@ -2025,6 +2131,10 @@ mod tests {
         );
         change_proto_file_readonly(&test_state.wen_restart_proto_path, false);
         assert!(write_wen_restart_records(&test_state.wen_restart_proto_path, &progress).is_ok());
+        assert!(!test_state
+            .wen_restart_proto_path
+            .with_extension("proto.tmp")
+            .exists());
         let last_voted_fork_slots = test_state.last_voted_fork_slots.clone();
         let last_vote_bankhash = Hash::new_unique();
         wen_restart_test_succeed_after_failure(
@@ -2360,6 +2470,7 @@ mod tests {
                     my_heaviest_fork: my_heaviest_fork.clone(),
                     heaviest_fork_aggregate,
                     my_snapshot: my_snapshot.clone(),
+                    dry_run: false,
                 },
             ),
         ] {
@@ -0,0 +1,310 @@
+//! Human readable summary of the wen_restart progress record, for operators to check where
+//! a restart stands without decoding the protobuf themselves.
+
+use {
+    crate::{solana::wen_restart_proto::WenRestartProgress, wen_restart::read_wen_restart_records},
+    anyhow::Result,
+    serde_derive::{Deserialize, Serialize},
+    solana_program::clock::Slot,
+    std::{collections::BTreeMap, fmt, path::Path},
+};
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WenRestartStatus {
+    pub phase: String,
+    pub dry_run: bool,
+    pub last_voted_slot: Option<Slot>,
+    pub last_vote_bankhash: Option<String>,
+    pub last_voted_fork_slots_aggregate: Option<LastVotedForkSlotsAggregateStatus>,
+    pub heaviest_fork: Option<HeaviestForkStatus>,
+    pub heaviest_fork_aggregate: Option<HeaviestForkAggregateStatus>,
+    pub snapshot: Option<SnapshotStatus>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LastVotedForkSlotsAggregateStatus {
+    pub peers_seen: Vec<String>,
+    /// Only known once the aggregation finished
+    pub total_active_stake: Option<u64>,
+    pub slots_stake: BTreeMap<Slot, u64>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaviestForkStatus {
+    pub slot: Slot,
+    pub bankhash: String,
+    pub total_active_stake: u64,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaviestForkAggregateStatus {
+    pub peers_seen: Vec<String>,
+    /// The following are only known once the aggregation finished
+    pub total_active_stake: Option<u64>,
+    pub total_active_stake_seen_supermajority: Option<u64>,
+    pub total_active_stake_agreed_with_me: Option<u64>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotStatus {
+    pub path: String,
+    pub slot: Slot,
+    pub bankhash: String,
+    pub shred_version: u32,
+}
+
+impl WenRestartStatus {
+    pub(crate) fn new_from_progress(progress: &WenRestartProgress) -> Self {
+        let last_voted_fork_slots_aggregate = progress
+            .last_voted_fork_slots_aggregate
+            .as_ref()
+            .map(|aggregate| {
+                let mut peers_seen: Vec<String> = aggregate.received.keys().cloned().collect();
+                peers_seen.sort();
+                LastVotedForkSlotsAggregateStatus {
+                    peers_seen,
+                    total_active_stake: aggregate
+                        .final_result
+                        .as_ref()
+                        .map(|result| result.total_active_stake),
+                    slots_stake: aggregate
+                        .final_result
+                        .as_ref()
+                        .map(|result| {
+                            result
+                                .slots_stake_map
+                                .iter()
+                                .map(|(slot, stake)| (*slot, *stake))
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                }
+            });
+        let heaviest_fork_aggregate = progress.heaviest_fork_aggregate.as_ref().map(|aggregate| {
+            let mut peers_seen: Vec<String> = aggregate.received.keys().cloned().collect();
+            peers_seen.sort();
+            let final_result = aggregate.final_result.as_ref();
+            HeaviestForkAggregateStatus {
+                peers_seen,
+                total_active_stake: final_result.map(|result| result.total_active_stake),
+                total_active_stake_seen_supermajority: final_result
+                    .map(|result| result.total_active_stake_seen_supermajority),
+                total_active_stake_agreed_with_me: final_result
+                    .map(|result| result.total_active_stake_agreed_with_me),
+            }
+        });
+        let my_last_voted_fork_slots = progress.my_last_voted_fork_slots.as_ref();
+        WenRestartStatus {
+            phase: format!("{:?}", progress.state()),
+            dry_run: progress.dry_run,
+            last_voted_slot: my_last_voted_fork_slots
+                .and_then(|record| record.last_voted_fork_slots.iter().max().copied()),
+            last_vote_bankhash: my_last_voted_fork_slots
+                .map(|record| record.last_vote_bankhash.clone()),
+            last_voted_fork_slots_aggregate,
+            heaviest_fork: progress
+                .my_heaviest_fork
+                .as_ref()
+                .map(|record| HeaviestForkStatus {
+                    slot: record.slot,
+                    bankhash: record.bankhash.clone(),
+                    total_active_stake: record.total_active_stake,
+                }),
+            heaviest_fork_aggregate,
+            snapshot: progress.my_snapshot.as_ref().map(|record| SnapshotStatus {
+                path: record.path.clone(),
+                slot: record.slot,
+                bankhash: record.bankhash.clone(),
+                shred_version: record.shred_version,
+            }),
+        }
+    }
+}
+
+/// Decodes the wen_restart progress record at `path`
+pub fn read_wen_restart_status(path: &Path) -> Result<WenRestartStatus> {
+    let progress = read_wen_restart_records(&path.to_path_buf())?;
+    Ok(WenRestartStatus::new_from_progress(&progress))
+}
+
+fn format_optional<T: fmt::Display>(value: Option<T>) -> String {
+    value.map_or_else(|| "-".to_string(), |value| value.to_string())
+}
+
+impl fmt::Display for WenRestartStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Phase: {}{}",
+            self.phase,
+            if self.dry_run { " (dry run)" } else { "" }
+        )?;
+        writeln!(
+            f,
+            "Last voted slot: {} bankhash: {}",
+            format_optional(self.last_voted_slot),
+            format_optional(self.last_vote_bankhash.as_ref()),
+        )?;
+        if let Some(aggregate) = &self.last_voted_fork_slots_aggregate {
+            writeln!(
+                f,
+                "Last voted fork slots: {} peers seen, total active stake: {}, {} slots with stake",
+                aggregate.peers_seen.len(),
+                format_optional(aggregate.total_active_stake),
+                aggregate.slots_stake.len(),
+            )?;
+            if let Some((slot, stake)) = aggregate.slots_stake.last_key_value() {
+                writeln!(f, "  Highest slot with stake: {slot} stake: {stake}")?;
+            }
+        }
+        if let Some(heaviest_fork) = &self.heaviest_fork {
+            writeln!(
+                f,
+                "Heaviest fork: slot: {} bankhash: {} total active stake: {}",
+                heaviest_fork.slot, heaviest_fork.bankhash, heaviest_fork.total_active_stake,
+            )?;
+        }
+        if let Some(aggregate) = &self.heaviest_fork_aggregate {
+            writeln!(
+                f,
+                "Heaviest fork aggregate: {} peers seen, total active stake: {}, seen supermajority: {}, agreed with me: {}",
+                aggregate.peers_seen.len(),
+                format_optional(aggregate.total_active_stake),
+                format_optional(aggregate.total_active_stake_seen_supermajority),
+                format_optional(aggregate.total_active_stake_agreed_with_me),
+            )?;
+        }
+        if let Some(snapshot) = &self.snapshot {
+            writeln!(
+                f,
+                "Snapshot: {} slot: {} bankhash: {} shred version: {}",
+                snapshot.path, snapshot.slot, snapshot.bankhash, snapshot.shred_version,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            solana::wen_restart_proto::{
+                GenerateSnapshotRecord, HeaviestForkAggregateFinal, HeaviestForkAggregateRecord,
+                HeaviestForkRecord, LastVotedForkSlotsAggregateFinal,
+                LastVotedForkSlotsAggregateRecord, LastVotedForkSlotsRecord, State as RestartState,
+            },
+            wen_restart::write_wen_restart_records,
+        },
+        std::collections::HashMap,
+    };
+
+    #[test]
+    fn test_read_wen_restart_status() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("wen_restart_progress.proto");
+        assert!(read_wen_restart_status(&path).is_err());
+
+        let last_voted_fork_slots = LastVotedForkSlotsRecord {
+            last_voted_fork_slots: vec![3, 2, 1, 0],
+            last_vote_bankhash: "bankhash3".to_string(),
+            shred_version: 2,
+            wallclock: 0,
+        };
+        let heaviest_fork = HeaviestForkRecord {
+            slot: 4,
+            bankhash: "bankhash4".to_string(),
+            total_active_stake: 800,
+            shred_version: 2,
+            wallclock: 0,
+        };
+        let progress = WenRestartProgress {
+            state: RestartState::Init.into(),
+            my_last_voted_fork_slots: Some(last_voted_fork_slots.clone()),
+            ..Default::default()
+        };
+        write_wen_restart_records(&path, &progress).unwrap();
+        assert_eq!(
+            read_wen_restart_status(&path).unwrap(),
+            WenRestartStatus {
+                phase: "Init".to_string(),
+                last_voted_slot: Some(3),
+                last_vote_bankhash: Some("bankhash3".to_string()),
+                ..WenRestartStatus::default()
+            }
+        );
+
+        let progress = WenRestartProgress {
+            state: RestartState::GenerateSnapshot.into(),
+            my_last_voted_fork_slots: Some(last_voted_fork_slots.clone()),
+            last_voted_fork_slots_aggregate: Some(LastVotedForkSlotsAggregateRecord {
+                received: HashMap::from([
+                    ("peer2".to_string(), last_voted_fork_slots.clone()),
+                    ("peer1".to_string(), last_voted_fork_slots),
+                ]),
+                final_result: Some(LastVotedForkSlotsAggregateFinal {
+                    slots_stake_map: HashMap::from([(3, 800), (4, 700)]),
+                    total_active_stake: 900,
+                }),
+            }),
+            my_heaviest_fork: Some(heaviest_fork.clone()),
+            heaviest_fork_aggregate: Some(HeaviestForkAggregateRecord {
+                received: HashMap::from([("peer1".to_string(), heaviest_fork)]),
+                final_result: Some(HeaviestForkAggregateFinal {
+                    total_active_stake: 800,
+                    total_active_stake_seen_supermajority: 800,
+                    total_active_stake_agreed_with_me: 700,
+                }),
+            }),
+            my_snapshot: Some(GenerateSnapshotRecord {
+                path: "/snapshots/incremental-snapshot-0-4.tar.zst".to_string(),
+                slot: 4,
+                bankhash: "bankhash4".to_string(),
+                shred_version: 3,
+            }),
+            dry_run: true,
+        };
+        write_wen_restart_records(&path, &progress).unwrap();
+        let status = read_wen_restart_status(&path).unwrap();
+        assert_eq!(
+            status,
+            WenRestartStatus {
+                phase: "GenerateSnapshot".to_string(),
+                dry_run: true,
+                last_voted_slot: Some(3),
+                last_vote_bankhash: Some("bankhash3".to_string()),
+                last_voted_fork_slots_aggregate: Some(LastVotedForkSlotsAggregateStatus {
+                    peers_seen: vec!["peer1".to_string(), "peer2".to_string()],
+                    total_active_stake: Some(900),
+                    slots_stake: BTreeMap::from([(3, 800), (4, 700)]),
+                }),
+                heaviest_fork: Some(HeaviestForkStatus {
+                    slot: 4,
+                    bankhash: "bankhash4".to_string(),
+                    total_active_stake: 800,
+                }),
+                heaviest_fork_aggregate: Some(HeaviestForkAggregateStatus {
+                    peers_seen: vec!["peer1".to_string()],
+                    total_active_stake: Some(800),
+                    total_active_stake_seen_supermajority: Some(800),
+                    total_active_stake_agreed_with_me: Some(700),
+                }),
+                snapshot: Some(SnapshotStatus {
+                    path: "/snapshots/incremental-snapshot-0-4.tar.zst".to_string(),
+                    slot: 4,
+                    bankhash: "bankhash4".to_string(),
+                    shred_version: 3,
+                }),
+            }
+        );
+        assert!(status
+            .to_string()
+            .starts_with("Phase: GenerateSnapshot (dry run)\n"));
+    }
+}